@@ -0,0 +1,411 @@
+//! Built-in behaviors.
+
+use crate::brain::{AiAction, Behavior, WorldView};
+use crate::dijkstra_map::DijkstraMap;
+use runeforge_geometry::prelude::*;
+use runeforge_pathfinding::prelude::*;
+use runeforge_random::prelude::*;
+
+/// Default radius (in tiles) that path searches are allowed to explore.
+const DEFAULT_SEARCH_RADIUS: u32 = 32;
+
+/// Adapts a [`WorldView`] into a [`PathProvider`], bounded around an origin.
+struct ViewProvider<'a> {
+    view: &'a dyn WorldView,
+    origin: IVec2,
+    goal: IVec2,
+    radius: u32,
+}
+
+impl PathProvider<()> for ViewProvider<'_> {
+    fn get_neighbors(&self, position: IVec2, _pass_through_data: &mut ()) -> Vec<IVec2> {
+        let mut neighbors: Vec<IVec2> = self
+            .view
+            .neighbors(position)
+            .into_iter()
+            .filter(|&n| n.distance(self.origin) <= self.radius)
+            .collect();
+        // The goal is often occupied (e.g. by the player), so allow stepping onto it.
+        if position.distance(self.goal) == 1 && !neighbors.contains(&self.goal) {
+            neighbors.push(self.goal);
+        }
+        neighbors
+    }
+}
+
+/// Returns the first step of an A* path from the actor to `goal`.
+fn step_towards(view: &dyn WorldView, goal: IVec2, radius: u32) -> Option<IVec2> {
+    let origin = view.position();
+    let mut provider = ViewProvider {
+        view,
+        origin,
+        goal,
+        radius,
+    };
+    AStar::compute_path(origin, goal, &mut provider, ())
+        .get(1)
+        .copied()
+}
+
+/// Steps to a random walkable neighbor.
+#[derive(Debug, Clone, Default)]
+pub struct Wander {
+    chance: Option<f32>,
+}
+
+impl Wander {
+    /// Creates a wander behavior that always moves.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only moves with the given probability each turn; otherwise defers to lower priorities.
+    pub fn with_chance(mut self, chance: f32) -> Self {
+        self.chance = Some(chance.clamp(0.0, 1.0));
+        self
+    }
+}
+
+impl Behavior for Wander {
+    fn decide(&mut self, view: &dyn WorldView, rng: &mut Rng) -> Option<AiAction> {
+        if let Some(chance) = self.chance {
+            if !rng.chance(chance) {
+                return None;
+            }
+        }
+        let neighbors = view.neighbors(view.position());
+        rng.choose(&neighbors).map(|&p| AiAction::MoveTo(p))
+    }
+}
+
+/// Chases the target along an A* path and attacks when adjacent.
+#[derive(Debug, Clone)]
+pub struct Chase {
+    range: Option<u32>,
+    search_radius: u32,
+}
+
+impl Default for Chase {
+    fn default() -> Self {
+        Self {
+            range: None,
+            search_radius: DEFAULT_SEARCH_RADIUS,
+        }
+    }
+}
+
+impl Chase {
+    /// Creates a chase behavior with unlimited range.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only chases targets within `range` tiles (Chebyshev distance).
+    pub fn within(mut self, range: u32) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    /// Sets how far from the actor the path search may explore.
+    pub fn with_search_radius(mut self, radius: u32) -> Self {
+        self.search_radius = radius.max(1);
+        self
+    }
+}
+
+impl Behavior for Chase {
+    fn decide(&mut self, view: &dyn WorldView, _rng: &mut Rng) -> Option<AiAction> {
+        let target = view.target()?;
+        let distance = view.position().distance(target);
+        if self.range.is_some_and(|range| distance > range) {
+            return None;
+        }
+        if distance == 1 {
+            return Some(AiAction::Attack(target));
+        }
+        step_towards(view, target, self.search_radius).map(AiAction::MoveTo)
+    }
+}
+
+/// Flees from the target by walking downhill on a Dijkstra flee map.
+#[derive(Debug, Clone)]
+pub struct Flee {
+    range: u32,
+    coefficient: f32,
+}
+
+impl Default for Flee {
+    fn default() -> Self {
+        Self {
+            range: 10,
+            coefficient: -1.2,
+        }
+    }
+}
+
+impl Flee {
+    /// Creates a flee behavior that reacts to targets within 10 tiles.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how close the target must be before fleeing.
+    pub fn within(mut self, range: u32) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Sets the flee map coefficient (clamped to be negative).
+    pub fn with_coefficient(mut self, coefficient: f32) -> Self {
+        self.coefficient = coefficient.min(-0.1);
+        self
+    }
+}
+
+impl Behavior for Flee {
+    fn decide(&mut self, view: &dyn WorldView, _rng: &mut Rng) -> Option<AiAction> {
+        let target = view.target()?;
+        let pos = view.position();
+        if pos.distance(target) > self.range {
+            return None;
+        }
+        // Search a little past the trigger range so there is somewhere to run to.
+        let approach = DijkstraMap::new(view, &[target], self.range * 2);
+        let flee = approach.flee(view, self.coefficient);
+        flee.downhill(view, pos).map(AiAction::MoveTo)
+    }
+}
+
+/// Walks between waypoints in order, looping back to the first.
+#[derive(Debug, Clone)]
+pub struct Patrol {
+    waypoints: Vec<IVec2>,
+    current: usize,
+    search_radius: u32,
+}
+
+impl Patrol {
+    /// Creates a patrol over the given waypoints.
+    pub fn new(waypoints: Vec<IVec2>) -> Self {
+        Self {
+            waypoints,
+            current: 0,
+            search_radius: DEFAULT_SEARCH_RADIUS,
+        }
+    }
+
+    /// Sets how far from the actor the path search may explore.
+    pub fn with_search_radius(mut self, radius: u32) -> Self {
+        self.search_radius = radius.max(1);
+        self
+    }
+
+    /// Returns the waypoint currently being walked to.
+    pub fn current_waypoint(&self) -> Option<IVec2> {
+        self.waypoints.get(self.current).copied()
+    }
+}
+
+impl Behavior for Patrol {
+    fn decide(&mut self, view: &dyn WorldView, _rng: &mut Rng) -> Option<AiAction> {
+        if self.waypoints.is_empty() {
+            return None;
+        }
+        if view.position() == self.waypoints[self.current] {
+            self.current = (self.current + 1) % self.waypoints.len();
+        }
+        let goal = self.waypoints[self.current];
+        step_towards(view, goal, self.search_radius).map(AiAction::MoveTo)
+    }
+}
+
+/// Walks to the nearest known item and picks it up.
+#[derive(Debug, Clone)]
+pub struct UseNearestItem {
+    range: u32,
+}
+
+impl Default for UseNearestItem {
+    fn default() -> Self {
+        Self { range: 10 }
+    }
+}
+
+impl UseNearestItem {
+    /// Creates a behavior that considers items within 10 walking steps.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum walking distance to an item.
+    pub fn within(mut self, range: u32) -> Self {
+        self.range = range;
+        self
+    }
+}
+
+impl Behavior for UseNearestItem {
+    fn decide(&mut self, view: &dyn WorldView, _rng: &mut Rng) -> Option<AiAction> {
+        let pos = view.position();
+        let items = view.items();
+        if items.contains(&pos) {
+            return Some(AiAction::PickUp(pos));
+        }
+        if items.is_empty() {
+            return None;
+        }
+        let map = DijkstraMap::new(view, &items, self.range);
+        map.downhill(view, pos).map(AiAction::MoveTo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brain::{BehaviorExt, Brain};
+
+    /// An open 10x10 room with optional walls.
+    struct Room {
+        me: IVec2,
+        target: Option<IVec2>,
+        items: Vec<IVec2>,
+        walls: Vec<IVec2>,
+    }
+
+    impl Room {
+        fn new(me: IVec2) -> Self {
+            Self {
+                me,
+                target: None,
+                items: Vec::new(),
+                walls: Vec::new(),
+            }
+        }
+    }
+
+    impl WorldView for Room {
+        fn position(&self) -> IVec2 {
+            self.me
+        }
+
+        fn is_walkable(&self, pos: IVec2) -> bool {
+            pos.x >= 0 && pos.y >= 0 && pos.x < 10 && pos.y < 10 && !self.walls.contains(&pos)
+        }
+
+        fn target(&self) -> Option<IVec2> {
+            self.target
+        }
+
+        fn items(&self) -> Vec<IVec2> {
+            self.items.clone()
+        }
+    }
+
+    fn seeded_rng(seed: u64) -> Rng {
+        Rng::with_seed(seed)
+    }
+
+    #[test]
+    fn test_wander_moves_to_neighbor() {
+        let room = Room::new(IVec2::new(5, 5));
+        let mut rng = seeded_rng(1);
+        match Wander::new().decide(&room, &mut rng) {
+            Some(AiAction::MoveTo(p)) => assert_eq!(p.distance(room.me), 1),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_chase_attacks_when_adjacent() {
+        let mut room = Room::new(IVec2::new(5, 5));
+        room.target = Some(IVec2::new(6, 6));
+        let mut rng = seeded_rng(1);
+        assert_eq!(
+            Chase::new().decide(&room, &mut rng),
+            Some(AiAction::Attack(IVec2::new(6, 6)))
+        );
+    }
+
+    #[test]
+    fn test_chase_routes_around_walls() {
+        let mut room = Room::new(IVec2::new(1, 5));
+        room.target = Some(IVec2::new(3, 5));
+        room.walls = vec![IVec2::new(2, 4), IVec2::new(2, 5), IVec2::new(2, 6)];
+        let mut rng = seeded_rng(1);
+        match Chase::new().decide(&room, &mut rng) {
+            Some(AiAction::MoveTo(p)) => {
+                assert!(p == IVec2::new(1, 4) || p == IVec2::new(1, 6) || p.x == 2);
+                assert!(!room.walls.contains(&p));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_chase_range() {
+        let mut room = Room::new(IVec2::new(0, 0));
+        room.target = Some(IVec2::new(9, 9));
+        let mut rng = seeded_rng(1);
+        assert_eq!(Chase::new().within(3).decide(&room, &mut rng), None);
+    }
+
+    #[test]
+    fn test_flee_increases_distance() {
+        let mut room = Room::new(IVec2::new(5, 5));
+        room.target = Some(IVec2::new(4, 5));
+        let mut rng = seeded_rng(1);
+        match Flee::new().decide(&room, &mut rng) {
+            Some(AiAction::MoveTo(p)) => {
+                assert!(p.distance(IVec2::new(4, 5)) > 1);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_patrol_advances_waypoints() {
+        let mut patrol = Patrol::new(vec![IVec2::new(2, 2), IVec2::new(6, 2)]);
+        let room = Room::new(IVec2::new(2, 2));
+        let mut rng = seeded_rng(1);
+        match patrol.decide(&room, &mut rng) {
+            Some(AiAction::MoveTo(p)) => assert_eq!(p.x, 3),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(patrol.current_waypoint(), Some(IVec2::new(6, 2)));
+    }
+
+    #[test]
+    fn test_use_nearest_item() {
+        let mut room = Room::new(IVec2::new(5, 5));
+        room.items = vec![IVec2::new(8, 5), IVec2::new(0, 0)];
+        let mut rng = seeded_rng(1);
+        match UseNearestItem::new().decide(&room, &mut rng) {
+            Some(AiAction::MoveTo(p)) => assert_eq!(p.x, 6),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        room.me = IVec2::new(8, 5);
+        assert_eq!(
+            UseNearestItem::new().decide(&room, &mut rng),
+            Some(AiAction::PickUp(IVec2::new(8, 5)))
+        );
+    }
+
+    #[test]
+    fn test_brain_priority_and_conditions() {
+        let mut room = Room::new(IVec2::new(5, 5));
+        room.target = Some(IVec2::new(6, 5));
+        let mut rng = seeded_rng(1);
+
+        let mut brain = Brain::new()
+            .with(Flee::new().when(|_| false))
+            .with(Chase::new());
+        assert_eq!(
+            brain.tick(&room, &mut rng),
+            AiAction::Attack(IVec2::new(6, 5))
+        );
+
+        let mut idle = Brain::new().with(Chase::new().within(0));
+        assert_eq!(idle.tick(&room, &mut rng), AiAction::Wait);
+    }
+}
@@ -0,0 +1,132 @@
+//! The [`Brain`] and the traits behaviors are built on.
+
+use runeforge_geometry::prelude::*;
+use runeforge_random::prelude::*;
+use std::fmt;
+
+/// The decision produced by a [`Brain`] for one turn.
+///
+/// Games translate these into their own actions (e.g. a `runeforge_core::action::Action`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AiAction {
+    /// Do nothing this turn.
+    Wait,
+    /// Step onto the given adjacent tile.
+    MoveTo(IVec2),
+    /// Attack whatever stands on the given adjacent tile.
+    Attack(IVec2),
+    /// Pick up the item lying on the given tile (the actor's own tile).
+    PickUp(IVec2),
+}
+
+/// The read-only slice of the world an AI actor can perceive.
+pub trait WorldView {
+    /// Returns the position of the actor being controlled.
+    fn position(&self) -> IVec2;
+
+    /// Returns true if the actor can step onto the given tile.
+    fn is_walkable(&self, pos: IVec2) -> bool;
+
+    /// Returns the position of the actor's current target (usually the player), if known.
+    fn target(&self) -> Option<IVec2> {
+        None
+    }
+
+    /// Returns the positions of items the actor knows about.
+    fn items(&self) -> Vec<IVec2> {
+        Vec::new()
+    }
+
+    /// Returns the walkable neighbors of a position (8-way by default).
+    fn neighbors(&self, pos: IVec2) -> Vec<IVec2> {
+        DirectionIter::all_2d()
+            .map(|d| pos + d.coord())
+            .filter(|&p| self.is_walkable(p))
+            .collect()
+    }
+}
+
+/// A single composable piece of AI logic.
+pub trait Behavior {
+    /// Returns the action to take, or `None` if this behavior does not apply right now.
+    fn decide(&mut self, view: &dyn WorldView, rng: &mut Rng) -> Option<AiAction>;
+}
+
+/// A behavior that only runs while a condition holds.
+pub struct Conditional<B, F> {
+    behavior: B,
+    condition: F,
+}
+
+impl<B, F> Behavior for Conditional<B, F>
+where
+    B: Behavior,
+    F: FnMut(&dyn WorldView) -> bool,
+{
+    fn decide(&mut self, view: &dyn WorldView, rng: &mut Rng) -> Option<AiAction> {
+        if (self.condition)(view) {
+            self.behavior.decide(view, rng)
+        } else {
+            None
+        }
+    }
+}
+
+/// Combinators available on every [`Behavior`].
+pub trait BehaviorExt: Behavior + Sized {
+    /// Runs this behavior only while `condition` returns true.
+    fn when<F>(self, condition: F) -> Conditional<Self, F>
+    where
+        F: FnMut(&dyn WorldView) -> bool,
+    {
+        Conditional {
+            behavior: self,
+            condition,
+        }
+    }
+}
+
+impl<B: Behavior> BehaviorExt for B {}
+
+/// A prioritized selector over behaviors.
+///
+/// Each tick the behaviors are tried in the order they were added; the first
+/// one that returns an action wins. If none applies the brain waits.
+#[derive(Default)]
+pub struct Brain {
+    behaviors: Vec<Box<dyn Behavior>>,
+}
+
+impl fmt::Debug for Brain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Brain")
+            .field("behaviors", &self.behaviors.len())
+            .finish()
+    }
+}
+
+impl Brain {
+    /// Creates a brain with no behaviors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a behavior with lower priority than those already added.
+    pub fn with<B: Behavior + 'static>(mut self, behavior: B) -> Self {
+        self.behaviors.push(Box::new(behavior));
+        self
+    }
+
+    /// Adds a behavior with lower priority than those already added.
+    pub fn push<B: Behavior + 'static>(&mut self, behavior: B) {
+        self.behaviors.push(Box::new(behavior));
+    }
+
+    /// Chooses the action for this turn.
+    pub fn tick(&mut self, view: &dyn WorldView, rng: &mut Rng) -> AiAction {
+        self.behaviors
+            .iter_mut()
+            .find_map(|b| b.decide(view, rng))
+            .unwrap_or(AiAction::Wait)
+    }
+}
@@ -0,0 +1,104 @@
+//! Dijkstra maps (distance fields) over a [`WorldView`].
+//!
+//! A Dijkstra map stores, for every reachable tile, the walking distance to the
+//! nearest goal. Rolling downhill approaches the goals; rolling uphill on a
+//! [`flee`](DijkstraMap::flee) map runs away from them intelligently (toward
+//! open space rather than into corners).
+
+use crate::brain::WorldView;
+use runeforge_geometry::prelude::*;
+use std::collections::{HashMap, VecDeque};
+
+/// A bounded distance field computed from one or more goal tiles.
+#[derive(Debug, Clone, Default)]
+pub struct DijkstraMap {
+    values: HashMap<IVec2, f32>,
+}
+
+impl DijkstraMap {
+    /// Computes distances from `goals` over walkable tiles, up to `max_distance` steps.
+    ///
+    /// Goal tiles are always included, even if they are not walkable.
+    pub fn new(view: &dyn WorldView, goals: &[IVec2], max_distance: u32) -> Self {
+        let mut values = HashMap::new();
+        let mut queue = VecDeque::new();
+        for &goal in goals {
+            values.insert(goal, 0.0);
+            queue.push_back((goal, 0u32));
+        }
+
+        while let Some((pos, dist)) = queue.pop_front() {
+            if dist >= max_distance {
+                continue;
+            }
+            for next in view.neighbors(pos) {
+                if let std::collections::hash_map::Entry::Vacant(e) = values.entry(next) {
+                    e.insert((dist + 1) as f32);
+                    queue.push_back((next, dist + 1));
+                }
+            }
+        }
+
+        Self { values }
+    }
+
+    /// Builds a flee map: inverts and rescales the distances, then relaxes them so
+    /// that walking downhill leads away from the goals.
+    ///
+    /// `coefficient` is usually around `-1.2`; more negative values make actors
+    /// prefer distance over escape routes.
+    pub fn flee(&self, view: &dyn WorldView, coefficient: f32) -> Self {
+        let mut values: HashMap<IVec2, f32> = self
+            .values
+            .iter()
+            .map(|(&p, &v)| (p, v * coefficient))
+            .collect();
+
+        // Relax until stable so values reflect reachable escape routes.
+        let mut changed = true;
+        while changed {
+            changed = false;
+            let keys: Vec<IVec2> = values.keys().copied().collect();
+            for pos in keys {
+                let current = values[&pos];
+                let best = view
+                    .neighbors(pos)
+                    .into_iter()
+                    .filter_map(|n| values.get(&n).copied())
+                    .fold(f32::INFINITY, f32::min);
+                if best + 1.0 < current {
+                    values.insert(pos, best + 1.0);
+                    changed = true;
+                }
+            }
+        }
+
+        Self { values }
+    }
+
+    /// Returns the value at a position, or `None` if it was not reached.
+    pub fn get(&self, pos: IVec2) -> Option<f32> {
+        self.values.get(&pos).copied()
+    }
+
+    /// Returns the walkable neighbor with the lowest value, if it improves on `pos`.
+    pub fn downhill(&self, view: &dyn WorldView, pos: IVec2) -> Option<IVec2> {
+        let here = self.get(pos).unwrap_or(f32::INFINITY);
+        view.neighbors(pos)
+            .into_iter()
+            .filter_map(|n| self.get(n).map(|v| (n, v)))
+            .filter(|&(_, v)| v < here)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(n, _)| n)
+    }
+
+    /// Returns the number of tiles with a value.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if no tiles were reached.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
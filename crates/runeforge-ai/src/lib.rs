@@ -0,0 +1,78 @@
+//! Composable monster AI for roguelike games.
+//!
+//! # Overview
+//!
+//! `runeforge-ai` glues the pathfinding and random crates together into ready-made
+//! monster behaviors. A [`Brain`](brain::Brain) holds a prioritized list of
+//! [`Behavior`](brain::Behavior)s and, each turn, returns the [`AiAction`](brain::AiAction)
+//! chosen by the first behavior that applies.
+//!
+//! # Behaviors
+//!
+//! *   **Wander:** Step to a random walkable neighbor.
+//! *   **Chase:** Follow an A* path to the target and attack when adjacent.
+//! *   **Flee:** Run away from the target using a Dijkstra map.
+//! *   **Patrol:** Walk between waypoints.
+//! *   **Use Nearest Item:** Walk to the closest visible item and pick it up.
+//!
+//! The [`ScentMap`](scent::ScentMap) complements these with a decaying scent field
+//! for bloodhound-style tracking and gas diffusion.
+//!
+//! # Usage
+//!
+//! Add this to your `Cargo.toml`:
+//!
+//! ```toml
+//! [dependencies]
+//! runeforge-ai = "0.1"
+//! ```
+//!
+//! ## Example
+//!
+//! ```rust
+//! use runeforge_ai::prelude::*;
+//!
+//! struct View {
+//!     me: IVec2,
+//!     player: IVec2,
+//! }
+//!
+//! impl WorldView for View {
+//!     fn position(&self) -> IVec2 {
+//!         self.me
+//!     }
+//!
+//!     fn is_walkable(&self, pos: IVec2) -> bool {
+//!         pos.x >= 0 && pos.y >= 0 && pos.x < 20 && pos.y < 20
+//!     }
+//!
+//!     fn target(&self) -> Option<IVec2> {
+//!         Some(self.player)
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let mut brain = Brain::new()
+//!         .with(Chase::new().within(8))
+//!         .with(Wander::new());
+//!     let mut rng = Rng::with_seed(1);
+//!
+//!     let view = View { me: IVec2::new(2, 2), player: IVec2::new(5, 2) };
+//!     assert_eq!(brain.tick(&view, &mut rng), AiAction::MoveTo(IVec2::new(3, 2)));
+//! }
+//! ```
+
+pub mod behaviors;
+pub mod brain;
+pub mod dijkstra_map;
+pub mod scent;
+
+pub mod prelude {
+    pub use runeforge_geometry::prelude::IVec2;
+    pub use runeforge_random::prelude::Rng;
+
+    pub use crate::behaviors::{Chase, Flee, Patrol, UseNearestItem, Wander};
+    pub use crate::brain::{AiAction, Behavior, BehaviorExt, Brain, Conditional, WorldView};
+    pub use crate::dijkstra_map::DijkstraMap;
+    pub use crate::scent::ScentMap;
+}
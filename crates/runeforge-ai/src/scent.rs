@@ -0,0 +1,189 @@
+//! Decaying scalar fields for scent tracking and gas diffusion.
+//!
+//! A [`ScentMap`] stores a non-negative intensity per tile. Actors
+//! [`deposit`](ScentMap::deposit) scent as they move, the map
+//! [`decay`](ScentMap::decay)s (and optionally [`diffuse`](ScentMap::diffuse)s)
+//! once per turn, and trackers follow the [`gradient`](ScentMap::gradient)
+//! toward the freshest trail.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_ai::scent::ScentMap;
+//! use runeforge_geometry::prelude::IVec2;
+//!
+//! let mut scent = ScentMap::new(20, 20);
+//! for x in 0..5 {
+//!     scent.decay(0.1);
+//!     scent.deposit(IVec2::new(x, 10), 1.0);
+//! }
+//!
+//! // A bloodhound at the start of the trail heads toward the fresher end.
+//! assert_eq!(scent.gradient(IVec2::new(0, 10)), Some(IVec2::new(1, 10)));
+//! ```
+
+use runeforge_geometry::prelude::*;
+
+/// A fixed-size grid of decaying scent intensities.
+#[derive(Debug, Clone)]
+pub struct ScentMap {
+    width: u32,
+    height: u32,
+    values: Vec<f32>,
+}
+
+impl ScentMap {
+    /// Creates an empty scent map.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            values: vec![0.0; width as usize * height as usize],
+        }
+    }
+
+    /// Returns the width of the map.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the map.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Adds scent at a position. Out of bounds deposits are ignored.
+    pub fn deposit(&mut self, pos: IVec2, amount: f32) {
+        if let Some(idx) = self.index(pos) {
+            self.values[idx] += amount.max(0.0);
+        }
+    }
+
+    /// Returns the scent intensity at a position (0.0 if out of bounds).
+    pub fn sample(&self, pos: IVec2) -> f32 {
+        self.index(pos).map_or(0.0, |idx| self.values[idx])
+    }
+
+    /// Reduces every value by the given fraction (0.0 to 1.0).
+    ///
+    /// Values that fall below a small epsilon are cleared to zero.
+    pub fn decay(&mut self, rate: f32) {
+        let keep = 1.0 - rate.clamp(0.0, 1.0);
+        for v in &mut self.values {
+            *v *= keep;
+            if *v < f32::EPSILON {
+                *v = 0.0;
+            }
+        }
+    }
+
+    /// Spreads a fraction of each tile's scent evenly to its 8 neighbors.
+    ///
+    /// `passable` decides which tiles scent can flow into (e.g. not walls).
+    /// Scent that would flow into blocked or out of bounds tiles stays put.
+    pub fn diffuse(&mut self, rate: f32, passable: impl Fn(IVec2) -> bool) {
+        let rate = rate.clamp(0.0, 1.0);
+        let mut next = self.values.clone();
+        for y in 0..self.height as i32 {
+            for x in 0..self.width as i32 {
+                let pos = IVec2::new(x, y);
+                let amount = self.sample(pos);
+                if amount == 0.0 {
+                    continue;
+                }
+                let share = amount * rate / 8.0;
+                for dir in DirectionIter::all_2d() {
+                    let n = pos + dir.coord();
+                    if let Some(n_idx) = self.index(n).filter(|_| passable(n)) {
+                        next[n_idx] += share;
+                        next[self.index(pos).unwrap()] -= share;
+                    }
+                }
+            }
+        }
+        self.values = next;
+    }
+
+    /// Returns the neighbor with the strongest scent, if it is stronger than `pos` itself.
+    pub fn gradient(&self, pos: IVec2) -> Option<IVec2> {
+        let here = self.sample(pos);
+        DirectionIter::all_2d()
+            .map(|d| pos + d.coord())
+            .filter(|&n| self.index(n).is_some())
+            .map(|n| (n, self.sample(n)))
+            .filter(|&(_, v)| v > here)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(n, _)| n)
+    }
+
+    /// Clears all scent.
+    pub fn clear(&mut self) {
+        self.values.iter_mut().for_each(|v| *v = 0.0);
+    }
+
+    /// Returns the raw values in row-major order.
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    fn index(&self, pos: IVec2) -> Option<usize> {
+        (pos.x >= 0 && pos.y >= 0 && (pos.x as u32) < self.width && (pos.y as u32) < self.height)
+            .then(|| pos.y as usize * self.width as usize + pos.x as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deposit_and_sample() {
+        let mut scent = ScentMap::new(5, 5);
+        scent.deposit(IVec2::new(2, 2), 3.0);
+        scent.deposit(IVec2::new(2, 2), 1.0);
+        scent.deposit(IVec2::new(-1, 2), 1.0);
+        assert_eq!(scent.sample(IVec2::new(2, 2)), 4.0);
+        assert_eq!(scent.sample(IVec2::new(9, 9)), 0.0);
+    }
+
+    #[test]
+    fn test_decay() {
+        let mut scent = ScentMap::new(3, 3);
+        scent.deposit(IVec2::new(1, 1), 10.0);
+        scent.decay(0.5);
+        assert!((scent.sample(IVec2::new(1, 1)) - 5.0).abs() < 1e-5);
+        scent.decay(1.0);
+        assert_eq!(scent.sample(IVec2::new(1, 1)), 0.0);
+    }
+
+    #[test]
+    fn test_diffuse_conserves_total() {
+        let mut scent = ScentMap::new(5, 5);
+        scent.deposit(IVec2::new(2, 2), 8.0);
+        scent.diffuse(0.5, |_| true);
+
+        let total: f32 = scent.values().iter().sum();
+        assert!((total - 8.0).abs() < 1e-4);
+        assert!((scent.sample(IVec2::new(2, 2)) - 4.0).abs() < 1e-5);
+        assert!((scent.sample(IVec2::new(3, 3)) - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_diffuse_respects_blockers() {
+        let mut scent = ScentMap::new(3, 1);
+        scent.deposit(IVec2::new(0, 0), 8.0);
+        scent.diffuse(1.0, |p| p.x != 1);
+        assert_eq!(scent.sample(IVec2::new(1, 0)), 0.0);
+        assert_eq!(scent.sample(IVec2::new(0, 0)), 8.0);
+    }
+
+    #[test]
+    fn test_gradient() {
+        let mut scent = ScentMap::new(5, 5);
+        assert_eq!(scent.gradient(IVec2::new(2, 2)), None);
+        scent.deposit(IVec2::new(3, 1), 1.0);
+        assert_eq!(scent.gradient(IVec2::new(2, 2)), Some(IVec2::new(3, 1)));
+        // Already at the peak.
+        assert_eq!(scent.gradient(IVec2::new(3, 1)), None);
+    }
+}
@@ -0,0 +1,408 @@
+//! Text snapshots of generated maps.
+//!
+//! [`Dungeon`](crate::bsp::Dungeon), [`CaveMap`](crate::caves::CaveMap) and
+//! [`DrunkardMap`](crate::drunkard::DrunkardMap) can be written to and read from
+//! plain text with `to_ascii` / `from_ascii`. This makes maps easy to embed as
+//! test fixtures, compare against golden files and edit by hand.
+//!
+//! An [`AsciiLegend`] decides which characters mean floor and wall. The default
+//! writes `.` for floor and `#` for wall.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_algorithms::prelude::*;
+//!
+//! let text = "#####\n#..~#\n#####\n";
+//! let legend = AsciiLegend::new().also_floor('~');
+//! let cave = CaveMap::from_ascii(text, &legend).unwrap();
+//! assert!(cave.is_floor(3, 1));
+//! assert_eq!(cave.to_ascii(), "#####\n#...#\n#####\n");
+//! ```
+//!
+//! Maps also implement [`Display`](fmt::Display), and `display(&options)`
+//! draws [`AsciiOptions`] overlays (room numbers, corridors, marked tiles) on
+//! top, so a map can be dropped straight into an assertion or error message.
+
+use std::fmt;
+
+use runeforge_geometry::prelude::{IVec2, Rect};
+use thiserror::Error;
+
+use crate::bsp::Corridor;
+
+/// Maps characters to floor and wall tiles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsciiLegend {
+    floor: char,
+    wall: char,
+    extra_floor: Vec<char>,
+    extra_wall: Vec<char>,
+}
+
+impl Default for AsciiLegend {
+    fn default() -> Self {
+        Self {
+            floor: '.',
+            wall: '#',
+            extra_floor: Vec::new(),
+            extra_wall: Vec::new(),
+        }
+    }
+}
+
+impl AsciiLegend {
+    /// Creates the default legend (`.` floor, `#` wall).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the character written for floor tiles.
+    pub fn with_floor(mut self, glyph: char) -> Self {
+        self.floor = glyph;
+        self
+    }
+
+    /// Sets the character written for wall tiles.
+    pub fn with_wall(mut self, glyph: char) -> Self {
+        self.wall = glyph;
+        self
+    }
+
+    /// Adds another character that reads as floor.
+    pub fn also_floor(mut self, glyph: char) -> Self {
+        self.extra_floor.push(glyph);
+        self
+    }
+
+    /// Adds another character that reads as wall.
+    pub fn also_wall(mut self, glyph: char) -> Self {
+        self.extra_wall.push(glyph);
+        self
+    }
+
+    /// Returns the character written for floor tiles.
+    pub fn floor(&self) -> char {
+        self.floor
+    }
+
+    /// Returns the character written for wall tiles.
+    pub fn wall(&self) -> char {
+        self.wall
+    }
+
+    /// Returns `Some(true)` for floor characters, `Some(false)` for walls and
+    /// `None` for characters not in the legend.
+    pub fn classify(&self, glyph: char) -> Option<bool> {
+        if glyph == self.floor || self.extra_floor.contains(&glyph) {
+            Some(true)
+        } else if glyph == self.wall || self.extra_wall.contains(&glyph) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the character for a tile.
+    pub fn glyph(&self, is_floor: bool) -> char {
+        if is_floor {
+            self.floor
+        } else {
+            self.wall
+        }
+    }
+}
+
+/// Overlays drawn on top of a map by `display`.
+///
+/// Later overlays win: corridors are drawn first, then room numbers, then marks.
+///
+/// # Example
+///
+/// ```
+/// use runeforge_algorithms::prelude::*;
+/// use runeforge_geometry::prelude::{IVec2, Rect};
+///
+/// let mut dungeon = Dungeon::new(7, 3);
+/// dungeon.carve_room(Rect::new(IVec2::new(1, 1), IVec2::new(2, 1)));
+/// dungeon.carve_room(Rect::new(IVec2::new(5, 1), IVec2::new(5, 1)));
+/// dungeon.carve_corridor(&Corridor::straight(IVec2::new(3, 1), IVec2::new(4, 1)));
+///
+/// let options = AsciiOptions::new()
+///     .with_room_numbers()
+///     .with_corridors('+')
+///     .with_mark(IVec2::new(2, 1), '@');
+/// assert_eq!(dungeon.display(&options).to_string(), "#######\n#0@++1#\n#######\n");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AsciiOptions {
+    legend: AsciiLegend,
+    room_numbers: bool,
+    corridor: Option<char>,
+    marks: Vec<(IVec2, char)>,
+}
+
+impl AsciiOptions {
+    /// Creates options that render plain tiles with the default legend.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the legend used for floor and wall tiles.
+    pub fn with_legend(mut self, legend: AsciiLegend) -> Self {
+        self.legend = legend;
+        self
+    }
+
+    /// Writes each room's index in its top-left corner, truncated to the room width.
+    pub fn with_room_numbers(mut self) -> Self {
+        self.room_numbers = true;
+        self
+    }
+
+    /// Draws corridor tiles outside rooms with `glyph`.
+    pub fn with_corridors(mut self, glyph: char) -> Self {
+        self.corridor = Some(glyph);
+        self
+    }
+
+    /// Draws `glyph` at one position.
+    pub fn with_mark(mut self, pos: IVec2, glyph: char) -> Self {
+        self.marks.push((pos, glyph));
+        self
+    }
+
+    /// Draws `glyph` at every position, such as the tiles in a field of view.
+    pub fn with_marks(mut self, positions: impl IntoIterator<Item = IVec2>, glyph: char) -> Self {
+        self.marks.extend(positions.into_iter().map(|p| (p, glyph)));
+        self
+    }
+
+    /// Returns the legend used for floor and wall tiles.
+    pub fn legend(&self) -> &AsciiLegend {
+        &self.legend
+    }
+}
+
+/// A map formatted with [`AsciiOptions`], returned by `display`.
+#[derive(Debug, Clone)]
+pub struct AsciiDisplay<'a> {
+    width: u32,
+    tiles: &'a [bool],
+    rooms: &'a [Rect],
+    corridors: &'a [Corridor],
+    options: &'a AsciiOptions,
+}
+
+impl<'a> AsciiDisplay<'a> {
+    pub(crate) fn new(width: u32, tiles: &'a [bool], options: &'a AsciiOptions) -> Self {
+        Self {
+            width,
+            tiles,
+            rooms: &[],
+            corridors: &[],
+            options,
+        }
+    }
+
+    pub(crate) fn with_layout(mut self, rooms: &'a [Rect], corridors: &'a [Corridor]) -> Self {
+        self.rooms = rooms;
+        self.corridors = corridors;
+        self
+    }
+}
+
+impl fmt::Display for AsciiDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.width.max(1) as usize;
+        let mut grid: Vec<char> = self
+            .tiles
+            .iter()
+            .map(|&t| self.options.legend.glyph(t))
+            .collect();
+        let height = (grid.len() / width) as i32;
+        let mut put = |pos: IVec2, glyph: char| {
+            if pos.x >= 0 && pos.y >= 0 && pos.x < width as i32 && pos.y < height {
+                grid[pos.y as usize * width + pos.x as usize] = glyph;
+            }
+        };
+
+        if let Some(glyph) = self.options.corridor {
+            let in_room = |p: IVec2| {
+                self.rooms
+                    .iter()
+                    .any(|r| p.cmpge(r.min).all() && p.cmple(r.max).all())
+            };
+            for p in self.corridors.iter().flat_map(Corridor::points) {
+                if !in_room(p) {
+                    put(p, glyph);
+                }
+            }
+        }
+        if self.options.room_numbers {
+            for (i, room) in self.rooms.iter().enumerate() {
+                let room_width = (room.max.x - room.min.x + 1).max(0) as usize;
+                for (dx, digit) in i.to_string().chars().take(room_width).enumerate() {
+                    put(room.min + IVec2::new(dx as i32, 0), digit);
+                }
+            }
+        }
+        for &(pos, glyph) in &self.options.marks {
+            put(pos, glyph);
+        }
+
+        for row in grid.chunks(width) {
+            for &glyph in row {
+                write!(f, "{glyph}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Errors that can occur when reading a map from text.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AsciiMapError {
+    /// The text contains no rows.
+    #[error("ASCII map is empty")]
+    Empty,
+    /// A row has a different length than the first row.
+    #[error("Row {row} has {found} columns, expected {expected}")]
+    RaggedRow {
+        /// Zero-based row index
+        row: usize,
+        /// Length of the first row
+        expected: usize,
+        /// Length of this row
+        found: usize,
+    },
+    /// A character is not in the legend.
+    #[error("Unknown glyph '{glyph}' at ({x}, {y})")]
+    UnknownGlyph {
+        /// The character
+        glyph: char,
+        /// Column of the character
+        x: u32,
+        /// Row of the character
+        y: u32,
+    },
+}
+
+impl AsciiMapError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AsciiMapError::Empty => "ascii.empty",
+            AsciiMapError::RaggedRow { .. } => "ascii.ragged_row",
+            AsciiMapError::UnknownGlyph { .. } => "ascii.unknown_glyph",
+        }
+    }
+}
+
+/// Parses text into `(width, height, tiles)`.
+///
+/// Blank lines before the first row and after the last row are ignored.
+pub(crate) fn parse(
+    text: &str,
+    legend: &AsciiLegend,
+) -> Result<(u32, u32, Vec<bool>), AsciiMapError> {
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.iter().position(|l| !l.is_empty());
+    let end = lines.iter().rposition(|l| !l.is_empty());
+    let (Some(start), Some(end)) = (start, end) else {
+        return Err(AsciiMapError::Empty);
+    };
+
+    let rows = &lines[start..=end];
+    let width = rows[0].chars().count();
+    let mut tiles = Vec::with_capacity(width * rows.len());
+    for (y, row) in rows.iter().enumerate() {
+        let found = row.chars().count();
+        if found != width {
+            return Err(AsciiMapError::RaggedRow {
+                row: y,
+                expected: width,
+                found,
+            });
+        }
+        for (x, glyph) in row.chars().enumerate() {
+            let tile = legend.classify(glyph).ok_or(AsciiMapError::UnknownGlyph {
+                glyph,
+                x: x as u32,
+                y: y as u32,
+            })?;
+            tiles.push(tile);
+        }
+    }
+    Ok((width as u32, rows.len() as u32, tiles))
+}
+
+/// Renders tiles as text, one line per row.
+pub(crate) fn render(width: u32, tiles: &[bool], legend: &AsciiLegend) -> String {
+    let mut out = String::with_capacity(tiles.len() + tiles.len() / width.max(1) as usize);
+    for row in tiles.chunks(width.max(1) as usize) {
+        out.extend(row.iter().map(|&t| legend.glyph(t)));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_round_trip() {
+        let text = "\n#.#\n...\n\n";
+        let (w, h, tiles) = parse(text, &AsciiLegend::new()).unwrap();
+        assert_eq!((w, h), (3, 2));
+        assert_eq!(render(w, &tiles, &AsciiLegend::new()), "#.#\n...\n");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let legend = AsciiLegend::new();
+        assert_eq!(parse("\n\n", &legend), Err(AsciiMapError::Empty));
+        assert_eq!(
+            parse("##\n#", &legend),
+            Err(AsciiMapError::RaggedRow {
+                row: 1,
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            parse("#.\n#x", &legend),
+            Err(AsciiMapError::UnknownGlyph {
+                glyph: 'x',
+                x: 1,
+                y: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_display_overlays() {
+        use crate::caves::CaveMap;
+
+        let cave = CaveMap::from_ascii("####\n#..#\n####\n", &AsciiLegend::new()).unwrap();
+        assert_eq!(cave.to_string(), cave.to_ascii());
+
+        let options = AsciiOptions::new()
+            .with_legend(AsciiLegend::new().with_floor(' '))
+            .with_marks([IVec2::new(1, 1), IVec2::new(9, 9)], '*');
+        assert_eq!(cave.display(&options).to_string(), "####\n#* #\n####\n");
+    }
+
+    #[test]
+    fn test_custom_legend() {
+        let legend = AsciiLegend::new()
+            .with_floor(' ')
+            .with_wall('X')
+            .also_wall('#');
+        let (w, _, tiles) = parse("X #", &legend).unwrap();
+        assert_eq!(tiles, vec![false, true, false]);
+        assert_eq!(render(w, &tiles, &legend), "X X\n");
+    }
+}
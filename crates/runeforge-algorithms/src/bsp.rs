@@ -12,7 +12,7 @@
 //!
 //! let config = BspConfig::default();
 //! let mut rng = Rng::new();
-//! let dungeon = DungeonGenerator::generate(80, 50, &config, &mut rng);
+//! let dungeon = DungeonGenerator::generate(80, 50, &config, &mut rng).unwrap();
 //!
 //! // Access generated rooms
 //! for room in dungeon.rooms() {
@@ -29,9 +29,49 @@
 //! }
 //! ```
 
+use std::fmt;
+
 use runeforge_geometry::prelude::*;
+use runeforge_pathfinding::prelude::{CostGrid, IMPASSABLE};
 use runeforge_random::prelude::*;
 
+use crate::ascii::{self, AsciiDisplay, AsciiLegend, AsciiMapError, AsciiOptions};
+use crate::decoration::DecorationLayer;
+use crate::features::{Feature, FeatureLayer};
+use crate::trace::GenerationTrace;
+use thiserror::Error;
+
+/// Errors that can occur during BSP dungeon generation.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BspError {
+    /// Invalid dimensions (width or height is zero).
+    #[error("Invalid dungeon dimensions: {width}x{height} (both must be > 0)")]
+    InvalidDimensions {
+        /// Map width
+        width: u32,
+        /// Map height
+        height: u32,
+    },
+    /// A configuration value would produce a degenerate dungeon.
+    #[error("Invalid BSP config field '{field}': {reason}")]
+    InvalidConfig {
+        /// Name of the offending field
+        field: &'static str,
+        /// Why the value is rejected
+        reason: String,
+    },
+}
+
+impl BspError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            BspError::InvalidDimensions { .. } => "bsp.invalid_dimensions",
+            BspError::InvalidConfig { .. } => "bsp.invalid_config",
+        }
+    }
+}
+
 /// Configuration for BSP dungeon generation.
 ///
 /// Controls the splitting behavior and room generation parameters.
@@ -53,6 +93,8 @@ pub struct BspConfig {
     pub split_ratio_min: f32,
     /// Split ratio range maximum
     pub split_ratio_max: f32,
+    /// Chance (0.0 to 1.0) of placing a closed door where a corridor enters a room
+    pub door_chance: f32,
 }
 
 impl Default for BspConfig {
@@ -66,6 +108,7 @@ impl Default for BspConfig {
             max_depth: 5,
             split_ratio_min: 0.4,
             split_ratio_max: 0.6,
+            door_chance: 0.0,
         }
     }
 }
@@ -108,6 +151,78 @@ impl BspConfig {
         self.split_ratio_max = max.clamp(0.1, 0.9);
         self
     }
+
+    /// Sets the chance of placing a door at each room entrance.
+    pub fn with_door_chance(mut self, chance: f32) -> Self {
+        self.door_chance = chance.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Checks that the configuration can produce rooms.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BspError::InvalidConfig` naming the first offending field,
+    /// for example when a padded minimum room does not fit in a minimum
+    /// partition or the split ratios are inverted.
+    pub fn validate(&self) -> Result<(), BspError> {
+        let invalid = |field, reason: String| Err(BspError::InvalidConfig { field, reason });
+
+        if self.min_room_width == 0 {
+            return invalid("min_room_width", "must be at least 1".into());
+        }
+        if self.min_room_height == 0 {
+            return invalid("min_room_height", "must be at least 1".into());
+        }
+        let padded_width = self.min_room_width + self.room_padding * 2;
+        if padded_width > self.min_partition_width {
+            return invalid(
+                "min_room_width",
+                format!(
+                    "room width {} plus padding {} exceeds min_partition_width {}",
+                    self.min_room_width,
+                    self.room_padding * 2,
+                    self.min_partition_width
+                ),
+            );
+        }
+        let padded_height = self.min_room_height + self.room_padding * 2;
+        if padded_height > self.min_partition_height {
+            return invalid(
+                "min_room_height",
+                format!(
+                    "room height {} plus padding {} exceeds min_partition_height {}",
+                    self.min_room_height,
+                    self.room_padding * 2,
+                    self.min_partition_height
+                ),
+            );
+        }
+        for (field, ratio) in [
+            ("split_ratio_min", self.split_ratio_min),
+            ("split_ratio_max", self.split_ratio_max),
+        ] {
+            if !(ratio > 0.0 && ratio < 1.0) {
+                return invalid(field, format!("{} is outside 0.0-1.0 (exclusive)", ratio));
+            }
+        }
+        if self.split_ratio_min > self.split_ratio_max {
+            return invalid(
+                "split_ratio_min",
+                format!(
+                    "{} is greater than split_ratio_max {}",
+                    self.split_ratio_min, self.split_ratio_max
+                ),
+            );
+        }
+        if !(0.0..=1.0).contains(&self.door_chance) {
+            return invalid(
+                "door_chance",
+                format!("{} is outside 0.0-1.0", self.door_chance),
+            );
+        }
+        Ok(())
+    }
 }
 
 /// Direction of a BSP split.
@@ -354,6 +469,17 @@ impl BspNode {
         }
     }
 
+    fn collect_leaf_bounds(&self) -> Vec<Rect> {
+        if self.is_leaf() {
+            return vec![self.bounds];
+        }
+        let mut bounds = Vec::new();
+        for child in [self.left(), self.right()].into_iter().flatten() {
+            bounds.extend(child.collect_leaf_bounds());
+        }
+        bounds
+    }
+
     /// Gets a room from this subtree (for corridor generation).
     pub fn get_room(&self) -> Option<Rect> {
         if let Some(room) = self.room {
@@ -437,6 +563,25 @@ impl Corridor {
     }
 }
 
+/// Identifies a room by its index in [`Dungeon::rooms`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RoomId(pub usize);
+
+/// A label attached to a room by generation hooks or game code.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RoomTag {
+    /// Where the player enters the level
+    Entrance,
+    /// Where the stairs down are
+    Exit,
+    /// Holds loot
+    Treasure,
+    /// Holds the level boss
+    Boss,
+    /// Any game-specific tag
+    Custom(String),
+}
+
 /// A generated dungeon with rooms and corridors.
 #[derive(Debug, Clone)]
 pub struct Dungeon {
@@ -444,7 +589,10 @@ pub struct Dungeon {
     height: u32,
     tiles: Vec<bool>, // true = floor, false = wall
     rooms: Vec<Rect>,
+    room_tags: Vec<Vec<RoomTag>>,
     corridors: Vec<Corridor>,
+    features: FeatureLayer,
+    decorations: DecorationLayer,
 }
 
 impl Dungeon {
@@ -455,7 +603,10 @@ impl Dungeon {
             height,
             tiles: vec![false; (width * height) as usize],
             rooms: Vec::new(),
+            room_tags: Vec::new(),
             corridors: Vec::new(),
+            features: FeatureLayer::new(),
+            decorations: DecorationLayer::new(),
         }
     }
 
@@ -477,9 +628,64 @@ impl Dungeon {
         self.tiles[(y as u32 * self.width + x as u32) as usize]
     }
 
-    /// Returns true if the position is walkable (a floor tile).
+    /// Returns true if the position is walkable (a floor tile not blocked by a
+    /// feature or decoration).
     pub fn is_walkable(&self, pos: IVec2) -> bool {
         self.is_floor(pos.x, pos.y)
+            && self.features.movement_cost(pos).is_some()
+            && !self.decorations.blocks_movement(pos)
+    }
+
+    /// Returns true if the position blocks line of sight (a wall or a closed door).
+    pub fn is_opaque(&self, pos: IVec2) -> bool {
+        !self.is_floor(pos.x, pos.y) || self.features.is_opaque(pos)
+    }
+
+    /// Returns the interactive features placed on the map.
+    pub fn features(&self) -> &FeatureLayer {
+        &self.features
+    }
+
+    /// Returns the interactive features for modification.
+    pub fn features_mut(&mut self) -> &mut FeatureLayer {
+        &mut self.features
+    }
+
+    /// Returns the decorations placed on the map.
+    pub fn decorations(&self) -> &DecorationLayer {
+        &self.decorations
+    }
+
+    /// Returns the decorations for modification.
+    pub fn decorations_mut(&mut self) -> &mut DecorationLayer {
+        &mut self.decorations
+    }
+
+    /// Returns the floor tiles just outside a room that lead into corridors.
+    ///
+    /// Only chokepoints (tiles with walls on both sides across the entrance)
+    /// are returned, so each one is a good spot for a door.
+    pub fn room_entrances(&self, room: Rect) -> Vec<IVec2> {
+        let outer = Rect::new(room.min - IVec2::ONE, room.max + IVec2::ONE);
+        let mut entrances = Vec::new();
+        for p in outer.points() {
+            let on_ring = p.x == outer.min.x
+                || p.x == outer.max.x
+                || p.y == outer.min.y
+                || p.y == outer.max.y;
+            let corner = (p.x == outer.min.x || p.x == outer.max.x)
+                && (p.y == outer.min.y || p.y == outer.max.y);
+            if !on_ring || corner || !self.is_floor(p.x, p.y) {
+                continue;
+            }
+            let wall = |d: IVec2| !self.is_floor(p.x + d.x, p.y + d.y);
+            let horizontal_gap = wall(IVec2::X) && wall(IVec2::NEG_X);
+            let vertical_gap = wall(IVec2::Y) && wall(IVec2::NEG_Y);
+            if horizontal_gap || vertical_gap {
+                entrances.push(p);
+            }
+        }
+        entrances
     }
 
     /// Sets a tile to floor.
@@ -493,6 +699,7 @@ impl Dungeon {
     pub fn carve_room(&mut self, room: Rect) {
         room.for_each(|p| self.set_floor(p.x, p.y));
         self.rooms.push(room);
+        self.room_tags.push(Vec::new());
     }
 
     /// Carves out a corridor.
@@ -508,6 +715,97 @@ impl Dungeon {
         &self.rooms
     }
 
+    /// Returns the room with the given id.
+    pub fn room(&self, id: RoomId) -> Option<Rect> {
+        self.rooms.get(id.0).copied()
+    }
+
+    /// Returns the room containing a position, if any.
+    pub fn room_at(&self, pos: IVec2) -> Option<RoomId> {
+        self.rooms
+            .iter()
+            .position(|r| {
+                pos.x >= r.min.x && pos.x <= r.max.x && pos.y >= r.min.y && pos.y <= r.max.y
+            })
+            .map(RoomId)
+    }
+
+    /// Returns every pair of rooms joined directly by a corridor.
+    ///
+    /// A corridor that passes through intermediate rooms connects each
+    /// consecutive pair of rooms along its path. Each pair is listed once, with
+    /// the lower id first.
+    pub fn room_connections(&self) -> Vec<(RoomId, RoomId)> {
+        let mut connections = Vec::new();
+        for corridor in &self.corridors {
+            let mut previous: Option<RoomId> = None;
+            for point in corridor.points() {
+                let Some(room) = self.room_at(point) else {
+                    continue;
+                };
+                if let Some(prev) = previous.filter(|&p| p != room) {
+                    let pair = (prev.min(room), prev.max(room));
+                    if !connections.contains(&pair) {
+                        connections.push(pair);
+                    }
+                }
+                previous = Some(room);
+            }
+        }
+        connections.sort();
+        connections
+    }
+
+    /// Returns the rooms directly connected to `room` by corridors.
+    pub fn adjacent_rooms(&self, room: RoomId) -> Vec<RoomId> {
+        self.room_connections()
+            .into_iter()
+            .filter_map(|(a, b)| match (a == room, b == room) {
+                (true, _) => Some(b),
+                (_, true) => Some(a),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Adds a tag to a room. Returns false if the room doesn't exist.
+    pub fn tag_room(&mut self, room: RoomId, tag: RoomTag) -> bool {
+        match self.room_tags.get_mut(room.0) {
+            Some(tags) => {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes a tag from a room.
+    pub fn untag_room(&mut self, room: RoomId, tag: &RoomTag) {
+        if let Some(tags) = self.room_tags.get_mut(room.0) {
+            tags.retain(|t| t != tag);
+        }
+    }
+
+    /// Returns the tags on a room.
+    pub fn room_tags(&self, room: RoomId) -> &[RoomTag] {
+        self.room_tags.get(room.0).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns true if a room has the given tag.
+    pub fn room_has_tag(&self, room: RoomId, tag: &RoomTag) -> bool {
+        self.room_tags(room).contains(tag)
+    }
+
+    /// Returns every room with the given tag.
+    pub fn rooms_with_tag(&self, tag: &RoomTag) -> Vec<RoomId> {
+        (0..self.rooms.len())
+            .map(RoomId)
+            .filter(|&id| self.room_has_tag(id, tag))
+            .collect()
+    }
+
     /// Returns all corridors in the dungeon.
     pub fn corridors(&self) -> &[Corridor] {
         &self.corridors
@@ -518,6 +816,38 @@ impl Dungeon {
         &self.tiles
     }
 
+    /// Renders the map as text using the default legend (`.` floor, `#` wall).
+    pub fn to_ascii(&self) -> String {
+        self.to_ascii_with(&AsciiLegend::default())
+    }
+
+    /// Renders the map as text using a custom legend.
+    pub fn to_ascii_with(&self, legend: &AsciiLegend) -> String {
+        ascii::render(self.width, &self.tiles, legend)
+    }
+
+    /// Formats the map with overlays such as room numbers and corridors.
+    pub fn display<'a>(&'a self, options: &'a AsciiOptions) -> AsciiDisplay<'a> {
+        AsciiDisplay::new(self.width, &self.tiles, options)
+            .with_layout(&self.rooms, &self.corridors)
+    }
+
+    /// Reads a map from text, one line per row.
+    ///
+    /// Only the tiles are restored: the result has no rooms, corridors or
+    /// features, so room queries return nothing until rooms are added.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AsciiMapError`] if the text is empty, rows have different
+    /// lengths, or a character is not in the legend.
+    pub fn from_ascii(text: &str, legend: &AsciiLegend) -> Result<Self, AsciiMapError> {
+        let (width, height, tiles) = ascii::parse(text, legend)?;
+        let mut map = Self::new(width, height);
+        map.tiles = tiles;
+        Ok(map)
+    }
+
     /// Returns a random floor position (useful for spawning entities).
     pub fn random_floor_position(&self, rng: &mut Rng) -> Option<IVec2> {
         let floor_tiles: Vec<IVec2> = (0..self.height)
@@ -536,6 +866,27 @@ impl Dungeon {
     }
 }
 
+impl fmt::Display for Dungeon {
+    /// Renders the map with the default legend, like [`Dungeon::to_ascii`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_ascii())
+    }
+}
+
+impl From<&Dungeon> for CostGrid {
+    /// Floor tiles cost their feature's movement cost (closed doors cost more);
+    /// walls, locked doors and blocking decorations are impassable.
+    fn from(dungeon: &Dungeon) -> Self {
+        CostGrid::from_fn(dungeon.width, dungeon.height, |pos| {
+            if dungeon.is_floor(pos.x, pos.y) && !dungeon.decorations.blocks_movement(pos) {
+                dungeon.features.movement_cost(pos).unwrap_or(IMPASSABLE)
+            } else {
+                IMPASSABLE
+            }
+        })
+    }
+}
+
 /// Generator for BSP-based dungeons.
 pub struct DungeonGenerator;
 
@@ -552,7 +903,83 @@ impl DungeonGenerator {
     /// # Returns
     ///
     /// A `Dungeon` with rooms and corridors carved out.
-    pub fn generate(width: u32, height: u32, config: &BspConfig, rng: &mut Rng) -> Dungeon {
+    ///
+    /// # Errors
+    ///
+    /// Returns `BspError::InvalidDimensions` if width or height is zero, or
+    /// `BspError::InvalidConfig` if [`BspConfig::validate`] fails.
+    pub fn generate(
+        width: u32,
+        height: u32,
+        config: &BspConfig,
+        rng: &mut Rng,
+    ) -> Result<Dungeon, BspError> {
+        Self::generate_with(width, height, config, rng, |_, _| {})
+    }
+
+    /// Generates a dungeon and then runs a hook on it.
+    ///
+    /// The hook runs after rooms, corridors and doors are placed and is the
+    /// place to tag rooms, add features or pick spawn points.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use runeforge_algorithms::bsp::{BspConfig, DungeonGenerator, RoomId, RoomTag};
+    /// use runeforge_random::prelude::Rng;
+    ///
+    /// let mut rng = Rng::with_seed(3);
+    /// let dungeon = DungeonGenerator::generate_with(80, 50, &BspConfig::default(), &mut rng, |d, _| {
+    ///     d.tag_room(RoomId(0), RoomTag::Entrance);
+    ///     let last = RoomId(d.rooms().len() - 1);
+    ///     d.tag_room(last, RoomTag::Boss);
+    /// }).unwrap();
+    /// assert_eq!(dungeon.rooms_with_tag(&RoomTag::Entrance), vec![RoomId(0)]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Same as [`generate`](Self::generate); the hook is not run on failure.
+    pub fn generate_with(
+        width: u32,
+        height: u32,
+        config: &BspConfig,
+        rng: &mut Rng,
+        hook: impl FnMut(&mut Dungeon, &mut Rng),
+    ) -> Result<Dungeon, BspError> {
+        Self::generate_inner(width, height, config, rng, hook, None)
+    }
+
+    /// Generates a dungeon, recording the partitions, rooms and each corridor.
+    ///
+    /// Produces the same map as [`generate`](Self::generate) for the same seed.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`generate`](Self::generate).
+    pub fn generate_traced(
+        width: u32,
+        height: u32,
+        config: &BspConfig,
+        rng: &mut Rng,
+        trace: &mut GenerationTrace,
+    ) -> Result<Dungeon, BspError> {
+        Self::generate_inner(width, height, config, rng, |_, _| {}, Some(trace))
+    }
+
+    fn generate_inner(
+        width: u32,
+        height: u32,
+        config: &BspConfig,
+        rng: &mut Rng,
+        mut hook: impl FnMut(&mut Dungeon, &mut Rng),
+        mut trace: Option<&mut GenerationTrace>,
+    ) -> Result<Dungeon, BspError> {
+        if width == 0 || height == 0 {
+            return Err(BspError::InvalidDimensions { width, height });
+        }
+        config.validate()?;
+
         let mut dungeon = Dungeon::new(width, height);
 
         // Create root BSP node covering the entire dungeon
@@ -562,6 +989,22 @@ impl DungeonGenerator {
         // Recursively split the space
         root.split_recursive(config, rng, 0);
 
+        let partitions = if trace.is_some() {
+            root.collect_leaf_bounds()
+        } else {
+            Vec::new()
+        };
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.record(
+                "partitions",
+                width,
+                height,
+                &dungeon.tiles,
+                Vec::new(),
+                partitions.clone(),
+            );
+        }
+
         // Create rooms in leaf nodes
         root.create_rooms_recursive(config, rng);
 
@@ -570,10 +1013,57 @@ impl DungeonGenerator {
             dungeon.carve_room(room);
         }
 
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.record(
+                "rooms",
+                width,
+                height,
+                &dungeon.tiles,
+                Vec::new(),
+                partitions,
+            );
+        }
+
         // Generate corridors between sibling rooms
         Self::generate_corridors(&root, &mut dungeon, rng);
 
-        dungeon
+        if let Some(trace) = trace.as_deref_mut() {
+            // Replay the corridors one at a time on top of the rooms.
+            let mut tiles = vec![false; dungeon.tiles.len()];
+            for room in &dungeon.rooms {
+                room.for_each(|p| tiles[(p.y as u32 * width + p.x as u32) as usize] = true);
+            }
+            for (i, corridor) in dungeon.corridors.iter().enumerate() {
+                let points: Vec<IVec2> = corridor
+                    .points()
+                    .into_iter()
+                    .filter(|p| p.x >= 0 && p.y >= 0 && p.x < width as i32 && p.y < height as i32)
+                    .collect();
+                for p in &points {
+                    tiles[(p.y as u32 * width + p.x as u32) as usize] = true;
+                }
+                trace.record(
+                    format!("corridor {}", i + 1),
+                    width,
+                    height,
+                    &tiles,
+                    points,
+                    Vec::new(),
+                );
+            }
+        }
+
+        if config.door_chance > 0.0 {
+            Self::place_doors(&mut dungeon, config.door_chance, rng);
+            if let Some(trace) = trace {
+                let doors = dungeon.features.iter().map(|(p, _)| p).collect();
+                trace.record("doors", width, height, &dungeon.tiles, doors, Vec::new());
+            }
+        }
+
+        hook(&mut dungeon, rng);
+
+        Ok(dungeon)
     }
 
     /// Generates corridors by traversing the BSP tree.
@@ -596,6 +1086,20 @@ impl DungeonGenerator {
         }
     }
 
+    /// Places closed doors at room entrances.
+    fn place_doors(dungeon: &mut Dungeon, chance: f32, rng: &mut Rng) {
+        let entrances: Vec<IVec2> = dungeon
+            .rooms
+            .iter()
+            .flat_map(|&room| dungeon.room_entrances(room))
+            .collect();
+        for pos in entrances {
+            if dungeon.features.get(pos).is_none() && rng.chance(chance) {
+                dungeon.features.place(pos, Feature::door());
+            }
+        }
+    }
+
     /// Creates a corridor between two rooms.
     fn create_corridor(room1: Rect, room2: Rect, rng: &mut Rng) -> Corridor {
         let center1 = room1.center();
@@ -617,6 +1121,8 @@ impl DungeonGenerator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::features::CLOSED_DOOR_MOVEMENT_COST;
+    use runeforge_pathfinding::prelude::PathFinder;
 
     #[test]
     fn test_bsp_config_default() {
@@ -627,6 +1133,36 @@ mod tests {
         assert_eq!(config.max_depth, 5);
     }
 
+    #[test]
+    fn test_bsp_config_validate() {
+        assert!(BspConfig::default().validate().is_ok());
+
+        let too_big = BspConfig::new().with_min_room_size(9, 4);
+        assert!(matches!(
+            too_big.validate(),
+            Err(BspError::InvalidConfig {
+                field: "min_room_width",
+                ..
+            })
+        ));
+
+        let inverted = BspConfig::new().with_split_ratio(0.7, 0.3);
+        assert!(matches!(
+            inverted.validate(),
+            Err(BspError::InvalidConfig {
+                field: "split_ratio_min",
+                ..
+            })
+        ));
+
+        let mut rng = Rng::with_seed(1);
+        assert!(DungeonGenerator::generate(80, 50, &too_big, &mut rng).is_err());
+        assert!(matches!(
+            DungeonGenerator::generate(0, 50, &BspConfig::default(), &mut rng),
+            Err(BspError::InvalidDimensions { .. })
+        ));
+    }
+
     #[test]
     fn test_bsp_config_builder() {
         let config = BspConfig::new()
@@ -707,7 +1243,7 @@ mod tests {
         let config = BspConfig::default();
         let mut rng = Rng::new();
 
-        let dungeon = DungeonGenerator::generate(80, 50, &config, &mut rng);
+        let dungeon = DungeonGenerator::generate(80, 50, &config, &mut rng).unwrap();
 
         assert_eq!(dungeon.width(), 80);
         assert_eq!(dungeon.height(), 50);
@@ -720,7 +1256,7 @@ mod tests {
         let config = BspConfig::default();
         let mut rng = Rng::new();
 
-        let dungeon = DungeonGenerator::generate(80, 50, &config, &mut rng);
+        let dungeon = DungeonGenerator::generate(80, 50, &config, &mut rng).unwrap();
 
         // Count floor tiles
         let floor_count: usize = dungeon.tiles().iter().filter(|&&t| t).count();
@@ -733,7 +1269,7 @@ mod tests {
         let config = BspConfig::default();
         let mut rng = Rng::new();
 
-        let dungeon = DungeonGenerator::generate(80, 50, &config, &mut rng);
+        let dungeon = DungeonGenerator::generate(80, 50, &config, &mut rng).unwrap();
 
         for room in dungeon.rooms() {
             assert!(room.min.x >= 0);
@@ -763,7 +1299,7 @@ mod tests {
         let config = BspConfig::default();
         let mut rng = Rng::new();
 
-        let dungeon = DungeonGenerator::generate(80, 50, &config, &mut rng);
+        let dungeon = DungeonGenerator::generate(80, 50, &config, &mut rng).unwrap();
 
         let pos = dungeon.random_floor_position(&mut rng);
         assert!(pos.is_some());
@@ -772,6 +1308,120 @@ mod tests {
         assert!(dungeon.is_floor(pos.x, pos.y));
     }
 
+    #[test]
+    fn test_doors_placed_at_entrances() {
+        let config = BspConfig::new().with_door_chance(1.0);
+        let mut rng = Rng::with_seed(11);
+        let dungeon = DungeonGenerator::generate(80, 50, &config, &mut rng).unwrap();
+
+        assert!(!dungeon.features().is_empty());
+        for (pos, feature) in dungeon.features().iter() {
+            assert_eq!(*feature, Feature::door());
+            assert!(dungeon.is_floor(pos.x, pos.y));
+            assert!(dungeon.is_opaque(pos));
+            assert!(dungeon.is_walkable(pos));
+        }
+    }
+
+    #[test]
+    fn test_no_doors_by_default() {
+        let mut rng = Rng::with_seed(11);
+        let dungeon = DungeonGenerator::generate(80, 50, &BspConfig::default(), &mut rng).unwrap();
+        assert!(dungeon.features().is_empty());
+    }
+
+    #[test]
+    fn test_room_at_and_connections() {
+        let mut dungeon = Dungeon::new(30, 10);
+        dungeon.carve_room(Rect::new_xywh(1, 1, 4, 4));
+        dungeon.carve_room(Rect::new_xywh(10, 1, 4, 4));
+        dungeon.carve_room(Rect::new_xywh(20, 1, 4, 4));
+        // One corridor running through all three rooms.
+        dungeon.carve_corridor(&Corridor::straight(IVec2::new(3, 3), IVec2::new(22, 3)));
+
+        assert_eq!(dungeon.room_at(IVec2::new(2, 2)), Some(RoomId(0)));
+        assert_eq!(dungeon.room_at(IVec2::new(7, 3)), None);
+        assert_eq!(
+            dungeon.room_connections(),
+            vec![(RoomId(0), RoomId(1)), (RoomId(1), RoomId(2))]
+        );
+        assert_eq!(
+            dungeon.adjacent_rooms(RoomId(1)),
+            vec![RoomId(0), RoomId(2)]
+        );
+    }
+
+    #[test]
+    fn test_room_tags() {
+        let mut dungeon = Dungeon::new(20, 20);
+        dungeon.carve_room(Rect::new_xywh(1, 1, 4, 4));
+        dungeon.carve_room(Rect::new_xywh(10, 10, 4, 4));
+
+        assert!(dungeon.tag_room(RoomId(1), RoomTag::Treasure));
+        assert!(dungeon.tag_room(RoomId(1), RoomTag::Treasure));
+        assert!(!dungeon.tag_room(RoomId(5), RoomTag::Boss));
+        assert_eq!(dungeon.room_tags(RoomId(1)), &[RoomTag::Treasure]);
+        assert_eq!(dungeon.rooms_with_tag(&RoomTag::Treasure), vec![RoomId(1)]);
+
+        dungeon.untag_room(RoomId(1), &RoomTag::Treasure);
+        assert!(dungeon.room_tags(RoomId(1)).is_empty());
+    }
+
+    #[test]
+    fn test_generated_rooms_are_connected() {
+        let mut rng = Rng::with_seed(5);
+        let dungeon = DungeonGenerator::generate(80, 50, &BspConfig::default(), &mut rng).unwrap();
+        for id in 0..dungeon.rooms().len() {
+            assert!(!dungeon.adjacent_rooms(RoomId(id)).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_traced_generation_matches_untraced() {
+        let config = BspConfig::default();
+        let plain = DungeonGenerator::generate(60, 40, &config, &mut Rng::with_seed(21)).unwrap();
+
+        let mut trace = GenerationTrace::new();
+        let traced =
+            DungeonGenerator::generate_traced(60, 40, &config, &mut Rng::with_seed(21), &mut trace)
+                .unwrap();
+
+        assert_eq!(plain.tiles(), traced.tiles());
+        let labels: Vec<_> = trace.frames().iter().map(|f| f.label.as_str()).collect();
+        assert_eq!(labels[..2], ["partitions", "rooms"]);
+        assert_eq!(trace.len(), 2 + traced.corridors().len());
+        assert_eq!(trace.last().unwrap().tiles, traced.tiles());
+    }
+
+    #[test]
+    fn test_ascii_round_trip() {
+        let mut rng = Rng::with_seed(2);
+        let dungeon = DungeonGenerator::generate(40, 30, &BspConfig::default(), &mut rng).unwrap();
+        let text = dungeon.to_ascii();
+        let restored = Dungeon::from_ascii(&text, &AsciiLegend::default()).unwrap();
+        assert_eq!(restored.tiles(), dungeon.tiles());
+        assert_eq!(restored.width(), 40);
+        assert!(restored.rooms().is_empty());
+    }
+
+    #[test]
+    fn test_cost_grid_from_dungeon() {
+        let mut rng = Rng::with_seed(5);
+        let config = BspConfig::default().with_door_chance(1.0);
+        let dungeon = DungeonGenerator::generate(60, 40, &config, &mut rng).unwrap();
+        let mut grid = CostGrid::from(&dungeon);
+
+        let (door, _) = dungeon.features().iter().next().expect("doors were placed");
+        assert_eq!(grid.cost_at(door), CLOSED_DOOR_MOVEMENT_COST);
+        assert_eq!(grid.cost_at(IVec2::new(0, 0)), IMPASSABLE);
+
+        let start = dungeon.rooms()[0].center();
+        let goal = dungeon.rooms()[dungeon.rooms().len() - 1].center();
+        let path = PathFinder::Astar.compute(start, goal, &mut grid, ());
+        assert_eq!(path.last(), Some(&goal));
+        assert!(path.iter().all(|&p| dungeon.is_walkable(p)));
+    }
+
     #[test]
     fn test_small_dungeon() {
         let config = BspConfig::new()
@@ -780,7 +1430,7 @@ mod tests {
             .with_max_depth(2);
 
         let mut rng = Rng::new();
-        let dungeon = DungeonGenerator::generate(30, 30, &config, &mut rng);
+        let dungeon = DungeonGenerator::generate(30, 30, &config, &mut rng).unwrap();
 
         assert!(!dungeon.rooms().is_empty());
     }
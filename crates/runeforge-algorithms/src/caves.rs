@@ -6,6 +6,13 @@
 //! 2. Iterative smoothing using neighbor counts
 //! 3. Birth/survival rules (e.g., "become wall if 5+ neighbors are walls")
 //!
+//! Walls are the "live" cells. By default a cell becomes a wall when at least
+//! [`CaveConfig::wall_threshold`] of its Moore neighbors are walls. A
+//! [`CaRule`] replaces that with any birth/survival rule in `B678/S345678`
+//! notation, over Moore or von Neumann [`Neighborhood`]s, and optionally with
+//! extra decay states, so one engine can produce open caverns, mazes or
+//! coral-like growth.
+//!
 //! # Example
 //!
 //! ```
@@ -25,37 +32,264 @@
 //!     println!();
 //! }
 //! ```
+//!
+//! Custom rules:
+//!
+//! ```
+//! use runeforge_algorithms::caves::{CaRule, CaveConfig, CaveGenerator, Neighborhood};
+//! use runeforge_random::prelude::Rng;
+//!
+//! let maze = CaveConfig::new()
+//!     .with_initial_wall_probability(0.3)
+//!     .with_rule("B3/S12345".parse::<CaRule>().unwrap());
+//! let cave = CaveGenerator::generate(60, 40, &maze, &mut Rng::with_seed(2)).unwrap();
+//!
+//! let diamonds = CaveConfig::new()
+//!     .with_neighborhood(Neighborhood::VonNeumann)
+//!     .with_rule(CaRule::new(&[3, 4], &[2, 3, 4]));
+//! assert!(diamonds.validate().is_ok());
+//! ```
 
-use runeforge_random::prelude::*;
+use std::collections::VecDeque;
 use std::fmt;
+use std::str::FromStr;
+
+use runeforge_geometry::prelude::IVec2;
+use runeforge_pathfinding::prelude::CostGrid;
+use runeforge_random::prelude::*;
+use thiserror::Error;
+
+use crate::ascii::{self, AsciiDisplay, AsciiLegend, AsciiMapError, AsciiOptions};
+use crate::progress::{Progress, ProgressSink};
+use crate::trace::GenerationTrace;
 
 /// Errors that can occur during cave generation.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum CaveError {
     /// Invalid dimensions (width or height is zero).
+    #[error("Invalid cave dimensions: {width}x{height} (both must be > 0)")]
     InvalidDimensions {
         /// Map width
         width: u32,
         /// Map height
         height: u32,
     },
+    /// A configuration value would produce a degenerate cave.
+    #[error("Invalid cave config field '{field}': {reason}")]
+    InvalidConfig {
+        /// Name of the offending field
+        field: &'static str,
+        /// Why the value is rejected
+        reason: String,
+    },
+    /// Generation was cancelled through its [`ProgressSink`].
+    #[error("Cave generation was cancelled")]
+    Cancelled,
 }
 
-impl fmt::Display for CaveError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl CaveError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
         match self {
-            CaveError::InvalidDimensions { width, height } => {
-                write!(
-                    f,
-                    "Invalid cave dimensions: {}x{} (both must be > 0)",
-                    width, height
-                )
-            }
+            CaveError::InvalidDimensions { .. } => "cave.invalid_dimensions",
+            CaveError::InvalidConfig { .. } => "cave.invalid_config",
+            CaveError::Cancelled => "cave.cancelled",
         }
     }
 }
 
-impl std::error::Error for CaveError {}
+/// Which cells count as neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Neighborhood {
+    /// The 8 surrounding cells.
+    #[default]
+    Moore,
+    /// The 4 orthogonally adjacent cells.
+    VonNeumann,
+}
+
+impl Neighborhood {
+    /// Returns the number of neighbors.
+    pub fn size(&self) -> u32 {
+        match self {
+            Neighborhood::Moore => 8,
+            Neighborhood::VonNeumann => 4,
+        }
+    }
+
+    /// Returns the neighbor offsets.
+    pub fn offsets(&self) -> &'static [(i32, i32)] {
+        match self {
+            Neighborhood::Moore => &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ],
+            Neighborhood::VonNeumann => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+        }
+    }
+}
+
+/// A birth/survival rule, with walls as the live cells.
+///
+/// A floor cell becomes a wall when its wall-neighbor count is in `birth`; a
+/// wall stays a wall when its count is in `survival`. With more than two
+/// [`states`](Self::states), a wall that dies decays through the extra states
+/// before becoming floor (as in "Generations" automata). Decaying cells don't
+/// count as wall neighbors, but are walls in the finished map.
+///
+/// Rules read and print in `B678/S345678` notation, with an optional `/C3`
+/// suffix for the state count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CaRule {
+    birth: u16,
+    survival: u16,
+    states: u8,
+}
+
+impl CaRule {
+    /// Creates a two-state rule from birth and survival neighbor counts.
+    /// Counts above 15 are ignored.
+    pub fn new(birth: &[u32], survival: &[u32]) -> Self {
+        let mask = |counts: &[u32]| {
+            counts
+                .iter()
+                .filter(|&&n| n < 16)
+                .fold(0u16, |mask, &n| mask | 1 << n)
+        };
+        Self {
+            birth: mask(birth),
+            survival: mask(survival),
+            states: 2,
+        }
+    }
+
+    /// Sets the number of states, including floor and wall (at least 2).
+    pub fn with_states(mut self, states: u8) -> Self {
+        self.states = states.max(2);
+        self
+    }
+
+    /// The rule equivalent to a wall threshold: a cell is a wall next step if
+    /// at least `threshold` of its `neighbors` are walls.
+    pub fn threshold(threshold: u32, neighbors: u32) -> Self {
+        let counts: Vec<u32> = (threshold..=neighbors).collect();
+        Self::new(&counts, &counts)
+    }
+
+    /// `B678/S345678`: smooth, open caverns.
+    pub fn caverns() -> Self {
+        Self::new(&[6, 7, 8], &[3, 4, 5, 6, 7, 8])
+    }
+
+    /// `B3/S12345`: narrow, winding maze corridors. Works best with a low
+    /// initial wall probability.
+    pub fn maze() -> Self {
+        Self::new(&[3], &[1, 2, 3, 4, 5])
+    }
+
+    /// `B3/S45678`: branching, coral-like growth.
+    pub fn coral() -> Self {
+        Self::new(&[3], &[4, 5, 6, 7, 8])
+    }
+
+    /// Returns true if a floor cell with `walls` wall neighbors becomes a wall.
+    pub fn births(&self, walls: u32) -> bool {
+        walls < 16 && self.birth & (1 << walls) != 0
+    }
+
+    /// Returns true if a wall with `walls` wall neighbors stays a wall.
+    pub fn survives(&self, walls: u32) -> bool {
+        walls < 16 && self.survival & (1 << walls) != 0
+    }
+
+    /// Returns the number of states, including floor and wall.
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// Returns the next state of a cell. State 0 is floor, 1 is wall and
+    /// higher states are decaying walls.
+    pub fn step(&self, state: u8, walls: u32) -> u8 {
+        match state {
+            0 => u8::from(self.births(walls)),
+            1 if self.survives(walls) => 1,
+            _ if state + 1 < self.states => state + 1,
+            _ => 0,
+        }
+    }
+
+    /// Returns the highest neighbor count the rule mentions.
+    fn max_count(&self) -> u32 {
+        (16 - (self.birth | self.survival).leading_zeros()).saturating_sub(1)
+    }
+}
+
+impl fmt::Display for CaRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |mask: u16| -> String {
+            (0..16)
+                .filter(|n| mask & (1 << n) != 0)
+                .map(|n: u32| char::from_digit(n, 16).unwrap_or('?'))
+                .collect()
+        };
+        write!(f, "B{}/S{}", counts(self.birth), counts(self.survival))?;
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for CaRule {
+    type Err = CaveError;
+
+    /// Parses `B678/S345678` or `B2/S/C4` (case-insensitive, parts in any
+    /// order). Each digit is a neighbor count.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: String| CaveError::InvalidConfig {
+            field: "rule",
+            reason,
+        };
+        let mut birth = None;
+        let mut survival = None;
+        let mut states = 2;
+        for part in s.trim().split('/') {
+            let mut chars = part.chars();
+            let kind = chars.next().map(|c| c.to_ascii_uppercase());
+            let rest = chars.as_str();
+            let digits = || -> Result<Vec<u32>, CaveError> {
+                rest.chars()
+                    .map(|c| {
+                        c.to_digit(10)
+                            .ok_or_else(|| invalid(format!("'{}' is not a neighbor count", c)))
+                    })
+                    .collect()
+            };
+            match kind {
+                Some('B') => birth = Some(digits()?),
+                Some('S') => survival = Some(digits()?),
+                Some('C') => {
+                    states = rest
+                        .parse::<u8>()
+                        .ok()
+                        .filter(|&n| n >= 2)
+                        .ok_or_else(|| invalid(format!("'{}' is not a state count", rest)))?;
+                }
+                _ => return Err(invalid(format!("unexpected part '{}' in '{}'", part, s))),
+            }
+        }
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(Self::new(&birth, &survival).with_states(states)),
+            _ => Err(invalid(format!("'{}' needs both B and S parts", s))),
+        }
+    }
+}
 
 /// Configuration for cellular automata cave generation.
 #[derive(Debug, Clone)]
@@ -66,7 +300,12 @@ pub struct CaveConfig {
     pub iterations: u32,
     /// Neighbor threshold for becoming a wall (0-8 for Moore neighborhood).
     /// A cell becomes a wall if it has >= this many wall neighbors.
+    /// Ignored when [`rule`](Self::rule) is set.
     pub wall_threshold: u32,
+    /// Which cells count as neighbors.
+    pub neighborhood: Neighborhood,
+    /// A custom birth/survival rule replacing `wall_threshold`.
+    pub rule: Option<CaRule>,
 }
 
 impl Default for CaveConfig {
@@ -75,6 +314,8 @@ impl Default for CaveConfig {
             initial_wall_probability: 0.45,
             iterations: 5,
             wall_threshold: 5,
+            neighborhood: Neighborhood::Moore,
+            rule: None,
         }
     }
 }
@@ -102,6 +343,72 @@ impl CaveConfig {
         self.wall_threshold = threshold.clamp(0, 8);
         self
     }
+
+    /// Sets the neighborhood.
+    pub fn with_neighborhood(mut self, neighborhood: Neighborhood) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    /// Sets a custom birth/survival rule, replacing the wall threshold.
+    pub fn with_rule(mut self, rule: CaRule) -> Self {
+        self.rule = Some(rule);
+        self
+    }
+
+    /// Returns the rule the simulation runs: the custom rule, or the one
+    /// equivalent to the wall threshold.
+    pub fn effective_rule(&self) -> CaRule {
+        self.rule
+            .unwrap_or_else(|| CaRule::threshold(self.wall_threshold, self.neighborhood.size()))
+    }
+
+    /// Checks that the configuration produces a cave rather than noise or a
+    /// solid block.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CaveError::InvalidConfig` naming the first offending field.
+    pub fn validate(&self) -> Result<(), CaveError> {
+        let invalid = |field, reason: String| Err(CaveError::InvalidConfig { field, reason });
+
+        if !(0.0..=1.0).contains(&self.initial_wall_probability) {
+            return invalid(
+                "initial_wall_probability",
+                format!("{} is outside 0.0-1.0", self.initial_wall_probability),
+            );
+        }
+        if self.iterations == 0 {
+            return invalid(
+                "iterations",
+                "must be at least 1 (zero leaves raw noise)".into(),
+            );
+        }
+        let neighbors = self.neighborhood.size();
+        let name = match self.neighborhood {
+            Neighborhood::Moore => "Moore",
+            Neighborhood::VonNeumann => "von Neumann",
+        };
+        match self.rule {
+            None if self.wall_threshold > neighbors => {
+                return invalid(
+                    "wall_threshold",
+                    format!(
+                        "{} exceeds the {} {} neighbors",
+                        self.wall_threshold, neighbors, name
+                    ),
+                );
+            }
+            Some(rule) if rule.max_count() > neighbors => {
+                return invalid(
+                    "rule",
+                    format!("{} counts past the {} {} neighbors", rule, neighbors, name),
+                );
+            }
+            _ => {}
+        }
+        Ok(())
+    }
 }
 
 /// A generated cave map.
@@ -169,12 +476,35 @@ impl CaveMap {
 
     /// Prints the cave to the console.
     pub fn print(&self) {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                print!("{}", if self.is_floor(x, y) { '.' } else { '#' });
-            }
-            println!();
-        }
+        print!("{}", self.to_ascii());
+    }
+
+    /// Renders the map as text using the default legend (`.` floor, `#` wall).
+    pub fn to_ascii(&self) -> String {
+        self.to_ascii_with(&AsciiLegend::default())
+    }
+
+    /// Renders the map as text using a custom legend.
+    pub fn to_ascii_with(&self, legend: &AsciiLegend) -> String {
+        ascii::render(self.width, &self.tiles, legend)
+    }
+
+    /// Formats the map with overlays such as marked tiles.
+    pub fn display<'a>(&'a self, options: &'a AsciiOptions) -> AsciiDisplay<'a> {
+        AsciiDisplay::new(self.width, &self.tiles, options)
+    }
+
+    /// Reads a map from text, one line per row.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AsciiMapError`] if the text is empty, rows have different
+    /// lengths, or a character is not in the legend.
+    pub fn from_ascii(text: &str, legend: &AsciiLegend) -> Result<Self, AsciiMapError> {
+        let (width, height, tiles) = ascii::parse(text, legend)?;
+        let mut map = Self::new(width, height);
+        map.tiles = tiles;
+        Ok(map)
     }
 
     /// Returns the percentage of tiles that are floors.
@@ -185,6 +515,141 @@ impl CaveMap {
         let floor_count = self.tiles.iter().filter(|&&is_floor| is_floor).count();
         floor_count as f32 / self.tiles.len() as f32
     }
+
+    /// Returns the 4-connected floor regions, largest first.
+    pub fn regions(&self) -> Vec<Vec<IVec2>> {
+        let mut seen = vec![false; self.tiles.len()];
+        let mut regions = Vec::new();
+        for idx in 0..self.tiles.len() {
+            if seen[idx] || !self.tiles[idx] {
+                continue;
+            }
+            let start = IVec2::new(
+                (idx as u32 % self.width) as i32,
+                (idx as u32 / self.width) as i32,
+            );
+            regions.push(self.flood(start, &mut seen));
+        }
+        regions.sort_by_key(|r| std::cmp::Reverse(r.len()));
+        regions
+    }
+
+    /// Joins every floor region to the largest one with the shortest possible
+    /// tunnels, never carving within `border` tiles of the map edge.
+    ///
+    /// Returns the tiles carved for each tunnel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use runeforge_algorithms::prelude::*;
+    ///
+    /// let mut cave = CaveMap::from_ascii("#####\n#.#.#\n#####", &AsciiLegend::default()).unwrap();
+    /// let tunnels = cave.connect_regions(1);
+    /// assert_eq!(tunnels.len(), 1);
+    /// assert_eq!(cave.regions().len(), 1);
+    /// ```
+    pub fn connect_regions(&mut self, border: u32) -> Vec<Vec<IVec2>> {
+        let (w, h) = (self.width, self.height);
+        let index = |p: IVec2| (p.y as u32 * w + p.x as u32) as usize;
+        let inside = |p: IVec2| {
+            p.x >= border as i32
+                && p.y >= border as i32
+                && (p.x as u32) < w.saturating_sub(border)
+                && (p.y as u32) < h.saturating_sub(border)
+        };
+
+        let mut tunnels = Vec::new();
+        let Some(&start) = self.regions().first().and_then(|r| r.first()) else {
+            return tunnels;
+        };
+        let mut connected = vec![false; self.tiles.len()];
+        self.flood(start, &mut connected);
+
+        // Breadth-first search outward from the connected area until it
+        // reaches an unconnected floor tile, then carve the path back.
+        loop {
+            let mut parent: Vec<Option<IVec2>> = vec![None; self.tiles.len()];
+            let mut visited = connected.clone();
+            let mut queue: VecDeque<IVec2> = (0..w * h)
+                .filter(|&i| connected[i as usize])
+                .map(|i| IVec2::new((i % w) as i32, (i / w) as i32))
+                .collect();
+
+            let mut target = None;
+            while let Some(p) = queue.pop_front() {
+                if self.tiles[index(p)] && !connected[index(p)] {
+                    target = Some(p);
+                    break;
+                }
+                for n in self.neighbors(p) {
+                    if inside(n) && !visited[index(n)] {
+                        visited[index(n)] = true;
+                        parent[index(n)] = Some(p);
+                        queue.push_back(n);
+                    }
+                }
+            }
+            let Some(target) = target else {
+                break;
+            };
+
+            let mut carved = Vec::new();
+            let mut p = parent[index(target)];
+            while let Some(step) = p.filter(|&s| !connected[index(s)]) {
+                self.tiles[index(step)] = true;
+                carved.push(step);
+                p = parent[index(step)];
+            }
+            self.flood(target, &mut connected);
+            tunnels.push(carved);
+        }
+        tunnels
+    }
+
+    /// Marks every floor tile 4-connected to `start` in `seen` and returns
+    /// the newly marked tiles.
+    fn flood(&self, start: IVec2, seen: &mut [bool]) -> Vec<IVec2> {
+        let mut region = Vec::new();
+        let mut queue = VecDeque::from([start]);
+        seen[self.index(start.x as u32, start.y as u32)] = true;
+        while let Some(p) = queue.pop_front() {
+            region.push(p);
+            for n in self.neighbors(p) {
+                let idx = self.index(n.x as u32, n.y as u32);
+                if !seen[idx] && self.tiles[idx] {
+                    seen[idx] = true;
+                    queue.push_back(n);
+                }
+            }
+        }
+        region
+    }
+
+    /// The in-bounds cardinal neighbors of `p`.
+    fn neighbors(&self, p: IVec2) -> impl Iterator<Item = IVec2> {
+        let (w, h) = (self.width as i32, self.height as i32);
+        [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y]
+            .into_iter()
+            .map(move |d| p + d)
+            .filter(move |n| n.x >= 0 && n.y >= 0 && n.x < w && n.y < h)
+    }
+}
+
+impl fmt::Display for CaveMap {
+    /// Renders the map with the default legend, like [`CaveMap::to_ascii`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_ascii())
+    }
+}
+
+impl From<&CaveMap> for CostGrid {
+    /// Floor tiles cost 1; walls are impassable.
+    fn from(cave: &CaveMap) -> Self {
+        CostGrid::from_walkable(cave.width, cave.height, |p| {
+            cave.is_floor(p.x as u32, p.y as u32)
+        })
+    }
 }
 
 /// Generator for cellular automata caves.
@@ -195,20 +660,87 @@ impl CaveGenerator {
     ///
     /// # Errors
     ///
-    /// Returns `CaveError::InvalidDimensions` if width or height is zero.
+    /// Returns `CaveError::InvalidDimensions` if width or height is zero, or
+    /// `CaveError::InvalidConfig` if [`CaveConfig::validate`] fails.
     pub fn generate(
         width: u32,
         height: u32,
         config: &CaveConfig,
         rng: &mut Rng,
+    ) -> Result<CaveMap, CaveError> {
+        Self::generate_inner(width, height, config, rng, None, None)
+    }
+
+    /// Generates a cave, recording the initial noise and every iteration.
+    ///
+    /// Produces the same map as [`generate`](Self::generate) for the same seed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CaveError::InvalidDimensions` if width or height is zero, or
+    /// `CaveError::InvalidConfig` if [`CaveConfig::validate`] fails.
+    pub fn generate_traced(
+        width: u32,
+        height: u32,
+        config: &CaveConfig,
+        rng: &mut Rng,
+        trace: &mut GenerationTrace,
+    ) -> Result<CaveMap, CaveError> {
+        Self::generate_inner(width, height, config, rng, Some(trace), None)
+    }
+
+    /// Generates a cave, reporting progress after every row of every
+    /// iteration and stopping early if the sink is cancelled.
+    ///
+    /// Produces the same map as [`generate`](Self::generate) for the same seed.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`generate`](Self::generate), plus `CaveError::Cancelled` if
+    /// the sink asked to stop.
+    pub fn generate_with_progress(
+        width: u32,
+        height: u32,
+        config: &CaveConfig,
+        rng: &mut Rng,
+        progress: &mut dyn ProgressSink,
+    ) -> Result<CaveMap, CaveError> {
+        Self::generate_inner(width, height, config, rng, None, Some(progress))
+    }
+
+    fn generate_inner(
+        width: u32,
+        height: u32,
+        config: &CaveConfig,
+        rng: &mut Rng,
+        mut trace: Option<&mut GenerationTrace>,
+        progress: Option<&mut dyn ProgressSink>,
     ) -> Result<CaveMap, CaveError> {
         if width == 0 || height == 0 {
             return Err(CaveError::InvalidDimensions { width, height });
         }
+        config.validate()?;
 
         let mut cave = CaveMap::new(width, height);
         Self::initialize_random(&mut cave, config.initial_wall_probability, rng);
-        Self::run_simulation(&mut cave, config.iterations, config.wall_threshold);
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.record(
+                "initial noise",
+                width,
+                height,
+                &cave.tiles,
+                Vec::new(),
+                Vec::new(),
+            );
+        }
+        Self::run_simulation(
+            &mut cave,
+            config.iterations,
+            &config.effective_rule(),
+            config.neighborhood,
+            trace,
+            progress,
+        )?;
 
         Ok(cave)
     }
@@ -225,58 +757,101 @@ impl CaveGenerator {
     }
 
     /// Runs the cellular automata simulation for the specified iterations.
-    fn run_simulation(cave: &mut CaveMap, iterations: u32, wall_threshold: u32) {
+    ///
+    /// Cells are tracked as states (0 floor, 1 wall, higher decaying), and
+    /// the map is updated from them after each iteration.
+    fn run_simulation(
+        cave: &mut CaveMap,
+        iterations: u32,
+        rule: &CaRule,
+        neighborhood: Neighborhood,
+        mut trace: Option<&mut GenerationTrace>,
+        mut progress: Option<&mut dyn ProgressSink>,
+    ) -> Result<(), CaveError> {
         let size = (cave.width() * cave.height()) as usize;
+        let mut states: Vec<u8> = cave.tiles.iter().map(|&floor| u8::from(!floor)).collect();
+        let mut next = vec![0u8; size];
         let mut buffer = vec![false; size];
 
-        for _ in 0..iterations {
+        let total = u64::from(iterations) * u64::from(cave.height());
+        for i in 0..iterations {
+            let stage = format!("iteration {}", i + 1);
             for y in 0..cave.height() {
+                if let Some(sink) = progress.as_deref_mut() {
+                    if sink.is_cancelled() {
+                        return Err(CaveError::Cancelled);
+                    }
+                }
                 for x in 0..cave.width() {
                     let idx = (y * cave.width() + x) as usize;
-                    buffer[idx] = Self::should_be_floor(cave, x, y, wall_threshold);
+                    let walls = Self::count_wall_neighbors(cave, &states, x, y, neighborhood);
+                    next[idx] = rule.step(states[idx], walls);
+                }
+                if let Some(sink) = progress.as_deref_mut() {
+                    sink.report(Progress {
+                        stage: &stage,
+                        done: u64::from(i) * u64::from(cave.height()) + u64::from(y) + 1,
+                        total,
+                    });
                 }
             }
+            std::mem::swap(&mut states, &mut next);
+            for (tile, &state) in buffer.iter_mut().zip(&states) {
+                *tile = state == 0;
+            }
             std::mem::swap(&mut cave.tiles, &mut buffer);
+
+            if let Some(trace) = trace.as_deref_mut() {
+                // Highlight the tiles this iteration flipped.
+                let changed = (0..size)
+                    .filter(|&idx| cave.tiles[idx] != buffer[idx])
+                    .map(|idx| {
+                        let idx = idx as u32;
+                        IVec2::new((idx % cave.width) as i32, (idx / cave.width) as i32)
+                    })
+                    .collect();
+                trace.record(
+                    stage,
+                    cave.width,
+                    cave.height,
+                    &cave.tiles,
+                    changed,
+                    Vec::new(),
+                );
+            }
         }
+        Ok(())
     }
 
-    /// Counts the number of wall neighbors around a position (Moore neighborhood).
+    /// Counts the live wall neighbors (state 1) around a position.
     ///
-    /// Uses the 8 surrounding cells. Tiles outside the map are treated as walls.
-    fn count_wall_neighbors(cave: &CaveMap, x: u32, y: u32) -> u32 {
+    /// Tiles outside the map are treated as walls.
+    fn count_wall_neighbors(
+        cave: &CaveMap,
+        states: &[u8],
+        x: u32,
+        y: u32,
+        neighborhood: Neighborhood,
+    ) -> u32 {
         let mut count = 0;
 
-        for dy in -1..=1i32 {
-            for dx in -1..=1i32 {
-                if dx == 0 && dy == 0 {
-                    continue;
-                }
-
-                let nx = x as i32 + dx;
-                let ny = y as i32 + dy;
+        for &(dx, dy) in neighborhood.offsets() {
+            let nx = x as i32 + dx;
+            let ny = y as i32 + dy;
 
-                // Bounds check first - out of bounds counts as wall
-                if nx < 0 || ny < 0 || nx >= cave.width() as i32 || ny >= cave.height() as i32 {
-                    count += 1;
-                    continue;
-                }
+            // Bounds check first - out of bounds counts as wall
+            if nx < 0 || ny < 0 || nx >= cave.width() as i32 || ny >= cave.height() as i32 {
+                count += 1;
+                continue;
+            }
 
-                if cave.is_wall(nx as u32, ny as u32) {
-                    count += 1;
-                }
+            if states[cave.index(nx as u32, ny as u32)] == 1 {
+                count += 1;
             }
         }
 
         count
     }
-
-    /// Determines if a cell should become a floor based on neighbor count.
-    ///
-    /// A cell becomes a floor if it has fewer than `threshold` wall neighbors.
-    fn should_be_floor(cave: &CaveMap, x: u32, y: u32, threshold: u32) -> bool {
-        let wall_count = Self::count_wall_neighbors(cave, x, y);
-        wall_count < threshold
-    }
 }
 
 #[cfg(test)]
@@ -287,6 +862,10 @@ mod tests {
         Rng::with_seed(seed)
     }
 
+    fn states(cave: &CaveMap) -> Vec<u8> {
+        cave.tiles().iter().map(|&floor| u8::from(!floor)).collect()
+    }
+
     #[test]
     fn test_cave_config_default() {
         let config = CaveConfig::default();
@@ -377,6 +956,28 @@ mod tests {
         assert!(matches!(result, Err(CaveError::InvalidDimensions { .. })));
     }
 
+    #[test]
+    fn test_cave_config_validate() {
+        assert!(CaveConfig::default().validate().is_ok());
+
+        let config = CaveConfig::new().with_iterations(0);
+        let err = CaveGenerator::generate(20, 20, &config, &mut create_seeded_rng(1)).unwrap_err();
+        assert_eq!(err.code(), "cave.invalid_config");
+        assert!(matches!(
+            err,
+            CaveError::InvalidConfig {
+                field: "iterations",
+                ..
+            }
+        ));
+
+        let config = CaveConfig {
+            wall_threshold: 9,
+            ..CaveConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_cave_generation_deterministic() {
         let config = CaveConfig::default();
@@ -421,7 +1022,8 @@ mod tests {
     fn test_count_wall_neighbors_corner() {
         let cave = CaveMap::new(10, 10);
         // All walls, corner has 3 in-bounds neighbors + 5 out-of-bounds = 8
-        let count = CaveGenerator::count_wall_neighbors(&cave, 0, 0);
+        let count =
+            CaveGenerator::count_wall_neighbors(&cave, &states(&cave), 0, 0, Neighborhood::Moore);
         assert_eq!(count, 8);
     }
 
@@ -430,7 +1032,8 @@ mod tests {
         let mut cave = CaveMap::new(10, 10);
         // Set center to floor, surrounded by walls
         cave.set_tile(5, 5, true);
-        let count = CaveGenerator::count_wall_neighbors(&cave, 5, 5);
+        let count =
+            CaveGenerator::count_wall_neighbors(&cave, &states(&cave), 5, 5, Neighborhood::Moore);
         assert_eq!(count, 8);
     }
 
@@ -444,7 +1047,8 @@ mod tests {
             }
         }
         // Center tile should have 0 wall neighbors
-        let count = CaveGenerator::count_wall_neighbors(&cave, 5, 5);
+        let count =
+            CaveGenerator::count_wall_neighbors(&cave, &states(&cave), 5, 5, Neighborhood::Moore);
         assert_eq!(count, 0);
     }
 
@@ -461,4 +1065,65 @@ mod tests {
         }
         assert!((cave.floor_percentage() - 0.5).abs() < f32::EPSILON);
     }
+
+    #[test]
+    fn test_rule_parse_and_display() {
+        let rule: CaRule = "B678/S345678".parse().unwrap();
+        assert_eq!(rule, CaRule::caverns());
+        assert_eq!(rule.to_string(), "B678/S345678");
+        assert!(rule.births(7) && !rule.births(5));
+        assert!(rule.survives(3) && !rule.survives(2));
+
+        let generations: CaRule = "s/b2/c4".parse().unwrap();
+        assert_eq!(generations.states(), 4);
+        assert_eq!(generations.to_string(), "B2/S/C4");
+        assert_eq!(generations.step(1, 0), 2);
+        assert_eq!(generations.step(2, 0), 3);
+        assert_eq!(generations.step(3, 0), 0);
+
+        for bad in ["B3", "B3/Sx", "B3/S2/C1", "X3/S2"] {
+            let err = bad.parse::<CaRule>().unwrap_err();
+            assert_eq!(err.code(), "cave.invalid_config", "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_threshold_rule_matches_default() {
+        let config = CaveConfig::default();
+        let with_rule = CaveConfig::new().with_rule(CaRule::threshold(5, 8));
+        let a = CaveGenerator::generate(40, 30, &config, &mut create_seeded_rng(3)).unwrap();
+        let b = CaveGenerator::generate(40, 30, &with_rule, &mut create_seeded_rng(3)).unwrap();
+        assert_eq!(a.tiles(), b.tiles());
+    }
+
+    #[test]
+    fn test_custom_rules_and_neighborhoods() {
+        let mut cave = CaveMap::new(3, 3);
+        cave.set_tile(1, 1, true);
+        cave.set_tile(1, 0, true);
+        let walls = CaveGenerator::count_wall_neighbors(
+            &cave,
+            &states(&cave),
+            1,
+            1,
+            Neighborhood::VonNeumann,
+        );
+        assert_eq!(walls, 3);
+
+        let config = CaveConfig::new()
+            .with_neighborhood(Neighborhood::VonNeumann)
+            .with_rule(CaRule::caverns());
+        let err = config.validate().unwrap_err();
+        assert!(matches!(
+            err,
+            CaveError::InvalidConfig { field: "rule", .. }
+        ));
+
+        let config = CaveConfig::new()
+            .with_initial_wall_probability(0.3)
+            .with_rule(CaRule::maze().with_states(3));
+        let maze = CaveGenerator::generate(40, 30, &config, &mut create_seeded_rng(8)).unwrap();
+        let floor = maze.floor_percentage();
+        assert!(floor > 0.0 && floor < 1.0);
+    }
 }
@@ -0,0 +1,443 @@
+//! Chunked world streaming for unbounded maps.
+//!
+//! This module provides a [`ChunkManager`] that splits an infinite world into
+//! fixed-size square chunks. Chunks are generated on demand, cached, and
+//! evicted (least recently used first) once the cache grows past its limit.
+//!
+//! Every chunk is generated with its own [`Rng`] seeded from the world seed and
+//! the chunk coordinates, so a chunk that is evicted and later regenerated comes
+//! back identical.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_algorithms::chunk::{ChunkConfig, ChunkManager};
+//! use runeforge_geometry::prelude::IVec2;
+//!
+//! let config = ChunkConfig::new().with_chunk_size(16).with_max_loaded_chunks(64);
+//! let mut world = ChunkManager::new(12345, config, |_coord, chunk, rng| {
+//!     for tile in chunk.tiles_mut() {
+//!         *tile = rng.chance(0.6);
+//!     }
+//! });
+//!
+//! // Query tiles in world coordinates; negative coordinates are fine.
+//! let floor = *world.tile(IVec2::new(-100, 250));
+//! assert_eq!(floor, *world.tile(IVec2::new(-100, 250)));
+//! ```
+
+use runeforge_geometry::prelude::*;
+use runeforge_random::prelude::*;
+use std::collections::HashMap;
+
+/// Configuration for chunked world streaming.
+#[derive(Debug, Clone)]
+pub struct ChunkConfig {
+    /// Width and height of a chunk in tiles.
+    pub chunk_size: u32,
+    /// Maximum number of chunks kept in memory before eviction kicks in.
+    pub max_loaded_chunks: usize,
+}
+
+impl Default for ChunkConfig {
+    fn default() -> Self {
+        Self {
+            chunk_size: 32,
+            max_loaded_chunks: 64,
+        }
+    }
+}
+
+impl ChunkConfig {
+    /// Creates a new configuration with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the chunk size (minimum 1).
+    pub fn with_chunk_size(mut self, size: u32) -> Self {
+        self.chunk_size = size.max(1);
+        self
+    }
+
+    /// Sets the maximum number of loaded chunks (minimum 1).
+    pub fn with_max_loaded_chunks(mut self, max: usize) -> Self {
+        self.max_loaded_chunks = max.max(1);
+        self
+    }
+}
+
+/// A single square chunk of tiles.
+#[derive(Debug, Clone)]
+pub struct Chunk<T> {
+    coord: IVec2,
+    size: u32,
+    tiles: Vec<T>,
+}
+
+impl<T: Clone + Default> Chunk<T> {
+    /// Creates a new chunk filled with `T::default()`.
+    pub fn new(coord: IVec2, size: u32) -> Self {
+        Self {
+            coord,
+            size,
+            tiles: vec![T::default(); size as usize * size as usize],
+        }
+    }
+}
+
+impl<T> Chunk<T> {
+    /// Returns the chunk coordinates (in chunks, not tiles).
+    pub fn coord(&self) -> IVec2 {
+        self.coord
+    }
+
+    /// Returns the chunk size in tiles.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Returns the world position of the chunk's top-left tile.
+    pub fn origin(&self) -> IVec2 {
+        self.coord * self.size as i32
+    }
+
+    /// Returns the tile at the given local position, if in bounds.
+    pub fn get(&self, x: u32, y: u32) -> Option<&T> {
+        if x < self.size && y < self.size {
+            self.tiles.get(self.index(x, y))
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the tile at the given local position, if in bounds.
+    pub fn get_mut(&mut self, x: u32, y: u32) -> Option<&mut T> {
+        if x < self.size && y < self.size {
+            let idx = self.index(x, y);
+            self.tiles.get_mut(idx)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the tile at the given local position. Out of bounds writes are ignored.
+    pub fn set(&mut self, x: u32, y: u32, tile: T) {
+        if let Some(t) = self.get_mut(x, y) {
+            *t = tile;
+        }
+    }
+
+    /// Returns the raw tile data in row-major order.
+    pub fn tiles(&self) -> &[T] {
+        &self.tiles
+    }
+
+    /// Returns the raw tile data mutably in row-major order.
+    pub fn tiles_mut(&mut self) -> &mut [T] {
+        &mut self.tiles
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        y as usize * self.size as usize + x as usize
+    }
+}
+
+/// Derives a deterministic seed for a chunk from the world seed and chunk coordinates.
+pub fn chunk_seed(world_seed: u64, coord: IVec2) -> u64 {
+    let packed = ((coord.x as u32 as u64) << 32) | coord.y as u32 as u64;
+    splitmix64(world_seed ^ splitmix64(packed))
+}
+
+struct CachedChunk<T> {
+    chunk: Chunk<T>,
+    last_used: u64,
+}
+
+/// Generates, caches and evicts chunks of an unbounded world.
+///
+/// The generator is called with the chunk coordinates, a default-filled chunk
+/// to populate, and an [`Rng`] seeded by [`chunk_seed`].
+///
+/// Edits made with [`ChunkManager::set_tile`] live only as long as the chunk
+/// stays loaded; an evicted chunk is regenerated from scratch.
+pub struct ChunkManager<T, F>
+where
+    F: FnMut(IVec2, &mut Chunk<T>, &mut Rng),
+{
+    world_seed: u64,
+    config: ChunkConfig,
+    generator: F,
+    chunks: HashMap<IVec2, CachedChunk<T>>,
+    clock: u64,
+}
+
+impl<T, F> ChunkManager<T, F>
+where
+    T: Clone + Default,
+    F: FnMut(IVec2, &mut Chunk<T>, &mut Rng),
+{
+    /// Creates a new chunk manager.
+    pub fn new(world_seed: u64, config: ChunkConfig, generator: F) -> Self {
+        let config = ChunkConfig::new()
+            .with_chunk_size(config.chunk_size)
+            .with_max_loaded_chunks(config.max_loaded_chunks);
+        Self {
+            world_seed,
+            config,
+            generator,
+            chunks: HashMap::new(),
+            clock: 0,
+        }
+    }
+
+    /// Returns the world seed.
+    pub fn world_seed(&self) -> u64 {
+        self.world_seed
+    }
+
+    /// Returns the chunk size in tiles.
+    pub fn chunk_size(&self) -> u32 {
+        self.config.chunk_size
+    }
+
+    /// Converts a world position to the coordinates of the chunk containing it.
+    pub fn chunk_coord(&self, world_pos: IVec2) -> IVec2 {
+        let size = self.config.chunk_size as i32;
+        IVec2::new(world_pos.x.div_euclid(size), world_pos.y.div_euclid(size))
+    }
+
+    /// Converts a world position to a position local to its chunk.
+    pub fn local_coord(&self, world_pos: IVec2) -> UVec2 {
+        let size = self.config.chunk_size as i32;
+        UVec2::new(
+            world_pos.x.rem_euclid(size) as u32,
+            world_pos.y.rem_euclid(size) as u32,
+        )
+    }
+
+    /// Returns the chunk at the given chunk coordinates, generating it if needed.
+    pub fn chunk(&mut self, coord: IVec2) -> &Chunk<T> {
+        self.load(coord);
+        &self.chunks[&coord].chunk
+    }
+
+    /// Returns the chunk at the given chunk coordinates mutably, generating it if needed.
+    pub fn chunk_mut(&mut self, coord: IVec2) -> &mut Chunk<T> {
+        self.load(coord);
+        &mut self.chunks.get_mut(&coord).unwrap().chunk
+    }
+
+    /// Returns the tile at a world position, generating its chunk if needed.
+    pub fn tile(&mut self, world_pos: IVec2) -> &T {
+        let local = self.local_coord(world_pos);
+        let chunk = self.chunk(self.chunk_coord(world_pos));
+        chunk.get(local.x, local.y).unwrap()
+    }
+
+    /// Sets the tile at a world position, generating its chunk if needed.
+    pub fn set_tile(&mut self, world_pos: IVec2, tile: T) {
+        let local = self.local_coord(world_pos);
+        let coord = self.chunk_coord(world_pos);
+        self.chunk_mut(coord).set(local.x, local.y, tile);
+    }
+
+    /// Returns the tile at a world position only if its chunk is already loaded.
+    pub fn peek_tile(&self, world_pos: IVec2) -> Option<&T> {
+        let local = self.local_coord(world_pos);
+        self.chunks
+            .get(&self.chunk_coord(world_pos))
+            .and_then(|c| c.chunk.get(local.x, local.y))
+    }
+
+    /// Returns a loaded chunk without generating it or marking it as
+    /// recently used.
+    pub fn peek_chunk(&self, coord: IVec2) -> Option<&Chunk<T>> {
+        self.chunks.get(&coord).map(|c| &c.chunk)
+    }
+
+    /// Returns a loaded chunk mutably without generating it or marking it as
+    /// recently used, so background work doesn't keep distant chunks alive.
+    pub fn peek_chunk_mut(&mut self, coord: IVec2) -> Option<&mut Chunk<T>> {
+        self.chunks.get_mut(&coord).map(|c| &mut c.chunk)
+    }
+
+    /// Loads every chunk within `radius` chunks of the chunk containing `world_pos`.
+    pub fn load_around(&mut self, world_pos: IVec2, radius: u32) {
+        let center = self.chunk_coord(world_pos);
+        let r = radius as i32;
+        for y in -r..=r {
+            for x in -r..=r {
+                self.load(center + IVec2::new(x, y));
+            }
+        }
+    }
+
+    /// Returns true if the chunk at the given coordinates is loaded.
+    pub fn is_loaded(&self, coord: IVec2) -> bool {
+        self.chunks.contains_key(&coord)
+    }
+
+    /// Returns the number of loaded chunks.
+    pub fn loaded_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Returns an iterator over the coordinates of all loaded chunks.
+    pub fn loaded_chunks(&self) -> impl Iterator<Item = IVec2> + '_ {
+        self.chunks.keys().copied()
+    }
+
+    /// Unloads a chunk, returning it if it was loaded.
+    pub fn unload(&mut self, coord: IVec2) -> Option<Chunk<T>> {
+        self.chunks.remove(&coord).map(|c| c.chunk)
+    }
+
+    /// Unloads every chunk.
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+    }
+
+    fn load(&mut self, coord: IVec2) {
+        self.clock += 1;
+        if let Some(cached) = self.chunks.get_mut(&coord) {
+            cached.last_used = self.clock;
+            return;
+        }
+
+        while self.chunks.len() >= self.config.max_loaded_chunks {
+            self.evict_oldest();
+        }
+
+        let mut chunk = Chunk::new(coord, self.config.chunk_size);
+        let mut rng = Rng::with_seed(chunk_seed(self.world_seed, coord));
+        (self.generator)(coord, &mut chunk, &mut rng);
+        self.chunks.insert(
+            coord,
+            CachedChunk {
+                chunk,
+                last_used: self.clock,
+            },
+        );
+    }
+
+    fn evict_oldest(&mut self) {
+        if let Some(oldest) = self
+            .chunks
+            .iter()
+            .min_by_key(|(_, c)| c.last_used)
+            .map(|(coord, _)| *coord)
+        {
+            self.chunks.remove(&oldest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn noise_world(
+        max_loaded: usize,
+    ) -> ChunkManager<u32, impl FnMut(IVec2, &mut Chunk<u32>, &mut Rng)> {
+        let config = ChunkConfig::new()
+            .with_chunk_size(8)
+            .with_max_loaded_chunks(max_loaded);
+        ChunkManager::new(42, config, |_, chunk: &mut Chunk<u32>, rng: &mut Rng| {
+            for tile in chunk.tiles_mut() {
+                *tile = rng.range(0, 1000) as u32;
+            }
+        })
+    }
+
+    #[test]
+    fn test_chunk_config_clamping() {
+        let config = ChunkConfig::new()
+            .with_chunk_size(0)
+            .with_max_loaded_chunks(0);
+        assert_eq!(config.chunk_size, 1);
+        assert_eq!(config.max_loaded_chunks, 1);
+    }
+
+    #[test]
+    fn test_chunk_coord_negative() {
+        let world = noise_world(4);
+        assert_eq!(world.chunk_coord(IVec2::new(0, 0)), IVec2::new(0, 0));
+        assert_eq!(world.chunk_coord(IVec2::new(7, 8)), IVec2::new(0, 1));
+        assert_eq!(world.chunk_coord(IVec2::new(-1, -8)), IVec2::new(-1, -1));
+        assert_eq!(world.chunk_coord(IVec2::new(-9, 0)), IVec2::new(-2, 0));
+        assert_eq!(world.local_coord(IVec2::new(-1, -9)), UVec2::new(7, 7));
+    }
+
+    #[test]
+    fn test_chunk_seed_distinct() {
+        let a = chunk_seed(1, IVec2::new(0, 1));
+        let b = chunk_seed(1, IVec2::new(1, 0));
+        let c = chunk_seed(2, IVec2::new(0, 1));
+        assert_ne!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(a, chunk_seed(1, IVec2::new(0, 1)));
+    }
+
+    #[test]
+    fn test_chunk_regenerates_identically() {
+        let mut world = noise_world(1);
+        let pos = IVec2::new(-20, 33);
+        let before = *world.tile(pos);
+        let coord = world.chunk_coord(pos);
+
+        // Force eviction by touching another chunk.
+        world.tile(IVec2::new(1000, 1000));
+        assert!(!world.is_loaded(coord));
+
+        assert_eq!(*world.tile(pos), before);
+    }
+
+    #[test]
+    fn test_chunk_lru_eviction() {
+        let mut world = noise_world(2);
+        world.chunk(IVec2::new(0, 0));
+        world.chunk(IVec2::new(1, 0));
+        // Touch (0, 0) so (1, 0) becomes the oldest.
+        world.chunk(IVec2::new(0, 0));
+        world.chunk(IVec2::new(2, 0));
+
+        assert_eq!(world.loaded_count(), 2);
+        assert!(world.is_loaded(IVec2::new(0, 0)));
+        assert!(!world.is_loaded(IVec2::new(1, 0)));
+        assert!(world.is_loaded(IVec2::new(2, 0)));
+    }
+
+    #[test]
+    fn test_chunk_set_and_peek_tile() {
+        let mut world = noise_world(4);
+        let pos = IVec2::new(5, -3);
+        assert!(world.peek_tile(pos).is_none());
+
+        world.set_tile(pos, 7777);
+        assert_eq!(world.peek_tile(pos), Some(&7777));
+        assert_eq!(*world.tile(pos), 7777);
+    }
+
+    #[test]
+    fn test_chunk_load_around() {
+        let mut world = noise_world(16);
+        world.load_around(IVec2::new(0, 0), 1);
+        assert_eq!(world.loaded_count(), 9);
+        assert!(world.is_loaded(IVec2::new(-1, -1)));
+        assert!(world.is_loaded(IVec2::new(1, 1)));
+    }
+
+    #[test]
+    fn test_chunk_peek_does_not_refresh_lru() {
+        let mut world = noise_world(2);
+        world.chunk(IVec2::new(0, 0));
+        world.chunk(IVec2::new(1, 0));
+        world.peek_chunk_mut(IVec2::new(0, 0)).unwrap().set(0, 0, 5);
+        world.chunk(IVec2::new(2, 0));
+
+        assert!(!world.is_loaded(IVec2::new(0, 0)));
+        assert!(world.peek_chunk(IVec2::new(1, 0)).is_some());
+        assert!(world.peek_chunk_mut(IVec2::new(3, 0)).is_none());
+    }
+}
@@ -0,0 +1,582 @@
+//! Map postconditions with automatic regeneration.
+//!
+//! Generators are random, so an occasional map comes out too sparse, with a
+//! sealed-off room or with the stairs next to the entrance. Instead of
+//! looping and checking around every generator call, describe what a
+//! playable map needs with [`GenerationConstraints`] and let it retry:
+//! - Minimum floor percentage
+//! - Every room reachable from the first one
+//! - Minimum and maximum room count
+//! - Minimum walking distance between start and exit
+//!
+//! Each attempt runs on a fresh RNG stream seeded from the caller's RNG, so
+//! a retried map is unrelated to the rejected one but the whole sequence is
+//! still reproducible from a single seed.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_algorithms::prelude::*;
+//!
+//! let constraints = GenerationConstraints::new()
+//!     .with_min_rooms(6)
+//!     .with_min_start_exit_distance(30)
+//!     .with_max_attempts(50);
+//!
+//! let mut rng = Rng::with_seed(4);
+//! let dungeon = constraints
+//!     .generate(&mut rng, |rng| {
+//!         DungeonGenerator::generate(80, 50, &BspConfig::default(), rng)
+//!     })
+//!     .unwrap();
+//! assert!(dungeon.rooms().len() >= 6);
+//! ```
+
+use std::collections::VecDeque;
+
+use runeforge_geometry::prelude::{IVec2, Rect};
+use runeforge_pathfinding::prelude::CostGrid;
+use runeforge_random::prelude::*;
+use thiserror::Error;
+
+use crate::bsp::{Dungeon, RoomTag};
+use crate::caves::CaveMap;
+use crate::drunkard::DrunkardMap;
+
+/// A postcondition a generated map failed.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ConstraintViolation {
+    /// Too little of the map is walkable.
+    #[error("floor covers {percentage:.2} of the map, below the required {required:.2}")]
+    FloorTooLow {
+        /// Walkable fraction of the map
+        percentage: f32,
+        /// Required fraction
+        required: f32,
+    },
+    /// A room can't be reached from the first room.
+    #[error("room {0} is unreachable from room 0")]
+    UnreachableRoom(usize),
+    /// The map has too few rooms.
+    #[error("{count} rooms, below the minimum of {min}")]
+    TooFewRooms {
+        /// Number of rooms generated
+        count: usize,
+        /// Required minimum
+        min: usize,
+    },
+    /// The map has too many rooms.
+    #[error("{count} rooms, above the maximum of {max}")]
+    TooManyRooms {
+        /// Number of rooms generated
+        count: usize,
+        /// Allowed maximum
+        max: usize,
+    },
+    /// The map has no walkable tile to start from.
+    #[error("map has no start or exit")]
+    NoStartExit,
+    /// The exit can't be reached from the start.
+    #[error("exit at {exit} is unreachable from start at {start}")]
+    ExitUnreachable {
+        /// Start position
+        start: IVec2,
+        /// Exit position
+        exit: IVec2,
+    },
+    /// The exit is reachable but too close to the start.
+    #[error("exit is {distance} steps from the start, below the required {required}")]
+    StartExitTooClose {
+        /// Walking distance between start and exit
+        distance: u32,
+        /// Required distance
+        required: u32,
+    },
+}
+
+impl ConstraintViolation {
+    /// Returns a stable machine-readable code for this violation.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ConstraintViolation::FloorTooLow { .. } => "constraints.floor_too_low",
+            ConstraintViolation::UnreachableRoom(_) => "constraints.unreachable_room",
+            ConstraintViolation::TooFewRooms { .. } => "constraints.too_few_rooms",
+            ConstraintViolation::TooManyRooms { .. } => "constraints.too_many_rooms",
+            ConstraintViolation::NoStartExit => "constraints.no_start_exit",
+            ConstraintViolation::ExitUnreachable { .. } => "constraints.exit_unreachable",
+            ConstraintViolation::StartExitTooClose { .. } => "constraints.start_exit_too_close",
+        }
+    }
+}
+
+/// Errors from [`GenerationConstraints::generate`].
+///
+/// `E` is the error type of the wrapped generator.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ConstraintError<E> {
+    /// A constraint value is invalid.
+    #[error("Invalid generation constraint '{field}': {reason}")]
+    InvalidConfig {
+        /// Name of the offending field
+        field: &'static str,
+        /// Why the value is rejected
+        reason: String,
+    },
+    /// The generator itself failed. Generator errors are configuration
+    /// problems, so they are not retried.
+    #[error(transparent)]
+    Generation(E),
+    /// Every attempt violated a constraint.
+    #[error("Constraints not met after {attempts} attempts: {last}")]
+    Unsatisfied {
+        /// Number of maps generated
+        attempts: u32,
+        /// The violation of the last attempt
+        last: ConstraintViolation,
+    },
+}
+
+impl<E> ConstraintError<E> {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ConstraintError::InvalidConfig { .. } => "constraints.invalid_config",
+            ConstraintError::Generation(_) => "constraints.generation",
+            ConstraintError::Unsatisfied { .. } => "constraints.unsatisfied",
+        }
+    }
+}
+
+/// A generated map that constraints can be checked against.
+pub trait ConstrainedMap {
+    /// Returns the map's walkability; impassable tiles count as wall.
+    fn cost_grid(&self) -> CostGrid;
+
+    /// Returns the map's rooms. Maps without rooms return an empty list,
+    /// which only fails a minimum room count.
+    fn room_bounds(&self) -> Vec<Rect> {
+        Vec::new()
+    }
+
+    /// Returns the start and exit positions, if the map defines them.
+    ///
+    /// When this returns `None`, the first walkable tile (in row-major
+    /// order) is the start and the walkable tile farthest from it is the exit.
+    fn start_and_exit(&self) -> Option<(IVec2, IVec2)> {
+        None
+    }
+}
+
+impl ConstrainedMap for Dungeon {
+    fn cost_grid(&self) -> CostGrid {
+        CostGrid::from(self)
+    }
+
+    fn room_bounds(&self) -> Vec<Rect> {
+        self.rooms().to_vec()
+    }
+
+    /// The centers of the rooms tagged [`RoomTag::Entrance`] and
+    /// [`RoomTag::Exit`], falling back to the first and last rooms.
+    fn start_and_exit(&self) -> Option<(IVec2, IVec2)> {
+        let tagged = |tag| {
+            self.rooms_with_tag(&tag)
+                .first()
+                .and_then(|&id| self.room(id))
+        };
+        let start = tagged(RoomTag::Entrance).or_else(|| self.rooms().first().copied())?;
+        let exit = tagged(RoomTag::Exit).or_else(|| self.rooms().last().copied())?;
+        Some((start.center(), exit.center()))
+    }
+}
+
+impl ConstrainedMap for CaveMap {
+    fn cost_grid(&self) -> CostGrid {
+        CostGrid::from(self)
+    }
+}
+
+impl ConstrainedMap for DrunkardMap {
+    fn cost_grid(&self) -> CostGrid {
+        CostGrid::from(self)
+    }
+}
+
+/// Postconditions a generated map must meet, with bounded retries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenerationConstraints {
+    /// Minimum walkable fraction of the map (0.0 to 1.0).
+    pub min_floor_percentage: f32,
+    /// Require every room to be reachable from the first room.
+    pub rooms_reachable: bool,
+    /// Minimum number of rooms.
+    pub min_rooms: usize,
+    /// Maximum number of rooms, if any.
+    pub max_rooms: Option<usize>,
+    /// Minimum walking distance from start to exit. Zero disables the check.
+    pub min_start_exit_distance: u32,
+    /// How many maps to generate before giving up.
+    pub max_attempts: u32,
+}
+
+impl Default for GenerationConstraints {
+    fn default() -> Self {
+        Self {
+            min_floor_percentage: 0.0,
+            rooms_reachable: true,
+            min_rooms: 0,
+            max_rooms: None,
+            min_start_exit_distance: 0,
+            max_attempts: 20,
+        }
+    }
+}
+
+impl GenerationConstraints {
+    /// Creates constraints with default values: every room reachable, at
+    /// most 20 attempts.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the minimum floor percentage (clamped to 0.0-1.0).
+    pub fn with_min_floor_percentage(mut self, percentage: f32) -> Self {
+        self.min_floor_percentage = percentage.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets whether every room must be reachable.
+    pub fn with_rooms_reachable(mut self, reachable: bool) -> Self {
+        self.rooms_reachable = reachable;
+        self
+    }
+
+    /// Sets the minimum room count.
+    pub fn with_min_rooms(mut self, min: usize) -> Self {
+        self.min_rooms = min;
+        self
+    }
+
+    /// Sets the maximum room count.
+    pub fn with_max_rooms(mut self, max: usize) -> Self {
+        self.max_rooms = Some(max);
+        self
+    }
+
+    /// Sets the minimum walking distance between start and exit.
+    pub fn with_min_start_exit_distance(mut self, distance: u32) -> Self {
+        self.min_start_exit_distance = distance;
+        self
+    }
+
+    /// Sets how many maps are generated before giving up.
+    pub fn with_max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts;
+        self
+    }
+
+    /// Checks that the constraints can be satisfied at all.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConstraintError::InvalidConfig` naming the first offending field.
+    pub fn validate<E>(&self) -> Result<(), ConstraintError<E>> {
+        if self.max_attempts == 0 {
+            return Err(ConstraintError::InvalidConfig {
+                field: "max_attempts",
+                reason: "must be at least 1".to_string(),
+            });
+        }
+        if !(0.0..=1.0).contains(&self.min_floor_percentage) {
+            return Err(ConstraintError::InvalidConfig {
+                field: "min_floor_percentage",
+                reason: format!("{} is outside 0.0-1.0", self.min_floor_percentage),
+            });
+        }
+        if let Some(max) = self.max_rooms.filter(|&max| max < self.min_rooms) {
+            return Err(ConstraintError::InvalidConfig {
+                field: "max_rooms",
+                reason: format!("{} is below min_rooms ({})", max, self.min_rooms),
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks a map against every constraint.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first violated constraint.
+    pub fn check<M: ConstrainedMap>(&self, map: &M) -> Result<(), ConstraintViolation> {
+        let grid = map.cost_grid();
+        let rooms = map.room_bounds();
+
+        if rooms.len() < self.min_rooms {
+            return Err(ConstraintViolation::TooFewRooms {
+                count: rooms.len(),
+                min: self.min_rooms,
+            });
+        }
+        if let Some(max) = self.max_rooms.filter(|&max| rooms.len() > max) {
+            return Err(ConstraintViolation::TooManyRooms {
+                count: rooms.len(),
+                max,
+            });
+        }
+
+        if self.min_floor_percentage > 0.0 {
+            let total = (grid.width() * grid.height()).max(1);
+            let floor = tiles(&grid).filter(|&p| grid.is_passable(p)).count();
+            let percentage = floor as f32 / total as f32;
+            if percentage < self.min_floor_percentage {
+                return Err(ConstraintViolation::FloorTooLow {
+                    percentage,
+                    required: self.min_floor_percentage,
+                });
+            }
+        }
+
+        if self.rooms_reachable && rooms.len() > 1 {
+            // The center may be blocked, e.g. by a pillar; any open tile works.
+            let start = room_tiles(&rooms[0])
+                .find(|&p| grid.in_bounds(p) && grid.is_passable(p))
+                .ok_or(ConstraintViolation::UnreachableRoom(0))?;
+            let distances = walk_distances(&grid, start);
+            let reached = |p: IVec2| distances[index(&grid, p)].is_some();
+            if let Some(i) = rooms
+                .iter()
+                .position(|room| !room_tiles(room).any(|p| grid.in_bounds(p) && reached(p)))
+            {
+                return Err(ConstraintViolation::UnreachableRoom(i));
+            }
+        }
+
+        if self.min_start_exit_distance > 0 {
+            let (start, exit) = match map.start_and_exit() {
+                Some(pair) => pair,
+                None => {
+                    let start = tiles(&grid)
+                        .find(|&p| grid.is_passable(p))
+                        .ok_or(ConstraintViolation::NoStartExit)?;
+                    let distances = walk_distances(&grid, start);
+                    let exit = tiles(&grid)
+                        .max_by_key(|&p| distances[index(&grid, p)])
+                        .unwrap_or(start);
+                    (start, exit)
+                }
+            };
+            let distances = walk_distances(&grid, start);
+            let distance = grid
+                .in_bounds(exit)
+                .then(|| distances[index(&grid, exit)])
+                .flatten()
+                .ok_or(ConstraintViolation::ExitUnreachable { start, exit })?;
+            if distance < self.min_start_exit_distance {
+                return Err(ConstraintViolation::StartExitTooClose {
+                    distance,
+                    required: self.min_start_exit_distance,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs a generator until its map meets every constraint.
+    ///
+    /// Each attempt gets its own RNG seeded from `rng`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConstraintError::InvalidConfig` if [`validate`](Self::validate)
+    /// fails, `ConstraintError::Generation` as soon as the generator fails, or
+    /// `ConstraintError::Unsatisfied` with the last violation once
+    /// `max_attempts` maps have been rejected.
+    pub fn generate<M, E>(
+        &self,
+        rng: &mut Rng,
+        mut generate: impl FnMut(&mut Rng) -> Result<M, E>,
+    ) -> Result<M, ConstraintError<E>>
+    where
+        M: ConstrainedMap,
+    {
+        self.validate()?;
+        let mut last = ConstraintViolation::NoStartExit;
+        for _ in 0..self.max_attempts {
+            let seed = ((rng.range(0, i32::MAX) as u64) << 32) | rng.range(0, i32::MAX) as u64;
+            let mut attempt_rng = Rng::with_seed(seed);
+            let map = generate(&mut attempt_rng).map_err(ConstraintError::Generation)?;
+            match self.check(&map) {
+                Ok(()) => return Ok(map),
+                Err(violation) => last = violation,
+            }
+        }
+        Err(ConstraintError::Unsatisfied {
+            attempts: self.max_attempts,
+            last,
+        })
+    }
+}
+
+fn tiles(grid: &CostGrid) -> impl Iterator<Item = IVec2> {
+    let (width, height) = (grid.width() as i32, grid.height() as i32);
+    (0..height).flat_map(move |y| (0..width).map(move |x| IVec2::new(x, y)))
+}
+
+fn room_tiles(room: &Rect) -> impl Iterator<Item = IVec2> {
+    let (min, max) = (room.min, room.max);
+    (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+}
+
+fn index(grid: &CostGrid, p: IVec2) -> usize {
+    (p.y as u32 * grid.width() + p.x as u32) as usize
+}
+
+/// Breadth-first walking distances from `start`; `None` for unreachable tiles.
+fn walk_distances(grid: &CostGrid, start: IVec2) -> Vec<Option<u32>> {
+    let mut distances = vec![None; (grid.width() * grid.height()) as usize];
+    if !grid.in_bounds(start) || !grid.is_passable(start) {
+        return distances;
+    }
+    distances[index(grid, start)] = Some(0);
+    let mut queue = VecDeque::from([start]);
+    while let Some(p) = queue.pop_front() {
+        let d = distances[index(grid, p)].unwrap_or_default();
+        for dir in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+            let next = p + dir;
+            if grid.in_bounds(next) && grid.is_passable(next) {
+                let slot = &mut distances[index(grid, next)];
+                if slot.is_none() {
+                    *slot = Some(d + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ascii::AsciiLegend;
+    use crate::bsp::{BspConfig, DungeonGenerator};
+
+    fn cave(text: &str) -> CaveMap {
+        CaveMap::from_ascii(text, &AsciiLegend::default()).unwrap()
+    }
+
+    #[test]
+    fn test_check_cave_constraints() {
+        let map = cave("#####\n#..##\n#####\n#..##\n#####");
+        let constraints = GenerationConstraints::new().with_min_floor_percentage(0.5);
+        assert_eq!(
+            constraints.check(&map).unwrap_err().code(),
+            "constraints.floor_too_low"
+        );
+
+        let constraints = GenerationConstraints::new().with_min_rooms(1);
+        assert_eq!(
+            constraints.check(&map),
+            Err(ConstraintViolation::TooFewRooms { count: 0, min: 1 })
+        );
+
+        let constraints = GenerationConstraints::new().with_min_start_exit_distance(1);
+        assert!(constraints.check(&map).is_ok());
+        let constraints = GenerationConstraints::new().with_min_start_exit_distance(2);
+        assert_eq!(
+            constraints.check(&map),
+            Err(ConstraintViolation::StartExitTooClose {
+                distance: 1,
+                required: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_unreachable_room() {
+        let mut dungeon = Dungeon::new(12, 5);
+        dungeon.carve_room(Rect::new(IVec2::new(1, 1), IVec2::new(3, 3)));
+        dungeon.carve_room(Rect::new(IVec2::new(7, 1), IVec2::new(9, 3)));
+        let constraints = GenerationConstraints::new();
+        assert_eq!(
+            constraints.check(&dungeon),
+            Err(ConstraintViolation::UnreachableRoom(1))
+        );
+        assert!(constraints
+            .with_rooms_reachable(false)
+            .with_max_rooms(2)
+            .check(&dungeon)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_reachability_ignores_blocked_room_center() {
+        use crate::decoration::Decoration;
+
+        let mut dungeon = Dungeon::new(10, 5);
+        let first = Rect::new(IVec2::new(1, 1), IVec2::new(3, 3));
+        dungeon.carve_room(first);
+        dungeon.carve_room(Rect::new(IVec2::new(5, 1), IVec2::new(7, 3)));
+        dungeon.set_floor(4, 2);
+        dungeon
+            .decorations_mut()
+            .place(first.center(), Decoration::Stalagmite);
+        assert!(GenerationConstraints::new().check(&dungeon).is_ok());
+    }
+
+    #[test]
+    fn test_generate_retries_deterministically() {
+        let constraints = GenerationConstraints::new()
+            .with_min_rooms(5)
+            .with_min_start_exit_distance(20)
+            .with_max_attempts(100);
+        let run = |seed| {
+            let mut calls = 0;
+            let dungeon = constraints
+                .generate(&mut Rng::with_seed(seed), |rng| {
+                    calls += 1;
+                    DungeonGenerator::generate(60, 40, &BspConfig::default(), rng)
+                })
+                .unwrap();
+            (dungeon.tiles().to_vec(), calls)
+        };
+        assert_eq!(run(8), run(8));
+    }
+
+    #[test]
+    fn test_generate_errors() {
+        let impossible = GenerationConstraints::new()
+            .with_min_floor_percentage(1.0)
+            .with_max_attempts(3);
+        let mut calls = 0;
+        let err = impossible
+            .generate(&mut Rng::with_seed(1), |rng| {
+                calls += 1;
+                DungeonGenerator::generate(40, 30, &BspConfig::default(), rng)
+            })
+            .unwrap_err();
+        assert_eq!(calls, 3);
+        assert!(matches!(
+            err,
+            ConstraintError::Unsatisfied {
+                attempts: 3,
+                last: ConstraintViolation::FloorTooLow { .. }
+            }
+        ));
+
+        let err = GenerationConstraints::new()
+            .generate(&mut Rng::with_seed(1), |rng| {
+                DungeonGenerator::generate(0, 30, &BspConfig::default(), rng)
+            })
+            .unwrap_err();
+        assert_eq!(err.code(), "constraints.generation");
+
+        let err = GenerationConstraints::new()
+            .with_min_rooms(4)
+            .with_max_rooms(2)
+            .validate::<()>()
+            .unwrap_err();
+        assert_eq!(err.code(), "constraints.invalid_config");
+    }
+}
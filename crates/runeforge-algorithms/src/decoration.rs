@@ -0,0 +1,386 @@
+//! Decoration pass: moss, rubble, puddles and stalagmites.
+//!
+//! Generators only carve floor and wall, which looks sterile. A [`Decorator`]
+//! runs a list of [`DecorationRule`]s over a finished map and writes the
+//! results to a [`DecorationLayer`]. Each rule picks tiles by:
+//! - Context: anywhere, against a wall, in a dead end or in the open
+//! - An optional noise mask, so decorations cluster into patches
+//! - A density, the chance that an eligible tile is decorated
+//!
+//! Rules run in order and never overwrite an earlier rule's decoration.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_algorithms::prelude::*;
+//!
+//! let mut rng = Rng::with_seed(5);
+//! let mut dungeon = DungeonGenerator::generate(80, 50, &BspConfig::default(), &mut rng).unwrap();
+//! Decorator::standard().decorate_dungeon(&mut dungeon, &mut rng).unwrap();
+//!
+//! for (pos, decoration) in dungeon.decorations().iter() {
+//!     assert!(dungeon.is_floor(pos.x, pos.y));
+//!     if decoration.blocks_movement() {
+//!         assert!(!dungeon.is_walkable(pos));
+//!     }
+//! }
+//! ```
+
+use std::collections::HashMap;
+
+use runeforge_geometry::prelude::IVec2;
+use runeforge_noise::{NoiseConfig, NoiseError, NoiseMap};
+use runeforge_random::prelude::*;
+
+use crate::bsp::Dungeon;
+
+/// A cosmetic or semantic decoration on a floor tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Decoration {
+    /// Moss or lichen, usually along walls.
+    Moss,
+    /// Loose stones, usually in dead ends.
+    Rubble,
+    /// A shallow puddle of water.
+    Puddle,
+    /// A stalagmite. Blocks movement, but not sight.
+    Stalagmite,
+    /// Any game-specific decoration.
+    Custom(u32),
+}
+
+impl Decoration {
+    /// Returns true if the decoration blocks movement.
+    pub fn blocks_movement(&self) -> bool {
+        matches!(self, Decoration::Stalagmite)
+    }
+}
+
+/// Which floor tiles a rule may decorate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    /// Any floor tile.
+    Anywhere,
+    /// Floor tiles with a wall on at least one of their four sides.
+    AgainstWall,
+    /// Floor tiles with exactly one floor neighbor on their four sides.
+    DeadEnd,
+    /// Floor tiles whose eight neighbors are all floor. Blocking decorations
+    /// placed here can't split the map, since the tiles around them stay
+    /// connected.
+    Open,
+}
+
+/// One kind of decoration and where it goes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecorationRule {
+    /// What to place.
+    pub decoration: Decoration,
+    /// Which tiles are eligible.
+    pub placement: Placement,
+    /// Chance (0.0 to 1.0) that an eligible tile is decorated.
+    pub density: f32,
+    /// Optional noise mask; only tiles where the noise is above
+    /// `noise_threshold` are eligible.
+    pub noise: Option<NoiseConfig>,
+    /// Noise value (-1.0 to 1.0) a tile must exceed when `noise` is set.
+    pub noise_threshold: f64,
+}
+
+impl DecorationRule {
+    /// Creates a rule placing a decoration on every eligible tile.
+    pub fn new(decoration: Decoration, placement: Placement) -> Self {
+        Self {
+            decoration,
+            placement,
+            density: 1.0,
+            noise: None,
+            noise_threshold: 0.0,
+        }
+    }
+
+    /// Sets the density (clamped to 0.0-1.0).
+    pub fn with_density(mut self, density: f32) -> Self {
+        self.density = density.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Restricts the rule to patches where noise is above a threshold.
+    pub fn with_noise(mut self, noise: NoiseConfig, threshold: f64) -> Self {
+        self.noise = Some(noise);
+        self.noise_threshold = threshold.clamp(-1.0, 1.0);
+        self
+    }
+}
+
+/// Sparse storage of decorations keyed by position.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DecorationLayer {
+    decorations: HashMap<IVec2, Decoration>,
+}
+
+impl DecorationLayer {
+    /// Creates an empty layer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Places a decoration, returning the one it replaced.
+    pub fn place(&mut self, pos: IVec2, decoration: Decoration) -> Option<Decoration> {
+        self.decorations.insert(pos, decoration)
+    }
+
+    /// Removes the decoration at a position.
+    pub fn remove(&mut self, pos: IVec2) -> Option<Decoration> {
+        self.decorations.remove(&pos)
+    }
+
+    /// Returns the decoration at a position.
+    pub fn get(&self, pos: IVec2) -> Option<Decoration> {
+        self.decorations.get(&pos).copied()
+    }
+
+    /// Returns an iterator over all decorations and their positions.
+    pub fn iter(&self) -> impl Iterator<Item = (IVec2, Decoration)> + '_ {
+        self.decorations.iter().map(|(&p, &d)| (p, d))
+    }
+
+    /// Returns the number of decorated tiles.
+    pub fn len(&self) -> usize {
+        self.decorations.len()
+    }
+
+    /// Returns true if nothing is decorated.
+    pub fn is_empty(&self) -> bool {
+        self.decorations.is_empty()
+    }
+
+    /// Returns the number of tiles with a given decoration.
+    pub fn count(&self, decoration: Decoration) -> usize {
+        self.decorations
+            .values()
+            .filter(|&&d| d == decoration)
+            .count()
+    }
+
+    /// Removes every decoration.
+    pub fn clear(&mut self) {
+        self.decorations.clear();
+    }
+
+    /// Returns true if a decoration at the position blocks movement.
+    pub fn blocks_movement(&self, pos: IVec2) -> bool {
+        self.get(pos).is_some_and(|d| d.blocks_movement())
+    }
+}
+
+/// An ordered list of decoration rules.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Decorator {
+    rules: Vec<DecorationRule>,
+}
+
+impl Decorator {
+    /// Creates a decorator with no rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a decorator with a reasonable default set of rules:
+    /// - Patches of moss along walls
+    /// - Rubble in most dead ends
+    /// - Scattered puddle clusters
+    /// - A few stalagmites in open areas
+    pub fn standard() -> Self {
+        Self::new()
+            .rule(
+                DecorationRule::new(Decoration::Moss, Placement::AgainstWall)
+                    .with_noise(NoiseConfig::new().with_scale(8.0).with_octaves(2), 0.1)
+                    .with_density(0.7),
+            )
+            .rule(DecorationRule::new(Decoration::Rubble, Placement::DeadEnd).with_density(0.6))
+            .rule(
+                DecorationRule::new(Decoration::Puddle, Placement::Anywhere)
+                    .with_noise(NoiseConfig::new().with_scale(6.0).with_octaves(2), 0.45)
+                    .with_density(0.8),
+            )
+            .rule(DecorationRule::new(Decoration::Stalagmite, Placement::Open).with_density(0.02))
+    }
+
+    /// Appends a rule.
+    pub fn rule(mut self, rule: DecorationRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Returns the rules in order.
+    pub fn rules(&self) -> &[DecorationRule] {
+        &self.rules
+    }
+
+    /// Decorates the floor tiles of a map.
+    ///
+    /// `is_floor` reports which tiles are floor; tiles outside the map count
+    /// as wall. Tiles already in `layer` are left alone. Returns the number of
+    /// decorations placed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `NoiseError` if a rule's noise settings are invalid. Nothing
+    /// is placed in that case.
+    pub fn decorate(
+        &self,
+        width: u32,
+        height: u32,
+        is_floor: impl Fn(IVec2) -> bool,
+        layer: &mut DecorationLayer,
+        rng: &mut Rng,
+    ) -> Result<usize, NoiseError> {
+        let masks = self
+            .rules
+            .iter()
+            .map(|rule| {
+                rule.noise
+                    .as_ref()
+                    .map(|config| {
+                        let seed = rng.range(0, i32::MAX) as u32;
+                        NoiseMap::with_config(width as usize, height as usize, seed, config)
+                    })
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let floor = |p: IVec2| {
+            p.x >= 0 && p.y >= 0 && p.x < width as i32 && p.y < height as i32 && is_floor(p)
+        };
+        let mut placed = 0;
+        for (rule, mask) in self.rules.iter().zip(&masks) {
+            for y in 0..height as i32 {
+                for x in 0..width as i32 {
+                    let p = IVec2::new(x, y);
+                    if !floor(p) || layer.get(p).is_some() || !fits(rule.placement, p, floor) {
+                        continue;
+                    }
+                    if mask
+                        .as_ref()
+                        .is_some_and(|m| m.get(x as usize, y as usize) <= rule.noise_threshold)
+                    {
+                        continue;
+                    }
+                    if rng.chance(rule.density) {
+                        layer.place(p, rule.decoration);
+                        placed += 1;
+                    }
+                }
+            }
+        }
+        Ok(placed)
+    }
+
+    /// Decorates a dungeon's floor, writing to [`Dungeon::decorations_mut`].
+    /// Tiles with interactive features (doors, levers, traps) are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`decorate`](Self::decorate).
+    pub fn decorate_dungeon(
+        &self,
+        dungeon: &mut Dungeon,
+        rng: &mut Rng,
+    ) -> Result<usize, NoiseError> {
+        let mut layer = std::mem::take(dungeon.decorations_mut());
+        let result = self.decorate(
+            dungeon.width(),
+            dungeon.height(),
+            |p| dungeon.is_floor(p.x, p.y) && dungeon.features().get(p).is_none(),
+            &mut layer,
+            rng,
+        );
+        *dungeon.decorations_mut() = layer;
+        result
+    }
+}
+
+fn fits(placement: Placement, p: IVec2, floor: impl Fn(IVec2) -> bool) -> bool {
+    const CARDINALS: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+    let open_sides = CARDINALS.iter().filter(|&&d| floor(p + d)).count();
+    match placement {
+        Placement::Anywhere => true,
+        Placement::AgainstWall => open_sides < 4,
+        Placement::DeadEnd => open_sides == 1,
+        Placement::Open => (-1..=1).all(|dy| (-1..=1).all(|dx| floor(p + IVec2::new(dx, dy)))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ascii::AsciiLegend;
+
+    fn dungeon(text: &str) -> Dungeon {
+        Dungeon::from_ascii(text, &AsciiLegend::default()).unwrap()
+    }
+
+    #[test]
+    fn test_placement_contexts() {
+        let map = dungeon("#######\n#.....#\n#.....#\n#.....#\n###.###\n###.###\n#######");
+        let place = |placement| {
+            let mut d = map.clone();
+            Decorator::new()
+                .rule(DecorationRule::new(Decoration::Rubble, placement))
+                .decorate_dungeon(&mut d, &mut Rng::with_seed(1))
+                .unwrap();
+            let mut tiles: Vec<_> = d.decorations().iter().map(|(p, _)| p).collect();
+            tiles.sort_by_key(|p| (p.y, p.x));
+            tiles
+        };
+        assert_eq!(place(Placement::DeadEnd), vec![IVec2::new(3, 5)]);
+        assert_eq!(
+            place(Placement::Open),
+            vec![IVec2::new(2, 2), IVec2::new(3, 2), IVec2::new(4, 2)]
+        );
+        assert_eq!(place(Placement::AgainstWall).len(), 13);
+        assert_eq!(place(Placement::Anywhere).len(), 17);
+    }
+
+    #[test]
+    fn test_rules_do_not_overwrite_and_block_movement() {
+        let mut d = dungeon("#####\n#...#\n#...#\n#...#\n#####");
+        let placed = Decorator::new()
+            .rule(DecorationRule::new(Decoration::Stalagmite, Placement::Open))
+            .rule(DecorationRule::new(Decoration::Moss, Placement::Anywhere))
+            .decorate_dungeon(&mut d, &mut Rng::with_seed(1))
+            .unwrap();
+        assert_eq!(placed, 9);
+        assert_eq!(
+            d.decorations().get(IVec2::new(2, 2)),
+            Some(Decoration::Stalagmite)
+        );
+        assert_eq!(d.decorations().count(Decoration::Moss), 8);
+        assert!(!d.is_walkable(IVec2::new(2, 2)));
+        assert!(d.is_walkable(IVec2::new(1, 1)));
+    }
+
+    #[test]
+    fn test_noise_mask_and_errors() {
+        let mut layer = DecorationLayer::new();
+        let rule = DecorationRule::new(Decoration::Puddle, Placement::Anywhere)
+            .with_noise(NoiseConfig::new().with_scale(5.0), 0.2);
+        let placed = Decorator::new()
+            .rule(rule)
+            .decorate(40, 40, |_| true, &mut layer, &mut Rng::with_seed(3))
+            .unwrap();
+        assert!(placed > 0 && placed < 1600);
+
+        let bad = Decorator::new().rule(
+            DecorationRule::new(Decoration::Moss, Placement::Anywhere)
+                .with_noise(NoiseConfig::new().with_octaves(0), 0.0),
+        );
+        let mut layer = DecorationLayer::new();
+        let err = bad
+            .decorate(10, 10, |_| true, &mut layer, &mut Rng::with_seed(1))
+            .unwrap_err();
+        assert_eq!(err.code(), "noise.invalid_config");
+        assert!(layer.is_empty());
+    }
+}
@@ -0,0 +1,590 @@
+//! Diffusion-limited aggregation (DLA) cave generation.
+//!
+//! DLA grows a cave one particle at a time. Each particle wanders until it
+//! touches the existing cave and then sticks, so the floor grows outward in
+//! branching, coral-like arms instead of the blobby chambers of cellular
+//! automata. The algorithm works by:
+//! 1. Carving a small seed around each attractor (the map center by default)
+//! 2. Releasing particles according to the [`DlaMode`]
+//! 3. Carving where each particle sticks
+//! 4. Stopping once the target floor percentage is reached
+//! 5. Joining clusters grown from different attractors with tunnels
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_algorithms::dla::{DlaConfig, DlaGenerator, DlaMode};
+//! use runeforge_geometry::prelude::IVec2;
+//! use runeforge_random::prelude::Rng;
+//!
+//! let config = DlaConfig::new()
+//!     .with_mode(DlaMode::Attractor)
+//!     .with_attractor(IVec2::new(20, 25))
+//!     .with_attractor(IVec2::new(60, 25));
+//! let mut rng = Rng::with_seed(1);
+//! let cave = DlaGenerator::generate(80, 50, &config, &mut rng).unwrap();
+//!
+//! assert!(cave.is_floor(20, 25) && cave.is_floor(60, 25));
+//! ```
+
+use runeforge_geometry::prelude::IVec2;
+use runeforge_random::prelude::*;
+use thiserror::Error;
+
+use crate::caves::CaveMap;
+use crate::progress::{Progress, ProgressSink};
+use crate::trace::GenerationTrace;
+
+/// Errors that can occur during DLA generation.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DlaError {
+    /// The map is too small to hold anything inside its solid border.
+    #[error("Invalid DLA dimensions: {width}x{height} (both must be > 2)")]
+    InvalidDimensions {
+        /// Map width
+        width: u32,
+        /// Map height
+        height: u32,
+    },
+    /// A configuration value would produce a degenerate map.
+    #[error("Invalid DLA config field '{field}': {reason}")]
+    InvalidConfig {
+        /// Name of the offending field
+        field: &'static str,
+        /// Why the value is rejected
+        reason: String,
+    },
+    /// Generation was cancelled through its [`ProgressSink`].
+    #[error("DLA generation was cancelled")]
+    Cancelled,
+}
+
+impl DlaError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            DlaError::InvalidDimensions { .. } => "dla.invalid_dimensions",
+            DlaError::InvalidConfig { .. } => "dla.invalid_config",
+            DlaError::Cancelled => "dla.cancelled",
+        }
+    }
+}
+
+/// How particles move before they stick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DlaMode {
+    /// Particles start on a random wall tile and wander until they touch the
+    /// cave, sticking just outside it. Gives fine, feathery branches.
+    #[default]
+    WalkInwards,
+    /// Particles start inside the cave and wander until they step into rock,
+    /// which they dig out. Gives thicker, winding passages.
+    WalkOutwards,
+    /// Particles start on a random wall tile and drift toward the nearest
+    /// attractor, sticking on contact. Gives radial spokes around each attractor.
+    Attractor,
+}
+
+/// Configuration for DLA generation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DlaConfig {
+    /// Target percentage of map that should be floor (0.0 to 1.0).
+    pub target_floor_percentage: f32,
+    /// How particles move.
+    pub mode: DlaMode,
+    /// Points the cave grows from. The map center is used when empty.
+    pub attractors: Vec<IVec2>,
+    /// Size of the square carved where a particle sticks (1 = one tile).
+    pub brush_size: u32,
+    /// Steps a particle may take before it is discarded.
+    pub max_walk_steps: u32,
+    /// Maximum particles released before giving up.
+    pub max_particles: u32,
+}
+
+impl Default for DlaConfig {
+    fn default() -> Self {
+        Self {
+            target_floor_percentage: 0.25,
+            mode: DlaMode::default(),
+            attractors: Vec::new(),
+            brush_size: 1,
+            max_walk_steps: 2_000,
+            max_particles: 20_000,
+        }
+    }
+}
+
+impl DlaConfig {
+    /// Creates a new configuration with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the target floor percentage (clamped to 0.0-1.0).
+    pub fn with_target_floor_percentage(mut self, percentage: f32) -> Self {
+        self.target_floor_percentage = percentage.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the growth mode.
+    pub fn with_mode(mut self, mode: DlaMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Adds an attractor point.
+    pub fn with_attractor(mut self, point: IVec2) -> Self {
+        self.attractors.push(point);
+        self
+    }
+
+    /// Sets the brush size (at least 1).
+    pub fn with_brush_size(mut self, size: u32) -> Self {
+        self.brush_size = size.max(1);
+        self
+    }
+
+    /// Sets the maximum steps per particle.
+    pub fn with_max_walk_steps(mut self, steps: u32) -> Self {
+        self.max_walk_steps = steps;
+        self
+    }
+
+    /// Sets the maximum number of particles.
+    pub fn with_max_particles(mut self, particles: u32) -> Self {
+        self.max_particles = particles;
+        self
+    }
+
+    /// Checks that the configuration grows a cave.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DlaError::InvalidConfig` naming the first offending field.
+    pub fn validate(&self) -> Result<(), DlaError> {
+        let invalid = |field, reason: String| Err(DlaError::InvalidConfig { field, reason });
+
+        if !(self.target_floor_percentage > 0.0 && self.target_floor_percentage <= 1.0) {
+            return invalid(
+                "target_floor_percentage",
+                format!(
+                    "{} is outside 0.0-1.0 (exclusive of 0)",
+                    self.target_floor_percentage
+                ),
+            );
+        }
+        if self.brush_size == 0 {
+            return invalid("brush_size", "must be at least 1".into());
+        }
+        if self.max_walk_steps == 0 {
+            return invalid("max_walk_steps", "must be at least 1".into());
+        }
+        if self.max_particles == 0 {
+            return invalid("max_particles", "must be at least 1".into());
+        }
+        Ok(())
+    }
+}
+
+/// Number of particles between progress reports in
+/// [`DlaGenerator::generate_with_progress`].
+pub const PROGRESS_INTERVAL: u32 = 256;
+
+/// Generator for DLA caves.
+pub struct DlaGenerator;
+
+impl DlaGenerator {
+    /// Generates a cave by diffusion-limited aggregation.
+    ///
+    /// The outermost ring of tiles is always wall, and the result is a single
+    /// connected cave.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DlaError::InvalidDimensions` if width or height is less than 3,
+    /// or `DlaError::InvalidConfig` if [`DlaConfig::validate`] fails.
+    pub fn generate(
+        width: u32,
+        height: u32,
+        config: &DlaConfig,
+        rng: &mut Rng,
+    ) -> Result<CaveMap, DlaError> {
+        Self::generate_inner(width, height, config, rng, None, None)
+    }
+
+    /// Generates a cave, recording a frame every [`GenerationTrace::interval`]
+    /// particles that highlights where they stuck.
+    ///
+    /// Produces the same map as [`generate`](Self::generate) for the same seed.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`generate`](Self::generate).
+    pub fn generate_traced(
+        width: u32,
+        height: u32,
+        config: &DlaConfig,
+        rng: &mut Rng,
+        trace: &mut GenerationTrace,
+    ) -> Result<CaveMap, DlaError> {
+        Self::generate_inner(width, height, config, rng, Some(trace), None)
+    }
+
+    /// Generates a cave, reporting floor tiles grown against the target every
+    /// [`PROGRESS_INTERVAL`] particles and stopping early if the sink is
+    /// cancelled.
+    ///
+    /// Produces the same map as [`generate`](Self::generate) for the same seed.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`generate`](Self::generate), plus `DlaError::Cancelled` if
+    /// the sink asked to stop.
+    pub fn generate_with_progress(
+        width: u32,
+        height: u32,
+        config: &DlaConfig,
+        rng: &mut Rng,
+        progress: &mut dyn ProgressSink,
+    ) -> Result<CaveMap, DlaError> {
+        Self::generate_inner(width, height, config, rng, None, Some(progress))
+    }
+
+    fn generate_inner(
+        width: u32,
+        height: u32,
+        config: &DlaConfig,
+        rng: &mut Rng,
+        mut trace: Option<&mut GenerationTrace>,
+        mut progress: Option<&mut dyn ProgressSink>,
+    ) -> Result<CaveMap, DlaError> {
+        if width < 3 || height < 3 {
+            return Err(DlaError::InvalidDimensions { width, height });
+        }
+        config.validate()?;
+
+        let mut grower = Grower {
+            cave: CaveMap::new(width, height),
+            floor: 0,
+        };
+        let attractors: Vec<IVec2> = if config.attractors.is_empty() {
+            vec![IVec2::new(width as i32 / 2, height as i32 / 2)]
+        } else {
+            config
+                .attractors
+                .iter()
+                .map(|&p| grower.clamp_inside(p))
+                .collect()
+        };
+        for &seed in &attractors {
+            grower.carve_square(seed, 1);
+        }
+
+        let interior = ((width - 2) * (height - 2)) as f32;
+        let target = (config.target_floor_percentage * width as f32 * height as f32)
+            .min(interior)
+            .ceil() as usize;
+
+        let mut stuck = Vec::new();
+        let mut particles = 0;
+        while grower.floor < target && particles < config.max_particles {
+            particles += 1;
+            let hit = match config.mode {
+                DlaMode::WalkInwards => grower.walk_inwards(config.max_walk_steps, rng),
+                DlaMode::WalkOutwards => grower.walk_outwards(config.max_walk_steps, rng),
+                DlaMode::Attractor => {
+                    grower.drift_to_attractor(&attractors, config.max_walk_steps, rng)
+                }
+            };
+            if let Some(pos) = hit {
+                grower.carve_square(pos, config.brush_size);
+                stuck.push(pos);
+            }
+
+            if let Some(sink) = progress.as_deref_mut() {
+                if particles % PROGRESS_INTERVAL == 0 {
+                    if sink.is_cancelled() {
+                        return Err(DlaError::Cancelled);
+                    }
+                    sink.report(Progress {
+                        stage: "growing",
+                        done: grower.floor as u64,
+                        total: target as u64,
+                    });
+                }
+            }
+
+            if let Some(trace) = trace.as_deref_mut() {
+                if particles % trace.interval() == 0 {
+                    trace.record(
+                        format!("particle {}", particles),
+                        width,
+                        height,
+                        grower.cave.tiles(),
+                        std::mem::take(&mut stuck),
+                        Vec::new(),
+                    );
+                }
+            }
+        }
+
+        // Separate attractors grow separate clusters; join them up.
+        if let Some(sink) = progress {
+            sink.report(Progress {
+                stage: "connecting",
+                done: target as u64,
+                total: target as u64,
+            });
+        }
+        let tunnels = grower.cave.connect_regions(1);
+        stuck.extend(tunnels.into_iter().flatten());
+
+        if let Some(trace) = trace {
+            if !stuck.is_empty() {
+                trace.record(
+                    format!("particle {}", particles),
+                    width,
+                    height,
+                    grower.cave.tiles(),
+                    stuck,
+                    Vec::new(),
+                );
+            }
+        }
+
+        Ok(grower.cave)
+    }
+}
+
+/// The cave being grown, with a running floor count.
+struct Grower {
+    cave: CaveMap,
+    floor: usize,
+}
+
+impl Grower {
+    fn inside(&self, p: IVec2) -> bool {
+        p.x >= 1
+            && p.y >= 1
+            && p.x < self.cave.width() as i32 - 1
+            && p.y < self.cave.height() as i32 - 1
+    }
+
+    fn clamp_inside(&self, p: IVec2) -> IVec2 {
+        p.clamp(
+            IVec2::ONE,
+            IVec2::new(self.cave.width() as i32 - 2, self.cave.height() as i32 - 2),
+        )
+    }
+
+    fn is_floor(&self, p: IVec2) -> bool {
+        self.inside(p) && self.cave.is_floor(p.x as u32, p.y as u32)
+    }
+
+    /// Carves a `size`-wide square centered on `center`, inside the border.
+    fn carve_square(&mut self, center: IVec2, size: u32) {
+        let low = (size as i32 - 1) / 2;
+        let high = size as i32 / 2;
+        for dy in -low..=high {
+            for dx in -low..=high {
+                let p = center + IVec2::new(dx, dy);
+                if self.inside(p) && !self.is_floor(p) {
+                    self.cave.set_tile(p.x as u32, p.y as u32, true);
+                    self.floor += 1;
+                }
+            }
+        }
+    }
+
+    fn random_inside(&self, rng: &mut Rng) -> IVec2 {
+        IVec2::new(
+            rng.range(1, self.cave.width() as i32 - 2),
+            rng.range(1, self.cave.height() as i32 - 2),
+        )
+    }
+
+    fn random_step(&self, p: IVec2, rng: &mut Rng) -> IVec2 {
+        let step = match rng.range(0, 3) {
+            0 => IVec2::NEG_Y,
+            1 => IVec2::Y,
+            2 => IVec2::X,
+            _ => IVec2::NEG_X,
+        };
+        self.clamp_inside(p + step)
+    }
+
+    /// Returns the wall tile the particle was on when it touched the cave.
+    fn walk_inwards(&self, max_steps: u32, rng: &mut Rng) -> Option<IVec2> {
+        let mut pos = self.random_inside(rng);
+        if self.is_floor(pos) {
+            return None;
+        }
+        for _ in 0..max_steps {
+            let next = self.random_step(pos, rng);
+            if self.is_floor(next) {
+                return Some(pos);
+            }
+            pos = next;
+        }
+        None
+    }
+
+    /// Returns the first wall tile the particle steps into.
+    fn walk_outwards(&self, max_steps: u32, rng: &mut Rng) -> Option<IVec2> {
+        let mut pos = loop {
+            let p = self.random_inside(rng);
+            if self.is_floor(p) {
+                break p;
+            }
+        };
+        for _ in 0..max_steps {
+            pos = self.random_step(pos, rng);
+            if !self.is_floor(pos) {
+                return Some(pos);
+            }
+        }
+        None
+    }
+
+    /// Moves the particle toward the nearest attractor, choosing the axis at
+    /// random in proportion to the remaining distance.
+    fn drift_to_attractor(
+        &self,
+        attractors: &[IVec2],
+        max_steps: u32,
+        rng: &mut Rng,
+    ) -> Option<IVec2> {
+        let mut pos = self.random_inside(rng);
+        if self.is_floor(pos) {
+            return None;
+        }
+        let target = attractors
+            .iter()
+            .copied()
+            .min_by_key(|a| (*a - pos).abs().element_sum())?;
+        for _ in 0..max_steps {
+            let delta = target - pos;
+            let total = delta.abs().element_sum();
+            if total == 0 {
+                return None;
+            }
+            let step = if rng.range(1, total) <= delta.x.abs() {
+                IVec2::new(delta.x.signum(), 0)
+            } else {
+                IVec2::new(0, delta.y.signum())
+            };
+            let next = pos + step;
+            if self.is_floor(next) {
+                return Some(pos);
+            }
+            pos = next;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_connected(cave: &CaveMap) -> bool {
+        let (w, h) = (cave.width() as i32, cave.height() as i32);
+        let floors: Vec<IVec2> = (0..h)
+            .flat_map(|y| (0..w).map(move |x| IVec2::new(x, y)))
+            .filter(|p| cave.is_floor(p.x as u32, p.y as u32))
+            .collect();
+        let mut seen = vec![floors[0]];
+        let mut stack = vec![floors[0]];
+        while let Some(p) = stack.pop() {
+            for d in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+                let n = p + d;
+                if cave.is_floor(n.x as u32, n.y as u32) && !seen.contains(&n) {
+                    seen.push(n);
+                    stack.push(n);
+                }
+            }
+        }
+        seen.len() == floors.len()
+    }
+
+    #[test]
+    fn test_all_modes_reach_target_connected() {
+        for mode in [
+            DlaMode::WalkInwards,
+            DlaMode::WalkOutwards,
+            DlaMode::Attractor,
+        ] {
+            let config = DlaConfig::new().with_mode(mode);
+            let cave = DlaGenerator::generate(40, 30, &config, &mut Rng::with_seed(5)).unwrap();
+            assert!(cave.floor_percentage() >= 0.25, "{mode:?}");
+            assert!(is_connected(&cave), "{mode:?}");
+            assert!(cave.is_wall(0, 0) && cave.is_wall(39, 29));
+        }
+    }
+
+    #[test]
+    fn test_separate_attractors_are_joined() {
+        let config = DlaConfig::new()
+            .with_mode(DlaMode::Attractor)
+            .with_attractor(IVec2::new(5, 5))
+            .with_attractor(IVec2::new(34, 24))
+            .with_target_floor_percentage(0.1);
+        let cave = DlaGenerator::generate(40, 30, &config, &mut Rng::with_seed(2)).unwrap();
+        assert!(is_connected(&cave));
+    }
+
+    #[test]
+    fn test_deterministic_and_traced() {
+        let config = DlaConfig::new().with_brush_size(2);
+        let a = DlaGenerator::generate(30, 30, &config, &mut Rng::with_seed(8)).unwrap();
+        let mut trace = GenerationTrace::new().with_interval(50);
+        let b = DlaGenerator::generate_traced(30, 30, &config, &mut Rng::with_seed(8), &mut trace)
+            .unwrap();
+        assert_eq!(a.tiles(), b.tiles());
+        assert!(!trace.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let config = DlaConfig::default();
+        let err = DlaGenerator::generate(2, 10, &config, &mut Rng::with_seed(1)).unwrap_err();
+        assert_eq!(err.code(), "dla.invalid_dimensions");
+        let config = DlaConfig {
+            max_particles: 0,
+            ..DlaConfig::default()
+        };
+        assert_eq!(config.validate().unwrap_err().code(), "dla.invalid_config");
+    }
+
+    #[test]
+    fn test_progress_and_cancel() {
+        let config = DlaConfig::default();
+        let mut last = None;
+        let mut report = |p: Progress<'_>| last = Some((p.done, p.total));
+        let cave = DlaGenerator::generate_with_progress(
+            60,
+            40,
+            &config,
+            &mut Rng::with_seed(5),
+            &mut report,
+        )
+        .unwrap();
+        let plain = DlaGenerator::generate(60, 40, &config, &mut Rng::with_seed(5)).unwrap();
+        assert_eq!(cave.tiles(), plain.tiles());
+        let (done, total) = last.unwrap();
+        assert_eq!(done, total);
+
+        let token = crate::progress::CancelToken::new();
+        token.cancel();
+        let err = DlaGenerator::generate_with_progress(
+            60,
+            40,
+            &config,
+            &mut Rng::with_seed(5),
+            &mut token.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), "dla.cancelled");
+    }
+}
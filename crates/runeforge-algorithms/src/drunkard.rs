@@ -26,37 +26,45 @@
 //! }
 //! ```
 
+use runeforge_geometry::prelude::IVec2;
+use runeforge_pathfinding::prelude::CostGrid;
 use runeforge_random::prelude::*;
-use std::fmt;
+use thiserror::Error;
+
+use crate::ascii::{self, AsciiLegend, AsciiMapError};
+use crate::trace::GenerationTrace;
 
 /// Errors that can occur during drunkard's walk generation.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum DrunkardError {
     /// Invalid dimensions (width or height is zero).
+    #[error("Invalid map dimensions: {width}x{height} (both must be > 0)")]
     InvalidDimensions {
         /// Map width
         width: u32,
         /// Map height
         height: u32,
     },
+    /// A configuration value would produce a degenerate map.
+    #[error("Invalid drunkard config field '{field}': {reason}")]
+    InvalidConfig {
+        /// Name of the offending field
+        field: &'static str,
+        /// Why the value is rejected
+        reason: String,
+    },
 }
 
-impl fmt::Display for DrunkardError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl DrunkardError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
         match self {
-            DrunkardError::InvalidDimensions { width, height } => {
-                write!(
-                    f,
-                    "Invalid map dimensions: {}x{} (both must be > 0)",
-                    width, height
-                )
-            }
+            DrunkardError::InvalidDimensions { .. } => "drunkard.invalid_dimensions",
+            DrunkardError::InvalidConfig { .. } => "drunkard.invalid_config",
         }
     }
 }
 
-impl std::error::Error for DrunkardError {}
-
 /// Configuration for drunkard's walk cave generation.
 #[derive(Debug, Clone)]
 pub struct DrunkardConfig {
@@ -110,6 +118,29 @@ impl DrunkardConfig {
         self.max_steps = max_steps;
         self
     }
+
+    /// Checks that the configuration carves more than a single tile.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DrunkardError::InvalidConfig` naming the first offending field.
+    pub fn validate(&self) -> Result<(), DrunkardError> {
+        let invalid = |field, reason: String| Err(DrunkardError::InvalidConfig { field, reason });
+
+        if !(self.target_floor_percentage > 0.0 && self.target_floor_percentage <= 1.0) {
+            return invalid(
+                "target_floor_percentage",
+                format!(
+                    "{} is outside 0.0-1.0 (exclusive of 0)",
+                    self.target_floor_percentage
+                ),
+            );
+        }
+        if self.max_steps == 0 {
+            return invalid("max_steps", "must be at least 1".into());
+        }
+        Ok(())
+    }
 }
 
 /// A generated cave map using drunkard's walk.
@@ -188,12 +219,30 @@ impl DrunkardMap {
 
     /// Prints the map to the console.
     pub fn print(&self) {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                print!("{}", if self.is_floor(x, y) { '.' } else { '#' });
-            }
-            println!();
-        }
+        print!("{}", self.to_ascii());
+    }
+
+    /// Renders the map as text using the default legend (`.` floor, `#` wall).
+    pub fn to_ascii(&self) -> String {
+        self.to_ascii_with(&AsciiLegend::default())
+    }
+
+    /// Renders the map as text using a custom legend.
+    pub fn to_ascii_with(&self, legend: &AsciiLegend) -> String {
+        ascii::render(self.width, &self.tiles, legend)
+    }
+
+    /// Reads a map from text, one line per row.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AsciiMapError`] if the text is empty, rows have different
+    /// lengths, or a character is not in the legend.
+    pub fn from_ascii(text: &str, legend: &AsciiLegend) -> Result<Self, AsciiMapError> {
+        let (width, height, tiles) = ascii::parse(text, legend)?;
+        let mut map = Self::new(width, height);
+        map.tiles = tiles;
+        Ok(map)
     }
 }
 
@@ -228,6 +277,15 @@ impl Direction {
     }
 }
 
+impl From<&DrunkardMap> for CostGrid {
+    /// Floor tiles cost 1; walls are impassable.
+    fn from(map: &DrunkardMap) -> Self {
+        CostGrid::from_walkable(map.width, map.height, |p| {
+            map.is_floor(p.x as u32, p.y as u32)
+        })
+    }
+}
+
 /// Generator for drunkard's walk caves.
 pub struct DrunkardGenerator;
 
@@ -236,16 +294,47 @@ impl DrunkardGenerator {
     ///
     /// # Errors
     ///
-    /// Returns `DrunkardError::InvalidDimensions` if width or height is zero.
+    /// Returns `DrunkardError::InvalidDimensions` if width or height is zero, or
+    /// `DrunkardError::InvalidConfig` if [`DrunkardConfig::validate`] fails.
     pub fn generate(
         width: u32,
         height: u32,
         config: &DrunkardConfig,
         rng: &mut Rng,
+    ) -> Result<DrunkardMap, DrunkardError> {
+        Self::generate_inner(width, height, config, rng, None)
+    }
+
+    /// Generates a cave, recording the walk every [`GenerationTrace::interval`] steps.
+    ///
+    /// Each frame highlights the path walked since the previous frame. Produces
+    /// the same map as [`generate`](Self::generate) for the same seed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DrunkardError::InvalidDimensions` if width or height is zero, or
+    /// `DrunkardError::InvalidConfig` if [`DrunkardConfig::validate`] fails.
+    pub fn generate_traced(
+        width: u32,
+        height: u32,
+        config: &DrunkardConfig,
+        rng: &mut Rng,
+        trace: &mut GenerationTrace,
+    ) -> Result<DrunkardMap, DrunkardError> {
+        Self::generate_inner(width, height, config, rng, Some(trace))
+    }
+
+    fn generate_inner(
+        width: u32,
+        height: u32,
+        config: &DrunkardConfig,
+        rng: &mut Rng,
+        mut trace: Option<&mut GenerationTrace>,
     ) -> Result<DrunkardMap, DrunkardError> {
         if width == 0 || height == 0 {
             return Err(DrunkardError::InvalidDimensions { width, height });
         }
+        config.validate()?;
 
         let mut map = DrunkardMap::new(width, height);
 
@@ -259,11 +348,40 @@ impl DrunkardGenerator {
 
         map.set_tile(x, y, true);
 
+        let mut path = vec![IVec2::new(x as i32, y as i32)];
         let mut steps = 0;
         while map.floor_percentage() < config.target_floor_percentage && steps < config.max_steps {
             (x, y) = Self::take_step(&map, x, y, rng);
             map.set_tile(x, y, true);
             steps += 1;
+
+            if let Some(trace) = trace.as_deref_mut() {
+                path.push(IVec2::new(x as i32, y as i32));
+                if steps % trace.interval() == 0 {
+                    let highlights = std::mem::take(&mut path);
+                    trace.record(
+                        format!("step {}", steps),
+                        width,
+                        height,
+                        map.tiles(),
+                        highlights,
+                        Vec::new(),
+                    );
+                }
+            }
+        }
+
+        if let Some(trace) = trace {
+            if !path.is_empty() {
+                trace.record(
+                    format!("step {}", steps),
+                    width,
+                    height,
+                    map.tiles(),
+                    path,
+                    Vec::new(),
+                );
+            }
         }
 
         Ok(map)
@@ -361,6 +479,23 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_drunkard_config_validate() {
+        assert!(DrunkardConfig::default().validate().is_ok());
+
+        let config = DrunkardConfig::new().with_target_floor_percentage(0.0);
+        assert!(matches!(
+            DrunkardGenerator::generate(20, 20, &config, &mut seeded_rng(1)),
+            Err(DrunkardError::InvalidConfig {
+                field: "target_floor_percentage",
+                ..
+            })
+        ));
+
+        let config = DrunkardConfig::new().with_max_steps(0);
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_drunkard_generation_dimensions() {
         let config = DrunkardConfig::default();
@@ -449,4 +584,30 @@ mod tests {
             assert!(y < map.height(), "y={} out of bounds", y);
         }
     }
+
+    #[test]
+    fn test_ascii_round_trip() {
+        let map =
+            DrunkardGenerator::generate(20, 10, &DrunkardConfig::default(), &mut seeded_rng(3))
+                .unwrap();
+        let restored = DrunkardMap::from_ascii(&map.to_ascii(), &AsciiLegend::new()).unwrap();
+        assert_eq!(restored.tiles(), map.tiles());
+        assert!(DrunkardMap::from_ascii("", &AsciiLegend::new()).is_err());
+    }
+
+    #[test]
+    fn test_traced_generation_records_path() {
+        let config = DrunkardConfig::new().with_target_floor_percentage(0.3);
+        let plain = DrunkardGenerator::generate(30, 20, &config, &mut seeded_rng(8)).unwrap();
+
+        let mut trace = GenerationTrace::new().with_interval(50);
+        let traced =
+            DrunkardGenerator::generate_traced(30, 20, &config, &mut seeded_rng(8), &mut trace)
+                .unwrap();
+
+        assert_eq!(plain.tiles(), traced.tiles());
+        assert!(trace.len() > 1);
+        assert_eq!(trace.frames()[0].highlights.len(), 51);
+        assert_eq!(trace.last().unwrap().tiles, traced.tiles());
+    }
 }
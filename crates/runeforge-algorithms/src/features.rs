@@ -0,0 +1,437 @@
+//! Interactive map features: doors, levers, pressure plates and traps.
+//!
+//! Generated maps only know whether a tile is floor or wall. A [`FeatureLayer`]
+//! sits on top of that and stores the tiles whose behavior can change during
+//! play. Generators place features, and the game queries the layer when it
+//! builds its FOV and pathfinding providers:
+//!
+//! *   [`is_opaque`](FeatureLayer::is_opaque) is true for closed doors.
+//! *   [`movement_cost`](FeatureLayer::movement_cost) is `None` for locked doors
+//!     and higher than normal for closed doors (opening takes time).
+//!
+//! Levers and pressure plates broadcast on a numbered channel. Doors and traps
+//! listening on the same channel react when it fires.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_algorithms::features::{Feature, FeatureEvent, FeatureLayer, TrapTrigger};
+//! use runeforge_geometry::prelude::IVec2;
+//!
+//! let mut layer = FeatureLayer::new();
+//! let door = IVec2::new(5, 2);
+//! layer.place(door, Feature::door().on_channel(1));
+//! layer.place(IVec2::new(1, 1), Feature::Lever { channel: 1, on: false });
+//! layer.place(IVec2::new(3, 3), Feature::trap(TrapTrigger::Step));
+//!
+//! assert!(layer.is_opaque(door));
+//!
+//! // Pulling the lever opens the linked door.
+//! let events = layer.interact(IVec2::new(1, 1));
+//! assert!(events.contains(&FeatureEvent::DoorOpened(door)));
+//! assert!(!layer.is_opaque(door));
+//!
+//! // Stepping on the trap springs it.
+//! let events = layer.step_on(IVec2::new(3, 3));
+//! assert_eq!(events, vec![FeatureEvent::TrapTriggered(IVec2::new(3, 3))]);
+//! ```
+
+use std::collections::HashMap;
+
+use runeforge_geometry::prelude::*;
+
+/// The base cost of moving onto a tile without features.
+pub const BASE_MOVEMENT_COST: u32 = 1;
+
+/// The cost of moving through a closed (but unlocked) door.
+pub const CLOSED_DOOR_MOVEMENT_COST: u32 = 2;
+
+/// What makes a trap go off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrapTrigger {
+    /// Fires when something steps on the trap.
+    Step,
+    /// Fires when the given channel is activated (by a lever or plate).
+    Channel(u32),
+}
+
+/// An interactive feature occupying a single tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// A door that can be opened and closed.
+    Door {
+        /// Whether the door is open
+        open: bool,
+        /// Locked doors cannot be opened by interacting with them
+        locked: bool,
+        /// Channel that toggles the door, if any
+        channel: Option<u32>,
+    },
+    /// A lever that activates a channel each time it is pulled.
+    Lever {
+        /// Channel activated by the lever
+        channel: u32,
+        /// Current lever position
+        on: bool,
+    },
+    /// A plate that activates a channel while something stands on it.
+    PressurePlate {
+        /// Channel activated by the plate
+        channel: u32,
+        /// Whether something is standing on the plate
+        pressed: bool,
+    },
+    /// A trap that fires when its trigger condition is met.
+    Trap {
+        /// What makes the trap fire
+        trigger: TrapTrigger,
+        /// Disarmed traps never fire
+        armed: bool,
+        /// Hidden traps are revealed when they fire
+        hidden: bool,
+        /// Whether the trap disarms itself after firing once
+        single_use: bool,
+    },
+}
+
+impl Feature {
+    /// Creates a closed, unlocked door.
+    pub fn door() -> Self {
+        Feature::Door {
+            open: false,
+            locked: false,
+            channel: None,
+        }
+    }
+
+    /// Creates an armed, hidden, reusable trap.
+    pub fn trap(trigger: TrapTrigger) -> Self {
+        Feature::Trap {
+            trigger,
+            armed: true,
+            hidden: true,
+            single_use: false,
+        }
+    }
+
+    /// Links a door to a channel. Has no effect on other features.
+    pub fn on_channel(mut self, new_channel: u32) -> Self {
+        if let Feature::Door { channel, .. } = &mut self {
+            *channel = Some(new_channel);
+        }
+        self
+    }
+
+    /// Returns true if the feature blocks line of sight.
+    pub fn is_opaque(&self) -> bool {
+        matches!(self, Feature::Door { open: false, .. })
+    }
+
+    /// Returns the cost of moving onto the feature, or `None` if it is impassable.
+    pub fn movement_cost(&self) -> Option<u32> {
+        match self {
+            Feature::Door {
+                open: false,
+                locked: true,
+                ..
+            } => None,
+            Feature::Door { open: false, .. } => Some(CLOSED_DOOR_MOVEMENT_COST),
+            _ => Some(BASE_MOVEMENT_COST),
+        }
+    }
+}
+
+/// Something that happened to a feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureEvent {
+    /// A door opened.
+    DoorOpened(IVec2),
+    /// A door closed.
+    DoorClosed(IVec2),
+    /// Tried to open a locked door.
+    DoorLocked(IVec2),
+    /// A lever was pulled.
+    LeverPulled {
+        /// Position of the lever
+        pos: IVec2,
+        /// New lever position
+        on: bool,
+    },
+    /// A pressure plate was pressed.
+    PlatePressed(IVec2),
+    /// A pressure plate was released.
+    PlateReleased(IVec2),
+    /// A channel was activated.
+    ChannelActivated(u32),
+    /// A trap fired.
+    TrapTriggered(IVec2),
+}
+
+/// Sparse storage of interactive features keyed by position.
+#[derive(Debug, Clone, Default)]
+pub struct FeatureLayer {
+    features: HashMap<IVec2, Feature>,
+}
+
+impl FeatureLayer {
+    /// Creates an empty layer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Places a feature, returning the one it replaced.
+    pub fn place(&mut self, pos: IVec2, feature: Feature) -> Option<Feature> {
+        self.features.insert(pos, feature)
+    }
+
+    /// Removes the feature at a position.
+    pub fn remove(&mut self, pos: IVec2) -> Option<Feature> {
+        self.features.remove(&pos)
+    }
+
+    /// Returns the feature at a position.
+    pub fn get(&self, pos: IVec2) -> Option<&Feature> {
+        self.features.get(&pos)
+    }
+
+    /// Returns a mutable reference to the feature at a position.
+    pub fn get_mut(&mut self, pos: IVec2) -> Option<&mut Feature> {
+        self.features.get_mut(&pos)
+    }
+
+    /// Returns an iterator over all features and their positions.
+    pub fn iter(&self) -> impl Iterator<Item = (IVec2, &Feature)> {
+        self.features.iter().map(|(&p, f)| (p, f))
+    }
+
+    /// Returns the number of features.
+    pub fn len(&self) -> usize {
+        self.features.len()
+    }
+
+    /// Returns true if the layer has no features.
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+
+    /// Returns true if a feature at the position blocks line of sight.
+    pub fn is_opaque(&self, pos: IVec2) -> bool {
+        self.get(pos).is_some_and(Feature::is_opaque)
+    }
+
+    /// Returns the cost of moving onto a position, or `None` if a feature blocks it.
+    pub fn movement_cost(&self, pos: IVec2) -> Option<u32> {
+        self.get(pos)
+            .map_or(Some(BASE_MOVEMENT_COST), Feature::movement_cost)
+    }
+
+    /// Interacts with the feature at a position (opens/closes doors, pulls levers).
+    pub fn interact(&mut self, pos: IVec2) -> Vec<FeatureEvent> {
+        let mut events = Vec::new();
+        match self.features.get_mut(&pos) {
+            Some(Feature::Door {
+                open: false,
+                locked: true,
+                ..
+            }) => events.push(FeatureEvent::DoorLocked(pos)),
+            Some(Feature::Door { open, .. }) => {
+                *open = !*open;
+                events.push(door_event(pos, *open));
+            }
+            Some(Feature::Lever { channel, on }) => {
+                *on = !*on;
+                let (channel, on) = (*channel, *on);
+                events.push(FeatureEvent::LeverPulled { pos, on });
+                self.activate_channel(channel, &mut events);
+            }
+            _ => {}
+        }
+        events
+    }
+
+    /// Notifies the layer that something stepped onto a position.
+    pub fn step_on(&mut self, pos: IVec2) -> Vec<FeatureEvent> {
+        let mut events = Vec::new();
+        match self.features.get_mut(&pos) {
+            Some(Feature::PressurePlate { channel, pressed }) if !*pressed => {
+                *pressed = true;
+                let channel = *channel;
+                events.push(FeatureEvent::PlatePressed(pos));
+                self.activate_channel(channel, &mut events);
+            }
+            Some(
+                trap @ Feature::Trap {
+                    trigger: TrapTrigger::Step,
+                    ..
+                },
+            ) => fire_trap(pos, trap, &mut events),
+            _ => {}
+        }
+        events
+    }
+
+    /// Notifies the layer that something left a position.
+    pub fn step_off(&mut self, pos: IVec2) -> Vec<FeatureEvent> {
+        match self.features.get_mut(&pos) {
+            Some(Feature::PressurePlate { pressed, .. }) if *pressed => {
+                *pressed = false;
+                vec![FeatureEvent::PlateReleased(pos)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Activates a channel directly, as if a lever had been pulled.
+    pub fn trigger_channel(&mut self, channel: u32) -> Vec<FeatureEvent> {
+        let mut events = Vec::new();
+        self.activate_channel(channel, &mut events);
+        events
+    }
+
+    fn activate_channel(&mut self, channel: u32, events: &mut Vec<FeatureEvent>) {
+        events.push(FeatureEvent::ChannelActivated(channel));
+
+        // Sort for deterministic event order regardless of hash order.
+        let mut positions: Vec<IVec2> = self.features.keys().copied().collect();
+        positions.sort_by_key(|p| (p.y, p.x));
+
+        for pos in positions {
+            let feature = self.features.get_mut(&pos).unwrap();
+            match feature {
+                Feature::Door {
+                    open,
+                    channel: Some(c),
+                    ..
+                } if *c == channel => {
+                    *open = !*open;
+                    events.push(door_event(pos, *open));
+                }
+                Feature::Trap {
+                    trigger: TrapTrigger::Channel(c),
+                    ..
+                } if *c == channel => fire_trap(pos, feature, events),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn door_event(pos: IVec2, open: bool) -> FeatureEvent {
+    if open {
+        FeatureEvent::DoorOpened(pos)
+    } else {
+        FeatureEvent::DoorClosed(pos)
+    }
+}
+
+fn fire_trap(pos: IVec2, trap: &mut Feature, events: &mut Vec<FeatureEvent>) {
+    if let Feature::Trap {
+        armed,
+        hidden,
+        single_use,
+        ..
+    } = trap
+    {
+        if *armed {
+            *hidden = false;
+            if *single_use {
+                *armed = false;
+            }
+            events.push(FeatureEvent::TrapTriggered(pos));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_door_opacity_and_cost() {
+        let mut layer = FeatureLayer::new();
+        let pos = IVec2::new(1, 1);
+        layer.place(pos, Feature::door());
+        assert!(layer.is_opaque(pos));
+        assert_eq!(layer.movement_cost(pos), Some(CLOSED_DOOR_MOVEMENT_COST));
+        assert_eq!(layer.movement_cost(IVec2::ZERO), Some(BASE_MOVEMENT_COST));
+
+        assert_eq!(layer.interact(pos), vec![FeatureEvent::DoorOpened(pos)]);
+        assert!(!layer.is_opaque(pos));
+        assert_eq!(layer.movement_cost(pos), Some(BASE_MOVEMENT_COST));
+
+        assert_eq!(layer.interact(pos), vec![FeatureEvent::DoorClosed(pos)]);
+    }
+
+    #[test]
+    fn test_locked_door() {
+        let mut layer = FeatureLayer::new();
+        let pos = IVec2::new(2, 0);
+        layer.place(
+            pos,
+            Feature::Door {
+                open: false,
+                locked: true,
+                channel: Some(4),
+            },
+        );
+        assert_eq!(layer.movement_cost(pos), None);
+        assert_eq!(layer.interact(pos), vec![FeatureEvent::DoorLocked(pos)]);
+
+        // A linked lever or plate still works.
+        let events = layer.trigger_channel(4);
+        assert!(events.contains(&FeatureEvent::DoorOpened(pos)));
+        assert_eq!(layer.movement_cost(pos), Some(BASE_MOVEMENT_COST));
+    }
+
+    #[test]
+    fn test_pressure_plate_fires_channel_traps() {
+        let mut layer = FeatureLayer::new();
+        let plate = IVec2::new(0, 0);
+        let trap = IVec2::new(3, 0);
+        layer.place(
+            plate,
+            Feature::PressurePlate {
+                channel: 7,
+                pressed: false,
+            },
+        );
+        layer.place(trap, Feature::trap(TrapTrigger::Channel(7)));
+
+        let events = layer.step_on(plate);
+        assert_eq!(
+            events,
+            vec![
+                FeatureEvent::PlatePressed(plate),
+                FeatureEvent::ChannelActivated(7),
+                FeatureEvent::TrapTriggered(trap),
+            ]
+        );
+        // Standing still doesn't re-trigger.
+        assert!(layer.step_on(plate).is_empty());
+        assert_eq!(
+            layer.step_off(plate),
+            vec![FeatureEvent::PlateReleased(plate)]
+        );
+        assert!(matches!(
+            layer.get(trap),
+            Some(Feature::Trap { hidden: false, .. })
+        ));
+    }
+
+    #[test]
+    fn test_single_use_trap_disarms() {
+        let mut layer = FeatureLayer::new();
+        let pos = IVec2::new(1, 1);
+        layer.place(
+            pos,
+            Feature::Trap {
+                trigger: TrapTrigger::Step,
+                armed: true,
+                hidden: true,
+                single_use: true,
+            },
+        );
+        assert_eq!(layer.step_on(pos).len(), 1);
+        assert!(layer.step_on(pos).is_empty());
+    }
+}
@@ -10,6 +10,22 @@
 //! *   **BSP (Binary Space Partitioning):** Creates structured dungeons with rooms and corridors.
 //! *   **Cellular Automata:** Generates organic, cave-like systems.
 //! *   **Drunkard's Walk:** Produces irregular, winding cave tunnels.
+//! *   **Diffusion-Limited Aggregation:** Grows branching, coral-like caverns one particle at a time.
+//! *   **Noise Caves:** Thresholds fractal noise into wide, connected caverns.
+//! *   **Voronoi Regions:** Partitions a map into nearest-seed zones with adjacency and Lloyd relaxation.
+//! *   **Road Networks:** Joins points of interest with merged, widened A* roads over terrain costs.
+//! *   **Towns:** Street grids, blocks of walled buildings with street-facing doors, and plazas.
+//! *   **Keys and Locks:** Solvable locked rooms and key placement over the room graph, with nesting.
+//! *   **Spawning:** Depth-aware monster and item placement with per-room caps, clear of doorways.
+//! *   **Chunk Streaming:** Generates and caches chunks of an unbounded world on demand.
+//! *   **Background Simulation:** Ticks off-screen chunks at reduced frequency within a per-frame budget.
+//! *   **Generation Constraints:** Playability postconditions with automatic, bounded regeneration.
+//! *   **Generation Pipelines:** Named post-processing stages that run after base generation.
+//! *   **Progress Reporting:** Progress callbacks and cancellation for long cave and DLA generations.
+//! *   **Generation Traces:** Step-by-step snapshots of BSP, cave and drunkard generation for debugging.
+//! *   **Decoration:** Moss, rubble, puddles and stalagmites placed by tile context and noise masks.
+//! *   **Spread Simulation:** Fire consuming flammable tiles, diffusing gas and flowing water, turn by turn.
+//! *   **Interactive Features:** Doors, levers, pressure plates and traps layered over generated maps.
 //!
 //! # Usage
 //!
@@ -29,7 +45,7 @@
 //! fn main() {
 //!     let config = BspConfig::default();
 //!     let mut rng = Rng::new();
-//!     let dungeon = DungeonGenerator::generate(80, 50, &config, &mut rng);
+//!     let dungeon = DungeonGenerator::generate(80, 50, &config, &mut rng).unwrap();
 //!     
 //!     // dungeon.rooms() returns an iterator over the rooms
 //!     for room in dungeon.rooms() {
@@ -53,14 +69,59 @@
 //!     // cave.map is a Vec<bool> where true represents a wall
 //! }
 //! ```
+pub mod ascii;
 pub mod bsp;
 pub mod caves;
+pub mod chunk;
+pub mod constraints;
+pub mod decoration;
+pub mod dla;
 pub mod drunkard;
+pub mod features;
+pub mod locks;
+pub mod noise_cave;
+pub mod pipeline;
+pub mod progress;
+pub mod roads;
+pub mod simulation;
+pub mod spawn;
+pub mod spread;
+pub mod town;
+pub mod trace;
+pub mod voronoi;
 
 pub mod prelude {
     pub use runeforge_random::prelude::Rng;
 
-    pub use crate::bsp::{BspConfig, BspNode, Corridor, Dungeon, DungeonGenerator, SplitDirection};
-    pub use crate::caves::{CaveConfig, CaveGenerator, CaveMap};
-    pub use crate::drunkard::{DrunkardConfig, DrunkardGenerator, DrunkardMap, StartPosition};
+    pub use crate::ascii::{AsciiDisplay, AsciiLegend, AsciiMapError, AsciiOptions};
+    pub use crate::bsp::{
+        BspConfig, BspError, BspNode, Corridor, Dungeon, DungeonGenerator, RoomId, RoomTag,
+        SplitDirection,
+    };
+    pub use crate::caves::{CaRule, CaveConfig, CaveError, CaveGenerator, CaveMap, Neighborhood};
+    pub use crate::chunk::{chunk_seed, Chunk, ChunkConfig, ChunkManager};
+    pub use crate::constraints::{
+        ConstrainedMap, ConstraintError, ConstraintViolation, GenerationConstraints,
+    };
+    pub use crate::decoration::{
+        Decoration, DecorationLayer, DecorationRule, Decorator, Placement,
+    };
+    pub use crate::dla::{DlaConfig, DlaError, DlaGenerator, DlaMode};
+    pub use crate::drunkard::{
+        DrunkardConfig, DrunkardError, DrunkardGenerator, DrunkardMap, StartPosition,
+    };
+    pub use crate::features::{Feature, FeatureEvent, FeatureLayer, TrapTrigger};
+    pub use crate::locks::{Lock, LockConfig, LockError, LockGenerator, LockPlan};
+    pub use crate::noise_cave::{NoiseCaveConfig, NoiseCaveError, NoiseCaveGenerator};
+    pub use crate::pipeline::GenerationPipeline;
+    pub use crate::progress::{CancelToken, Progress, ProgressCallback, ProgressSink};
+    pub use crate::roads::{RoadConfig, RoadError, RoadGenerator, RoadNetwork, RoadSegment};
+    pub use crate::simulation::{
+        BackgroundSimulation, SimulationConfig, SimulationStep, SimulationTier,
+    };
+    pub use crate::spawn::{Spawn, SpawnEntry, SpawnError, Spawner};
+    pub use crate::spread::{SpreadConfig, SpreadEvent, SpreadSimulation, SpreadTerrain};
+    pub use crate::town::{Building, TownConfig, TownError, TownGenerator, TownMap, TownTile};
+    pub use crate::trace::{GenerationTrace, TraceFrame, TraceTile};
+    pub use crate::voronoi::{VoronoiError, VoronoiMap};
 }
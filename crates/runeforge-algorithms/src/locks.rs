@@ -0,0 +1,472 @@
+//! Key-and-lock progression over the room graph.
+//!
+//! A [`LockGenerator`] locks rooms and hides each lock's key elsewhere so the
+//! level is always solvable: every key can be reached from the start using only
+//! the keys found before it. A lock seals every way into its room, so it works
+//! on graphs with loops, and rooms that can only be reached through a locked
+//! room are behind its lock too.
+//!
+//! Locks are numbered in the order the player can open them. With nesting,
+//! the key to lock `n` sits behind lock `n - 1`, forming chains up to
+//! [`LockConfig::max_nesting`] locks long; when a chain is full, the next key
+//! goes back in the area reachable without any keys.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_algorithms::prelude::*;
+//!
+//! let mut rng = Rng::with_seed(8);
+//! let dungeon = DungeonGenerator::generate(80, 50, &BspConfig::default(), &mut rng).unwrap();
+//!
+//! let config = LockConfig::new().with_locks(3).with_max_nesting(2);
+//! let plan = LockGenerator::generate_dungeon(&dungeon, &config, &mut rng).unwrap();
+//!
+//! assert!(plan.solve(dungeon.rooms().len(), &dungeon.room_connections()).is_some());
+//! for lock in plan.locks() {
+//!     println!("room {:?} is locked, its key is in room {:?}", lock.room, lock.key_room);
+//! }
+//! ```
+
+use std::collections::VecDeque;
+
+use runeforge_geometry::prelude::IVec2;
+use runeforge_random::prelude::*;
+use thiserror::Error;
+
+use crate::bsp::{Dungeon, RoomId, RoomTag};
+
+/// Errors that can occur while placing locks.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum LockError {
+    /// A configuration value is unusable.
+    #[error("Invalid lock config field '{field}': {reason}")]
+    InvalidConfig {
+        /// Name of the offending field
+        field: &'static str,
+        /// Why the value is rejected
+        reason: String,
+    },
+    /// The start room is not in the graph.
+    #[error("Start room {start:?} is not one of the {rooms} rooms")]
+    InvalidStart {
+        /// The requested start room
+        start: RoomId,
+        /// Number of rooms in the graph
+        rooms: usize,
+    },
+}
+
+impl LockError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            LockError::InvalidConfig { .. } => "locks.invalid_config",
+            LockError::InvalidStart { .. } => "locks.invalid_start",
+        }
+    }
+}
+
+/// Configuration for lock placement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockConfig {
+    /// How many locks to place. Fewer are placed if the graph has no room
+    /// for more.
+    pub locks: u32,
+    /// Longest chain of keys hidden behind earlier locks. 1 means every key
+    /// is reachable without other keys.
+    pub max_nesting: u32,
+}
+
+impl Default for LockConfig {
+    fn default() -> Self {
+        Self {
+            locks: 2,
+            max_nesting: 2,
+        }
+    }
+}
+
+impl LockConfig {
+    /// Creates a new configuration with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how many locks to place.
+    pub fn with_locks(mut self, locks: u32) -> Self {
+        self.locks = locks;
+        self
+    }
+
+    /// Sets the longest chain of nested keys.
+    pub fn with_max_nesting(mut self, max_nesting: u32) -> Self {
+        self.max_nesting = max_nesting;
+        self
+    }
+
+    /// Checks that the configuration is usable.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LockError::InvalidConfig` if `max_nesting` is 0.
+    pub fn validate(&self) -> Result<(), LockError> {
+        if self.max_nesting == 0 {
+            return Err(LockError::InvalidConfig {
+                field: "max_nesting",
+                reason: "must be at least 1".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// A locked room and where its key is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lock {
+    /// The locked room. Every way into it needs the key.
+    pub room: RoomId,
+    /// The room holding the key.
+    pub key_room: RoomId,
+    /// Position in its chain: 1 if the key needs no other keys, 2 if it is
+    /// behind the previous lock, and so on.
+    pub depth: u32,
+    /// Door tiles sealing the room. Only filled in by
+    /// [`LockGenerator::generate_dungeon`].
+    pub doors: Vec<IVec2>,
+    /// Tile to put the key on. Only filled in by
+    /// [`LockGenerator::generate_dungeon`].
+    pub key: Option<IVec2>,
+}
+
+/// The locks placed on a level, in the order they can be opened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockPlan {
+    start: RoomId,
+    locks: Vec<Lock>,
+}
+
+impl LockPlan {
+    /// Returns the room the player starts in.
+    pub fn start(&self) -> RoomId {
+        self.start
+    }
+
+    /// Returns the locks in the order they can be opened.
+    pub fn locks(&self) -> &[Lock] {
+        &self.locks
+    }
+
+    /// Returns the index of the lock on a room, if it is locked.
+    pub fn lock_on(&self, room: RoomId) -> Option<usize> {
+        self.locks.iter().position(|lock| lock.room == room)
+    }
+
+    /// Returns the indices of the locks whose keys are in a room.
+    pub fn keys_in(&self, room: RoomId) -> Vec<usize> {
+        (0..self.locks.len())
+            .filter(|&i| self.locks[i].key_room == room)
+            .collect()
+    }
+
+    /// Plays the level through, picking up every reachable key and opening
+    /// what it can until nothing changes.
+    ///
+    /// Returns the order in which locks were opened, or `None` if some locked
+    /// room can never be entered.
+    pub fn solve(&self, rooms: usize, connections: &[(RoomId, RoomId)]) -> Option<Vec<usize>> {
+        let graph = adjacency(rooms, connections);
+        let mut held = vec![false; self.locks.len()];
+        let mut order = Vec::new();
+        loop {
+            let closed: Vec<usize> = (0..self.locks.len())
+                .filter(|&i| !held[i])
+                .map(|i| self.locks[i].room.0)
+                .collect();
+            let reached = reachable(&graph, self.start.0, &closed);
+            let found: Vec<usize> = (0..self.locks.len())
+                .filter(|&i| !held[i] && reached[self.locks[i].key_room.0])
+                .collect();
+            if found.is_empty() {
+                break;
+            }
+            for i in found {
+                held[i] = true;
+                order.push(i);
+            }
+        }
+        (order.len() == self.locks.len()).then_some(order)
+    }
+}
+
+/// Places locks and keys on a room graph.
+pub struct LockGenerator;
+
+impl LockGenerator {
+    /// Places locks on a graph of `rooms` rooms joined by `connections`,
+    /// starting from `start`.
+    ///
+    /// The start room is never locked. Rooms nearest the start are tried
+    /// first, in random order among equals, and a room is only locked if every
+    /// earlier lock still guards something and every earlier key stays
+    /// reachable in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error from [`LockConfig::validate`], or
+    /// `LockError::InvalidStart` if `start` is out of range.
+    pub fn generate(
+        rooms: usize,
+        connections: &[(RoomId, RoomId)],
+        start: RoomId,
+        config: &LockConfig,
+        rng: &mut Rng,
+    ) -> Result<LockPlan, LockError> {
+        config.validate()?;
+        if start.0 >= rooms {
+            return Err(LockError::InvalidStart { start, rooms });
+        }
+        let graph = adjacency(rooms, connections);
+        let mut plan = LockPlan {
+            start,
+            locks: Vec::new(),
+        };
+
+        for _ in 0..config.locks {
+            let Some(lock) = Self::next_lock(&graph, &plan, config, rng) else {
+                break;
+            };
+            plan.locks.push(lock);
+        }
+        Ok(plan)
+    }
+
+    /// Places locks on a dungeon's rooms, then picks door and key tiles.
+    ///
+    /// The start is the first room tagged [`RoomTag::Entrance`], or room 0.
+    /// Doors are the room's [entrances](Dungeon::room_entrances), and keys go on
+    /// free floor in their room.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`generate`](Self::generate); a dungeon without rooms is an
+    /// invalid start.
+    pub fn generate_dungeon(
+        dungeon: &Dungeon,
+        config: &LockConfig,
+        rng: &mut Rng,
+    ) -> Result<LockPlan, LockError> {
+        let start = dungeon
+            .rooms_with_tag(&RoomTag::Entrance)
+            .first()
+            .copied()
+            .unwrap_or(RoomId(0));
+        let connections = dungeon.room_connections();
+        let mut plan = Self::generate(dungeon.rooms().len(), &connections, start, config, rng)?;
+
+        let mut used = Vec::new();
+        for lock in &mut plan.locks {
+            let room = dungeon.rooms()[lock.room.0];
+            lock.doors = dungeon.room_entrances(room);
+            let key_room = dungeon.rooms()[lock.key_room.0];
+            let free: Vec<IVec2> = key_room
+                .points()
+                .filter(|&p| {
+                    dungeon.is_walkable(p)
+                        && dungeon.features().get(p).is_none()
+                        && dungeon.room_at(p) == Some(lock.key_room)
+                        && !used.contains(&p)
+                })
+                .collect();
+            lock.key = rng.choose(&free).copied();
+            used.extend(lock.key);
+        }
+        Ok(plan)
+    }
+
+    /// Finds a room to lock next and a place for its key.
+    fn next_lock(
+        graph: &[Vec<usize>],
+        plan: &LockPlan,
+        config: &LockConfig,
+        rng: &mut Rng,
+    ) -> Option<Lock> {
+        let start = plan.start.0;
+        let locked: Vec<usize> = plan.locks.iter().map(|l| l.room.0).collect();
+        // Everything reachable once the existing locks are open.
+        let distance = distances(graph, start, &[]);
+        let mut candidates: Vec<usize> = (0..graph.len())
+            .filter(|&r| distance[r].is_some() && r != start && !locked.contains(&r))
+            .collect();
+        // Nearest first, so later locks go deeper and chains can grow.
+        rng.shuffle(&mut candidates);
+        candidates.sort_by_key(|&r| distance[r]);
+
+        // Rooms reachable holding the keys to locks before `k`, with `room`
+        // locked as well.
+        let region = |k: usize, room: usize| {
+            let mut closed = locked[k..].to_vec();
+            closed.push(room);
+            reachable(graph, start, &closed)
+        };
+
+        for room in candidates {
+            let keeps_order = plan.locks.iter().enumerate().all(|(k, lock)| {
+                region(k, room)[lock.key_room.0] && region(k + 1, room)[lock.room.0]
+            });
+            if !keeps_order {
+                continue;
+            }
+
+            let m = plan.locks.len();
+            let current = region(m, room);
+            let previous = plan.locks.last();
+            let nested = previous.filter(|lock| lock.depth < config.max_nesting);
+            let behind_previous: Vec<usize> = match nested {
+                Some(_) => {
+                    let before = region(m - 1, room);
+                    (0..graph.len())
+                        .filter(|&r| current[r] && !before[r])
+                        .collect()
+                }
+                None => Vec::new(),
+            };
+
+            let (keys, depth) = match nested {
+                Some(lock) if !behind_previous.is_empty() => (behind_previous, lock.depth + 1),
+                _ => {
+                    let hub = region(0, room);
+                    ((0..graph.len()).filter(|&r| hub[r]).collect(), 1)
+                }
+            };
+            let key_room = *rng.choose(&keys)?;
+            return Some(Lock {
+                room: RoomId(room),
+                key_room: RoomId(key_room),
+                depth,
+                doors: Vec::new(),
+                key: None,
+            });
+        }
+        None
+    }
+}
+
+fn adjacency(rooms: usize, connections: &[(RoomId, RoomId)]) -> Vec<Vec<usize>> {
+    let mut graph = vec![Vec::new(); rooms];
+    for &(a, b) in connections {
+        if a.0 < rooms && b.0 < rooms && a != b {
+            graph[a.0].push(b.0);
+            graph[b.0].push(a.0);
+        }
+    }
+    graph
+}
+
+/// Breadth-first search from `start` that never enters a `closed` room.
+/// Returns each room's distance in steps, or `None` if it can't be reached.
+fn distances(graph: &[Vec<usize>], start: usize, closed: &[usize]) -> Vec<Option<u32>> {
+    let mut distance = vec![None; graph.len()];
+    distance[start] = Some(0);
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((room, steps)) = queue.pop_front() {
+        for &next in &graph[room] {
+            if distance[next].is_none() && !closed.contains(&next) {
+                distance[next] = Some(steps + 1);
+                queue.push_back((next, steps + 1));
+            }
+        }
+    }
+    distance
+}
+
+fn reachable(graph: &[Vec<usize>], start: usize, closed: &[usize]) -> Vec<bool> {
+    distances(graph, start, closed)
+        .into_iter()
+        .map(|d| d.is_some())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bsp::{BspConfig, DungeonGenerator};
+
+    fn edges(pairs: &[(usize, usize)]) -> Vec<(RoomId, RoomId)> {
+        pairs.iter().map(|&(a, b)| (RoomId(a), RoomId(b))).collect()
+    }
+
+    #[test]
+    fn test_chain_nests_keys() {
+        // 0 - 1 - 2 - 3 - 4
+        let path = edges(&[(0, 1), (1, 2), (2, 3), (3, 4)]);
+        let config = LockConfig::new().with_locks(4).with_max_nesting(4);
+        let plan =
+            LockGenerator::generate(5, &path, RoomId(0), &config, &mut Rng::with_seed(1)).unwrap();
+
+        assert_eq!(plan.locks().len(), 4);
+        assert_eq!(plan.solve(5, &path), Some(vec![0, 1, 2, 3]));
+        for (i, lock) in plan.locks().iter().enumerate() {
+            assert_ne!(lock.room, RoomId(0));
+            assert_eq!(lock.depth, i as u32 + 1);
+        }
+        assert_eq!(plan.lock_on(plan.locks()[2].room), Some(2));
+    }
+
+    #[test]
+    fn test_loops_and_nesting_limit() {
+        // Two loops joined at room 0, with a spur off each.
+        let graph = edges(&[
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (0, 3),
+            (3, 4),
+            (4, 0),
+            (2, 5),
+            (4, 6),
+        ]);
+        for seed in 0..20 {
+            let config = LockConfig::new().with_locks(3).with_max_nesting(1);
+            let plan =
+                LockGenerator::generate(7, &graph, RoomId(0), &config, &mut Rng::with_seed(seed))
+                    .unwrap();
+            assert!(!plan.locks().is_empty());
+            assert!(plan.solve(7, &graph).is_some(), "seed {} unsolvable", seed);
+            for lock in plan.locks() {
+                assert_eq!(lock.depth, 1);
+                assert_ne!(lock.room, lock.key_room);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dungeon_locks_are_solvable() {
+        for seed in 0..10 {
+            let mut rng = Rng::with_seed(seed);
+            let dungeon =
+                DungeonGenerator::generate(80, 50, &BspConfig::default(), &mut rng).unwrap();
+            let config = LockConfig::new().with_locks(3);
+            let plan = LockGenerator::generate_dungeon(&dungeon, &config, &mut rng).unwrap();
+            let connections = dungeon.room_connections();
+            assert!(plan.solve(dungeon.rooms().len(), &connections).is_some());
+            for lock in plan.locks() {
+                let key = lock.key.unwrap();
+                assert_eq!(dungeon.room_at(key), Some(lock.key_room));
+                assert!(lock.depth <= 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let config = LockConfig::new().with_max_nesting(0);
+        assert_eq!(
+            config.validate().unwrap_err().code(),
+            "locks.invalid_config"
+        );
+        let err = LockGenerator::generate(2, &[], RoomId(5), &LockConfig::new(), &mut Rng::new())
+            .unwrap_err();
+        assert_eq!(err.code(), "locks.invalid_start");
+    }
+}
@@ -0,0 +1,344 @@
+//! Noise-based cave generation.
+//!
+//! This module carves caves by thresholding fractal (fBm) Perlin noise from
+//! `runeforge-noise`. The algorithm works by:
+//! 1. Sampling a noise map seeded from the RNG
+//! 2. Turning every tile above the threshold into floor, inside a solid border
+//! 3. Filling pockets smaller than the minimum region size
+//! 4. Joining the remaining regions with the shortest possible tunnels
+//!
+//! The result is a [`CaveMap`], so it can be used anywhere a cellular automata
+//! cave can. Noise caves have larger, smoother features than cellular automata
+//! and scale to big maps without iterating.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_algorithms::noise_cave::{NoiseCaveConfig, NoiseCaveGenerator};
+//! use runeforge_random::prelude::Rng;
+//!
+//! let config = NoiseCaveConfig::default();
+//! let mut rng = Rng::with_seed(7);
+//! let cave = NoiseCaveGenerator::generate(80, 50, &config, &mut rng).unwrap();
+//!
+//! assert_eq!(cave.width(), 80);
+//! assert!(cave.is_wall(0, 0)); // the border is solid
+//! ```
+
+use runeforge_noise::{NoiseConfig, NoiseError, NoiseMap};
+use runeforge_random::prelude::*;
+use thiserror::Error;
+
+use crate::caves::CaveMap;
+use crate::trace::GenerationTrace;
+
+/// Errors that can occur during noise cave generation.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum NoiseCaveError {
+    /// The map is too small to hold anything inside its border.
+    #[error("Invalid noise cave dimensions: {width}x{height} with a border of {border}")]
+    InvalidDimensions {
+        /// Map width
+        width: u32,
+        /// Map height
+        height: u32,
+        /// Border thickness
+        border: u32,
+    },
+    /// A configuration value would produce a degenerate cave.
+    #[error("Invalid noise cave config field '{field}': {reason}")]
+    InvalidConfig {
+        /// Name of the offending field
+        field: &'static str,
+        /// Why the value is rejected
+        reason: String,
+    },
+    /// The noise settings are invalid.
+    #[error(transparent)]
+    Noise(#[from] NoiseError),
+}
+
+impl NoiseCaveError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            NoiseCaveError::InvalidDimensions { .. } => "noise_cave.invalid_dimensions",
+            NoiseCaveError::InvalidConfig { .. } => "noise_cave.invalid_config",
+            NoiseCaveError::Noise(e) => e.code(),
+        }
+    }
+}
+
+/// Configuration for noise cave generation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoiseCaveConfig {
+    /// Noise settings. Larger scales give wider caverns.
+    pub noise: NoiseConfig,
+    /// Tiles whose noise value is above this (-1.0 to 1.0) become floor.
+    /// Lower values give more open caves.
+    pub threshold: f64,
+    /// Thickness of the solid wall around the map edge.
+    pub border: u32,
+    /// Floor regions with fewer tiles than this are filled in.
+    pub min_region_size: usize,
+    /// Join separate regions with tunnels. When false, every region except
+    /// the largest is filled instead.
+    pub connect_regions: bool,
+}
+
+impl Default for NoiseCaveConfig {
+    fn default() -> Self {
+        Self {
+            noise: NoiseConfig::new().with_scale(12.0).with_octaves(4),
+            threshold: 0.0,
+            border: 1,
+            min_region_size: 8,
+            connect_regions: true,
+        }
+    }
+}
+
+impl NoiseCaveConfig {
+    /// Creates a new configuration with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the noise settings.
+    pub fn with_noise(mut self, noise: NoiseConfig) -> Self {
+        self.noise = noise;
+        self
+    }
+
+    /// Sets the floor threshold (clamped to -1.0-1.0).
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold.clamp(-1.0, 1.0);
+        self
+    }
+
+    /// Sets the border thickness.
+    pub fn with_border(mut self, border: u32) -> Self {
+        self.border = border;
+        self
+    }
+
+    /// Sets the smallest region that is kept.
+    pub fn with_min_region_size(mut self, size: usize) -> Self {
+        self.min_region_size = size;
+        self
+    }
+
+    /// Sets whether regions are joined with tunnels or culled.
+    pub fn with_connect_regions(mut self, connect: bool) -> Self {
+        self.connect_regions = connect;
+        self
+    }
+
+    /// Checks that the configuration produces a cave.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoiseCaveError::InvalidConfig` naming the first offending
+    /// field, or `NoiseCaveError::Noise` if the noise settings are invalid.
+    pub fn validate(&self) -> Result<(), NoiseCaveError> {
+        self.noise.validate()?;
+        if !(self.threshold > -1.0 && self.threshold < 1.0) {
+            return Err(NoiseCaveError::InvalidConfig {
+                field: "threshold",
+                reason: format!(
+                    "{} is outside -1.0-1.0 (exclusive), leaving no floor or no wall",
+                    self.threshold
+                ),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Generator for noise-based caves.
+pub struct NoiseCaveGenerator;
+
+impl NoiseCaveGenerator {
+    /// Generates a connected cave by thresholding fBm noise.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoiseCaveError::InvalidDimensions` if the map has no room
+    /// inside its border, or an error from [`NoiseCaveConfig::validate`].
+    pub fn generate(
+        width: u32,
+        height: u32,
+        config: &NoiseCaveConfig,
+        rng: &mut Rng,
+    ) -> Result<CaveMap, NoiseCaveError> {
+        Self::generate_inner(width, height, config, rng, None)
+    }
+
+    /// Generates a cave, recording the thresholded noise, the culled map and
+    /// every tunnel.
+    ///
+    /// Produces the same map as [`generate`](Self::generate) for the same seed.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`generate`](Self::generate).
+    pub fn generate_traced(
+        width: u32,
+        height: u32,
+        config: &NoiseCaveConfig,
+        rng: &mut Rng,
+        trace: &mut GenerationTrace,
+    ) -> Result<CaveMap, NoiseCaveError> {
+        Self::generate_inner(width, height, config, rng, Some(trace))
+    }
+
+    fn generate_inner(
+        width: u32,
+        height: u32,
+        config: &NoiseCaveConfig,
+        rng: &mut Rng,
+        mut trace: Option<&mut GenerationTrace>,
+    ) -> Result<CaveMap, NoiseCaveError> {
+        let border = config.border;
+        if width <= border.saturating_mul(2) || height <= border.saturating_mul(2) {
+            return Err(NoiseCaveError::InvalidDimensions {
+                width,
+                height,
+                border,
+            });
+        }
+        config.validate()?;
+
+        let seed = rng.range(0, i32::MAX) as u32;
+        let noise = NoiseMap::with_config(width as usize, height as usize, seed, &config.noise)?;
+
+        let mut cave = CaveMap::new(width, height);
+        for y in border..height - border {
+            for x in border..width - border {
+                cave.set_tile(
+                    x,
+                    y,
+                    noise.threshold(x as usize, y as usize, config.threshold),
+                );
+            }
+        }
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.record(
+                "threshold",
+                width,
+                height,
+                cave.tiles(),
+                Vec::new(),
+                Vec::new(),
+            );
+        }
+
+        let regions = cave.regions();
+        let keep = if config.connect_regions {
+            regions.len()
+        } else {
+            1
+        };
+        for (i, region) in regions.iter().enumerate() {
+            if i >= keep || region.len() < config.min_region_size {
+                for p in region {
+                    cave.set_tile(p.x as u32, p.y as u32, false);
+                }
+            }
+        }
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.record(
+                "cull regions",
+                width,
+                height,
+                cave.tiles(),
+                Vec::new(),
+                Vec::new(),
+            );
+        }
+
+        if config.connect_regions {
+            let tunnels = cave.connect_regions(border);
+            if let Some(trace) = trace {
+                let carved = tunnels.into_iter().flatten().collect();
+                trace.record(
+                    "connect regions",
+                    width,
+                    height,
+                    cave.tiles(),
+                    carved,
+                    Vec::new(),
+                );
+            }
+        }
+
+        Ok(cave)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generation_is_connected_and_deterministic() {
+        let config = NoiseCaveConfig::default();
+        let a = NoiseCaveGenerator::generate(60, 40, &config, &mut Rng::with_seed(3)).unwrap();
+        let b = NoiseCaveGenerator::generate(60, 40, &config, &mut Rng::with_seed(3)).unwrap();
+        assert_eq!(a.tiles(), b.tiles());
+        assert_eq!(a.regions().len(), 1);
+        assert!(a.floor_percentage() > 0.2);
+    }
+
+    #[test]
+    fn test_border_is_solid() {
+        let config = NoiseCaveConfig::default()
+            .with_border(2)
+            .with_threshold(-0.9);
+        let cave = NoiseCaveGenerator::generate(20, 20, &config, &mut Rng::with_seed(1)).unwrap();
+        for i in 0..20 {
+            for b in [0, 1, 18, 19] {
+                assert!(cave.is_wall(i, b) && cave.is_wall(b, i));
+            }
+        }
+        assert!(cave.is_floor(10, 10));
+    }
+
+    #[test]
+    fn test_cull_keeps_largest_region() {
+        let config = NoiseCaveConfig::default().with_connect_regions(false);
+        let mut trace = GenerationTrace::new();
+        let cave = NoiseCaveGenerator::generate_traced(
+            60,
+            40,
+            &config,
+            &mut Rng::with_seed(9),
+            &mut trace,
+        )
+        .unwrap();
+        assert_eq!(cave.regions().len(), 1);
+        assert_eq!(trace.len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let config = NoiseCaveConfig::default();
+        let err = NoiseCaveGenerator::generate(2, 10, &config, &mut Rng::with_seed(1)).unwrap_err();
+        assert_eq!(err.code(), "noise_cave.invalid_dimensions");
+
+        let config = NoiseCaveConfig {
+            threshold: 1.0,
+            ..NoiseCaveConfig::default()
+        };
+        assert_eq!(
+            config.validate().unwrap_err().code(),
+            "noise_cave.invalid_config"
+        );
+
+        let config = NoiseCaveConfig::default().with_noise(NoiseConfig::new().with_octaves(0));
+        assert_eq!(
+            config.validate().unwrap_err().code(),
+            "noise.invalid_config"
+        );
+    }
+}
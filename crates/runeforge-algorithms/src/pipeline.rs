@@ -0,0 +1,279 @@
+//! Named post-processing stages for generated maps.
+//!
+//! A [`GenerationPipeline`] holds an ordered list of stages that run after a
+//! generator has produced the base map: adding water pools, scattering rubble,
+//! stamping vaults, tagging rooms, and so on. Stages are named so they can be
+//! inserted relative to each other, replaced or removed without rebuilding the
+//! whole pipeline.
+//!
+//! The pipeline works with any map type; it defaults to [`Dungeon`].
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_algorithms::prelude::*;
+//!
+//! let mut pipeline = GenerationPipeline::new()
+//!     .stage("entrance", |d: &mut Dungeon, _: &mut Rng| {
+//!         d.tag_room(RoomId(0), RoomTag::Entrance);
+//!     })
+//!     .stage("treasure", |d: &mut Dungeon, rng: &mut Rng| {
+//!         let room = RoomId(rng.range(1, d.rooms().len() as i32 - 1) as usize);
+//!         d.tag_room(room, RoomTag::Treasure);
+//!     });
+//! pipeline.insert_before("treasure", "boss", |d: &mut Dungeon, _: &mut Rng| {
+//!     let last = RoomId(d.rooms().len() - 1);
+//!     d.tag_room(last, RoomTag::Boss);
+//! });
+//! assert_eq!(pipeline.stage_names(), vec!["entrance", "boss", "treasure"]);
+//!
+//! let mut rng = Rng::with_seed(9);
+//! let mut dungeon = DungeonGenerator::generate(80, 50, &BspConfig::default(), &mut rng).unwrap();
+//! pipeline.run(&mut dungeon, &mut rng);
+//! assert_eq!(dungeon.rooms_with_tag(&RoomTag::Entrance), vec![RoomId(0)]);
+//! ```
+
+use std::fmt;
+
+use runeforge_random::prelude::Rng;
+
+use crate::bsp::Dungeon;
+
+type StageFn<M> = Box<dyn FnMut(&mut M, &mut Rng)>;
+
+struct Stage<M> {
+    name: String,
+    enabled: bool,
+    run: StageFn<M>,
+}
+
+/// An ordered list of named map post-processing stages.
+pub struct GenerationPipeline<M = Dungeon> {
+    stages: Vec<Stage<M>>,
+}
+
+impl<M> Default for GenerationPipeline<M> {
+    fn default() -> Self {
+        Self { stages: Vec::new() }
+    }
+}
+
+impl<M> fmt::Debug for GenerationPipeline<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GenerationPipeline")
+            .field("stages", &self.stage_names())
+            .finish()
+    }
+}
+
+impl<M> GenerationPipeline<M> {
+    /// Creates an empty pipeline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a stage (builder form of [`push`](Self::push)).
+    pub fn stage(
+        mut self,
+        name: impl Into<String>,
+        run: impl FnMut(&mut M, &mut Rng) + 'static,
+    ) -> Self {
+        self.push(name, run);
+        self
+    }
+
+    /// Appends a stage. A stage with the same name is replaced in place.
+    pub fn push(&mut self, name: impl Into<String>, run: impl FnMut(&mut M, &mut Rng) + 'static) {
+        let stage = Stage {
+            name: name.into(),
+            enabled: true,
+            run: Box::new(run),
+        };
+        match self.position(&stage.name) {
+            Some(idx) => self.stages[idx] = stage,
+            None => self.stages.push(stage),
+        }
+    }
+
+    /// Inserts a stage before the stage named `before`.
+    ///
+    /// Returns false (and does nothing) if `before` doesn't exist.
+    pub fn insert_before(
+        &mut self,
+        before: &str,
+        name: impl Into<String>,
+        run: impl FnMut(&mut M, &mut Rng) + 'static,
+    ) -> bool {
+        self.insert_at_offset(before, 0, name.into(), Box::new(run))
+    }
+
+    /// Inserts a stage after the stage named `after`.
+    ///
+    /// Returns false (and does nothing) if `after` doesn't exist.
+    pub fn insert_after(
+        &mut self,
+        after: &str,
+        name: impl Into<String>,
+        run: impl FnMut(&mut M, &mut Rng) + 'static,
+    ) -> bool {
+        self.insert_at_offset(after, 1, name.into(), Box::new(run))
+    }
+
+    /// Removes a stage. Returns true if it existed.
+    pub fn remove(&mut self, name: &str) -> bool {
+        match self.position(name) {
+            Some(idx) => {
+                self.stages.remove(idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Enables or disables a stage without removing it.
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        match self.position(name) {
+            Some(idx) => {
+                self.stages[idx].enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns true if a stage with the given name exists.
+    pub fn contains(&self, name: &str) -> bool {
+        self.position(name).is_some()
+    }
+
+    /// Returns the stage names in execution order.
+    pub fn stage_names(&self) -> Vec<&str> {
+        self.stages.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    /// Returns the number of stages.
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// Returns true if the pipeline has no stages.
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Runs every enabled stage in order.
+    pub fn run(&mut self, map: &mut M, rng: &mut Rng) {
+        for stage in self.stages.iter_mut().filter(|s| s.enabled) {
+            (stage.run)(map, rng);
+        }
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.stages.iter().position(|s| s.name == name)
+    }
+
+    fn insert_at_offset(
+        &mut self,
+        anchor: &str,
+        offset: usize,
+        name: String,
+        run: StageFn<M>,
+    ) -> bool {
+        let Some(mut idx) = self.position(anchor) else {
+            return false;
+        };
+        if name != anchor {
+            if let Some(old) = self.position(&name) {
+                self.stages.remove(old);
+                if old < idx {
+                    idx -= 1;
+                }
+            }
+        }
+        self.stages.insert(
+            idx + offset,
+            Stage {
+                name,
+                enabled: true,
+                run,
+            },
+        );
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeded_rng(seed: u64) -> Rng {
+        Rng::with_seed(seed)
+    }
+
+    fn log_stage(tag: &'static str) -> impl FnMut(&mut Vec<&'static str>, &mut Rng) {
+        move |log, _| log.push(tag)
+    }
+
+    #[test]
+    fn test_stages_run_in_order() {
+        let mut pipeline = GenerationPipeline::new()
+            .stage("a", log_stage("a"))
+            .stage("c", log_stage("c"));
+        assert!(pipeline.insert_after("a", "b", log_stage("b")));
+        assert!(pipeline.insert_before("a", "start", log_stage("start")));
+        assert!(!pipeline.insert_before("missing", "x", log_stage("x")));
+
+        let mut log = Vec::new();
+        pipeline.run(&mut log, &mut seeded_rng(1));
+        assert_eq!(log, vec!["start", "a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_push_replaces_existing_stage() {
+        let mut pipeline = GenerationPipeline::new()
+            .stage("a", log_stage("a"))
+            .stage("b", log_stage("b"));
+        pipeline.push("a", log_stage("A"));
+        assert_eq!(pipeline.len(), 2);
+
+        let mut log = Vec::new();
+        pipeline.run(&mut log, &mut seeded_rng(1));
+        assert_eq!(log, vec!["A", "b"]);
+    }
+
+    #[test]
+    fn test_remove_and_disable() {
+        let mut pipeline = GenerationPipeline::new()
+            .stage("a", log_stage("a"))
+            .stage("b", log_stage("b"))
+            .stage("c", log_stage("c"));
+        assert!(pipeline.remove("b"));
+        assert!(!pipeline.remove("b"));
+        assert!(pipeline.set_enabled("c", false));
+
+        let mut log = Vec::new();
+        pipeline.run(&mut log, &mut seeded_rng(1));
+        assert_eq!(log, vec!["a"]);
+        assert!(pipeline.contains("c"));
+    }
+
+    #[test]
+    fn test_moving_stage_with_insert() {
+        let mut pipeline = GenerationPipeline::new()
+            .stage("a", log_stage("a"))
+            .stage("b", log_stage("b"));
+        pipeline.insert_before("a", "b", log_stage("b"));
+        assert_eq!(pipeline.stage_names(), vec!["b", "a"]);
+        pipeline.insert_after("a", "b", log_stage("b"));
+        assert_eq!(pipeline.stage_names(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_insert_with_missing_anchor_keeps_stage() {
+        let mut pipeline = GenerationPipeline::new()
+            .stage("a", log_stage("a"))
+            .stage("b", log_stage("b"));
+        assert!(!pipeline.insert_after("missing", "a", log_stage("a2")));
+        assert_eq!(pipeline.stage_names(), vec!["a", "b"]);
+    }
+}
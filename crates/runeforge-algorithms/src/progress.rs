@@ -0,0 +1,177 @@
+//! Progress reporting and cancellation for long generations.
+//!
+//! Generating a large map can take long enough to freeze a UI. The slower
+//! generators have a `generate_with_progress` variant that takes a
+//! [`ProgressSink`]: it is told how far generation has got, and polled to see
+//! whether it should stop early. A cancelled generation returns the
+//! generator's `Cancelled` error.
+//!
+//! Any `FnMut(Progress)` closure is a sink, a [`CancelToken`] is a sink that
+//! only cancels, and [`CancelToken::with_callback`] combines the two.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_algorithms::caves::{CaveConfig, CaveError, CaveGenerator};
+//! use runeforge_algorithms::progress::{CancelToken, Progress};
+//! use runeforge_random::prelude::Rng;
+//!
+//! let token = CancelToken::new();
+//! let ui_token = token.clone(); // Hand this to the UI thread
+//!
+//! let mut sink = token.with_callback(|progress: Progress<'_>| {
+//!     println!("{}: {:.0}%", progress.stage, progress.fraction() * 100.0);
+//! });
+//! let config = CaveConfig::default();
+//! let cave = CaveGenerator::generate_with_progress(200, 200, &config, &mut Rng::new(), &mut sink);
+//! assert!(cave.is_ok());
+//!
+//! ui_token.cancel();
+//! let cancelled = CaveGenerator::generate_with_progress(200, 200, &config, &mut Rng::new(), &mut sink);
+//! assert!(matches!(cancelled, Err(CaveError::Cancelled)));
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// How far a generation has got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress<'a> {
+    /// What the generator is doing, e.g. "iteration 3".
+    pub stage: &'a str,
+    /// Units of work finished.
+    pub done: u64,
+    /// Units of work in total. The unit depends on the generator.
+    pub total: u64,
+}
+
+impl Progress<'_> {
+    /// Returns the finished fraction, from 0.0 to 1.0.
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        (self.done as f32 / self.total as f32).min(1.0)
+    }
+}
+
+/// Receives progress from a generator and can ask it to stop.
+pub trait ProgressSink {
+    /// Called as work completes.
+    fn report(&mut self, progress: Progress<'_>);
+
+    /// Polled between steps. Returning true aborts the generation.
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+impl<F: FnMut(Progress<'_>)> ProgressSink for F {
+    fn report(&mut self, progress: Progress<'_>) {
+        self(progress);
+    }
+}
+
+/// A shared flag for cancelling a generation from another thread.
+///
+/// Clones share the flag, so one clone can be kept by the UI while another is
+/// passed to the generator.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks every generation watching this token to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true once [`cancel`](Self::cancel) has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Clears the flag so the token can be reused.
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+
+    /// Combines this token with a progress callback.
+    pub fn with_callback<F: FnMut(Progress<'_>)>(&self, callback: F) -> ProgressCallback<F> {
+        ProgressCallback {
+            callback,
+            token: self.clone(),
+        }
+    }
+}
+
+impl ProgressSink for CancelToken {
+    fn report(&mut self, _progress: Progress<'_>) {}
+
+    fn is_cancelled(&self) -> bool {
+        CancelToken::is_cancelled(self)
+    }
+}
+
+/// A progress callback that can be cancelled, made by
+/// [`CancelToken::with_callback`].
+#[derive(Debug, Clone)]
+pub struct ProgressCallback<F> {
+    callback: F,
+    token: CancelToken,
+}
+
+impl<F: FnMut(Progress<'_>)> ProgressSink for ProgressCallback<F> {
+    fn report(&mut self, progress: Progress<'_>) {
+        (self.callback)(progress);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fraction() {
+        let progress = Progress {
+            stage: "test",
+            done: 3,
+            total: 4,
+        };
+        assert!((progress.fraction() - 0.75).abs() < f32::EPSILON);
+        let empty = Progress {
+            stage: "test",
+            done: 0,
+            total: 0,
+        };
+        assert!((empty.fraction() - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_token_is_shared() {
+        let token = CancelToken::new();
+        let mut reports = 0;
+        {
+            let mut sink = token.with_callback(|_: Progress<'_>| reports += 1);
+            sink.report(Progress {
+                stage: "a",
+                done: 1,
+                total: 2,
+            });
+            assert!(!sink.is_cancelled());
+            token.clone().cancel();
+            assert!(sink.is_cancelled());
+        }
+        assert_eq!(reports, 1);
+        token.reset();
+        assert!(!ProgressSink::is_cancelled(&token));
+    }
+}
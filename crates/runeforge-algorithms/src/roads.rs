@@ -0,0 +1,421 @@
+//! Road networks between points of interest.
+//!
+//! [`RoadGenerator`] joins towns, dungeon entrances and other points of
+//! interest with roads:
+//! 1. Points are connected one at a time, nearest first, forming a tree
+//! 2. Each road is an A* search over a terrain [`CostGrid`]
+//! 3. Tiles already paved get cheaper, so later roads merge onto earlier
+//!    ones instead of running in parallel
+//! 4. Stair-step corners are cut and the road is widened into a tile layer
+//!
+//! [`RoadGenerator::terrain_costs`] builds a cost grid from a heightmap that
+//! prefers flat ground and avoids water.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_algorithms::roads::{RoadConfig, RoadGenerator};
+//! use runeforge_geometry::prelude::IVec2;
+//! use runeforge_noise::{Hydrology, HydrologyConfig, NoiseMap};
+//!
+//! let terrain = NoiseMap::new(60, 40, 7);
+//! // An inland map: no ocean, and rivers end in marshes rather than lakes.
+//! let hydrology = HydrologyConfig::new().with_sea_level(0.0).with_fill_lakes(false);
+//! let water = Hydrology::compute(&terrain, &hydrology).unwrap();
+//! let config = RoadConfig::new().with_width(2);
+//! let costs = RoadGenerator::terrain_costs(&terrain, Some(&water), &config);
+//!
+//! let towns = [IVec2::new(5, 5), IVec2::new(50, 8), IVec2::new(30, 35)];
+//! let roads = RoadGenerator::generate(&costs, &towns, &config).unwrap();
+//! assert_eq!(roads.segments().len(), 2);
+//! assert!(roads.is_road(towns[0]));
+//! ```
+
+use runeforge_geometry::prelude::IVec2;
+use runeforge_noise::{HydrologyMap, NoiseMap, Water};
+use runeforge_pathfinding::prelude::{CostGrid, PathFinder, IMPASSABLE};
+use thiserror::Error;
+
+/// Errors that can occur during road generation.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RoadError {
+    /// A configuration value is out of range.
+    #[error("Invalid road config field '{field}': {reason}")]
+    InvalidConfig {
+        /// Name of the offending field
+        field: &'static str,
+        /// Why the value is rejected
+        reason: String,
+    },
+    /// A point of interest is outside the map or on impassable terrain.
+    #[error("Point of interest {index} at {pos} is outside the map or impassable")]
+    InvalidPoint {
+        /// Index of the point in the input
+        index: usize,
+        /// Its position
+        pos: IVec2,
+    },
+    /// Two points can't be joined by any road.
+    #[error("No road possible from point {from} to point {to}")]
+    Unreachable {
+        /// Index of the point being connected
+        from: usize,
+        /// Index of the point it was routed to
+        to: usize,
+    },
+}
+
+impl RoadError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            RoadError::InvalidConfig { .. } => "roads.invalid_config",
+            RoadError::InvalidPoint { .. } => "roads.invalid_point",
+            RoadError::Unreachable { .. } => "roads.unreachable",
+        }
+    }
+}
+
+/// Configuration for road generation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoadConfig {
+    /// Road width in tiles.
+    pub width: u32,
+    /// Cut stair-step corners so roads run diagonally. The road stays
+    /// connected diagonally only, which suits games with 8-way movement.
+    pub smooth: bool,
+    /// Cost multiplier (0.0 to 1.0) for tiles already paved. Lower values
+    /// merge roads more eagerly.
+    pub reuse_factor: f32,
+    /// Extra cost per unit of slope in [`RoadGenerator::terrain_costs`].
+    pub slope_cost: u32,
+    /// Cost of crossing a river tile (a bridge) in
+    /// [`RoadGenerator::terrain_costs`]. `None` makes rivers impassable.
+    pub river_cost: Option<u32>,
+}
+
+impl Default for RoadConfig {
+    fn default() -> Self {
+        Self {
+            width: 1,
+            smooth: true,
+            reuse_factor: 0.5,
+            slope_cost: 100,
+            river_cost: Some(20),
+        }
+    }
+}
+
+impl RoadConfig {
+    /// Creates a new configuration with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the road width.
+    pub fn with_width(mut self, width: u32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets whether corners are cut.
+    pub fn with_smooth(mut self, smooth: bool) -> Self {
+        self.smooth = smooth;
+        self
+    }
+
+    /// Sets the cost multiplier for paved tiles (clamped to 0.0-1.0).
+    pub fn with_reuse_factor(mut self, factor: f32) -> Self {
+        self.reuse_factor = factor.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the extra cost per unit of slope.
+    pub fn with_slope_cost(mut self, cost: u32) -> Self {
+        self.slope_cost = cost;
+        self
+    }
+
+    /// Sets the river crossing cost; `None` makes rivers impassable.
+    pub fn with_river_cost(mut self, cost: Option<u32>) -> Self {
+        self.river_cost = cost;
+        self
+    }
+
+    /// Checks that the configuration produces roads.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RoadError::InvalidConfig` naming the first offending field.
+    pub fn validate(&self) -> Result<(), RoadError> {
+        if self.width == 0 {
+            return Err(RoadError::InvalidConfig {
+                field: "width",
+                reason: "must be at least 1".to_string(),
+            });
+        }
+        if !(0.0..=1.0).contains(&self.reuse_factor) {
+            return Err(RoadError::InvalidConfig {
+                field: "reuse_factor",
+                reason: format!("{} is outside 0.0-1.0", self.reuse_factor),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// One road, joining a point of interest to the network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoadSegment {
+    /// Index of the point that was connected.
+    pub from: usize,
+    /// Index of the already-connected point the road was routed to.
+    pub to: usize,
+    /// The road's center line, from `from` to `to` (or to where it joins an
+    /// earlier road, which it then follows).
+    pub path: Vec<IVec2>,
+}
+
+/// The generated roads as a tile layer plus their center lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoadNetwork {
+    width: u32,
+    height: u32,
+    tiles: Vec<bool>,
+    segments: Vec<RoadSegment>,
+}
+
+impl RoadNetwork {
+    /// Returns the map width.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the map height.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns true if a tile is road.
+    pub fn is_road(&self, pos: IVec2) -> bool {
+        self.index(pos).is_some_and(|i| self.tiles[i])
+    }
+
+    /// Returns the road layer in row-major order.
+    pub fn tiles(&self) -> &[bool] {
+        &self.tiles
+    }
+
+    /// Returns the number of road tiles.
+    pub fn road_count(&self) -> usize {
+        self.tiles.iter().filter(|&&t| t).count()
+    }
+
+    /// Returns the roads in the order they were built.
+    pub fn segments(&self) -> &[RoadSegment] {
+        &self.segments
+    }
+
+    fn index(&self, pos: IVec2) -> Option<usize> {
+        (pos.x >= 0 && pos.y >= 0 && pos.x < self.width as i32 && pos.y < self.height as i32)
+            .then(|| (pos.y as u32 * self.width + pos.x as u32) as usize)
+    }
+}
+
+/// Generator for road networks.
+pub struct RoadGenerator;
+
+impl RoadGenerator {
+    /// Connects every point of interest to the network.
+    ///
+    /// Duplicate points share a road. Roads never pave impassable tiles,
+    /// even when widened.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RoadError::InvalidConfig` if [`RoadConfig::validate`] fails,
+    /// `RoadError::InvalidPoint` if a point is outside the grid or impassable,
+    /// or `RoadError::Unreachable` if two points can't be joined.
+    pub fn generate(
+        costs: &CostGrid,
+        points: &[IVec2],
+        config: &RoadConfig,
+    ) -> Result<RoadNetwork, RoadError> {
+        config.validate()?;
+        if let Some((index, &pos)) = points
+            .iter()
+            .enumerate()
+            .find(|(_, &p)| !costs.in_bounds(p) || !costs.is_passable(p))
+        {
+            return Err(RoadError::InvalidPoint { index, pos });
+        }
+
+        let (width, height) = (costs.width(), costs.height());
+        let mut network = RoadNetwork {
+            width,
+            height,
+            tiles: vec![false; (width * height) as usize],
+            segments: Vec::new(),
+        };
+        let mut grid = costs.clone();
+        let mut connected: Vec<usize> = Vec::new();
+        let mut pending: Vec<usize> = (0..points.len()).collect();
+        if !pending.is_empty() {
+            connected.push(pending.remove(0));
+        }
+
+        while !pending.is_empty() {
+            let (slot, to) = pending
+                .iter()
+                .enumerate()
+                .flat_map(|(slot, &p)| connected.iter().map(move |&c| (slot, p, c)))
+                .min_by_key(|&(_, p, c)| (points[p] - points[c]).length_squared())
+                .map(|(slot, _, c)| (slot, c))
+                .unwrap_or_default();
+            let from = pending.remove(slot);
+
+            let (start, goal) = (points[from], points[to]);
+            let mut path = if start == goal {
+                vec![start]
+            } else {
+                PathFinder::Astar.compute(start, goal, &mut grid, ())
+            };
+            if path.is_empty() {
+                return Err(RoadError::Unreachable { from, to });
+            }
+            if config.smooth {
+                path = Self::cut_corners(path);
+            }
+
+            for &p in &path {
+                let cost = grid.cost_at(p);
+                let reduced = (cost as f32 * config.reuse_factor).round() as u32;
+                grid.set_cost(p, reduced.clamp(1, cost));
+            }
+            Self::pave(&mut network, costs, &path, config.width);
+            network.segments.push(RoadSegment { from, to, path });
+            connected.push(from);
+        }
+
+        Ok(network)
+    }
+
+    /// Builds a road cost grid from a heightmap.
+    ///
+    /// Each tile costs 1 plus `slope_cost` times its steepest height
+    /// difference to a neighbor (normalized heights, 0.0 to 1.0). Ocean and
+    /// lake tiles are impassable; river tiles cost `river_cost`.
+    pub fn terrain_costs(
+        heightmap: &NoiseMap,
+        water: Option<&HydrologyMap>,
+        config: &RoadConfig,
+    ) -> CostGrid {
+        let (width, height) = (heightmap.width(), heightmap.height());
+        CostGrid::from_fn(width as u32, height as u32, |p| {
+            let (x, y) = (p.x as usize, p.y as usize);
+            match water.and_then(|w| w.water(x, y)) {
+                Some(Water::Ocean | Water::Lake) => return IMPASSABLE,
+                Some(Water::River) => return config.river_cost.unwrap_or(IMPASSABLE),
+                None => {}
+            }
+            let here = heightmap.normalized(x, y);
+            let slope = [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                .into_iter()
+                .map(|(dx, dy)| (x as i64 + dx, y as i64 + dy))
+                .filter(|&(nx, ny)| {
+                    nx >= 0 && ny >= 0 && (nx as usize) < width && (ny as usize) < height
+                })
+                .map(|(nx, ny)| (heightmap.normalized(nx as usize, ny as usize) - here).abs())
+                .fold(0.0, f64::max);
+            1 + (slope * config.slope_cost as f64).round() as u32
+        })
+    }
+
+    /// Drops the corner tile of every stair step (a tile whose path
+    /// neighbors touch diagonally).
+    fn cut_corners(path: Vec<IVec2>) -> Vec<IVec2> {
+        let mut result: Vec<IVec2> = Vec::with_capacity(path.len());
+        for (i, &p) in path.iter().enumerate() {
+            let corner = match (result.last(), path.get(i + 1)) {
+                (Some(&prev), Some(&next)) => (next - prev).abs().max_element() == 1,
+                _ => false,
+            };
+            if !corner {
+                result.push(p);
+            }
+        }
+        result
+    }
+
+    /// Paints a square brush of `width` tiles around every path tile.
+    fn pave(network: &mut RoadNetwork, costs: &CostGrid, path: &[IVec2], width: u32) {
+        let low = -((width as i32 - 1) / 2);
+        let high = width as i32 / 2;
+        for &center in path {
+            for dy in low..=high {
+                for dx in low..=high {
+                    let p = center + IVec2::new(dx, dy);
+                    if costs.in_bounds(p) && costs.is_passable(p) {
+                        if let Some(i) = network.index(p) {
+                            network.tiles[i] = true;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roads_connect_every_point() {
+        let costs = CostGrid::new(30, 20, 1);
+        let points = [IVec2::new(2, 2), IVec2::new(27, 3), IVec2::new(15, 17)];
+        let network = RoadGenerator::generate(&costs, &points, &RoadConfig::new()).unwrap();
+        assert_eq!(network.segments().len(), 2);
+        for p in points {
+            assert!(network.is_road(p));
+        }
+        for segment in network.segments() {
+            assert_eq!(segment.path[0], points[segment.from]);
+            for pair in segment.path.windows(2) {
+                assert_eq!((pair[1] - pair[0]).abs().max_element(), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_roads_avoid_expensive_terrain() {
+        // A marsh band with a cheap ford at x = 15.
+        let costs = CostGrid::from_fn(30, 11, |p| if p.y == 5 && p.x != 15 { 50 } else { 1 });
+        let points = [IVec2::new(5, 1), IVec2::new(5, 9)];
+        let network = RoadGenerator::generate(&costs, &points, &RoadConfig::new()).unwrap();
+        assert!(network.is_road(IVec2::new(15, 5)));
+        assert!(!network.is_road(IVec2::new(5, 5)));
+    }
+
+    #[test]
+    fn test_width_and_errors() {
+        let mut costs = CostGrid::new(20, 10, 1);
+        let points = [IVec2::new(1, 5), IVec2::new(18, 5)];
+        let wide = RoadConfig::new().with_width(3).with_smooth(false);
+        let network = RoadGenerator::generate(&costs, &points, &wide).unwrap();
+        assert!(network.is_road(IVec2::new(10, 4)) && network.is_road(IVec2::new(10, 6)));
+        assert!(!network.is_road(IVec2::new(10, 8)));
+
+        for y in 0..10 {
+            costs.set_cost(IVec2::new(10, y), IMPASSABLE);
+        }
+        let err = RoadGenerator::generate(&costs, &points, &RoadConfig::new()).unwrap_err();
+        assert_eq!(err, RoadError::Unreachable { from: 1, to: 0 });
+        let err =
+            RoadGenerator::generate(&costs, &[IVec2::new(10, 0)], &RoadConfig::new()).unwrap_err();
+        assert_eq!(err.code(), "roads.invalid_point");
+        let err =
+            RoadGenerator::generate(&costs, &points, &RoadConfig::new().with_width(0)).unwrap_err();
+        assert_eq!(err.code(), "roads.invalid_config");
+    }
+}
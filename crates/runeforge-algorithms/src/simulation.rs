@@ -0,0 +1,343 @@
+//! Time-sliced simulation of off-screen chunks.
+//!
+//! A [`ChunkManager`] keeps distant chunks loaded, but nothing happens in
+//! them unless the game simulates them, and simulating every loaded chunk
+//! every turn is too slow. A [`BackgroundSimulation`] schedules them
+//! instead:
+//!
+//! *   Chunks within the active radius of the player are left alone; the
+//!     game simulates them in full.
+//! *   Further chunks fall into [`SimulationTier`]s. Each tier is ticked
+//!     every `interval` turns, and the callback is told how many turns have
+//!     passed so it can catch up in one coarse step: move monsters a few
+//!     tiles at once, spread fire a generation, and so on.
+//! *   Chunks beyond the last tier are frozen until the player comes closer,
+//!     then catch up on their next tick.
+//! *   At most `budget` chunks are simulated per tick, most overdue first, so
+//!     the cost per frame stays flat however large the world is.
+//!
+//! Each step gets an [`Rng`] seeded from the chunk and turn, so background
+//! simulation replays identically.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_algorithms::chunk::{Chunk, ChunkConfig, ChunkManager};
+//! use runeforge_algorithms::simulation::{BackgroundSimulation, SimulationConfig};
+//! use runeforge_geometry::prelude::IVec2;
+//!
+//! // Each tile counts how many turns of background simulation it has seen
+//! let config = ChunkConfig::new().with_chunk_size(8);
+//! let mut world = ChunkManager::new(7, config, |_, _: &mut Chunk<u32>, _| {});
+//! world.load_around(IVec2::ZERO, 3);
+//!
+//! let config = SimulationConfig::new()
+//!     .with_active_radius(1)
+//!     .with_tier(2, 4) // 2 chunks away: every 4 turns
+//!     .with_tier(3, 10) // 3 chunks away: every 10 turns
+//!     .with_budget(64);
+//! let mut sim = BackgroundSimulation::new(config);
+//!
+//! for turn in 0..=20 {
+//!     sim.tick(&mut world, IVec2::ZERO, turn, |chunk, step, _rng| {
+//!         for tile in chunk.tiles_mut() {
+//!             *tile += step.elapsed as u32;
+//!         }
+//!     });
+//! }
+//!
+//! assert_eq!(*world.tile(IVec2::new(0, 0)), 0); // active: left to the game
+//! assert_eq!(*world.tile(IVec2::new(16, 0)), 20); // near tier, caught up
+//! assert_eq!(*world.tile(IVec2::new(24, 0)), 20);
+//! ```
+
+use std::collections::HashMap;
+
+use runeforge_geometry::prelude::*;
+use runeforge_random::prelude::*;
+
+use crate::chunk::{chunk_seed, Chunk, ChunkManager};
+
+/// A band of chunks simulated at the same rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulationTier {
+    /// Furthest chunk distance (Chebyshev, in chunks) in this tier.
+    pub radius: u32,
+    /// Turns between ticks of a chunk in this tier (minimum 1).
+    pub interval: u64,
+}
+
+/// Configuration for a [`BackgroundSimulation`].
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    /// Chunks this close to the focus are simulated by the game itself.
+    pub active_radius: u32,
+    /// Tiers ordered by radius; chunks beyond the last are frozen.
+    pub tiers: Vec<SimulationTier>,
+    /// Most chunks simulated per tick.
+    pub budget: usize,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            active_radius: 1,
+            tiers: vec![
+                SimulationTier {
+                    radius: 3,
+                    interval: 4,
+                },
+                SimulationTier {
+                    radius: 6,
+                    interval: 16,
+                },
+            ],
+            budget: 8,
+        }
+    }
+}
+
+impl SimulationConfig {
+    /// Creates a configuration with no tiers, so nothing off-screen is
+    /// simulated until tiers are added.
+    pub fn new() -> Self {
+        Self {
+            tiers: Vec::new(),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the radius, in chunks, that the game simulates itself.
+    pub fn with_active_radius(mut self, radius: u32) -> Self {
+        self.active_radius = radius;
+        self
+    }
+
+    /// Adds a tier simulating chunks up to `radius` chunks away every
+    /// `interval` turns.
+    pub fn with_tier(mut self, radius: u32, interval: u64) -> Self {
+        self.tiers.push(SimulationTier {
+            radius,
+            interval: interval.max(1),
+        });
+        self.tiers.sort_by_key(|t| t.radius);
+        self
+    }
+
+    /// Sets the most chunks simulated per tick (minimum 1).
+    pub fn with_budget(mut self, budget: usize) -> Self {
+        self.budget = budget.max(1);
+        self
+    }
+}
+
+/// What a background simulation callback is asked to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulationStep {
+    /// Coordinates of the chunk, in chunks.
+    pub coord: IVec2,
+    /// The current turn.
+    pub turn: u64,
+    /// Turns since the chunk was last simulated, to catch up on.
+    pub elapsed: u64,
+    /// Index of the chunk's [`SimulationTier`]; higher tiers are further
+    /// away and can be simulated more coarsely.
+    pub tier: usize,
+}
+
+/// Schedules ticks of off-screen chunks at reduced frequency, within a
+/// per-tick budget.
+#[derive(Debug, Clone)]
+pub struct BackgroundSimulation {
+    config: SimulationConfig,
+    /// Turn each known chunk was last simulated (or seen active).
+    last_simulated: HashMap<IVec2, u64>,
+}
+
+impl BackgroundSimulation {
+    /// Creates a scheduler.
+    pub fn new(config: SimulationConfig) -> Self {
+        let config = SimulationConfig {
+            budget: config.budget.max(1),
+            ..config
+        };
+        Self {
+            config,
+            last_simulated: HashMap::new(),
+        }
+    }
+
+    /// Returns the configuration.
+    pub fn config(&self) -> &SimulationConfig {
+        &self.config
+    }
+
+    /// Simulates the loaded chunks that are due on `turn`, with `focus` the
+    /// world position the player is at. Returns how many chunks were
+    /// simulated.
+    ///
+    /// Chunks are only read through [`ChunkManager::peek_chunk_mut`], so
+    /// background simulation neither loads chunks nor keeps them from being
+    /// evicted. A chunk seen for the first time starts its clock on this
+    /// turn rather than catching up on its whole history.
+    pub fn tick<T, F>(
+        &mut self,
+        world: &mut ChunkManager<T, F>,
+        focus: IVec2,
+        turn: u64,
+        mut simulate: impl FnMut(&mut Chunk<T>, &SimulationStep, &mut Rng),
+    ) -> usize
+    where
+        T: Clone + Default,
+        F: FnMut(IVec2, &mut Chunk<T>, &mut Rng),
+    {
+        let center = world.chunk_coord(focus);
+        self.last_simulated
+            .retain(|coord, _| world.is_loaded(*coord));
+
+        let mut due = Vec::new();
+        for coord in world.loaded_chunks() {
+            let distance = (coord - center).abs().max_element() as u32;
+            let last = self.last_simulated.entry(coord).or_insert(turn);
+            if distance <= self.config.active_radius {
+                *last = turn;
+                continue;
+            }
+            let Some(tier) = self.config.tiers.iter().position(|t| distance <= t.radius) else {
+                continue;
+            };
+            let elapsed = turn.saturating_sub(*last);
+            let interval = self.config.tiers[tier].interval;
+            if elapsed >= interval {
+                let step = SimulationStep {
+                    coord,
+                    turn,
+                    elapsed,
+                    tier,
+                };
+                due.push((elapsed as f64 / interval as f64, step));
+            }
+        }
+
+        // Most overdue first; ties broken by position so runs are repeatable
+        due.sort_by(|(a, sa), (b, sb)| {
+            b.total_cmp(a)
+                .then_with(|| (sa.coord.y, sa.coord.x).cmp(&(sb.coord.y, sb.coord.x)))
+        });
+        due.truncate(self.config.budget);
+
+        let world_seed = world.world_seed();
+        for (_, step) in &due {
+            if let Some(chunk) = world.peek_chunk_mut(step.coord) {
+                let seed = splitmix64(chunk_seed(world_seed, step.coord) ^ step.turn);
+                simulate(chunk, step, &mut Rng::with_seed(seed));
+                self.last_simulated.insert(step.coord, turn);
+            }
+        }
+        due.len()
+    }
+
+    /// Returns the turn the chunk at `coord` was last simulated, or seen in
+    /// the active area.
+    pub fn last_simulated(&self, coord: IVec2) -> Option<u64> {
+        self.last_simulated.get(&coord).copied()
+    }
+
+    /// Forgets a chunk's schedule, e.g. after it was regenerated.
+    pub fn forget(&mut self, coord: IVec2) {
+        self.last_simulated.remove(&coord);
+    }
+
+    /// Forgets every chunk's schedule.
+    pub fn clear(&mut self) {
+        self.last_simulated.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::ChunkConfig;
+
+    fn world(max_loaded: usize) -> ChunkManager<u32, impl FnMut(IVec2, &mut Chunk<u32>, &mut Rng)> {
+        let config = ChunkConfig::new()
+            .with_chunk_size(4)
+            .with_max_loaded_chunks(max_loaded);
+        ChunkManager::new(1, config, |_, _: &mut Chunk<u32>, _: &mut Rng| {})
+    }
+
+    /// Counts ticks in the first tile of each chunk.
+    fn count(chunk: &mut Chunk<u32>, _: &SimulationStep, _: &mut Rng) {
+        chunk.tiles_mut()[0] += 1;
+    }
+
+    #[test]
+    fn test_tiers_tick_at_their_interval() {
+        let mut world = world(64);
+        world.load_around(IVec2::ZERO, 3);
+        let config = SimulationConfig::new()
+            .with_active_radius(0)
+            .with_tier(1, 2)
+            .with_tier(2, 5)
+            .with_budget(100);
+        let mut sim = BackgroundSimulation::new(config);
+        for turn in 0..=10 {
+            sim.tick(&mut world, IVec2::ZERO, turn, count);
+        }
+
+        let ticks = |coord: IVec2| world.peek_chunk(coord).unwrap().tiles()[0];
+        assert_eq!(ticks(IVec2::ZERO), 0);
+        assert_eq!(ticks(IVec2::new(1, 0)), 5);
+        assert_eq!(ticks(IVec2::new(-2, 2)), 2);
+        assert_eq!(ticks(IVec2::new(3, 0)), 0);
+        assert_eq!(sim.last_simulated(IVec2::new(1, 1)), Some(10));
+    }
+
+    #[test]
+    fn test_budget_prefers_most_overdue() {
+        let mut world = world(64);
+        world.load_around(IVec2::ZERO, 2);
+        let config = SimulationConfig::new()
+            .with_active_radius(0)
+            .with_tier(2, 1)
+            .with_budget(3);
+        let mut sim = BackgroundSimulation::new(config);
+        assert_eq!(sim.tick(&mut world, IVec2::ZERO, 0, count), 0);
+
+        // 24 chunks are due every turn, but only 3 run per tick
+        for turn in 1..=8 {
+            assert_eq!(sim.tick(&mut world, IVec2::ZERO, turn, count), 3);
+        }
+        // Everyone got a turn before anyone got a second one
+        for coord in world.loaded_chunks().filter(|c| *c != IVec2::ZERO) {
+            assert_eq!(world.peek_chunk(coord).unwrap().tiles()[0], 1);
+        }
+    }
+
+    #[test]
+    fn test_frozen_chunks_catch_up_and_unloaded_are_forgotten() {
+        let mut world = world(64);
+        world.load_around(IVec2::ZERO, 0);
+        world.chunk(IVec2::new(5, 0));
+        let config = SimulationConfig::new()
+            .with_active_radius(0)
+            .with_tier(2, 3);
+        let mut sim = BackgroundSimulation::new(config);
+
+        let mut elapsed = Vec::new();
+        let mut record = |_: &mut Chunk<u32>, step: &SimulationStep, _: &mut Rng| {
+            elapsed.push(step.elapsed);
+        };
+        // Too far away: frozen
+        sim.tick(&mut world, IVec2::ZERO, 0, &mut record);
+        sim.tick(&mut world, IVec2::ZERO, 10, &mut record);
+        // The player walks closer and the chunk catches up in one step
+        sim.tick(&mut world, IVec2::new(16, 0), 12, &mut record);
+
+        world.unload(IVec2::new(5, 0));
+        sim.tick(&mut world, IVec2::new(16, 0), 13, &mut record);
+        assert_eq!(elapsed, [12]);
+        assert_eq!(sim.last_simulated(IVec2::new(5, 0)), None);
+        assert_eq!(sim.last_simulated(IVec2::ZERO), Some(10));
+    }
+}
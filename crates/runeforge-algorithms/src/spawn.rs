@@ -0,0 +1,365 @@
+//! Population: placing monsters and items in a generated dungeon.
+//!
+//! A [`Spawner`] walks the rooms of a [`Dungeon`] and rolls a
+//! [`RandomTable`] of [`SpawnEntry`]s for each one. Its density rules decide
+//! how many spawns a room gets:
+//! - A base range per room, growing with depth
+//! - A global per-room cap, and an area rule so closets stay sparse
+//! - Per-tag caps, e.g. nothing in the entrance room
+//!
+//! Spawns only go on free room floor. Corridors, doors and the tiles around
+//! doorways are left clear so nothing blocks the way in.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_algorithms::prelude::*;
+//! use runeforge_random::prelude::RandomTable;
+//!
+//! let mut rng = Rng::with_seed(3);
+//! let dungeon = DungeonGenerator::generate(80, 50, &BspConfig::default(), &mut rng).unwrap();
+//!
+//! let table = RandomTable::new()
+//!     .add(SpawnEntry::new("rat").with_depth(1, Some(3)), 10)
+//!     .add(SpawnEntry::new("orc").with_depth(2, None), 5)
+//!     .add(SpawnEntry::new("potion"), 2);
+//!
+//! let spawner = Spawner::new().with_spawns_per_room(1, 3).with_max_per_room(4);
+//! for spawn in spawner.populate(&dungeon, 2, &table, &mut rng).unwrap() {
+//!     assert!(dungeon.is_walkable(spawn.pos));
+//!     println!("{} at {:?}", spawn.template, spawn.pos);
+//! }
+//! ```
+
+use std::collections::HashSet;
+
+use runeforge_geometry::prelude::IVec2;
+use runeforge_random::prelude::*;
+use thiserror::Error;
+
+use crate::bsp::{Dungeon, RoomId, RoomTag};
+
+/// Errors that can occur while populating a map.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SpawnError {
+    /// A density rule would produce a degenerate population.
+    #[error("Invalid spawner config field '{field}': {reason}")]
+    InvalidConfig {
+        /// Name of the offending field
+        field: &'static str,
+        /// Why the value is rejected
+        reason: String,
+    },
+}
+
+impl SpawnError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SpawnError::InvalidConfig { .. } => "spawn.invalid_config",
+        }
+    }
+}
+
+/// One row of a spawn table: a template id and the depths it appears at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpawnEntry<T> {
+    /// The template to spawn, usually an id into a template set.
+    pub template: T,
+    /// Shallowest depth the entry appears at.
+    pub min_depth: u32,
+    /// Deepest depth the entry appears at, or `None` for no limit.
+    pub max_depth: Option<u32>,
+}
+
+impl<T> SpawnEntry<T> {
+    /// Creates an entry that appears at every depth.
+    pub fn new(template: T) -> Self {
+        Self {
+            template,
+            min_depth: 0,
+            max_depth: None,
+        }
+    }
+
+    /// Limits the entry to a depth range (inclusive).
+    pub fn with_depth(mut self, min: u32, max: Option<u32>) -> Self {
+        self.min_depth = min;
+        self.max_depth = max;
+        self
+    }
+
+    /// Returns true if the entry can appear at `depth`.
+    pub fn allows(&self, depth: u32) -> bool {
+        depth >= self.min_depth && self.max_depth.is_none_or(|max| depth <= max)
+    }
+}
+
+/// A placed spawn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spawn<T> {
+    /// Where to spawn.
+    pub pos: IVec2,
+    /// What to spawn.
+    pub template: T,
+    /// The room the spawn is in.
+    pub room: RoomId,
+}
+
+/// Places spawns in a dungeon's rooms according to density rules.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spawner {
+    /// Base number of spawns per room (inclusive range).
+    pub spawns_per_room: (u32, u32),
+    /// Extra spawns per room for each level of depth, rounded down.
+    pub per_depth: f32,
+    /// Hard cap on spawns in one room.
+    pub max_per_room: u32,
+    /// Floor tiles needed per spawn, so small rooms get fewer. 0 disables it.
+    pub tiles_per_spawn: u32,
+    /// Caps for rooms with a given tag. The lowest matching cap wins.
+    pub tag_caps: Vec<(RoomTag, u32)>,
+}
+
+impl Default for Spawner {
+    /// 0-2 spawns per room plus one per four levels, at most 6, one per 12
+    /// tiles, and nothing in the entrance room.
+    fn default() -> Self {
+        Self {
+            spawns_per_room: (0, 2),
+            per_depth: 0.25,
+            max_per_room: 6,
+            tiles_per_spawn: 12,
+            tag_caps: vec![(RoomTag::Entrance, 0)],
+        }
+    }
+}
+
+impl Spawner {
+    /// Creates a spawner with default density rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the base number of spawns per room.
+    pub fn with_spawns_per_room(mut self, min: u32, max: u32) -> Self {
+        self.spawns_per_room = (min, max);
+        self
+    }
+
+    /// Sets the extra spawns per room for each level of depth.
+    pub fn with_per_depth(mut self, per_depth: f32) -> Self {
+        self.per_depth = per_depth;
+        self
+    }
+
+    /// Sets the hard cap on spawns in one room.
+    pub fn with_max_per_room(mut self, max: u32) -> Self {
+        self.max_per_room = max;
+        self
+    }
+
+    /// Sets the floor tiles needed per spawn. 0 disables the area rule.
+    pub fn with_tiles_per_spawn(mut self, tiles: u32) -> Self {
+        self.tiles_per_spawn = tiles;
+        self
+    }
+
+    /// Caps spawns in rooms with `tag`, replacing any earlier cap for it.
+    pub fn with_tag_cap(mut self, tag: RoomTag, cap: u32) -> Self {
+        self.tag_caps.retain(|(t, _)| *t != tag);
+        self.tag_caps.push((tag, cap));
+        self
+    }
+
+    /// Checks that the density rules are usable.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpawnError::InvalidConfig` naming the first offending field.
+    pub fn validate(&self) -> Result<(), SpawnError> {
+        let (min, max) = self.spawns_per_room;
+        if min > max {
+            return Err(SpawnError::InvalidConfig {
+                field: "spawns_per_room",
+                reason: format!("min {} is above max {}", min, max),
+            });
+        }
+        if !self.per_depth.is_finite() || self.per_depth < 0.0 {
+            return Err(SpawnError::InvalidConfig {
+                field: "per_depth",
+                reason: format!("{} must be finite and not negative", self.per_depth),
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns how many spawns a room may hold at most, given its tags and
+    /// floor area.
+    pub fn room_cap(&self, tags: &[RoomTag], area: u32) -> u32 {
+        let mut cap = self.max_per_room;
+        if let Some(by_area) = area.checked_div(self.tiles_per_spawn) {
+            cap = cap.min(by_area);
+        }
+        for (tag, tag_cap) in &self.tag_caps {
+            if tags.contains(tag) {
+                cap = cap.min(*tag_cap);
+            }
+        }
+        cap
+    }
+
+    /// Places spawns in every room of a dungeon.
+    ///
+    /// Entries of `table` outside `depth` are ignored; if none are left, no
+    /// spawns are placed. Each room rolls its count, clamps it to
+    /// [`room_cap`](Self::room_cap) and to its free tiles, then rolls a
+    /// template for each spawn.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error from [`validate`](Self::validate).
+    pub fn populate<T: Clone>(
+        &self,
+        dungeon: &Dungeon,
+        depth: u32,
+        table: &RandomTable<SpawnEntry<T>>,
+        rng: &mut Rng,
+    ) -> Result<Vec<Spawn<T>>, SpawnError> {
+        self.validate()?;
+        let table: RandomTable<&SpawnEntry<T>> = table
+            .iter()
+            .filter(|(entry, _)| entry.allows(depth))
+            .collect();
+        let mut spawns = Vec::new();
+        if table.is_empty() {
+            return Ok(spawns);
+        }
+
+        let (min, max) = self.spawns_per_room;
+        let bonus = (depth as f32 * self.per_depth) as u32;
+        for (index, &room) in dungeon.rooms().iter().enumerate() {
+            let id = RoomId(index);
+            let area = room.points().filter(|&p| dungeon.is_walkable(p)).count() as u32;
+            let cap = self.room_cap(dungeon.room_tags(id), area);
+            let wanted = (rng.range(min as i32, max as i32) as u32 + bonus).min(cap);
+            if wanted == 0 {
+                continue;
+            }
+
+            let mut free = free_tiles(dungeon, id);
+            rng.shuffle(&mut free);
+            for pos in free.into_iter().take(wanted as usize) {
+                if let Some(entry) = table.roll(rng) {
+                    spawns.push(Spawn {
+                        pos,
+                        template: entry.template.clone(),
+                        room: id,
+                    });
+                }
+            }
+        }
+        Ok(spawns)
+    }
+}
+
+/// Walkable, feature-free tiles of a room that are not in or next to a
+/// doorway and don't belong to an earlier, overlapping room.
+fn free_tiles(dungeon: &Dungeon, id: RoomId) -> Vec<IVec2> {
+    let Some(room) = dungeon.room(id) else {
+        return Vec::new();
+    };
+    let mut blocked = HashSet::new();
+    for entrance in dungeon.room_entrances(room) {
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                blocked.insert(entrance + IVec2::new(dx, dy));
+            }
+        }
+    }
+    room.points()
+        .filter(|&p| {
+            dungeon.is_walkable(p)
+                && dungeon.features().get(p).is_none()
+                && !blocked.contains(&p)
+                && dungeon.room_at(p) == Some(id)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bsp::{BspConfig, DungeonGenerator};
+
+    fn table() -> RandomTable<SpawnEntry<&'static str>> {
+        RandomTable::new()
+            .add(SpawnEntry::new("rat").with_depth(1, Some(2)), 10)
+            .add(SpawnEntry::new("dragon").with_depth(10, None), 10)
+    }
+
+    #[test]
+    fn test_spawns_respect_caps_and_doorways() {
+        let mut rng = Rng::with_seed(21);
+        let mut dungeon =
+            DungeonGenerator::generate(80, 50, &BspConfig::default(), &mut rng).unwrap();
+        dungeon.tag_room(RoomId(1), RoomTag::Boss);
+        let spawner = Spawner::new()
+            .with_spawns_per_room(2, 5)
+            .with_max_per_room(3)
+            .with_tag_cap(RoomTag::Boss, 1);
+        let spawns = spawner.populate(&dungeon, 1, &table(), &mut rng).unwrap();
+        assert!(!spawns.is_empty());
+
+        let mut seen = HashSet::new();
+        for spawn in &spawns {
+            assert_eq!(spawn.template, "rat");
+            assert!(seen.insert(spawn.pos), "two spawns on {:?}", spawn.pos);
+            assert_eq!(dungeon.room_at(spawn.pos), Some(spawn.room));
+            let room = dungeon.room(spawn.room).unwrap();
+            for entrance in dungeon.room_entrances(room) {
+                let d = (spawn.pos - entrance).abs();
+                assert!(d.x > 1 || d.y > 1, "{:?} blocks a doorway", spawn.pos);
+            }
+        }
+        for (index, _) in dungeon.rooms().iter().enumerate() {
+            let count = spawns.iter().filter(|s| s.room == RoomId(index)).count();
+            let entrance = dungeon.room_has_tag(RoomId(index), &RoomTag::Entrance);
+            let cap = if entrance {
+                0
+            } else if index == 1 {
+                1
+            } else {
+                3
+            };
+            assert!(count <= cap, "room {} has {} spawns", index, count);
+        }
+    }
+
+    #[test]
+    fn test_depth_filters_table() {
+        let mut rng = Rng::with_seed(2);
+        let dungeon = DungeonGenerator::generate(80, 50, &BspConfig::default(), &mut rng).unwrap();
+        let spawner = Spawner::new().with_spawns_per_room(1, 1);
+        let deep = spawner.populate(&dungeon, 12, &table(), &mut rng).unwrap();
+        assert!(deep.iter().all(|s| s.template == "dragon"));
+        assert!(spawner
+            .populate(&dungeon, 5, &table(), &mut rng)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_room_cap_and_validation() {
+        let spawner = Spawner::new();
+        assert_eq!(spawner.room_cap(&[], 100), 6);
+        assert_eq!(spawner.room_cap(&[], 30), 2);
+        assert_eq!(spawner.room_cap(&[RoomTag::Entrance], 100), 0);
+
+        let err = Spawner::new()
+            .with_spawns_per_room(3, 1)
+            .validate()
+            .unwrap_err();
+        assert_eq!(err.code(), "spawn.invalid_config");
+    }
+}
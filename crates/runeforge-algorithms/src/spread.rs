@@ -0,0 +1,575 @@
+//! Cellular spread of fire, gas and water.
+//!
+//! A [`SpreadSimulation`] keeps three layers over a map and advances them one
+//! turn per [`step`](SpreadSimulation::step):
+//!
+//! *   **Fire** burns for a tile's fuel, then leaves it burnt. Each turn a
+//!     burning tile may light each of its four neighbors, with a chance scaled
+//!     by the neighbor's flammability. Burnt tiles never relight, and water
+//!     puts fires out and keeps tiles from catching.
+//! *   **Gas** diffuses between open neighbors and decays a little every turn.
+//! *   **Water** flows from higher to lower levels (floor elevation plus
+//!     depth) until it settles.
+//!
+//! The map itself is read through [`SpreadTerrain`], implemented over the
+//! game's tile layer. The simulation reports what changed as
+//! [`SpreadEvent`]s, such as a tile burning out, so the game can update its
+//! tiles (grass to ash) and its FOV and pathfinding data.
+//!
+//! Each turn draws from its own [`Rng`], derived from the seed and the turn
+//! number, so a simulation replays identically from the same seed.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_algorithms::spread::{SpreadConfig, SpreadSimulation, SpreadTerrain};
+//! use runeforge_geometry::prelude::IVec2;
+//!
+//! // Dry grass on the left, bare stone on the right
+//! struct Meadow;
+//! impl SpreadTerrain for Meadow {
+//!     fn flammability(&self, pos: IVec2) -> f32 {
+//!         if pos.x < 10 { 1.0 } else { 0.0 }
+//!     }
+//!     fn is_open(&self, _pos: IVec2) -> bool {
+//!         true
+//!     }
+//! }
+//!
+//! let config = SpreadConfig::new().with_ignite_chance(1.0);
+//! let mut sim = SpreadSimulation::new(20, 5, 42).with_config(config);
+//! sim.ignite(&Meadow, IVec2::new(0, 2));
+//! for _ in 0..30 {
+//!     sim.step(&Meadow);
+//! }
+//!
+//! // The grass burnt away and the fire stopped at the stone
+//! assert!(sim.is_burnt(IVec2::new(9, 0)));
+//! assert!(!sim.is_burnt(IVec2::new(10, 0)));
+//! assert!(!sim.is_burning(IVec2::new(10, 0)));
+//! ```
+
+use runeforge_geometry::prelude::*;
+use runeforge_random::prelude::*;
+
+/// Turns a tile burns for when [`SpreadTerrain::fuel`] is not overridden.
+pub const DEFAULT_FUEL: u8 = 3;
+
+/// The map a [`SpreadSimulation`] runs over, usually implemented by the
+/// game's tile layer.
+///
+/// Positions passed in are always inside the simulation's bounds.
+pub trait SpreadTerrain {
+    /// How readily a tile catches fire, from 0.0 (stone, water) to 1.0 (dry
+    /// grass).
+    fn flammability(&self, pos: IVec2) -> f32;
+
+    /// Returns true if gas and water can enter a tile; false for walls.
+    fn is_open(&self, pos: IVec2) -> bool;
+
+    /// Turns a tile burns for once lit (minimum 1).
+    fn fuel(&self, _pos: IVec2) -> u8 {
+        DEFAULT_FUEL
+    }
+
+    /// Floor height, which water flows down.
+    fn elevation(&self, _pos: IVec2) -> f32 {
+        0.0
+    }
+}
+
+/// Rates for a [`SpreadSimulation`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpreadConfig {
+    /// Chance per turn that a fire lights a fully flammable neighbor.
+    pub ignite_chance: f32,
+    /// Fraction (0.0 to 1.0) of the difference in gas evened out between
+    /// neighbors per turn.
+    pub gas_diffusion: f32,
+    /// Fraction of gas lost every turn.
+    pub gas_decay: f32,
+    /// Fraction (0.0 to 1.0) of the difference in water level evened out
+    /// between neighbors per turn.
+    pub water_flow: f32,
+    /// Water at least this deep puts out fires and keeps tiles from catching.
+    pub extinguish_depth: f32,
+    /// Gas and water below this amount vanish.
+    pub min_amount: f32,
+}
+
+impl Default for SpreadConfig {
+    fn default() -> Self {
+        Self {
+            ignite_chance: 0.5,
+            gas_diffusion: 0.5,
+            gas_decay: 0.05,
+            water_flow: 0.5,
+            extinguish_depth: 0.1,
+            min_amount: 0.01,
+        }
+    }
+}
+
+impl SpreadConfig {
+    /// Creates a configuration with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the chance a fire lights a fully flammable neighbor per turn.
+    pub fn with_ignite_chance(mut self, chance: f32) -> Self {
+        self.ignite_chance = chance.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets how quickly gas spreads (clamped to 0.0-1.0).
+    pub fn with_gas_diffusion(mut self, diffusion: f32) -> Self {
+        self.gas_diffusion = diffusion.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the fraction of gas lost per turn (clamped to 0.0-1.0).
+    pub fn with_gas_decay(mut self, decay: f32) -> Self {
+        self.gas_decay = decay.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets how quickly water flows (clamped to 0.0-1.0).
+    pub fn with_water_flow(mut self, flow: f32) -> Self {
+        self.water_flow = flow.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the water depth that puts out fires.
+    pub fn with_extinguish_depth(mut self, depth: f32) -> Self {
+        self.extinguish_depth = depth.max(0.0);
+        self
+    }
+
+    /// Sets the amount below which gas and water vanish.
+    pub fn with_min_amount(mut self, amount: f32) -> Self {
+        self.min_amount = amount.max(0.0);
+        self
+    }
+}
+
+/// A change to a tile made by [`SpreadSimulation::step`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpreadEvent {
+    /// Fire spread to a tile.
+    Ignited(IVec2),
+    /// A fire ran out of fuel; the tile is now burnt.
+    BurnedOut(IVec2),
+    /// Water put out a fire before it burnt the tile.
+    Extinguished(IVec2),
+}
+
+/// Fire, gas and water layers over a `width` x `height` map.
+#[derive(Debug, Clone)]
+pub struct SpreadSimulation {
+    width: u32,
+    height: u32,
+    config: SpreadConfig,
+    seed: u64,
+    turn: u64,
+    /// Turns of fuel left; 0 when not burning.
+    fire: Vec<u8>,
+    burnt: BitGrid,
+    gas: Vec<f32>,
+    water: Vec<f32>,
+}
+
+impl SpreadSimulation {
+    /// Creates a simulation with nothing burning and no gas or water.
+    pub fn new(width: u32, height: u32, seed: u64) -> Self {
+        let len = width as usize * height as usize;
+        Self {
+            width,
+            height,
+            config: SpreadConfig::default(),
+            seed,
+            turn: 0,
+            fire: vec![0; len],
+            burnt: BitGrid::new(width, height),
+            gas: vec![0.0; len],
+            water: vec![0.0; len],
+        }
+    }
+
+    /// Sets the rates.
+    pub fn with_config(mut self, config: SpreadConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Returns the rates.
+    pub fn config(&self) -> &SpreadConfig {
+        &self.config
+    }
+
+    /// Returns the width in tiles.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height in tiles.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the number of steps taken.
+    pub fn turn(&self) -> u64 {
+        self.turn
+    }
+
+    /// Sets a tile on fire, e.g. from a fireball. Returns false if it can't
+    /// burn: it isn't flammable, is burnt, burning, under water or outside
+    /// the map.
+    pub fn ignite(&mut self, terrain: &impl SpreadTerrain, pos: IVec2) -> bool {
+        match self.index(pos) {
+            Some(i) if self.can_ignite(terrain, pos, i) => {
+                self.fire[i] = terrain.fuel(pos).max(1);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if a tile is on fire.
+    pub fn is_burning(&self, pos: IVec2) -> bool {
+        self.fire_at(pos) > 0
+    }
+
+    /// Returns the turns a fire has left to burn, or 0 if not burning.
+    pub fn fire_at(&self, pos: IVec2) -> u8 {
+        self.index(pos).map_or(0, |i| self.fire[i])
+    }
+
+    /// Returns true if a tile has burnt out.
+    pub fn is_burnt(&self, pos: IVec2) -> bool {
+        self.burnt.is_set(pos)
+    }
+
+    /// Returns an iterator over the tiles on fire, for drawing.
+    pub fn burning(&self) -> impl Iterator<Item = IVec2> + '_ {
+        (0..self.fire.len())
+            .filter(|&i| self.fire[i] > 0)
+            .map(|i| self.position(i))
+    }
+
+    /// Adds gas to a tile, e.g. from a stinking cloud. Returns false outside
+    /// the map.
+    pub fn add_gas(&mut self, pos: IVec2, amount: f32) -> bool {
+        let Some(i) = self.index(pos) else {
+            return false;
+        };
+        self.gas[i] = (self.gas[i] + amount).max(0.0);
+        true
+    }
+
+    /// Returns the amount of gas on a tile.
+    pub fn gas_at(&self, pos: IVec2) -> f32 {
+        self.index(pos).map_or(0.0, |i| self.gas[i])
+    }
+
+    /// Adds water to a tile, e.g. from a spring. Returns false outside the
+    /// map.
+    pub fn add_water(&mut self, pos: IVec2, depth: f32) -> bool {
+        let Some(i) = self.index(pos) else {
+            return false;
+        };
+        self.water[i] = (self.water[i] + depth).max(0.0);
+        true
+    }
+
+    /// Returns the depth of water on a tile.
+    pub fn water_at(&self, pos: IVec2) -> f32 {
+        self.index(pos).map_or(0.0, |i| self.water[i])
+    }
+
+    /// Advances every layer by one turn and returns the fire events, in
+    /// row-major order within each kind.
+    pub fn step(&mut self, terrain: &impl SpreadTerrain) -> Vec<SpreadEvent> {
+        let open: Vec<bool> = (0..self.fire.len())
+            .map(|i| terrain.is_open(self.position(i)))
+            .collect();
+
+        if self.water.iter().any(|&w| w > 0.0) {
+            let elevation: Vec<f32> = (0..self.water.len())
+                .map(|i| terrain.elevation(self.position(i)))
+                .collect();
+            let levels: Vec<f32> = (0..self.water.len())
+                .map(|i| elevation[i] + self.water[i])
+                .collect();
+            self.exchange(Layer::Water, &levels, &open, self.config.water_flow);
+            settle(&mut self.water, 0.0, self.config.min_amount);
+        }
+
+        if self.gas.iter().any(|&g| g > 0.0) {
+            let levels = self.gas.clone();
+            self.exchange(Layer::Gas, &levels, &open, self.config.gas_diffusion);
+            settle(&mut self.gas, self.config.gas_decay, self.config.min_amount);
+        }
+
+        let events = self.spread_fire(terrain);
+        self.turn += 1;
+        events
+    }
+
+    /// Clears every layer, including burnt tiles, and resets the turn.
+    pub fn clear(&mut self) {
+        self.turn = 0;
+        self.fire.fill(0);
+        self.burnt.fill(0);
+        self.gas.fill(0.0);
+        self.water.fill(0.0);
+    }
+
+    fn spread_fire(&mut self, terrain: &impl SpreadTerrain) -> Vec<SpreadEvent> {
+        let mut rng = Rng::with_seed(splitmix64(self.seed ^ splitmix64(self.turn)));
+        let mut lit = Vec::new();
+        let mut events = Vec::new();
+
+        for i in 0..self.fire.len() {
+            if self.fire[i] == 0 {
+                continue;
+            }
+            let pos = self.position(i);
+            if self.water[i] >= self.config.extinguish_depth {
+                self.fire[i] = 0;
+                events.push(SpreadEvent::Extinguished(pos));
+                continue;
+            }
+
+            for next in pos.neighbors() {
+                let Some(j) = self.index(next) else {
+                    continue;
+                };
+                if self.can_ignite(terrain, next, j)
+                    && rng.chance(self.config.ignite_chance * terrain.flammability(next))
+                {
+                    lit.push(next);
+                }
+            }
+
+            self.fire[i] -= 1;
+            if self.fire[i] == 0 {
+                self.burnt.set(pos, 1);
+                events.push(SpreadEvent::BurnedOut(pos));
+            }
+        }
+
+        // Tiles lit this turn start burning next turn, so fire moves one
+        // tile per turn
+        lit.sort_by_key(|p| (p.y, p.x));
+        lit.dedup();
+        for pos in lit {
+            if self.ignite(terrain, pos) {
+                events.push(SpreadEvent::Ignited(pos));
+            }
+        }
+        events
+    }
+
+    /// Evens out `levels` between open orthogonal neighbors by moving the
+    /// layer's contents from the higher tile to the lower one.
+    fn exchange(&mut self, layer: Layer, levels: &[f32], open: &[bool], rate: f32) {
+        let values = match layer {
+            Layer::Gas => &mut self.gas,
+            Layer::Water => &mut self.water,
+        };
+        let mut delta = vec![0.0; values.len()];
+        let width = self.width as usize;
+
+        for a in 0..values.len() {
+            if !open[a] {
+                continue;
+            }
+            let right = (a % width + 1 < width).then_some(a + 1);
+            let down = Some(a + width).filter(|&b| b < values.len());
+            for b in [right, down].into_iter().flatten() {
+                if !open[b] {
+                    continue;
+                }
+                // A fifth of the difference at most, so a tile giving to all
+                // four neighbors never goes below their level
+                let flow = rate * (levels[a] - levels[b]) / 5.0;
+                let flow = if flow > 0.0 {
+                    flow.min(values[a] / 4.0)
+                } else {
+                    flow.max(-values[b] / 4.0)
+                };
+                delta[a] -= flow;
+                delta[b] += flow;
+            }
+        }
+
+        for (value, delta) in values.iter_mut().zip(delta) {
+            *value = (*value + delta).max(0.0);
+        }
+    }
+
+    fn can_ignite(&self, terrain: &impl SpreadTerrain, pos: IVec2, i: usize) -> bool {
+        self.fire[i] == 0
+            && !self.burnt.is_set(pos)
+            && self.water[i] < self.config.extinguish_depth
+            && terrain.flammability(pos) > 0.0
+    }
+
+    fn index(&self, pos: IVec2) -> Option<usize> {
+        self.burnt
+            .in_bounds(pos)
+            .then(|| pos.y as usize * self.width as usize + pos.x as usize)
+    }
+
+    fn position(&self, i: usize) -> IVec2 {
+        let width = self.width as usize;
+        IVec2::new((i % width) as i32, (i / width) as i32)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Layer {
+    Gas,
+    Water,
+}
+
+/// Applies decay and removes amounts too small to matter.
+fn settle(values: &mut [f32], decay: f32, min_amount: f32) {
+    for value in values {
+        *value *= 1.0 - decay;
+        if *value < min_amount {
+            *value = 0.0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flammable open floor with walls at `x == wall`, sloping down to the
+    /// right.
+    struct Field {
+        wall: i32,
+        slope: f32,
+    }
+
+    impl SpreadTerrain for Field {
+        fn flammability(&self, pos: IVec2) -> f32 {
+            if pos.x == self.wall {
+                0.0
+            } else {
+                1.0
+            }
+        }
+
+        fn is_open(&self, pos: IVec2) -> bool {
+            pos.x != self.wall
+        }
+
+        fn elevation(&self, pos: IVec2) -> f32 {
+            -self.slope * pos.x as f32
+        }
+    }
+
+    const FIELD: Field = Field {
+        wall: 6,
+        slope: 0.0,
+    };
+
+    fn total(sim: &SpreadSimulation, amount: impl Fn(&SpreadSimulation, IVec2) -> f32) -> f32 {
+        let mut sum = 0.0;
+        for y in 0..sim.height() as i32 {
+            for x in 0..sim.width() as i32 {
+                sum += amount(sim, IVec2::new(x, y));
+            }
+        }
+        sum
+    }
+
+    #[test]
+    fn test_fire_is_deterministic_and_burns_out() {
+        let run = |seed| {
+            let mut sim = SpreadSimulation::new(12, 12, seed);
+            sim.ignite(&FIELD, IVec2::new(2, 5));
+            (0..40).flat_map(|_| sim.step(&FIELD)).collect::<Vec<_>>()
+        };
+        let events = run(9);
+        assert_eq!(events, run(9));
+        assert!(events.contains(&SpreadEvent::BurnedOut(IVec2::new(2, 5))));
+        // Nothing relights, and the wall stops the fire
+        let lit = |p| {
+            events
+                .iter()
+                .filter(|e| **e == SpreadEvent::Ignited(p))
+                .count()
+        };
+        assert!((0..12).all(|y| (0..12).all(|x| lit(IVec2::new(x, y)) <= 1)));
+        assert!((0..12).all(|y| lit(IVec2::new(7, y)) == 0));
+    }
+
+    #[test]
+    fn test_water_puts_out_and_blocks_fire() {
+        let config = SpreadConfig::new()
+            .with_ignite_chance(1.0)
+            .with_water_flow(0.0);
+        let mut sim = SpreadSimulation::new(6, 1, 1).with_config(config);
+        sim.add_water(IVec2::new(3, 0), 1.0);
+        assert!(!sim.ignite(&FIELD, IVec2::new(3, 0)));
+        assert!(sim.ignite(&FIELD, IVec2::new(1, 0)));
+        for _ in 0..10 {
+            sim.step(&FIELD);
+        }
+        assert!(sim.is_burnt(IVec2::new(0, 0)));
+        assert!(!sim.is_burnt(IVec2::new(3, 0)));
+        assert!(!sim.is_burnt(IVec2::new(4, 0)));
+
+        assert!(sim.ignite(&FIELD, IVec2::new(5, 0)));
+        sim.add_water(IVec2::new(5, 0), 1.0);
+        assert_eq!(
+            sim.step(&FIELD),
+            [SpreadEvent::Extinguished(IVec2::new(5, 0))]
+        );
+    }
+
+    #[test]
+    fn test_gas_diffuses_around_walls_and_decays() {
+        let config = SpreadConfig::new().with_gas_decay(0.0).with_min_amount(0.0);
+        let mut sim = SpreadSimulation::new(10, 3, 1).with_config(config);
+        sim.add_gas(IVec2::new(2, 1), 9.0);
+        for _ in 0..20 {
+            sim.step(&FIELD);
+        }
+        assert!((total(&sim, SpreadSimulation::gas_at) - 9.0).abs() < 1e-3);
+        assert!(sim.gas_at(IVec2::new(0, 0)) > 0.1);
+        assert_eq!(sim.gas_at(IVec2::new(6, 1)), 0.0);
+        assert_eq!(sim.gas_at(IVec2::new(7, 1)), 0.0);
+
+        let mut sim = SpreadSimulation::new(10, 3, 1);
+        sim.add_gas(IVec2::new(2, 1), 1.0);
+        for _ in 0..200 {
+            sim.step(&FIELD);
+        }
+        assert_eq!(total(&sim, SpreadSimulation::gas_at), 0.0);
+    }
+
+    #[test]
+    fn test_water_flows_downhill() {
+        let slope = Field {
+            wall: -1,
+            slope: 1.0,
+        };
+        let config = SpreadConfig::new().with_min_amount(0.0);
+        let mut sim = SpreadSimulation::new(5, 1, 1).with_config(config);
+        sim.add_water(IVec2::new(0, 0), 2.0);
+        for _ in 0..200 {
+            sim.step(&slope);
+        }
+        assert!((total(&sim, SpreadSimulation::water_at) - 2.0).abs() < 1e-3);
+        // Settles in the lowest two tiles at a common level
+        assert!((sim.water_at(IVec2::new(4, 0)) - 1.5).abs() < 0.01);
+        assert!((sim.water_at(IVec2::new(3, 0)) - 0.5).abs() < 0.01);
+        assert!(sim.water_at(IVec2::new(0, 0)) < 0.01);
+    }
+}
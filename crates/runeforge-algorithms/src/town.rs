@@ -0,0 +1,652 @@
+//! Town generation: streets, blocks, buildings and plazas.
+//!
+//! This module lays out a settlement in three steps:
+//! 1. The map is split recursively into city blocks separated by streets,
+//!    with a street running around the edge
+//! 2. Some blocks become open plazas
+//! 3. The rest are divided into lots, and each street-facing lot may get a
+//!    walled building with a door towards the street
+//!
+//! The result is a [`TownMap`] of typed [`TownTile`]s plus a [`Building`]
+//! record for every house, ready for assigning shops, homes and NPCs.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_algorithms::town::{TownConfig, TownGenerator, TownTile};
+//! use runeforge_random::prelude::Rng;
+//!
+//! let config = TownConfig::default().with_density(0.9);
+//! let mut rng = Rng::with_seed(12);
+//! let town = TownGenerator::generate(80, 50, &config, &mut rng).unwrap();
+//!
+//! for building in town.buildings() {
+//!     assert_eq!(town.tile(building.door), Some(TownTile::Door));
+//! }
+//! println!("{}", town.to_ascii());
+//! ```
+
+use std::fmt;
+
+use runeforge_geometry::prelude::*;
+use runeforge_pathfinding::prelude::{CostGrid, IMPASSABLE};
+use runeforge_random::prelude::*;
+use thiserror::Error;
+
+use crate::features::{BASE_MOVEMENT_COST, CLOSED_DOOR_MOVEMENT_COST};
+use crate::trace::GenerationTrace;
+
+/// Errors that can occur during town generation.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TownError {
+    /// The map can't hold a single block inside its edge street.
+    #[error("Invalid town dimensions: {width}x{height} (too small for one block)")]
+    InvalidDimensions {
+        /// Map width
+        width: u32,
+        /// Map height
+        height: u32,
+    },
+    /// A configuration value would produce a degenerate town.
+    #[error("Invalid town config field '{field}': {reason}")]
+    InvalidConfig {
+        /// Name of the offending field
+        field: &'static str,
+        /// Why the value is rejected
+        reason: String,
+    },
+}
+
+impl TownError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            TownError::InvalidDimensions { .. } => "town.invalid_dimensions",
+            TownError::InvalidConfig { .. } => "town.invalid_config",
+        }
+    }
+}
+
+/// A tile of a generated town.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TownTile {
+    /// A street between blocks.
+    Street,
+    /// Open ground inside a block: yards, alleys and empty lots.
+    Ground,
+    /// A paved square.
+    Plaza,
+    /// A building wall.
+    Wall,
+    /// A building's interior floor.
+    Floor,
+    /// A building's front door.
+    Door,
+}
+
+impl TownTile {
+    /// Returns true if the tile can be walked on.
+    pub fn is_walkable(&self) -> bool {
+        !matches!(self, TownTile::Wall)
+    }
+
+    /// Returns true if the tile blocks line of sight (walls and closed doors).
+    pub fn is_opaque(&self) -> bool {
+        matches!(self, TownTile::Wall | TownTile::Door)
+    }
+
+    /// Returns the character used by [`TownMap::to_ascii`].
+    pub fn glyph(&self) -> char {
+        match self {
+            TownTile::Street => ':',
+            TownTile::Ground => ',',
+            TownTile::Plaza => '_',
+            TownTile::Wall => '#',
+            TownTile::Floor => '.',
+            TownTile::Door => '+',
+        }
+    }
+}
+
+/// A building placed by the generator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Building {
+    /// Outer bounds, walls included (inclusive).
+    pub bounds: Rect,
+    /// The door tile, in the wall facing a street.
+    pub door: IVec2,
+    /// Index of the block the building stands in, see [`TownMap::blocks`].
+    pub block: usize,
+}
+
+impl Building {
+    /// Returns the floor area inside the walls (inclusive bounds).
+    pub fn interior(&self) -> Rect {
+        Rect::new(self.bounds.min + IVec2::ONE, self.bounds.max - IVec2::ONE)
+    }
+}
+
+/// Configuration for town generation. Sizes are in tiles.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TownConfig {
+    /// Smallest block side. Blocks are only split if both halves stay this big.
+    pub min_block_size: u32,
+    /// Blocks with a side longer than this are split.
+    pub max_block_size: u32,
+    /// Street width.
+    pub street_width: u32,
+    /// Smallest building side, walls included.
+    pub min_building_size: u32,
+    /// Largest building side, walls included.
+    pub max_building_size: u32,
+    /// Chance (0.0 to 1.0) that a street-facing lot gets a building.
+    pub density: f32,
+    /// Chance (0.0 to 1.0) that a block becomes a plaza.
+    pub plaza_chance: f32,
+}
+
+impl Default for TownConfig {
+    fn default() -> Self {
+        Self {
+            min_block_size: 8,
+            max_block_size: 20,
+            street_width: 2,
+            min_building_size: 4,
+            max_building_size: 8,
+            density: 0.85,
+            plaza_chance: 0.1,
+        }
+    }
+}
+
+impl TownConfig {
+    /// Creates a new configuration with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the block size range.
+    pub fn with_block_size(mut self, min: u32, max: u32) -> Self {
+        self.min_block_size = min;
+        self.max_block_size = max;
+        self
+    }
+
+    /// Sets the street width.
+    pub fn with_street_width(mut self, width: u32) -> Self {
+        self.street_width = width;
+        self
+    }
+
+    /// Sets the building size range.
+    pub fn with_building_size(mut self, min: u32, max: u32) -> Self {
+        self.min_building_size = min;
+        self.max_building_size = max;
+        self
+    }
+
+    /// Sets the building density (clamped to 0.0-1.0).
+    pub fn with_density(mut self, density: f32) -> Self {
+        self.density = density.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the plaza chance (clamped to 0.0-1.0).
+    pub fn with_plaza_chance(mut self, chance: f32) -> Self {
+        self.plaza_chance = chance.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Checks that the configuration produces a town.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TownError::InvalidConfig` naming the first offending field.
+    pub fn validate(&self) -> Result<(), TownError> {
+        let invalid = |field, reason: String| Err(TownError::InvalidConfig { field, reason });
+        if self.street_width == 0 {
+            return invalid("street_width", "must be at least 1".to_string());
+        }
+        if self.min_building_size < 3 {
+            return invalid(
+                "min_building_size",
+                format!("{} leaves no room inside the walls", self.min_building_size),
+            );
+        }
+        if self.max_building_size < self.min_building_size {
+            return invalid(
+                "max_building_size",
+                format!(
+                    "{} is below min_building_size ({})",
+                    self.max_building_size, self.min_building_size
+                ),
+            );
+        }
+        if self.min_block_size < self.min_building_size + 2 {
+            return invalid(
+                "min_block_size",
+                format!(
+                    "{} can't fit a building of {} plus its yard",
+                    self.min_block_size, self.min_building_size
+                ),
+            );
+        }
+        if self.max_block_size < self.min_block_size {
+            return invalid(
+                "max_block_size",
+                format!(
+                    "{} is below min_block_size ({})",
+                    self.max_block_size, self.min_block_size
+                ),
+            );
+        }
+        for (field, value) in [
+            ("density", self.density),
+            ("plaza_chance", self.plaza_chance),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                return invalid(field, format!("{} is outside 0.0-1.0", value));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A generated town.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TownMap {
+    width: u32,
+    height: u32,
+    tiles: Vec<TownTile>,
+    blocks: Vec<Rect>,
+    plazas: Vec<usize>,
+    buildings: Vec<Building>,
+}
+
+impl TownMap {
+    /// Creates a town that is all street.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            tiles: vec![TownTile::Street; (width * height) as usize],
+            blocks: Vec::new(),
+            plazas: Vec::new(),
+            buildings: Vec::new(),
+        }
+    }
+
+    /// Returns the map width.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the map height.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the tile at a position, or `None` outside the map.
+    pub fn tile(&self, pos: IVec2) -> Option<TownTile> {
+        self.index(pos).map(|i| self.tiles[i])
+    }
+
+    /// Sets the tile at a position. Does nothing outside the map.
+    pub fn set_tile(&mut self, pos: IVec2, tile: TownTile) {
+        if let Some(i) = self.index(pos) {
+            self.tiles[i] = tile;
+        }
+    }
+
+    /// Returns all tiles in row-major order.
+    pub fn tiles(&self) -> &[TownTile] {
+        &self.tiles
+    }
+
+    /// Returns true if the position can be walked on.
+    pub fn is_walkable(&self, pos: IVec2) -> bool {
+        self.tile(pos).is_some_and(|t| t.is_walkable())
+    }
+
+    /// Returns true if the position blocks line of sight. Outside the map is opaque.
+    pub fn is_opaque(&self, pos: IVec2) -> bool {
+        self.tile(pos).is_none_or(|t| t.is_opaque())
+    }
+
+    /// Returns the city blocks (inclusive bounds), streets excluded.
+    pub fn blocks(&self) -> &[Rect] {
+        &self.blocks
+    }
+
+    /// Returns the indices of the blocks that are plazas.
+    pub fn plazas(&self) -> &[usize] {
+        &self.plazas
+    }
+
+    /// Returns every building.
+    pub fn buildings(&self) -> &[Building] {
+        &self.buildings
+    }
+
+    /// Returns the index of the building covering a position, walls included.
+    pub fn building_at(&self, pos: IVec2) -> Option<usize> {
+        self.buildings.iter().position(|b| contains(b.bounds, pos))
+    }
+
+    /// Renders the town with [`TownTile::glyph`].
+    pub fn to_ascii(&self) -> String {
+        let mut out = String::with_capacity(((self.width + 1) * self.height) as usize);
+        for row in self.tiles.chunks(self.width as usize) {
+            out.extend(row.iter().map(TownTile::glyph));
+            out.push('\n');
+        }
+        out
+    }
+
+    fn walkable_mask(&self) -> Vec<bool> {
+        self.tiles.iter().map(TownTile::is_walkable).collect()
+    }
+
+    fn index(&self, pos: IVec2) -> Option<usize> {
+        (pos.x >= 0 && pos.y >= 0 && pos.x < self.width as i32 && pos.y < self.height as i32)
+            .then(|| (pos.y as u32 * self.width + pos.x as u32) as usize)
+    }
+}
+
+impl fmt::Display for TownMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_ascii())
+    }
+}
+
+impl From<&TownMap> for CostGrid {
+    /// Walkable tiles cost 1 (doors cost more); walls are impassable.
+    fn from(town: &TownMap) -> Self {
+        CostGrid::from_fn(town.width, town.height, |pos| match town.tile(pos) {
+            Some(TownTile::Wall) | None => IMPASSABLE,
+            Some(TownTile::Door) => CLOSED_DOOR_MOVEMENT_COST,
+            Some(_) => BASE_MOVEMENT_COST,
+        })
+    }
+}
+
+/// Generator for towns.
+pub struct TownGenerator;
+
+impl TownGenerator {
+    /// Generates a town.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TownError::InvalidDimensions` if the map can't fit one block
+    /// inside the edge street, or an error from [`TownConfig::validate`].
+    pub fn generate(
+        width: u32,
+        height: u32,
+        config: &TownConfig,
+        rng: &mut Rng,
+    ) -> Result<TownMap, TownError> {
+        Self::generate_inner(width, height, config, rng, None)
+    }
+
+    /// Generates a town, recording the street layout and the buildings.
+    ///
+    /// Produces the same map as [`generate`](Self::generate) for the same seed.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`generate`](Self::generate).
+    pub fn generate_traced(
+        width: u32,
+        height: u32,
+        config: &TownConfig,
+        rng: &mut Rng,
+        trace: &mut GenerationTrace,
+    ) -> Result<TownMap, TownError> {
+        Self::generate_inner(width, height, config, rng, Some(trace))
+    }
+
+    fn generate_inner(
+        width: u32,
+        height: u32,
+        config: &TownConfig,
+        rng: &mut Rng,
+        mut trace: Option<&mut GenerationTrace>,
+    ) -> Result<TownMap, TownError> {
+        config.validate()?;
+        let street = config.street_width;
+        let needed = config.min_block_size + 2 * street;
+        if width < needed || height < needed {
+            return Err(TownError::InvalidDimensions { width, height });
+        }
+
+        let mut town = TownMap::new(width, height);
+        let inner = Rect::new(
+            IVec2::splat(street as i32),
+            IVec2::new((width - street) as i32 - 1, (height - street) as i32 - 1),
+        );
+        split(
+            inner,
+            config.min_block_size,
+            config.max_block_size,
+            street,
+            rng,
+            &mut town.blocks,
+        );
+        for block in town.blocks.clone() {
+            block.for_each(|p| town.set_tile(p, TownTile::Ground));
+        }
+        if let Some(trace) = trace.as_deref_mut() {
+            let mask = town.walkable_mask();
+            trace.record(
+                "blocks",
+                width,
+                height,
+                &mask,
+                Vec::new(),
+                town.blocks.clone(),
+            );
+        }
+
+        for index in 0..town.blocks.len() {
+            let block = town.blocks[index];
+            if rng.chance(config.plaza_chance) {
+                block.for_each(|p| town.set_tile(p, TownTile::Plaza));
+                town.plazas.push(index);
+                continue;
+            }
+
+            let mut lots = Vec::new();
+            split(
+                block,
+                config.min_building_size + 2,
+                config.max_building_size + 2,
+                0,
+                rng,
+                &mut lots,
+            );
+            for lot in lots {
+                let street_sides: Vec<IVec2> = [
+                    (lot.min.y == block.min.y, IVec2::NEG_Y),
+                    (lot.max.y == block.max.y, IVec2::Y),
+                    (lot.min.x == block.min.x, IVec2::NEG_X),
+                    (lot.max.x == block.max.x, IVec2::X),
+                ]
+                .into_iter()
+                .filter_map(|(faces, side)| faces.then_some(side))
+                .collect();
+                let Some(&side) = rng.choose(&street_sides) else {
+                    continue; // A courtyard, cut off from the street.
+                };
+                if !rng.chance(config.density) {
+                    continue;
+                }
+                let building = place_building(&mut town, lot, side, index, rng);
+                town.buildings.push(building);
+            }
+        }
+
+        if let Some(trace) = trace {
+            let mask = town.walkable_mask();
+            let doors = town.buildings.iter().map(|b| b.door).collect();
+            let bounds = town.buildings.iter().map(|b| b.bounds).collect();
+            trace.record("buildings", width, height, &mask, doors, bounds);
+        }
+
+        Ok(town)
+    }
+}
+
+/// Builds walls one tile inside a lot and puts the door in the wall facing
+/// `side`.
+fn place_building(
+    town: &mut TownMap,
+    lot: Rect,
+    side: IVec2,
+    block: usize,
+    rng: &mut Rng,
+) -> Building {
+    let bounds = Rect::new(lot.min + IVec2::ONE, lot.max - IVec2::ONE);
+    bounds.for_each(|p| {
+        let edge = p.x == bounds.min.x
+            || p.x == bounds.max.x
+            || p.y == bounds.min.y
+            || p.y == bounds.max.y;
+        town.set_tile(
+            p,
+            if edge {
+                TownTile::Wall
+            } else {
+                TownTile::Floor
+            },
+        );
+    });
+
+    let door = if side.x == 0 {
+        let y = if side.y < 0 {
+            bounds.min.y
+        } else {
+            bounds.max.y
+        };
+        IVec2::new(rng.range(bounds.min.x + 1, bounds.max.x - 1), y)
+    } else {
+        let x = if side.x < 0 {
+            bounds.min.x
+        } else {
+            bounds.max.x
+        };
+        IVec2::new(x, rng.range(bounds.min.y + 1, bounds.max.y - 1))
+    };
+    town.set_tile(door, TownTile::Door);
+
+    Building {
+        bounds,
+        door,
+        block,
+    }
+}
+
+/// Recursively splits `rect` (inclusive bounds) until no side exceeds `max`,
+/// leaving a `gap` between the halves. Halves are never smaller than `min`.
+fn split(rect: Rect, min: u32, max: u32, gap: u32, rng: &mut Rng, out: &mut Vec<Rect>) {
+    let size = (rect.max - rect.min) + IVec2::ONE;
+    let (min, max, gap) = (min as i32, max as i32, gap as i32);
+    let can_split = |len: i32| len > max && len >= 2 * min + gap;
+    let horizontal = match (can_split(size.x), can_split(size.y)) {
+        (false, false) => {
+            out.push(rect);
+            return;
+        }
+        (true, true) => size.x >= size.y,
+        (x, _) => x,
+    };
+
+    let len = if horizontal { size.x } else { size.y };
+    let first = rng.range(min, len - gap - min);
+    let (a, b) = if horizontal {
+        let cut = rect.min.x + first;
+        (
+            Rect::new(rect.min, IVec2::new(cut - 1, rect.max.y)),
+            Rect::new(IVec2::new(cut + gap, rect.min.y), rect.max),
+        )
+    } else {
+        let cut = rect.min.y + first;
+        (
+            Rect::new(rect.min, IVec2::new(rect.max.x, cut - 1)),
+            Rect::new(IVec2::new(rect.min.x, cut + gap), rect.max),
+        )
+    };
+    split(a, min as u32, max as u32, gap as u32, rng, out);
+    split(b, min as u32, max as u32, gap as u32, rng, out);
+}
+
+fn contains(rect: Rect, p: IVec2) -> bool {
+    p.x >= rect.min.x && p.x <= rect.max.x && p.y >= rect.min.y && p.y <= rect.max.y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runeforge_pathfinding::prelude::PathFinder;
+
+    #[test]
+    fn test_doors_face_streets() {
+        let config = TownConfig::default()
+            .with_density(1.0)
+            .with_plaza_chance(0.0);
+        let town = TownGenerator::generate(70, 45, &config, &mut Rng::with_seed(4)).unwrap();
+        assert!(town.blocks().len() > 1);
+        assert!(!town.buildings().is_empty());
+
+        let mut grid = CostGrid::from(&town);
+        for building in town.buildings() {
+            assert_eq!(town.tile(building.door), Some(TownTile::Door));
+            assert!(town.is_opaque(building.door));
+            let inside = building.interior().center();
+            assert_eq!(town.tile(inside), Some(TownTile::Floor));
+            let path = PathFinder::Astar.compute(IVec2::ZERO, inside, &mut grid, ());
+            assert!(!path.is_empty(), "{:?} is unreachable", building);
+            assert_eq!(
+                town.building_at(inside),
+                Some(town.building_at(building.door).unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn test_blocks_respect_sizes_and_streets() {
+        let config = TownConfig::default()
+            .with_block_size(10, 14)
+            .with_street_width(3);
+        let mut trace = GenerationTrace::new();
+        let town =
+            TownGenerator::generate_traced(60, 60, &config, &mut Rng::with_seed(9), &mut trace)
+                .unwrap();
+        for block in town.blocks() {
+            let size = block.max - block.min + IVec2::ONE;
+            assert!(size.x >= 10 && size.y >= 10);
+            // Sides over the maximum only survive when too short to split.
+            assert!(size.x <= 14 || size.x < 2 * 10 + 3);
+            assert!(size.y <= 14 || size.y < 2 * 10 + 3);
+        }
+        for i in 0..60 {
+            for edge in [0, 1, 2, 57, 58, 59] {
+                assert_eq!(town.tile(IVec2::new(i, edge)), Some(TownTile::Street));
+            }
+        }
+        assert_eq!(trace.len(), 2);
+    }
+
+    #[test]
+    fn test_plazas_and_errors() {
+        let config = TownConfig::default().with_plaza_chance(1.0);
+        let town = TownGenerator::generate(40, 40, &config, &mut Rng::with_seed(1)).unwrap();
+        assert_eq!(town.plazas().len(), town.blocks().len());
+        assert!(town.buildings().is_empty());
+
+        let err = TownGenerator::generate(10, 40, &config, &mut Rng::with_seed(1)).unwrap_err();
+        assert_eq!(err.code(), "town.invalid_dimensions");
+        let config = TownConfig::default().with_building_size(6, 5);
+        assert_eq!(config.validate().unwrap_err().code(), "town.invalid_config");
+    }
+}
@@ -0,0 +1,424 @@
+//! Step-by-step recording of map generation.
+//!
+//! Passing a [`GenerationTrace`] to one of the `generate_traced` functions
+//! records a [`TraceFrame`] at each interesting step: BSP splits and corridors,
+//! every cellular automata iteration, or the path of the drunkard's walk.
+//! Frames can be replayed to see why a configuration produces bad maps.
+//!
+//! [`TraceFrame::to_ascii`] gives quick text dumps. With the `terminal`
+//! feature, `GenerationTrace::replay` steps through the frames on any
+//! `Console`, and `GenerationTrace::save_png_sequence` writes each frame as a
+//! numbered PNG through the software renderer.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_algorithms::prelude::*;
+//!
+//! let mut trace = GenerationTrace::new();
+//! let mut rng = Rng::with_seed(4);
+//! let config = CaveConfig::new().with_iterations(3);
+//! let cave = CaveGenerator::generate_traced(20, 10, &config, &mut rng, &mut trace).unwrap();
+//!
+//! // The initial noise plus one frame per iteration.
+//! assert_eq!(trace.len(), 4);
+//! assert_eq!(trace.frames()[0].label, "initial noise");
+//!
+//! // The last frame matches the finished map.
+//! assert_eq!(trace.last().unwrap().tiles, cave.tiles());
+//! for frame in trace.frames() {
+//!     println!("{}\n{}", frame.label, frame.to_ascii());
+//! }
+//! ```
+
+#[cfg(feature = "terminal")]
+use std::io;
+#[cfg(feature = "terminal")]
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "terminal")]
+use runeforge_color::Color;
+use runeforge_geometry::prelude::*;
+#[cfg(feature = "terminal")]
+use runeforge_terminal::prelude::{Cell, Console, SoftwareRenderer};
+
+/// What a single cell of a [`TraceFrame`] shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceTile {
+    /// A wall tile.
+    Wall,
+    /// A floor tile.
+    Floor,
+    /// The border of a region recorded with the frame (e.g. a BSP partition).
+    RegionBorder,
+    /// A tile highlighted by the step (e.g. a new corridor or walk path).
+    Highlight,
+}
+
+impl TraceTile {
+    /// Returns the glyph used by [`TraceFrame::to_ascii`].
+    pub fn glyph(self) -> char {
+        match self {
+            TraceTile::Wall => '#',
+            TraceTile::Floor => '.',
+            TraceTile::RegionBorder => '+',
+            TraceTile::Highlight => '*',
+        }
+    }
+
+    /// Returns the cell used to draw this tile on a console.
+    #[cfg(feature = "terminal")]
+    pub fn cell(self) -> Cell {
+        let fg = match self {
+            TraceTile::Wall => Color::GRAY,
+            TraceTile::Floor => Color::DARK_GRAY,
+            TraceTile::RegionBorder => Color::CYAN,
+            TraceTile::Highlight => Color::YELLOW,
+        };
+        Cell::new(self.glyph(), fg, Color::BLACK)
+    }
+}
+
+/// A snapshot of a map during generation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceFrame {
+    /// Short description of the step
+    pub label: String,
+    /// Map width
+    pub width: u32,
+    /// Map height
+    pub height: u32,
+    /// Floor (true) / wall (false) tiles in row-major order
+    pub tiles: Vec<bool>,
+    /// Tiles touched by this step
+    pub highlights: Vec<IVec2>,
+    /// Regions relevant to this step (e.g. BSP partitions)
+    pub regions: Vec<Rect>,
+}
+
+impl TraceFrame {
+    /// Returns what the frame shows at a position, or `None` if out of bounds.
+    ///
+    /// Highlights take priority over region borders, which take priority over
+    /// the underlying floor or wall.
+    pub fn tile_at(&self, pos: IVec2) -> Option<TraceTile> {
+        if pos.x < 0 || pos.y < 0 || pos.x >= self.width as i32 || pos.y >= self.height as i32 {
+            return None;
+        }
+        if self.highlights.contains(&pos) {
+            return Some(TraceTile::Highlight);
+        }
+        let on_border = self.regions.iter().any(|r| {
+            let inside =
+                pos.x >= r.min.x && pos.x <= r.max.x && pos.y >= r.min.y && pos.y <= r.max.y;
+            inside && (pos.x == r.min.x || pos.x == r.max.x || pos.y == r.min.y || pos.y == r.max.y)
+        });
+        if on_border {
+            return Some(TraceTile::RegionBorder);
+        }
+        let idx = (pos.y as u32 * self.width + pos.x as u32) as usize;
+        Some(if self.tiles[idx] {
+            TraceTile::Floor
+        } else {
+            TraceTile::Wall
+        })
+    }
+
+    /// Renders the frame as text, one line per row.
+    pub fn to_ascii(&self) -> String {
+        let mut out = String::with_capacity(((self.width + 1) * self.height) as usize);
+        for y in 0..self.height as i32 {
+            for x in 0..self.width as i32 {
+                out.push(self.tile_at(IVec2::new(x, y)).unwrap().glyph());
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Draws the frame with its top-left corner at `origin`. Tiles that fall
+    /// outside the console are skipped.
+    #[cfg(feature = "terminal")]
+    pub fn draw<C: Console + ?Sized>(&self, console: &mut C, origin: IVec2) {
+        for y in 0..self.height as i32 {
+            for x in 0..self.width as i32 {
+                let pos = IVec2::new(x, y);
+                console.set(origin + pos, self.tile_at(pos).unwrap().cell());
+            }
+        }
+    }
+}
+
+/// An ordered recording of generation steps.
+#[derive(Debug, Clone)]
+pub struct GenerationTrace {
+    frames: Vec<TraceFrame>,
+    interval: u32,
+}
+
+impl Default for GenerationTrace {
+    fn default() -> Self {
+        Self {
+            frames: Vec::new(),
+            interval: 100,
+        }
+    }
+}
+
+impl GenerationTrace {
+    /// Creates an empty trace.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how many steps fine-grained generators (like the drunkard's walk)
+    /// take between frames. Defaults to 100.
+    pub fn with_interval(mut self, steps: u32) -> Self {
+        self.interval = steps.max(1);
+        self
+    }
+
+    /// Returns the frame interval for fine-grained generators.
+    pub fn interval(&self) -> u32 {
+        self.interval
+    }
+
+    /// Records a frame.
+    pub fn record(
+        &mut self,
+        label: impl Into<String>,
+        width: u32,
+        height: u32,
+        tiles: &[bool],
+        highlights: Vec<IVec2>,
+        regions: Vec<Rect>,
+    ) {
+        self.frames.push(TraceFrame {
+            label: label.into(),
+            width,
+            height,
+            tiles: tiles.to_vec(),
+            highlights,
+            regions,
+        });
+    }
+
+    /// Returns the recorded frames in order.
+    pub fn frames(&self) -> &[TraceFrame] {
+        &self.frames
+    }
+
+    /// Returns the last recorded frame.
+    pub fn last(&self) -> Option<&TraceFrame> {
+        self.frames.last()
+    }
+
+    /// Returns the number of frames.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns true if nothing was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Removes all frames.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    /// Plays the frames back in order on any console.
+    ///
+    /// Each frame is drawn on a cleared console and presented, then `next`
+    /// is called with it; this is where to wait for a key or sleep between
+    /// frames. Returning false stops the replay. Returns the number of
+    /// frames shown.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use runeforge_algorithms::prelude::*;
+    /// use runeforge_terminal::rfc::ConsoleImage;
+    ///
+    /// let mut trace = GenerationTrace::new();
+    /// let config = CaveConfig::new().with_iterations(3);
+    /// CaveGenerator::generate_traced(20, 10, &config, &mut Rng::with_seed(4), &mut trace)
+    ///     .unwrap();
+    ///
+    /// let mut console = ConsoleImage::new(20, 10);
+    /// let shown = trace.replay(&mut console, |frame| {
+    ///     println!("{}", frame.label);
+    ///     // std::thread::sleep(std::time::Duration::from_millis(200));
+    ///     true
+    /// });
+    /// assert_eq!(shown, trace.len());
+    /// ```
+    #[cfg(feature = "terminal")]
+    pub fn replay<C: Console + ?Sized>(
+        &self,
+        console: &mut C,
+        mut next: impl FnMut(&TraceFrame) -> bool,
+    ) -> usize {
+        let mut shown = 0;
+        for frame in &self.frames {
+            console.clear();
+            frame.draw(console, IVec2::ZERO);
+            console.present();
+            shown += 1;
+            if !next(frame) {
+                break;
+            }
+        }
+        shown
+    }
+
+    /// Renders every frame with `renderer` and saves it as a PNG in `dir`,
+    /// named `frame_0000.png`, `frame_0001.png` and so on so the files sort
+    /// in order. `dir` is created if needed. Returns the paths written.
+    ///
+    /// The renderer's font and cell size decide how the images look; frames
+    /// larger than the renderer are cut off.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory can't be created or a file can't
+    /// be written.
+    #[cfg(feature = "terminal")]
+    pub fn save_png_sequence<P: AsRef<Path>>(
+        &self,
+        renderer: &mut SoftwareRenderer,
+        dir: P,
+    ) -> io::Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+
+        let mut paths = Vec::with_capacity(self.frames.len());
+        for (i, frame) in self.frames.iter().enumerate() {
+            renderer.clear();
+            frame.draw(renderer, IVec2::ZERO);
+            let path = dir.join(format!("frame_{i:04}.png"));
+            renderer.save_png(&path)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tile_priority() {
+        let mut trace = GenerationTrace::new();
+        trace.record(
+            "test",
+            4,
+            3,
+            &[
+                false, true, true, false, false, true, true, false, false, true, true, false,
+            ],
+            vec![IVec2::new(1, 1)],
+            vec![Rect::new_xywh(0, 0, 2, 1)],
+        );
+        let frame = trace.last().unwrap();
+        assert_eq!(frame.tile_at(IVec2::new(1, 1)), Some(TraceTile::Highlight));
+        assert_eq!(
+            frame.tile_at(IVec2::new(0, 0)),
+            Some(TraceTile::RegionBorder)
+        );
+        assert_eq!(frame.tile_at(IVec2::new(3, 2)), Some(TraceTile::Wall));
+        assert_eq!(frame.tile_at(IVec2::new(4, 0)), None);
+        assert_eq!(frame.to_ascii(), "+++#\n+*+#\n#..#\n");
+    }
+
+    #[test]
+    fn test_interval_minimum() {
+        assert_eq!(GenerationTrace::new().with_interval(0).interval(), 1);
+    }
+
+    #[cfg(feature = "terminal")]
+    fn two_frames() -> GenerationTrace {
+        let mut trace = GenerationTrace::new();
+        trace.record("walls", 3, 2, &[false; 6], vec![], vec![]);
+        trace.record("dug", 3, 2, &[true; 6], vec![IVec2::new(2, 1)], vec![]);
+        trace
+    }
+
+    #[cfg(feature = "terminal")]
+    #[test]
+    fn test_replay_draws_frames_until_stopped() {
+        use runeforge_terminal::rfc::ConsoleImage;
+
+        let trace = two_frames();
+        let mut console = ConsoleImage::new(4, 2);
+        let mut labels = Vec::new();
+        let shown = trace.replay(&mut console, |frame| {
+            labels.push(frame.label.clone());
+            true
+        });
+        assert_eq!(shown, 2);
+        assert_eq!(labels, ["walls", "dug"]);
+        assert_eq!(console.get(IVec2::ZERO), Some(TraceTile::Floor.cell()));
+        assert_eq!(console.get(IVec2::new(2, 1)).unwrap().ch, '*');
+        // Outside the frame
+        assert_eq!(console.get(IVec2::new(3, 0)), Some(Cell::empty()));
+
+        assert_eq!(trace.replay(&mut console, |_| false), 1);
+        assert_eq!(console.get(IVec2::ZERO), Some(TraceTile::Wall.cell()));
+    }
+
+    #[cfg(feature = "terminal")]
+    #[test]
+    fn test_save_png_sequence() {
+        use runeforge_tileset::prelude::{Font, RenderedGlyph};
+
+        struct Block;
+
+        impl Font for Block {
+            fn name(&self) -> &str {
+                "block"
+            }
+
+            fn cell_width(&self) -> u32 {
+                2
+            }
+
+            fn cell_height(&self) -> u32 {
+                2
+            }
+
+            fn line_height(&self) -> u32 {
+                2
+            }
+
+            fn render_glyph(&self, c: char) -> Option<RenderedGlyph> {
+                Some(RenderedGlyph {
+                    character: c,
+                    width: 2,
+                    height: 2,
+                    bearing_x: 0,
+                    bearing_y: 2,
+                    bitmap: vec![255; 4],
+                    rgba: None,
+                })
+            }
+
+            fn has_glyph(&self, _c: char) -> bool {
+                true
+            }
+        }
+
+        let dir = std::env::temp_dir().join(format!("runeforge-trace-{}", std::process::id()));
+        let mut renderer = SoftwareRenderer::new(3, 2, &Block);
+        let paths = two_frames().save_png_sequence(&mut renderer, &dir).unwrap();
+
+        assert_eq!(
+            paths,
+            [dir.join("frame_0000.png"), dir.join("frame_0001.png")]
+        );
+        assert!(paths.iter().all(|p| p.is_file()));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,333 @@
+//! Voronoi region partitioning on a tile grid.
+//!
+//! A [`VoronoiMap`] assigns every tile to its nearest seed point, splitting a
+//! map into regions for zone-based generation, territory assignment or biome
+//! cells. The map also provides:
+//! - Region adjacency (the grid's Delaunay graph), for connecting zones
+//! - Region borders, for walls, roads or rivers between cells
+//! - Lloyd relaxation, which evens out region sizes
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_algorithms::voronoi::VoronoiMap;
+//! use runeforge_geometry::prelude::*;
+//! use runeforge_random::prelude::Rng;
+//!
+//! let mut rng = Rng::with_seed(3);
+//! let mut zones = VoronoiMap::random(60, 40, 8, Distance::Pythagoras, &mut rng).unwrap();
+//! zones.relax(2);
+//!
+//! let home = zones.region_at(IVec2::new(30, 20)).unwrap();
+//! for neighbor in zones.neighbors(home) {
+//!     println!("zone {} borders zone {}", home, neighbor);
+//! }
+//! ```
+
+use std::collections::BTreeSet;
+
+use runeforge_geometry::prelude::{Distance, IVec2, Vec2};
+use runeforge_random::prelude::*;
+use thiserror::Error;
+
+/// Errors that can occur when building a Voronoi map.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum VoronoiError {
+    /// Invalid dimensions (width or height is zero).
+    #[error("Invalid Voronoi dimensions: {width}x{height} (both must be > 0)")]
+    InvalidDimensions {
+        /// Map width
+        width: u32,
+        /// Map height
+        height: u32,
+    },
+    /// No seed points were given.
+    #[error("A Voronoi map needs at least one seed point")]
+    NoSeeds,
+}
+
+impl VoronoiError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            VoronoiError::InvalidDimensions { .. } => "voronoi.invalid_dimensions",
+            VoronoiError::NoSeeds => "voronoi.no_seeds",
+        }
+    }
+}
+
+/// A grid where every tile belongs to the region of its nearest seed.
+///
+/// Regions are numbered by seed index. Ties go to the lower index.
+#[derive(Debug, Clone)]
+pub struct VoronoiMap {
+    width: u32,
+    height: u32,
+    metric: Distance,
+    seeds: Vec<IVec2>,
+    regions: Vec<usize>,
+}
+
+impl VoronoiMap {
+    /// Partitions a map around the given seed points.
+    ///
+    /// # Errors
+    ///
+    /// Returns `VoronoiError::InvalidDimensions` if width or height is zero,
+    /// or `VoronoiError::NoSeeds` if `seeds` is empty.
+    pub fn new(
+        width: u32,
+        height: u32,
+        seeds: Vec<IVec2>,
+        metric: Distance,
+    ) -> Result<Self, VoronoiError> {
+        if width == 0 || height == 0 {
+            return Err(VoronoiError::InvalidDimensions { width, height });
+        }
+        if seeds.is_empty() {
+            return Err(VoronoiError::NoSeeds);
+        }
+        let mut map = Self {
+            width,
+            height,
+            metric,
+            seeds,
+            regions: vec![0; (width * height) as usize],
+        };
+        map.assign();
+        Ok(map)
+    }
+
+    /// Partitions a map around `count` distinct random seed points.
+    ///
+    /// If the map has fewer tiles than `count`, every tile becomes a seed.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`new`](Self::new).
+    pub fn random(
+        width: u32,
+        height: u32,
+        count: usize,
+        metric: Distance,
+        rng: &mut Rng,
+    ) -> Result<Self, VoronoiError> {
+        if width == 0 || height == 0 {
+            return Err(VoronoiError::InvalidDimensions { width, height });
+        }
+        let mut tiles: Vec<IVec2> = (0..height as i32)
+            .flat_map(|y| (0..width as i32).map(move |x| IVec2::new(x, y)))
+            .collect();
+        rng.shuffle(&mut tiles);
+        tiles.truncate(count);
+        Self::new(width, height, tiles, metric)
+    }
+
+    /// Returns the map width.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the map height.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the seed points, indexed by region.
+    pub fn seeds(&self) -> &[IVec2] {
+        &self.seeds
+    }
+
+    /// Returns the number of regions.
+    pub fn region_count(&self) -> usize {
+        self.seeds.len()
+    }
+
+    /// Returns the region of every tile in row-major order.
+    pub fn regions(&self) -> &[usize] {
+        &self.regions
+    }
+
+    /// Returns the region a tile belongs to, or `None` outside the map.
+    pub fn region_at(&self, pos: IVec2) -> Option<usize> {
+        self.index(pos).map(|i| self.regions[i])
+    }
+
+    /// Returns every tile in a region.
+    pub fn region_tiles(&self, region: usize) -> Vec<IVec2> {
+        self.tiles()
+            .filter(|&(_, r)| r == region)
+            .map(|(p, _)| p)
+            .collect()
+    }
+
+    /// Returns the number of tiles in each region.
+    pub fn region_sizes(&self) -> Vec<usize> {
+        let mut sizes = vec![0; self.seeds.len()];
+        for &r in &self.regions {
+            sizes[r] += 1;
+        }
+        sizes
+    }
+
+    /// Returns the center of mass of each region. Empty regions report
+    /// their seed.
+    pub fn centroids(&self) -> Vec<Vec2> {
+        let mut sums = vec![(Vec2::ZERO, 0u32); self.seeds.len()];
+        for (p, r) in self.tiles() {
+            sums[r].0 += p.as_vec2();
+            sums[r].1 += 1;
+        }
+        sums.into_iter()
+            .zip(&self.seeds)
+            .map(|((sum, n), seed)| {
+                if n == 0 {
+                    seed.as_vec2()
+                } else {
+                    sum / n as f32
+                }
+            })
+            .collect()
+    }
+
+    /// Returns true if a tile touches a different region on one of its four sides.
+    pub fn is_border(&self, pos: IVec2) -> bool {
+        let Some(region) = self.region_at(pos) else {
+            return false;
+        };
+        [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y]
+            .into_iter()
+            .filter_map(|d| self.region_at(pos + d))
+            .any(|r| r != region)
+    }
+
+    /// Returns the regions that share an edge with `region`, in ascending order.
+    pub fn neighbors(&self, region: usize) -> Vec<usize> {
+        self.adjacency()
+            .into_iter()
+            .filter_map(|(a, b)| match (a == region, b == region) {
+                (true, _) => Some(b),
+                (_, true) => Some(a),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns every pair of regions that share an edge, as `(lower, higher)`
+    /// in ascending order.
+    ///
+    /// On a grid this is the Delaunay graph of the seeds: use it to place
+    /// corridors or roads between neighboring zones.
+    pub fn adjacency(&self) -> Vec<(usize, usize)> {
+        let mut edges = BTreeSet::new();
+        for (p, r) in self.tiles() {
+            for d in [IVec2::X, IVec2::Y] {
+                if let Some(other) = self.region_at(p + d).filter(|&o| o != r) {
+                    edges.insert((r.min(other), r.max(other)));
+                }
+            }
+        }
+        edges.into_iter().collect()
+    }
+
+    /// Runs Lloyd relaxation: moves each seed to its region's centroid and
+    /// reassigns tiles, `iterations` times. Regions become rounder and more
+    /// even in size.
+    pub fn relax(&mut self, iterations: u32) {
+        for _ in 0..iterations {
+            let moved: Vec<IVec2> = self
+                .centroids()
+                .into_iter()
+                .map(|c| c.round().as_ivec2())
+                .collect();
+            if moved == self.seeds {
+                break;
+            }
+            self.seeds = moved;
+            self.assign();
+        }
+    }
+
+    fn assign(&mut self) {
+        for y in 0..self.height as i32 {
+            for x in 0..self.width as i32 {
+                let p = IVec2::new(x, y);
+                let mut best = (0, f32::INFINITY);
+                for (i, &seed) in self.seeds.iter().enumerate() {
+                    let d = self.metric.distance2d(p, seed);
+                    if d < best.1 {
+                        best = (i, d);
+                    }
+                }
+                self.regions[(y as u32 * self.width + x as u32) as usize] = best.0;
+            }
+        }
+    }
+
+    fn tiles(&self) -> impl Iterator<Item = (IVec2, usize)> + '_ {
+        self.regions.iter().enumerate().map(|(i, &r)| {
+            let i = i as u32;
+            (
+                IVec2::new((i % self.width) as i32, (i / self.width) as i32),
+                r,
+            )
+        })
+    }
+
+    fn index(&self, pos: IVec2) -> Option<usize> {
+        if pos.x < 0 || pos.y < 0 || pos.x >= self.width as i32 || pos.y >= self.height as i32 {
+            return None;
+        }
+        Some((pos.y as u32 * self.width + pos.x as u32) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_seeds_split_in_half() {
+        let map = VoronoiMap::new(
+            10,
+            4,
+            vec![IVec2::new(0, 0), IVec2::new(9, 0)],
+            Distance::Manhattan,
+        )
+        .unwrap();
+        assert_eq!(map.region_sizes(), vec![20, 20]);
+        assert_eq!(map.region_at(IVec2::new(4, 2)), Some(0));
+        assert_eq!(map.region_at(IVec2::new(5, 2)), Some(1));
+        assert!(map.is_border(IVec2::new(4, 0)));
+        assert!(!map.is_border(IVec2::new(0, 0)));
+        assert_eq!(map.adjacency(), vec![(0, 1)]);
+        assert_eq!(map.neighbors(1), vec![0]);
+    }
+
+    #[test]
+    fn test_relax_evens_out_sizes() {
+        let seeds = vec![IVec2::new(0, 0), IVec2::new(1, 0), IVec2::new(2, 0)];
+        let mut map = VoronoiMap::new(30, 30, seeds, Distance::Pythagoras).unwrap();
+        let spread = |m: &VoronoiMap| {
+            let sizes = m.region_sizes();
+            sizes.iter().max().unwrap() - sizes.iter().min().unwrap()
+        };
+        let before = spread(&map);
+        map.relax(10);
+        assert!(spread(&map) < before);
+        assert_eq!(map.region_sizes().iter().sum::<usize>(), 900);
+    }
+
+    #[test]
+    fn test_random_and_errors() {
+        let mut rng = Rng::with_seed(1);
+        let map = VoronoiMap::random(3, 3, 20, Distance::Chebyshev, &mut rng).unwrap();
+        assert_eq!(map.region_count(), 9);
+        assert!(map.region_sizes().iter().all(|&s| s == 1));
+
+        let err = VoronoiMap::new(4, 4, Vec::new(), Distance::Manhattan).unwrap_err();
+        assert_eq!(err.code(), "voronoi.no_seeds");
+        let err = VoronoiMap::new(0, 4, vec![IVec2::ZERO], Distance::Manhattan).unwrap_err();
+        assert_eq!(err.code(), "voronoi.invalid_dimensions");
+    }
+}
@@ -0,0 +1,188 @@
+//! Time-based color effects: pulses, flashes, fades and keyframe sequences.
+//!
+//! Every function takes the elapsed time `t` (in seconds, or any unit as long
+//! as it matches the durations) and returns the color to draw this frame, so
+//! effects need no state beyond a start time. They are meant for cell
+//! foreground and background colors: a pulsing low-health bar, a flashing
+//! hit marker, a fading message.
+//!
+//! Nothing here needs `std`; curves use polynomial easing rather than
+//! trigonometry.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_color::effects::{fade_to, flash, pulse};
+//! use runeforge_color::Color;
+//!
+//! let t = 0.25;
+//! let health_bar = pulse(Color::RED, Color::WHITE, 1.0, t);
+//! let hit = flash(Color::GRAY, Color::YELLOW, 3, 0.6, t);
+//! let message = fade_to(Color::WHITE, Color::BLACK, 2.0, t);
+//! # let _ = (health_bar, hit, message);
+//! ```
+
+use crate::Color;
+
+/// Smoothly oscillates between `base` and `highlight`.
+///
+/// Starts at `base`, reaches `highlight` half way through each `period` and
+/// returns to `base` at the end. A `period` of zero or less gives `base`.
+pub fn pulse(base: Color, highlight: Color, period: f32, t: f32) -> Color {
+    if period <= 0.0 {
+        return base;
+    }
+    let phase = fract(t / period);
+    let triangle = 1.0 - (2.0 * phase - 1.0).abs();
+    base.lerp(highlight, smoothstep(triangle))
+}
+
+/// Flashes `highlight` on and off `times` times over `duration`, then stays
+/// at `base`.
+///
+/// Each flash is `highlight` for the first half of its slot and `base` for
+/// the second, so the last frame of the effect is always `base`.
+pub fn flash(base: Color, highlight: Color, times: u32, duration: f32, t: f32) -> Color {
+    if times == 0 || duration <= 0.0 || t < 0.0 || t >= duration {
+        return base;
+    }
+    let slot = t / duration * times as f32;
+    if fract(slot) < 0.5 {
+        highlight
+    } else {
+        base
+    }
+}
+
+/// Fades a color's alpha from its own value to zero over `duration`.
+///
+/// For backends that ignore alpha, use [`fade_to`] with the background
+/// color instead.
+pub fn fade_out(color: Color, duration: f32, t: f32) -> Color {
+    let alpha = color.a as f32 * (1.0 - progress(duration, t));
+    Color {
+        a: alpha as u8,
+        ..color
+    }
+}
+
+/// Fades a color's alpha from zero up to its own value over `duration`.
+pub fn fade_in(color: Color, duration: f32, t: f32) -> Color {
+    let alpha = color.a as f32 * progress(duration, t);
+    Color {
+        a: alpha as u8,
+        ..color
+    }
+}
+
+/// Blends from `from` to `to` over `duration`, then stays at `to`.
+pub fn fade_to(from: Color, to: Color, duration: f32, t: f32) -> Color {
+    from.lerp(to, progress(duration, t))
+}
+
+/// Samples a keyframe sequence of `(time, color)` pairs at `t`.
+///
+/// Keys must be sorted by time. Colors blend linearly between keys; before
+/// the first key the first color is used and after the last key the last.
+/// An empty sequence gives [`Color::BLACK`]. To loop a sequence, pass
+/// `t % last_key_time`.
+///
+/// ```
+/// use runeforge_color::effects::sequence;
+/// use runeforge_color::Color;
+///
+/// let fire = [(0.0, Color::RED), (0.5, Color::YELLOW), (1.0, Color::RED)];
+/// assert_eq!(sequence(&fire, 0.5), Color::YELLOW);
+/// assert_eq!(sequence(&fire, 7.0), Color::RED);
+/// ```
+pub fn sequence(keys: &[(f32, Color)], t: f32) -> Color {
+    let Some(&(first_time, first)) = keys.first() else {
+        return Color::BLACK;
+    };
+    if t <= first_time {
+        return first;
+    }
+    for pair in keys.windows(2) {
+        let ((start, from), (end, to)) = (pair[0], pair[1]);
+        if t < end {
+            let span = end - start;
+            let local = if span > 0.0 { (t - start) / span } else { 1.0 };
+            return from.lerp(to, local);
+        }
+    }
+    keys[keys.len() - 1].1
+}
+
+/// Fraction of `duration` elapsed at `t`, clamped to 0.0-1.0.
+fn progress(duration: f32, t: f32) -> f32 {
+    if duration <= 0.0 {
+        return 1.0;
+    }
+    (t / duration).clamp(0.0, 1.0)
+}
+
+/// Fractional part, always in 0.0..1.0 (also for negative input).
+fn fract(x: f32) -> f32 {
+    let f = x - (x as i64) as f32;
+    if f < 0.0 {
+        f + 1.0
+    } else {
+        f
+    }
+}
+
+fn smoothstep(x: f32) -> f32 {
+    x * x * (3.0 - 2.0 * x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pulse_cycle() {
+        let (base, high) = (Color::BLACK, Color::WHITE);
+        assert_eq!(pulse(base, high, 2.0, 0.0), base);
+        assert_eq!(pulse(base, high, 2.0, 1.0), high);
+        assert_eq!(pulse(base, high, 2.0, 2.0), base);
+        assert_eq!(pulse(base, high, 2.0, 0.5), pulse(base, high, 2.0, 1.5));
+        assert_eq!(pulse(base, high, 2.0, -1.0), high);
+        assert_eq!(pulse(base, high, 0.0, 1.0), base);
+    }
+
+    #[test]
+    fn test_flash_counts() {
+        let (base, high) = (Color::BLACK, Color::WHITE);
+        let frames: Vec<Color> = (0..12)
+            .map(|i| flash(base, high, 3, 1.2, i as f32 * 0.1 + 0.05))
+            .collect();
+        let flashes = frames
+            .windows(2)
+            .filter(|w| w[0] == base && w[1] == high)
+            .count();
+        assert_eq!(frames[0], high);
+        assert_eq!(flashes, 2); // Plus the one the effect starts with
+        assert_eq!(flash(base, high, 3, 1.2, 1.2), base);
+    }
+
+    #[test]
+    fn test_fades() {
+        let c = Color::rgba(200, 100, 50, 200);
+        assert_eq!(fade_out(c, 2.0, 0.0).a, 200);
+        assert_eq!(fade_out(c, 2.0, 1.0).a, 100);
+        assert_eq!(fade_out(c, 2.0, 5.0).a, 0);
+        assert_eq!(fade_in(c, 2.0, 2.0), c);
+        assert_eq!(fade_to(Color::BLACK, Color::WHITE, 0.0, 0.0), Color::WHITE);
+        assert_eq!(fade_to(Color::BLACK, Color::WHITE, 4.0, 2.0).r, 127);
+    }
+
+    #[test]
+    fn test_sequence() {
+        assert_eq!(sequence(&[], 1.0), Color::BLACK);
+        let keys = [(1.0, Color::BLACK), (1.0, Color::RED), (3.0, Color::WHITE)];
+        assert_eq!(sequence(&keys, 0.0), Color::BLACK);
+        assert_eq!(sequence(&keys, 1.0), Color::BLACK);
+        assert_eq!(sequence(&keys, 2.0), Color::RED.lerp(Color::WHITE, 0.5));
+        assert_eq!(sequence(&keys, 3.0), Color::WHITE);
+    }
+}
@@ -8,6 +8,8 @@
 //! *   **RGBA Colors:** Standard 32-bit color representation.
 //! *   **HSV Conversion:** Easy conversion between RGB and HSV color spaces.
 //! *   **Blending & Manipulation:** Operations like linear interpolation (lerp), multiplication, and component-wise addition.
+//! *   **Effects:** Time-based pulses, flashes, fades and keyframe sequences (see [`effects`]).
+//! *   **Palettes:** Nearest-color quantization to fixed retro palettes, with ordered dithering (see [`quantize`]).
 //! *   **Predefined Colors:** A set of standard terminal colors (e.g., `Color::RED`, `Color::DARK_GRAY`).
 //!
 //! # Usage
@@ -38,11 +40,19 @@
 //!     println!("Result: {}", dark_purple);
 //! }
 //! ```
+//!
+//! # `no_std`
+//!
+//! The crate is `no_std` when the default `std` feature is disabled.
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![deny(missing_docs)]
 
-use std::fmt;
-use std::ops::Add;
+use core::fmt;
+use core::ops::Add;
+
+pub mod effects;
+pub mod quantize;
 
 /// An RGBA color represented as four 8-bit unsigned integers.
 ///
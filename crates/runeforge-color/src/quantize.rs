@@ -0,0 +1,264 @@
+//! Fixed palettes and nearest-color quantization for retro styles.
+//!
+//! A retro-styled game often wants every color on screen to come from one
+//! small palette, however the colors were produced (lighting, blending,
+//! effects). [`nearest`] maps any color onto a palette, and [`dither`] does
+//! the same with a 4x4 ordered (Bayer) offset so gradients turn into
+//! patterns instead of bands.
+//!
+//! The slice functions work without `std`; [`Palette`] owns its colors and
+//! needs the `std` feature.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_color::quantize::{nearest, EGA};
+//! use runeforge_color::Color;
+//!
+//! assert_eq!(nearest(&EGA, Color::rgb(250, 240, 90)), Color::rgb(255, 255, 85));
+//! ```
+
+use crate::Color;
+
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// The 16-color EGA / CGA text mode palette, in its usual index order.
+pub const EGA: [Color; 16] = [
+    Color::rgb(0x00, 0x00, 0x00),
+    Color::rgb(0x00, 0x00, 0xAA),
+    Color::rgb(0x00, 0xAA, 0x00),
+    Color::rgb(0x00, 0xAA, 0xAA),
+    Color::rgb(0xAA, 0x00, 0x00),
+    Color::rgb(0xAA, 0x00, 0xAA),
+    Color::rgb(0xAA, 0x55, 0x00),
+    Color::rgb(0xAA, 0xAA, 0xAA),
+    Color::rgb(0x55, 0x55, 0x55),
+    Color::rgb(0x55, 0x55, 0xFF),
+    Color::rgb(0x55, 0xFF, 0x55),
+    Color::rgb(0x55, 0xFF, 0xFF),
+    Color::rgb(0xFF, 0x55, 0x55),
+    Color::rgb(0xFF, 0x55, 0xFF),
+    Color::rgb(0xFF, 0xFF, 0x55),
+    Color::rgb(0xFF, 0xFF, 0xFF),
+];
+
+/// The 16-color PICO-8 palette.
+pub const PICO8: [Color; 16] = [
+    Color::rgb(0x00, 0x00, 0x00),
+    Color::rgb(0x1D, 0x2B, 0x53),
+    Color::rgb(0x7E, 0x25, 0x53),
+    Color::rgb(0x00, 0x87, 0x51),
+    Color::rgb(0xAB, 0x52, 0x36),
+    Color::rgb(0x5F, 0x57, 0x4F),
+    Color::rgb(0xC2, 0xC3, 0xC7),
+    Color::rgb(0xFF, 0xF1, 0xE8),
+    Color::rgb(0xFF, 0x00, 0x4D),
+    Color::rgb(0xFF, 0xA3, 0x00),
+    Color::rgb(0xFF, 0xEC, 0x27),
+    Color::rgb(0x00, 0xE4, 0x36),
+    Color::rgb(0x29, 0xAD, 0xFF),
+    Color::rgb(0x83, 0x76, 0x9C),
+    Color::rgb(0xFF, 0x77, 0xA8),
+    Color::rgb(0xFF, 0xCC, 0xAA),
+];
+
+/// The four greens of the original Game Boy, darkest first.
+pub const GAME_BOY: [Color; 4] = [
+    Color::rgb(0x0F, 0x38, 0x0F),
+    Color::rgb(0x30, 0x62, 0x30),
+    Color::rgb(0x8B, 0xAC, 0x0F),
+    Color::rgb(0x9B, 0xBC, 0x0F),
+];
+
+/// 4x4 Bayer threshold matrix, values 0-15.
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Perceptual distance between two colors, ignoring alpha.
+///
+/// Uses the "redmean" weighting, which tracks how the eye sees differences
+/// much better than plain RGB distance at the same cost.
+pub fn distance(a: Color, b: Color) -> u32 {
+    let mean = (a.r as i32 + b.r as i32) / 2;
+    let dr = a.r as i32 - b.r as i32;
+    let dg = a.g as i32 - b.g as i32;
+    let db = a.b as i32 - b.b as i32;
+    let d = (((512 + mean) * dr * dr) >> 8) + 4 * dg * dg + (((767 - mean) * db * db) >> 8);
+    d as u32
+}
+
+/// Returns the index of the palette entry closest to `color`, or `None` for
+/// an empty palette. Ties go to the earlier entry.
+pub fn nearest_index(colors: &[Color], color: Color) -> Option<usize> {
+    colors
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, &c)| distance(c, color))
+        .map(|(i, _)| i)
+}
+
+/// Returns the palette entry closest to `color`, keeping `color`'s alpha.
+///
+/// An empty palette leaves the color unchanged.
+pub fn nearest(colors: &[Color], color: Color) -> Color {
+    match nearest_index(colors, color) {
+        Some(i) => Color {
+            a: color.a,
+            ..colors[i]
+        },
+        None => color,
+    }
+}
+
+/// Returns the ordered-dither offset for pixel `(x, y)`, between
+/// `-spread / 2` and `spread / 2`.
+pub fn dither_offset(x: u32, y: u32, spread: f32) -> f32 {
+    let threshold = BAYER[(y % 4) as usize][(x % 4) as usize] as f32;
+    ((threshold + 0.5) / 16.0 - 0.5) * spread
+}
+
+/// Quantizes `color` for the pixel at `(x, y)` with ordered dithering.
+///
+/// `spread` is how far, in 0-255 channel units, a color may be pushed
+/// before matching. About the gap between neighboring palette colors works
+/// well (e.g. 85 for EGA); 0 is the same as [`nearest`].
+pub fn dither(colors: &[Color], color: Color, x: u32, y: u32, spread: f32) -> Color {
+    let offset = dither_offset(x, y, spread);
+    let shift = |c: u8| (c as f32 + offset).clamp(0.0, 255.0) as u8;
+    let shifted = Color {
+        r: shift(color.r),
+        g: shift(color.g),
+        b: shift(color.b),
+        a: color.a,
+    };
+    nearest(colors, shifted)
+}
+
+/// An owned, fixed set of colors to quantize to.
+///
+/// ```
+/// use runeforge_color::quantize::Palette;
+/// use runeforge_color::Color;
+///
+/// let palette = Palette::game_boy();
+/// assert_eq!(palette.nearest(Color::BLACK), Color::rgb(0x0F, 0x38, 0x0F));
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Palette {
+    colors: Vec<Color>,
+}
+
+#[cfg(feature = "std")]
+impl Palette {
+    /// Creates a palette from a list of colors.
+    pub fn new(colors: impl Into<Vec<Color>>) -> Self {
+        Self {
+            colors: colors.into(),
+        }
+    }
+
+    /// The 16-color EGA palette ([`EGA`]).
+    pub fn ega() -> Self {
+        Self::new(EGA)
+    }
+
+    /// The 16-color PICO-8 palette ([`PICO8`]).
+    pub fn pico8() -> Self {
+        Self::new(PICO8)
+    }
+
+    /// The 4-color Game Boy palette ([`GAME_BOY`]).
+    pub fn game_boy() -> Self {
+        Self::new(GAME_BOY)
+    }
+
+    /// Returns the palette's colors.
+    pub fn colors(&self) -> &[Color] {
+        &self.colors
+    }
+
+    /// Returns the number of colors.
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    /// Returns true if the palette has no colors.
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// Returns the index of the closest entry. See [`nearest_index`].
+    pub fn nearest_index(&self, color: Color) -> Option<usize> {
+        nearest_index(&self.colors, color)
+    }
+
+    /// Returns the closest entry, keeping `color`'s alpha. See [`nearest`].
+    pub fn nearest(&self, color: Color) -> Color {
+        nearest(&self.colors, color)
+    }
+
+    /// Quantizes with ordered dithering. See [`dither`].
+    pub fn dither(&self, color: Color, x: u32, y: u32, spread: f32) -> Color {
+        dither(&self.colors, color, x, y, spread)
+    }
+
+    /// Quantizes a tightly packed RGBA8 buffer `width` pixels wide in place,
+    /// dithering when `spread` is given.
+    pub fn apply(&self, buffer: &mut [u8], width: u32, spread: Option<f32>) {
+        if self.colors.is_empty() || width == 0 {
+            return;
+        }
+        for (i, px) in buffer.chunks_exact_mut(4).enumerate() {
+            let color = Color::rgba(px[0], px[1], px[2], px[3]);
+            let (x, y) = (i as u32 % width, i as u32 / width);
+            let mapped = match spread {
+                Some(spread) => self.dither(color, x, y, spread),
+                None => self.nearest(color),
+            };
+            px[..3].copy_from_slice(&[mapped.r, mapped.g, mapped.b]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_exact_and_close() {
+        for (i, &c) in PICO8.iter().enumerate() {
+            assert_eq!(nearest_index(&PICO8, c), Some(i));
+        }
+        assert_eq!(nearest(&EGA, Color::rgb(160, 10, 5)), EGA[4]);
+        assert_eq!(nearest(&EGA, Color::rgba(250, 250, 250, 7)).a, 7);
+        assert_eq!(nearest(&[], Color::RED), Color::RED);
+        assert_eq!(nearest_index(&[], Color::RED), None);
+    }
+
+    #[test]
+    fn test_dither_mixes_neighbors() {
+        let gray = Color::grayscale(128);
+        let two = [Color::BLACK, Color::WHITE];
+        let mut whites = 0;
+        for y in 0..4 {
+            for x in 0..4 {
+                let c = dither(&two, gray, x, y, 255.0);
+                assert!(c == Color::BLACK || c == Color::WHITE);
+                whites += (c == Color::WHITE) as u32;
+            }
+        }
+        assert_eq!(whites, 8);
+        assert_eq!(dither(&two, gray, 1, 2, 0.0), nearest(&two, gray));
+    }
+
+    #[test]
+    fn test_palette_apply() {
+        let palette = Palette::game_boy();
+        let mut buffer = vec![0, 0, 0, 255, 250, 250, 250, 128];
+        palette.apply(&mut buffer, 2, None);
+        assert_eq!(buffer, vec![0x0F, 0x38, 0x0F, 255, 0x9B, 0xBC, 0x0F, 128]);
+        assert_eq!(Palette::ega().len(), 16);
+        assert!(Palette::new(Vec::new()).is_empty());
+    }
+}
@@ -0,0 +1,420 @@
+//! Run statistics and the achievements they unlock.
+//!
+//! Game systems report what happened as [`StatEvent`]s: a monster was
+//! killed, tiles were explored, a new depth was reached. [`Statistics`]
+//! turns them into named counters, and an [`AchievementTracker`] checks
+//! those counters against each [`Achievement`]'s threshold, returning an
+//! [`AchievementUnlocked`] the first time one is reached so the UI can show
+//! a banner. With the `serialization` feature the tracker, including what
+//! is already unlocked, can be saved between sessions.
+//!
+//! Counter names are plain strings. The built-in events use these:
+//!
+//! | Event | Counters |
+//! |-------|----------|
+//! | [`Killed`](StatEvent::Killed) | `kills`, `kills.<kind>` |
+//! | [`Explored`](StatEvent::Explored) | `tiles_explored` |
+//! | [`Descended`](StatEvent::Descended) | `deepest_depth` (highest value) |
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_core::prelude::*;
+//!
+//! let mut tracker = AchievementTracker::new()
+//!     .with_achievement(Achievement::new("rat_catcher", "Rat Catcher", "kills.rat", 3))
+//!     .with_achievement(Achievement::new("delver", "Delver", "deepest_depth", 5));
+//!
+//! tracker.record(StatEvent::Killed("rat".into()));
+//! tracker.record(StatEvent::Killed("rat".into()));
+//! let unlocked = tracker.record(StatEvent::Killed("rat".into()));
+//! assert_eq!(unlocked[0].id, "rat_catcher");
+//!
+//! // Already unlocked, so nothing new
+//! assert!(tracker.record(StatEvent::Killed("rat".into())).is_empty());
+//!
+//! tracker.record(StatEvent::Descended(6));
+//! tracker.record(StatEvent::Descended(2));
+//! assert_eq!(tracker.stats().deepest_depth(), 6);
+//! assert!(tracker.is_unlocked("delver"));
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::event::EventBus;
+
+/// Counter for all kills.
+pub const KILLS: &str = "kills";
+/// Counter for explored tiles.
+pub const TILES_EXPLORED: &str = "tiles_explored";
+/// Counter for the deepest level reached.
+pub const DEEPEST_DEPTH: &str = "deepest_depth";
+
+/// Something worth counting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatEvent {
+    /// A monster of this kind was killed.
+    Killed(String),
+    /// This many tiles were seen for the first time.
+    Explored(u64),
+    /// The player reached this depth.
+    Descended(u64),
+    /// Adds to a game-defined counter.
+    Add {
+        /// Counter name
+        stat: String,
+        /// Amount to add
+        amount: u64,
+    },
+    /// Raises a game-defined counter to `value` if it is higher, e.g. the
+    /// most gold ever carried.
+    Max {
+        /// Counter name
+        stat: String,
+        /// The new candidate value
+        value: u64,
+    },
+}
+
+/// Named counters for one run or one profile.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct Statistics {
+    /// Sorted by name so saves and listings are stable.
+    counters: BTreeMap<String, u64>,
+}
+
+impl Statistics {
+    /// Creates statistics with every counter at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the counters for `event`, returning the names that changed.
+    pub fn record(&mut self, event: &StatEvent) -> Vec<String> {
+        match event {
+            StatEvent::Killed(kind) => {
+                let by_kind = format!("{KILLS}.{kind}");
+                self.add(KILLS, 1);
+                self.add(&by_kind, 1);
+                vec![KILLS.to_string(), by_kind]
+            }
+            StatEvent::Explored(tiles) => self.add(TILES_EXPLORED, *tiles).into_iter().collect(),
+            StatEvent::Descended(depth) => self.max(DEEPEST_DEPTH, *depth).into_iter().collect(),
+            StatEvent::Add { stat, amount } => self.add(stat, *amount).into_iter().collect(),
+            StatEvent::Max { stat, value } => self.max(stat, *value).into_iter().collect(),
+        }
+    }
+
+    /// Adds `amount` to a counter, saturating. Returns its name if it
+    /// changed.
+    pub fn add(&mut self, stat: &str, amount: u64) -> Option<String> {
+        if amount == 0 {
+            return None;
+        }
+        let counter = self.counters.entry(stat.to_string()).or_default();
+        *counter = counter.saturating_add(amount);
+        Some(stat.to_string())
+    }
+
+    /// Raises a counter to `value` if it is higher. Returns its name if it
+    /// changed.
+    pub fn max(&mut self, stat: &str, value: u64) -> Option<String> {
+        if value <= self.get(stat) {
+            return None;
+        }
+        self.counters.insert(stat.to_string(), value);
+        Some(stat.to_string())
+    }
+
+    /// Returns a counter, zero if it was never recorded.
+    pub fn get(&self, stat: &str) -> u64 {
+        self.counters.get(stat).copied().unwrap_or(0)
+    }
+
+    /// Returns how many monsters of `kind` were killed.
+    pub fn kills(&self, kind: &str) -> u64 {
+        self.get(&format!("{KILLS}.{kind}"))
+    }
+
+    /// Returns how many monsters were killed in total.
+    pub fn total_kills(&self) -> u64 {
+        self.get(KILLS)
+    }
+
+    /// Returns how many tiles were explored.
+    pub fn tiles_explored(&self) -> u64 {
+        self.get(TILES_EXPLORED)
+    }
+
+    /// Returns the deepest depth reached.
+    pub fn deepest_depth(&self) -> u64 {
+        self.get(DEEPEST_DEPTH)
+    }
+
+    /// Returns every recorded counter, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.counters.iter().map(|(k, v)| (k.as_str(), *v))
+    }
+
+    /// Resets every counter to zero.
+    pub fn clear(&mut self) {
+        self.counters.clear();
+    }
+}
+
+/// A goal reached when a counter hits a threshold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct Achievement {
+    /// Stable id, used to remember that it was unlocked
+    pub id: String,
+    /// Name shown to the player
+    pub name: String,
+    /// Text shown to the player
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub description: String,
+    /// The counter to watch
+    pub stat: String,
+    /// Unlocks when the counter is at least this
+    pub threshold: u64,
+}
+
+impl Achievement {
+    /// Creates an achievement that unlocks when `stat` reaches `threshold`.
+    pub fn new(
+        id: impl Into<String>,
+        name: impl Into<String>,
+        stat: impl Into<String>,
+        threshold: u64,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            description: String::new(),
+            stat: stat.into(),
+            threshold,
+        }
+    }
+
+    /// Sets the description.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+}
+
+/// An achievement was unlocked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AchievementUnlocked {
+    /// Id of the achievement
+    pub id: String,
+    /// Name shown to the player
+    pub name: String,
+    /// The counter's value when it unlocked
+    pub value: u64,
+}
+
+/// Statistics plus the achievements they unlock.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct AchievementTracker {
+    stats: Statistics,
+    achievements: Vec<Achievement>,
+    unlocked: BTreeSet<String>,
+}
+
+impl AchievementTracker {
+    /// Creates a tracker without achievements.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an achievement, builder style.
+    pub fn with_achievement(mut self, achievement: Achievement) -> Self {
+        self.add(achievement);
+        self
+    }
+
+    /// Adds an achievement, replacing any with the same id. It is not
+    /// checked until the next [`record`](Self::record) or
+    /// [`check`](Self::check).
+    pub fn add(&mut self, achievement: Achievement) {
+        match self
+            .achievements
+            .iter_mut()
+            .find(|a| a.id == achievement.id)
+        {
+            Some(existing) => *existing = achievement,
+            None => self.achievements.push(achievement),
+        }
+    }
+
+    /// Returns the statistics.
+    pub fn stats(&self) -> &Statistics {
+        &self.stats
+    }
+
+    /// Returns the statistics for changes. Call [`check`](Self::check)
+    /// afterwards to pick up unlocks.
+    pub fn stats_mut(&mut self) -> &mut Statistics {
+        &mut self.stats
+    }
+
+    /// Returns every achievement in the order they were added.
+    pub fn achievements(&self) -> &[Achievement] {
+        &self.achievements
+    }
+
+    /// Returns true if the achievement has been unlocked.
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked.contains(id)
+    }
+
+    /// Returns the unlocked achievements in the order they were added.
+    pub fn unlocked(&self) -> impl Iterator<Item = &Achievement> {
+        self.achievements
+            .iter()
+            .filter(|a| self.unlocked.contains(&a.id))
+    }
+
+    /// Records an event and returns the achievements it unlocked.
+    pub fn record(&mut self, event: StatEvent) -> Vec<AchievementUnlocked> {
+        let changed = self.stats.record(&event);
+        self.unlock_where(|a| changed.contains(&a.stat))
+    }
+
+    /// Records an event and publishes what it unlocked on `bus`, returning
+    /// how many were published.
+    pub fn record_into(
+        &mut self,
+        event: StatEvent,
+        bus: &mut EventBus<AchievementUnlocked>,
+    ) -> usize {
+        let unlocked = self.record(event);
+        let count = unlocked.len();
+        for event in unlocked {
+            bus.publish(event);
+        }
+        count
+    }
+
+    /// Checks every achievement against the current statistics, e.g. after
+    /// adding achievements to a loaded profile.
+    pub fn check(&mut self) -> Vec<AchievementUnlocked> {
+        self.unlock_where(|_| true)
+    }
+
+    /// Forgets which achievements were unlocked, keeping the statistics.
+    pub fn reset_unlocked(&mut self) {
+        self.unlocked.clear();
+    }
+
+    fn unlock_where(&mut self, watch: impl Fn(&Achievement) -> bool) -> Vec<AchievementUnlocked> {
+        let mut events = Vec::new();
+        for achievement in &self.achievements {
+            if !watch(achievement) || self.unlocked.contains(&achievement.id) {
+                continue;
+            }
+            let value = self.stats.get(&achievement.stat);
+            if value >= achievement.threshold {
+                self.unlocked.insert(achievement.id.clone());
+                events.push(AchievementUnlocked {
+                    id: achievement.id.clone(),
+                    name: achievement.name.clone(),
+                    value,
+                });
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statistics_counters() {
+        let mut stats = Statistics::new();
+        assert_eq!(
+            stats.record(&StatEvent::Killed("orc".into())),
+            vec!["kills".to_string(), "kills.orc".to_string()]
+        );
+        stats.record(&StatEvent::Killed("bat".into()));
+        stats.record(&StatEvent::Explored(40));
+        stats.record(&StatEvent::Explored(0));
+        assert!(stats.record(&StatEvent::Descended(0)).is_empty());
+        stats.record(&StatEvent::Descended(3));
+        stats.record(&StatEvent::Max {
+            stat: "gold".into(),
+            value: 10,
+        });
+        stats.record(&StatEvent::Add {
+            stat: "gold".into(),
+            amount: u64::MAX,
+        });
+
+        assert_eq!(stats.total_kills(), 2);
+        assert_eq!(stats.kills("orc"), 1);
+        assert_eq!(stats.kills("dragon"), 0);
+        assert_eq!(stats.tiles_explored(), 40);
+        assert_eq!(stats.deepest_depth(), 3);
+        assert_eq!(stats.get("gold"), u64::MAX);
+        let names: Vec<_> = stats.iter().map(|(k, _)| k).collect();
+        assert_eq!(
+            names,
+            vec![
+                "deepest_depth",
+                "gold",
+                "kills",
+                "kills.bat",
+                "kills.orc",
+                "tiles_explored"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tracker_unlocks_once() {
+        let mut tracker = AchievementTracker::new()
+            .with_achievement(Achievement::new(
+                "explorer",
+                "Explorer",
+                TILES_EXPLORED,
+                100,
+            ))
+            .with_achievement(Achievement::new("slayer", "Slayer", KILLS, 2));
+
+        let mut bus = EventBus::new();
+        assert_eq!(tracker.record_into(StatEvent::Explored(60), &mut bus), 0);
+        assert_eq!(tracker.record_into(StatEvent::Explored(60), &mut bus), 1);
+        assert_eq!(
+            bus.drain().collect::<Vec<_>>(),
+            vec![AchievementUnlocked {
+                id: "explorer".into(),
+                name: "Explorer".into(),
+                value: 120,
+            }]
+        );
+        assert!(tracker.record(StatEvent::Explored(60)).is_empty());
+
+        // Changing stats directly needs an explicit check
+        tracker.stats_mut().add(KILLS, 5);
+        assert!(!tracker.is_unlocked("slayer"));
+        assert_eq!(tracker.check().len(), 1);
+        assert_eq!(tracker.unlocked().count(), 2);
+
+        tracker.reset_unlocked();
+        assert_eq!(tracker.check().len(), 2);
+    }
+}
@@ -0,0 +1,433 @@
+//! Command-pattern actions with validation, energy costs and undo.
+//!
+//! Instead of mutating the world directly from input handlers, players and AI
+//! both produce [`Action`]s. An [`ActionQueue`] validates and executes them in
+//! order, records the executed actions for replay logging, and can undo them
+//! (e.g. for a wizard-mode "take back" command).
+//!
+//! [`ActionBindings`] maps input keys (such as `runeforge_input::VirtualKey`)
+//! to action factories so the same actions can be reused by the AI.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_core::prelude::*;
+//!
+//! struct World {
+//!     player: IVec2,
+//! }
+//!
+//! struct Move {
+//!     delta: IVec2,
+//! }
+//!
+//! impl Action<World> for Move {
+//!     fn name(&self) -> &str {
+//!         "move"
+//!     }
+//!
+//!     fn validate(&self, world: &World) -> Result<(), ActionError> {
+//!         let target = world.player + self.delta;
+//!         if target.x < 0 || target.y < 0 {
+//!             return Err(ActionError::Invalid("blocked".to_string()));
+//!         }
+//!         Ok(())
+//!     }
+//!
+//!     fn execute(&mut self, world: &mut World) {
+//!         world.player += self.delta;
+//!     }
+//!
+//!     fn undo(&mut self, world: &mut World) -> bool {
+//!         world.player -= self.delta;
+//!         true
+//!     }
+//! }
+//!
+//! let mut world = World { player: IVec2::new(0, 0) };
+//! let mut queue = ActionQueue::new();
+//!
+//! queue.push(Move { delta: IVec2::new(1, 0) });
+//! queue.push(Move { delta: IVec2::new(-5, 0) }); // rejected by validate
+//! queue.process_all(&mut world);
+//! assert_eq!(world.player, IVec2::new(1, 0));
+//!
+//! queue.undo_last(&mut world).unwrap();
+//! assert_eq!(world.player, IVec2::new(0, 0));
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+
+use thiserror::Error;
+
+/// The default energy cost of an action (one standard turn).
+pub const DEFAULT_ENERGY_COST: u32 = 100;
+
+/// Errors that can occur when validating or undoing actions.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ActionError {
+    /// The action cannot be performed in the current world state.
+    #[error("Invalid action: {0}")]
+    Invalid(String),
+    /// There is no executed action to undo.
+    #[error("Nothing to undo")]
+    NothingToUndo,
+    /// The action does not support undo.
+    #[error("Action '{name}' cannot be undone")]
+    NotUndoable {
+        /// Name of the action
+        name: String,
+    },
+}
+
+impl ActionError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ActionError::Invalid(_) => "action.invalid",
+            ActionError::NothingToUndo => "action.nothing_to_undo",
+            ActionError::NotUndoable { .. } => "action.not_undoable",
+        }
+    }
+}
+
+/// A single game action performed against a world of type `W`.
+pub trait Action<W> {
+    /// A short, stable name used for replay logs and debugging.
+    fn name(&self) -> &str;
+
+    /// Checks whether the action can be performed. Defaults to always valid.
+    fn validate(&self, _world: &W) -> Result<(), ActionError> {
+        Ok(())
+    }
+
+    /// Performs the action. Only called after [`validate`](Action::validate) succeeds.
+    fn execute(&mut self, world: &mut W);
+
+    /// Reverts the action. Returns `false` if the action cannot be undone.
+    fn undo(&mut self, _world: &mut W) -> bool {
+        false
+    }
+
+    /// Energy spent by the actor performing this action.
+    fn energy_cost(&self) -> u32 {
+        DEFAULT_ENERGY_COST
+    }
+}
+
+/// The result of processing a queued action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActionOutcome {
+    /// The action was executed.
+    Performed {
+        /// Energy the action cost
+        energy_cost: u32,
+    },
+    /// The action failed validation and had no effect.
+    Rejected(ActionError),
+}
+
+/// A FIFO queue of pending actions plus a history of executed ones.
+pub struct ActionQueue<W> {
+    pending: VecDeque<Box<dyn Action<W>>>,
+    history: VecDeque<Box<dyn Action<W>>>,
+    max_history: usize,
+}
+
+impl<W> Default for ActionQueue<W> {
+    fn default() -> Self {
+        Self {
+            pending: VecDeque::new(),
+            history: VecDeque::new(),
+            max_history: 1000,
+        }
+    }
+}
+
+impl<W> fmt::Debug for ActionQueue<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ActionQueue")
+            .field("pending", &self.pending.len())
+            .field("history", &self.history.len())
+            .finish()
+    }
+}
+
+impl<W> ActionQueue<W> {
+    /// Creates an empty action queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how many executed actions are kept for undo (minimum 1).
+    pub fn with_max_history(mut self, max_history: usize) -> Self {
+        self.max_history = max_history.max(1);
+        self
+    }
+
+    /// Queues an action.
+    pub fn push<A: Action<W> + 'static>(&mut self, action: A) {
+        self.pending.push_back(Box::new(action));
+    }
+
+    /// Queues an already boxed action.
+    pub fn push_boxed(&mut self, action: Box<dyn Action<W>>) {
+        self.pending.push_back(action);
+    }
+
+    /// Validates and executes the next pending action.
+    ///
+    /// Returns `None` if the queue is empty.
+    pub fn process_next(&mut self, world: &mut W) -> Option<ActionOutcome> {
+        let mut action = self.pending.pop_front()?;
+        if let Err(err) = action.validate(world) {
+            return Some(ActionOutcome::Rejected(err));
+        }
+
+        action.execute(world);
+        let energy_cost = action.energy_cost();
+        self.history.push_back(action);
+        if self.history.len() > self.max_history {
+            self.history.pop_front();
+        }
+        Some(ActionOutcome::Performed { energy_cost })
+    }
+
+    /// Processes every pending action, returning their outcomes in order.
+    pub fn process_all(&mut self, world: &mut W) -> Vec<ActionOutcome> {
+        let mut outcomes = Vec::with_capacity(self.pending.len());
+        while let Some(outcome) = self.process_next(world) {
+            outcomes.push(outcome);
+        }
+        outcomes
+    }
+
+    /// Undoes the most recently executed action.
+    ///
+    /// If the action does not support undo it stays in the history.
+    pub fn undo_last(&mut self, world: &mut W) -> Result<(), ActionError> {
+        let mut action = self.history.pop_back().ok_or(ActionError::NothingToUndo)?;
+        if action.undo(world) {
+            Ok(())
+        } else {
+            let name = action.name().to_string();
+            self.history.push_back(action);
+            Err(ActionError::NotUndoable { name })
+        }
+    }
+
+    /// Returns the names of executed actions, oldest first, for replay logging.
+    pub fn history_names(&self) -> impl Iterator<Item = &str> {
+        self.history.iter().map(|a| a.name())
+    }
+
+    /// Returns the number of pending actions.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns true if no actions are pending.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Discards all pending actions.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Discards the executed-action history.
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+}
+
+type ActionFactory<W> = Box<dyn Fn() -> Box<dyn Action<W>>>;
+
+/// Maps input keys to action factories.
+///
+/// `K` is typically `runeforge_input::VirtualKey`, but any hashable key works.
+pub struct ActionBindings<K, W> {
+    bindings: HashMap<K, ActionFactory<W>>,
+}
+
+impl<K, W> Default for ActionBindings<K, W> {
+    fn default() -> Self {
+        Self {
+            bindings: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Hash + Eq, W> ActionBindings<K, W> {
+    /// Creates an empty set of bindings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds a key to a factory producing a fresh action each time.
+    pub fn bind<F, A>(&mut self, key: K, factory: F)
+    where
+        F: Fn() -> A + 'static,
+        A: Action<W> + 'static,
+    {
+        self.bindings.insert(
+            key,
+            Box::new(move || Box::new(factory()) as Box<dyn Action<W>>),
+        );
+    }
+
+    /// Removes the binding for a key.
+    pub fn unbind(&mut self, key: &K) {
+        self.bindings.remove(key);
+    }
+
+    /// Creates the action bound to a key, if any.
+    pub fn action_for(&self, key: &K) -> Option<Box<dyn Action<W>>> {
+        self.bindings.get(key).map(|factory| factory())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Counter {
+        value: i32,
+    }
+
+    struct Add(i32);
+
+    impl Action<Counter> for Add {
+        fn name(&self) -> &str {
+            "add"
+        }
+
+        fn validate(&self, world: &Counter) -> Result<(), ActionError> {
+            if world.value + self.0 < 0 {
+                Err(ActionError::Invalid("negative".to_string()))
+            } else {
+                Ok(())
+            }
+        }
+
+        fn execute(&mut self, world: &mut Counter) {
+            world.value += self.0;
+        }
+
+        fn undo(&mut self, world: &mut Counter) -> bool {
+            world.value -= self.0;
+            true
+        }
+
+        fn energy_cost(&self) -> u32 {
+            50
+        }
+    }
+
+    struct Permanent;
+
+    impl Action<Counter> for Permanent {
+        fn name(&self) -> &str {
+            "permanent"
+        }
+
+        fn execute(&mut self, world: &mut Counter) {
+            world.value *= 2;
+        }
+    }
+
+    #[test]
+    fn test_queue_processes_in_order() {
+        let mut world = Counter { value: 1 };
+        let mut queue = ActionQueue::new();
+        queue.push(Add(2));
+        queue.push(Permanent);
+        let outcomes = queue.process_all(&mut world);
+
+        assert_eq!(world.value, 6);
+        assert_eq!(
+            outcomes,
+            vec![
+                ActionOutcome::Performed { energy_cost: 50 },
+                ActionOutcome::Performed {
+                    energy_cost: DEFAULT_ENERGY_COST
+                },
+            ]
+        );
+        assert_eq!(
+            queue.history_names().collect::<Vec<_>>(),
+            vec!["add", "permanent"]
+        );
+    }
+
+    #[test]
+    fn test_rejected_action_has_no_effect() {
+        let mut world = Counter { value: 1 };
+        let mut queue = ActionQueue::new();
+        queue.push(Add(-5));
+        let outcome = queue.process_next(&mut world).unwrap();
+
+        assert!(matches!(outcome, ActionOutcome::Rejected(_)));
+        assert_eq!(world.value, 1);
+        assert_eq!(queue.history_names().count(), 0);
+    }
+
+    #[test]
+    fn test_undo() {
+        let mut world = Counter { value: 0 };
+        let mut queue = ActionQueue::new();
+        queue.push(Add(3));
+        queue.push(Add(4));
+        queue.process_all(&mut world);
+
+        queue.undo_last(&mut world).unwrap();
+        assert_eq!(world.value, 3);
+        queue.undo_last(&mut world).unwrap();
+        assert_eq!(world.value, 0);
+        assert_eq!(queue.undo_last(&mut world), Err(ActionError::NothingToUndo));
+    }
+
+    #[test]
+    fn test_undo_not_supported() {
+        let mut world = Counter { value: 2 };
+        let mut queue = ActionQueue::new();
+        queue.push(Permanent);
+        queue.process_all(&mut world);
+
+        assert_eq!(
+            queue.undo_last(&mut world),
+            Err(ActionError::NotUndoable {
+                name: "permanent".to_string()
+            })
+        );
+        assert_eq!(world.value, 4);
+        assert_eq!(queue.history_names().count(), 1);
+    }
+
+    #[test]
+    fn test_history_limit() {
+        let mut world = Counter { value: 0 };
+        let mut queue = ActionQueue::new().with_max_history(2);
+        for _ in 0..5 {
+            queue.push(Add(1));
+        }
+        queue.process_all(&mut world);
+        assert_eq!(queue.history_names().count(), 2);
+    }
+
+    #[test]
+    fn test_bindings() {
+        let mut bindings: ActionBindings<char, Counter> = ActionBindings::new();
+        bindings.bind('+', || Add(1));
+
+        let mut world = Counter { value: 0 };
+        let mut queue = ActionQueue::new();
+        queue.push_boxed(bindings.action_for(&'+').unwrap());
+        queue.process_all(&mut world);
+        assert_eq!(world.value, 1);
+        assert!(bindings.action_for(&'-').is_none());
+    }
+}
@@ -0,0 +1,403 @@
+//! Dice-driven attack resolution.
+//!
+//! A small starter kit for melee and ranged combat in the d20 tradition:
+//!
+//! 1.  Roll a d20 and add the attack's [`accuracy`](Attack::accuracy). The
+//!     attack hits if the total reaches the defender's
+//!     [`armor`](Defense::armor). A natural 1 always misses; a natural roll
+//!     at or above the [`CritRule`] threshold always hits, critically.
+//! 2.  Roll the damage dice, rolling them again for each extra crit
+//!     multiplier, and add the modifier.
+//! 3.  Scale by the defender's [`Resistance`] to the damage type, then
+//!     subtract its flat [`soak`](Defense::soak).
+//!
+//! [`resolve_attack`] returns a [`CombatResult`] that records every step, so
+//! it can be published on an [`EventBus`](crate::event::EventBus) and turned
+//! into a message log line with [`CombatResult::message`]. Armor and accuracy
+//! are plain numbers; derive them from [`StatusEffects`](crate::status::StatusEffects)
+//! with [`modified`](crate::status::StatusEffects::modified) to let buffs and
+//! curses take part.
+//!
+//! Enable with the `combat` feature.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_core::combat::{resolve_attack, Attack, Defense, Resistance};
+//! use runeforge_core::prelude::*;
+//! use runeforge_random::prelude::Rng;
+//!
+//! #[derive(Debug, Clone, PartialEq)]
+//! enum Element { Slash, Fire }
+//!
+//! let sword = Attack::new("1d8+2", Element::Slash).unwrap().with_accuracy(5);
+//! let troll = Defense::new(12).with_resistance(Element::Slash, Resistance::percent(50));
+//!
+//! let mut rng = Rng::with_seed(7);
+//! let mut bus = EventBus::new();
+//! let result = resolve_attack(&mut rng, EntityId(1), EntityId(2), &sword, &troll);
+//! if result.is_hit() {
+//!     assert!(result.damage <= result.raw_damage);
+//! }
+//! println!("{}", result.message("You", "the troll"));
+//! bus.publish(result);
+//!
+//! assert_eq!(sword.hit_chance(&troll), 0.7);
+//! ```
+
+use std::fmt;
+
+use runeforge_random::prelude::{parse_dice, DiceError, Rng};
+
+use crate::entity::EntityId;
+
+/// When an attack roll is a critical hit, and what that does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CritRule {
+    /// Natural d20 rolls at or above this are critical hits.
+    pub threshold: i32,
+    /// How many times the damage dice are rolled on a critical hit.
+    pub multiplier: u32,
+}
+
+impl Default for CritRule {
+    /// Crits on a natural 20 and doubles the dice.
+    fn default() -> Self {
+        Self {
+            threshold: 20,
+            multiplier: 2,
+        }
+    }
+}
+
+/// An attack: its accuracy, damage dice and damage type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attack<D> {
+    /// Bonus added to the d20 attack roll.
+    pub accuracy: i32,
+    /// Number of damage dice.
+    pub dice: u32,
+    /// Sides of each damage die.
+    pub sides: i32,
+    /// Flat amount added to the damage roll.
+    pub modifier: i32,
+    /// The kind of damage dealt, matched against resistances.
+    pub damage_type: D,
+    /// Critical hit rule.
+    pub crit: CritRule,
+}
+
+impl<D> Attack<D> {
+    /// Creates an attack dealing `damage` (dice notation such as `"2d6+1"`)
+    /// of `damage_type`.
+    pub fn new(damage: &str, damage_type: D) -> Result<Self, DiceError> {
+        let (dice, sides, modifier) = parse_dice(damage)?;
+        Ok(Self {
+            accuracy: 0,
+            dice,
+            sides,
+            modifier,
+            damage_type,
+            crit: CritRule::default(),
+        })
+    }
+
+    /// Sets the bonus added to the attack roll.
+    pub fn with_accuracy(mut self, accuracy: i32) -> Self {
+        self.accuracy = accuracy;
+        self
+    }
+
+    /// Sets the critical hit rule.
+    pub fn with_crit(mut self, crit: CritRule) -> Self {
+        self.crit = crit;
+        self
+    }
+
+    /// Returns the chance, from 0 to 1, that this attack hits `defense`.
+    pub fn hit_chance(&self, defense: &Defense<D>) -> f32 {
+        let hits = (2..=20)
+            .filter(|&natural| {
+                natural >= self.crit.threshold
+                    || natural.saturating_add(self.accuracy) >= defense.armor
+            })
+            .count();
+        hits as f32 / 20.0
+    }
+}
+
+/// How much of one damage type gets through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resistance(i32);
+
+impl Resistance {
+    /// Blocks `percent` of the damage. 100 is immunity; negative values are
+    /// vulnerabilities that increase damage.
+    pub const fn percent(percent: i32) -> Self {
+        Self(percent)
+    }
+
+    /// Takes no damage of this type.
+    pub const IMMUNE: Self = Self(100);
+
+    /// Returns the blocked percentage.
+    pub const fn value(self) -> i32 {
+        self.0
+    }
+
+    fn apply(self, damage: i32) -> i32 {
+        let scaled = i64::from(damage) * (100 - i64::from(self.0.min(100))) / 100;
+        scaled.clamp(i32::MIN.into(), i32::MAX.into()) as i32
+    }
+}
+
+/// A defender's armor, resistances and damage soak.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Defense<D> {
+    /// The attack roll total needed to hit.
+    pub armor: i32,
+    /// Flat damage removed from every hit after resistances.
+    pub soak: i32,
+    /// Resistances by damage type.
+    pub resistances: Vec<(D, Resistance)>,
+}
+
+impl<D> Defense<D> {
+    /// Creates a defense that attack rolls must reach `armor` to hit.
+    pub fn new(armor: i32) -> Self {
+        Self {
+            armor,
+            soak: 0,
+            resistances: Vec::new(),
+        }
+    }
+
+    /// Sets the flat damage removed from every hit.
+    pub fn with_soak(mut self, soak: i32) -> Self {
+        self.soak = soak;
+        self
+    }
+
+    /// Adds a resistance to one damage type.
+    pub fn with_resistance(mut self, damage_type: D, resistance: Resistance) -> Self {
+        self.resistances.push((damage_type, resistance));
+        self
+    }
+}
+
+impl<D: PartialEq> Defense<D> {
+    /// Returns the resistance to `damage_type`, if any.
+    pub fn resistance(&self, damage_type: &D) -> Option<Resistance> {
+        self.resistances
+            .iter()
+            .find(|(d, _)| d == damage_type)
+            .map(|&(_, r)| r)
+    }
+}
+
+/// Whether an attack landed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HitOutcome {
+    /// The attack missed.
+    Miss,
+    /// The attack hit.
+    Hit,
+    /// The attack hit critically.
+    Critical,
+}
+
+/// Everything that happened in one attack, for game logic and the message
+/// log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CombatResult<D> {
+    /// The attacking entity
+    pub attacker: EntityId,
+    /// The defending entity
+    pub defender: EntityId,
+    /// Whether the attack landed
+    pub outcome: HitOutcome,
+    /// The natural d20 roll
+    pub roll: i32,
+    /// The roll plus accuracy, compared against armor
+    pub attack_total: i32,
+    /// Damage rolled before resistances and soak (0 on a miss)
+    pub raw_damage: i32,
+    /// Damage dealt after resistances and soak, never negative
+    pub damage: i32,
+    /// The kind of damage dealt
+    pub damage_type: D,
+}
+
+impl<D> CombatResult<D> {
+    /// Returns true if the attack landed.
+    pub fn is_hit(&self) -> bool {
+        self.outcome != HitOutcome::Miss
+    }
+
+    /// Formats the result as a message log line, e.g. "The orc hits you for
+    /// 4 damage." or "You critically hit the orc for 12 damage!".
+    pub fn message(&self, attacker: &str, defender: &str) -> String {
+        let mut attacker = attacker.to_string();
+        if let Some(first) = attacker.get(..1) {
+            attacker.replace_range(..1, &first.to_uppercase());
+        }
+        match self.outcome {
+            HitOutcome::Miss => format!("{attacker} misses {defender}."),
+            HitOutcome::Hit if self.damage == 0 => {
+                format!("{attacker} hits {defender} but does no damage.")
+            }
+            HitOutcome::Hit => {
+                format!("{attacker} hits {defender} for {} damage.", self.damage)
+            }
+            HitOutcome::Critical => {
+                format!(
+                    "{attacker} critically hits {defender} for {} damage!",
+                    self.damage
+                )
+            }
+        }
+    }
+}
+
+impl<D> fmt::Display for CombatResult<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message(&self.attacker.to_string(), &self.defender.to_string()))
+    }
+}
+
+/// Rolls `attack` against `defense` and returns what happened.
+pub fn resolve_attack<D: PartialEq + Clone>(
+    rng: &mut Rng,
+    attacker: EntityId,
+    defender: EntityId,
+    attack: &Attack<D>,
+    defense: &Defense<D>,
+) -> CombatResult<D> {
+    let roll = rng.range(1, 20);
+    let attack_total = roll.saturating_add(attack.accuracy);
+    let outcome = if roll == 1 {
+        HitOutcome::Miss
+    } else if roll >= attack.crit.threshold {
+        HitOutcome::Critical
+    } else if attack_total >= defense.armor {
+        HitOutcome::Hit
+    } else {
+        HitOutcome::Miss
+    };
+
+    let (raw_damage, damage) = match outcome {
+        HitOutcome::Miss => (0, 0),
+        _ => {
+            let rolls = if outcome == HitOutcome::Critical {
+                attack.crit.multiplier.max(1)
+            } else {
+                1
+            };
+            let raw = rng
+                .roll_dice(attack.dice.saturating_mul(rolls), attack.sides)
+                .saturating_add(attack.modifier);
+            let resisted = match defense.resistance(&attack.damage_type) {
+                Some(resistance) => resistance.apply(raw),
+                None => raw,
+            };
+            (raw, resisted.saturating_sub(defense.soak).max(0))
+        }
+    };
+
+    CombatResult {
+        attacker,
+        defender,
+        outcome,
+        roll,
+        attack_total,
+        raw_damage,
+        damage,
+        damage_type: attack.damage_type.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hit_chance_bounds() {
+        let attack = Attack::new("1d4", ()).unwrap();
+        // Natural 1 misses and natural 20 hits, whatever the numbers
+        assert_eq!(
+            attack
+                .clone()
+                .with_accuracy(100)
+                .hit_chance(&Defense::new(0)),
+            0.95
+        );
+        assert_eq!(attack.hit_chance(&Defense::new(100)), 0.05);
+        let keen = attack.with_crit(CritRule {
+            threshold: 19,
+            multiplier: 3,
+        });
+        assert_eq!(keen.hit_chance(&Defense::new(100)), 0.1);
+    }
+
+    #[test]
+    fn test_resistance_and_soak() {
+        let mut rng = Rng::with_seed(1);
+        let attack = Attack::new("2d1+2", "fire").unwrap().with_accuracy(50);
+        let immune = Defense::new(0).with_resistance("fire", Resistance::IMMUNE);
+        let weak = Defense::new(0)
+            .with_resistance("fire", Resistance::percent(-50))
+            .with_soak(1);
+        for _ in 0..50 {
+            let result = resolve_attack(&mut rng, EntityId(1), EntityId(2), &attack, &immune);
+            if result.outcome == HitOutcome::Hit {
+                assert_eq!((result.raw_damage, result.damage), (4, 0));
+            }
+            let result = resolve_attack(&mut rng, EntityId(1), EntityId(2), &attack, &weak);
+            match result.outcome {
+                HitOutcome::Hit => assert_eq!(result.damage, 5),
+                HitOutcome::Critical => assert_eq!(result.damage, 8),
+                HitOutcome::Miss => assert_eq!(result.roll, 1),
+            }
+        }
+        assert!(Attack::new("2x6", "fire").is_err());
+    }
+
+    #[test]
+    fn test_extreme_values_saturate() {
+        let mut rng = Rng::with_seed(3);
+        let attack = Attack::new("1d1", ()).unwrap().with_accuracy(i32::MAX);
+        assert_eq!(attack.hit_chance(&Defense::new(i32::MAX)), 0.95);
+        let result = resolve_attack(
+            &mut rng,
+            EntityId(1),
+            EntityId(2),
+            &attack,
+            &Defense::new(0),
+        );
+        assert_eq!(result.attack_total, i32::MAX);
+
+        let vulnerable = Resistance::percent(i32::MIN);
+        assert_eq!(vulnerable.apply(1), 21_474_837);
+        assert_eq!(vulnerable.apply(1_000), i32::MAX);
+        assert_eq!(vulnerable.apply(-1_000), i32::MIN);
+    }
+
+    #[test]
+    fn test_messages() {
+        let result = CombatResult {
+            attacker: EntityId(1),
+            defender: EntityId(2),
+            outcome: HitOutcome::Critical,
+            roll: 20,
+            attack_total: 25,
+            raw_damage: 12,
+            damage: 12,
+            damage_type: (),
+        };
+        assert_eq!(
+            result.message("the orc", "you"),
+            "The orc critically hits you for 12 damage!"
+        );
+        assert_eq!(result.to_string(), "#1 critically hits #2 for 12 damage!");
+    }
+}
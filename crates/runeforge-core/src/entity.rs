@@ -0,0 +1,29 @@
+//! Entity handles.
+//!
+//! Runeforge does not own your entity storage. [`EntityId`] is a plain handle
+//! that events and components use to refer to whatever the game uses to store
+//! actors and items (a `Vec`, a slot map, an ECS world, ...).
+
+use std::fmt;
+
+/// An opaque handle identifying a game entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct EntityId(pub u64);
+
+impl EntityId {
+    /// Creates a new entity id.
+    pub const fn new(id: u64) -> Self {
+        Self(id)
+    }
+
+    /// Returns the raw id value.
+    pub const fn raw(self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for EntityId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
@@ -0,0 +1,238 @@
+//! Typed event queue for decoupled game systems.
+//!
+//! Systems [`publish`](EventBus::publish) events as things happen during a turn,
+//! and the game loop [`drain`](EventBus::drain)s the queue once per frame. Draining
+//! first delivers every queued event to the registered subscribers, then hands
+//! the events to the caller.
+//!
+//! The bus is generic over the event type so games can use their own enum;
+//! [`GameEvent`] covers the events the Runeforge crates care about.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_core::prelude::*;
+//! use std::cell::Cell;
+//! use std::rc::Rc;
+//!
+//! let mut bus = EventBus::new();
+//! let moves = Rc::new(Cell::new(0));
+//!
+//! let counter = moves.clone();
+//! bus.subscribe(move |event| {
+//!     if let GameEvent::EntityMoved { .. } = event {
+//!         counter.set(counter.get() + 1);
+//!     }
+//! });
+//!
+//! bus.publish(GameEvent::EntityMoved {
+//!     entity: EntityId(1),
+//!     from: IVec2::new(0, 0),
+//!     to: IVec2::new(1, 0),
+//! });
+//!
+//! assert_eq!(bus.drain().count(), 1);
+//! assert_eq!(moves.get(), 1);
+//! ```
+
+use crate::entity::EntityId;
+use runeforge_geometry::prelude::IVec2;
+use std::fmt;
+
+/// Events shared between the world, input, FOV and UI layers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    /// An entity moved from one tile to another.
+    EntityMoved {
+        /// The entity that moved
+        entity: EntityId,
+        /// Previous position
+        from: IVec2,
+        /// New position
+        to: IVec2,
+    },
+    /// An entity was added to the world.
+    EntitySpawned {
+        /// The new entity
+        entity: EntityId,
+        /// Spawn position
+        pos: IVec2,
+    },
+    /// An entity was removed from the world.
+    EntityDespawned {
+        /// The removed entity
+        entity: EntityId,
+    },
+    /// A map tile changed (e.g. a door opened or a wall was dug out).
+    TileChanged {
+        /// Position of the changed tile
+        pos: IVec2,
+    },
+    /// A message should be shown to the player.
+    MessageLogged(String),
+}
+
+/// Handle returned by [`EventBus::subscribe`], used to unsubscribe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+type Handler<E> = Box<dyn FnMut(&E)>;
+
+/// A per-frame queue of events with optional subscribers.
+pub struct EventBus<E = GameEvent> {
+    queue: Vec<E>,
+    subscribers: Vec<(SubscriptionId, Handler<E>)>,
+    next_id: u64,
+}
+
+impl<E> Default for EventBus<E> {
+    fn default() -> Self {
+        Self {
+            queue: Vec::new(),
+            subscribers: Vec::new(),
+            next_id: 0,
+        }
+    }
+}
+
+impl<E> fmt::Debug for EventBus<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventBus")
+            .field("pending", &self.queue.len())
+            .field("subscribers", &self.subscribers.len())
+            .finish()
+    }
+}
+
+impl<E> EventBus<E> {
+    /// Creates an empty event bus.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues an event for the next [`drain`](Self::drain).
+    pub fn publish(&mut self, event: E) {
+        self.queue.push(event);
+    }
+
+    /// Registers a handler that is called for every drained event.
+    pub fn subscribe<F>(&mut self, handler: F) -> SubscriptionId
+    where
+        F: FnMut(&E) + 'static,
+    {
+        let id = SubscriptionId(self.next_id);
+        self.next_id += 1;
+        self.subscribers.push((id, Box::new(handler)));
+        id
+    }
+
+    /// Removes a subscriber. Returns `true` if it was registered.
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let before = self.subscribers.len();
+        self.subscribers.retain(|(sub, _)| *sub != id);
+        self.subscribers.len() != before
+    }
+
+    /// Delivers all queued events to subscribers, then drains them in publish order.
+    pub fn drain(&mut self) -> std::vec::Drain<'_, E> {
+        for event in &self.queue {
+            for (_, handler) in &mut self.subscribers {
+                handler(event);
+            }
+        }
+        self.queue.drain(..)
+    }
+
+    /// Returns the queued events without draining them.
+    pub fn pending(&self) -> &[E] {
+        &self.queue
+    }
+
+    /// Returns the number of queued events.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns true if no events are queued.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Discards all queued events without notifying subscribers.
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_publish_and_drain_order() {
+        let mut bus = EventBus::new();
+        bus.publish(GameEvent::TileChanged {
+            pos: IVec2::new(1, 1),
+        });
+        bus.publish(GameEvent::MessageLogged("hello".to_string()));
+        assert_eq!(bus.len(), 2);
+
+        let events: Vec<_> = bus.drain().collect();
+        assert_eq!(
+            events,
+            vec![
+                GameEvent::TileChanged {
+                    pos: IVec2::new(1, 1)
+                },
+                GameEvent::MessageLogged("hello".to_string()),
+            ]
+        );
+        assert!(bus.is_empty());
+    }
+
+    #[test]
+    fn test_subscribers_receive_events() {
+        let mut bus: EventBus<u32> = EventBus::new();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+
+        let sink = seen.clone();
+        bus.subscribe(move |e| sink.borrow_mut().push(*e));
+        bus.publish(1);
+        bus.publish(2);
+
+        // Subscribers are only notified on drain.
+        assert!(seen.borrow().is_empty());
+        bus.drain();
+        assert_eq!(*seen.borrow(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_unsubscribe() {
+        let mut bus: EventBus<u32> = EventBus::new();
+        let count = Rc::new(RefCell::new(0));
+
+        let c = count.clone();
+        let id = bus.subscribe(move |_| *c.borrow_mut() += 1);
+        assert!(bus.unsubscribe(id));
+        assert!(!bus.unsubscribe(id));
+
+        bus.publish(5);
+        bus.drain();
+        assert_eq!(*count.borrow(), 0);
+    }
+
+    #[test]
+    fn test_clear_skips_subscribers() {
+        let mut bus: EventBus<u32> = EventBus::new();
+        let count = Rc::new(RefCell::new(0));
+
+        let c = count.clone();
+        bus.subscribe(move |_| *c.borrow_mut() += 1);
+        bus.publish(5);
+        bus.clear();
+        bus.drain();
+        assert_eq!(*count.borrow(), 0);
+    }
+}
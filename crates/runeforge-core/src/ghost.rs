@@ -0,0 +1,281 @@
+//! "Last seen here" markers for entities that left view.
+//!
+//! When a monster walks out of sight, or the player turns a corner, players
+//! expect to still see where it was. A [`GhostMemory`] tracks the entities
+//! in view each turn and, when one disappears, leaves a [`Ghost`] at the
+//! cell it was last seen in. The ghost stays until that cell is observed
+//! again, or the entity comes back into view, and renderers draw it as a
+//! dimmed glyph over remembered terrain.
+//!
+//! If the last-seen cell is still in view when the entity disappears (it
+//! teleported, or stepped around a corner), no ghost is left: the player
+//! can see it is not there.
+//!
+//! With the `terminal` feature, [`draw`](GhostMemory::draw) puts the ghosts
+//! on a `runeforge-terminal` console.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_core::prelude::*;
+//!
+//! let mut ghosts = GhostMemory::new();
+//!
+//! // Turn 1: the player sees an orc at (5, 0)
+//! let in_view = |p: IVec2| p.x < 8;
+//! ghosts.observe(in_view, [("orc", IVec2::new(5, 0), 'o')], 1);
+//! assert!(ghosts.is_empty());
+//!
+//! // Turn 2: the player walks away and can only see x < 3
+//! let in_view = |p: IVec2| p.x < 3;
+//! ghosts.observe(in_view, [], 2);
+//! assert_eq!(ghosts.ghost_at(IVec2::new(5, 0)).unwrap().glyph, 'o');
+//!
+//! // Turn 3: back in view of (5, 0), and the orc isn't there any more
+//! ghosts.observe(|p: IVec2| p.x < 8, [], 3);
+//! assert!(ghosts.ghost_at(IVec2::new(5, 0)).is_none());
+//! ```
+
+use runeforge_geometry::prelude::IVec2;
+
+/// The remembered position of an entity that is out of view.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct Ghost<K> {
+    /// The entity, e.g. an [`EntityId`](crate::entity::EntityId)
+    pub entity: K,
+    /// Where it was last seen
+    pub position: IVec2,
+    /// Glyph it was drawn with
+    pub glyph: char,
+    /// Turn it was last seen
+    pub last_seen: u64,
+}
+
+/// Entities in view this turn, and ghosts of those that were seen before.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct GhostMemory<K> {
+    /// Entities seen on the last [`observe`](GhostMemory::observe).
+    in_view: Vec<Ghost<K>>,
+    ghosts: Vec<Ghost<K>>,
+}
+
+impl<K> Default for GhostMemory<K> {
+    fn default() -> Self {
+        Self {
+            in_view: Vec::new(),
+            ghosts: Vec::new(),
+        }
+    }
+}
+
+impl<K: Clone + PartialEq> GhostMemory<K> {
+    /// Creates an empty memory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the memory after FOV is computed for `turn`.
+    ///
+    /// `is_visible` says whether a cell is in view, and `seen` lists every
+    /// entity in view with its position and glyph. Entities that were seen
+    /// last time but not now leave a ghost at their last position, unless
+    /// that position is visible. Ghosts on visible cells, and ghosts of
+    /// entities seen again, are removed.
+    ///
+    /// Returns the entities that left a new ghost.
+    pub fn observe(
+        &mut self,
+        is_visible: impl Fn(IVec2) -> bool,
+        seen: impl IntoIterator<Item = (K, IVec2, char)>,
+        turn: u64,
+    ) -> Vec<K> {
+        let now: Vec<Ghost<K>> = seen
+            .into_iter()
+            .map(|(entity, position, glyph)| Ghost {
+                entity,
+                position,
+                glyph,
+                last_seen: turn,
+            })
+            .collect();
+
+        let gone: Vec<Ghost<K>> = self
+            .in_view
+            .drain(..)
+            .filter(|old| !now.iter().any(|n| n.entity == old.entity))
+            .collect();
+
+        self.ghosts
+            .retain(|g| !is_visible(g.position) && !now.iter().any(|n| n.entity == g.entity));
+
+        let mut left = Vec::new();
+        for ghost in gone {
+            if !is_visible(ghost.position) {
+                left.push(ghost.entity.clone());
+                self.ghosts.push(ghost);
+            }
+        }
+        self.in_view = now;
+        left
+    }
+
+    /// Returns the ghost of `entity`, if it is out of view and remembered.
+    pub fn ghost(&self, entity: &K) -> Option<&Ghost<K>> {
+        self.ghosts.iter().find(|g| g.entity == *entity)
+    }
+
+    /// Returns the most recent ghost at `pos`.
+    pub fn ghost_at(&self, pos: IVec2) -> Option<&Ghost<K>> {
+        self.ghosts
+            .iter()
+            .filter(|g| g.position == pos)
+            .max_by_key(|g| g.last_seen)
+    }
+
+    /// Returns true if `entity` was in view on the last update.
+    pub fn is_in_view(&self, entity: &K) -> bool {
+        self.in_view.iter().any(|g| g.entity == *entity)
+    }
+
+    /// Forgets the ghost of `entity`, e.g. when it is known to have died.
+    pub fn forget(&mut self, entity: &K) -> Option<Ghost<K>> {
+        let idx = self.ghosts.iter().position(|g| g.entity == *entity)?;
+        Some(self.ghosts.remove(idx))
+    }
+
+    /// Forgets every ghost at `pos`.
+    pub fn forget_at(&mut self, pos: IVec2) {
+        self.ghosts.retain(|g| g.position != pos);
+    }
+
+    /// Returns the position and glyph of every ghost, oldest first, for
+    /// drawing.
+    pub fn markers(&self) -> impl Iterator<Item = (IVec2, char)> + '_ {
+        self.iter().map(|g| (g.position, g.glyph))
+    }
+
+    /// Returns every ghost, oldest first, so newer ghosts on the same cell
+    /// draw on top.
+    pub fn iter(&self) -> impl Iterator<Item = &Ghost<K>> {
+        let mut ghosts: Vec<&Ghost<K>> = self.ghosts.iter().collect();
+        ghosts.sort_by_key(|g| g.last_seen);
+        ghosts.into_iter()
+    }
+
+    /// Returns the number of ghosts.
+    pub fn len(&self) -> usize {
+        self.ghosts.len()
+    }
+
+    /// Returns true if there are no ghosts.
+    pub fn is_empty(&self) -> bool {
+        self.ghosts.is_empty()
+    }
+
+    /// Forgets every ghost and entity in view, e.g. on a new level.
+    pub fn clear(&mut self) {
+        self.in_view.clear();
+        self.ghosts.clear();
+    }
+}
+
+#[cfg(feature = "terminal")]
+impl<K: Clone + PartialEq> GhostMemory<K> {
+    /// Draws every ghost's glyph in `color`, keeping the background of the
+    /// cell underneath, so they sit dimmed over remembered terrain.
+    /// `mapping` places world positions on the console.
+    pub fn draw<C: runeforge_terminal::prelude::Console + ?Sized>(
+        &self,
+        console: &mut C,
+        mapping: &runeforge_geometry::prelude::ScreenMapping,
+        color: runeforge_color::Color,
+    ) {
+        use runeforge_geometry::prelude::WorldPos;
+
+        for (pos, glyph) in self.markers() {
+            let pos = WorldPos(pos);
+            if let Some(cell) = console.get_world(mapping, pos) {
+                console.set_world(mapping, pos, cell.with_char(glyph).with_fg(color));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(x: i32) -> IVec2 {
+        IVec2::new(x, 0)
+    }
+
+    #[test]
+    fn test_ghost_left_when_view_moves() {
+        let mut memory = GhostMemory::new();
+        memory.observe(|p| p.x < 10, [(1, line(4), 'k'), (2, line(8), 'o')], 1);
+        let left = memory.observe(|p| p.x < 6, [(1, line(5), 'k')], 2);
+        assert_eq!(left, [2]);
+        assert_eq!(memory.ghost(&2).unwrap().position, line(8));
+        assert_eq!(memory.ghost(&2).unwrap().last_seen, 1);
+        assert!(memory.ghost(&1).is_none());
+        assert!(memory.is_in_view(&1));
+
+        // Still out of view: the ghost stays
+        memory.observe(|p| p.x < 6, [(1, line(5), 'k')], 3);
+        assert_eq!(memory.len(), 1);
+    }
+
+    #[test]
+    fn test_no_ghost_on_visible_cell() {
+        let mut memory = GhostMemory::new();
+        memory.observe(|_| true, [(1, line(2), 'g')], 1);
+        assert!(memory.observe(|_| true, [], 2).is_empty());
+        assert!(memory.is_empty());
+    }
+
+    #[test]
+    fn test_ghost_cleared_when_entity_returns() {
+        let mut memory = GhostMemory::new();
+        memory.observe(|p| p.x < 5, [(7, line(3), 'D')], 1);
+        memory.observe(|p| p.x < 2, [], 2);
+        assert_eq!(memory.ghost_at(line(3)).unwrap().entity, 7);
+
+        // Seen somewhere else, still far from its ghost
+        memory.observe(|p| p.x < 2, [(7, line(0), 'D')], 3);
+        assert!(memory.ghost_at(line(3)).is_none());
+
+        memory.observe(|p| p.x < 1, [], 4);
+        memory.forget(&7);
+        assert!(memory.is_empty());
+    }
+
+    #[cfg(feature = "terminal")]
+    #[test]
+    fn test_draw_keeps_background() {
+        use runeforge_color::Color;
+        use runeforge_geometry::prelude::{CoordinateSystem, ScreenMapping, WorldPos};
+        use runeforge_terminal::prelude::*;
+        use runeforge_terminal::rfc::ConsoleImage;
+
+        let mut memory = GhostMemory::new();
+        memory.observe(|_| false, [(1, IVec2::new(3, 2), 'o')], 1);
+        memory.observe(|_| false, [], 2);
+
+        let mut console = ConsoleImage::new(4, 4);
+        console.set(IVec2::new(2, 1), Cell::new('.', Color::WHITE, Color::BLUE));
+        let mapping = ScreenMapping::new(CoordinateSystem::YDown, WorldPos::new(1, 1));
+        memory.draw(&mut console, &mapping, Color::GRAY);
+        assert_eq!(
+            console.get(IVec2::new(2, 1)),
+            Some(Cell::new('o', Color::GRAY, Color::BLUE))
+        );
+    }
+}
@@ -0,0 +1,645 @@
+//! Item containers with stacking, capacity limits and equipment slots.
+//!
+//! An [`Inventory`] holds [`ItemStack`]s of any type implementing [`Item`].
+//! Capacity is either a number of slots or a total carry weight, items that
+//! [stack](Item::stacks_with) are merged up to their maximum stack size, and
+//! equippable items can be moved into named equipment slots.
+//!
+//! Every change is recorded as an [`InventoryEvent`]; forward them to an
+//! [`EventBus`] with [`publish_events`](Inventory::publish_events) so the message
+//! log and UI can react.
+//!
+//! For keyboard menus, [`menu`](Inventory::menu) lists the stacks sorted by
+//! category with an `a`-`z` letter each, and [`index_for_letter`](Inventory::index_for_letter)
+//! maps a pressed letter back to a stack index.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_core::inventory::{Capacity, Inventory, Item};
+//!
+//! #[derive(Debug, Clone, PartialEq)]
+//! enum Thing { Arrow, Sword }
+//!
+//! impl Item for Thing {
+//!     type Category = u8;
+//!     type Slot = &'static str;
+//!
+//!     fn category(&self) -> u8 {
+//!         match self { Thing::Sword => 0, Thing::Arrow => 1 }
+//!     }
+//!     fn max_stack(&self) -> u32 {
+//!         if *self == Thing::Arrow { 99 } else { 1 }
+//!     }
+//!     fn stacks_with(&self, other: &Self) -> bool {
+//!         self == other
+//!     }
+//!     fn equip_slot(&self) -> Option<&'static str> {
+//!         (*self == Thing::Sword).then_some("weapon")
+//!     }
+//! }
+//!
+//! let mut pack = Inventory::new(Capacity::Slots(10));
+//! pack.add(Thing::Arrow, 20).unwrap();
+//! pack.add(Thing::Arrow, 5).unwrap();
+//! pack.add(Thing::Sword, 1).unwrap();
+//! assert_eq!(pack.len(), 2);
+//!
+//! let menu = pack.menu();
+//! assert_eq!(menu[0].0, 'a');
+//! assert_eq!(menu[0].1.item, Thing::Sword);
+//! assert_eq!(menu[1].1.count, 25);
+//!
+//! let sword = pack.index_for_letter('a').unwrap();
+//! pack.equip(sword).unwrap();
+//! assert_eq!(pack.equipped(&"weapon").unwrap().item, Thing::Sword);
+//! ```
+
+use thiserror::Error;
+
+use crate::event::EventBus;
+
+/// Behavior an item type must provide to be stored in an [`Inventory`].
+pub trait Item: Clone {
+    /// Category used to sort items in menus (e.g. weapons before potions).
+    type Category: Ord;
+    /// Equipment slot identifier.
+    type Slot: PartialEq + Clone;
+
+    /// Returns the item's category.
+    fn category(&self) -> Self::Category;
+
+    /// Returns the weight of a single item. Defaults to 0.
+    fn weight(&self) -> u32 {
+        0
+    }
+
+    /// Returns the maximum number of items in one stack. Defaults to 1.
+    fn max_stack(&self) -> u32 {
+        1
+    }
+
+    /// Returns true if this item can share a stack with `other`.
+    fn stacks_with(&self, other: &Self) -> bool;
+
+    /// Returns the slot this item is equipped in, or `None` if it cannot be equipped.
+    fn equip_slot(&self) -> Option<Self::Slot> {
+        None
+    }
+}
+
+/// A number of identical items.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemStack<I> {
+    /// The item
+    pub item: I,
+    /// How many items are in the stack
+    pub count: u32,
+}
+
+impl<I: Item> ItemStack<I> {
+    /// Returns the combined weight of the stack.
+    pub fn weight(&self) -> u32 {
+        self.item.weight().saturating_mul(self.count)
+    }
+}
+
+/// How much an inventory can hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capacity {
+    /// A fixed number of stacks.
+    Slots(usize),
+    /// A maximum total weight.
+    Weight(u32),
+    /// No limit.
+    Unlimited,
+}
+
+/// Errors returned by inventory operations.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InventoryError {
+    /// There is no free slot for a new stack.
+    #[error("Inventory is full")]
+    NoFreeSlot,
+    /// The items would exceed the carry weight.
+    #[error("Too heavy to carry ({weight} / {max})")]
+    TooHeavy {
+        /// Weight the inventory would have after the change
+        weight: u32,
+        /// Maximum carry weight
+        max: u32,
+    },
+    /// No stack exists at the given index.
+    #[error("No item at index {0}")]
+    InvalidIndex(usize),
+    /// Tried to remove more items than the stack holds.
+    #[error("Requested {requested} items but only {available} available")]
+    NotEnough {
+        /// Number of items requested
+        requested: u32,
+        /// Number of items available
+        available: u32,
+    },
+    /// The item cannot be equipped.
+    #[error("Item cannot be equipped")]
+    NotEquippable,
+    /// Nothing is equipped in the given slot.
+    #[error("Nothing equipped in that slot")]
+    SlotEmpty,
+}
+
+impl InventoryError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            InventoryError::NoFreeSlot => "inventory.no_free_slot",
+            InventoryError::TooHeavy { .. } => "inventory.too_heavy",
+            InventoryError::InvalidIndex(_) => "inventory.invalid_index",
+            InventoryError::NotEnough { .. } => "inventory.not_enough",
+            InventoryError::NotEquippable => "inventory.not_equippable",
+            InventoryError::SlotEmpty => "inventory.slot_empty",
+        }
+    }
+}
+
+/// A change to an inventory.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InventoryEvent<I: Item> {
+    /// Items were added.
+    Added(ItemStack<I>),
+    /// Items were removed.
+    Removed(ItemStack<I>),
+    /// An item was equipped.
+    Equipped {
+        /// Slot the item went into
+        slot: I::Slot,
+        /// The equipped item
+        item: I,
+    },
+    /// An item was unequipped and returned to the pack.
+    Unequipped {
+        /// Slot the item came from
+        slot: I::Slot,
+        /// The unequipped item
+        item: I,
+    },
+}
+
+/// A container of item stacks with optional equipment slots.
+#[derive(Debug, Clone)]
+pub struct Inventory<I: Item> {
+    capacity: Capacity,
+    stacks: Vec<ItemStack<I>>,
+    equipped: Vec<(I::Slot, ItemStack<I>)>,
+    events: Vec<InventoryEvent<I>>,
+}
+
+impl<I: Item> Inventory<I> {
+    /// Creates an empty inventory with the given capacity.
+    pub fn new(capacity: Capacity) -> Self {
+        Self {
+            capacity,
+            stacks: Vec::new(),
+            equipped: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Returns the capacity.
+    pub fn capacity(&self) -> Capacity {
+        self.capacity
+    }
+
+    /// Returns the total weight of carried and equipped items.
+    pub fn weight(&self) -> u32 {
+        self.stacks
+            .iter()
+            .chain(self.equipped.iter().map(|(_, s)| s))
+            .map(ItemStack::weight)
+            .sum()
+    }
+
+    /// Returns true if `count` copies of `item` would fit.
+    pub fn can_add(&self, item: &I, count: u32) -> bool {
+        self.check_add(item, count).is_ok()
+    }
+
+    /// Adds items, merging into existing stacks where possible.
+    ///
+    /// The operation is all-or-nothing: if the items don't fit, nothing is added.
+    pub fn add(&mut self, item: I, count: u32) -> Result<(), InventoryError> {
+        if count == 0 {
+            return Ok(());
+        }
+        self.check_add(&item, count)?;
+
+        let max = item.max_stack().max(1);
+        let mut remaining = count;
+        for stack in &mut self.stacks {
+            if remaining == 0 {
+                break;
+            }
+            if stack.item.stacks_with(&item) && stack.count < max {
+                let moved = remaining.min(max - stack.count);
+                stack.count += moved;
+                remaining -= moved;
+            }
+        }
+        while remaining > 0 {
+            let moved = remaining.min(max);
+            self.stacks.push(ItemStack {
+                item: item.clone(),
+                count: moved,
+            });
+            remaining -= moved;
+        }
+
+        self.events
+            .push(InventoryEvent::Added(ItemStack { item, count }));
+        Ok(())
+    }
+
+    /// Removes `count` items from the stack at `index`, returning them.
+    pub fn remove(&mut self, index: usize, count: u32) -> Result<ItemStack<I>, InventoryError> {
+        let stack = self
+            .stacks
+            .get_mut(index)
+            .ok_or(InventoryError::InvalidIndex(index))?;
+        if count > stack.count {
+            return Err(InventoryError::NotEnough {
+                requested: count,
+                available: stack.count,
+            });
+        }
+
+        stack.count -= count;
+        let removed = ItemStack {
+            item: stack.item.clone(),
+            count,
+        };
+        if stack.count == 0 {
+            self.stacks.remove(index);
+        }
+        self.events.push(InventoryEvent::Removed(removed.clone()));
+        Ok(removed)
+    }
+
+    /// Moves the item at `index` into its equipment slot.
+    ///
+    /// Whatever was in the slot before is returned to the pack. Equipping takes
+    /// a single item from the stack.
+    pub fn equip(&mut self, index: usize) -> Result<(), InventoryError> {
+        let stack = self
+            .stacks
+            .get(index)
+            .ok_or(InventoryError::InvalidIndex(index))?;
+        let slot = stack
+            .item
+            .equip_slot()
+            .ok_or(InventoryError::NotEquippable)?;
+
+        let taken = self.take(index, 1);
+        if self.is_equipped(&slot) {
+            if let Err(err) = self.unequip(&slot) {
+                self.put_back(index, taken);
+                return Err(err);
+            }
+        }
+
+        self.events.push(InventoryEvent::Equipped {
+            slot: slot.clone(),
+            item: taken.item.clone(),
+        });
+        self.equipped.push((slot, taken));
+        Ok(())
+    }
+
+    /// Moves the item in `slot` back into the pack.
+    pub fn unequip(&mut self, slot: &I::Slot) -> Result<(), InventoryError> {
+        let idx = self
+            .equipped
+            .iter()
+            .position(|(s, _)| s == slot)
+            .ok_or(InventoryError::SlotEmpty)?;
+
+        let (slot, stack) = self.equipped.remove(idx);
+        if let Err(err) = self.check_add(&stack.item, stack.count) {
+            // Equipped weight already counts toward the limit, so only a lack
+            // of free slots can get us here.
+            self.equipped.insert(idx, (slot, stack));
+            return Err(err);
+        }
+
+        self.insert_quiet(stack.clone());
+        self.events.push(InventoryEvent::Unequipped {
+            slot,
+            item: stack.item,
+        });
+        Ok(())
+    }
+
+    /// Returns the item equipped in `slot`.
+    pub fn equipped(&self, slot: &I::Slot) -> Option<&ItemStack<I>> {
+        self.equipped
+            .iter()
+            .find(|(s, _)| s == slot)
+            .map(|(_, i)| i)
+    }
+
+    /// Returns true if something is equipped in `slot`.
+    pub fn is_equipped(&self, slot: &I::Slot) -> bool {
+        self.equipped(slot).is_some()
+    }
+
+    /// Returns an iterator over equipped items and their slots.
+    pub fn iter_equipped(&self) -> impl Iterator<Item = (&I::Slot, &ItemStack<I>)> {
+        self.equipped.iter().map(|(s, i)| (s, i))
+    }
+
+    /// Returns the stack at `index`.
+    pub fn get(&self, index: usize) -> Option<&ItemStack<I>> {
+        self.stacks.get(index)
+    }
+
+    /// Returns an iterator over the carried stacks in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = &ItemStack<I>> {
+        self.stacks.iter()
+    }
+
+    /// Returns `(index, stack)` pairs sorted by category, keeping insertion
+    /// order within a category.
+    pub fn sorted(&self) -> Vec<(usize, &ItemStack<I>)> {
+        let mut sorted: Vec<_> = self.stacks.iter().enumerate().collect();
+        sorted.sort_by_key(|(_, s)| s.item.category());
+        sorted
+    }
+
+    /// Returns the stacks sorted by category, each labeled with a menu letter.
+    ///
+    /// Only the first 26 stacks get a letter (`a` to `z`).
+    pub fn menu(&self) -> Vec<(char, &ItemStack<I>)> {
+        self.sorted()
+            .into_iter()
+            .zip('a'..='z')
+            .map(|((_, stack), letter)| (letter, stack))
+            .collect()
+    }
+
+    /// Maps a menu letter from [`menu`](Self::menu) back to a stack index.
+    pub fn index_for_letter(&self, letter: char) -> Option<usize> {
+        let pos = (letter as u32).checked_sub('a' as u32)? as usize;
+        if pos >= 26 {
+            return None;
+        }
+        self.sorted().get(pos).map(|&(index, _)| index)
+    }
+
+    /// Returns the number of carried stacks.
+    pub fn len(&self) -> usize {
+        self.stacks.len()
+    }
+
+    /// Returns true if no stacks are carried.
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+
+    /// Takes the recorded events, oldest first.
+    pub fn drain_events(&mut self) -> std::vec::Drain<'_, InventoryEvent<I>> {
+        self.events.drain(..)
+    }
+
+    /// Publishes the recorded events to an event bus.
+    pub fn publish_events<E: From<InventoryEvent<I>>>(&mut self, bus: &mut EventBus<E>) {
+        for event in self.events.drain(..) {
+            bus.publish(event.into());
+        }
+    }
+
+    fn check_add(&self, item: &I, count: u32) -> Result<(), InventoryError> {
+        match self.capacity {
+            Capacity::Unlimited => Ok(()),
+            Capacity::Weight(max) => {
+                let weight = self
+                    .weight()
+                    .saturating_add(item.weight().saturating_mul(count));
+                if weight > max {
+                    Err(InventoryError::TooHeavy { weight, max })
+                } else {
+                    Ok(())
+                }
+            }
+            Capacity::Slots(slots) => {
+                let max = item.max_stack().max(1);
+                let room: u32 = self
+                    .stacks
+                    .iter()
+                    .filter(|s| s.item.stacks_with(item))
+                    .map(|s| max.saturating_sub(s.count))
+                    .sum();
+                let overflow = count.saturating_sub(room);
+                let new_stacks = overflow.div_ceil(max) as usize;
+                if self.stacks.len() + new_stacks > slots {
+                    Err(InventoryError::NoFreeSlot)
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    fn take(&mut self, index: usize, count: u32) -> ItemStack<I> {
+        let stack = &mut self.stacks[index];
+        stack.count -= count;
+        let taken = ItemStack {
+            item: stack.item.clone(),
+            count,
+        };
+        if stack.count == 0 {
+            self.stacks.remove(index);
+        }
+        taken
+    }
+
+    fn put_back(&mut self, index: usize, stack: ItemStack<I>) {
+        match self.stacks.get_mut(index) {
+            Some(existing) if existing.item.stacks_with(&stack.item) => {
+                existing.count += stack.count
+            }
+            _ => self.stacks.insert(index.min(self.stacks.len()), stack),
+        }
+    }
+
+    fn insert_quiet(&mut self, stack: ItemStack<I>) {
+        let max = stack.item.max_stack().max(1);
+        if let Some(existing) = self
+            .stacks
+            .iter_mut()
+            .find(|s| s.item.stacks_with(&stack.item) && s.count + stack.count <= max)
+        {
+            existing.count += stack.count;
+        } else {
+            self.stacks.push(stack);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Thing {
+        Potion,
+        Sword(u8),
+        Boulder,
+    }
+
+    impl Item for Thing {
+        type Category = u8;
+        type Slot = &'static str;
+
+        fn category(&self) -> u8 {
+            match self {
+                Thing::Sword(_) => 0,
+                Thing::Potion => 1,
+                Thing::Boulder => 2,
+            }
+        }
+
+        fn weight(&self) -> u32 {
+            match self {
+                Thing::Boulder => 50,
+                _ => 1,
+            }
+        }
+
+        fn max_stack(&self) -> u32 {
+            if *self == Thing::Potion {
+                5
+            } else {
+                1
+            }
+        }
+
+        fn stacks_with(&self, other: &Self) -> bool {
+            self == other
+        }
+
+        fn equip_slot(&self) -> Option<&'static str> {
+            matches!(self, Thing::Sword(_)).then_some("weapon")
+        }
+    }
+
+    #[test]
+    fn test_stacking_respects_max_stack() {
+        let mut inv = Inventory::new(Capacity::Unlimited);
+        inv.add(Thing::Potion, 3).unwrap();
+        inv.add(Thing::Potion, 4).unwrap();
+        let counts: Vec<_> = inv.iter().map(|s| s.count).collect();
+        assert_eq!(counts, vec![5, 2]);
+    }
+
+    #[test]
+    fn test_slot_capacity_is_all_or_nothing() {
+        let mut inv = Inventory::new(Capacity::Slots(2));
+        inv.add(Thing::Potion, 4).unwrap();
+        assert_eq!(inv.add(Thing::Potion, 7), Err(InventoryError::NoFreeSlot));
+        assert_eq!(inv.get(0).unwrap().count, 4);
+        inv.add(Thing::Potion, 6).unwrap();
+        assert_eq!(inv.len(), 2);
+    }
+
+    #[test]
+    fn test_weight_capacity() {
+        let mut inv = Inventory::new(Capacity::Weight(60));
+        inv.add(Thing::Boulder, 1).unwrap();
+        assert_eq!(
+            inv.add(Thing::Boulder, 1),
+            Err(InventoryError::TooHeavy {
+                weight: 100,
+                max: 60
+            })
+        );
+        assert!(inv.can_add(&Thing::Potion, 10));
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut inv = Inventory::new(Capacity::Unlimited);
+        inv.add(Thing::Potion, 3).unwrap();
+        assert_eq!(inv.remove(0, 2).unwrap().count, 2);
+        assert!(matches!(
+            inv.remove(0, 5),
+            Err(InventoryError::NotEnough { .. })
+        ));
+        inv.remove(0, 1).unwrap();
+        assert!(inv.is_empty());
+        assert_eq!(inv.remove(0, 1), Err(InventoryError::InvalidIndex(0)));
+    }
+
+    #[test]
+    fn test_equip_swaps_previous_item() {
+        let mut inv = Inventory::new(Capacity::Slots(3));
+        inv.add(Thing::Sword(1), 1).unwrap();
+        inv.add(Thing::Sword(2), 1).unwrap();
+        inv.add(Thing::Potion, 1).unwrap();
+        assert_eq!(inv.equip(2), Err(InventoryError::NotEquippable));
+
+        inv.equip(0).unwrap();
+        assert_eq!(inv.equipped(&"weapon").unwrap().item, Thing::Sword(1));
+        assert_eq!(inv.len(), 2);
+
+        let sword2 = inv.iter().position(|s| s.item == Thing::Sword(2)).unwrap();
+        inv.equip(sword2).unwrap();
+        assert_eq!(inv.equipped(&"weapon").unwrap().item, Thing::Sword(2));
+        assert!(inv.iter().any(|s| s.item == Thing::Sword(1)));
+
+        inv.unequip(&"weapon").unwrap();
+        assert!(!inv.is_equipped(&"weapon"));
+        assert_eq!(inv.unequip(&"weapon"), Err(InventoryError::SlotEmpty));
+    }
+
+    #[test]
+    fn test_menu_sorted_by_category() {
+        let mut inv = Inventory::new(Capacity::Unlimited);
+        inv.add(Thing::Potion, 1).unwrap();
+        inv.add(Thing::Boulder, 1).unwrap();
+        inv.add(Thing::Sword(0), 1).unwrap();
+
+        let letters: Vec<_> = inv
+            .menu()
+            .into_iter()
+            .map(|(c, s)| (c, s.item.clone()))
+            .collect();
+        assert_eq!(
+            letters,
+            vec![
+                ('a', Thing::Sword(0)),
+                ('b', Thing::Potion),
+                ('c', Thing::Boulder)
+            ]
+        );
+        assert_eq!(inv.index_for_letter('a'), Some(2));
+        assert_eq!(inv.index_for_letter('d'), None);
+        assert_eq!(inv.index_for_letter('A'), None);
+    }
+
+    #[test]
+    fn test_events_published() {
+        let mut inv = Inventory::new(Capacity::Unlimited);
+        inv.add(Thing::Sword(0), 1).unwrap();
+        inv.equip(0).unwrap();
+        inv.unequip(&"weapon").unwrap();
+
+        let mut bus: EventBus<InventoryEvent<Thing>> = EventBus::new();
+        inv.publish_events(&mut bus);
+        let events: Vec<_> = bus.drain().collect();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(
+            events[1],
+            InventoryEvent::Equipped { slot: "weapon", .. }
+        ));
+        assert_eq!(inv.drain_events().count(), 0);
+    }
+}
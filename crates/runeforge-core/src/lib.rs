@@ -0,0 +1,107 @@
+//! Core game-object plumbing for roguelike games.
+//!
+//! # Overview
+//!
+//! `runeforge-core` holds the glue that sits between the other Runeforge crates:
+//! the world, input, FOV and UI layers talk to each other through the types
+//! defined here instead of calling into one another directly.
+//!
+//! *   **Achievements:** Run [`Statistics`](achievement::Statistics) and threshold-based [`Achievement`](achievement::Achievement)s.
+//! *   **Actions:** Command-pattern [`Action`](action::Action)s with validation, energy costs and undo.
+//! *   **Combat:** Dice-driven [`resolve_attack`](combat::resolve_attack) with crits and resistances (`combat` feature).
+//! *   **Entities:** A lightweight [`EntityId`](entity::EntityId) handle.
+//! *   **Inventory:** An [`Inventory`](inventory::Inventory) with stacking, carry limits and equipment slots.
+//! *   **Events:** A typed [`EventBus`](event::EventBus) for publishing [`GameEvent`](event::GameEvent)s.
+//! *   **Ghosts:** A [`GhostMemory`](ghost::GhostMemory) of where out-of-view entities were last seen, drawn as dimmed glyphs.
+//! *   **Points of Interest:** A [`PoiMemory`](poi::PoiMemory) of seen items and features for auto-travel and minimaps.
+//! *   **High Scores:** A signed [`HighScoreTable`](score::HighScoreTable) with a board per seed.
+//! *   **Stats:** [`Stats`](stats::Stats) with base attributes, modifier sources, cached derived values and change hooks.
+//! *   **Status Effects:** Timed [`StatusEffects`](status::StatusEffects) with stacking policies and stat [`Modifier`](modifier::Modifier)s.
+//! *   **Survival:** Decaying resources (hunger, fuel, oxygen) in a [`SurvivalClock`](survival::SurvivalClock) with level thresholds.
+//! *   **Timers:** A turn-based [`DelayQueue`](timer::DelayQueue) for delayed and repeating events.
+//! *   **Travel:** A [`TravelExecutor`](travel::TravelExecutor) for running and auto-travel that stops for enemies, items and junctions.
+//! *   **Templates:** Data-driven [`EntityTemplate`](template::EntityTemplate)s with depth-weighted spawning.
+//!
+//! # Features
+//!
+//! *   `combat`: The [`combat`] starter kit.
+//! *   `serialization`: Serde support for templates, remembered points of interest and achievements.
+//! *   `terminal`: Draw high-score boards and ghosts to a `runeforge-terminal` console.
+//! *   `ron`: Load templates from RON ([`TemplateSet::from_ron_str`](template::TemplateSet)).
+//! *   `toml`: Load templates from TOML ([`TemplateSet::from_toml_str`](template::TemplateSet)).
+//!
+//! # Usage
+//!
+//! Add this to your `Cargo.toml`:
+//!
+//! ```toml
+//! [dependencies]
+//! runeforge-core = "0.1"
+//! ```
+//!
+//! ## Event Bus Example
+//!
+//! ```rust
+//! use runeforge_core::prelude::*;
+//!
+//! fn main() {
+//!     let mut bus = EventBus::new();
+//!     bus.publish(GameEvent::MessageLogged("You hear a door creak.".to_string()));
+//!
+//!     // Drain all events once per frame
+//!     for event in bus.drain() {
+//!         println!("{:?}", event);
+//!     }
+//! }
+//! ```
+
+#![deny(missing_docs)]
+
+pub mod achievement;
+pub mod action;
+#[cfg(feature = "combat")]
+pub mod combat;
+pub mod entity;
+pub mod event;
+pub mod ghost;
+pub mod inventory;
+pub mod modifier;
+pub mod poi;
+pub mod score;
+mod sha256;
+pub mod stats;
+pub mod status;
+pub mod survival;
+pub mod template;
+pub mod timer;
+pub mod travel;
+
+/// Prelude module for convenient imports.
+pub mod prelude {
+    pub use runeforge_geometry::prelude::IVec2;
+
+    pub use crate::achievement::{
+        Achievement, AchievementTracker, AchievementUnlocked, StatEvent, Statistics,
+    };
+    pub use crate::action::{
+        Action, ActionBindings, ActionError, ActionOutcome, ActionQueue, DEFAULT_ENERGY_COST,
+    };
+    pub use crate::entity::EntityId;
+    pub use crate::event::{EventBus, GameEvent, SubscriptionId};
+    pub use crate::ghost::{Ghost, GhostMemory};
+    pub use crate::inventory::{
+        Capacity, Inventory, InventoryError, InventoryEvent, Item, ItemStack,
+    };
+    pub use crate::modifier::{apply_modifiers, Modifier, ModifierOp};
+    pub use crate::poi::{PoiMemory, PointOfInterest};
+    pub use crate::score::{HighScore, HighScoreTable, ScoreError};
+    pub use crate::stats::Stats;
+    pub use crate::status::{StackingPolicy, StatusEffect, StatusEffects};
+    pub use crate::survival::{DecayConfig, Resource, ResourceEvent, SurvivalClock};
+    pub use crate::template::{EntityTemplate, SpawnWeight, TemplateError, TemplateSet};
+    pub use crate::timer::{DelayQueue, TimerId};
+    pub use crate::travel::{
+        InterruptRules, TravelExecutor, TravelMap, TravelStatus, TravelStop,
+        DEFAULT_MAX_TRAVEL_STEPS,
+    };
+}
@@ -0,0 +1,118 @@
+//! Stat modifiers shared by status effects, equipment and derived stats.
+
+use std::borrow::Borrow;
+
+/// How a [`Modifier`] changes a stat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModifierOp {
+    /// Adds a flat amount.
+    Add(i32),
+    /// Scales by a percentage (e.g. `Percent(25)` is +25%, `Percent(-50)` is -50%).
+    Percent(i32),
+}
+
+/// A change to a single stat, identified by a key of type `S`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Modifier<S> {
+    /// The stat this modifier applies to
+    pub stat: S,
+    /// The change applied to the stat
+    pub op: ModifierOp,
+}
+
+impl ModifierOp {
+    /// Returns this change applied `times` times over, saturating at the
+    /// bounds of `i32`.
+    pub fn scaled(self, times: u32) -> Self {
+        let times = i32::try_from(times).unwrap_or(i32::MAX);
+        match self {
+            ModifierOp::Add(amount) => ModifierOp::Add(amount.saturating_mul(times)),
+            ModifierOp::Percent(p) => ModifierOp::Percent(p.saturating_mul(times)),
+        }
+    }
+}
+
+impl<S> Modifier<S> {
+    /// Creates a flat modifier.
+    pub fn add(stat: S, amount: i32) -> Self {
+        Self {
+            stat,
+            op: ModifierOp::Add(amount),
+        }
+    }
+
+    /// Creates a percentage modifier.
+    pub fn percent(stat: S, percent: i32) -> Self {
+        Self {
+            stat,
+            op: ModifierOp::Percent(percent),
+        }
+    }
+}
+
+/// Applies modifiers to a base value.
+///
+/// All flat modifiers are summed first, then the summed percentages are applied
+/// to the result, so the order of the modifiers does not matter. A total below
+/// -100% scales the value to zero rather than flipping its sign. The result is
+/// rounded toward zero and saturates at the bounds of `i32`.
+///
+/// # Example
+///
+/// ```
+/// use runeforge_core::modifier::{apply_modifiers, ModifierOp};
+///
+/// let ops = [ModifierOp::Add(5), ModifierOp::Percent(50)];
+/// assert_eq!(apply_modifiers(10, ops.iter()), 22);
+/// ```
+pub fn apply_modifiers<I>(base: i32, ops: I) -> i32
+where
+    I: IntoIterator,
+    I::Item: Borrow<ModifierOp>,
+{
+    let mut flat = 0i64;
+    let mut percent = 0i64;
+    for op in ops {
+        match *op.borrow() {
+            ModifierOp::Add(amount) => flat = flat.saturating_add(amount as i64),
+            ModifierOp::Percent(p) => percent = percent.saturating_add(p as i64),
+        }
+    }
+    let value = (base as i64)
+        .saturating_add(flat)
+        .saturating_mul(percent.saturating_add(100).max(0))
+        / 100;
+    value.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_modifiers_saturates() {
+        let ops = [
+            ModifierOp::Percent(i32::MAX),
+            ModifierOp::Percent(i32::MAX),
+            ModifierOp::Percent(i32::MAX),
+            ModifierOp::Add(i32::MAX),
+            ModifierOp::Add(i32::MAX),
+            ModifierOp::Add(i32::MAX),
+        ];
+        assert_eq!(apply_modifiers(i32::MAX, ops.iter()), i32::MAX);
+        assert_eq!(
+            apply_modifiers(i32::MIN, [ModifierOp::Percent(i32::MAX)]),
+            i32::MIN
+        );
+        assert_eq!(apply_modifiers(10, [ModifierOp::Percent(-250)]), 0);
+    }
+
+    #[test]
+    fn test_scaled_saturates() {
+        assert_eq!(ModifierOp::Add(3).scaled(4), ModifierOp::Add(12));
+        assert_eq!(
+            ModifierOp::Percent(-10).scaled(u32::MAX),
+            ModifierOp::Percent(i32::MIN)
+        );
+    }
+}
@@ -0,0 +1,241 @@
+//! Remembered items and map features.
+//!
+//! Once the player walks away from a staircase or a dropped sword, the map
+//! still needs to show it, and "travel to the down stairs" needs to know
+//! where it was. A [`PoiMemory`] keeps the [`PointOfInterest`]s the player
+//! has seen, as they were when last seen: pass it the tiles in view each
+//! turn and it records what is there and forgets what has gone. Its
+//! contents feed auto-travel goals ([`nearest`](PoiMemory::nearest)),
+//! minimap markers ([`markers`](PoiMemory::markers)) and, with the
+//! `serialization` feature, save files.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_core::prelude::*;
+//!
+//! #[derive(Debug, Clone, PartialEq)]
+//! enum Poi { Stairs, Item(&'static str) }
+//!
+//! let look = |p: IVec2| match (p.x, p.y) {
+//!     (5, 0) => Some((Poi::Stairs, '>')),
+//!     (2, 0) => Some((Poi::Item("sword"), '/')),
+//!     _ => None,
+//! };
+//!
+//! let mut memory = PoiMemory::new();
+//! let seen = memory.observe((0..8).map(|x| IVec2::new(x, 0)), look, 1);
+//! assert_eq!(seen, vec![IVec2::new(2, 0), IVec2::new(5, 0)]);
+//!
+//! // Where are the stairs again?
+//! let stairs = memory.nearest(IVec2::new(9, 9), |k| *k == Poi::Stairs).unwrap();
+//! assert_eq!(stairs.position, IVec2::new(5, 0));
+//!
+//! // A monster picked up the sword while the player watched
+//! memory.observe([IVec2::new(2, 0)], |_| None, 2);
+//! assert_eq!(memory.markers().count(), 1);
+//! ```
+
+use runeforge_geometry::prelude::IVec2;
+
+/// Something the player has seen at a position.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct PointOfInterest<K> {
+    /// What it is, e.g. an item, stairs or a shop
+    pub kind: K,
+    /// Where it was seen
+    pub position: IVec2,
+    /// Glyph to draw it with on maps
+    pub glyph: char,
+    /// Turn it was last seen
+    pub last_seen: u64,
+}
+
+/// The points of interest the player remembers, one per position.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct PoiMemory<K> {
+    /// Kept sorted by position, row by row, so lookups are binary searches
+    /// and saves are stable.
+    points: Vec<PointOfInterest<K>>,
+}
+
+impl<K> Default for PoiMemory<K> {
+    fn default() -> Self {
+        Self { points: Vec::new() }
+    }
+}
+
+impl<K: Clone + PartialEq> PoiMemory<K> {
+    /// Creates an empty memory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the memory from the tiles in view, such as an FOV result.
+    ///
+    /// `look` returns what is at a visible tile now, if anything of
+    /// interest. Visible tiles where it returns `None` are forgotten.
+    /// Returns the positions where something new was seen, in row order,
+    /// so auto-explore can stop and announce them.
+    pub fn observe(
+        &mut self,
+        visible: impl IntoIterator<Item = IVec2>,
+        mut look: impl FnMut(IVec2) -> Option<(K, char)>,
+        turn: u64,
+    ) -> Vec<IVec2> {
+        let mut discovered = Vec::new();
+        for pos in visible {
+            match look(pos) {
+                Some((kind, glyph)) => {
+                    let poi = PointOfInterest {
+                        kind,
+                        position: pos,
+                        glyph,
+                        last_seen: turn,
+                    };
+                    let known = self.get(pos).is_some_and(|old| old.kind == poi.kind);
+                    self.remember(poi);
+                    if !known {
+                        discovered.push(pos);
+                    }
+                }
+                None => {
+                    self.forget(pos);
+                }
+            }
+        }
+        discovered.sort_by_key(|p| key(*p));
+        discovered
+    }
+
+    /// Records a point of interest, replacing anything remembered at its
+    /// position.
+    pub fn remember(&mut self, poi: PointOfInterest<K>) {
+        match self.search(poi.position) {
+            Ok(idx) => self.points[idx] = poi,
+            Err(idx) => self.points.insert(idx, poi),
+        }
+    }
+
+    /// Forgets whatever was remembered at `pos`.
+    pub fn forget(&mut self, pos: IVec2) -> Option<PointOfInterest<K>> {
+        let idx = self.search(pos).ok()?;
+        Some(self.points.remove(idx))
+    }
+
+    /// Returns what is remembered at `pos`.
+    pub fn get(&self, pos: IVec2) -> Option<&PointOfInterest<K>> {
+        self.search(pos).ok().map(|idx| &self.points[idx])
+    }
+
+    /// Returns the remembered point nearest to `from` (Euclidean) whose kind
+    /// matches, e.g. as a goal for auto-travel. Ties go to the first in row
+    /// order.
+    pub fn nearest(
+        &self,
+        from: IVec2,
+        matches: impl Fn(&K) -> bool,
+    ) -> Option<&PointOfInterest<K>> {
+        self.points
+            .iter()
+            .filter(|p| matches(&p.kind))
+            .min_by_key(|p| (p.position - from).length_squared())
+    }
+
+    /// Returns every remembered point whose kind matches, nearest to `from`
+    /// first, for travel menus.
+    pub fn destinations(
+        &self,
+        from: IVec2,
+        matches: impl Fn(&K) -> bool,
+    ) -> Vec<&PointOfInterest<K>> {
+        let mut found: Vec<_> = self.points.iter().filter(|p| matches(&p.kind)).collect();
+        found.sort_by_key(|p| (p.position - from).length_squared());
+        found
+    }
+
+    /// Returns the position and glyph of every remembered point, for
+    /// drawing on a minimap or over remembered terrain.
+    pub fn markers(&self) -> impl Iterator<Item = (IVec2, char)> + '_ {
+        self.points.iter().map(|p| (p.position, p.glyph))
+    }
+
+    /// Returns every remembered point in row order.
+    pub fn iter(&self) -> impl Iterator<Item = &PointOfInterest<K>> {
+        self.points.iter()
+    }
+
+    /// Returns the number of remembered points.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Returns true if nothing is remembered.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Forgets everything, e.g. on a new level.
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+
+    fn search(&self, pos: IVec2) -> Result<usize, usize> {
+        self.points
+            .binary_search_by_key(&key(pos), |p| key(p.position))
+    }
+}
+
+fn key(pos: IVec2) -> (i32, i32) {
+    (pos.y, pos.x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_observe_updates_and_rediscovers() {
+        let mut memory = PoiMemory::new();
+        let a = IVec2::new(3, 1);
+        let b = IVec2::new(-1, 1);
+        assert_eq!(
+            memory.observe([a, b], |_| Some(("potion", '!')), 1),
+            vec![b, a]
+        );
+        assert_eq!(memory.observe([a], |_| Some(("potion", '!')), 4), vec![]);
+        assert_eq!(memory.get(a).unwrap().last_seen, 4);
+        assert_eq!(memory.get(b).unwrap().last_seen, 1);
+
+        // Something else lies there now
+        assert_eq!(memory.observe([a], |_| Some(("scroll", '?')), 5), vec![a]);
+        let keys: Vec<_> = memory.iter().map(|p| p.position).collect();
+        assert_eq!(keys, vec![b, a]);
+    }
+
+    #[test]
+    fn test_destinations_by_distance() {
+        let mut memory = PoiMemory::new();
+        for (x, kind) in [(0, "shop"), (10, "shop"), (4, "altar")] {
+            memory.remember(PointOfInterest {
+                kind,
+                position: IVec2::new(x, 0),
+                glyph: '_',
+                last_seen: 0,
+            });
+        }
+        let shops = memory.destinations(IVec2::new(8, 0), |k| *k == "shop");
+        assert_eq!(shops[0].position, IVec2::new(10, 0));
+        assert_eq!(shops.len(), 2);
+        assert!(memory.forget(IVec2::new(4, 0)).is_some());
+        assert!(memory.nearest(IVec2::ZERO, |k| *k == "altar").is_none());
+    }
+}
@@ -0,0 +1,559 @@
+//! Local high-score tables.
+//!
+//! A [`HighScoreTable`] keeps the best [`HighScore`]s ranked by score, with a
+//! separate board for each game seed so runs of the same daily or shared
+//! seed compete only with each other. Tables save to a small line-based text
+//! file with [`to_text`](HighScoreTable::to_text) and
+//! [`save`](HighScoreTable::save).
+//!
+//! A table given a key with [`with_key`](HighScoreTable::with_key) signs
+//! each entry with an HMAC-SHA256 over its score, seed, version and other
+//! fields, truncated to 64 bits, so editing a number in the file is noticed
+//! by [`verify`](HighScoreTable::verify). The key ships inside the game, so
+//! this only deters casual editing; it is not a security guarantee.
+//!
+//! With the `terminal` feature, [`draw`](HighScoreTable::draw) renders a
+//! board to any `runeforge-terminal` console.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_core::prelude::*;
+//!
+//! let mut scores = HighScoreTable::new(10).with_key("not-very-secret");
+//! scores.submit(HighScore::new("Ayla", 1200, 42).with_depth(7).with_cause("a lich"));
+//! scores.submit(HighScore::new("Bob", 300, 42));
+//! let rank = scores.submit(HighScore::new("Cy", 900, 7));
+//! assert_eq!(rank, Some(0)); // best on seed 7's board
+//!
+//! let names: Vec<_> = scores.board(42).map(|s| s.name.as_str()).collect();
+//! assert_eq!(names, ["Ayla", "Bob"]);
+//!
+//! // Round-trips through the save format with signatures intact
+//! let text = scores.to_text();
+//! let loaded = HighScoreTable::from_text(&text, 10).unwrap().with_key("not-very-secret");
+//! assert!(loaded.iter().all(|s| loaded.verify(s)));
+//!
+//! // An edited score no longer verifies
+//! let forged = HighScoreTable::from_text(&text.replace("1200", "9999"), 10)
+//!     .unwrap()
+//!     .with_key("not-very-secret");
+//! assert!(!forged.verify(forged.top().unwrap()));
+//! ```
+
+use std::{fs, io, path::Path};
+
+use thiserror::Error;
+
+use crate::sha256::hmac_sha256;
+
+const HEADER: &str = "runeforge-scores";
+const VERSION: u32 = 1;
+
+/// Errors produced while loading or saving high scores.
+#[derive(Error, Debug)]
+pub enum ScoreError {
+    /// A line of the score file could not be parsed.
+    #[error("Invalid score file at line {line}: {message}")]
+    Parse {
+        /// Line number, starting at 1
+        line: usize,
+        /// What was wrong with it
+        message: String,
+    },
+    /// The file was written by an unknown format version.
+    #[error("Unsupported score file version {0}")]
+    UnsupportedVersion(u32),
+    /// I/O error.
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+}
+
+impl ScoreError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ScoreError::Parse { .. } => "score.parse",
+            ScoreError::UnsupportedVersion(_) => "score.unsupported_version",
+            ScoreError::Io(_) => "score.io",
+        }
+    }
+}
+
+/// One finished run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighScore {
+    /// Character name
+    pub name: String,
+    /// Points scored
+    pub score: u64,
+    /// Seed the run was played on
+    pub seed: u64,
+    /// Deepest depth reached
+    pub depth: u32,
+    /// Turns played
+    pub turns: u64,
+    /// How the run ended, e.g. "killed by a lich"
+    pub cause: String,
+    /// Game version the run was played on
+    pub version: String,
+    /// Keyed checksum of the other fields, set when a signing table accepts
+    /// the entry
+    pub signature: Option<u64>,
+}
+
+impl HighScore {
+    /// Creates an unsigned entry.
+    pub fn new(name: impl Into<String>, score: u64, seed: u64) -> Self {
+        Self {
+            name: name.into(),
+            score,
+            seed,
+            depth: 0,
+            turns: 0,
+            cause: String::new(),
+            version: String::new(),
+            signature: None,
+        }
+    }
+
+    /// Sets the deepest depth reached.
+    pub fn with_depth(mut self, depth: u32) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Sets the number of turns played.
+    pub fn with_turns(mut self, turns: u64) -> Self {
+        self.turns = turns;
+        self
+    }
+
+    /// Sets how the run ended.
+    pub fn with_cause(mut self, cause: impl Into<String>) -> Self {
+        self.cause = cause.into();
+        self
+    }
+
+    /// Sets the game version.
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    /// Computes the signature of this entry under `key`: the first 8 bytes
+    /// of an HMAC-SHA256 over every other field.
+    pub fn sign(&self, key: &[u8]) -> u64 {
+        let mut message = Vec::new();
+        for text in [&self.name, &self.cause, &self.version] {
+            message.extend_from_slice(&(text.len() as u64).to_le_bytes());
+            message.extend_from_slice(text.as_bytes());
+        }
+        for number in [self.score, self.seed, self.depth as u64, self.turns] {
+            message.extend_from_slice(&number.to_le_bytes());
+        }
+        let mac = hmac_sha256(key, &message);
+        u64::from_be_bytes([
+            mac[0], mac[1], mac[2], mac[3], mac[4], mac[5], mac[6], mac[7],
+        ])
+    }
+}
+
+/// Ranked high scores, one board per seed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HighScoreTable {
+    /// Sorted by descending score; ties keep submission order.
+    entries: Vec<HighScore>,
+    capacity: usize,
+    key: Option<Vec<u8>>,
+}
+
+impl HighScoreTable {
+    /// Creates an empty table keeping the best `capacity` entries per seed.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            capacity,
+            key: None,
+        }
+    }
+
+    /// Signs submitted entries and enables [`verify`](Self::verify) with
+    /// `key`.
+    pub fn with_key(mut self, key: impl AsRef<[u8]>) -> Self {
+        self.key = Some(key.as_ref().to_vec());
+        self
+    }
+
+    /// Returns how many entries each seed's board keeps.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Adds a finished run, signing it if the table has a key. Returns its
+    /// rank on its seed's board, counting from 0, or `None` if it didn't
+    /// make the board.
+    pub fn submit(&mut self, mut entry: HighScore) -> Option<usize> {
+        entry.name = clean(&entry.name);
+        entry.cause = clean(&entry.cause);
+        entry.version = clean(&entry.version).replace(' ', "_");
+        let rank = self
+            .board(entry.seed)
+            .take_while(|e| e.score >= entry.score)
+            .count();
+        if rank >= self.capacity {
+            return None;
+        }
+        entry.signature = self.key.as_deref().map(|key| entry.sign(key));
+
+        let at = self.entries.partition_point(|e| e.score >= entry.score);
+        let seed = entry.seed;
+        self.entries.insert(at, entry);
+        if let Some(evicted) = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.seed == seed)
+            .nth(self.capacity)
+            .map(|(i, _)| i)
+        {
+            self.entries.remove(evicted);
+        }
+        Some(rank)
+    }
+
+    /// Returns the board for one seed, best first.
+    pub fn board(&self, seed: u64) -> impl Iterator<Item = &HighScore> {
+        self.entries.iter().filter(move |e| e.seed == seed)
+    }
+
+    /// Returns the seeds that have boards, in order of their best score.
+    pub fn seeds(&self) -> Vec<u64> {
+        let mut seeds = Vec::new();
+        for entry in &self.entries {
+            if !seeds.contains(&entry.seed) {
+                seeds.push(entry.seed);
+            }
+        }
+        seeds
+    }
+
+    /// Returns every entry across all seeds, best first.
+    pub fn iter(&self) -> impl Iterator<Item = &HighScore> {
+        self.entries.iter()
+    }
+
+    /// Returns the best entry on any seed.
+    pub fn top(&self) -> Option<&HighScore> {
+        self.entries.first()
+    }
+
+    /// Returns the number of entries across all seeds.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the table has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns true if the entry's signature matches the table's key.
+    /// Without a key every entry passes.
+    pub fn verify(&self, entry: &HighScore) -> bool {
+        match &self.key {
+            Some(key) => entry.signature == Some(entry.sign(key)),
+            None => true,
+        }
+    }
+
+    /// Removes entries that fail [`verify`](Self::verify), returning how
+    /// many were removed.
+    pub fn retain_verified(&mut self) -> usize {
+        let before = self.entries.len();
+        let key = self.key.clone();
+        self.entries.retain(|e| match &key {
+            Some(key) => e.signature == Some(e.sign(key)),
+            None => true,
+        });
+        before - self.entries.len()
+    }
+
+    /// Writes the table in a line-based text format, one tab-separated
+    /// entry per line.
+    pub fn to_text(&self) -> String {
+        let mut out = format!("{} {}\n", HEADER, VERSION);
+        for e in &self.entries {
+            let signature = e
+                .signature
+                .map_or_else(|| "-".to_string(), |s| format!("{s:016x}"));
+            let version = if e.version.is_empty() {
+                "-"
+            } else {
+                &e.version
+            };
+            out.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                e.score, e.seed, e.depth, e.turns, version, signature, e.name, e.cause
+            ));
+        }
+        out
+    }
+
+    /// Reads a table from its text format, keeping the best `capacity`
+    /// entries per seed. Blank lines and lines starting with `;` are
+    /// ignored. Signatures are kept as written; call
+    /// [`with_key`](Self::with_key) and [`verify`](Self::verify) to check
+    /// them.
+    pub fn from_text(text: &str, capacity: usize) -> Result<Self, ScoreError> {
+        let mut table = HighScoreTable::new(capacity);
+        let mut saw_header = false;
+
+        for (idx, line) in text.lines().enumerate() {
+            let line_no = idx + 1;
+            let err = |message: &str| ScoreError::Parse {
+                line: line_no,
+                message: message.to_string(),
+            };
+            if line.trim().is_empty() || line.starts_with(';') {
+                continue;
+            }
+
+            if !saw_header {
+                let mut words = line.split_whitespace();
+                if words.next() != Some(HEADER) {
+                    return Err(err("missing score file header"));
+                }
+                let version = words
+                    .next()
+                    .and_then(|v| v.parse().ok())
+                    .ok_or_else(|| err("missing version"))?;
+                if version != VERSION {
+                    return Err(ScoreError::UnsupportedVersion(version));
+                }
+                saw_header = true;
+                continue;
+            }
+
+            let fields: Vec<&str> = line.splitn(8, '\t').collect();
+            if fields.len() < 7 {
+                return Err(err("expected at least 7 tab-separated fields"));
+            }
+            let number = |i: usize, what: &str| {
+                fields[i]
+                    .parse::<u64>()
+                    .map_err(|_| err(&format!("invalid {what} '{}'", fields[i])))
+            };
+            let entry = HighScore {
+                score: number(0, "score")?,
+                seed: number(1, "seed")?,
+                depth: u32::try_from(number(2, "depth")?).map_err(|_| err("depth too large"))?,
+                turns: number(3, "turns")?,
+                version: if fields[4] == "-" {
+                    String::new()
+                } else {
+                    fields[4].to_string()
+                },
+                signature: match fields[5] {
+                    "-" => None,
+                    hex => Some(
+                        u64::from_str_radix(hex, 16)
+                            .map_err(|_| err(&format!("invalid signature '{hex}'")))?,
+                    ),
+                },
+                name: fields[6].to_string(),
+                cause: fields.get(7).copied().unwrap_or_default().to_string(),
+            };
+            table.insert_loaded(entry);
+        }
+
+        if !saw_header {
+            return Err(ScoreError::Parse {
+                line: 1,
+                message: "missing score file header".to_string(),
+            });
+        }
+        Ok(table)
+    }
+
+    /// Saves the table to a file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be written.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ScoreError> {
+        fs::write(path, self.to_text())?;
+        Ok(())
+    }
+
+    /// Loads a table from a file, keeping the best `capacity` entries per
+    /// seed. A missing file gives an empty table, as on first launch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but can't be read or parsed.
+    pub fn load<P: AsRef<Path>>(path: P, capacity: usize) -> Result<Self, ScoreError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::from_text(&text, capacity),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::new(capacity)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Inserts a loaded entry as-is, keeping its signature.
+    fn insert_loaded(&mut self, entry: HighScore) {
+        if self.board(entry.seed).count() >= self.capacity {
+            return;
+        }
+        let at = self.entries.partition_point(|e| e.score >= entry.score);
+        self.entries.insert(at, entry);
+    }
+}
+
+#[cfg(feature = "terminal")]
+impl HighScoreTable {
+    /// Draws the board for `seed` as a ranked list with a header row,
+    /// starting at `pos` and cut off at `width` columns. The entry at
+    /// `highlight`, such as the rank just returned by
+    /// [`submit`](Self::submit), is drawn in `accent`; entries that fail
+    /// [`verify`](Self::verify) are marked with `?`.
+    ///
+    /// Returns the number of rows drawn.
+    pub fn draw<C: runeforge_terminal::prelude::Console + ?Sized>(
+        &self,
+        console: &mut C,
+        pos: runeforge_geometry::prelude::IVec2,
+        width: u32,
+        seed: u64,
+        highlight: Option<usize>,
+        accent: runeforge_color::Color,
+    ) -> usize {
+        use runeforge_color::Color;
+        use runeforge_geometry::prelude::IVec2;
+
+        let width = width as usize;
+        let mut row = |y: i32, text: String, fg: Color| {
+            let text: String = text.chars().take(width).collect();
+            console.draw_string(IVec2::new(pos.x, pos.y + y), &text, fg, Color::BLACK);
+        };
+
+        row(
+            0,
+            format!(
+                "{:>3}  {:<16} {:>8} {:>5}  {}",
+                "#", "Name", "Score", "Depth", "Fate"
+            ),
+            Color::GRAY,
+        );
+        let mut drawn = 1;
+        for (rank, entry) in self.board(seed).enumerate() {
+            let mark = if self.verify(entry) { ' ' } else { '?' };
+            let name: String = entry.name.chars().take(16).collect();
+            let text = format!(
+                "{:>3}.{mark}{:<16} {:>8} {:>5}  {}",
+                rank + 1,
+                name,
+                entry.score,
+                entry.depth,
+                entry.cause
+            );
+            let fg = if Some(rank) == highlight {
+                accent
+            } else {
+                Color::WHITE
+            };
+            row(drawn as i32, text, fg);
+            drawn += 1;
+        }
+        drawn
+    }
+}
+
+/// Keeps a field on one line of the save file.
+fn clean(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ranking_and_capacity_per_seed() {
+        let mut table = HighScoreTable::new(2);
+        assert_eq!(table.submit(HighScore::new("a", 10, 1)), Some(0));
+        assert_eq!(table.submit(HighScore::new("b", 30, 1)), Some(0));
+        assert_eq!(table.submit(HighScore::new("c", 10, 1)), None);
+        assert_eq!(table.submit(HighScore::new("d", 20, 1)), Some(1));
+        assert_eq!(table.submit(HighScore::new("e", 1, 2)), Some(0));
+
+        let board: Vec<_> = table.board(1).map(|e| e.name.as_str()).collect();
+        assert_eq!(board, ["b", "d"]);
+        assert_eq!(table.seeds(), vec![1, 2]);
+        assert_eq!(table.len(), 3);
+    }
+
+    #[test]
+    fn test_signatures() {
+        let mut table = HighScoreTable::new(5).with_key("k");
+        table.submit(HighScore::new("Ayla", 100, 9).with_version("1.0"));
+        let entry = table.top().unwrap().clone();
+        assert!(table.verify(&entry));
+
+        let mut bumped = entry.clone();
+        bumped.score += 1;
+        assert!(!table.verify(&bumped));
+        let mut other_version = entry.clone();
+        other_version.version = "1.1".into();
+        assert!(!table.verify(&other_version));
+        assert_ne!(entry.sign(b"k"), entry.sign(b"other"));
+
+        // Unsigned tables accept anything
+        assert!(HighScoreTable::new(5).verify(&bumped));
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let mut table = HighScoreTable::new(5).with_key("k");
+        table.submit(
+            HighScore::new("Tab\tName", 50, 3)
+                .with_depth(4)
+                .with_turns(900)
+                .with_cause("killed by a goblin"),
+        );
+        table.submit(HighScore::new("Bob", 70, 3).with_version("0.2 beta"));
+
+        let loaded = HighScoreTable::from_text(&table.to_text(), 5)
+            .unwrap()
+            .with_key("k");
+        assert_eq!(loaded, table);
+        assert_eq!(loaded.top().unwrap().version, "0.2_beta");
+        assert_eq!(loaded.iter().nth(1).unwrap().name, "Tab Name");
+
+        let forged_text = table.to_text().replace("70\t3", "99\t3");
+        let mut forged = HighScoreTable::from_text(&forged_text, 5)
+            .unwrap()
+            .with_key("k");
+        assert_eq!(forged.retain_verified(), 1);
+        assert_eq!(forged.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            HighScoreTable::from_text("", 5).unwrap_err().code(),
+            "score.parse"
+        );
+        assert_eq!(
+            HighScoreTable::from_text("runeforge-scores 9\n", 5)
+                .unwrap_err()
+                .code(),
+            "score.unsupported_version"
+        );
+        let err = HighScoreTable::from_text("runeforge-scores 1\nx\t1\t1\t1\t-\t-\tBob\n", 5)
+            .unwrap_err();
+        assert!(matches!(err, ScoreError::Parse { line: 2, .. }));
+    }
+}
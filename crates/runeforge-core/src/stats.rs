@@ -0,0 +1,296 @@
+//! Character stats with modifiers and derived values.
+//!
+//! A [`Stats`] block holds an entity's base attributes (strength, agility,
+//! ...), the [`Modifier`]s currently acting on them grouped by source
+//! (equipment slots, status effects, auras), and formulas for derived values
+//! such as max HP, speed or FOV radius. Final values are recalculated and
+//! cached whenever something changes, so reading a stat is a lookup, and
+//! [`on_change`](Stats::on_change) hooks hear about every value that moved,
+//! e.g. to clamp current HP to a lowered max HP or reschedule a slowed
+//! actor.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_core::prelude::*;
+//!
+//! #[derive(Debug, Clone, PartialEq)]
+//! enum Stat { Con, Dex, MaxHp, Speed }
+//!
+//! let mut stats = Stats::new();
+//! stats.set_base(Stat::Con, 12);
+//! stats.set_base(Stat::Dex, 10);
+//! stats.derive(Stat::MaxHp, |get| 10 + get(&Stat::Con) * 2);
+//! stats.derive(Stat::Speed, |get| 90 + get(&Stat::Dex));
+//! assert_eq!(stats.get(&Stat::MaxHp), 34);
+//!
+//! // A ring of health, then a haste spell
+//! stats.set_source("ring", [Modifier::add(Stat::Con, 2)]);
+//! let mut effects = StatusEffects::new();
+//! effects.apply(
+//!     StatusEffect::new("haste", Some(5)).with_modifier(Modifier::percent(Stat::Speed, 50)),
+//!     StackingPolicy::Refresh,
+//! );
+//! stats.sync_status(&effects);
+//!
+//! assert_eq!(stats.get(&Stat::MaxHp), 38);
+//! assert_eq!(stats.get(&Stat::Speed), 150);
+//! ```
+
+use std::fmt;
+
+use crate::modifier::{apply_modifiers, Modifier};
+use crate::status::StatusEffects;
+
+/// The modifier source used by [`Stats::sync_status`].
+pub const STATUS_SOURCE: &str = "status";
+
+type Formula<S> = Box<dyn Fn(&dyn Fn(&S) -> i32) -> i32>;
+type Hook<S> = Box<dyn FnMut(&S, i32, i32)>;
+
+/// Base attributes, modifiers and cached final values for one entity.
+///
+/// Stats are keyed by `S`, usually a game-defined enum. There are rarely more
+/// than a few dozen, so they are kept in insertion order rather than hashed.
+pub struct Stats<S> {
+    base: Vec<(S, i32)>,
+    sources: Vec<(String, Vec<Modifier<S>>)>,
+    derived: Vec<(S, Formula<S>)>,
+    values: Vec<(S, i32)>,
+    hooks: Vec<Hook<S>>,
+}
+
+impl<S> Default for Stats<S> {
+    fn default() -> Self {
+        Self {
+            base: Vec::new(),
+            sources: Vec::new(),
+            derived: Vec::new(),
+            values: Vec::new(),
+            hooks: Vec::new(),
+        }
+    }
+}
+
+impl<S: fmt::Debug> fmt::Debug for Stats<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stats")
+            .field("base", &self.base)
+            .field("sources", &self.sources)
+            .field("values", &self.values)
+            .field("derived", &self.derived.len())
+            .field("hooks", &self.hooks.len())
+            .finish()
+    }
+}
+
+impl<S: Clone + PartialEq> Stats<S> {
+    /// Creates an empty stat block.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a base attribute.
+    pub fn set_base(&mut self, stat: S, value: i32) {
+        match self.base.iter_mut().find(|(s, _)| *s == stat) {
+            Some(entry) => entry.1 = value,
+            None => self.base.push((stat, value)),
+        }
+        self.recalculate();
+    }
+
+    /// Returns a base attribute, if set.
+    pub fn base(&self, stat: &S) -> Option<i32> {
+        lookup(&self.base, stat)
+    }
+
+    /// Defines `stat` as derived from other stats.
+    ///
+    /// `formula` receives a lookup for final values: base attributes with
+    /// their modifiers, and derived stats defined before this one. Modifiers
+    /// that target `stat` itself apply on top of the formula.
+    pub fn derive(&mut self, stat: S, formula: impl Fn(&dyn Fn(&S) -> i32) -> i32 + 'static) {
+        let formula: Formula<S> = Box::new(formula);
+        match self.derived.iter_mut().find(|(s, _)| *s == stat) {
+            Some(entry) => entry.1 = formula,
+            None => self.derived.push((stat, formula)),
+        }
+        self.recalculate();
+    }
+
+    /// Replaces the modifiers coming from `source`, such as an equipment
+    /// slot.
+    pub fn set_source(
+        &mut self,
+        source: impl Into<String>,
+        modifiers: impl IntoIterator<Item = Modifier<S>>,
+    ) {
+        let source = source.into();
+        let modifiers: Vec<_> = modifiers.into_iter().collect();
+        match self.sources.iter_mut().find(|(s, _)| *s == source) {
+            Some(entry) => entry.1 = modifiers,
+            None => self.sources.push((source, modifiers)),
+        }
+        self.recalculate();
+    }
+
+    /// Removes every modifier from `source`. Returns false if there were
+    /// none.
+    pub fn remove_source(&mut self, source: &str) -> bool {
+        let before = self.sources.len();
+        self.sources.retain(|(s, _)| s != source);
+        let removed = self.sources.len() != before;
+        if removed {
+            self.recalculate();
+        }
+        removed
+    }
+
+    /// Takes the modifiers of every active status effect, scaled by its
+    /// stack count, as the [`STATUS_SOURCE`] source. Call after applying, removing or
+    /// ticking effects.
+    pub fn sync_status<K: PartialEq>(&mut self, effects: &StatusEffects<K, S>) {
+        let modifiers: Vec<Modifier<S>> = effects
+            .iter()
+            .flat_map(|e| {
+                e.modifiers.iter().map(move |m| Modifier {
+                    stat: m.stat.clone(),
+                    op: m.op.scaled(e.stacks),
+                })
+            })
+            .collect();
+        self.set_source(STATUS_SOURCE, modifiers);
+    }
+
+    /// Returns the final value of a stat, or 0 if it is unknown.
+    pub fn get(&self, stat: &S) -> i32 {
+        lookup(&self.values, stat).unwrap_or(0)
+    }
+
+    /// Returns every stat with its final value.
+    pub fn iter(&self) -> impl Iterator<Item = (&S, i32)> {
+        self.values.iter().map(|(s, v)| (s, *v))
+    }
+
+    /// Registers a hook called with the stat, old value and new value
+    /// whenever a final value changes.
+    pub fn on_change(&mut self, hook: impl FnMut(&S, i32, i32) + 'static) {
+        self.hooks.push(Box::new(hook));
+    }
+
+    /// Recalculates every final value and runs the change hooks.
+    ///
+    /// Changes made through `Stats` recalculate on their own; call this when
+    /// a formula reads state from outside.
+    pub fn recalculate(&mut self) {
+        let mut values: Vec<(S, i32)> = Vec::with_capacity(self.values.len());
+        let modified = |stat: &S, base: i32| {
+            let ops = self
+                .sources
+                .iter()
+                .flat_map(|(_, mods)| mods)
+                .filter(|m| m.stat == *stat)
+                .map(|m| &m.op);
+            apply_modifiers(base, ops)
+        };
+
+        for (stat, base) in &self.base {
+            if !self.derived.iter().any(|(s, _)| s == stat) {
+                values.push((stat.clone(), modified(stat, *base)));
+            }
+        }
+        // Stats that only modifiers mention start from 0.
+        for modifier in self.sources.iter().flat_map(|(_, mods)| mods) {
+            let known = values.iter().any(|(s, _)| *s == modifier.stat)
+                || self.derived.iter().any(|(s, _)| *s == modifier.stat);
+            if !known {
+                values.push((modifier.stat.clone(), modified(&modifier.stat, 0)));
+            }
+        }
+        for (stat, formula) in &self.derived {
+            let base = formula(&|s: &S| lookup(&values, s).unwrap_or(0));
+            values.push((stat.clone(), modified(stat, base)));
+        }
+
+        let old = std::mem::replace(&mut self.values, values);
+        for (stat, new) in &self.values {
+            let before = lookup(&old, stat).unwrap_or(0);
+            if before != *new {
+                for hook in &mut self.hooks {
+                    hook(stat, before, *new);
+                }
+            }
+        }
+        for (stat, before) in &old {
+            if before != &0 && lookup(&self.values, stat).is_none() {
+                for hook in &mut self.hooks {
+                    hook(stat, *before, 0);
+                }
+            }
+        }
+    }
+}
+
+fn lookup<S: PartialEq>(entries: &[(S, i32)], stat: &S) -> Option<i32> {
+    entries.iter().find(|(s, _)| s == stat).map(|&(_, v)| v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::status::{StackingPolicy, StatusEffect};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_sources_replace_and_remove() {
+        let mut stats = Stats::new();
+        stats.set_base("str", 10);
+        stats.set_source("weapon", [Modifier::add("str", 3)]);
+        stats.set_source(
+            "weapon",
+            [Modifier::add("str", 1), Modifier::add("luck", 2)],
+        );
+        assert_eq!(stats.get(&"str"), 11);
+        assert_eq!(stats.get(&"luck"), 2);
+
+        assert!(stats.remove_source("weapon"));
+        assert!(!stats.remove_source("weapon"));
+        assert_eq!(stats.get(&"str"), 10);
+        assert_eq!(stats.get(&"luck"), 0);
+        assert_eq!(stats.base(&"str"), Some(10));
+    }
+
+    #[test]
+    fn test_derived_values_and_hooks() {
+        let mut stats = Stats::new();
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let log = changes.clone();
+        stats.on_change(move |stat, old, new| log.borrow_mut().push((*stat, old, new)));
+
+        stats.set_base("per", 8);
+        stats.derive("fov", |get| get(&"per") / 2);
+        stats.derive("fov_bonus", |get| get(&"fov") + 1);
+        stats.set_source("torch", [Modifier::add("fov", 2)]);
+        assert_eq!(stats.get(&"fov"), 6);
+        assert_eq!(stats.get(&"fov_bonus"), 7);
+
+        let changes = changes.borrow();
+        assert_eq!(changes[0], ("per", 0, 8));
+        assert!(changes.contains(&("fov", 4, 6)));
+        assert_eq!(changes.last(), Some(&("fov_bonus", 5, 7)));
+    }
+
+    #[test]
+    fn test_sync_status_scales_stacks() {
+        let mut effects = StatusEffects::new();
+        let mut curse = StatusEffect::new("curse", None).with_modifier(Modifier::add("str", -1));
+        curse.stacks = u32::MAX;
+        effects.apply(curse, StackingPolicy::Replace);
+
+        let mut stats = Stats::new();
+        stats.set_base("str", 10);
+        stats.sync_status(&effects);
+        assert_eq!(stats.get(&"str"), 10 - i32::MAX);
+    }
+}
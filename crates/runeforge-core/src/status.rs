@@ -0,0 +1,358 @@
+//! Timed status effects with stacking policies.
+//!
+//! A [`StatusEffects`] container holds the effects currently applied to one
+//! entity. Each effect has a kind (usually a game-defined enum), an optional
+//! duration in turns, a stack count and a list of [`Modifier`]s. Calling
+//! [`tick`](StatusEffects::tick) once per turn runs a per-effect hook (poison
+//! damage, regeneration, ...) and removes expired effects.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_core::modifier::Modifier;
+//! use runeforge_core::status::{StackingPolicy, StatusEffect, StatusEffects};
+//!
+//! #[derive(Debug, Clone, PartialEq, Eq)]
+//! enum Effect { Poison, Haste }
+//!
+//! let mut effects: StatusEffects<Effect, &str> = StatusEffects::new();
+//! effects.apply(
+//!     StatusEffect::new(Effect::Haste, Some(3)).with_modifier(Modifier::percent("speed", 50)),
+//!     StackingPolicy::Refresh,
+//! );
+//! effects.apply(StatusEffect::new(Effect::Poison, Some(2)), StackingPolicy::Stack { max: 3 });
+//! effects.apply(StatusEffect::new(Effect::Poison, Some(2)), StackingPolicy::Stack { max: 3 });
+//!
+//! let mut damage = 0;
+//! effects.tick(|effect| {
+//!     if effect.kind == Effect::Poison {
+//!         damage += effect.stacks;
+//!     }
+//! });
+//! assert_eq!(damage, 2);
+//! assert_eq!(effects.modifiers_for(&"speed").count(), 1);
+//! ```
+
+use crate::modifier::{apply_modifiers, Modifier, ModifierOp};
+
+/// What happens when an effect is applied while one of the same kind is active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackingPolicy {
+    /// Keep the existing effect and discard the new one.
+    Ignore,
+    /// Replace the existing effect entirely.
+    Replace,
+    /// Keep the existing effect but take the longer of the two durations.
+    Refresh,
+    /// Add the new duration to the remaining duration.
+    Extend,
+    /// Add a stack (up to `max`) and refresh the duration.
+    Stack {
+        /// Maximum number of stacks
+        max: u32,
+    },
+}
+
+/// A single status effect.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusEffect<K, S> {
+    /// The kind of effect
+    pub kind: K,
+    /// Remaining duration in turns, or `None` for a permanent effect
+    pub duration: Option<u32>,
+    /// Number of stacks (at least 1)
+    pub stacks: u32,
+    /// Modifiers applied per stack
+    pub modifiers: Vec<Modifier<S>>,
+}
+
+impl<K, S> StatusEffect<K, S> {
+    /// Creates an effect with one stack and no modifiers.
+    pub fn new(kind: K, duration: Option<u32>) -> Self {
+        Self {
+            kind,
+            duration,
+            stacks: 1,
+            modifiers: Vec::new(),
+        }
+    }
+
+    /// Adds a modifier to the effect.
+    pub fn with_modifier(mut self, modifier: Modifier<S>) -> Self {
+        self.modifiers.push(modifier);
+        self
+    }
+
+    /// Returns true if the effect has run out.
+    pub fn is_expired(&self) -> bool {
+        self.duration == Some(0)
+    }
+}
+
+/// The set of status effects active on one entity.
+#[derive(Debug, Clone)]
+pub struct StatusEffects<K, S> {
+    effects: Vec<StatusEffect<K, S>>,
+}
+
+impl<K, S> Default for StatusEffects<K, S> {
+    fn default() -> Self {
+        Self {
+            effects: Vec::new(),
+        }
+    }
+}
+
+impl<K: PartialEq, S: PartialEq> StatusEffects<K, S> {
+    /// Creates an empty container.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies an effect using the given stacking policy.
+    pub fn apply(&mut self, mut effect: StatusEffect<K, S>, policy: StackingPolicy) {
+        let Some(existing) = self.effects.iter_mut().find(|e| e.kind == effect.kind) else {
+            if let StackingPolicy::Stack { max } = policy {
+                effect.stacks = effect.stacks.min(max.max(1));
+            }
+            self.effects.push(effect);
+            return;
+        };
+
+        match policy {
+            StackingPolicy::Ignore => {}
+            StackingPolicy::Replace => *existing = effect,
+            StackingPolicy::Refresh => {
+                existing.duration = longer(existing.duration, effect.duration);
+            }
+            StackingPolicy::Extend => {
+                existing.duration = match (existing.duration, effect.duration) {
+                    (Some(a), Some(b)) => Some(a.saturating_add(b)),
+                    _ => None,
+                };
+            }
+            StackingPolicy::Stack { max } => {
+                existing.stacks = existing
+                    .stacks
+                    .saturating_add(effect.stacks)
+                    .min(max.max(1));
+                existing.duration = longer(existing.duration, effect.duration);
+            }
+        }
+    }
+
+    /// Removes the effect of the given kind, returning it if present.
+    pub fn remove(&mut self, kind: &K) -> Option<StatusEffect<K, S>> {
+        let idx = self.effects.iter().position(|e| e.kind == *kind)?;
+        Some(self.effects.remove(idx))
+    }
+
+    /// Returns true if an effect of the given kind is active.
+    pub fn has(&self, kind: &K) -> bool {
+        self.effects.iter().any(|e| e.kind == *kind)
+    }
+
+    /// Returns the active effect of the given kind.
+    pub fn get(&self, kind: &K) -> Option<&StatusEffect<K, S>> {
+        self.effects.iter().find(|e| e.kind == *kind)
+    }
+
+    /// Advances all effects by one turn.
+    ///
+    /// `on_tick` is called for every active effect before its duration is
+    /// decremented. Effects that expire are removed and returned.
+    pub fn tick(
+        &mut self,
+        mut on_tick: impl FnMut(&StatusEffect<K, S>),
+    ) -> Vec<StatusEffect<K, S>> {
+        for effect in &mut self.effects {
+            on_tick(effect);
+            if let Some(d) = effect.duration.as_mut() {
+                *d = d.saturating_sub(1);
+            }
+        }
+
+        let mut expired = Vec::new();
+        let mut i = 0;
+        while i < self.effects.len() {
+            if self.effects[i].is_expired() {
+                expired.push(self.effects.remove(i));
+            } else {
+                i += 1;
+            }
+        }
+        expired
+    }
+
+    /// Returns every active modifier for a stat, scaled by its effect's
+    /// stack count.
+    pub fn modifiers_for<'a>(&'a self, stat: &'a S) -> impl Iterator<Item = ModifierOp> + 'a {
+        self.effects.iter().flat_map(move |e| {
+            e.modifiers
+                .iter()
+                .filter(move |m| m.stat == *stat)
+                .map(move |m| m.op.scaled(e.stacks))
+        })
+    }
+
+    /// Applies every active modifier for a stat to a base value.
+    pub fn modified(&self, stat: &S, base: i32) -> i32 {
+        apply_modifiers(base, self.modifiers_for(stat))
+    }
+
+    /// Returns an iterator over the active effects.
+    pub fn iter(&self) -> impl Iterator<Item = &StatusEffect<K, S>> {
+        self.effects.iter()
+    }
+
+    /// Returns the number of active effects.
+    pub fn len(&self) -> usize {
+        self.effects.len()
+    }
+
+    /// Returns true if no effects are active.
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    /// Removes all effects.
+    pub fn clear(&mut self) {
+        self.effects.clear();
+    }
+}
+
+/// Returns the longer of two durations, where `None` (permanent) always wins.
+fn longer(a: Option<u32>, b: Option<u32>) -> Option<u32> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Kind {
+        Poison,
+        Blessed,
+    }
+
+    type Effects = StatusEffects<Kind, &'static str>;
+
+    #[test]
+    fn test_apply_and_expire() {
+        let mut effects = Effects::new();
+        effects.apply(
+            StatusEffect::new(Kind::Poison, Some(2)),
+            StackingPolicy::Replace,
+        );
+        assert!(effects.has(&Kind::Poison));
+
+        assert!(effects.tick(|_| {}).is_empty());
+        let expired = effects.tick(|_| {});
+        assert_eq!(expired.len(), 1);
+        assert!(effects.is_empty());
+    }
+
+    #[test]
+    fn test_permanent_effect_never_expires() {
+        let mut effects = Effects::new();
+        effects.apply(
+            StatusEffect::new(Kind::Blessed, None),
+            StackingPolicy::Replace,
+        );
+        for _ in 0..100 {
+            effects.tick(|_| {});
+        }
+        assert!(effects.has(&Kind::Blessed));
+    }
+
+    #[test]
+    fn test_stacking_policies() {
+        let mut effects = Effects::new();
+        effects.apply(
+            StatusEffect::new(Kind::Poison, Some(5)),
+            StackingPolicy::Ignore,
+        );
+        effects.apply(
+            StatusEffect::new(Kind::Poison, Some(9)),
+            StackingPolicy::Ignore,
+        );
+        assert_eq!(effects.get(&Kind::Poison).unwrap().duration, Some(5));
+
+        effects.apply(
+            StatusEffect::new(Kind::Poison, Some(3)),
+            StackingPolicy::Refresh,
+        );
+        assert_eq!(effects.get(&Kind::Poison).unwrap().duration, Some(5));
+
+        effects.apply(
+            StatusEffect::new(Kind::Poison, Some(3)),
+            StackingPolicy::Extend,
+        );
+        assert_eq!(effects.get(&Kind::Poison).unwrap().duration, Some(8));
+
+        effects.apply(
+            StatusEffect::new(Kind::Poison, Some(1)),
+            StackingPolicy::Replace,
+        );
+        assert_eq!(effects.get(&Kind::Poison).unwrap().duration, Some(1));
+
+        for _ in 0..5 {
+            effects.apply(
+                StatusEffect::new(Kind::Poison, Some(1)),
+                StackingPolicy::Stack { max: 3 },
+            );
+        }
+        assert_eq!(effects.get(&Kind::Poison).unwrap().stacks, 3);
+        assert_eq!(effects.len(), 1);
+    }
+
+    #[test]
+    fn test_stacks_capped_without_overflow() {
+        let mut effects = Effects::new();
+        let mut huge = StatusEffect::new(Kind::Poison, Some(1));
+        huge.stacks = u32::MAX;
+
+        effects.apply(huge.clone(), StackingPolicy::Stack { max: 4 });
+        assert_eq!(effects.get(&Kind::Poison).unwrap().stacks, 4);
+
+        effects.apply(huge.clone(), StackingPolicy::Stack { max: u32::MAX });
+        effects.apply(huge, StackingPolicy::Stack { max: u32::MAX });
+        assert_eq!(effects.get(&Kind::Poison).unwrap().stacks, u32::MAX);
+    }
+
+    #[test]
+    fn test_modified_with_max_stacks() {
+        let mut effects = Effects::new();
+        let mut huge = StatusEffect::new(Kind::Blessed, None)
+            .with_modifier(Modifier::add("str", 1))
+            .with_modifier(Modifier::percent("dex", -100));
+        huge.stacks = u32::MAX;
+        effects.apply(huge, StackingPolicy::Replace);
+
+        assert_eq!(effects.modified(&"str", 10), i32::MAX);
+        assert_eq!(effects.modified(&"dex", 10), 0);
+    }
+
+    #[test]
+    fn test_modifiers_scale_with_stacks() {
+        let mut effects = Effects::new();
+        let effect = StatusEffect::new(Kind::Blessed, None).with_modifier(Modifier::add("str", 2));
+        effects.apply(effect.clone(), StackingPolicy::Stack { max: 5 });
+        effects.apply(effect, StackingPolicy::Stack { max: 5 });
+
+        assert_eq!(effects.modified(&"str", 10), 14);
+        assert_eq!(effects.modified(&"dex", 10), 10);
+    }
+
+    #[test]
+    fn test_apply_modifiers_order_independent() {
+        let a = [ModifierOp::Percent(100), ModifierOp::Add(5)];
+        let b = [ModifierOp::Add(5), ModifierOp::Percent(100)];
+        assert_eq!(apply_modifiers(5, a.iter()), 20);
+        assert_eq!(apply_modifiers(5, b.iter()), 20);
+    }
+}
@@ -0,0 +1,342 @@
+//! Resources that run down over time: hunger, torch fuel, oxygen.
+//!
+//! A [`Resource`] loses a configured amount every few turns and reports a
+//! level, such as "hungry" or "starving", based on thresholds. A
+//! [`SurvivalClock`] keeps every resource of one actor, and
+//! [`tick`](SurvivalClock::tick) once per scheduler turn returns a
+//! [`ResourceEvent`] whenever a resource crosses into another level, in
+//! either direction. Each resource has its own [`DecayConfig`], so a torch can
+//! burn down every turn while hunger only drops every ten.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_core::prelude::*;
+//!
+//! #[derive(Debug, Clone, PartialEq)]
+//! enum Need { Food, Torch }
+//! #[derive(Debug, Clone, PartialEq)]
+//! enum Level { Hungry, Starving, Dim, Out }
+//!
+//! let mut clock = SurvivalClock::new();
+//! clock.add(
+//!     Need::Food,
+//!     DecayConfig::new(100)
+//!         .with_decay(5, 1)
+//!         .with_threshold(20, Level::Hungry)
+//!         .with_threshold(0, Level::Starving),
+//! );
+//! clock.add(
+//!     Need::Torch,
+//!     DecayConfig::new(3).with_threshold(1, Level::Dim).with_threshold(0, Level::Out),
+//! );
+//!
+//! clock.tick();
+//! let events = clock.tick();
+//! assert_eq!(events, vec![ResourceEvent { resource: Need::Torch, from: None, to: Some(Level::Dim) }]);
+//!
+//! for _ in 0..14 {
+//!     clock.tick();
+//! }
+//! assert_eq!(clock.level(&Need::Food), Some(&Level::Hungry));
+//!
+//! // Eating lifts the level again
+//! let event = clock.restore(&Need::Food, 50).unwrap();
+//! assert_eq!(event.to, None);
+//! ```
+
+use crate::event::EventBus;
+
+/// How a resource decays and which levels it reports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecayConfig<L> {
+    /// The full value.
+    pub max: i32,
+    /// How much is lost each time the resource decays.
+    pub amount: i32,
+    /// Turns between decays.
+    pub every: u32,
+    /// Levels and the value at or below which each applies, lowest value
+    /// first.
+    pub thresholds: Vec<(i32, L)>,
+}
+
+impl<L> DecayConfig<L> {
+    /// Creates a resource that starts full at `max` and loses 1 every turn.
+    pub fn new(max: i32) -> Self {
+        Self {
+            max,
+            amount: 1,
+            every: 1,
+            thresholds: Vec::new(),
+        }
+    }
+
+    /// Loses `amount` every `every` turns. A zero `every` is treated as 1.
+    pub fn with_decay(mut self, amount: i32, every: u32) -> Self {
+        self.amount = amount;
+        self.every = every.max(1);
+        self
+    }
+
+    /// Reports `level` while the value is at or below `at`, unless a lower
+    /// threshold also applies.
+    pub fn with_threshold(mut self, at: i32, level: L) -> Self {
+        let idx = self.thresholds.partition_point(|(t, _)| *t <= at);
+        self.thresholds.insert(idx, (at, level));
+        self
+    }
+
+    /// Returns the level for `value`.
+    pub fn level_at(&self, value: i32) -> Option<&L> {
+        self.thresholds
+            .iter()
+            .find(|(at, _)| value <= *at)
+            .map(|(_, level)| level)
+    }
+}
+
+/// A resource crossed into another level.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceEvent<K, L> {
+    /// The resource that changed.
+    pub resource: K,
+    /// The previous level, `None` above every threshold.
+    pub from: Option<L>,
+    /// The new level, `None` above every threshold.
+    pub to: Option<L>,
+}
+
+/// One decaying resource.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resource<L> {
+    config: DecayConfig<L>,
+    value: i32,
+    /// Turns since the last decay.
+    elapsed: u32,
+}
+
+impl<L: Clone + PartialEq> Resource<L> {
+    /// Creates a full resource.
+    pub fn new(config: DecayConfig<L>) -> Self {
+        Self {
+            value: config.max,
+            config,
+            elapsed: 0,
+        }
+    }
+
+    /// Returns the current value.
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    /// Returns the current level.
+    pub fn level(&self) -> Option<&L> {
+        self.config.level_at(self.value)
+    }
+
+    /// Returns the value as a fraction of the maximum, for gauges.
+    pub fn fraction(&self) -> f32 {
+        if self.config.max <= 0 {
+            return 0.0;
+        }
+        self.value as f32 / self.config.max as f32
+    }
+
+    /// Returns the configuration.
+    pub fn config(&self) -> &DecayConfig<L> {
+        &self.config
+    }
+
+    /// Returns the configuration for changes, e.g. a slow-digestion ring.
+    pub fn config_mut(&mut self) -> &mut DecayConfig<L> {
+        &mut self.config
+    }
+
+    /// Sets the value, clamped to `0..=max`. Returns the old and new level
+    /// if they differ.
+    pub fn set(&mut self, value: i32) -> Option<(Option<L>, Option<L>)> {
+        let before = self.level().cloned();
+        self.value = value.clamp(0, self.config.max);
+        let after = self.level().cloned();
+        (before != after).then_some((before, after))
+    }
+
+    /// Adds `amount`, which may be negative. Returns the old and new level
+    /// if they differ.
+    pub fn restore(&mut self, amount: i32) -> Option<(Option<L>, Option<L>)> {
+        self.set(self.value.saturating_add(amount))
+    }
+
+    /// Advances one turn, decaying if due. Returns the old and new level if
+    /// they differ.
+    pub fn tick(&mut self) -> Option<(Option<L>, Option<L>)> {
+        self.elapsed += 1;
+        if self.elapsed < self.config.every {
+            return None;
+        }
+        self.elapsed = 0;
+        self.restore(-self.config.amount)
+    }
+}
+
+/// The decaying resources of one actor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SurvivalClock<K, L> {
+    resources: Vec<(K, Resource<L>)>,
+}
+
+impl<K, L> Default for SurvivalClock<K, L> {
+    fn default() -> Self {
+        Self {
+            resources: Vec::new(),
+        }
+    }
+}
+
+impl<K: Clone + PartialEq, L: Clone + PartialEq> SurvivalClock<K, L> {
+    /// Creates a clock without resources.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a full resource, replacing any with the same key.
+    pub fn add(&mut self, key: K, config: DecayConfig<L>) {
+        let resource = Resource::new(config);
+        match self.resources.iter_mut().find(|(k, _)| *k == key) {
+            Some(entry) => entry.1 = resource,
+            None => self.resources.push((key, resource)),
+        }
+    }
+
+    /// Removes a resource.
+    pub fn remove(&mut self, key: &K) -> Option<Resource<L>> {
+        let idx = self.resources.iter().position(|(k, _)| k == key)?;
+        Some(self.resources.remove(idx).1)
+    }
+
+    /// Returns a resource.
+    pub fn get(&self, key: &K) -> Option<&Resource<L>> {
+        self.resources
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, r)| r)
+    }
+
+    /// Returns a resource for changes.
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut Resource<L>> {
+        self.resources
+            .iter_mut()
+            .find(|(k, _)| k == key)
+            .map(|(_, r)| r)
+    }
+
+    /// Returns the current level of a resource.
+    pub fn level(&self, key: &K) -> Option<&L> {
+        self.get(key)?.level()
+    }
+
+    /// Adds `amount` to a resource, e.g. when eating or refuelling. Returns
+    /// an event if its level changed.
+    pub fn restore(&mut self, key: &K, amount: i32) -> Option<ResourceEvent<K, L>> {
+        let (from, to) = self.get_mut(key)?.restore(amount)?;
+        Some(ResourceEvent {
+            resource: key.clone(),
+            from,
+            to,
+        })
+    }
+
+    /// Returns every resource in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &Resource<L>)> {
+        self.resources.iter().map(|(k, r)| (k, r))
+    }
+
+    /// Returns the number of resources.
+    pub fn len(&self) -> usize {
+        self.resources.len()
+    }
+
+    /// Returns true if there are no resources.
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+    }
+
+    /// Advances every resource one turn, returning the level changes.
+    pub fn tick(&mut self) -> Vec<ResourceEvent<K, L>> {
+        self.resources
+            .iter_mut()
+            .filter_map(|(key, resource)| {
+                let (from, to) = resource.tick()?;
+                Some(ResourceEvent {
+                    resource: key.clone(),
+                    from,
+                    to,
+                })
+            })
+            .collect()
+    }
+
+    /// Advances one turn and publishes the level changes on `bus`, returning
+    /// how many were published.
+    pub fn tick_into(&mut self, bus: &mut EventBus<ResourceEvent<K, L>>) -> usize {
+        let events = self.tick();
+        let count = events.len();
+        for event in events {
+            bus.publish(event);
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decay_interval_and_thresholds() {
+        let config = DecayConfig::new(10)
+            .with_decay(2, 3)
+            .with_threshold(0, "empty")
+            .with_threshold(6, "low");
+        assert_eq!(config.thresholds[0].0, 0);
+        let mut resource = Resource::new(config);
+
+        assert_eq!(resource.tick(), None);
+        assert_eq!(resource.tick(), None);
+        assert_eq!(resource.tick(), None);
+        assert_eq!(resource.value(), 8);
+        for _ in 0..3 {
+            resource.tick();
+        }
+        assert_eq!(resource.level(), Some(&"low"));
+        assert_eq!(resource.restore(-100), Some((Some("low"), Some("empty"))));
+        assert_eq!(resource.value(), 0);
+        assert_eq!(resource.restore(100), Some((Some("empty"), None)));
+        assert_eq!(resource.fraction(), 1.0);
+    }
+
+    #[test]
+    fn test_clock_publishes_changes() {
+        let mut clock = SurvivalClock::new();
+        clock.add("oxygen", DecayConfig::new(2).with_threshold(1, "gasping"));
+        clock.add("fuel", DecayConfig::new(5).with_decay(0, 1));
+
+        let mut bus = EventBus::new();
+        assert_eq!(clock.tick_into(&mut bus), 1);
+        assert_eq!(clock.tick_into(&mut bus), 0);
+        let events: Vec<_> = bus.drain().collect();
+        assert_eq!(
+            events,
+            vec![ResourceEvent {
+                resource: "oxygen",
+                from: None,
+                to: Some("gasping"),
+            }]
+        );
+        assert_eq!(clock.get(&"fuel").map(Resource::value), Some(5));
+        assert!(clock.remove(&"oxygen").is_some());
+        assert_eq!(clock.len(), 1);
+    }
+}
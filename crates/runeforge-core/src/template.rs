@@ -0,0 +1,407 @@
+//! Data-driven entity templates.
+//!
+//! An [`EntityTemplate`] describes one kind of monster or item: how it is
+//! drawn, its starting stats, which AI drives it and how often it appears at
+//! each dungeon depth. Templates are grouped into a validated [`TemplateSet`],
+//! usually loaded from a RON or TOML file (behind the `ron` and `toml`
+//! features), and the set builds depth-aware [`RandomTable`]s for spawning.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_color::Color;
+//! use runeforge_core::template::{EntityTemplate, SpawnWeight, TemplateSet};
+//! use runeforge_random::prelude::Rng;
+//!
+//! let set = TemplateSet::new(vec![
+//!     EntityTemplate::new("rat", "giant rat", 'r', Color::rgb(140, 110, 80))
+//!         .with_stat("hp", 4)
+//!         .with_spawn(SpawnWeight::new(1, Some(4), 10)),
+//!     EntityTemplate::new("troll", "cave troll", 'T', Color::rgb(60, 160, 60))
+//!         .with_stat("hp", 30)
+//!         .with_ai("brute")
+//!         .with_spawn(SpawnWeight::new(5, None, 3)),
+//! ])
+//! .unwrap();
+//!
+//! let mut rng = Rng::with_seed(1);
+//! assert_eq!(set.spawn(2, &mut rng).unwrap().id, "rat");
+//! assert_eq!(set.spawn(8, &mut rng).unwrap().id, "troll");
+//! ```
+//!
+//! With the `ron` feature, the same set can be loaded from a file:
+//!
+//! ```ron
+//! [
+//!     (
+//!         id: "rat",
+//!         name: "giant rat",
+//!         glyph: 'r',
+//!         fg: (r: 140, g: 110, b: 80, a: 255),
+//!         stats: { "hp": 4 },
+//!         spawn: [(min_depth: 1, max_depth: Some(4), weight: 10)],
+//!     ),
+//! ]
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+
+use runeforge_color::Color;
+use runeforge_random::prelude::{RandomTable, Rng};
+use thiserror::Error;
+
+/// Errors produced while loading or validating templates.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// The source text could not be parsed.
+    #[error("Failed to parse templates: {0}")]
+    Parse(String),
+    /// A template has an empty id.
+    #[error("Template at index {index} has an empty id")]
+    EmptyId {
+        /// Index of the template in the source list
+        index: usize,
+    },
+    /// Two templates share the same id.
+    #[error("Duplicate template id '{0}'")]
+    DuplicateId(String),
+    /// A spawn rule has `max_depth` below `min_depth`.
+    #[error("Template '{id}' has an invalid depth range {min_depth}..={max_depth}")]
+    InvalidDepthRange {
+        /// Id of the offending template
+        id: String,
+        /// Minimum depth of the rule
+        min_depth: u32,
+        /// Maximum depth of the rule
+        max_depth: u32,
+    },
+}
+
+impl TemplateError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            TemplateError::Parse(_) => "template.parse",
+            TemplateError::EmptyId { .. } => "template.empty_id",
+            TemplateError::DuplicateId(_) => "template.duplicate_id",
+            TemplateError::InvalidDepthRange { .. } => "template.invalid_depth_range",
+        }
+    }
+}
+
+/// How often a template spawns within a range of depths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct SpawnWeight {
+    /// Shallowest depth the rule applies to
+    pub min_depth: u32,
+    /// Deepest depth the rule applies to, or `None` for no limit
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub max_depth: Option<u32>,
+    /// Relative weight in the spawn table
+    pub weight: u32,
+}
+
+impl SpawnWeight {
+    /// Creates a spawn rule.
+    pub fn new(min_depth: u32, max_depth: Option<u32>, weight: u32) -> Self {
+        Self {
+            min_depth,
+            max_depth,
+            weight,
+        }
+    }
+
+    /// Returns true if the rule applies at the given depth.
+    pub fn contains(&self, depth: u32) -> bool {
+        depth >= self.min_depth && self.max_depth.is_none_or(|max| depth <= max)
+    }
+}
+
+/// The definition of one kind of entity.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct EntityTemplate {
+    /// Unique identifier, referenced by game data and save files
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// Glyph drawn for the entity
+    pub glyph: char,
+    /// Foreground color
+    pub fg: Color,
+    /// Background color, or `None` to keep the tile's background
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub bg: Option<Color>,
+    /// Starting stats by name
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub stats: BTreeMap<String, i32>,
+    /// Name of the AI that drives the entity, or `None` for inert entities
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub ai: Option<String>,
+    /// Spawn rules; the first rule that contains a depth decides the weight
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub spawn: Vec<SpawnWeight>,
+}
+
+impl EntityTemplate {
+    /// Creates a template with no stats, AI or spawn rules.
+    pub fn new(id: impl Into<String>, name: impl Into<String>, glyph: char, fg: Color) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            glyph,
+            fg,
+            bg: None,
+            stats: BTreeMap::new(),
+            ai: None,
+            spawn: Vec::new(),
+        }
+    }
+
+    /// Sets the background color.
+    pub fn with_bg(mut self, bg: Color) -> Self {
+        self.bg = Some(bg);
+        self
+    }
+
+    /// Sets a starting stat.
+    pub fn with_stat(mut self, stat: impl Into<String>, value: i32) -> Self {
+        self.stats.insert(stat.into(), value);
+        self
+    }
+
+    /// Sets the AI type.
+    pub fn with_ai(mut self, ai: impl Into<String>) -> Self {
+        self.ai = Some(ai.into());
+        self
+    }
+
+    /// Adds a spawn rule.
+    pub fn with_spawn(mut self, spawn: SpawnWeight) -> Self {
+        self.spawn.push(spawn);
+        self
+    }
+
+    /// Returns a starting stat, if defined.
+    pub fn stat(&self, stat: &str) -> Option<i32> {
+        self.stats.get(stat).copied()
+    }
+
+    /// Returns the spawn weight at a depth (0 if no rule applies).
+    pub fn weight_at(&self, depth: u32) -> u32 {
+        self.spawn
+            .iter()
+            .find(|rule| rule.contains(depth))
+            .map_or(0, |rule| rule.weight)
+    }
+}
+
+/// A validated collection of templates with unique ids.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TemplateSet {
+    templates: Vec<EntityTemplate>,
+}
+
+impl TemplateSet {
+    /// Validates and wraps a list of templates.
+    pub fn new(templates: Vec<EntityTemplate>) -> Result<Self, TemplateError> {
+        let mut seen = HashSet::new();
+        for (index, template) in templates.iter().enumerate() {
+            if template.id.trim().is_empty() {
+                return Err(TemplateError::EmptyId { index });
+            }
+            if !seen.insert(template.id.as_str()) {
+                return Err(TemplateError::DuplicateId(template.id.clone()));
+            }
+            for rule in &template.spawn {
+                if let Some(max_depth) = rule.max_depth.filter(|&max| max < rule.min_depth) {
+                    return Err(TemplateError::InvalidDepthRange {
+                        id: template.id.clone(),
+                        min_depth: rule.min_depth,
+                        max_depth,
+                    });
+                }
+            }
+        }
+        Ok(Self { templates })
+    }
+
+    /// Loads templates from a RON list.
+    #[cfg(feature = "ron")]
+    pub fn from_ron_str(source: &str) -> Result<Self, TemplateError> {
+        let templates = ron::from_str(source).map_err(|e| TemplateError::Parse(e.to_string()))?;
+        Self::new(templates)
+    }
+
+    /// Loads templates from a TOML document with one `[[template]]` table per entity.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(source: &str) -> Result<Self, TemplateError> {
+        #[derive(serde::Deserialize)]
+        struct Document {
+            #[serde(default)]
+            template: Vec<EntityTemplate>,
+        }
+
+        let doc: Document =
+            toml::from_str(source).map_err(|e| TemplateError::Parse(e.to_string()))?;
+        Self::new(doc.template)
+    }
+
+    /// Returns the template with the given id.
+    pub fn get(&self, id: &str) -> Option<&EntityTemplate> {
+        self.templates.iter().find(|t| t.id == id)
+    }
+
+    /// Returns an iterator over all templates.
+    pub fn iter(&self) -> impl Iterator<Item = &EntityTemplate> {
+        self.templates.iter()
+    }
+
+    /// Returns the number of templates.
+    pub fn len(&self) -> usize {
+        self.templates.len()
+    }
+
+    /// Returns true if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty()
+    }
+
+    /// Builds a spawn table containing every template that can appear at `depth`.
+    pub fn spawn_table(&self, depth: u32) -> RandomTable<&EntityTemplate> {
+        self.templates
+            .iter()
+            .map(|t| (t, t.weight_at(depth)))
+            .collect()
+    }
+
+    /// Picks a template to spawn at `depth`, or `None` if nothing can spawn there.
+    pub fn spawn(&self, depth: u32, rng: &mut Rng) -> Option<&EntityTemplate> {
+        self.spawn_table(depth).roll(rng).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeded_rng(seed: u64) -> Rng {
+        Rng::with_seed(seed)
+    }
+
+    fn goblin() -> EntityTemplate {
+        EntityTemplate::new("goblin", "goblin", 'g', Color::rgb(0, 200, 0))
+            .with_stat("hp", 7)
+            .with_ai("melee")
+            .with_spawn(SpawnWeight::new(1, Some(3), 10))
+            .with_spawn(SpawnWeight::new(4, None, 2))
+    }
+
+    #[test]
+    fn test_weight_at_uses_first_matching_rule() {
+        let goblin = goblin();
+        assert_eq!(goblin.weight_at(0), 0);
+        assert_eq!(goblin.weight_at(2), 10);
+        assert_eq!(goblin.weight_at(40), 2);
+        assert_eq!(goblin.stat("hp"), Some(7));
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let mut unnamed = goblin();
+        unnamed.id = " ".into();
+        assert_eq!(
+            TemplateSet::new(vec![goblin(), unnamed]),
+            Err(TemplateError::EmptyId { index: 1 })
+        );
+
+        assert_eq!(
+            TemplateSet::new(vec![goblin(), goblin()]),
+            Err(TemplateError::DuplicateId("goblin".into()))
+        );
+
+        let inverted = goblin().with_spawn(SpawnWeight::new(9, Some(2), 1));
+        assert!(matches!(
+            TemplateSet::new(vec![inverted]),
+            Err(TemplateError::InvalidDepthRange {
+                min_depth: 9,
+                max_depth: 2,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_spawn_table_filters_by_depth() {
+        let set = TemplateSet::new(vec![
+            goblin(),
+            EntityTemplate::new("dragon", "dragon", 'D', Color::rgb(200, 0, 0))
+                .with_spawn(SpawnWeight::new(10, None, 1)),
+        ])
+        .unwrap();
+
+        assert_eq!(set.spawn_table(2).len(), 1);
+        assert_eq!(set.spawn_table(12).len(), 2);
+        assert!(set.spawn(0, &mut seeded_rng(1)).is_none());
+        assert_eq!(set.spawn(1, &mut seeded_rng(1)).unwrap().id, "goblin");
+    }
+
+    #[cfg(feature = "ron")]
+    #[test]
+    fn test_from_ron_str() {
+        let set = TemplateSet::from_ron_str(
+            r#"[
+                (
+                    id: "bat",
+                    name: "cave bat",
+                    glyph: 'b',
+                    fg: (r: 120, g: 90, b: 60, a: 255),
+                    stats: { "hp": 3 },
+                    ai: Some("erratic"),
+                    spawn: [(min_depth: 1, weight: 5)],
+                ),
+            ]"#,
+        )
+        .unwrap();
+        let bat = set.get("bat").unwrap();
+        assert_eq!(bat.glyph, 'b');
+        assert_eq!(bat.ai.as_deref(), Some("erratic"));
+        assert_eq!(bat.weight_at(99), 5);
+
+        assert!(matches!(
+            TemplateSet::from_ron_str("[(id: \"x\")]"),
+            Err(TemplateError::Parse(_))
+        ));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_from_toml_str() {
+        let set = TemplateSet::from_toml_str(
+            r#"
+            [[template]]
+            id = "bat"
+            name = "cave bat"
+            glyph = "b"
+            fg = { r = 120, g = 90, b = 60, a = 255 }
+            stats = { hp = 3 }
+
+            [[template.spawn]]
+            min_depth = 1
+            max_depth = 5
+            weight = 5
+            "#,
+        )
+        .unwrap();
+        let bat = set.get("bat").unwrap();
+        assert_eq!(bat.stat("hp"), Some(3));
+        assert_eq!(bat.weight_at(6), 0);
+    }
+}
@@ -0,0 +1,244 @@
+//! Events scheduled in game turns.
+//!
+//! A [`DelayQueue`] holds events that should happen later: a bomb that goes
+//! off in three turns, poison that ticks every turn for five turns, a spell
+//! that wears off after twenty. Call [`advance`](DelayQueue::advance) once
+//! per game turn to collect the events that are due, or
+//! [`advance_into`](DelayQueue::advance_into) to publish them straight onto an
+//! [`EventBus`]. Every scheduled event gets a [`TimerId`] so it can be
+//! cancelled, e.g. when the bomb is defused.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_core::prelude::*;
+//!
+//! #[derive(Debug, Clone, PartialEq)]
+//! enum Timed { Explode, PoisonTick }
+//!
+//! let mut timers = DelayQueue::new();
+//! let bomb = timers.schedule(3, Timed::Explode);
+//! timers.schedule_every(1, Some(2), Timed::PoisonTick);
+//!
+//! let fired: Vec<Timed> = timers.advance().into_iter().map(|(_, e)| e).collect();
+//! assert_eq!(fired, vec![Timed::PoisonTick]);
+//! assert_eq!(timers.remaining(bomb), Some(2));
+//!
+//! timers.advance();
+//! assert_eq!(timers.cancel(bomb), Some(Timed::Explode));
+//! assert!(timers.advance().is_empty());
+//! assert!(timers.is_empty());
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::event::EventBus;
+
+/// Handle returned when scheduling an event, used to cancel it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TimerId(u64);
+
+#[derive(Debug, Clone)]
+struct Timer<E> {
+    due: u64,
+    every: Option<u64>,
+    /// Firings left for repeating timers; `None` repeats forever.
+    times: Option<u32>,
+    event: E,
+}
+
+/// Events waiting for a future turn.
+#[derive(Debug, Clone)]
+pub struct DelayQueue<E> {
+    turn: u64,
+    next_id: u64,
+    timers: HashMap<TimerId, Timer<E>>,
+    /// Due turn and id of every pending firing. Entries for cancelled timers
+    /// are skipped when popped.
+    order: BinaryHeap<Reverse<(u64, TimerId)>>,
+}
+
+impl<E> Default for DelayQueue<E> {
+    fn default() -> Self {
+        Self {
+            turn: 0,
+            next_id: 0,
+            timers: HashMap::new(),
+            order: BinaryHeap::new(),
+        }
+    }
+}
+
+impl<E> DelayQueue<E> {
+    /// Creates an empty queue at turn 0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of turns advanced so far.
+    pub fn turn(&self) -> u64 {
+        self.turn
+    }
+
+    /// Schedules `event` to fire `delay` turns from now. A delay of 0 fires
+    /// on the next advance, like a delay of 1.
+    pub fn schedule(&mut self, delay: u32, event: E) -> TimerId {
+        self.insert(delay, None, Some(1), event)
+    }
+
+    /// Schedules `event` to fire every `every` turns, starting `every` turns
+    /// from now, `times` times or until cancelled if `None`. An interval of
+    /// 0 is treated as 1.
+    pub fn schedule_every(&mut self, every: u32, times: Option<u32>, event: E) -> TimerId {
+        let every = every.max(1);
+        self.insert(every, Some(u64::from(every)), times, event)
+    }
+
+    /// Cancels a timer, returning its event if it was still pending.
+    pub fn cancel(&mut self, id: TimerId) -> Option<E> {
+        self.timers.remove(&id).map(|timer| timer.event)
+    }
+
+    /// Returns true if the timer will still fire.
+    pub fn is_scheduled(&self, id: TimerId) -> bool {
+        self.timers.contains_key(&id)
+    }
+
+    /// Returns the number of turns until the timer next fires.
+    pub fn remaining(&self, id: TimerId) -> Option<u32> {
+        let timer = self.timers.get(&id)?;
+        Some(timer.due.saturating_sub(self.turn).min(u64::from(u32::MAX)) as u32)
+    }
+
+    /// Returns the event a pending timer will fire.
+    pub fn get(&self, id: TimerId) -> Option<&E> {
+        self.timers.get(&id).map(|timer| &timer.event)
+    }
+
+    /// Returns the number of pending timers.
+    pub fn len(&self) -> usize {
+        self.timers.len()
+    }
+
+    /// Returns true if no timers are pending.
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    /// Cancels every timer. The turn count is kept.
+    pub fn clear(&mut self) {
+        self.timers.clear();
+        self.order.clear();
+    }
+
+    fn insert(&mut self, delay: u32, every: Option<u64>, times: Option<u32>, event: E) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+        if times == Some(0) {
+            return id;
+        }
+        let due = self.turn + u64::from(delay.max(1));
+        self.timers.insert(
+            id,
+            Timer {
+                due,
+                every,
+                times,
+                event,
+            },
+        );
+        self.order.push(Reverse((due, id)));
+        id
+    }
+}
+
+impl<E: Clone> DelayQueue<E> {
+    /// Ends a turn and returns the events that fire, in the order they were
+    /// due and then the order they were scheduled.
+    pub fn advance(&mut self) -> Vec<(TimerId, E)> {
+        self.advance_by(1)
+    }
+
+    /// Ends `turns` turns at once, returning every event that fires along
+    /// the way. Repeating timers fire once per interval that passes.
+    pub fn advance_by(&mut self, turns: u32) -> Vec<(TimerId, E)> {
+        self.turn += u64::from(turns);
+        let mut fired = Vec::new();
+        while let Some(&Reverse((due, id))) = self.order.peek() {
+            if due > self.turn {
+                break;
+            }
+            self.order.pop();
+            let Some(timer) = self.timers.get_mut(&id) else {
+                continue;
+            };
+            if let Some(times) = &mut timer.times {
+                *times -= 1;
+            }
+            match timer.every {
+                Some(every) if timer.times != Some(0) => {
+                    timer.due += every;
+                    self.order.push(Reverse((timer.due, id)));
+                    fired.push((id, timer.event.clone()));
+                }
+                _ => {
+                    let timer = self.timers.remove(&id).expect("timer exists");
+                    fired.push((id, timer.event));
+                }
+            }
+        }
+        fired
+    }
+
+    /// Ends a turn and publishes the events that fire onto `bus`, returning
+    /// how many fired.
+    pub fn advance_into(&mut self, bus: &mut EventBus<E>) -> usize {
+        let fired = self.advance();
+        let count = fired.len();
+        for (_, event) in fired {
+            bus.publish(event);
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delays_fire_in_order() {
+        let mut timers = DelayQueue::new();
+        timers.schedule(2, "late");
+        let first = timers.schedule(1, "first");
+        timers.schedule(0, "also first");
+
+        let fired: Vec<_> = timers.advance().into_iter().map(|(_, e)| e).collect();
+        assert_eq!(fired, vec!["first", "also first"]);
+        assert!(!timers.is_scheduled(first));
+        assert_eq!(timers.advance().len(), 1);
+        assert_eq!(timers.turn(), 2);
+    }
+
+    #[test]
+    fn test_repeating_timers_and_cancel() {
+        let mut timers = DelayQueue::new();
+        let dot = timers.schedule_every(2, Some(3), 'p');
+        let aura = timers.schedule_every(1, None, 'a');
+
+        let fired = timers.advance_by(7);
+        assert_eq!(fired.iter().filter(|(id, _)| *id == dot).count(), 3);
+        assert_eq!(fired.iter().filter(|(id, _)| *id == aura).count(), 7);
+        assert!(!timers.is_scheduled(dot));
+        assert_eq!(timers.remaining(aura), Some(1));
+
+        let mut bus = EventBus::new();
+        assert_eq!(timers.advance_into(&mut bus), 1);
+        assert_eq!(timers.cancel(aura), Some('a'));
+        assert_eq!(timers.advance_into(&mut bus), 0);
+        assert_eq!(bus.drain().len(), 1);
+        timers.schedule_every(1, Some(0), 'x');
+        assert!(timers.is_empty());
+    }
+}
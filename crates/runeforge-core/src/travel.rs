@@ -0,0 +1,573 @@
+//! Multi-step running and auto-travel with interruption rules.
+//!
+//! A [`TravelExecutor`] turns one command ("run west", "travel to the
+//! stairs") into a series of ordinary move [`Action`]s, one per step, so
+//! running goes through the same validation, energy costs and replay log as
+//! single steps. Before every step it checks the standard reasons to stop:
+//!
+//! *   an enemy is in view (running won't even start while one is),
+//! *   there is an item under the traveller's feet,
+//! *   when running, the surroundings change: a side passage opens up, a
+//!     corridor ends in a room, and so on.
+//!
+//! Running follows a corridor around corners. Paths (e.g. from
+//! `runeforge-pathfinding`) are followed as given.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_core::prelude::*;
+//! use runeforge_geometry::prelude::{CoordinateSystem, Direction};
+//!
+//! struct World {
+//!     player: IVec2,
+//!     map: Vec<&'static str>,
+//! }
+//!
+//! impl TravelMap for World {
+//!     fn traveller_position(&self) -> IVec2 {
+//!         self.player
+//!     }
+//!
+//!     fn is_passable(&self, pos: IVec2) -> bool {
+//!         let row = self.map.get(pos.y as usize).map(|r| r.as_bytes());
+//!         matches!(row.and_then(|r| r.get(pos.x as usize)), Some(b'.' | b'!'))
+//!     }
+//!
+//!     fn enemy_in_view(&self) -> bool {
+//!         false
+//!     }
+//!
+//!     fn has_item_at(&self, pos: IVec2) -> bool {
+//!         self.map[pos.y as usize].as_bytes()[pos.x as usize] == b'!'
+//!     }
+//! }
+//!
+//! struct Move(IVec2);
+//!
+//! impl Action<World> for Move {
+//!     fn name(&self) -> &str {
+//!         "move"
+//!     }
+//!
+//!     fn execute(&mut self, world: &mut World) {
+//!         world.player += self.0;
+//!     }
+//! }
+//!
+//! let mut world = World {
+//!     player: IVec2::new(1, 1),
+//!     map: vec![
+//!         "#########",
+//!         "#....!..#",
+//!         "#########",
+//!     ],
+//! };
+//! let mut queue = ActionQueue::new();
+//! let mut travel = TravelExecutor::new(Move).with_coordinate_system(CoordinateSystem::YDown);
+//!
+//! travel.run(Direction::EAST);
+//! assert_eq!(travel.travel(&mut world, &mut queue), TravelStop::ItemUnderFoot);
+//! assert_eq!(world.player, IVec2::new(5, 1));
+//! assert_eq!(queue.history_names().count(), 4);
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use runeforge_geometry::prelude::{CoordinateSystem, Direction, GridPoint, IVec2};
+
+use crate::action::{Action, ActionError, ActionOutcome, ActionQueue};
+
+/// The default limit on the number of steps in one run or trip.
+pub const DEFAULT_MAX_TRAVEL_STEPS: u32 = 1000;
+
+/// What a [`TravelExecutor`] needs to know about the world.
+pub trait TravelMap {
+    /// Returns the position of the one travelling.
+    fn traveller_position(&self) -> IVec2;
+
+    /// Returns true if the traveller can step onto `pos`.
+    fn is_passable(&self, pos: IVec2) -> bool;
+
+    /// Returns true if the traveller can see an enemy.
+    fn enemy_in_view(&self) -> bool;
+
+    /// Returns true if there is an item at `pos`. Defaults to false.
+    fn has_item_at(&self, _pos: IVec2) -> bool {
+        false
+    }
+}
+
+/// Which conditions stop travel early.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InterruptRules {
+    /// Stop when an enemy is in view.
+    pub enemy_in_view: bool,
+    /// Stop on a square holding an item.
+    pub item_under_foot: bool,
+    /// Stop a run when the surroundings change, e.g. at a corridor junction.
+    pub corridor_junction: bool,
+}
+
+impl Default for InterruptRules {
+    fn default() -> Self {
+        Self {
+            enemy_in_view: true,
+            item_under_foot: true,
+            corridor_junction: true,
+        }
+    }
+}
+
+impl InterruptRules {
+    /// Rules that never interrupt; travel only ends on arrival or when
+    /// blocked.
+    pub fn none() -> Self {
+        Self {
+            enemy_in_view: false,
+            item_under_foot: false,
+            corridor_junction: false,
+        }
+    }
+}
+
+/// Why travel ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TravelStop {
+    /// The end of the path was reached.
+    Arrived,
+    /// An enemy is in view.
+    EnemyInView,
+    /// The traveller is standing on an item.
+    ItemUnderFoot,
+    /// A run reached a junction or the edge of a room.
+    Junction,
+    /// The way ahead is blocked, or the path no longer starts next to the
+    /// traveller.
+    Blocked,
+    /// The step limit was reached.
+    StepLimit,
+    /// Travel was cancelled, or none was in progress.
+    Cancelled,
+    /// The move action failed validation.
+    Rejected(ActionError),
+}
+
+impl fmt::Display for TravelStop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TravelStop::Arrived => write!(f, "Arrived"),
+            TravelStop::EnemyInView => write!(f, "An enemy is in view"),
+            TravelStop::ItemUnderFoot => write!(f, "There is an item here"),
+            TravelStop::Junction => write!(f, "Reached a junction"),
+            TravelStop::Blocked => write!(f, "The way is blocked"),
+            TravelStop::StepLimit => write!(f, "Travelled too far"),
+            TravelStop::Cancelled => write!(f, "Travel cancelled"),
+            TravelStop::Rejected(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+/// The result of one [`TravelExecutor::advance`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TravelStatus {
+    /// One step was taken and travel continues.
+    Moved,
+    /// Travel is over.
+    Stopped(TravelStop),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Plan {
+    Idle,
+    Path(VecDeque<IVec2>),
+    Run(IVec2),
+}
+
+type MoveFactory<W> = Box<dyn Fn(IVec2) -> Box<dyn Action<W>>>;
+
+/// Runs in a direction or follows a path, one move action per step.
+pub struct TravelExecutor<W> {
+    make_move: MoveFactory<W>,
+    rules: InterruptRules,
+    system: CoordinateSystem,
+    max_steps: u32,
+    plan: Plan,
+    steps: u32,
+    previous: Option<IVec2>,
+    exits: Option<usize>,
+}
+
+impl<W> fmt::Debug for TravelExecutor<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TravelExecutor")
+            .field("rules", &self.rules)
+            .field("plan", &self.plan)
+            .field("steps", &self.steps)
+            .finish()
+    }
+}
+
+impl<W: TravelMap> TravelExecutor<W> {
+    /// Creates an idle executor. `make_move` builds the action for a single
+    /// step by the given offset.
+    pub fn new<F, A>(make_move: F) -> Self
+    where
+        F: Fn(IVec2) -> A + 'static,
+        A: Action<W> + 'static,
+    {
+        Self {
+            make_move: Box::new(move |delta| Box::new(make_move(delta)) as Box<dyn Action<W>>),
+            rules: InterruptRules::default(),
+            system: CoordinateSystem::default(),
+            max_steps: DEFAULT_MAX_TRAVEL_STEPS,
+            plan: Plan::Idle,
+            steps: 0,
+            previous: None,
+            exits: None,
+        }
+    }
+
+    /// Sets which conditions interrupt travel.
+    pub fn with_rules(mut self, rules: InterruptRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Sets how [`run`](Self::run) directions map onto the world's axes.
+    /// Defaults to [`CoordinateSystem::YDown`].
+    pub fn with_coordinate_system(mut self, system: CoordinateSystem) -> Self {
+        self.system = system;
+        self
+    }
+
+    /// Sets the most steps one run or trip may take (minimum 1).
+    pub fn with_max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = max_steps.max(1);
+        self
+    }
+
+    /// Returns the interruption rules.
+    pub fn rules(&self) -> InterruptRules {
+        self.rules
+    }
+
+    /// Starts running towards `direction`, replacing any travel in progress.
+    pub fn run(&mut self, direction: Direction) {
+        self.start(Plan::Run(direction.coord_in(self.system)));
+    }
+
+    /// Starts following `path`, replacing any travel in progress.
+    ///
+    /// The path may include the traveller's current position; it is
+    /// skipped.
+    pub fn follow_path(&mut self, path: impl IntoIterator<Item = IVec2>) {
+        self.start(Plan::Path(path.into_iter().collect()));
+    }
+
+    /// Returns true while a run or trip is in progress.
+    pub fn is_travelling(&self) -> bool {
+        self.plan != Plan::Idle
+    }
+
+    /// Returns how many steps the current or last trip has taken.
+    pub fn steps(&self) -> u32 {
+        self.steps
+    }
+
+    /// Stops travelling, e.g. when the player presses a key.
+    pub fn cancel(&mut self) {
+        self.plan = Plan::Idle;
+    }
+
+    /// Checks the interruption rules and returns the action for the next
+    /// step, or why travel stopped.
+    ///
+    /// The step is counted as taken; use [`advance`](Self::advance) to also
+    /// queue and perform it.
+    pub fn next_action(&mut self, world: &W) -> Result<Box<dyn Action<W>>, TravelStop> {
+        match self.next_delta(world) {
+            Ok(delta) => {
+                self.previous = Some(world.traveller_position());
+                self.steps += 1;
+                Ok((self.make_move)(delta))
+            }
+            Err(stop) => {
+                self.plan = Plan::Idle;
+                Err(stop)
+            }
+        }
+    }
+
+    /// Takes one step: queues the next move and processes the queue.
+    ///
+    /// Call once per turn, letting the rest of the world act in between.
+    pub fn advance(&mut self, world: &mut W, queue: &mut ActionQueue<W>) -> TravelStatus {
+        let action = match self.next_action(world) {
+            Ok(action) => action,
+            Err(stop) => return TravelStatus::Stopped(stop),
+        };
+        queue.push_boxed(action);
+        // The move was queued last, so its outcome comes last
+        match queue.process_all(world).pop() {
+            Some(ActionOutcome::Rejected(err)) => {
+                self.plan = Plan::Idle;
+                TravelStatus::Stopped(TravelStop::Rejected(err))
+            }
+            _ => TravelStatus::Moved,
+        }
+    }
+
+    /// Keeps stepping until travel stops and returns why.
+    ///
+    /// Only suitable when nothing else needs to act between steps.
+    pub fn travel(&mut self, world: &mut W, queue: &mut ActionQueue<W>) -> TravelStop {
+        loop {
+            if let TravelStatus::Stopped(stop) = self.advance(world, queue) {
+                return stop;
+            }
+        }
+    }
+
+    fn start(&mut self, plan: Plan) {
+        self.plan = plan;
+        self.steps = 0;
+        self.previous = None;
+        self.exits = None;
+    }
+
+    fn next_delta(&mut self, world: &W) -> Result<IVec2, TravelStop> {
+        let pos = world.traveller_position();
+        if self.plan == Plan::Idle {
+            return Err(TravelStop::Cancelled);
+        }
+        if self.rules.enemy_in_view && world.enemy_in_view() {
+            return Err(TravelStop::EnemyInView);
+        }
+        if self.steps > 0 && self.rules.item_under_foot && world.has_item_at(pos) {
+            return Err(TravelStop::ItemUnderFoot);
+        }
+        if self.steps >= self.max_steps {
+            return Err(TravelStop::StepLimit);
+        }
+
+        match &mut self.plan {
+            Plan::Idle => Err(TravelStop::Cancelled),
+            Plan::Path(path) => {
+                while path.front() == Some(&pos) {
+                    path.pop_front();
+                }
+                let next = path.pop_front().ok_or(TravelStop::Arrived)?;
+                if pos.distance(next) != 1 || !world.is_passable(next) {
+                    return Err(TravelStop::Blocked);
+                }
+                Ok(next - pos)
+            }
+            Plan::Run(delta) => {
+                let came_from = self.previous.unwrap_or(pos - *delta);
+                let exits: Vec<IVec2> = pos
+                    .neighbors()
+                    .filter(|&n| n != came_from && world.is_passable(n))
+                    .collect();
+                // A dead end isn't a junction; it's reported as blocked below
+                let changed = self.exits.is_some_and(|count| count != exits.len());
+                if self.rules.corridor_junction && changed && !exits.is_empty() {
+                    return Err(TravelStop::Junction);
+                }
+                self.exits = Some(exits.len());
+
+                if !world.is_passable(pos + *delta) {
+                    // Follow a corridor around a corner
+                    let cardinal = delta.x == 0 || delta.y == 0;
+                    match exits.as_slice() {
+                        [exit] if self.steps > 0 && cardinal => *delta = *exit - pos,
+                        _ => return Err(TravelStop::Blocked),
+                    }
+                }
+                Ok(*delta)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct World {
+        player: IVec2,
+        map: Vec<&'static str>,
+        enemy: bool,
+    }
+
+    impl World {
+        fn new(player: IVec2, map: Vec<&'static str>) -> Self {
+            Self {
+                player,
+                map,
+                enemy: false,
+            }
+        }
+
+        fn tile(&self, pos: IVec2) -> u8 {
+            self.map
+                .get(pos.y as usize)
+                .and_then(|row| row.as_bytes().get(pos.x as usize))
+                .copied()
+                .unwrap_or(b'#')
+        }
+    }
+
+    impl TravelMap for World {
+        fn traveller_position(&self) -> IVec2 {
+            self.player
+        }
+
+        fn is_passable(&self, pos: IVec2) -> bool {
+            self.tile(pos) != b'#'
+        }
+
+        fn enemy_in_view(&self) -> bool {
+            self.enemy
+        }
+
+        fn has_item_at(&self, pos: IVec2) -> bool {
+            self.tile(pos) == b'!'
+        }
+    }
+
+    struct Move(IVec2);
+
+    impl Action<World> for Move {
+        fn name(&self) -> &str {
+            "move"
+        }
+
+        fn validate(&self, world: &World) -> Result<(), ActionError> {
+            if world.is_passable(world.player + self.0) {
+                Ok(())
+            } else {
+                Err(ActionError::Invalid("wall".to_string()))
+            }
+        }
+
+        fn execute(&mut self, world: &mut World) {
+            world.player += self.0;
+        }
+    }
+
+    fn executor() -> TravelExecutor<World> {
+        TravelExecutor::new(Move)
+    }
+
+    #[test]
+    fn test_run_follows_corner_and_stops_at_junction() {
+        let mut world = World::new(
+            IVec2::new(1, 1),
+            vec![
+                "#######", //
+                "#....##", //
+                "####.##", //
+                "###...#", //
+                "####.##", //
+            ],
+        );
+        let mut queue = ActionQueue::new();
+        let mut travel = executor();
+        travel.run(Direction::EAST);
+        assert_eq!(travel.travel(&mut world, &mut queue), TravelStop::Junction);
+        assert_eq!(world.player, IVec2::new(4, 3));
+        assert_eq!(travel.steps(), 5);
+        assert!(!travel.is_travelling());
+    }
+
+    #[test]
+    fn test_run_stops_when_blocked() {
+        let mut world = World::new(IVec2::new(1, 1), vec!["#####", "#...#", "#####"]);
+        let mut queue = ActionQueue::new();
+        let mut travel = executor();
+        travel.run(Direction::EAST);
+        assert_eq!(travel.travel(&mut world, &mut queue), TravelStop::Blocked);
+        assert_eq!(world.player, IVec2::new(3, 1));
+    }
+
+    #[test]
+    fn test_enemy_prevents_and_interrupts() {
+        let mut world = World::new(IVec2::new(1, 1), vec!["#######", "#.....#", "#######"]);
+        world.enemy = true;
+        let mut queue = ActionQueue::new();
+        let mut travel = executor();
+        travel.run(Direction::EAST);
+        assert_eq!(
+            travel.advance(&mut world, &mut queue),
+            TravelStatus::Stopped(TravelStop::EnemyInView)
+        );
+        assert_eq!(world.player, IVec2::new(1, 1));
+
+        world.enemy = false;
+        travel.run(Direction::EAST);
+        assert_eq!(travel.advance(&mut world, &mut queue), TravelStatus::Moved);
+        world.enemy = true;
+        assert_eq!(
+            travel.advance(&mut world, &mut queue),
+            TravelStatus::Stopped(TravelStop::EnemyInView)
+        );
+        assert_eq!(world.player, IVec2::new(2, 1));
+
+        let mut travel = executor().with_rules(InterruptRules::none());
+        travel.run(Direction::EAST);
+        assert_eq!(travel.travel(&mut world, &mut queue), TravelStop::Blocked);
+    }
+
+    #[test]
+    fn test_follow_path() {
+        let mut world = World::new(
+            IVec2::new(1, 1),
+            vec![
+                "#####", //
+                "#...#", //
+                "#.!.#", //
+                "#...#", //
+                "#####",
+            ],
+        );
+        let mut queue = ActionQueue::new();
+        let mut travel = executor();
+        let path = [(1, 1), (2, 1), (3, 2), (3, 3)].map(|(x, y)| IVec2::new(x, y));
+        travel.follow_path(path);
+        assert_eq!(travel.travel(&mut world, &mut queue), TravelStop::Arrived);
+        assert_eq!(world.player, IVec2::new(3, 3));
+        assert_eq!(queue.history_names().count(), 3);
+
+        // A path that doesn't start next to the traveller is rejected
+        travel.follow_path([IVec2::new(1, 1)]);
+        assert_eq!(travel.travel(&mut world, &mut queue), TravelStop::Blocked);
+        assert_eq!(world.player, IVec2::new(3, 3));
+
+        // Walking over an item stops on it
+        travel.follow_path([IVec2::new(2, 2), IVec2::new(1, 1)]);
+        assert_eq!(
+            travel.travel(&mut world, &mut queue),
+            TravelStop::ItemUnderFoot
+        );
+        assert_eq!(world.player, IVec2::new(2, 2));
+    }
+
+    #[test]
+    fn test_step_limit_and_cancel() {
+        let mut world = World::new(IVec2::new(1, 1), vec!["#######", "#.....#", "#######"]);
+        let mut queue = ActionQueue::new();
+        let mut travel = executor().with_max_steps(2);
+        travel.run(Direction::EAST);
+        assert_eq!(travel.travel(&mut world, &mut queue), TravelStop::StepLimit);
+        assert_eq!(world.player, IVec2::new(3, 1));
+
+        travel.run(Direction::WEST);
+        travel.cancel();
+        assert_eq!(
+            travel.advance(&mut world, &mut queue),
+            TravelStatus::Stopped(TravelStop::Cancelled)
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! Which way the Y axis points.
+
+use crate::prelude::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+/// The direction of the Y axis in a coordinate space.
+///
+/// [`Direction::coord`] is Y-up: `North` is `+1` on the `Y` axis. Consoles,
+/// screens and most map arrays are Y-down, with row 0 at the top. Pick the
+/// convention your world uses once and convert through it with
+/// [`Direction::coord_in`] instead of flipping signs by hand.
+///
+/// The default is [`YDown`](Self::YDown), the layout of consoles and
+/// row-major maps.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum CoordinateSystem {
+    /// `Y` grows upwards: `North` is `+1`.
+    YUp,
+    /// `Y` grows downwards: `North` is `-1`.
+    #[default]
+    YDown,
+}
+
+impl CoordinateSystem {
+    /// Returns `1` for [`YUp`](Self::YUp) and `-1` for [`YDown`](Self::YDown),
+    /// the factor that turns a Y-up offset into one in this system.
+    pub const fn y_sign(self) -> i32 {
+        match self {
+            Self::YUp => 1,
+            Self::YDown => -1,
+        }
+    }
+
+    /// Converts a Y-up offset into this system.
+    pub const fn from_y_up(self, offset: IVec2) -> IVec2 {
+        IVec2::new(offset.x, offset.y * self.y_sign())
+    }
+
+    /// Converts an offset in this system into a Y-up one.
+    pub const fn to_y_up(self, offset: IVec2) -> IVec2 {
+        // Flipping is its own inverse
+        self.from_y_up(offset)
+    }
+}
+
+impl Direction {
+    /// Retrieves the 2d coordinate value from the [`Direction`] in `system`.
+    ///
+    /// ```
+    /// use runeforge_direction::prelude::*;
+    ///
+    /// assert_eq!(Direction::NORTH.coord_in(CoordinateSystem::YUp), IVec2::new(0, 1));
+    /// assert_eq!(Direction::NORTH.coord_in(CoordinateSystem::YDown), IVec2::new(0, -1));
+    /// ```
+    pub const fn coord_in(self, system: CoordinateSystem) -> IVec2 {
+        system.from_y_up(self.coord())
+    }
+
+    /// Retrieves the [`Direction`] from a 2d coordinate value in `system`.
+    pub fn from_coord_in(coord: IVec2, system: CoordinateSystem) -> Self {
+        Self::from_coord(system.to_y_up(coord))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn coord_in_round_trips() {
+        for system in [CoordinateSystem::YUp, CoordinateSystem::YDown] {
+            for dir in Direction::all() {
+                assert_eq!(Direction::from_coord_in(dir.coord_in(system), system), dir);
+            }
+        }
+        assert_eq!(
+            Direction::SOUTH_EAST.coord_in(CoordinateSystem::YDown),
+            IVec2::new(1, 1)
+        );
+        assert_eq!(
+            Direction::from_coord_in(IVec2::new(-1, -1), CoordinateSystem::YDown),
+            Direction::NORTH_WEST
+        );
+    }
+}
@@ -1,12 +1,16 @@
 //! Core Direction type and implementation.
 
-use derive_more::{Deref, DerefMut};
-#[cfg(feature = "serialize")]
-use serde::{Deserialize, Serialize};
-use std::{
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::{
     fmt::Display,
     ops::{Add, AddAssign, Sub, SubAssign},
 };
+use derive_more::{Deref, DerefMut};
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
 
 use crate::prelude::*;
 
@@ -233,16 +237,16 @@ impl Direction {
 
         // Check Y
         match coord.y.cmp(&0) {
-            std::cmp::Ordering::Equal => {}
-            std::cmp::Ordering::Less => direction += Self::SOUTH,
-            std::cmp::Ordering::Greater => direction += Self::NORTH,
+            core::cmp::Ordering::Equal => {}
+            core::cmp::Ordering::Less => direction += Self::SOUTH,
+            core::cmp::Ordering::Greater => direction += Self::NORTH,
         }
 
         // Check Z
         match coord.z.cmp(&0) {
-            std::cmp::Ordering::Equal => {}
-            std::cmp::Ordering::Less => direction += Self::DOWN,
-            std::cmp::Ordering::Greater => direction += Self::UP,
+            core::cmp::Ordering::Equal => {}
+            core::cmp::Ordering::Less => direction += Self::DOWN,
+            core::cmp::Ordering::Greater => direction += Self::UP,
         }
 
         direction
@@ -628,7 +632,7 @@ impl DirectionIterator for Direction {
 }
 
 impl Display for Direction {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         fn append(old_string: String, next: &str, first: bool) -> String {
             if first {
                 next.to_string()
@@ -12,6 +12,7 @@
 //! *   **Flexible Types:** Supports `CardinalDirection`, `OrdinalDirection`, and general `Direction`.
 //! *   **Arithmetic Operations:** Add/Subtract directions (e.g., `NORTH + EAST = NORTH_EAST`).
 //! *   **Coordinate Conversion:** Easily convert between Directions and `IVec2`/`IVec3` vectors.
+//! *   **Coordinate Systems:** `Direction::coord` is Y-up; `CoordinateSystem` converts to and from Y-down screen space.
 //! *   **Iterators:** Efficiently iterate over all neighbors or specific subsets of directions.
 //!
 //! # Usage
@@ -54,8 +55,18 @@
 //! *   [`cardinal_direction`]: Helpers for 4-way movement.
 //! *   [`ordinal_direction`]: Helpers for diagonal movement.
 //! *   [`direction_iter`]: Iterators for direction sets.
+//! *   [`coordinate_system`]: Y-up vs Y-down conversions.
+//!
+//! # `no_std`
+//!
+//! The crate is `no_std` + `alloc` when the default `std` feature is disabled.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
 pub mod cardinal_direction;
+pub mod coordinate_system;
 pub mod direction;
 pub mod direction_flags;
 pub mod direction_iter;
@@ -69,6 +80,7 @@ pub mod prelude {
     pub use glam::{IVec2, IVec3};
 
     pub use crate::cardinal_direction::*;
+    pub use crate::coordinate_system::*;
     pub use crate::direction::*;
     pub use crate::direction_flags::*;
     pub use crate::direction_iter::*;
@@ -9,9 +9,57 @@ impl FovAlgorithm for AdamsFov {
         origin: IVec2,
         range: u32,
         provider: &mut impl FovProvider<T>,
-        mut pass_through_data: T,
+        pass_through_data: T,
+    ) -> HashSet<IVec2> {
+        Self::compute_with_shape(
+            origin,
+            range,
+            FovShape::default(),
+            provider,
+            pass_through_data,
+        )
+    }
+}
+
+impl AdamsFov {
+    /// Computes the field of view, limiting range with `shape`.
+    pub fn compute_with_shape<T>(
+        origin: IVec2,
+        range: u32,
+        shape: FovShape,
+        provider: &mut impl FovProvider<T>,
+        pass_through_data: T,
     ) -> HashSet<IVec2> {
-        let mut visible_points = HashSet::with_capacity(((range * 2) * (range * 2)) as usize);
+        let mut visible_points = HashSet::new();
+        Self::compute_with_shape_into(
+            origin,
+            range,
+            shape,
+            provider,
+            pass_through_data,
+            &mut visible_points,
+        );
+        visible_points
+    }
+
+    /// Like [`compute_with_shape`](Self::compute_with_shape), clearing and
+    /// refilling `visible_points` so its allocation can be reused.
+    pub fn compute_with_shape_into<T>(
+        origin: IVec2,
+        range: u32,
+        shape: FovShape,
+        provider: &mut impl FovProvider<T>,
+        mut pass_through_data: T,
+        visible_points: &mut HashSet<IVec2>,
+    ) {
+        let extent = shape.extent(range);
+        visible_points.clear();
+        visible_points.reserve(((extent * 2) * (extent * 2)) as usize);
+        let bounds = Bounds {
+            shape,
+            range,
+            extent: extent as i32,
+        };
 
         visible_points.insert(origin);
 
@@ -19,26 +67,22 @@ impl FovAlgorithm for AdamsFov {
             Self::compute_octant(
                 octant,
                 origin,
-                range as i32,
+                &bounds,
                 1,
                 Slope { x: 1, y: 1 },
                 Slope { x: 1, y: 0 },
                 provider,
                 &mut pass_through_data,
-                &mut visible_points,
+                visible_points,
             )
         }
-
-        visible_points
     }
-}
 
-impl AdamsFov {
     #[allow(clippy::too_many_arguments)]
     fn compute_octant<T>(
         octant: i32,
         origin: IVec2,
-        range: i32,
+        bounds: &Bounds,
         x: i32,
         mut top: Slope,
         mut bottom: Slope,
@@ -46,7 +90,7 @@ impl AdamsFov {
         pass_through_data: &mut T,
         visible_points: &mut HashSet<IVec2>,
     ) {
-        for x in x..=range {
+        for x in x..=bounds.extent {
             let y_coords = Self::compute_y(
                 octant,
                 origin,
@@ -63,7 +107,7 @@ impl AdamsFov {
             if !Self::compute_visiblity(
                 top_y,
                 bottom_y,
-                range,
+                bounds,
                 octant,
                 origin,
                 x,
@@ -145,7 +189,7 @@ impl AdamsFov {
     fn compute_visiblity<T>(
         top_y: i32,
         bottom_y: i32,
-        range: i32,
+        bounds: &Bounds,
         octant: i32,
         origin: IVec2,
         x: i32,
@@ -158,7 +202,7 @@ impl AdamsFov {
         let mut was_opaque = -1;
 
         for y in (bottom_y..=top_y).rev() {
-            if range < 0 || (x * x + y * y) <= (range * range) {
+            if bounds.contains(Self::transform(x, y, octant, origin) - origin) {
                 let is_opaque =
                     Self::blocks_light(x, y, octant, origin, provider, pass_through_data);
 
@@ -173,7 +217,7 @@ impl AdamsFov {
                     Self::set_visible(x, y, octant, origin, visible_points);
                 }
 
-                if x != range {
+                if x != bounds.extent {
                     if is_opaque {
                         if was_opaque == 0 {
                             let mut nx = x * 2;
@@ -196,7 +240,7 @@ impl AdamsFov {
                                     Self::compute_octant(
                                         octant,
                                         origin,
-                                        range,
+                                        bounds,
                                         x + 1,
                                         *top,
                                         Slope { y: ny, x: nx },
@@ -306,3 +350,16 @@ impl AdamsFov {
         IVec2::new(nx, ny)
     }
 }
+
+/// The range limit shared by every octant.
+struct Bounds {
+    shape: FovShape,
+    range: u32,
+    extent: i32,
+}
+
+impl Bounds {
+    fn contains(&self, offset: IVec2) -> bool {
+        self.shape.contains(offset, self.range)
+    }
+}
@@ -0,0 +1,117 @@
+//! Explosions and other area effects that walls can stop.
+//!
+//! A fireball that hits every tile within its radius burns through walls.
+//! [`compute_blast`] spreads the effect with the same shadowcasting as
+//! field of view, so it reaches exactly the tiles that can be seen from its
+//! center, and scales each tile's intensity by a [`Falloff`].
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_fov::prelude::*;
+//!
+//! // A wall at x = 2 shields everything behind it
+//! let wall = |p: IVec2| p.x == 2 && p.y.abs() <= 3;
+//! let blast = compute_blast(IVec2::ZERO, 3, wall, Falloff::Linear);
+//!
+//! assert_eq!(blast[0], (IVec2::ZERO, 1.0));
+//! let hit = |p: IVec2| blast.iter().find(|(q, _)| *q == p).map(|&(_, i)| i);
+//! assert_eq!(hit(IVec2::new(-2, 0)), Some(0.5));
+//! assert!(hit(IVec2::new(2, 0)).is_some()); // the wall itself takes the hit
+//! assert_eq!(hit(IVec2::new(3, 0)), None);
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::harness::FnProvider;
+use crate::math;
+use crate::prelude::*;
+
+/// How a blast weakens away from its center.
+///
+/// Each function maps `t`, the distance from the center divided by
+/// `radius + 1`, to an intensity. `t` stays below 1 inside the blast, so the
+/// outermost ring still takes some of it.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Falloff {
+    /// Full intensity everywhere.
+    None,
+    /// Intensity drops evenly with distance.
+    #[default]
+    Linear,
+    /// Intensity stays high near the center and drops quickly at the edge.
+    Quadratic,
+    /// A custom curve from `t` to intensity.
+    Custom(fn(f32) -> f32),
+}
+
+impl Falloff {
+    /// Returns the intensity at `t`.
+    pub fn intensity(&self, t: f32) -> f32 {
+        match self {
+            Self::None => 1.0,
+            Self::Linear => 1.0 - t,
+            Self::Quadratic => 1.0 - t * t,
+            Self::Custom(curve) => curve(t),
+        }
+    }
+}
+
+/// Returns every tile an explosion at `origin` reaches, with its intensity,
+/// nearest first.
+///
+/// The blast fills a Euclidean circle of `radius` but is occluded like field
+/// of view: it doesn't pass tiles for which `is_blocking` returns true or
+/// squeeze through gaps of no width. Blocking tiles on its edge are included,
+/// so destructible walls can react.
+pub fn compute_blast(
+    origin: IVec2,
+    radius: u32,
+    is_blocking: impl Fn(IVec2) -> bool,
+    falloff: Falloff,
+) -> Vec<(IVec2, f32)> {
+    let mut provider = FnProvider(is_blocking);
+    let reached = Shadowcast::compute_fov(origin, radius, &mut provider, ());
+
+    let mut tiles: Vec<IVec2> = reached.into_iter().collect();
+    tiles.sort_by_key(|&p| ((p - origin).length_squared(), p.y, p.x));
+
+    let scale = radius as f32 + 1.0;
+    tiles
+        .into_iter()
+        .map(|p| {
+            let distance = math::sqrtf((p - origin).length_squared() as f32);
+            (p, falloff.intensity(distance / scale))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_blast_is_a_disc() {
+        let blast = compute_blast(IVec2::new(5, 5), 2, |_| false, Falloff::None);
+        assert_eq!(blast.len(), 13);
+        assert!(blast.iter().all(|&(_, i)| i == 1.0));
+        assert_eq!(
+            compute_blast(IVec2::ZERO, 0, |_| false, Falloff::Linear).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_walls_occlude_and_falloff_orders() {
+        // A closed 3x3 room
+        let room = |p: IVec2| p.x.abs() == 2 || p.y.abs() == 2;
+        let blast = compute_blast(IVec2::ZERO, 5, room, Falloff::Quadratic);
+        assert!(blast.iter().all(|(p, _)| p.x.abs() <= 2 && p.y.abs() <= 2));
+        assert_eq!(blast.len(), 25);
+
+        let intensities: Vec<f32> = blast.iter().map(|&(_, i)| i).collect();
+        assert!(intensities.windows(2).all(|w| w[0] >= w[1]));
+        let custom = Falloff::Custom(|t| if t < 0.5 { 2.0 } else { 0.0 });
+        assert_eq!(custom.intensity(0.2), 2.0);
+    }
+}
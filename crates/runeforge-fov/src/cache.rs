@@ -0,0 +1,390 @@
+//! Cached field of view for many entities.
+//!
+//! Recomputing FOV for every monster every turn is expensive, and most of
+//! them didn't move. [`FovCache`] keeps one [`VisibilitySet`] per entity and
+//! only recomputes it when the entity moved, its range changed, or opacity
+//! changed within its range ([`invalidate_at`](FovCache::invalidate_at)).
+//! With an [`OpacityGrid`], [`update_grid`](FovCache::update_grid) finds the
+//! opacity changes itself from the grid's revision counter.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_fov::prelude::*;
+//!
+//! struct Map;
+//! impl FovProvider<()> for Map {
+//!     fn is_opaque(&mut self, p: IVec2, _: &mut ()) -> bool {
+//!         p.x == 5
+//!     }
+//! }
+//!
+//! let mut cache = FovCache::new(Fov::Shadowcast);
+//! cache.update("orc", IVec2::new(0, 0), 8, &mut Map, ());
+//! cache.update("bat", IVec2::new(9, 0), 8, &mut Map, ());
+//!
+//! let player = IVec2::new(3, 1);
+//! let watchers: Vec<_> = cache.seen_by(player).collect();
+//! assert_eq!(watchers, vec![&"orc"]);
+//!
+//! // Nothing moved, so this is free.
+//! cache.update("orc", IVec2::new(0, 0), 8, &mut Map, ());
+//! assert_eq!(cache.recompute_count(), 2);
+//! ```
+
+use alloc::vec::Vec;
+use core::hash::Hash;
+
+use hashbrown::HashMap;
+
+use crate::prelude::*;
+
+/// The tiles visible from one origin, stored as a bitset over the square
+/// that the FOV range can reach.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VisibilitySet {
+    min: IVec2,
+    side: i32,
+    bits: Vec<u64>,
+    len: usize,
+}
+
+impl VisibilitySet {
+    /// Creates an empty set covering the square of `extent` tiles around `center`.
+    pub fn new(center: IVec2, extent: u32) -> Self {
+        let mut set = Self::default();
+        set.reset(center, extent);
+        set
+    }
+
+    /// Builds a set from visible positions.
+    ///
+    /// Positions further than `extent` tiles (on either axis) from `center` are ignored.
+    pub fn from_positions(
+        center: IVec2,
+        extent: u32,
+        positions: impl IntoIterator<Item = IVec2>,
+    ) -> Self {
+        let mut set = Self::new(center, extent);
+        positions.into_iter().for_each(|p| set.insert(p));
+        set
+    }
+
+    /// Clears the set and moves it to a new square, reusing its buffer.
+    pub fn reset(&mut self, center: IVec2, extent: u32) {
+        let extent = extent as i32;
+        self.min = center - IVec2::splat(extent);
+        self.side = extent * 2 + 1;
+        let words = (self.side as usize * self.side as usize).div_ceil(64);
+        self.bits.clear();
+        self.bits.resize(words, 0);
+        self.len = 0;
+    }
+
+    /// Marks a position as visible. Positions outside the square are ignored.
+    pub fn insert(&mut self, pos: IVec2) {
+        if let Some(idx) = self.index(pos) {
+            let (word, bit) = (idx / 64, idx % 64);
+            if self.bits[word] & (1 << bit) == 0 {
+                self.bits[word] |= 1 << bit;
+                self.len += 1;
+            }
+        }
+    }
+
+    /// Returns true if the position is visible.
+    pub fn contains(&self, pos: IVec2) -> bool {
+        self.index(pos)
+            .is_some_and(|idx| self.bits[idx / 64] & (1 << (idx % 64)) != 0)
+    }
+
+    /// Returns true if the position lies within the square this set covers.
+    pub fn covers(&self, pos: IVec2) -> bool {
+        self.index(pos).is_some()
+    }
+
+    /// Returns the corners (inclusive) of the square this set covers.
+    pub fn bounds(&self) -> (IVec2, IVec2) {
+        (self.min, self.min + IVec2::splat(self.side - 1))
+    }
+
+    /// Returns the number of visible positions.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if nothing is visible.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates over the visible positions in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = IVec2> + '_ {
+        let side = self.side.max(1) as usize;
+        self.bits.iter().enumerate().flat_map(move |(word, &bits)| {
+            (0..64)
+                .filter(move |bit| bits & (1 << bit) != 0)
+                .map(move |bit| {
+                    let idx = word * 64 + bit;
+                    self.min + IVec2::new((idx % side) as i32, (idx / side) as i32)
+                })
+        })
+    }
+
+    fn index(&self, pos: IVec2) -> Option<usize> {
+        let local = pos - self.min;
+        if local.x < 0 || local.y < 0 || local.x >= self.side || local.y >= self.side {
+            return None;
+        }
+        Some(local.y as usize * self.side as usize + local.x as usize)
+    }
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    origin: IVec2,
+    range: u32,
+    dirty: bool,
+    visible: VisibilitySet,
+}
+
+/// Per-entity FOV results that are only recomputed when needed.
+pub struct FovCache<K> {
+    fov: Fov,
+    shape: FovShape,
+    entries: HashMap<K, CacheEntry>,
+    /// Reused by every recompute, so updates don't allocate.
+    scratch: HashSet<IVec2>,
+    recomputes: usize,
+    /// Revision of the [`OpacityGrid`] the entries were last synced with.
+    grid_revision: Option<u64>,
+}
+
+impl<K: Hash + Eq + Clone> FovCache<K> {
+    /// Creates an empty cache using the given algorithm and a circular range.
+    pub fn new(fov: Fov) -> Self {
+        Self {
+            fov,
+            shape: FovShape::default(),
+            entries: HashMap::new(),
+            scratch: HashSet::new(),
+            recomputes: 0,
+            grid_revision: None,
+        }
+    }
+
+    /// Sets the range shape. Existing entries are recomputed on their next update.
+    pub fn with_shape(mut self, shape: impl Into<FovShape>) -> Self {
+        self.shape = shape.into();
+        self.invalidate_all();
+        self
+    }
+
+    /// Returns the FOV of `key` from `origin`, recomputing it only if the
+    /// entity is new, moved, changed range or was invalidated.
+    pub fn update<T>(
+        &mut self,
+        key: K,
+        origin: IVec2,
+        range: u32,
+        provider: &mut impl FovProvider<T>,
+        pass_through_data: T,
+    ) -> &VisibilitySet {
+        let entry = self.entries.entry(key).or_insert_with(|| CacheEntry {
+            origin,
+            range,
+            dirty: true,
+            visible: VisibilitySet::default(),
+        });
+        if entry.dirty || entry.origin != origin || entry.range != range {
+            self.fov.compute_with_shape_into(
+                origin,
+                range,
+                self.shape,
+                provider,
+                pass_through_data,
+                &mut self.scratch,
+            );
+            entry.visible.reset(origin, self.shape.extent(range));
+            self.scratch.iter().for_each(|&p| entry.visible.insert(p));
+            entry.origin = origin;
+            entry.range = range;
+            entry.dirty = false;
+            self.recomputes += 1;
+        }
+        &entry.visible
+    }
+
+    /// Like [`update`](Self::update), reading opacity from `grid`.
+    ///
+    /// Changes made to the grid since the last call invalidate the entities
+    /// whose range covers them, so no [`invalidate_at`](Self::invalidate_at)
+    /// calls are needed. Use one grid per cache.
+    pub fn update_grid(
+        &mut self,
+        key: K,
+        origin: IVec2,
+        range: u32,
+        grid: &OpacityGrid,
+    ) -> &VisibilitySet {
+        self.sync(grid);
+        self.update(key, origin, range, &mut &*grid, ())
+    }
+
+    /// Invalidates the entities affected by changes to `grid` since the last
+    /// sync. If the grid's change log doesn't reach back that far, or this
+    /// is the first sync, every entity is invalidated.
+    pub fn sync(&mut self, grid: &OpacityGrid) {
+        if self.grid_revision == Some(grid.revision()) {
+            return;
+        }
+        match self.grid_revision.and_then(|rev| grid.changes_since(rev)) {
+            Some(changes) => changes.for_each(|pos| self.invalidate_at(pos)),
+            None => self.invalidate_all(),
+        }
+        self.grid_revision = Some(grid.revision());
+    }
+
+    /// Returns the cached FOV of `key`, which may be stale if it was invalidated.
+    pub fn get(&self, key: &K) -> Option<&VisibilitySet> {
+        self.entries.get(key).map(|e| &e.visible)
+    }
+
+    /// Returns true if `key` was seeing `pos` when last updated.
+    pub fn can_see(&self, key: &K, pos: IVec2) -> bool {
+        self.get(key).is_some_and(|v| v.contains(pos))
+    }
+
+    /// Iterates over the entities that see `pos`.
+    pub fn seen_by(&self, pos: IVec2) -> impl Iterator<Item = &K> + '_ {
+        self.entries
+            .iter()
+            .filter(move |(_, e)| e.visible.contains(pos))
+            .map(|(k, _)| k)
+    }
+
+    /// Returns every position seen by at least one entity.
+    pub fn union(&self) -> HashSet<IVec2> {
+        self.entries
+            .values()
+            .flat_map(|e| e.visible.iter())
+            .collect()
+    }
+
+    /// Marks every entity whose range covers `pos` for recomputation.
+    ///
+    /// Call this when the opacity of `pos` changes (a door opens, a wall is dug).
+    pub fn invalidate_at(&mut self, pos: IVec2) {
+        self.entries
+            .values_mut()
+            .filter(|e| e.visible.covers(pos))
+            .for_each(|e| e.dirty = true);
+    }
+
+    /// Marks every entity for recomputation.
+    pub fn invalidate_all(&mut self) {
+        self.entries.values_mut().for_each(|e| e.dirty = true);
+    }
+
+    /// Forgets an entity. Returns true if it was cached.
+    pub fn remove(&mut self, key: &K) -> bool {
+        self.entries.remove(key).is_some()
+    }
+
+    /// Returns the number of cached entities.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if no entities are cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns how many FOV computations the cache has performed.
+    pub fn recompute_count(&self) -> usize {
+        self.recomputes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Walls(Vec<IVec2>);
+    impl FovProvider<()> for Walls {
+        fn is_opaque(&mut self, p: IVec2, _: &mut ()) -> bool {
+            self.0.contains(&p)
+        }
+    }
+
+    #[test]
+    fn test_visibility_set_matches_hash_set() {
+        let mut map = Walls(vec![IVec2::new(2, 0), IVec2::new(0, -3)]);
+        let origin = IVec2::new(1, 1);
+        let expected = Fov::Shadowcast.compute(origin, 5_u32, &mut map, ());
+        let set = VisibilitySet::from_positions(origin, 5, expected.iter().copied());
+        assert_eq!(set.len(), expected.len());
+        assert_eq!(set.iter().collect::<HashSet<_>>(), expected);
+        assert!(!set.contains(IVec2::new(100, 100)));
+    }
+
+    #[test]
+    fn test_only_recomputes_when_needed() {
+        let mut map = Walls(vec![]);
+        let mut cache = FovCache::new(Fov::Shadowcast);
+        cache.update(1, IVec2::ZERO, 4, &mut map, ());
+        cache.update(2, IVec2::new(20, 0), 4, &mut map, ());
+        cache.update(1, IVec2::ZERO, 4, &mut map, ());
+        assert_eq!(cache.recompute_count(), 2);
+
+        // A wall near entity 1 only invalidates entity 1.
+        map.0.push(IVec2::new(1, 0));
+        cache.invalidate_at(IVec2::new(1, 0));
+        cache.update(1, IVec2::ZERO, 4, &mut map, ());
+        cache.update(2, IVec2::new(20, 0), 4, &mut map, ());
+        assert_eq!(cache.recompute_count(), 3);
+        assert!(!cache.can_see(&1, IVec2::new(3, 0)));
+
+        cache.update(2, IVec2::new(21, 0), 4, &mut map, ());
+        assert_eq!(cache.recompute_count(), 4);
+    }
+
+    #[test]
+    fn test_grid_changes_invalidate_nearby_entities() {
+        let mut grid = OpacityGrid::new(40, 10);
+        let mut cache = FovCache::new(Fov::Shadowcast);
+        cache.update_grid(1, IVec2::new(2, 2), 4, &grid);
+        cache.update_grid(2, IVec2::new(30, 2), 4, &grid);
+        cache.update_grid(1, IVec2::new(2, 2), 4, &grid);
+        assert_eq!(cache.recompute_count(), 2);
+
+        grid.set(IVec2::new(3, 2));
+        cache.update_grid(1, IVec2::new(2, 2), 4, &grid);
+        cache.update_grid(2, IVec2::new(30, 2), 4, &grid);
+        assert_eq!(cache.recompute_count(), 3);
+        assert!(!cache.can_see(&1, IVec2::new(5, 2)));
+
+        grid.fill(false);
+        cache.update_grid(2, IVec2::new(30, 2), 4, &grid);
+        assert_eq!(cache.recompute_count(), 4);
+    }
+
+    #[test]
+    fn test_union_and_seen_by() {
+        let mut map = Walls(vec![]);
+        let mut cache = FovCache::new(Fov::Shadowcast).with_shape(FovShape::Metric(
+            runeforge_geometry::prelude::Distance::Chebyshev,
+        ));
+        cache.update('a', IVec2::ZERO, 1, &mut map, ());
+        cache.update('b', IVec2::new(2, 0), 1, &mut map, ());
+        assert_eq!(cache.union().len(), 15);
+
+        let mut watchers: Vec<_> = cache.seen_by(IVec2::new(1, 0)).copied().collect();
+        watchers.sort();
+        assert_eq!(watchers, vec!['a', 'b']);
+
+        assert!(cache.remove(&'a'));
+        assert_eq!(cache.seen_by(IVec2::new(1, 0)).count(), 1);
+    }
+}
@@ -0,0 +1,154 @@
+//! Text rendering of FOV results for docs, tests and error messages.
+//!
+//! [`FovDisplay`] draws the tiles in a rectangle, marking which are visible.
+//! [`VisibilitySet`] implements [`Display`](fmt::Display) directly.
+//!
+//! ```
+//! use runeforge_fov::prelude::*;
+//!
+//! let visible: HashSet<IVec2> = [IVec2::new(0, 0), IVec2::new(1, 0)].into_iter().collect();
+//! let text = FovDisplay::new(IVec2::ZERO, IVec2::new(2, 1), |p| visible.contains(&p))
+//!     .with_origin(IVec2::ZERO)
+//!     .with_opaque(|p| p.x == 1)
+//!     .to_string();
+//! assert_eq!(text, "@# \n   \n");
+//! ```
+
+use alloc::boxed::Box;
+use core::fmt;
+
+use crate::prelude::*;
+
+/// Glyphs used by [`FovDisplay`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FovFormat {
+    /// A visible tile that doesn't block sight
+    pub visible: char,
+    /// A visible tile that blocks sight
+    pub visible_wall: char,
+    /// A tile that isn't visible
+    pub hidden: char,
+    /// The viewer
+    pub origin: char,
+}
+
+impl Default for FovFormat {
+    fn default() -> Self {
+        Self {
+            visible: '.',
+            visible_wall: '#',
+            hidden: ' ',
+            origin: '@',
+        }
+    }
+}
+
+/// Renders the visible tiles in a rectangle, one line per row.
+pub struct FovDisplay<'a> {
+    min: IVec2,
+    max: IVec2,
+    is_visible: Box<dyn Fn(IVec2) -> bool + 'a>,
+    is_opaque: Option<Box<dyn Fn(IVec2) -> bool + 'a>>,
+    origin: Option<IVec2>,
+    format: FovFormat,
+}
+
+impl<'a> FovDisplay<'a> {
+    /// Creates a display of the tiles from `min` to `max` inclusive.
+    pub fn new(min: IVec2, max: IVec2, is_visible: impl Fn(IVec2) -> bool + 'a) -> Self {
+        Self {
+            min,
+            max,
+            is_visible: Box::new(is_visible),
+            is_opaque: None,
+            origin: None,
+            format: FovFormat::default(),
+        }
+    }
+
+    /// Draws visible opaque tiles with [`FovFormat::visible_wall`].
+    pub fn with_opaque(mut self, is_opaque: impl Fn(IVec2) -> bool + 'a) -> Self {
+        self.is_opaque = Some(Box::new(is_opaque));
+        self
+    }
+
+    /// Marks the viewer's position.
+    pub fn with_origin(mut self, origin: IVec2) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    /// Sets the glyphs.
+    pub fn with_format(mut self, format: FovFormat) -> Self {
+        self.format = format;
+        self
+    }
+}
+
+impl fmt::Display for FovDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in self.min.y..=self.max.y {
+            for x in self.min.x..=self.max.x {
+                let p = IVec2::new(x, y);
+                let glyph = if self.origin == Some(p) {
+                    self.format.origin
+                } else if !(self.is_visible)(p) {
+                    self.format.hidden
+                } else if self.is_opaque.as_ref().is_some_and(|opaque| opaque(p)) {
+                    self.format.visible_wall
+                } else {
+                    self.format.visible
+                };
+                write!(f, "{glyph}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for FovDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FovDisplay")
+            .field("min", &self.min)
+            .field("max", &self.max)
+            .field("origin", &self.origin)
+            .field("format", &self.format)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for VisibilitySet {
+    /// Renders the square the set covers with the default [`FovFormat`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (min, max) = self.bounds();
+        FovDisplay::new(min, max, |p| self.contains(p)).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visibility_set_display() {
+        let set = VisibilitySet::from_positions(
+            IVec2::new(5, 5),
+            1,
+            [IVec2::new(5, 5), IVec2::new(6, 4)],
+        );
+        assert_eq!(set.to_string(), "  .\n . \n   \n");
+    }
+
+    #[test]
+    fn test_custom_format() {
+        let format = FovFormat {
+            hidden: '?',
+            ..FovFormat::default()
+        };
+        let text = FovDisplay::new(IVec2::ZERO, IVec2::new(1, 0), |p| p.x == 0)
+            .with_format(format)
+            .to_string();
+        assert_eq!(text, ".?\n");
+    }
+}
@@ -23,10 +23,20 @@ pub enum Fov {
     /// Use the Shadowcast algorithm for computing field of view
     Shadowcast,
     /// Use the Shadowcast algorithm for computing field of view, but only in a single direction
+    ///
+    /// The direction is Y-up, as [`Direction::coord`] is: `NORTH` looks towards +Y. Use
+    /// [`Fov::facing`] to build one for a Y-down map.
     ShadowcastDirection(Direction),
 }
 
 impl Fov {
+    /// Creates a [`Fov::ShadowcastDirection`] looking towards `direction` in a map using
+    /// `system`, so `NORTH` looks up the screen on a Y-down map.
+    pub fn facing(direction: Direction, system: CoordinateSystem) -> Self {
+        // The quadrant walk reads directions as map offsets
+        Self::ShadowcastDirection(Direction::from_coord(direction.coord_in(system)))
+    }
+
     pub fn compute<FovRange: Into<u32>, T>(
         &self,
         origin: IVec2,
@@ -48,6 +58,70 @@ impl Fov {
         }
     }
 
+    /// Computes the field of view, limiting range with `shape` instead of a
+    /// Euclidean circle.
+    pub fn compute_with_shape<FovRange: Into<u32>, T>(
+        &self,
+        origin: IVec2,
+        range: FovRange,
+        shape: impl Into<FovShape>,
+        provider: &mut impl FovProvider<T>,
+        pass_through_data: T,
+    ) -> HashSet<IVec2> {
+        let mut visible = HashSet::new();
+        self.compute_with_shape_into(
+            origin,
+            range,
+            shape,
+            provider,
+            pass_through_data,
+            &mut visible,
+        );
+        visible
+    }
+
+    /// Like [`compute_with_shape`](Self::compute_with_shape), clearing and
+    /// refilling `visible` so its allocation can be reused between calls.
+    pub fn compute_with_shape_into<FovRange: Into<u32>, T>(
+        &self,
+        origin: IVec2,
+        range: FovRange,
+        shape: impl Into<FovShape>,
+        provider: &mut impl FovProvider<T>,
+        pass_through_data: T,
+        visible: &mut HashSet<IVec2>,
+    ) {
+        let range = range.into();
+        let shape = shape.into();
+        match self {
+            Self::Adams => AdamsFov::compute_with_shape_into(
+                origin,
+                range,
+                shape,
+                provider,
+                pass_through_data,
+                visible,
+            ),
+            Self::Shadowcast => Shadowcast::compute_with_shape_into(
+                origin,
+                range,
+                shape,
+                provider,
+                pass_through_data,
+                visible,
+            ),
+            Self::ShadowcastDirection(direction) => Shadowcast::compute_direction_with_shape_into(
+                origin,
+                range,
+                shape,
+                provider,
+                *direction,
+                pass_through_data,
+                visible,
+            ),
+        }
+    }
+
     pub fn within_fov<FovRange: Into<u32>, T>(
         &self,
         origin: IVec2,
@@ -121,6 +195,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn facing_follows_coordinate_system() {
+        let pos = IVec2::new(5, 5);
+        let up_screen = IVec2::new(5, 2);
+        let down_screen = IVec2::new(5, 8);
+
+        let fov = Fov::facing(Direction::NORTH, CoordinateSystem::YDown);
+        let visible = fov.compute(pos, 4_u32, &mut Provider, ());
+        assert!(visible.contains(&up_screen));
+        assert!(!visible.contains(&down_screen));
+
+        let fov = Fov::facing(Direction::NORTH, CoordinateSystem::YUp);
+        let visible = fov.compute(pos, 4_u32, &mut Provider, ());
+        assert!(visible.contains(&down_screen));
+        assert!(!visible.contains(&up_screen));
+    }
+
+    #[test]
+    fn compute_into_replaces_previous_contents() {
+        let shape = FovShape::default();
+        let mut visible = HashSet::new();
+        for fov in [
+            Fov::Adams,
+            Fov::Shadowcast,
+            Fov::ShadowcastDirection(Direction::EAST),
+        ] {
+            visible.insert(IVec2::new(-50, -50));
+            fov.compute_with_shape_into(
+                IVec2::new(5, 5),
+                4_u32,
+                shape,
+                &mut Provider,
+                (),
+                &mut visible,
+            );
+            let fresh = fov.compute_with_shape(IVec2::new(5, 5), 4_u32, shape, &mut Provider, ());
+            assert_eq!(visible, fresh);
+        }
+    }
+
     mod adams {
         use super::*;
 
@@ -0,0 +1,316 @@
+//! Invariant checks for field of view implementations.
+//!
+//! [`FovTestHarness`] runs an [`Fov`] algorithm against a blocking function and
+//! reports violations of properties every roguelike FOV should have:
+//!
+//! *   **Symmetry:** if floor tile A sees floor tile B, then B sees A.
+//! *   **Radius:** nothing farther than the range (Euclidean) is visible.
+//! *   **Open field:** with no obstacles, every tile within range is visible.
+//! *   **Room walls:** standing inside an empty rectangular room, every wall of
+//!     the room within range is visible (no missing wall "artifacts").
+//!
+//! The harness is exported so games with custom map representations can check
+//! their own `is_blocking` functions, not just the built-in algorithms.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_fov::harness::FovTestHarness;
+//! use runeforge_fov::prelude::*;
+//!
+//! let harness = FovTestHarness::new(Fov::Shadowcast).with_range(6);
+//!
+//! // Check a specific map...
+//! let pillar = |p: IVec2| p == IVec2::new(5, 5);
+//! assert!(harness.symmetry_violations(12, 12, pillar).is_empty());
+//!
+//! // ...or a batch of random ones.
+//! let report = harness.check_random_maps(5, 16, 16, 0.3, 42);
+//! assert!(report.radius.is_empty());
+//! assert!(report.open_field.is_empty());
+//! ```
+
+use crate::prelude::*;
+use alloc::vec::Vec;
+
+/// Violations found by [`FovTestHarness::check_random_maps`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FovReport {
+    /// Pairs `(a, b)` where `a` sees `b` but `b` doesn't see `a`
+    pub symmetry: Vec<(IVec2, IVec2)>,
+    /// Visible tiles outside the range, as `(origin, tile)`
+    pub radius: Vec<(IVec2, IVec2)>,
+    /// Tiles within range that were not visible in an open field
+    pub open_field: Vec<IVec2>,
+    /// Room wall tiles within range that were not visible
+    pub room_walls: Vec<IVec2>,
+}
+
+impl FovReport {
+    /// Returns true if no violations were found.
+    pub fn is_clean(&self) -> bool {
+        self.symmetry.is_empty()
+            && self.radius.is_empty()
+            && self.open_field.is_empty()
+            && self.room_walls.is_empty()
+    }
+}
+
+/// Checks FOV invariants against arbitrary blocking functions.
+pub struct FovTestHarness {
+    fov: Fov,
+    range: u32,
+}
+
+impl FovTestHarness {
+    /// Creates a harness for an algorithm with a default range of 8.
+    pub fn new(fov: Fov) -> Self {
+        Self { fov, range: 8 }
+    }
+
+    /// Sets the FOV range used for every check.
+    pub fn with_range(mut self, range: u32) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Returns the FOV range used for every check.
+    pub fn range(&self) -> u32 {
+        self.range
+    }
+
+    /// Computes the visible set from `origin`.
+    pub fn visible(&self, origin: IVec2, is_blocking: impl Fn(IVec2) -> bool) -> HashSet<IVec2> {
+        let mut provider = FnProvider(is_blocking);
+        self.fov.compute(origin, self.range, &mut provider, ())
+    }
+
+    /// Returns asymmetric pairs among the floor tiles of a `width` x `height` map.
+    pub fn symmetry_violations(
+        &self,
+        width: u32,
+        height: u32,
+        is_blocking: impl Fn(IVec2) -> bool,
+    ) -> Vec<(IVec2, IVec2)> {
+        let floors: Vec<IVec2> = grid(width, height).filter(|&p| !is_blocking(p)).collect();
+        let visible: hashbrown::HashMap<IVec2, HashSet<IVec2>> = floors
+            .iter()
+            .map(|&p| (p, self.visible(p, &is_blocking)))
+            .collect();
+
+        let mut violations = Vec::new();
+        for (&a, seen) in &visible {
+            for &b in seen {
+                if a == b {
+                    continue;
+                }
+                if let Some(back) = visible.get(&b) {
+                    if !back.contains(&a) {
+                        violations.push((a, b));
+                    }
+                }
+            }
+        }
+        violations.sort_by_key(|&(a, b)| (a.y, a.x, b.y, b.x));
+        violations
+    }
+
+    /// Returns visible tiles that lie outside the range from `origin`.
+    pub fn radius_violations(
+        &self,
+        origin: IVec2,
+        is_blocking: impl Fn(IVec2) -> bool,
+    ) -> Vec<IVec2> {
+        let max = self.range as i64 * self.range as i64;
+        let mut out: Vec<IVec2> = self
+            .visible(origin, is_blocking)
+            .into_iter()
+            .filter(|&p| distance_squared(origin, p) > max)
+            .collect();
+        out.sort_by_key(|p| (p.y, p.x));
+        out
+    }
+
+    /// Returns tiles within range that are not visible when nothing blocks.
+    pub fn open_field_violations(&self) -> Vec<IVec2> {
+        let origin = IVec2::ZERO;
+        let visible = self.visible(origin, |_| false);
+        let r = self.range as i32;
+        let max = self.range as i64 * self.range as i64;
+        let mut missing: Vec<IVec2> = (-r..=r)
+            .flat_map(|y| (-r..=r).map(move |x| IVec2::new(x, y)))
+            .filter(|&p| distance_squared(origin, p) <= max && !visible.contains(&p))
+            .collect();
+        missing.sort_by_key(|p| (p.y, p.x));
+        missing
+    }
+
+    /// Returns wall tiles of an empty `width` x `height` room (interior size)
+    /// that are within range of `origin` but not visible.
+    ///
+    /// The room interior spans `(1, 1)` to `(width, height)`, surrounded by walls.
+    pub fn room_wall_violations(&self, width: u32, height: u32, origin: IVec2) -> Vec<IVec2> {
+        let (w, h) = (width as i32, height as i32);
+        let is_wall = move |p: IVec2| p.x <= 0 || p.y <= 0 || p.x > w || p.y > h;
+        let visible = self.visible(origin, is_wall);
+        let max = self.range as i64 * self.range as i64;
+        let mut missing: Vec<IVec2> = grid(width + 2, height + 2)
+            .filter(|&p| is_wall(p))
+            // Corners can be legitimately hidden by the walls next to them.
+            .filter(|&p| !((p.x == 0 || p.x == w + 1) && (p.y == 0 || p.y == h + 1)))
+            .filter(|&p| distance_squared(origin, p) <= max && !visible.contains(&p))
+            .collect();
+        missing.sort_by_key(|p| (p.y, p.x));
+        missing
+    }
+
+    /// Runs every check on `count` random maps and collects the violations.
+    ///
+    /// Maps are `width` x `height` with roughly `wall_density` of the tiles
+    /// blocking; `seed` makes the maps reproducible.
+    pub fn check_random_maps(
+        &self,
+        count: u32,
+        width: u32,
+        height: u32,
+        wall_density: f32,
+        seed: u64,
+    ) -> FovReport {
+        let mut report = FovReport {
+            open_field: self.open_field_violations(),
+            ..FovReport::default()
+        };
+
+        let mut rng = SplitMix(seed);
+        for _ in 0..count {
+            let map = random_map(width, height, wall_density, &mut rng);
+            let is_blocking = |p: IVec2| {
+                p.x < 0
+                    || p.y < 0
+                    || p.x >= width as i32
+                    || p.y >= height as i32
+                    || map[(p.y as u32 * width + p.x as u32) as usize]
+            };
+            report
+                .symmetry
+                .extend(self.symmetry_violations(width, height, is_blocking));
+            for origin in grid(width, height).filter(|&p| !is_blocking(p)) {
+                let outside = self.radius_violations(origin, is_blocking);
+                report
+                    .radius
+                    .extend(outside.into_iter().map(|p| (origin, p)));
+            }
+
+            let room_w = 1 + (rng.next() % width.max(1) as u64) as u32;
+            let room_h = 1 + (rng.next() % height.max(1) as u64) as u32;
+            let origin = IVec2::new(
+                1 + (rng.next() % room_w as u64) as i32,
+                1 + (rng.next() % room_h as u64) as i32,
+            );
+            report
+                .room_walls
+                .extend(self.room_wall_violations(room_w, room_h, origin));
+        }
+        report
+    }
+}
+
+pub(crate) struct FnProvider<F>(pub(crate) F);
+
+impl<F: Fn(IVec2) -> bool> FovProvider<()> for FnProvider<F> {
+    fn is_opaque(&mut self, position: IVec2, _: &mut ()) -> bool {
+        (self.0)(position)
+    }
+}
+
+/// SplitMix64, kept local because this crate is `no_std` and
+/// `runeforge_random::splitmix` needs `std`.
+struct SplitMix(u64);
+
+impl SplitMix {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+fn random_map(width: u32, height: u32, wall_density: f32, rng: &mut SplitMix) -> Vec<bool> {
+    let threshold = (wall_density.clamp(0.0, 1.0) as f64 * u32::MAX as f64) as u64;
+    (0..width * height)
+        .map(|_| (rng.next() >> 32) < threshold)
+        .collect()
+}
+
+fn grid(width: u32, height: u32) -> impl Iterator<Item = IVec2> {
+    (0..height as i32).flat_map(move |y| (0..width as i32).map(move |x| IVec2::new(x, y)))
+}
+
+fn distance_squared(a: IVec2, b: IVec2) -> i64 {
+    let d = (a - b).as_i64vec2();
+    d.x * d.x + d.y * d.y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_shadowcast_is_clean() {
+        let harness = FovTestHarness::new(Fov::Shadowcast).with_range(6);
+        let report = harness.check_random_maps(4, 14, 14, 0.3, 7);
+        assert!(report.is_clean(), "{:?}", report);
+    }
+
+    #[test]
+    fn test_detects_broken_fov() {
+        let harness = FovTestHarness::new(Fov::Shadowcast).with_range(0);
+        assert!(harness.open_field_violations().is_empty());
+
+        // A directional cone only sees one side, so the full-circle checks fail.
+
+        let harness = FovTestHarness::new(Fov::ShadowcastDirection(Direction::NORTH));
+        assert!(!harness.open_field_violations().is_empty());
+        assert!(!harness
+            .room_wall_violations(5, 5, IVec2::new(3, 3))
+            .is_empty());
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn prop_shadowcast_symmetric(seed in any::<u64>(), density in 0.0f32..0.5) {
+            let harness = FovTestHarness::new(Fov::Shadowcast).with_range(5);
+            let mut rng = SplitMix(seed);
+            let map = random_map(10, 10, density, &mut rng);
+            let blocking = |p: IVec2| {
+                p.x < 0 || p.y < 0 || p.x >= 10 || p.y >= 10 || map[(p.y * 10 + p.x) as usize]
+            };
+            prop_assert!(harness.symmetry_violations(10, 10, blocking).is_empty());
+        }
+
+        #[test]
+        fn prop_radius_bounded(
+            range in 0u32..12,
+            x in -20i32..20,
+            y in -20i32..20,
+        ) {
+            for fov in [Fov::Shadowcast, Fov::Adams] {
+                let harness = FovTestHarness::new(fov).with_range(range);
+                let origin = IVec2::new(x, y);
+                prop_assert!(harness.radius_violations(origin, |p| (p.x + p.y) % 5 == 0 && p != origin).is_empty());
+            }
+        }
+
+        #[test]
+        fn prop_room_walls_visible(w in 1u32..10, h in 1u32..10, ox in 0u32..10, oy in 0u32..10) {
+            let origin = IVec2::new(1 + (ox % w) as i32, 1 + (oy % h) as i32);
+            let harness = FovTestHarness::new(Fov::Shadowcast).with_range(20);
+            prop_assert!(harness.room_wall_violations(w, h, origin).is_empty());
+        }
+    }
+}
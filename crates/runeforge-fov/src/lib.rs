@@ -11,6 +11,36 @@
 //! *   **Shadowcasting:** Recursive shadowcasting. Efficient and symmetric. Good for most use cases.
 //! *   **Adams:** An implementation of the Adams FOV algorithm.
 //!
+//! Range is a Euclidean circle by default; [`shape::FovShape`] selects a square,
+//! diamond or ellipse instead.
+//!
+//! [`cache::FovCache`] keeps FOV for many entities and only recomputes it when an
+//! entity moves or nearby opacity changes. [`opacity::OpacityGrid`] stores blockers
+//! as a bitset that FOV reads directly, and tells the cache what changed.
+//!
+//! For single "can A see B?" queries, [`los::line_of_sight`] and
+//! [`los::first_obstruction`] walk only the tiles between the two points.
+//!
+//! Projectiles use [`ray::cast_ray`], which follows the same tiles as line of sight so
+//! anything the shooter can see, it can hit.
+//!
+//! Explosions spread with [`blast::compute_blast`], which walls occlude just as they
+//! block sight.
+//!
+//! Stealth checks use [`stealth::visibility_score`], which grades how visible a
+//! target is from distance, light and partial cover instead of a yes/no FOV test.
+//!
+//! Spells and abilities pick their tiles with [`targeting::TargetShape`]: single
+//! target, beam, cone, smite or ball, built from the same rays, FOV and blasts.
+//!
+//! [`display::FovDisplay`] renders results as compact ASCII for docs, tests and
+//! error messages.
+//!
+//! # Testing
+//!
+//! [`harness::FovTestHarness`] checks symmetry, radius and wall-visibility invariants
+//! on random maps. Use it to validate custom `FovProvider` implementations.
+//!
 //! # Usage
 //!
 //! Add this to your `Cargo.toml`:
@@ -51,19 +81,51 @@
 //!     assert!(visible_cells.contains(&IVec2::new(1, 1)));
 //! }
 //! ```
+//!
+//! # `no_std`
+//!
+//! The crate is `no_std` + `alloc` when the default `std` feature is disabled,
+//! so the same FOV code can run on embedded targets.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
 pub mod adams;
+pub mod blast;
+pub mod cache;
+pub mod display;
 pub mod fov;
+pub mod harness;
+pub mod los;
+mod math;
+pub mod opacity;
+pub mod ray;
 pub mod shadowcast;
+pub mod shape;
 pub mod slope;
+pub mod stealth;
+pub mod targeting;
 
 pub mod prelude {
     pub use hashbrown::HashSet;
-    pub use runeforge_direction::prelude::{CardinalDirection, Direction, DirectionIterator};
+    pub use runeforge_direction::prelude::{
+        CardinalDirection, CoordinateSystem, Direction, DirectionIterator,
+    };
     pub use runeforge_geometry::prelude::IVec2;
 
     pub use crate::adams::*;
+    pub use crate::blast::*;
+    pub use crate::cache::*;
+    pub use crate::display::*;
     pub use crate::fov::*;
+    pub use crate::harness::{FovReport, FovTestHarness};
+    pub use crate::los::*;
+    pub use crate::opacity::*;
+    pub use crate::ray::*;
     pub use crate::shadowcast::*;
+    pub use crate::shape::*;
     pub use crate::slope::*;
+    pub use crate::stealth::*;
+    pub use crate::targeting::*;
 }
@@ -0,0 +1,235 @@
+//! Line of sight queries between two points.
+//!
+//! Computing a full field of view to answer a single "can A see B?" question
+//! is wasteful. [`line_of_sight`] follows only the slice of the shadowcast
+//! that contains the line between the two points, using the same rules as
+//! [`Shadowcast`](crate::shadowcast::Shadowcast):
+//!
+//! *   The line runs between tile centers; the end points never block.
+//! *   A wall shadows the slopes that pass through it, but not the slopes that
+//!     only touch its edge.
+//! *   A gap with no width (for example between two walls that touch
+//!     diagonally) doesn't let sight through.
+//!
+//! With these rules, a floor tile is in a shadowcast FOV (ignoring range) if and
+//! only if `line_of_sight` returns true, so the result is also symmetric.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_fov::prelude::*;
+//!
+//! let wall = IVec2::new(2, 0);
+//! let is_blocking = |p: IVec2| p == wall;
+//!
+//! assert!(!line_of_sight(IVec2::ZERO, IVec2::new(4, 0), is_blocking));
+//! assert!(line_of_sight(IVec2::ZERO, IVec2::new(4, 2), is_blocking));
+//! assert_eq!(
+//!     first_obstruction(IVec2::ZERO, IVec2::new(4, 0), is_blocking),
+//!     Some(wall)
+//! );
+//! ```
+
+use crate::prelude::*;
+
+/// Returns true if nothing between `a` and `b` blocks sight.
+///
+/// The tiles at `a` and `b` themselves are never checked.
+pub fn line_of_sight(a: IVec2, b: IVec2, is_blocking: impl FnMut(IVec2) -> bool) -> bool {
+    first_obstruction(a, b, is_blocking).is_none()
+}
+
+/// Returns the wall that blocks sight from `a` to `b`, if any.
+///
+/// This is the wall nearest the line in the first row (counted from `a`)
+/// where sight is lost. The tiles at `a` and `b` themselves are never
+/// returned.
+pub fn first_obstruction(
+    a: IVec2,
+    b: IVec2,
+    mut is_blocking: impl FnMut(IVec2) -> bool,
+) -> Option<IVec2> {
+    let delta = b - a;
+    if delta.x.abs() == delta.y.abs() && delta != IVec2::ZERO {
+        // Diagonals are covered by two quadrants; either one seeing is enough.
+        let x_major = scan_line(a, delta, true, &mut is_blocking)?;
+        scan_line(a, delta, false, &mut is_blocking).map(|_| x_major)
+    } else {
+        scan_line(a, delta, delta.x.abs() > delta.y.abs(), &mut is_blocking)
+    }
+}
+
+/// A slope `num / den` with a positive denominator.
+#[derive(Clone, Copy)]
+struct Ratio {
+    num: i64,
+    den: i64,
+}
+
+impl Ratio {
+    /// Slope through the near edge of the tile at `col`, `depth` (`slope` in
+    /// the shadowcasting article).
+    fn tile_edge(col: i64, depth: i64) -> Self {
+        Self {
+            num: 2 * col - 1,
+            den: 2 * depth,
+        }
+    }
+
+    fn le(self, other: Ratio) -> bool {
+        self.num * other.den <= other.num * self.den
+    }
+}
+
+/// Walks one quadrant of a shadowcast, following only the interval of slopes
+/// that contains the line to `delta`.
+fn scan_line(
+    a: IVec2,
+    delta: IVec2,
+    x_major: bool,
+    is_blocking: &mut impl FnMut(IVec2) -> bool,
+) -> Option<IVec2> {
+    let (major, minor) = if x_major {
+        (delta.x, delta.y)
+    } else {
+        (delta.y, delta.x)
+    };
+    let depth = major.abs() as i64;
+    let step = major.signum();
+    let to_world = |d: i64, col: i64| {
+        let (d, col) = (d as i32 * step, col as i32);
+        a + if x_major {
+            IVec2::new(d, col)
+        } else {
+            IVec2::new(col, d)
+        }
+    };
+
+    let target = Ratio {
+        num: minor as i64,
+        den: depth.max(1),
+    };
+    let contains = |start: Ratio, end: Ratio| start.le(target) && target.le(end);
+    let (mut start, mut end) = (Ratio { num: -1, den: 1 }, Ratio { num: 1, den: 1 });
+    let mut narrowed_by = None;
+
+    for d in 1..depth {
+        // Round ties up at the start and down at the end, as shadowcasting does.
+        let first = (2 * d * start.num + start.den).div_euclid(2 * start.den);
+        let last = -(-(2 * d * end.num - end.den)).div_euclid(2 * end.den);
+
+        let mut row_start = start;
+        let mut row_start_wall = narrowed_by;
+        let mut next = None;
+        let mut nearest_wall: Option<(i64, i64)> = None;
+        let mut prev_wall = None;
+        for col in first..=last {
+            let wall = is_blocking(to_world(d, col));
+            if wall {
+                let dist = (col * target.den - d * target.num).abs();
+                if nearest_wall.is_none_or(|(best, _)| dist < best) {
+                    nearest_wall = Some((dist, col));
+                }
+            }
+            match (prev_wall, wall) {
+                (Some(true), false) => {
+                    row_start = Ratio::tile_edge(col, d);
+                    row_start_wall = Some(to_world(d, col - 1));
+                }
+                (Some(false), true) => {
+                    let row_end = Ratio::tile_edge(col, d);
+                    if contains(row_start, row_end) {
+                        next = Some((row_start, row_end, Some(to_world(d, col))));
+                    }
+                }
+                _ => {}
+            }
+            prev_wall = Some(wall);
+        }
+        if prev_wall == Some(false) && contains(row_start, end) {
+            next = Some((row_start, end, row_start_wall));
+        }
+
+        match next {
+            Some((s, e, wall)) => {
+                start = s;
+                end = e;
+                narrowed_by = wall;
+            }
+            None => {
+                return nearest_wall
+                    .map(|(_, col)| to_world(d, col))
+                    .or(narrowed_by);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_same_and_adjacent_points() {
+        let all_walls = |_| true;
+        assert!(line_of_sight(IVec2::ZERO, IVec2::ZERO, all_walls));
+        assert!(line_of_sight(IVec2::ZERO, IVec2::new(1, 1), all_walls));
+        assert!(line_of_sight(IVec2::ZERO, IVec2::new(-1, 0), all_walls));
+        assert!(!line_of_sight(IVec2::ZERO, IVec2::new(-2, 0), all_walls));
+    }
+
+    #[test]
+    fn test_gap_between_walls() {
+        // The line to (2, 1) passes exactly between (1, 0) and (1, 1).
+        let one = |p: IVec2| p == IVec2::new(1, 0);
+        let both = |p: IVec2| p == IVec2::new(1, 0) || p == IVec2::new(1, 1);
+        assert!(line_of_sight(IVec2::ZERO, IVec2::new(2, 1), one));
+        assert_eq!(
+            first_obstruction(IVec2::ZERO, IVec2::new(2, 1), both),
+            Some(IVec2::new(1, 0))
+        );
+    }
+
+    #[test]
+    fn test_first_obstruction_is_closest() {
+        let walls = |p: IVec2| p.y == 0 && (p.x == -3 || p.x == -5);
+        assert_eq!(
+            first_obstruction(IVec2::ZERO, IVec2::new(-8, 0), walls),
+            Some(IVec2::new(-3, 0))
+        );
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(128))]
+
+        #[test]
+        fn prop_matches_shadowcast(
+            walls in proptest::collection::vec(proptest::bool::weighted(0.3), 121),
+            ox in 0i32..11,
+            oy in 0i32..11,
+        ) {
+            let blocking = |p: IVec2| {
+                p.x < 0 || p.y < 0 || p.x >= 11 || p.y >= 11 || walls[(p.y * 11 + p.x) as usize]
+            };
+            let origin = IVec2::new(ox, oy);
+            prop_assume!(!blocking(origin));
+
+            let mut provider = crate::harness::FnProvider(blocking);
+            let visible = Fov::Shadowcast.compute(origin, 20u32, &mut provider, ());
+            for y in 0..11 {
+                for x in 0..11 {
+                    let target = IVec2::new(x, y);
+                    if !blocking(target) {
+                        prop_assert_eq!(
+                            visible.contains(&target),
+                            line_of_sight(origin, target, blocking),
+                            "{:?} -> {:?}", origin, target
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! Float functions that fall back to `libm` without `std`.
+
+#[cfg(feature = "std")]
+pub(crate) fn mul_add(x: f64, a: f64, b: f64) -> f64 {
+    x.mul_add(a, b)
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn mul_add(x: f64, a: f64, b: f64) -> f64 {
+    libm::fma(x, a, b)
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn floor(x: f64) -> f64 {
+    x.floor()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn floor(x: f64) -> f64 {
+    libm::floor(x)
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn ceil(x: f64) -> f64 {
+    x.ceil()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn ceil(x: f64) -> f64 {
+    libm::ceil(x)
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn floorf(x: f32) -> f32 {
+    x.floor()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn floorf(x: f32) -> f32 {
+    libm::floorf(x)
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn sqrtf(x: f32) -> f32 {
+    x.sqrt()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn sqrtf(x: f32) -> f32 {
+    libm::sqrtf(x)
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn cosf(x: f32) -> f32 {
+    x.cos()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn cosf(x: f32) -> f32 {
+    libm::cosf(x)
+}
@@ -0,0 +1,234 @@
+//! A bitset of sight blockers that FOV reads directly.
+//!
+//! Most maps answer "is this tile opaque?" by looking at terrain, then at
+//! doors, then at features such as smoke, which is slow to repeat for every
+//! tile of every FOV. An [`OpacityGrid`] keeps the answer as one bit per
+//! tile instead. Update it from the tile and feature layers whenever a door
+//! opens or a wall is dug, and pass it straight to [`Fov::compute`] or
+//! [`FovCache::update_grid`].
+//!
+//! Every change bumps the grid's [`revision`](OpacityGrid::revision) and is
+//! logged, so a [`FovCache`] can tell exactly which entities a change
+//! affects instead of being told with
+//! [`invalidate_at`](FovCache::invalidate_at).
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_fov::prelude::*;
+//!
+//! // A wall across the map, with a closed door in it
+//! let mut grid = OpacityGrid::from_fn(10, 10, |p| p.x == 5);
+//! let mut cache = FovCache::new(Fov::Shadowcast);
+//! assert!(!cache.update_grid("player", IVec2::new(2, 5), 8, &grid).contains(IVec2::new(7, 5)));
+//!
+//! // Open the door: the player's view is recomputed on the next update
+//! grid.clear(IVec2::new(5, 5));
+//! assert!(cache.update_grid("player", IVec2::new(2, 5), 8, &grid).contains(IVec2::new(7, 5)));
+//! ```
+
+use alloc::collections::VecDeque;
+
+use runeforge_geometry::prelude::{BitGrid, GridStorage};
+
+use crate::prelude::*;
+
+/// How many changes an [`OpacityGrid`] remembers for
+/// [`changes_since`](OpacityGrid::changes_since).
+pub const OPACITY_CHANGE_LOG_LEN: usize = 1024;
+
+/// One bit per tile marking what blocks sight.
+///
+/// Positions outside the grid are opaque, so FOV stops at the map edge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpacityGrid {
+    blockers: BitGrid,
+    revision: u64,
+    /// `(revision, position)` of recent changes, oldest first.
+    changes: VecDeque<(u64, IVec2)>,
+}
+
+impl OpacityGrid {
+    /// Creates a grid with nothing blocking sight.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            blockers: BitGrid::new(width, height),
+            revision: 0,
+            changes: VecDeque::new(),
+        }
+    }
+
+    /// Creates a grid with `is_opaque` deciding every tile.
+    pub fn from_fn(width: u32, height: u32, mut is_opaque: impl FnMut(IVec2) -> bool) -> Self {
+        let mut grid = Self::new(width, height);
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                let pos = IVec2::new(x, y);
+                if is_opaque(pos) {
+                    grid.blockers.set(pos, 1);
+                }
+            }
+        }
+        grid
+    }
+
+    /// Returns the width in tiles.
+    pub fn width(&self) -> u32 {
+        self.blockers.width()
+    }
+
+    /// Returns the height in tiles.
+    pub fn height(&self) -> u32 {
+        self.blockers.height()
+    }
+
+    /// Returns true if `pos` is inside the grid.
+    pub fn in_bounds(&self, pos: IVec2) -> bool {
+        self.blockers.in_bounds(pos)
+    }
+
+    /// Returns true if `pos` blocks sight. Positions outside the grid do.
+    pub fn is_opaque(&self, pos: IVec2) -> bool {
+        self.blockers.get(pos).is_none_or(|v| v != 0)
+    }
+
+    /// Marks `pos` as blocking sight, e.g. when a door closes. Returns true
+    /// if it changed.
+    pub fn set(&mut self, pos: IVec2) -> bool {
+        self.set_opaque(pos, true)
+    }
+
+    /// Marks `pos` as letting sight through, e.g. when a door opens. Returns
+    /// true if it changed.
+    pub fn clear(&mut self, pos: IVec2) -> bool {
+        self.set_opaque(pos, false)
+    }
+
+    /// Sets whether `pos` blocks sight. Returns true if it changed; setting
+    /// a tile to what it already is, or a position outside the grid, does
+    /// not bump the revision.
+    pub fn set_opaque(&mut self, pos: IVec2, opaque: bool) -> bool {
+        if !self.in_bounds(pos) || Self::is_opaque(self, pos) == opaque {
+            return false;
+        }
+        self.blockers.set(pos, opaque as u8);
+        self.revision += 1;
+        if self.changes.len() == OPACITY_CHANGE_LOG_LEN {
+            self.changes.pop_front();
+        }
+        self.changes.push_back((self.revision, pos));
+        true
+    }
+
+    /// Sets every tile to `opaque`. This counts as one change to every
+    /// tile, so caches recompute everything.
+    pub fn fill(&mut self, opaque: bool) {
+        self.blockers.fill(opaque as u8);
+        self.revision += 1;
+        self.changes.clear();
+    }
+
+    /// Returns a counter that goes up with every change.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Returns the positions changed after `revision`, oldest first.
+    ///
+    /// Returns `None` if the log no longer reaches back that far (or
+    /// `revision` is from the future), in which case anything may have
+    /// changed.
+    pub fn changes_since(&self, revision: u64) -> Option<impl Iterator<Item = IVec2> + '_> {
+        if revision > self.revision {
+            return None;
+        }
+        if revision < self.revision {
+            let oldest = self.changes.front()?.0;
+            if oldest > revision + 1 {
+                return None;
+            }
+        }
+        Some(
+            self.changes
+                .iter()
+                .filter(move |(rev, _)| *rev > revision)
+                .map(|(_, pos)| *pos),
+        )
+    }
+
+    /// Returns the underlying bitset.
+    pub fn as_bit_grid(&self) -> &BitGrid {
+        &self.blockers
+    }
+}
+
+impl<T> FovProvider<T> for OpacityGrid {
+    fn is_opaque(&mut self, position: IVec2, _pass_through_data: &mut T) -> bool {
+        OpacityGrid::is_opaque(self, position)
+    }
+}
+
+impl<T> FovProvider<T> for &OpacityGrid {
+    fn is_opaque(&mut self, position: IVec2, _pass_through_data: &mut T) -> bool {
+        OpacityGrid::is_opaque(self, position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    #[test]
+    fn test_revision_only_counts_changes() {
+        let mut grid = OpacityGrid::new(4, 4);
+        assert!(!grid.is_opaque(IVec2::new(1, 1)));
+        assert!(grid.is_opaque(IVec2::new(-1, 0)));
+
+        assert!(grid.set(IVec2::new(1, 1)));
+        assert!(!grid.set(IVec2::new(1, 1)));
+        assert!(!grid.set(IVec2::new(9, 9)));
+        assert_eq!(grid.revision(), 1);
+
+        assert!(grid.clear(IVec2::new(1, 1)));
+        assert!(grid.set(IVec2::new(2, 3)));
+        let changes: Vec<IVec2> = grid.changes_since(1).unwrap().collect();
+        assert_eq!(changes, [IVec2::new(1, 1), IVec2::new(2, 3)]);
+        assert_eq!(grid.changes_since(3).unwrap().count(), 0);
+        assert!(grid.changes_since(4).is_none());
+
+        grid.fill(false);
+        assert!(grid.changes_since(3).is_none());
+    }
+
+    #[test]
+    fn test_change_log_is_bounded() {
+        let mut grid = OpacityGrid::new(64, 64);
+        for i in 0..OPACITY_CHANGE_LOG_LEN as i32 + 1 {
+            grid.set(IVec2::new(i % 64, i / 64));
+        }
+        assert!(grid.changes_since(0).is_none());
+        assert!(grid.changes_since(1).is_some());
+    }
+
+    #[test]
+    fn test_matches_closure_provider() {
+        let wall = |p: IVec2| p.x == 3 && p.y != 4;
+        let mut grid = OpacityGrid::from_fn(8, 8, wall);
+
+        struct Closure<F>(F);
+        impl<F: Fn(IVec2) -> bool> FovProvider<()> for Closure<F> {
+            fn is_opaque(&mut self, p: IVec2, _: &mut ()) -> bool {
+                !(0..8).contains(&p.x) || !(0..8).contains(&p.y) || (self.0)(p)
+            }
+        }
+
+        let origin = IVec2::new(1, 4);
+        let expected = Fov::Shadowcast.compute(origin, 6_u32, &mut Closure(wall), ());
+        assert_eq!(
+            Fov::Shadowcast.compute(origin, 6_u32, &mut grid, ()),
+            expected
+        );
+    }
+}
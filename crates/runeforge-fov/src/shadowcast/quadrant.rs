@@ -35,8 +35,9 @@ impl<'a, T> Quadrant<'a, T> {
         }
     }
 
-    pub const fn distance_squared(&self, tile: IVec2) -> u64 {
-        (tile.x * tile.x + tile.y * tile.y) as u64
+    // offset of this tile from the origin
+    pub const fn offset(&self, tile: IVec2) -> IVec2 {
+        self.transform(tile)
     }
 
     // mark this tile as visible
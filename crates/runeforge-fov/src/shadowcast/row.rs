@@ -1,3 +1,4 @@
+use crate::math;
 use crate::prelude::*;
 pub struct Row {
     depth: u32,
@@ -28,19 +29,24 @@ impl Row {
     }
 
     pub fn calc_ending_slope(&mut self, tile: IVec2) {
-        // Ray through the far-right corner of the tile
-        self.end_slope = Slope::new(2 * tile.y + 1, 2 * tile.x);
+        // Ray through the near-left corner of the blocking tile, matching
+        // the starting slope so shadows are symmetric
+        self.end_slope = Slope::new(2 * tile.y - 1, 2 * tile.x);
     }
 
     pub fn tiles(&self) -> RowIter {
         RowIter {
             depth: self.depth,
-            current_col: (self.depth as f64)
-                .mul_add(self.start_slope.value(), 0.5)
-                .floor() as i32, /* round up */
-            max_col: (self.depth as f64)
-                .mul_add(self.end_slope.value(), -0.5)
-                .ceil() as i32, // round down
+            current_col: math::floor(math::mul_add(
+                self.depth as f64,
+                self.start_slope.value(),
+                0.5,
+            )) as i32, /* round up */
+            max_col: math::ceil(math::mul_add(
+                self.depth as f64,
+                self.end_slope.value(),
+                -0.5,
+            )) as i32, // round down
         }
     }
 
@@ -0,0 +1,160 @@
+//! The shape of the area a field of view can reach.
+//!
+//! By default FOV range is a Euclidean circle. [`FovShape`] selects another
+//! metric from [`Distance`] (a Chebyshev square, a Manhattan diamond, ...) or an
+//! ellipse, for terminals whose cells are taller than they are wide. The shape
+//! is applied while scanning, so there's no need to filter the result.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_fov::prelude::*;
+//! use runeforge_geometry::prelude::Distance;
+//!
+//! struct Open;
+//! impl FovProvider<()> for Open {
+//!     fn is_opaque(&mut self, _: IVec2, _: &mut ()) -> bool {
+//!         false
+//!     }
+//! }
+//!
+//! let square = Fov::Shadowcast.compute_with_shape(IVec2::ZERO, 2_u32, Distance::Chebyshev, &mut Open, ());
+//! assert_eq!(square.len(), 25);
+//!
+//! // Twice as wide as it is tall.
+//! let ellipse = FovShape::Ellipse { aspect: 2.0 };
+//! let visible = Fov::Shadowcast.compute_with_shape(IVec2::ZERO, 2_u32, ellipse, &mut Open, ());
+//! assert!(visible.contains(&IVec2::new(4, 0)));
+//! assert!(!visible.contains(&IVec2::new(0, 3)));
+//! ```
+
+use runeforge_geometry::prelude::Distance;
+
+use crate::math;
+use crate::prelude::*;
+
+/// Which tiles count as within range of the origin.
+#[derive(Debug, Clone, Copy)]
+pub enum FovShape {
+    /// Tiles whose distance from the origin is at most the range.
+    ///
+    /// `Distance::PythagorasSquared` is treated like `Distance::Pythagoras`,
+    /// so the range is always a radius.
+    Metric(Distance),
+    /// An ellipse whose horizontal radius is `aspect` times the range and
+    /// whose vertical radius is the range.
+    Ellipse {
+        /// Width to height ratio; values at or below zero are treated as 1
+        aspect: f32,
+    },
+}
+
+impl Default for FovShape {
+    fn default() -> Self {
+        Self::Metric(Distance::Pythagoras)
+    }
+}
+
+impl From<Distance> for FovShape {
+    fn from(distance: Distance) -> Self {
+        Self::Metric(distance)
+    }
+}
+
+impl FovShape {
+    /// Returns true if a tile at `offset` from the origin is within `range`.
+    pub fn contains(&self, offset: IVec2, range: u32) -> bool {
+        let (dx, dy) = (
+            offset.x.unsigned_abs() as u64,
+            offset.y.unsigned_abs() as u64,
+        );
+        let r = range as u64;
+        match *self {
+            Self::Metric(Distance::Pythagoras | Distance::PythagorasSquared) => {
+                dx * dx + dy * dy <= r * r
+            }
+            Self::Metric(Distance::Manhattan) => dx + dy <= r,
+            Self::Metric(Distance::Chebyshev) => dx.max(dy) <= r,
+            Self::Metric(distance) => distance.distance2d(IVec2::ZERO, offset) <= range as f32,
+            Self::Ellipse { aspect } => {
+                let aspect = Self::aspect(aspect) as f64;
+                let x = dx as f64 / aspect;
+                x * x + (dy * dy) as f64 <= (r * r) as f64
+            }
+        }
+    }
+
+    /// Returns the largest distance along either axis that [`contains`](Self::contains)
+    /// can accept for `range`.
+    pub fn extent(&self, range: u32) -> u32 {
+        match *self {
+            Self::Metric(Distance::DiagonalWithCosts(cardinal, diagonal)) => {
+                let step = cardinal.min(diagonal);
+                if step > 0.0 {
+                    math::floorf(range as f32 / step) as u32
+                } else {
+                    range
+                }
+            }
+            Self::Metric(_) => range,
+            Self::Ellipse { aspect } => {
+                math::floorf(range as f32 * Self::aspect(aspect).max(1.0)) as u32
+            }
+        }
+    }
+
+    fn aspect(aspect: f32) -> f32 {
+        if aspect > 0.0 {
+            aspect
+        } else {
+            1.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Open;
+    impl FovProvider<()> for Open {
+        fn is_opaque(&mut self, _: IVec2, _: &mut ()) -> bool {
+            false
+        }
+    }
+
+    fn count(fov: &Fov, shape: impl Into<FovShape>) -> usize {
+        fov.compute_with_shape(IVec2::new(3, -2), 4_u32, shape, &mut Open, ())
+            .len()
+    }
+
+    #[test]
+    fn test_metric_shapes_in_open_field() {
+        for fov in [Fov::Shadowcast, Fov::Adams] {
+            assert_eq!(count(&fov, Distance::Chebyshev), 81);
+            assert_eq!(count(&fov, Distance::Manhattan), 41);
+            assert_eq!(count(&fov, FovShape::default()), 49);
+        }
+    }
+
+    #[test]
+    fn test_ellipse_reaches_further_horizontally() {
+        let shape = FovShape::Ellipse { aspect: 2.0 };
+        assert_eq!(shape.extent(4), 8);
+        assert!(shape.contains(IVec2::new(-8, 0), 4));
+        assert!(!shape.contains(IVec2::new(0, 5), 4));
+
+        let visible = Fov::Adams.compute_with_shape(IVec2::ZERO, 4_u32, shape, &mut Open, ());
+        assert!(visible.contains(&IVec2::new(8, 0)));
+        assert!(visible.contains(&IVec2::new(0, -4)));
+        assert!(!visible.contains(&IVec2::new(0, 5)));
+    }
+
+    #[test]
+    fn test_diagonal_extent() {
+        let shape = FovShape::Metric(Distance::DiagonalWithCosts(0.5, 1.0));
+        assert_eq!(shape.extent(3), 6);
+        assert!(shape.contains(IVec2::new(6, 0), 3));
+        assert_eq!(FovShape::from(Distance::Diagonal).extent(3), 3);
+    }
+}
@@ -0,0 +1,220 @@
+//! Graded visibility for stealth.
+//!
+//! Field of view answers "could A see B if B were there?". Stealth needs
+//! "how easy is B to spot?": a thief in a dark corner behind a crate should
+//! be harder to notice than one standing in torchlight. [`visibility_score`]
+//! combines three factors into a score from 0.0 (unseen) to 1.0 (in plain
+//! sight):
+//!
+//! *   **Distance:** falls off linearly to 0 at the model's range.
+//! *   **Light:** the light level on the target's tile, from a [`LightMap`].
+//! *   **Cover:** each tile between the two, and the target's own tile,
+//!     hides part of the target. Tiles with full cover block sight, using
+//!     the same rules as [`line_of_sight`].
+//!
+//! Games typically compare the score against a perception roll.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_fov::prelude::*;
+//!
+//! // A torch lights everything within 3 tiles of (0, 0)
+//! let light = |p: IVec2| if p.length_squared() <= 9 { 1.0 } else { 0.2 };
+//! // Tall grass at (4, 0) hides half of whatever is behind it
+//! let cover = |p: IVec2| if p == IVec2::new(4, 0) { 0.5 } else { 0.0 };
+//!
+//! let guard = IVec2::ZERO;
+//! let lit = visibility_score(guard, IVec2::new(2, 0), &light, cover);
+//! let hidden = visibility_score(guard, IVec2::new(5, 0), &light, cover);
+//! assert!(lit > 0.8);
+//! assert!(hidden < 0.1);
+//! ```
+
+use runeforge_geometry::prelude::Line;
+
+use crate::prelude::*;
+
+/// Light levels for [`visibility_score`], from 0.0 (pitch dark) to 1.0
+/// (fully lit).
+///
+/// Implemented for closures, so the output of a lighting pass can be passed
+/// as e.g. `|p: IVec2| light_grid[p.y as usize][p.x as usize]`.
+pub trait LightMap {
+    /// Returns the light level at `pos`.
+    fn light_at(&self, pos: IVec2) -> f32;
+}
+
+impl<F: Fn(IVec2) -> f32> LightMap for F {
+    fn light_at(&self, pos: IVec2) -> f32 {
+        self(pos)
+    }
+}
+
+/// How much each factor reduced a target's visibility, each from 0.0 to 1.0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VisibilityFactors {
+    /// 1.0 next to the observer, 0.0 at the edge of its range.
+    pub distance: f32,
+    /// Light on the target, after the observer's dark vision.
+    pub light: f32,
+    /// Fraction of the target not hidden by cover; 0.0 if sight is blocked.
+    pub cover: f32,
+}
+
+impl VisibilityFactors {
+    /// Returns the combined score, the product of the factors.
+    pub fn score(&self) -> f32 {
+        self.distance * self.light * self.cover
+    }
+}
+
+/// Tuning for [`VisibilityModel::score`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VisibilityModel {
+    /// Distance (Euclidean) at which nothing can be seen.
+    pub range: f32,
+    /// Light level the observer sees by even in darkness, e.g. 0.5 for a
+    /// creature with dark vision.
+    pub dark_vision: f32,
+}
+
+impl Default for VisibilityModel {
+    fn default() -> Self {
+        Self {
+            range: 12.0,
+            dark_vision: 0.0,
+        }
+    }
+}
+
+impl VisibilityModel {
+    /// Creates a model with the given range and no dark vision.
+    pub fn new(range: f32) -> Self {
+        Self {
+            range,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the light level the observer sees by in darkness.
+    pub fn with_dark_vision(mut self, dark_vision: f32) -> Self {
+        self.dark_vision = dark_vision.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Returns how visible `target` is to `observer`, from 0.0 to 1.0.
+    ///
+    /// `cover` returns how much of what's behind a tile it hides, from 0.0
+    /// (open floor) to 1.0 (a wall).
+    pub fn score(
+        &self,
+        observer: IVec2,
+        target: IVec2,
+        light: &impl LightMap,
+        cover: impl FnMut(IVec2) -> f32,
+    ) -> f32 {
+        self.factors(observer, target, light, cover).score()
+    }
+
+    /// Returns the factors behind [`score`](Self::score), e.g. to tell the
+    /// player why they were spotted.
+    pub fn factors(
+        &self,
+        observer: IVec2,
+        target: IVec2,
+        light: &impl LightMap,
+        mut cover: impl FnMut(IVec2) -> f32,
+    ) -> VisibilityFactors {
+        let distance = if self.range > 0.0 {
+            (1.0 - (target - observer).as_vec2().length() / self.range).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let light = light.light_at(target).clamp(0.0, 1.0).max(self.dark_vision);
+
+        let cover = if observer == target {
+            1.0
+        } else if !line_of_sight(observer, target, |p| cover(p) >= 1.0) {
+            0.0
+        } else {
+            // The target's own tile counts too: grass hides whoever is in it
+            Line::new(observer, target)
+                .into_iter()
+                .skip(1)
+                .map(|p| 1.0 - cover(p).clamp(0.0, 1.0))
+                .product()
+        };
+
+        VisibilityFactors {
+            distance,
+            light,
+            cover,
+        }
+    }
+}
+
+/// Returns how visible `target` is to `observer`, from 0.0 to 1.0, using
+/// the default [`VisibilityModel`].
+///
+/// `light` gives the light level on each tile and `cover` how much of what's
+/// behind a tile it hides, both from 0.0 to 1.0.
+pub fn visibility_score(
+    observer: IVec2,
+    target: IVec2,
+    light: &impl LightMap,
+    cover: impl FnMut(IVec2) -> f32,
+) -> f32 {
+    VisibilityModel::default().score(observer, target, light, cover)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lit(_: IVec2) -> f32 {
+        1.0
+    }
+
+    fn open(_: IVec2) -> f32 {
+        0.0
+    }
+
+    #[test]
+    fn test_distance_falls_off() {
+        let model = VisibilityModel::new(10.0);
+        let near = model.score(IVec2::ZERO, IVec2::new(1, 0), &lit, open);
+        let far = model.score(IVec2::ZERO, IVec2::new(5, 0), &lit, open);
+        assert!((near - 0.9).abs() < 1e-5);
+        assert!((far - 0.5).abs() < 1e-5);
+        assert_eq!(model.score(IVec2::ZERO, IVec2::new(10, 0), &lit, open), 0.0);
+    }
+
+    #[test]
+    fn test_darkness_and_dark_vision() {
+        let dark = |_: IVec2| 0.0;
+        let model = VisibilityModel::new(10.0);
+        assert_eq!(model.score(IVec2::ZERO, IVec2::new(1, 0), &dark, open), 0.0);
+
+        let model = model.with_dark_vision(0.5);
+        let factors = model.factors(IVec2::ZERO, IVec2::new(1, 0), &dark, open);
+        assert_eq!(factors.light, 0.5);
+        assert!((factors.score() - 0.45).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_cover_stacks_and_walls_block() {
+        let model = VisibilityModel::new(100.0);
+        let target = IVec2::new(4, 0);
+        let bush = |p: IVec2| if p.x == 2 || p.x == 4 { 0.5 } else { 0.0 };
+        let factors = model.factors(IVec2::ZERO, target, &lit, bush);
+        assert!((factors.cover - 0.25).abs() < 1e-5);
+
+        let wall = |p: IVec2| if p.x == 2 { 1.0 } else { 0.0 };
+        assert_eq!(model.score(IVec2::ZERO, target, &lit, wall), 0.0);
+
+        // The observer's own tile doesn't hide the target
+        let at_observer = |p: IVec2| if p == IVec2::ZERO { 1.0 } else { 0.0 };
+        assert!(model.score(IVec2::ZERO, target, &lit, at_observer) > 0.9);
+    }
+}
@@ -0,0 +1,313 @@
+//! Area templates for spells and abilities.
+//!
+//! A [`TargetShape`] describes how an ability picks its tiles: a bolt that
+//! hits one target, a beam through everything up to a wall, a cone, a smite
+//! that lands anywhere in view, or a ball that bursts where its bolt stops.
+//! [`TargetShape::resolve`] turns a caster position and an aimed tile into a
+//! [`TargetArea`] using the same line of sight, rays and blasts as the rest
+//! of the crate, so what the player is shown while aiming is what the spell
+//! will hit.
+//!
+//! The result carries both the path the effect travels and the tiles it
+//! affects, ready to draw as a targeting overlay.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_fov::prelude::*;
+//!
+//! let wall = |p: IVec2| p.x == 5;
+//!
+//! // A fireball aimed behind the wall bursts against it
+//! let area = TargetShape::Ball { range: 8, radius: 1 }.resolve(IVec2::ZERO, IVec2::new(7, 0), wall);
+//! assert!(!area.reaches_target);
+//! assert_eq!(area.impact, IVec2::new(4, 0));
+//! assert!(area.contains(IVec2::new(3, 0)));
+//! assert!(!area.contains(IVec2::new(6, 0)));
+//!
+//! // A beam fries everything up to and including the wall
+//! let area = TargetShape::Beam { range: 8 }.resolve(IVec2::ZERO, IVec2::new(2, 0), wall);
+//! assert_eq!(area.affected.len(), 5);
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::blast::{compute_blast, Falloff};
+use crate::harness::FnProvider;
+use crate::math;
+use crate::prelude::*;
+
+/// How an ability chooses the tiles it affects.
+///
+/// Ranges are Euclidean, in tiles from the caster.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TargetShape {
+    /// A bolt that hits the aimed tile, or the first obstruction before it.
+    Single {
+        /// How far the bolt flies.
+        range: u32,
+    },
+    /// Every tile along a line through the aimed tile, up to and including
+    /// the first obstruction.
+    Beam {
+        /// How far the beam reaches.
+        range: u32,
+    },
+    /// Every tile in view within `range` whose direction from the caster is
+    /// within `angle / 2` of the aimed direction.
+    Cone {
+        /// How far the cone reaches.
+        range: u32,
+        /// Full opening angle in degrees.
+        angle: f32,
+    },
+    /// The aimed tile, if the caster can see it.
+    Smite {
+        /// How far away the caster can smite.
+        range: u32,
+    },
+    /// A bolt that bursts into a blast of `radius` where it stops.
+    Ball {
+        /// How far the bolt flies.
+        range: u32,
+        /// Radius of the burst.
+        radius: u32,
+    },
+}
+
+/// The tiles an ability would affect.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TargetArea {
+    /// Tiles the effect travels through to get to its impact, excluding the
+    /// caster. Empty for cones and smites.
+    pub path: Vec<IVec2>,
+    /// Where the effect lands: the aimed tile, or where it was stopped.
+    pub impact: IVec2,
+    /// Affected tiles, nearest to the impact (or caster) first.
+    pub affected: Vec<IVec2>,
+    /// True if the effect gets to the aimed tile. Useful for greying out
+    /// the cursor over a target that can't be reached.
+    pub reaches_target: bool,
+}
+
+impl TargetArea {
+    /// Returns true if `pos` is affected.
+    pub fn contains(&self, pos: IVec2) -> bool {
+        self.affected.contains(&pos)
+    }
+
+    /// Returns true if nothing is affected.
+    pub fn is_empty(&self) -> bool {
+        self.affected.is_empty()
+    }
+}
+
+impl TargetShape {
+    /// Returns the range of the shape.
+    pub fn range(&self) -> u32 {
+        match *self {
+            Self::Single { range }
+            | Self::Beam { range }
+            | Self::Cone { range, .. }
+            | Self::Smite { range }
+            | Self::Ball { range, .. } => range,
+        }
+    }
+
+    /// Resolves the shape cast from `origin` at `target`, with tiles for
+    /// which `is_blocking` returns true stopping bolts and beams and
+    /// occluding cones and balls.
+    ///
+    /// Aiming at the caster's own tile affects only that tile for single
+    /// targets and smites, centers a ball on the caster, and affects nothing
+    /// for beams and cones, which have no direction.
+    pub fn resolve(
+        &self,
+        origin: IVec2,
+        target: IVec2,
+        is_blocking: impl Fn(IVec2) -> bool,
+    ) -> TargetArea {
+        match *self {
+            Self::Single { range } => {
+                let (path, impact, reaches_target) = bolt(origin, target, range, &is_blocking);
+                TargetArea {
+                    path,
+                    impact,
+                    affected: alloc::vec![impact],
+                    reaches_target,
+                }
+            }
+            Self::Beam { range } => {
+                let hit = cast_ray(origin, target, range, &is_blocking);
+                let mut affected = hit.path.clone();
+                if hit.blocked {
+                    affected.push(hit.hit_point);
+                }
+                TargetArea {
+                    reaches_target: affected.contains(&target),
+                    path: hit.path,
+                    impact: hit.hit_point,
+                    affected,
+                }
+            }
+            Self::Cone { range, angle } => {
+                let affected = cone(origin, target, range, angle, is_blocking);
+                TargetArea {
+                    path: Vec::new(),
+                    impact: target,
+                    reaches_target: affected.contains(&target),
+                    affected,
+                }
+            }
+            Self::Smite { range } => {
+                let mut provider = FnProvider(is_blocking);
+                let visible = Shadowcast::compute_fov(origin, range, &mut provider, ());
+                let reaches_target = visible.contains(&target);
+                TargetArea {
+                    path: Vec::new(),
+                    impact: target,
+                    affected: if reaches_target {
+                        alloc::vec![target]
+                    } else {
+                        Vec::new()
+                    },
+                    reaches_target,
+                }
+            }
+            Self::Ball { range, radius } => {
+                let (path, mut impact, reaches_target) = bolt(origin, target, range, &is_blocking);
+                // Burst in front of whatever stopped the bolt, not inside it
+                if is_blocking(impact) && impact != origin {
+                    impact = path
+                        .iter()
+                        .rev()
+                        .copied()
+                        .find(|&p| p != impact)
+                        .unwrap_or(origin);
+                }
+                let affected = compute_blast(impact, radius, &is_blocking, Falloff::None)
+                    .into_iter()
+                    .map(|(p, _)| p)
+                    .collect();
+                TargetArea {
+                    path,
+                    impact,
+                    affected,
+                    reaches_target,
+                }
+            }
+        }
+    }
+}
+
+/// Flies a bolt toward `target`, returning its path (ending at the impact),
+/// the impact and whether it got there.
+fn bolt(
+    origin: IVec2,
+    target: IVec2,
+    range: u32,
+    is_blocking: &impl Fn(IVec2) -> bool,
+) -> (Vec<IVec2>, IVec2, bool) {
+    if origin == target {
+        return (Vec::new(), origin, true);
+    }
+    let hit = cast_ray(origin, target, range, is_blocking);
+    let mut path = hit.path;
+    if let Some(idx) = path.iter().position(|&p| p == target) {
+        path.truncate(idx + 1);
+        return (path, target, true);
+    }
+    if hit.blocked {
+        path.push(hit.hit_point);
+    }
+    (path, hit.hit_point, hit.blocked && hit.hit_point == target)
+}
+
+fn cone(
+    origin: IVec2,
+    target: IVec2,
+    range: u32,
+    angle: f32,
+    is_blocking: impl Fn(IVec2) -> bool,
+) -> Vec<IVec2> {
+    if target == origin {
+        return Vec::new();
+    }
+    let aim = (target - origin).as_vec2();
+    let min_cos = math::cosf(angle.to_radians() / 2.0);
+    let aim_len = math::sqrtf(aim.length_squared());
+
+    let mut provider = FnProvider(is_blocking);
+    let visible = Shadowcast::compute_fov(origin, range, &mut provider, ());
+    let mut tiles: Vec<IVec2> = visible
+        .into_iter()
+        .filter(|&p| {
+            let offset = (p - origin).as_vec2();
+            let len = math::sqrtf(offset.length_squared());
+            // A small tolerance keeps tiles exactly on the edge in.
+            p != origin && offset.dot(aim) >= min_cos * len * aim_len - 1e-4
+        })
+        .collect();
+    tiles.sort_by_key(|&p| ((p - origin).length_squared(), p.y, p.x));
+    tiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_stops_at_target_or_wall() {
+        let wall = |p: IVec2| p == IVec2::new(3, 0);
+        let shape = TargetShape::Single { range: 10 };
+        let area = shape.resolve(IVec2::ZERO, IVec2::new(2, 0), wall);
+        assert!(area.reaches_target);
+        assert_eq!(area.path, vec![IVec2::new(1, 0), IVec2::new(2, 0)]);
+        assert_eq!(area.affected, vec![IVec2::new(2, 0)]);
+
+        let area = shape.resolve(IVec2::ZERO, IVec2::new(6, 0), wall);
+        assert!(!area.reaches_target);
+        assert_eq!(area.impact, IVec2::new(3, 0));
+
+        let short = TargetShape::Single { range: 2 }.resolve(IVec2::ZERO, IVec2::new(6, 0), wall);
+        assert_eq!(short.impact, IVec2::new(2, 0));
+        assert!(!short.reaches_target);
+    }
+
+    #[test]
+    fn test_cone_angle() {
+        let open = |_| false;
+        let narrow = TargetShape::Cone {
+            range: 4,
+            angle: 0.0,
+        };
+        let area = narrow.resolve(IVec2::ZERO, IVec2::new(0, 2), open);
+        assert_eq!(area.affected.len(), 4);
+        assert!(area.affected.iter().all(|p| p.x == 0 && p.y > 0));
+
+        let wide = TargetShape::Cone {
+            range: 3,
+            angle: 90.0,
+        };
+        let area = wide.resolve(IVec2::ZERO, IVec2::new(1, 0), open);
+        assert!(area.contains(IVec2::new(2, 2)));
+        assert!(area.contains(IVec2::new(2, -2)));
+        assert!(!area.contains(IVec2::new(1, 2)));
+        assert!(!area.contains(IVec2::new(-1, 0)));
+        assert!(area.reaches_target);
+    }
+
+    #[test]
+    fn test_smite_needs_sight() {
+        let pillar = |p: IVec2| p == IVec2::new(2, 0);
+        let smite = TargetShape::Smite { range: 6 };
+        assert!(
+            smite
+                .resolve(IVec2::ZERO, IVec2::new(4, 1), pillar)
+                .reaches_target
+        );
+        let hidden = smite.resolve(IVec2::ZERO, IVec2::new(4, 0), pillar);
+        assert!(!hidden.reaches_target);
+        assert!(hidden.is_empty());
+    }
+}
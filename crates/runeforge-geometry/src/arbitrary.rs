@@ -0,0 +1,73 @@
+//! [`proptest`] strategies for geometry types.
+//!
+//! Enabled by the `proptest` feature, so other crates' property tests can
+//! draw the same points, rectangles and directions:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! runeforge-geometry = { version = "0.1", features = ["proptest"] }
+//! ```
+//!
+//! Coordinates stay within [`COORD_LIMIT`] and sizes within
+//! [`SIZE_LIMIT`], so arithmetic on generated values can't overflow and
+//! iterating a generated shape stays quick.
+//!
+//! # Example
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use runeforge_geometry::arbitrary;
+//! use runeforge_geometry::prelude::*;
+//!
+//! proptest! {
+//!     fn step_and_back(p in arbitrary::point(), d in arbitrary::direction()) {
+//!         prop_assert_eq!(p + d.coord() - d.coord(), p);
+//!     }
+//! }
+//!
+//! // In a test module this would be `#[test]`; here it runs directly
+//! step_and_back();
+//! ```
+
+use alloc::vec::Vec;
+
+use proptest::prelude::*;
+
+use crate::prelude::*;
+
+/// Largest absolute coordinate generated by [`point`] and [`rect`].
+pub const COORD_LIMIT: i32 = 10_000;
+/// Largest width or height generated by [`rect`].
+pub const SIZE_LIMIT: i32 = 64;
+
+/// Any point with coordinates in `-COORD_LIMIT..=COORD_LIMIT`.
+pub fn point() -> impl Strategy<Value = IVec2> {
+    (-COORD_LIMIT..=COORD_LIMIT, -COORD_LIMIT..=COORD_LIMIT).prop_map(|(x, y)| IVec2::new(x, y))
+}
+
+/// A point inside `rect`, edges included.
+pub fn point_in(rect: Rect) -> impl Strategy<Value = IVec2> {
+    (rect.left()..=rect.right(), rect.bottom()..=rect.top()).prop_map(|(x, y)| IVec2::new(x, y))
+}
+
+/// A rectangle with its minimum corner from [`point`] and a width and
+/// height in `0..=SIZE_LIMIT`.
+pub fn rect() -> impl Strategy<Value = Rect> {
+    (point(), 0..=SIZE_LIMIT, 0..=SIZE_LIMIT)
+        .prop_map(|(min, w, h)| Rect::new_xywh(min.x, min.y, w, h))
+}
+
+/// One of the eight 2D directions.
+pub fn direction() -> impl Strategy<Value = Direction> {
+    proptest::sample::select(DirectionIter::all_2d().collect::<Vec<_>>())
+}
+
+/// One of the four cardinal directions.
+pub fn cardinal() -> impl Strategy<Value = Direction> {
+    proptest::sample::select(DirectionIter::cardinal().collect::<Vec<_>>())
+}
+
+/// Any of the 26 directions, including up and down.
+pub fn direction_3d() -> impl Strategy<Value = Direction> {
+    proptest::sample::select(DirectionIter::all_3d().collect::<Vec<_>>())
+}
@@ -27,9 +27,11 @@
 //! assert_eq!(Pythagoras.distance2d(start, end), 5.0);
 //! ```
 
+use core::f32::consts::SQRT_2;
+use core::ops::Sub;
 use glam::IVec2;
-use std::f32::consts::SQRT_2;
-use std::ops::Sub;
+
+use crate::math;
 
 /// Trait for 2D distance algorithms.
 pub trait DistanceAlgorithm {
@@ -74,7 +76,7 @@ pub struct Pythagoras;
 impl DistanceAlgorithm for Pythagoras {
     fn distance2d(self, start: IVec2, end: IVec2) -> f32 {
         let distance_squared = PythagorasSquared.distance2d(start, end);
-        f32::sqrt(distance_squared)
+        math::sqrt(distance_squared)
     }
 }
 
@@ -124,7 +126,8 @@ impl DistanceAlgorithm for DiagonalWithCosts {
         let start = start.as_vec2();
         let end = end.as_vec2();
         let distance = start.sub(end).abs();
-        self.0.mul_add(
+        math::mul_add(
+            self.0,
             distance.max_element(),
             (self.1 - self.0) * distance.min_element(),
         )
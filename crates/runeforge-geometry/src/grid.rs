@@ -0,0 +1,453 @@
+//! Compact storage for large per-tile grids.
+//!
+//! Explored flags, visibility and tile kinds for a 1000x1000+ world add up:
+//! a `Vec<bool>` spends a byte per tile and a `HashSet<IVec2>` far more.
+//! This module offers two smaller layouts behind one [`GridStorage`]
+//! accessor API, so code can switch between them without changes:
+//!
+//! *   [`PackedGrid`] packs 1, 2, 4 or 8 bits per tile into words. A
+//!     [`BitGrid`] of explored flags costs an eighth of a `Vec<bool>`; 2 bits
+//!     hold unseen / remembered / visible.
+//! *   [`RleGrid`] stores each row as runs of equal values, which is tiny for
+//!     mostly uniform data such as wide oceans or an unexplored world.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_geometry::prelude::*;
+//!
+//! const UNSEEN: u8 = 0;
+//! const REMEMBERED: u8 = 1;
+//! const VISIBLE: u8 = 2;
+//!
+//! let mut memory: PackedGrid<2> = PackedGrid::new(2000, 2000);
+//! memory.set(IVec2::new(10, 4), VISIBLE);
+//! memory.set(IVec2::new(11, 4), REMEMBERED);
+//! assert_eq!(memory.get(IVec2::new(10, 4)), Some(VISIBLE));
+//! assert_eq!(memory.get(IVec2::new(0, 0)), Some(UNSEEN));
+//! assert_eq!(memory.get(IVec2::new(-1, 0)), None);
+//! assert_eq!(memory.memory_bytes(), 1_000_000);
+//!
+//! // The same calls work on run-length storage
+//! let mut explored = RleGrid::new(2000, 2000);
+//! explored.set(IVec2::new(10, 4), 1);
+//! assert_eq!(explored.get(IVec2::new(10, 4)), Some(1));
+//! assert!(explored.memory_bytes() < 100_000);
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+use crate::prelude::*;
+
+/// Read and write access shared by the grid layouts.
+///
+/// Cells hold small unsigned values; positions outside the grid read as
+/// `None` and ignore writes.
+pub trait GridStorage {
+    /// Returns the width in tiles.
+    fn width(&self) -> u32;
+
+    /// Returns the height in tiles.
+    fn height(&self) -> u32;
+
+    /// Returns the value at `pos`, or `None` outside the grid.
+    fn get(&self, pos: IVec2) -> Option<u8>;
+
+    /// Sets the value at `pos`. Returns false outside the grid.
+    fn set(&mut self, pos: IVec2, value: u8) -> bool;
+
+    /// Sets every cell to `value`.
+    fn fill(&mut self, value: u8);
+
+    /// Returns the approximate heap memory used, in bytes.
+    fn memory_bytes(&self) -> usize;
+
+    /// Returns true if `pos` is inside the grid.
+    fn in_bounds(&self, pos: IVec2) -> bool {
+        pos.x >= 0 && pos.y >= 0 && (pos.x as u32) < self.width() && (pos.y as u32) < self.height()
+    }
+
+    /// Returns true if the value at `pos` is non-zero, for flag grids.
+    fn is_set(&self, pos: IVec2) -> bool {
+        self.get(pos).is_some_and(|v| v != 0)
+    }
+}
+
+/// A grid of `BITS`-bit values packed into 64-bit words.
+///
+/// `BITS` must be 1, 2, 4 or 8. Values are truncated to `BITS` bits when
+/// set.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawPackedGrid"))]
+pub struct PackedGrid<const BITS: u32 = 2> {
+    width: u32,
+    height: u32,
+    words: Vec<u64>,
+}
+
+/// A grid of flags, one bit per tile.
+pub type BitGrid = PackedGrid<1>;
+
+impl<const BITS: u32> PackedGrid<BITS> {
+    const PER_WORD: usize = (64 / BITS) as usize;
+    const MASK: u64 = (1 << BITS) - 1;
+
+    /// Creates a grid with every cell 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `BITS` is not 1, 2, 4 or 8.
+    pub fn new(width: u32, height: u32) -> Self {
+        assert!(
+            matches!(BITS, 1 | 2 | 4 | 8),
+            "PackedGrid supports 1, 2, 4 or 8 bits per cell, not {BITS}"
+        );
+        let cells = width as usize * height as usize;
+        Self {
+            width,
+            height,
+            words: vec![0; cells.div_ceil(Self::PER_WORD)],
+        }
+    }
+
+    /// Returns the largest value a cell can hold.
+    pub fn max_value(&self) -> u8 {
+        Self::MASK as u8
+    }
+
+    /// Returns every position and its value, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (IVec2, u8)> + '_ {
+        let width = self.width.max(1);
+        (0..self.width as usize * self.height as usize).map(move |idx| {
+            let pos = IVec2::new((idx as u32 % width) as i32, (idx as u32 / width) as i32);
+            (pos, self.read(idx))
+        })
+    }
+
+    /// Returns how many cells hold `value`.
+    pub fn count(&self, value: u8) -> usize {
+        self.iter().filter(|&(_, v)| v == value).count()
+    }
+
+    fn index(&self, pos: IVec2) -> Option<usize> {
+        self.in_bounds(pos)
+            .then(|| pos.y as usize * self.width as usize + pos.x as usize)
+    }
+
+    fn read(&self, idx: usize) -> u8 {
+        let shift = (idx % Self::PER_WORD) as u32 * BITS;
+        ((self.words[idx / Self::PER_WORD] >> shift) & Self::MASK) as u8
+    }
+}
+
+impl<const BITS: u32> GridStorage for PackedGrid<BITS> {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn get(&self, pos: IVec2) -> Option<u8> {
+        self.index(pos).map(|idx| self.read(idx))
+    }
+
+    fn set(&mut self, pos: IVec2, value: u8) -> bool {
+        let Some(idx) = self.index(pos) else {
+            return false;
+        };
+        let shift = (idx % Self::PER_WORD) as u32 * BITS;
+        let word = &mut self.words[idx / Self::PER_WORD];
+        *word = (*word & !(Self::MASK << shift)) | ((value as u64 & Self::MASK) << shift);
+        true
+    }
+
+    fn fill(&mut self, value: u8) {
+        let value = value as u64 & Self::MASK;
+        let pattern = (0..Self::PER_WORD).fold(0u64, |acc, i| acc | value << (i as u32 * BITS));
+        self.words.fill(pattern);
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.words.len() * core::mem::size_of::<u64>()
+    }
+}
+
+/// A grid stored as runs of equal values per row.
+///
+/// Reads are a binary search within the row; writes split and merge runs.
+/// Memory grows with the number of value changes along each row rather
+/// than with the area.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawRleGrid"))]
+pub struct RleGrid {
+    width: u32,
+    height: u32,
+    /// Per row, the start column and value of each run. Every row starts
+    /// with a run at column 0, and neighbouring runs differ in value.
+    rows: Vec<Vec<(u32, u8)>>,
+}
+
+impl RleGrid {
+    /// Creates a grid with every cell 0.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self::filled(width, height, 0)
+    }
+
+    /// Creates a grid with every cell set to `value`.
+    pub fn filled(width: u32, height: u32, value: u8) -> Self {
+        Self {
+            width,
+            height,
+            rows: vec![vec![(0, value)]; height as usize],
+        }
+    }
+
+    /// Returns the total number of runs, a measure of how compressible the
+    /// data is.
+    pub fn runs(&self) -> usize {
+        self.rows.iter().map(Vec::len).sum()
+    }
+
+    /// Returns the runs of one row as `(start, len, value)`.
+    pub fn row_runs(&self, y: u32) -> impl Iterator<Item = (u32, u32, u8)> + '_ {
+        let row = self.rows.get(y as usize).map_or(&[][..], Vec::as_slice);
+        row.iter().enumerate().map(move |(i, &(start, value))| {
+            let end = row.get(i + 1).map_or(self.width, |r| r.0);
+            (start, end - start, value)
+        })
+    }
+
+    fn run_index(row: &[(u32, u8)], x: u32) -> usize {
+        row.partition_point(|&(start, _)| start <= x) - 1
+    }
+}
+
+impl GridStorage for RleGrid {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn get(&self, pos: IVec2) -> Option<u8> {
+        if !self.in_bounds(pos) {
+            return None;
+        }
+        let row = &self.rows[pos.y as usize];
+        Some(row[Self::run_index(row, pos.x as u32)].1)
+    }
+
+    fn set(&mut self, pos: IVec2, value: u8) -> bool {
+        if !self.in_bounds(pos) {
+            return false;
+        }
+        let width = self.width;
+        let row = &mut self.rows[pos.y as usize];
+        let x = pos.x as u32;
+        let i = Self::run_index(row, x);
+        let (start, old) = row[i];
+        if old == value {
+            return true;
+        }
+        let end = row.get(i + 1).map_or(width, |r| r.0);
+
+        let mut replacement = Vec::with_capacity(3);
+        if start < x {
+            replacement.push((start, old));
+        }
+        replacement.push((x, value));
+        if x + 1 < end {
+            replacement.push((x + 1, old));
+        }
+        row.splice(i..=i, replacement);
+        // Merge with equal neighbours, keeping the earlier start
+        row.dedup_by(|later, earlier| later.1 == earlier.1);
+        true
+    }
+
+    fn fill(&mut self, value: u8) {
+        for row in &mut self.rows {
+            row.clear();
+            row.push((0, value));
+        }
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.rows.len() * core::mem::size_of::<Vec<(u32, u8)>>()
+            + self.runs() * core::mem::size_of::<(u32, u8)>()
+    }
+}
+
+/// A [`PackedGrid`] as deserialized, before its word count is checked.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawPackedGrid {
+    width: u32,
+    height: u32,
+    words: Vec<u64>,
+}
+
+#[cfg(feature = "serde")]
+impl<const BITS: u32> TryFrom<RawPackedGrid> for PackedGrid<BITS> {
+    type Error = alloc::string::String;
+
+    fn try_from(raw: RawPackedGrid) -> Result<Self, Self::Error> {
+        if !matches!(BITS, 1 | 2 | 4 | 8) {
+            return Err(alloc::format!("{BITS} bits per cell is not supported"));
+        }
+        let cells = raw.width as usize * raw.height as usize;
+        let expected = cells.div_ceil(Self::PER_WORD);
+        if raw.words.len() != expected {
+            return Err(alloc::format!(
+                "{}x{} grid needs {expected} words, got {}",
+                raw.width,
+                raw.height,
+                raw.words.len()
+            ));
+        }
+        Ok(Self {
+            width: raw.width,
+            height: raw.height,
+            words: raw.words,
+        })
+    }
+}
+
+/// An [`RleGrid`] as deserialized, before its runs are checked.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawRleGrid {
+    width: u32,
+    height: u32,
+    rows: Vec<Vec<(u32, u8)>>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawRleGrid> for RleGrid {
+    type Error = alloc::string::String;
+
+    fn try_from(raw: RawRleGrid) -> Result<Self, Self::Error> {
+        if raw.rows.len() != raw.height as usize {
+            return Err(alloc::format!(
+                "grid of height {} has {} rows",
+                raw.height,
+                raw.rows.len()
+            ));
+        }
+        for (y, row) in raw.rows.iter().enumerate() {
+            if row.first().map(|r| r.0) != Some(0) {
+                return Err(alloc::format!("row {y} doesn't start with a run at 0"));
+            }
+            let ordered = row
+                .windows(2)
+                .all(|pair| pair[0].0 < pair[1].0 && pair[0].1 != pair[1].1);
+            if !ordered || row.last().is_some_and(|r| r.0 >= raw.width.max(1)) {
+                return Err(alloc::format!("row {y} has misplaced runs"));
+            }
+        }
+        Ok(Self {
+            width: raw.width,
+            height: raw.height,
+            rows: raw.rows,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Applies the same writes to a grid and a plain `Vec<u8>`, comparing
+    /// every read.
+    fn check_against_vec(grid: &mut impl GridStorage, max: u8) {
+        let (w, h) = (grid.width() as i32, grid.height() as i32);
+        let mut plain = vec![0u8; (w * h) as usize];
+        let mut seed = 12345u32;
+        for _ in 0..500 {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let pos = IVec2::new((seed >> 8) as i32 % w, (seed >> 20) as i32 % h);
+            let value = ((seed >> 4) % (max as u32 + 1)) as u8;
+            assert!(grid.set(pos, value));
+            plain[(pos.y * w + pos.x) as usize] = value;
+        }
+        for y in 0..h {
+            for x in 0..w {
+                let pos = IVec2::new(x, y);
+                assert_eq!(grid.get(pos), Some(plain[(y * w + x) as usize]));
+            }
+        }
+        assert!(!grid.set(IVec2::new(w, 0), 1));
+        assert_eq!(grid.get(IVec2::new(0, h)), None);
+    }
+
+    #[test]
+    fn test_packed_matches_plain_storage() {
+        check_against_vec(&mut PackedGrid::<1>::new(37, 11), 1);
+        check_against_vec(&mut PackedGrid::<2>::new(37, 11), 3);
+        check_against_vec(&mut PackedGrid::<8>::new(5, 9), 255);
+
+        let mut grid: PackedGrid<4> = PackedGrid::new(10, 10);
+        grid.fill(9);
+        assert_eq!(grid.count(9), 100);
+        grid.set(IVec2::new(3, 3), 0x1F);
+        assert_eq!(grid.get(IVec2::new(3, 3)), Some(0xF));
+        assert!(BitGrid::new(64, 1).memory_bytes() == 8);
+    }
+
+    #[test]
+    fn test_rle_splits_and_merges_runs() {
+        check_against_vec(&mut RleGrid::new(23, 7), 3);
+
+        let mut grid = RleGrid::new(10, 1);
+        grid.set(IVec2::new(4, 0), 2);
+        grid.set(IVec2::new(5, 0), 2);
+        let runs: Vec<_> = grid.row_runs(0).collect();
+        assert_eq!(runs, vec![(0, 4, 0), (4, 2, 2), (6, 4, 0)]);
+
+        grid.set(IVec2::new(4, 0), 0);
+        grid.set(IVec2::new(5, 0), 0);
+        assert_eq!(grid.runs(), 1);
+        grid.fill(1);
+        assert!(grid.is_set(IVec2::new(9, 0)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialized_grids_are_validated() {
+        let packed = |words: usize| RawPackedGrid {
+            width: 40,
+            height: 2,
+            words: vec![0; words],
+        };
+        // 80 cells: 3 words at 2 bits each, 2 at 1 bit
+        assert!(PackedGrid::<2>::try_from(packed(3)).is_ok());
+        assert!(PackedGrid::<2>::try_from(packed(2)).is_err());
+        assert!(PackedGrid::<1>::try_from(packed(3)).is_err());
+
+        let rle = |rows: Vec<Vec<(u32, u8)>>| RawRleGrid {
+            width: 10,
+            height: rows.len() as u32,
+            rows,
+        };
+        let grid = RleGrid::try_from(rle(vec![vec![(0, 1), (4, 0)]])).unwrap();
+        assert_eq!(grid.get(IVec2::new(5, 0)), Some(0));
+        for row in [
+            vec![],
+            vec![(2, 1)],
+            vec![(0, 1), (6, 0), (4, 1)],
+            vec![(0, 1), (4, 1)],
+            vec![(0, 1), (10, 0)],
+        ] {
+            assert!(RleGrid::try_from(rle(vec![row])).is_err());
+        }
+        let mut missing_row = rle(vec![vec![(0, 0)]]);
+        missing_row.height = 2;
+        assert!(RleGrid::try_from(missing_row).is_err());
+    }
+}
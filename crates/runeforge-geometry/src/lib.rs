@@ -11,7 +11,14 @@
 //! *   **Distance:** Multiple distance algorithms (Manhattan, Euclidean, Chebyshev, Diagonal).
 //! *   **Iterators:** Efficiently iterate over points within shapes (e.g., all tiles in a room).
 //! *   **Intersections:** Check if shapes overlap.
-//! *   **Grid Points:** Utilities for manipulating integer coordinates (`IVec2`).
+//! *   **Compact Grids:** `PackedGrid` (1-8 bits per tile) and run-length `RleGrid` storage for huge maps.
+//! *   **Spatial Index:** `SpatialHash` buckets entities for fast rect, circle and range queries.
+//! *   **Grid Points:** Utilities for manipulating integer coordinates (`IVec2`), including
+//!     `step`, `neighbors`, `neighbors_ordinal` and `ring` for direction-aware movement.
+//! *   **Coordinate Spaces:** Typed `WorldPos`/`ScreenPos` and a `ScreenMapping` that converts
+//!     between a Y-up or Y-down world and the Y-down screen.
+//! *   **Property Testing:** [`proptest`](https://docs.rs/proptest) strategies for points,
+//!     rectangles and directions behind the `proptest` feature (see `arbitrary`).
 //!
 //! # Usage
 //!
@@ -49,15 +56,30 @@
 //!     assert_eq!(dist, 5.0);
 //! }
 //! ```
+//!
+//! # `no_std`
+//!
+//! The crate is `no_std` + `alloc` when the default `std` feature is disabled.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
+#[cfg(any(test, feature = "proptest"))]
+pub mod arbitrary;
 pub mod distance;
+pub mod grid;
+mod math;
 pub mod point;
 pub mod shapes;
+pub mod spatial;
 
 pub mod prelude {
     pub use crate::distance::*;
+    pub use crate::grid::*;
     pub use crate::point::*;
     pub use crate::shapes::*;
+    pub use crate::spatial::*;
     pub use glam::{IVec2, UVec2, Vec2};
     pub use runeforge_direction::prelude::*;
 }
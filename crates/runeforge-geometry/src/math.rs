@@ -0,0 +1,21 @@
+//! Float functions that fall back to `libm` without `std`.
+
+#[cfg(feature = "std")]
+pub(crate) fn sqrt(x: f32) -> f32 {
+    x.sqrt()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn sqrt(x: f32) -> f32 {
+    libm::sqrtf(x)
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn mul_add(x: f32, a: f32, b: f32) -> f32 {
+    x.mul_add(a, b)
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn mul_add(x: f32, a: f32, b: f32) -> f32 {
+    libm::fmaf(x, a, b)
+}
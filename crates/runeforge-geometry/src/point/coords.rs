@@ -0,0 +1,209 @@
+use crate::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A position in the game world.
+///
+/// Whether its `y` grows up or down is up to the game; a [`ScreenMapping`]
+/// records the choice and converts to and from [`ScreenPos`]. Keeping the
+/// two in separate types stops a world coordinate from being drawn as if it
+/// were a screen one.
+///
+/// # Examples
+///
+/// ```
+/// use runeforge_geometry::prelude::*;
+///
+/// let pos = WorldPos::new(3, 3).step(Direction::NORTH, CoordinateSystem::YUp);
+/// assert_eq!(pos, WorldPos::new(3, 4));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WorldPos(pub IVec2);
+
+/// A cell position on a console or screen: `(0, 0)` is the top-left corner
+/// and `y` grows downwards.
+///
+/// # Examples
+///
+/// ```
+/// use runeforge_geometry::prelude::*;
+///
+/// assert_eq!(ScreenPos::new(3, 3).step(Direction::NORTH), ScreenPos::new(3, 2));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScreenPos(pub IVec2);
+
+impl WorldPos {
+    /// Creates a new world position.
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self(IVec2::new(x, y))
+    }
+
+    /// Returns the neighbouring position towards `direction` in a world
+    /// using `system`.
+    pub fn step(self, direction: Direction, system: CoordinateSystem) -> Self {
+        Self(self.0 + direction.coord_in(system))
+    }
+}
+
+impl ScreenPos {
+    /// Creates a new screen position.
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self(IVec2::new(x, y))
+    }
+
+    /// Returns the neighbouring cell towards `direction`, with north being
+    /// up the screen.
+    pub fn step(self, direction: Direction) -> Self {
+        Self(self.0 + direction.coord_in(CoordinateSystem::YDown))
+    }
+}
+
+impl GridPoint for WorldPos {
+    fn x(&self) -> i32 {
+        self.0.x
+    }
+
+    fn y(&self) -> i32 {
+        self.0.y
+    }
+}
+
+impl GridPoint for ScreenPos {
+    fn x(&self) -> i32 {
+        self.0.x
+    }
+
+    fn y(&self) -> i32 {
+        self.0.y
+    }
+}
+
+impl From<IVec2> for WorldPos {
+    fn from(value: IVec2) -> Self {
+        Self(value)
+    }
+}
+
+impl From<WorldPos> for IVec2 {
+    fn from(value: WorldPos) -> Self {
+        value.0
+    }
+}
+
+impl From<IVec2> for ScreenPos {
+    fn from(value: IVec2) -> Self {
+        Self(value)
+    }
+}
+
+impl From<ScreenPos> for IVec2 {
+    fn from(value: ScreenPos) -> Self {
+        value.0
+    }
+}
+
+/// Converts between [`WorldPos`] and [`ScreenPos`].
+///
+/// `origin` is the world position shown in the screen's top-left cell, and
+/// `system` says which way world `y` grows. With
+/// [`CoordinateSystem::YDown`] the mapping is a plain translation; with
+/// [`CoordinateSystem::YUp`] rows are flipped so world north is still drawn
+/// towards the top of the screen.
+///
+/// # Examples
+///
+/// ```
+/// use runeforge_geometry::prelude::*;
+///
+/// // A Y-up world, with a 20x10 view centered on the player
+/// let player = WorldPos::new(100, 50);
+/// let mapping = ScreenMapping::centered_on(CoordinateSystem::YUp, player, UVec2::new(20, 10));
+/// assert_eq!(mapping.to_screen(player), ScreenPos::new(10, 5));
+///
+/// // One step north in the world is one row up on screen
+/// let north = player.step(Direction::NORTH, CoordinateSystem::YUp);
+/// assert_eq!(mapping.to_screen(north), ScreenPos::new(10, 4));
+/// assert_eq!(mapping.to_world(ScreenPos::new(10, 4)), north);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScreenMapping {
+    /// Which way world `y` grows.
+    pub system: CoordinateSystem,
+    /// The world position drawn at screen `(0, 0)`.
+    pub origin: WorldPos,
+}
+
+impl ScreenMapping {
+    /// Creates a mapping that draws `origin` in the top-left cell.
+    pub const fn new(system: CoordinateSystem, origin: WorldPos) -> Self {
+        Self { system, origin }
+    }
+
+    /// Creates a mapping that draws `center` in the middle of a screen of
+    /// `size` cells.
+    pub fn centered_on(system: CoordinateSystem, center: WorldPos, size: UVec2) -> Self {
+        let mapping = Self::new(system, WorldPos::default());
+        let half = size.as_ivec2() / 2;
+        Self::new(system, WorldPos(center.0 - mapping.world_delta(half)))
+    }
+
+    /// Converts a world position to the screen cell it is drawn in.
+    pub fn to_screen(&self, pos: WorldPos) -> ScreenPos {
+        ScreenPos(self.screen_delta(pos.0 - self.origin.0))
+    }
+
+    /// Converts a screen cell to the world position drawn in it.
+    pub fn to_world(&self, pos: ScreenPos) -> WorldPos {
+        WorldPos(self.origin.0 + self.world_delta(pos.0))
+    }
+
+    /// Converts an offset between screen cells into a world offset.
+    pub fn world_delta(&self, screen_delta: IVec2) -> IVec2 {
+        self.system.from_y_up(screen_delta * IVec2::new(1, -1))
+    }
+
+    /// Converts a world offset into an offset between screen cells.
+    pub fn screen_delta(&self, world_delta: IVec2) -> IVec2 {
+        self.system.to_y_up(world_delta) * IVec2::new(1, -1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mapping_round_trips() {
+        for system in [CoordinateSystem::YUp, CoordinateSystem::YDown] {
+            let mapping = ScreenMapping::new(system, WorldPos::new(-7, 12));
+            for y in -3..3 {
+                for x in -3..3 {
+                    let screen = ScreenPos::new(x, y);
+                    assert_eq!(mapping.to_screen(mapping.to_world(screen)), screen);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn north_is_up_on_screen() {
+        for system in [CoordinateSystem::YUp, CoordinateSystem::YDown] {
+            let mapping =
+                ScreenMapping::centered_on(system, WorldPos::new(5, 5), UVec2::new(11, 11));
+            let center = mapping.to_screen(WorldPos::new(5, 5));
+            assert_eq!(center, ScreenPos::new(5, 5));
+            for dir in Direction::all() {
+                let world = WorldPos::new(5, 5).step(dir, system);
+                assert_eq!(
+                    mapping.to_screen(world),
+                    center.step(dir),
+                    "{system:?} {dir:?}"
+                );
+            }
+        }
+    }
+}
@@ -1,5 +1,4 @@
 use crate::prelude::*;
-use std::ops::Sub;
 
 /// Trait to implement a 2D point within a grid.
 pub trait GridPoint: Clone + Copy {
@@ -72,15 +71,51 @@ pub trait GridPoint: Clone + Copy {
     /// Chevbyshev distance between two points.
     #[inline]
     fn distance(&self, point: impl GridPoint) -> u32 {
-        let start = self.as_uvec2();
-        let end = point.as_uvec2();
-        start.sub(end).max_element()
+        let start = self.as_ivec2();
+        let end = point.as_ivec2();
+        (start - end).abs().max_element() as u32
     }
 
     /// Returns an iterator over all points within the size.
     fn iter(self) -> PointIterRowMajor {
         PointIterRowMajor::new(self.as_uvec2())
     }
+
+    /// Returns the adjacent point towards `direction`.
+    ///
+    /// Like [`Direction::coord`], `NORTH` is `+1` on the `Y` axis; use
+    /// [`step_in`](Self::step_in) for a Y-down map.
+    #[inline]
+    fn step(&self, direction: Direction) -> IVec2 {
+        self.as_ivec2() + direction.coord()
+    }
+
+    /// Returns the adjacent point towards `direction` in a map using `system`.
+    #[inline]
+    fn step_in(&self, direction: Direction, system: CoordinateSystem) -> IVec2 {
+        self.as_ivec2() + direction.coord_in(system)
+    }
+
+    /// Returns an iterator over the four orthogonally adjacent points.
+    #[inline]
+    fn neighbors(&self) -> AdjIterator {
+        AdjIterator::new(self.as_ivec2(), DirectionIter::cardinal())
+    }
+
+    /// Returns an iterator over all eight adjacent points, diagonals
+    /// included.
+    #[inline]
+    fn neighbors_ordinal(&self) -> AdjIterator {
+        AdjIterator::new(self.as_ivec2(), DirectionIter::all_2d())
+    }
+
+    /// Returns an iterator over the points at exactly `radius` Chebyshev
+    /// [`distance`](Self::distance): the outline of the square centered on
+    /// this point. A radius of 0 yields only the point itself.
+    #[inline]
+    fn ring(&self, radius: u32) -> RingIter {
+        RingIter::new(self.as_ivec2(), radius)
+    }
 }
 
 macro_rules! impl_grid_point {
@@ -105,10 +140,24 @@ impl_grid_point!([usize; 2]);
 
 impl GridPoint for Vec2 {
     fn x(&self) -> i32 {
-        self.x.floor() as i32
+        self.floor().x as i32
     }
 
     fn y(&self) -> i32 {
-        self.y.floor() as i32
+        self.floor().y as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_to_larger_point() {
+        let near = IVec2::new(1, 2);
+        let far = IVec2::new(4, 8);
+        assert_eq!(near.distance(far), 6);
+        assert_eq!(far.distance(near), 6);
+        assert_eq!(UVec2::new(0, 0).distance(UVec2::new(3, 1)), 3);
     }
 }
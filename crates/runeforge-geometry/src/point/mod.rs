@@ -1,4 +1,6 @@
-mod grid_point;
-pub use grid_point::*;
-mod point_iter;
-pub use point_iter::*;
+mod coords;
+pub use coords::*;
+mod grid_point;
+pub use grid_point::*;
+mod point_iter;
+pub use point_iter::*;
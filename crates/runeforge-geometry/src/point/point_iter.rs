@@ -68,3 +68,117 @@ impl Iterator for AdjIterator {
         Some(p)
     }
 }
+
+/// Iterator over the points at a fixed Chebyshev distance from a center.
+///
+/// Points are yielded clockwise on a Y-down grid, starting from the corner
+/// with the smallest coordinates.
+pub struct RingIter {
+    p: IVec2,
+    side: i32,
+    i: i32,
+    count: i32,
+}
+
+impl RingIter {
+    /// Creates a new iterator over the ring of `radius` around `center`.
+    pub fn new(center: IVec2, radius: u32) -> Self {
+        let radius = radius.min(i32::MAX as u32 / 8) as i32;
+        Self {
+            p: center - IVec2::splat(radius),
+            side: radius * 2,
+            i: 0,
+            count: (radius * 8).max(1),
+        }
+    }
+}
+
+impl Iterator for RingIter {
+    type Item = IVec2;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.i >= self.count {
+            return None;
+        }
+
+        let p = self.p;
+        if self.side > 0 {
+            // Walk each edge in turn: +x, +y, -x, -y
+            self.p += match self.i / self.side {
+                0 => IVec2::X,
+                1 => IVec2::Y,
+                2 => IVec2::NEG_X,
+                _ => IVec2::NEG_Y,
+            };
+        }
+        self.i += 1;
+        Some(p)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.count - self.i) as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for RingIter {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use proptest::prelude::*;
+
+    use crate::arbitrary;
+    use crate::prelude::*;
+
+    #[test]
+    fn neighbors() {
+        let p = IVec2::new(3, 3);
+        let cardinal: Vec<IVec2> = p.neighbors().collect();
+        assert_eq!(cardinal.len(), 4);
+        assert!(cardinal.contains(&IVec2::new(3, 4)));
+        assert!(cardinal.iter().all(|n| (*n - p).abs().element_sum() == 1));
+
+        let all: Vec<IVec2> = p.neighbors_ordinal().collect();
+        assert_eq!(all.len(), 8);
+        assert!(all.contains(&IVec2::new(2, 2)));
+        assert_eq!(p.step(Direction::NORTH_EAST), IVec2::new(4, 4));
+        assert_eq!(
+            p.step_in(Direction::NORTH_EAST, CoordinateSystem::YDown),
+            IVec2::new(4, 2)
+        );
+    }
+
+    #[test]
+    fn ring() {
+        let p = IVec2::new(-1, 2);
+        assert_eq!(p.ring(0).collect::<Vec<_>>(), [p]);
+        let mut ring: Vec<IVec2> = p.ring(1).collect();
+        let mut adjacent: Vec<IVec2> = p.neighbors_ordinal().collect();
+        ring.sort_by_key(|p| (p.x, p.y));
+        adjacent.sort_by_key(|p| (p.x, p.y));
+        assert_eq!(ring, adjacent);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_ring_is_at_radius(p in arbitrary::point(), radius in 0u32..20) {
+            let ring: Vec<IVec2> = p.ring(radius).collect();
+            prop_assert_eq!(ring.len(), (radius as usize * 8).max(1));
+            for q in &ring {
+                prop_assert_eq!(p.distance(*q), radius);
+            }
+            let mut unique = ring.clone();
+            unique.sort_by_key(|p| (p.x, p.y));
+            unique.dedup();
+            prop_assert_eq!(unique.len(), ring.len());
+        }
+
+        #[test]
+        fn prop_step_is_adjacent(p in arbitrary::point(), d in arbitrary::direction()) {
+            prop_assert_eq!(p.distance(p.step(d)), 1);
+            prop_assert!(p.neighbors_ordinal().any(|n| n == p.step(d)));
+        }
+    }
+}
@@ -102,3 +102,37 @@ impl Iterator for BresenhamLineInclusiveIter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrary;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn prop_inclusive_line_connects_endpoints(
+            start in arbitrary::point(),
+            end in arbitrary::point(),
+        ) {
+            let points: Vec<IVec2> = BresenhamLineInclusiveIter::new(start, end).collect();
+            let steps = (end - start).abs().max_element();
+            prop_assert_eq!(points.len(), steps as usize + 1);
+            prop_assert_eq!(points[0], start);
+            prop_assert_eq!(points[points.len() - 1], end);
+            for pair in points.windows(2) {
+                prop_assert_eq!((pair[1] - pair[0]).abs().max_element(), 1);
+            }
+        }
+
+        #[test]
+        fn prop_exclusive_line_stops_before_end(
+            start in arbitrary::point(),
+            end in arbitrary::point(),
+        ) {
+            let exclusive: Vec<IVec2> = BresenhamLineIter::new(start, end).collect();
+            let inclusive: Vec<IVec2> = BresenhamLineInclusiveIter::new(start, end).collect();
+            prop_assert_eq!(&exclusive[..], &inclusive[..inclusive.len() - 1]);
+        }
+    }
+}
@@ -44,3 +44,36 @@ impl From<Rect> for RectIter {
         rect.into_iter()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrary;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn prop_covers_rect_once_in_row_major_order(rect in arbitrary::rect()) {
+            let points: Vec<IVec2> = rect.into_iter().collect();
+            let expected = (rect.width() + 1) * (rect.height() + 1);
+            prop_assert_eq!(points.len(), expected as usize);
+            prop_assert_eq!(points.first().copied(), Some(rect.min()));
+            prop_assert_eq!(points.last().copied(), Some(rect.max()));
+            for pair in points.windows(2) {
+                prop_assert!((pair[0].y, pair[0].x) < (pair[1].y, pair[1].x));
+            }
+            for p in points {
+                prop_assert!((rect.left()..=rect.right()).contains(&p.x));
+                prop_assert!((rect.bottom()..=rect.top()).contains(&p.y));
+            }
+        }
+
+        #[test]
+        fn prop_contains_generated_points(
+            (rect, p) in arbitrary::rect().prop_flat_map(|r| (Just(r), arbitrary::point_in(r)))
+        ) {
+            prop_assert!(rect.points().any(|q| q == p));
+            prop_assert!(rect.intersects(Rect::new(p, p)));
+        }
+    }
+}
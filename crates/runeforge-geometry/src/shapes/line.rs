@@ -1,6 +1,7 @@
 use crate::prelude::*;
+use alloc::boxed::Box;
+use core::fmt::Display;
 use hashbrown::HashSet;
-use std::fmt::Display;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -69,7 +70,7 @@ impl IntoIterator for Line {
 }
 
 impl Display for Line {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "Line {{Start: {}, End: {}}}", self.start, self.end)
     }
 }
@@ -1,4 +1,5 @@
 use crate::prelude::*;
+use alloc::boxed::Box;
 use hashbrown::HashSet;
 
 /// Boxed shape
@@ -0,0 +1,301 @@
+//! Finding entities by position without scanning them all.
+//!
+//! AI that looks for targets in view, a mouse click that picks what is
+//! under the cursor and an explosion that damages everything in a radius
+//! all ask "who is near here?". A [`SpatialHash`] answers that by sorting
+//! entities into square buckets, so a query only visits the buckets it
+//! overlaps instead of every entity on the map.
+//!
+//! Results come back in a deterministic order (bucket by bucket, row by
+//! row, then in insertion order), so games stay reproducible from a seed.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_geometry::prelude::*;
+//!
+//! let mut index = SpatialHash::new(8);
+//! index.insert("goblin", IVec2::new(3, 4));
+//! index.insert("orc", IVec2::new(30, 4));
+//! index.insert("rat", IVec2::new(5, 5));
+//!
+//! // Fireball at (4, 4)
+//! assert_eq!(index.query_circle(IVec2::new(4, 4), 2), vec!["goblin", "rat"]);
+//!
+//! // Mouse picking
+//! assert_eq!(index.at(IVec2::new(30, 4)).collect::<Vec<_>>(), vec!["orc"]);
+//!
+//! index.move_to(&"orc", IVec2::new(6, 6));
+//! let room = Rect::new(IVec2::new(0, 0), IVec2::new(9, 9));
+//! assert_eq!(index.query_rect(room).len(), 3);
+//! assert_eq!(index.nearest(IVec2::new(7, 7), 5), Some("orc"));
+//! ```
+
+use alloc::vec::Vec;
+use core::hash::Hash;
+
+use hashbrown::HashMap;
+
+use crate::prelude::*;
+
+/// Entities bucketed by position on a uniform grid.
+///
+/// Any number of entities may share a tile.
+#[derive(Debug, Clone)]
+pub struct SpatialHash<K> {
+    cell_size: i32,
+    buckets: HashMap<IVec2, Vec<(K, IVec2)>>,
+    positions: HashMap<K, IVec2>,
+}
+
+impl<K: Copy + Eq + Hash> Default for SpatialHash<K> {
+    /// Buckets of 16x16 tiles.
+    fn default() -> Self {
+        Self::new(16)
+    }
+}
+
+impl<K: Copy + Eq + Hash> SpatialHash<K> {
+    /// Creates an empty index with buckets of `cell_size` x `cell_size`
+    /// tiles. Pick roughly the size of a typical query; 0 is treated as 1.
+    pub fn new(cell_size: u32) -> Self {
+        Self {
+            cell_size: cell_size.clamp(1, i32::MAX as u32) as i32,
+            buckets: HashMap::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Returns the bucket size in tiles.
+    pub fn cell_size(&self) -> u32 {
+        self.cell_size as u32
+    }
+
+    /// Adds an entity, or moves it if it is already indexed. Returns its
+    /// previous position.
+    pub fn insert(&mut self, id: K, pos: IVec2) -> Option<IVec2> {
+        let old = self.remove(&id);
+        self.buckets
+            .entry(self.bucket(pos))
+            .or_default()
+            .push((id, pos));
+        self.positions.insert(id, pos);
+        old
+    }
+
+    /// Removes an entity, returning its position.
+    pub fn remove(&mut self, id: &K) -> Option<IVec2> {
+        let pos = self.positions.remove(id)?;
+        let bucket = self.bucket(pos);
+        if let Some(entries) = self.buckets.get_mut(&bucket) {
+            entries.retain(|(other, _)| other != id);
+            if entries.is_empty() {
+                self.buckets.remove(&bucket);
+            }
+        }
+        Some(pos)
+    }
+
+    /// Moves an indexed entity. Returns false if it isn't indexed.
+    pub fn move_to(&mut self, id: &K, pos: IVec2) -> bool {
+        let Some(&old) = self.positions.get(id) else {
+            return false;
+        };
+        let (from, to) = (self.bucket(old), self.bucket(pos));
+        if from == to {
+            if let Some(entry) = self
+                .buckets
+                .get_mut(&from)
+                .and_then(|entries| entries.iter_mut().find(|(other, _)| other == id))
+            {
+                entry.1 = pos;
+            }
+            self.positions.insert(*id, pos);
+        } else {
+            self.insert(*id, pos);
+        }
+        true
+    }
+
+    /// Returns where an entity is.
+    pub fn position(&self, id: &K) -> Option<IVec2> {
+        self.positions.get(id).copied()
+    }
+
+    /// Returns true if the entity is indexed.
+    pub fn contains(&self, id: &K) -> bool {
+        self.positions.contains_key(id)
+    }
+
+    /// Returns the entities on one tile.
+    pub fn at(&self, pos: IVec2) -> impl Iterator<Item = K> + '_ {
+        self.buckets
+            .get(&self.bucket(pos))
+            .into_iter()
+            .flatten()
+            .filter(move |(_, p)| *p == pos)
+            .map(|&(id, _)| id)
+    }
+
+    /// Returns the entities inside `rect`, edges included.
+    pub fn query_rect(&self, rect: Rect) -> Vec<K> {
+        self.query(rect.min, rect.max, |_| true)
+    }
+
+    /// Returns the entities within Euclidean distance `radius` of `center`,
+    /// the same disc that FOV radii and blasts cover.
+    pub fn query_circle(&self, center: IVec2, radius: u32) -> Vec<K> {
+        let r = radius as i32;
+        let r2 = radius as i64 * radius as i64;
+        self.query(center - r, center + r, |p| {
+            let d = (p - center).as_i64vec2();
+            d.x * d.x + d.y * d.y <= r2
+        })
+    }
+
+    /// Returns the entities within `range` steps of `center`, counting
+    /// diagonal steps as one (a square).
+    pub fn query_range(&self, center: IVec2, range: u32) -> Vec<K> {
+        let r = range as i32;
+        self.query(center - r, center + r, |_| true)
+    }
+
+    /// Returns the entity nearest to `center` (Euclidean) within `radius`.
+    /// Ties go to the first in query order.
+    pub fn nearest(&self, center: IVec2, radius: u32) -> Option<K> {
+        self.query_circle(center, radius)
+            .into_iter()
+            .min_by_key(|id| {
+                let d = (self.positions[id] - center).as_i64vec2();
+                d.x * d.x + d.y * d.y
+            })
+    }
+
+    /// Returns every entity and its position, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (K, IVec2)> + '_ {
+        self.positions.iter().map(|(&id, &pos)| (id, pos))
+    }
+
+    /// Returns the number of indexed entities.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns true if nothing is indexed.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Removes every entity.
+    pub fn clear(&mut self) {
+        self.buckets.clear();
+        self.positions.clear();
+    }
+
+    fn bucket(&self, pos: IVec2) -> IVec2 {
+        IVec2::new(
+            pos.x.div_euclid(self.cell_size),
+            pos.y.div_euclid(self.cell_size),
+        )
+    }
+
+    /// Collects entities between `min` and `max` (inclusive) that pass
+    /// `keep`, visiting buckets row by row.
+    fn query(&self, min: IVec2, max: IVec2, keep: impl Fn(IVec2) -> bool) -> Vec<K> {
+        let mut found = Vec::new();
+        if min.x > max.x || min.y > max.y {
+            return found;
+        }
+        let (lo, hi) = (self.bucket(min), self.bucket(max));
+        // Huge queries over a sparse index are cheaper as a scan of the
+        // occupied buckets.
+        let span = (hi - lo + IVec2::ONE).as_i64vec2();
+        let mut buckets: Vec<IVec2> = if span.x * span.y > self.buckets.len() as i64 {
+            self.buckets
+                .keys()
+                .copied()
+                .filter(|b| b.cmpge(lo).all() && b.cmple(hi).all())
+                .collect()
+        } else {
+            (lo.y..=hi.y)
+                .flat_map(|y| (lo.x..=hi.x).map(move |x| IVec2::new(x, y)))
+                .collect()
+        };
+        buckets.sort_by_key(|b| (b.y, b.x));
+
+        for bucket in buckets {
+            let Some(entries) = self.buckets.get(&bucket) else {
+                continue;
+            };
+            found.extend(
+                entries
+                    .iter()
+                    .filter(|(_, p)| p.cmpge(min).all() && p.cmple(max).all() && keep(*p))
+                    .map(|&(id, _)| id),
+            );
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queries_match_a_linear_scan() {
+        let mut index = SpatialHash::new(5);
+        let mut all = Vec::new();
+        for i in 0..200u32 {
+            let pos = IVec2::new((i * 37 % 61) as i32 - 30, (i * 17 % 43) as i32 - 20);
+            index.insert(i, pos);
+            all.push((i, pos));
+        }
+        for i in (0..200).step_by(3) {
+            let pos = all[i as usize].1 + IVec2::new(7, -3);
+            assert!(index.move_to(&i, pos));
+            all[i as usize].1 = pos;
+        }
+        for i in (0..200).step_by(7) {
+            assert!(index.remove(&i).is_some());
+        }
+        all.retain(|(i, _)| i % 7 != 0);
+
+        let center = IVec2::new(2, -1);
+        let mut circle = index.query_circle(center, 9);
+        circle.sort();
+        let expected: Vec<u32> = all
+            .iter()
+            .filter(|(_, p)| (*p - center).length_squared() <= 81)
+            .map(|&(i, _)| i)
+            .collect();
+        assert_eq!(circle, expected);
+
+        let rect = Rect::new(IVec2::new(-12, -4), IVec2::new(3, 10));
+        let mut inside = index.query_rect(rect);
+        inside.sort();
+        let expected: Vec<u32> = all
+            .iter()
+            .filter(|(_, p)| p.cmpge(rect.min).all() && p.cmple(rect.max).all())
+            .map(|&(i, _)| i)
+            .collect();
+        assert_eq!(inside, expected);
+        assert_eq!(index.len(), all.len());
+    }
+
+    #[test]
+    fn test_negative_positions_and_stacking() {
+        let mut index = SpatialHash::new(4);
+        index.insert('a', IVec2::new(-1, -1));
+        index.insert('b', IVec2::new(-1, -1));
+        index.insert('c', IVec2::new(-4, 0));
+        assert_eq!(
+            index.at(IVec2::new(-1, -1)).collect::<Vec<_>>(),
+            vec!['a', 'b']
+        );
+        assert_eq!(index.query_range(IVec2::new(-2, 0), 2), vec!['a', 'b', 'c']);
+        assert_eq!(index.insert('a', IVec2::ZERO), Some(IVec2::new(-1, -1)));
+        assert!(!index.move_to(&'z', IVec2::ZERO));
+        assert_eq!(index.query_circle(IVec2::new(40, 40), 3), vec![]);
+    }
+}
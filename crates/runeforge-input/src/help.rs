@@ -0,0 +1,364 @@
+//! Keybinding help screens and command palettes generated from an
+//! [`InputMap`].
+//!
+//! [`InputMap::help`] lists every action reachable with the active keymap,
+//! each with a readable name, a category and the names of the keys bound to
+//! it. The same data drives a '?' help screen ([`help_lines`]) and a
+//! searchable command palette ([`search`]), so neither drifts from the real
+//! bindings.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_input::help::{help_lines, search};
+//! use runeforge_input::{InputMap, VirtualKey};
+//!
+//! let map = InputMap::roguelike_default();
+//! let help = map.help();
+//!
+//! let inventory = help.iter().find(|h| h.action == VirtualKey::Inventory).unwrap();
+//! assert_eq!(inventory.keys, vec!["i"]);
+//!
+//! for line in help_lines(&help) {
+//!     println!("{}", line);
+//! }
+//!
+//! let matches = search(&help, "inv");
+//! assert_eq!(matches[0].name, "Inventory");
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+
+use winit::keyboard::KeyCode;
+
+use crate::{Direction, InputMap, VirtualKey};
+
+/// Help for one action: what it is called and which keys trigger it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindingHelp {
+    /// The action
+    pub action: VirtualKey,
+    /// Readable name, e.g. "Move north-west"
+    pub name: String,
+    /// Group heading, e.g. "Movement"
+    pub category: &'static str,
+    /// Names of the bound keys, sorted
+    pub keys: Vec<String>,
+}
+
+impl InputMap {
+    /// Lists every action bound in the active keymap, grouped by category.
+    ///
+    /// Keys hidden by the active contexts are left out, so the list always
+    /// matches what pressing the keys would do right now. Actions come in a
+    /// fixed order: movement, then actions, then system commands.
+    pub fn help(&self) -> Vec<BindingHelp> {
+        let mut by_action: BTreeMap<(u8, u32), BindingHelp> = BTreeMap::new();
+        let mut seen = HashSet::new();
+        for key in self.bound_keys() {
+            if !seen.insert(key) {
+                continue;
+            }
+            for &action in self.get(key).unwrap_or_default() {
+                let entry = by_action
+                    .entry(order(action))
+                    .or_insert_with(|| BindingHelp {
+                        action,
+                        name: match action {
+                            VirtualKey::Custom(id) => self
+                                .custom_name(id)
+                                .map_or_else(|| action_name(action), str::to_string),
+                            _ => action_name(action),
+                        },
+                        category: category(action),
+                        keys: Vec::new(),
+                    });
+                entry.keys.push(key_name(key));
+            }
+        }
+        by_action
+            .into_values()
+            .map(|mut help| {
+                help.keys.sort_by(|a, b| (a.len(), a).cmp(&(b.len(), b)));
+                help.keys.dedup();
+                help
+            })
+            .collect()
+    }
+}
+
+/// Returns a readable name for an action, e.g. "Quaff" or "Move south".
+///
+/// Custom actions are called "Custom N" here; [`InputMap::help`] uses the
+/// names given with [`InputMap::name_custom`] instead.
+pub fn action_name(action: VirtualKey) -> String {
+    let name = match action {
+        VirtualKey::Move(dir) => return format!("Move {}", direction_name(dir)),
+        VirtualKey::Custom(id) => return format!("Custom {}", id),
+        VirtualKey::Confirm => "Confirm",
+        VirtualKey::Cancel => "Cancel",
+        VirtualKey::Pickup => "Pick up",
+        VirtualKey::Drop => "Drop",
+        VirtualKey::Inventory => "Inventory",
+        VirtualKey::Use => "Use",
+        VirtualKey::Look => "Look",
+        VirtualKey::Wait => "Wait",
+        VirtualKey::Eat => "Eat",
+        VirtualKey::Quaff => "Quaff",
+        VirtualKey::Read => "Read",
+        VirtualKey::Zap => "Zap",
+        VirtualKey::Open => "Open door",
+        VirtualKey::Close => "Close door",
+        VirtualKey::Help => "Help",
+        VirtualKey::Quit => "Quit",
+    };
+    name.to_string()
+}
+
+/// Returns the help-screen group of an action.
+pub fn category(action: VirtualKey) -> &'static str {
+    match action {
+        VirtualKey::Move(_) | VirtualKey::Wait => "Movement",
+        VirtualKey::Confirm | VirtualKey::Cancel | VirtualKey::Help | VirtualKey::Quit => "System",
+        _ => "Actions",
+    }
+}
+
+/// Returns a short readable name for a physical key, e.g. "i", "Up",
+/// "Numpad 8" or "Esc".
+pub fn key_name(key: KeyCode) -> String {
+    let name = match key {
+        KeyCode::ArrowUp => "Up",
+        KeyCode::ArrowDown => "Down",
+        KeyCode::ArrowLeft => "Left",
+        KeyCode::ArrowRight => "Right",
+        KeyCode::Enter | KeyCode::NumpadEnter => "Enter",
+        KeyCode::Escape => "Esc",
+        KeyCode::Space => "Space",
+        KeyCode::Tab => "Tab",
+        KeyCode::Backspace => "Backspace",
+        KeyCode::Delete => "Del",
+        KeyCode::Home => "Home",
+        KeyCode::End => "End",
+        KeyCode::PageUp => "PgUp",
+        KeyCode::PageDown => "PgDn",
+        KeyCode::Period => ".",
+        KeyCode::Comma => ",",
+        KeyCode::Slash => "/",
+        KeyCode::Backslash => "\\",
+        KeyCode::Semicolon => ";",
+        KeyCode::Quote => "'",
+        KeyCode::Minus => "-",
+        KeyCode::Equal => "=",
+        KeyCode::BracketLeft => "[",
+        KeyCode::BracketRight => "]",
+        KeyCode::Backquote => "`",
+        _ => {
+            let debug = format!("{:?}", key);
+            if let Some(letter) = debug.strip_prefix("Key") {
+                return letter.to_lowercase();
+            }
+            if let Some(digit) = debug.strip_prefix("Digit") {
+                return digit.to_string();
+            }
+            if let Some(rest) = debug.strip_prefix("Numpad") {
+                return format!("Numpad {}", rest);
+            }
+            return debug;
+        }
+    };
+    name.to_string()
+}
+
+/// Formats help entries as text lines: a heading per category followed by
+/// one "keys  name" line per action, with the key column aligned.
+pub fn help_lines(help: &[BindingHelp]) -> Vec<String> {
+    let joined: Vec<String> = help.iter().map(|h| h.keys.join(" ")).collect();
+    let width = joined.iter().map(|k| k.chars().count()).max().unwrap_or(0);
+    let mut lines = Vec::new();
+    let mut current = None;
+    for (entry, keys) in help.iter().zip(&joined) {
+        if current != Some(entry.category) {
+            if current.is_some() {
+                lines.push(String::new());
+            }
+            lines.push(entry.category.to_string());
+            current = Some(entry.category);
+        }
+        lines.push(format!("  {:<width$}  {}", keys, entry.name, width = width));
+    }
+    lines
+}
+
+/// Filters help entries for a command palette, best matches first.
+///
+/// Matching ignores case. Names starting with the query rank first, then
+/// names containing it, then names containing its letters in order (so
+/// "cld" finds "Close door"). An empty query returns everything.
+pub fn search<'a>(help: &'a [BindingHelp], query: &str) -> Vec<&'a BindingHelp> {
+    let query = query.trim().to_lowercase();
+    let mut ranked: Vec<(u8, usize, &BindingHelp)> = help
+        .iter()
+        .enumerate()
+        .filter_map(|(i, entry)| {
+            let name = entry.name.to_lowercase();
+            let rank = if name.starts_with(&query) {
+                0
+            } else if name.contains(&query) {
+                1
+            } else if is_subsequence(&query, &name) {
+                2
+            } else {
+                return None;
+            };
+            Some((rank, i, entry))
+        })
+        .collect();
+    ranked.sort_by_key(|&(rank, i, _)| (rank, i));
+    ranked.into_iter().map(|(_, _, entry)| entry).collect()
+}
+
+impl InputMap {
+    /// Physical keys bound in the global map or in any active context.
+    fn bound_keys(&self) -> impl Iterator<Item = KeyCode> + '_ {
+        let contexts = self
+            .stack
+            .iter()
+            .filter_map(|name| self.contexts.get(name))
+            .flat_map(|context| context.keys());
+        self.mappings.keys().copied().chain(contexts)
+    }
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut chars = haystack.chars();
+    needle.chars().all(|c| chars.any(|h| h == c))
+}
+
+fn direction_name(dir: Direction) -> String {
+    let c = dir.coord();
+    let vertical = match c.y.signum() {
+        1 => "north",
+        -1 => "south",
+        _ => "",
+    };
+    let horizontal = match c.x.signum() {
+        1 => "east",
+        -1 => "west",
+        _ => "",
+    };
+    match (vertical, horizontal) {
+        ("", "") => "nowhere".to_string(),
+        (v, "") => v.to_string(),
+        ("", h) => h.to_string(),
+        (v, h) => format!("{}-{}", v, h),
+    }
+}
+
+/// Sort key keeping actions in declaration order within their category,
+/// with custom actions after the built-in ones.
+fn order(action: VirtualKey) -> (u8, u32) {
+    let group = match category(action) {
+        "Movement" => 0,
+        "Actions" => 1,
+        _ => 2,
+    };
+    let index = match action {
+        VirtualKey::Move(dir) => {
+            // Clockwise from north
+            const ORDER: [Direction; 8] = [
+                Direction::NORTH,
+                Direction::NORTH_EAST,
+                Direction::EAST,
+                Direction::SOUTH_EAST,
+                Direction::SOUTH,
+                Direction::SOUTH_WEST,
+                Direction::WEST,
+                Direction::NORTH_WEST,
+            ];
+            ORDER.iter().position(|&d| d == dir).unwrap_or(8) as u32
+        }
+        VirtualKey::Wait => 9,
+        VirtualKey::Confirm => 0,
+        VirtualKey::Cancel => 1,
+        VirtualKey::Pickup => 2,
+        VirtualKey::Drop => 3,
+        VirtualKey::Inventory => 4,
+        VirtualKey::Use => 5,
+        VirtualKey::Look => 6,
+        VirtualKey::Eat => 7,
+        VirtualKey::Quaff => 8,
+        VirtualKey::Read => 9,
+        VirtualKey::Zap => 10,
+        VirtualKey::Open => 11,
+        VirtualKey::Close => 12,
+        VirtualKey::Help => 13,
+        VirtualKey::Quit => 14,
+        VirtualKey::Custom(id) => 100 + id as u32,
+    };
+    (group, index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymap::Fallthrough;
+
+    #[test]
+    fn test_help_groups_and_names() {
+        let help = InputMap::roguelike_default().help();
+        let categories: Vec<&str> = help.iter().map(|h| h.category).collect();
+        let mut sorted = categories.clone();
+        sorted.sort_by_key(|c| {
+            ["Movement", "Actions", "System"]
+                .iter()
+                .position(|x| x == c)
+        });
+        assert_eq!(categories, sorted);
+
+        let north = &help[0];
+        assert_eq!(north.name, "Move north");
+        assert_eq!(north.keys, vec!["k", "w", "Up", "Numpad 8"]);
+        let wait = help.iter().find(|h| h.action == VirtualKey::Wait).unwrap();
+        assert_eq!(wait.keys, vec![".", "Space", "Numpad 5"]);
+        assert_eq!(
+            action_name(VirtualKey::Move(Direction::SOUTH_WEST)),
+            "Move south-west"
+        );
+    }
+
+    #[test]
+    fn test_help_follows_active_context() {
+        let mut map = InputMap::roguelike_default();
+        map.define_context("prompt", Fallthrough::Block)
+            .bind(KeyCode::Escape, VirtualKey::Cancel);
+        map.push_context("prompt");
+        let help = map.help();
+        assert_eq!(help.len(), 1);
+        assert_eq!(help[0].keys, vec!["Esc"]);
+    }
+
+    #[test]
+    fn test_lines_and_search() {
+        let help = InputMap::roguelike_default().help();
+        let lines = help_lines(&help);
+        assert_eq!(lines[0], "Movement");
+        assert!(lines.contains(&"Actions".to_string()));
+        assert!(lines
+            .iter()
+            .any(|l| l.trim_start().starts_with("i ") && l.ends_with("Inventory")));
+
+        let names: Vec<&str> = search(&help, "cld")
+            .iter()
+            .map(|h| h.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Close door"]);
+        let names: Vec<&str> = search(&help, "Door")
+            .iter()
+            .map(|h| h.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Open door", "Close door"]);
+        assert_eq!(search(&help, "").len(), help.len());
+        assert!(search(&help, "xyzzy").is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+//! Keymap contexts for modal input.
+//!
+//! The same key often means different things depending on what is on
+//! screen: 'i' opens the inventory in-game, selects an item in a menu and is
+//! just a letter in a text prompt. An [`InputMap`](crate::InputMap) keeps a
+//! stack of named [`KeymapContext`]s on top of its global bindings. Keys
+//! resolve in the top context first; whether unbound keys reach the contexts
+//! below is up to each context's [`Fallthrough`].
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_input::keymap::Fallthrough;
+//! use runeforge_input::{InputMap, VirtualKey};
+//! use winit::keyboard::KeyCode;
+//!
+//! let mut map = InputMap::roguelike_default();
+//! map.define_context("targeting", Fallthrough::Unbound)
+//!     .bind(KeyCode::KeyI, VirtualKey::Look);
+//! map.define_context("prompt", Fallthrough::Block)
+//!     .bind(KeyCode::Escape, VirtualKey::Cancel)
+//!     .bind(KeyCode::Enter, VirtualKey::Confirm);
+//!
+//! assert_eq!(map.get(KeyCode::KeyI), Some(&[VirtualKey::Inventory][..]));
+//!
+//! map.push_context("targeting");
+//! assert_eq!(map.get(KeyCode::KeyI), Some(&[VirtualKey::Look][..]));
+//! assert_eq!(map.get(KeyCode::KeyE), Some(&[VirtualKey::Eat][..]));
+//!
+//! // In a text prompt, letters are text, so nothing is bound to them
+//! map.push_context("prompt");
+//! assert_eq!(map.get(KeyCode::KeyI), None);
+//! assert_eq!(map.get(KeyCode::Escape), Some(&[VirtualKey::Cancel][..]));
+//!
+//! map.pop_context();
+//! map.pop_context();
+//! assert_eq!(map.active_context(), None);
+//! ```
+
+use std::collections::HashMap;
+
+use winit::keyboard::KeyCode;
+
+use crate::VirtualKey;
+
+/// What happens to keys a context doesn't bind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Fallthrough {
+    /// Unbound keys resolve in the context below, down to the global
+    /// bindings.
+    #[default]
+    Unbound,
+    /// Unbound keys resolve to nothing, hiding every binding below. Use it
+    /// for text entry and other fully modal screens.
+    Block,
+}
+
+/// A named set of bindings that can be pushed onto an input map.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct KeymapContext {
+    mappings: HashMap<KeyCode, Vec<VirtualKey>>,
+    fallthrough: Fallthrough,
+}
+
+impl KeymapContext {
+    /// Creates a context without bindings.
+    pub fn new(fallthrough: Fallthrough) -> Self {
+        Self {
+            mappings: HashMap::new(),
+            fallthrough,
+        }
+    }
+
+    /// Binds a physical key to a virtual key in this context.
+    ///
+    /// Multiple virtual keys can be bound to the same physical key.
+    pub fn bind(&mut self, key: KeyCode, virtual_key: VirtualKey) -> &mut Self {
+        self.mappings.entry(key).or_default().push(virtual_key);
+        self
+    }
+
+    /// Removes all bindings for a physical key in this context.
+    pub fn unbind(&mut self, key: KeyCode) -> &mut Self {
+        self.mappings.remove(&key);
+        self
+    }
+
+    /// Returns the virtual keys this context binds to a physical key.
+    pub fn get(&self, key: KeyCode) -> Option<&[VirtualKey]> {
+        self.mappings.get(&key).map(|v| v.as_slice())
+    }
+
+    /// Returns the physical keys this context binds.
+    pub fn keys(&self) -> impl Iterator<Item = KeyCode> + '_ {
+        self.mappings.keys().copied()
+    }
+
+    /// Returns what happens to keys this context doesn't bind.
+    pub fn fallthrough(&self) -> Fallthrough {
+        self.fallthrough
+    }
+
+    /// Changes what happens to keys this context doesn't bind.
+    pub fn set_fallthrough(&mut self, fallthrough: Fallthrough) -> &mut Self {
+        self.fallthrough = fallthrough;
+        self
+    }
+}
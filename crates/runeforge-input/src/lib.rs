@@ -7,8 +7,14 @@
 //!
 //! *   **Action Mapping:** Bind keys to `VirtualKey`s (e.g., 'i' -> `Inventory`).
 //! *   **Input State:** Track pressed keys and mouse positions.
+//! *   **Mouse Gestures:** Double-clicks, drags and wheel scrolling from raw mouse input ([`pointer`]).
 //! *   **Presets:** Built-in support for common roguelike schemes (Vi-keys, WASD, Numpad).
 //! *   **Rebinding:** Easily change bindings at runtime.
+//! *   **Help Screens:** List bindings with readable key names for '?' screens and command palettes ([`help`]).
+//! *   **Diagonals:** Compose held cardinal keys (W+D) into diagonal moves ([`movement`]).
+//! *   **Contexts:** Push and pop keymap layers for menus, targeting and text prompts ([`keymap`]).
+//! *   **Macros:** Record input per frame and play it back for demos, tutorials and UI tests ([`recording`]).
+//! *   **Replays:** Record seeded input sessions and verify they replay to the same state ([`replay`]).
 //!
 //! # Usage
 //!
@@ -52,7 +58,8 @@
 //!
 //! This crate uses `runeforge_direction::Direction` which uses **mathematical coordinates**
 //! where North = +Y. For screen coordinates (origin top-left, Y increases downward),
-//! use the [`screen_delta`] helper function.
+//! use the [`screen_delta`] helper function. If the game world uses its own
+//! [`CoordinateSystem`], convert with [`world_delta`] instead.
 
 #![deny(missing_docs)]
 
@@ -60,8 +67,17 @@ use std::collections::{HashMap, HashSet};
 use winit::event::{KeyEvent, MouseButton as WinitMouseButton};
 use winit::keyboard::{KeyCode, PhysicalKey};
 
+use keymap::{Fallthrough, KeymapContext};
+
+pub mod help;
+pub mod keymap;
+pub mod movement;
+pub mod pointer;
+pub mod recording;
+pub mod replay;
+
 // Re-export Direction from runeforge-direction
-pub use runeforge_direction::prelude::Direction;
+pub use runeforge_direction::prelude::{CoordinateSystem, Direction};
 
 /// Convert a Direction to screen coordinates (Y increases downward).
 ///
@@ -82,9 +98,22 @@ pub use runeforge_direction::prelude::Direction;
 /// ```
 #[inline]
 pub fn screen_delta(dir: Direction) -> (i32, i32) {
-    let coord = dir.coord();
-    // Flip Y axis for screen coordinates
-    (coord.x, -coord.y)
+    world_delta(dir, CoordinateSystem::YDown)
+}
+
+/// Convert a Direction to a step in a world using `system`.
+///
+/// # Example
+///
+/// ```
+/// use runeforge_input::{world_delta, CoordinateSystem, Direction};
+///
+/// assert_eq!(world_delta(Direction::NORTH, CoordinateSystem::YUp), (0, 1));
+/// assert_eq!(world_delta(Direction::NORTH, CoordinateSystem::YDown), (0, -1));
+/// ```
+#[inline]
+pub fn world_delta(dir: Direction, system: CoordinateSystem) -> (i32, i32) {
+    dir.coord_in(system).into()
 }
 
 /// A virtual key representing a logical game action.
@@ -143,6 +172,27 @@ pub enum VirtualKey {
 
     /// Quit game (Ctrl+Q, Esc in menus)
     Quit,
+
+    /// A game-defined action, identified by a number the game chooses.
+    ///
+    /// Define the game's own actions as constants, bind them like any other
+    /// key and give them a help name with [`InputMap::name_custom`]:
+    ///
+    /// ```
+    /// use runeforge_input::{InputMap, VirtualKey};
+    /// use winit::keyboard::KeyCode;
+    ///
+    /// const CAST_SPELL_1: VirtualKey = VirtualKey::Custom(0);
+    /// const TOGGLE_MINIMAP: VirtualKey = VirtualKey::Custom(1);
+    ///
+    /// let mut map = InputMap::roguelike_default();
+    /// map.bind(KeyCode::Digit1, CAST_SPELL_1);
+    /// map.bind(KeyCode::KeyM, TOGGLE_MINIMAP);
+    /// map.name_custom(1, "Toggle minimap");
+    ///
+    /// assert_eq!(map.get(KeyCode::KeyM), Some(&[TOGGLE_MINIMAP][..]));
+    /// ```
+    Custom(u16),
 }
 
 /// Mouse button identifier.
@@ -208,6 +258,67 @@ pub enum InputEvent {
         button: MouseButton,
     },
 
+    /// Mouse button pressed twice in quick succession at (x, y) in pixels.
+    ///
+    /// Sent after the second [`MouseClick`](Self::MouseClick).
+    DoubleClick {
+        /// X coordinate in pixels
+        x: f64,
+        /// Y coordinate in pixels
+        y: f64,
+        /// Which button was double-clicked
+        button: MouseButton,
+    },
+
+    /// Scroll wheel moved, in lines. Positive `dy` scrolls up and positive
+    /// `dx` scrolls right.
+    MouseWheel {
+        /// Horizontal scroll in lines
+        dx: f64,
+        /// Vertical scroll in lines
+        dy: f64,
+    },
+
+    /// The mouse moved far enough with a button held to start a drag.
+    DragStart {
+        /// X coordinate where the button was pressed
+        x: f64,
+        /// Y coordinate where the button was pressed
+        y: f64,
+        /// Which button is held
+        button: MouseButton,
+    },
+
+    /// The mouse moved during a drag.
+    Drag {
+        /// Current X coordinate in pixels
+        x: f64,
+        /// Current Y coordinate in pixels
+        y: f64,
+        /// X coordinate where the drag started
+        origin_x: f64,
+        /// Y coordinate where the drag started
+        origin_y: f64,
+        /// Which button is held
+        button: MouseButton,
+    },
+
+    /// The button of a drag was released.
+    ///
+    /// Sent before the matching [`MouseRelease`](Self::MouseRelease).
+    DragEnd {
+        /// X coordinate where the button was released
+        x: f64,
+        /// Y coordinate where the button was released
+        y: f64,
+        /// X coordinate where the drag started
+        origin_x: f64,
+        /// Y coordinate where the drag started
+        origin_y: f64,
+        /// Which button was held
+        button: MouseButton,
+    },
+
     /// Window close requested
     Quit,
 }
@@ -215,8 +326,15 @@ pub enum InputEvent {
 /// Maps physical keyboard keys to virtual game actions.
 ///
 /// Supports multiple control schemes and allows custom key rebinding.
+/// Bindings made with [`bind`](Self::bind) are global; modal screens can
+/// layer [`KeymapContext`]s on top of them (see [`keymap`]).
 pub struct InputMap {
     mappings: HashMap<KeyCode, Vec<VirtualKey>>,
+    contexts: HashMap<String, KeymapContext>,
+    /// Names of the active contexts, top last.
+    stack: Vec<String>,
+    /// Help names of [`VirtualKey::Custom`] actions.
+    custom_names: HashMap<u16, String>,
 }
 
 impl InputMap {
@@ -224,6 +342,9 @@ impl InputMap {
     pub fn new() -> Self {
         Self {
             mappings: HashMap::new(),
+            contexts: HashMap::new(),
+            stack: Vec::new(),
+            custom_names: HashMap::new(),
         }
     }
 
@@ -325,22 +446,91 @@ impl InputMap {
         self.mappings.remove(&key);
     }
 
+    /// Names a [`VirtualKey::Custom`] action for help screens.
+    pub fn name_custom(&mut self, id: u16, name: impl Into<String>) {
+        self.custom_names.insert(id, name.into());
+    }
+
+    /// Returns the help name given to a custom action, if any.
+    pub fn custom_name(&self, id: u16) -> Option<&str> {
+        self.custom_names.get(&id).map(String::as_str)
+    }
+
+    /// Defines a context, or replaces the fallthrough of an existing one,
+    /// and returns it for binding.
+    pub fn define_context(
+        &mut self,
+        name: impl Into<String>,
+        fallthrough: Fallthrough,
+    ) -> &mut KeymapContext {
+        let context = self.contexts.entry(name.into()).or_default();
+        context.set_fallthrough(fallthrough);
+        context
+    }
+
+    /// Returns a defined context.
+    pub fn context(&self, name: &str) -> Option<&KeymapContext> {
+        self.contexts.get(name)
+    }
+
+    /// Returns a defined context for rebinding.
+    pub fn context_mut(&mut self, name: &str) -> Option<&mut KeymapContext> {
+        self.contexts.get_mut(name)
+    }
+
+    /// Makes a defined context the active one, on top of any already active.
+    ///
+    /// Returns `false` if no context has that name.
+    pub fn push_context(&mut self, name: &str) -> bool {
+        if !self.contexts.contains_key(name) {
+            return false;
+        }
+        self.stack.push(name.to_string());
+        true
+    }
+
+    /// Deactivates the top context and returns its name.
+    pub fn pop_context(&mut self) -> Option<String> {
+        self.stack.pop()
+    }
+
+    /// Returns the name of the top context, or `None` when only the global
+    /// bindings are active.
+    pub fn active_context(&self) -> Option<&str> {
+        self.stack.last().map(String::as_str)
+    }
+
     /// Map a winit `KeyEvent` to virtual keys.
     ///
-    /// Returns `None` if the key is not mapped.
+    /// Returns `None` if the key is not mapped in the active contexts.
     ///
     /// This method is typically called inside your winit event handler to
     /// translate physical key presses into logical game actions.
     pub fn map_key_event(&self, event: &KeyEvent) -> Option<&[VirtualKey]> {
         if let PhysicalKey::Code(keycode) = event.physical_key {
-            self.mappings.get(&keycode).map(|v| v.as_slice())
+            self.get(keycode)
         } else {
             None
         }
     }
 
     /// Get all virtual keys bound to a physical key.
+    ///
+    /// The key resolves in the top context first, then in the ones below as
+    /// long as each lets unbound keys fall through, and finally in the
+    /// global bindings.
     pub fn get(&self, key: KeyCode) -> Option<&[VirtualKey]> {
+        for name in self.stack.iter().rev() {
+            let Some(context) = self.contexts.get(name) else {
+                continue;
+            };
+            if let Some(keys) = context.get(key) {
+                return Some(keys);
+            }
+            if context.fallthrough() == Fallthrough::Block {
+                return None;
+            }
+        }
         self.mappings.get(&key).map(|v| v.as_slice())
     }
 }
@@ -356,6 +546,7 @@ pub struct InputState {
     pressed_keys: HashSet<VirtualKey>,
     mouse_pos: (f64, f64),
     mouse_buttons: HashSet<MouseButton>,
+    drag: Option<(MouseButton, (f64, f64))>,
 }
 
 impl InputState {
@@ -365,6 +556,7 @@ impl InputState {
             pressed_keys: HashSet::new(),
             mouse_pos: (0.0, 0.0),
             mouse_buttons: HashSet::new(),
+            drag: None,
         }
     }
 
@@ -397,7 +589,17 @@ impl InputState {
             InputEvent::MouseRelease { button, .. } => {
                 self.mouse_buttons.remove(button);
             }
-            InputEvent::Quit => {}
+            InputEvent::DragStart { x, y, button } => {
+                self.drag = Some((*button, (*x, *y)));
+            }
+            InputEvent::Drag { x, y, .. } => {
+                self.mouse_pos = (*x, *y);
+            }
+            InputEvent::DragEnd { x, y, .. } => {
+                self.mouse_pos = (*x, *y);
+                self.drag = None;
+            }
+            InputEvent::DoubleClick { .. } | InputEvent::MouseWheel { .. } | InputEvent::Quit => {}
         }
     }
 
@@ -406,11 +608,35 @@ impl InputState {
         self.pressed_keys.contains(&key)
     }
 
+    /// Returns the combined direction of every held movement key, e.g.
+    /// north-east while W and D are both down. Opposite keys cancel out.
+    ///
+    /// ```
+    /// use runeforge_input::{Direction, InputEvent, InputState, VirtualKey};
+    ///
+    /// let mut state = InputState::new();
+    /// state.update(&InputEvent::KeyPress(VirtualKey::Move(Direction::NORTH)));
+    /// state.update(&InputEvent::KeyPress(VirtualKey::Move(Direction::EAST)));
+    /// assert_eq!(state.held_direction(), Some(Direction::NORTH_EAST));
+    /// ```
+    pub fn held_direction(&self) -> Option<Direction> {
+        movement::compose(self.pressed_keys.iter().filter_map(|key| match key {
+            VirtualKey::Move(dir) => Some(*dir),
+            _ => None,
+        }))
+    }
+
     /// Check if a mouse button is currently pressed.
     pub fn is_mouse_pressed(&self, button: MouseButton) -> bool {
         self.mouse_buttons.contains(&button)
     }
 
+    /// Returns the held button and start position of the drag in progress,
+    /// if any.
+    pub fn drag(&self) -> Option<(MouseButton, (f64, f64))> {
+        self.drag
+    }
+
     /// Get the current mouse position in pixels.
     pub fn mouse_position(&self) -> (f64, f64) {
         self.mouse_pos
@@ -420,6 +646,7 @@ impl InputState {
     pub fn clear(&mut self) {
         self.pressed_keys.clear();
         self.mouse_buttons.clear();
+        self.drag = None;
     }
 }
 
@@ -442,6 +669,10 @@ mod tests {
         assert_eq!(screen_delta(Direction::WEST), (-1, 0));
         assert_eq!(screen_delta(Direction::NORTH_EAST), (1, -1));
         assert_eq!(screen_delta(Direction::SOUTH_WEST), (-1, 1));
+        assert_eq!(
+            world_delta(Direction::SOUTH_WEST, CoordinateSystem::YUp),
+            (-1, -1)
+        );
     }
 
     #[test]
@@ -558,4 +789,37 @@ mod tests {
         map.unbind(KeyCode::Space);
         assert!(map.get(KeyCode::Space).is_none());
     }
+
+    #[test]
+    fn test_context_stack_resolution() {
+        let mut map = InputMap::roguelike_default();
+        map.define_context("menu", Fallthrough::Unbound)
+            .bind(KeyCode::KeyJ, VirtualKey::Move(Direction::SOUTH))
+            .bind(KeyCode::KeyD, VirtualKey::Drop);
+        map.define_context("text", Fallthrough::Block)
+            .bind(KeyCode::Enter, VirtualKey::Confirm);
+
+        assert!(!map.push_context("missing"));
+        assert!(map.push_context("menu"));
+        assert_eq!(map.get(KeyCode::KeyD), Some(&[VirtualKey::Drop][..]));
+        assert_eq!(map.get(KeyCode::KeyI), Some(&[VirtualKey::Inventory][..]));
+
+        assert!(map.push_context("text"));
+        assert_eq!(map.active_context(), Some("text"));
+        assert_eq!(map.get(KeyCode::KeyD), None);
+        assert_eq!(map.get(KeyCode::Enter), Some(&[VirtualKey::Confirm][..]));
+
+        map.context_mut("text")
+            .unwrap()
+            .set_fallthrough(Fallthrough::Unbound);
+        assert_eq!(map.get(KeyCode::KeyD), Some(&[VirtualKey::Drop][..]));
+
+        assert_eq!(map.pop_context().as_deref(), Some("text"));
+        assert_eq!(map.pop_context().as_deref(), Some("menu"));
+        assert_eq!(map.pop_context(), None);
+        assert_eq!(
+            map.get(KeyCode::KeyD),
+            Some(&[VirtualKey::Move(Direction::EAST)][..])
+        );
+    }
 }
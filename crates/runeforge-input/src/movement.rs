@@ -0,0 +1,276 @@
+//! Composing held cardinal keys into diagonal moves.
+//!
+//! Keyboards without a numpad have no diagonal keys, so WASD and arrow-key
+//! players expect W+D to move north-east. With a plain press model the two
+//! keys arrive as two separate cardinal moves. A [`DiagonalComposer`] holds
+//! a lone cardinal press back for a short grace window: if a perpendicular
+//! key joins it in time, the pair becomes one diagonal move; otherwise the
+//! cardinal move goes through on its own (on release or when the window
+//! closes, whichever comes first).
+//!
+//! For real-time movement, [`InputState::held_direction`](crate::InputState::held_direction)
+//! gives the combined direction of every held movement key.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_input::movement::DiagonalComposer;
+//! use runeforge_input::{Direction, InputEvent, VirtualKey};
+//! use web_time::{Duration, Instant};
+//!
+//! let mut composer = DiagonalComposer::new(Duration::from_millis(80));
+//! let t = Instant::now();
+//!
+//! let w = InputEvent::KeyPress(VirtualKey::Move(Direction::NORTH));
+//! let d = InputEvent::KeyPress(VirtualKey::Move(Direction::EAST));
+//! assert_eq!(composer.handle(&w, t), None);
+//! assert_eq!(composer.handle(&d, t + Duration::from_millis(30)), Some(Direction::NORTH_EAST));
+//!
+//! // A lone key moves once the grace window closes
+//! let up = InputEvent::KeyRelease(VirtualKey::Move(Direction::EAST));
+//! composer.handle(&up, t + Duration::from_millis(200));
+//! let later = t + Duration::from_secs(1);
+//! composer.handle(&InputEvent::KeyRelease(VirtualKey::Move(Direction::NORTH)), later);
+//! assert_eq!(composer.handle(&d, later), None);
+//! assert_eq!(composer.update(later + Duration::from_millis(80)), Some(Direction::EAST));
+//! ```
+
+use runeforge_direction::prelude::IVec2;
+use web_time::{Duration, Instant};
+
+use crate::{Direction, InputEvent, VirtualKey};
+
+/// Combines cardinal movement keys pressed close together into diagonals.
+#[derive(Debug, Clone)]
+pub struct DiagonalComposer {
+    grace: Duration,
+    /// Cardinal movement keys currently held.
+    held: Vec<Direction>,
+    /// A lone cardinal press waiting for a partner.
+    pending: Option<(Direction, Instant)>,
+    /// A move decided while another was being returned, handed out by the
+    /// next [`update`](Self::update).
+    ready: Option<Direction>,
+}
+
+impl Default for DiagonalComposer {
+    /// An 80 ms grace window.
+    fn default() -> Self {
+        Self::new(Duration::from_millis(80))
+    }
+}
+
+impl DiagonalComposer {
+    /// Creates a composer that waits up to `grace` for a second key. A zero
+    /// window still composes keys that are already held, but never delays.
+    pub fn new(grace: Duration) -> Self {
+        Self {
+            grace,
+            held: Vec::new(),
+            pending: None,
+            ready: None,
+        }
+    }
+
+    /// Returns the grace window.
+    pub fn grace(&self) -> Duration {
+        self.grace
+    }
+
+    /// Changes the grace window.
+    pub fn set_grace(&mut self, grace: Duration) {
+        self.grace = grace;
+    }
+
+    /// Returns true while a press is being held back.
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some() || self.ready.is_some()
+    }
+
+    /// Handles a movement key going down, returning the move to make now.
+    ///
+    /// Diagonal keys move at once. A cardinal key moves diagonally at once
+    /// if a perpendicular key is already held, and otherwise waits for the
+    /// grace window. A waiting press that isn't joined by a perpendicular
+    /// key is moved first when another key arrives.
+    pub fn press(&mut self, dir: Direction, now: Instant) -> Option<Direction> {
+        if !dir.is_cardinal() {
+            return Some(dir);
+        }
+        if !self.held.contains(&dir) {
+            self.held.push(dir);
+        }
+        if let Some(partner) = self.held.iter().copied().find(|&h| perpendicular(h, dir)) {
+            self.pending = None;
+            return Some(partner + dir);
+        }
+        if self.grace.is_zero() {
+            return Some(dir);
+        }
+        // Another lone press overrides the waiting one; move that first.
+        let flushed = self.pending.take().map(|(d, _)| d);
+        self.pending = Some((dir, now));
+        flushed
+    }
+
+    /// Handles a movement key going up. A press released before its window
+    /// closed was a tap, and moves now.
+    pub fn release(&mut self, dir: Direction) -> Option<Direction> {
+        self.held.retain(|&h| h != dir);
+        match self.pending {
+            Some((pending, _)) if pending == dir => {
+                self.pending = None;
+                Some(dir)
+            }
+            _ => None,
+        }
+    }
+
+    /// Moves a waiting press whose grace window has closed. Call every
+    /// frame.
+    pub fn update(&mut self, now: Instant) -> Option<Direction> {
+        if let Some(dir) = self.ready.take() {
+            return Some(dir);
+        }
+        match self.pending {
+            Some((dir, at)) if now.saturating_duration_since(at) >= self.grace => {
+                self.pending = None;
+                Some(dir)
+            }
+            _ => None,
+        }
+    }
+
+    /// Feeds an input event through [`press`](Self::press),
+    /// [`release`](Self::release) or [`update`](Self::update). Events other
+    /// than movement keys only advance the clock.
+    ///
+    /// If a press arrives after the waiting one's window closed, the waiting
+    /// move is returned and the new press's move comes out of the next
+    /// [`update`](Self::update).
+    pub fn handle(&mut self, event: &InputEvent, now: Instant) -> Option<Direction> {
+        match event {
+            InputEvent::KeyPress(VirtualKey::Move(dir)) => {
+                let flushed = self.update(now);
+                let moved = self.press(*dir, now);
+                match flushed {
+                    Some(flushed) => {
+                        self.ready = self.ready.or(moved);
+                        Some(flushed)
+                    }
+                    None => moved,
+                }
+            }
+            InputEvent::KeyRelease(VirtualKey::Move(dir)) => self.release(*dir),
+            _ => self.update(now),
+        }
+    }
+
+    /// Forgets held keys and any waiting press, e.g. when focus is lost.
+    pub fn clear(&mut self) {
+        self.held.clear();
+        self.pending = None;
+        self.ready = None;
+    }
+}
+
+/// Combines movement directions, letting opposite ones cancel out.
+///
+/// Returns `None` when nothing is left, e.g. for north plus south.
+pub fn compose(dirs: impl IntoIterator<Item = Direction>) -> Option<Direction> {
+    let sum = dirs.into_iter().fold(IVec2::ZERO, |acc, d| acc + d.coord());
+    let dir = Direction::from_coord(sum.clamp(IVec2::NEG_ONE, IVec2::ONE));
+    (dir != Direction::NONE).then_some(dir)
+}
+
+fn perpendicular(a: Direction, b: Direction) -> bool {
+    a.coord().dot(b.coord()) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn test_pairs_within_grace_compose() {
+        let mut composer = DiagonalComposer::new(50 * MS);
+        let t = Instant::now();
+        assert_eq!(composer.press(Direction::SOUTH, t), None);
+        assert!(composer.is_pending());
+        assert_eq!(composer.update(t + 20 * MS), None);
+        assert_eq!(
+            composer.press(Direction::WEST, t + 40 * MS),
+            Some(Direction::SOUTH_WEST)
+        );
+        // Key repeat while both are held keeps moving diagonally
+        assert_eq!(
+            composer.press(Direction::WEST, t + 500 * MS),
+            Some(Direction::SOUTH_WEST)
+        );
+        assert_eq!(composer.release(Direction::SOUTH), None);
+        assert_eq!(composer.press(Direction::WEST, t + 600 * MS), None);
+    }
+
+    #[test]
+    fn test_lone_presses_go_through() {
+        let mut composer = DiagonalComposer::new(50 * MS);
+        let t = Instant::now();
+        composer.press(Direction::NORTH, t);
+        assert_eq!(composer.release(Direction::NORTH), Some(Direction::NORTH));
+        assert_eq!(composer.update(t + 100 * MS), None);
+
+        composer.press(Direction::EAST, t);
+        assert_eq!(composer.update(t + 50 * MS), Some(Direction::EAST));
+        assert_eq!(composer.release(Direction::EAST), None);
+
+        // Opposite keys don't compose; the first moves when the second lands
+        composer.press(Direction::EAST, t);
+        assert_eq!(
+            composer.press(Direction::WEST, t + MS),
+            Some(Direction::EAST)
+        );
+        assert_eq!(composer.update(t + 60 * MS), Some(Direction::WEST));
+
+        assert_eq!(
+            composer.press(Direction::NORTH_WEST, t),
+            Some(Direction::NORTH_WEST)
+        );
+        let mut instant = DiagonalComposer::new(Duration::ZERO);
+        assert_eq!(instant.press(Direction::SOUTH, t), Some(Direction::SOUTH));
+    }
+
+    #[test]
+    fn test_press_after_grace_is_kept() {
+        let mut composer = DiagonalComposer::new(50 * MS);
+        let t = Instant::now();
+        let press = |dir| InputEvent::KeyPress(VirtualKey::Move(dir));
+        assert_eq!(composer.handle(&press(Direction::NORTH), t), None);
+
+        // The window closed unnoticed; the late key still counts as held.
+        assert_eq!(
+            composer.handle(&press(Direction::EAST), t + 100 * MS),
+            Some(Direction::NORTH)
+        );
+        assert_eq!(composer.update(t + 101 * MS), Some(Direction::NORTH_EAST));
+        assert!(!composer.is_pending());
+        assert_eq!(composer.release(Direction::NORTH), None);
+        assert_eq!(composer.handle(&press(Direction::EAST), t + 200 * MS), None);
+        assert_eq!(composer.update(t + 250 * MS), Some(Direction::EAST));
+    }
+
+    #[test]
+    fn test_compose() {
+        assert_eq!(
+            compose([Direction::NORTH, Direction::EAST]),
+            Some(Direction::NORTH_EAST)
+        );
+        assert_eq!(compose([Direction::NORTH, Direction::SOUTH]), None);
+        assert_eq!(
+            compose([Direction::NORTH, Direction::NORTH_WEST, Direction::EAST]),
+            Some(Direction::NORTH)
+        );
+        assert_eq!(compose([]), None);
+    }
+}
@@ -0,0 +1,345 @@
+//! Turning raw mouse input into clicks, double-clicks, drags and scrolling.
+//!
+//! Windowing backends report low-level facts: the cursor moved, a button
+//! went down or up, the wheel turned. A [`PointerTracker`] keeps the little
+//! state needed to turn those into the [`InputEvent`]s a game wants:
+//! - [`DoubleClick`](InputEvent::DoubleClick) when the same button is
+//!   pressed twice within [`PointerConfig::double_click_time`]
+//! - [`DragStart`](InputEvent::DragStart), [`Drag`](InputEvent::Drag) and
+//!   [`DragEnd`](InputEvent::DragEnd) once a held button moves further than
+//!   [`PointerConfig::drag_threshold`]
+//! - [`MouseWheel`](InputEvent::MouseWheel) in lines, whatever unit the
+//!   device scrolls in
+//!
+//! [`PointerTracker::handle_window_event`] converts winit events directly.
+//! Other backends can call [`cursor_moved`](PointerTracker::cursor_moved),
+//! [`button_pressed`](PointerTracker::button_pressed),
+//! [`button_released`](PointerTracker::button_released) and
+//! [`wheel`](PointerTracker::wheel) themselves.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_input::pointer::PointerTracker;
+//! use runeforge_input::{InputEvent, MouseButton};
+//! use web_time::{Duration, Instant};
+//!
+//! let mut pointer = PointerTracker::new();
+//! let start = Instant::now();
+//!
+//! pointer.cursor_moved(10.0, 10.0);
+//! pointer.button_pressed(MouseButton::Left, start);
+//! pointer.button_released(MouseButton::Left);
+//! let events = pointer.button_pressed(MouseButton::Left, start + Duration::from_millis(150));
+//! assert!(matches!(events.last(), Some(InputEvent::DoubleClick { .. })));
+//! pointer.button_released(MouseButton::Left);
+//!
+//! // Pan the map with the right button
+//! pointer.button_pressed(MouseButton::Right, start + Duration::from_secs(1));
+//! let events = pointer.cursor_moved(40.0, 10.0);
+//! assert!(matches!(events[1], InputEvent::DragStart { x: 10.0, .. }));
+//! assert!(matches!(events[2], InputEvent::Drag { x: 40.0, .. }));
+//! ```
+
+use web_time::{Duration, Instant};
+use winit::event::{ElementState, MouseScrollDelta, WindowEvent};
+
+use crate::{InputEvent, MouseButton};
+
+/// Timing and distance thresholds for pointer gestures.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointerConfig {
+    /// Longest gap between two presses that still counts as a double-click.
+    pub double_click_time: Duration,
+    /// Furthest two presses may be apart, in pixels, to double-click.
+    pub double_click_distance: f64,
+    /// How far, in pixels, a held button must move before a drag starts.
+    pub drag_threshold: f64,
+    /// Pixels per line, for devices that scroll in pixels (touchpads).
+    pub pixels_per_line: f64,
+}
+
+impl Default for PointerConfig {
+    /// 400 ms and 4 px for double-clicks, 4 px to start a drag, 20 px per
+    /// scrolled line.
+    fn default() -> Self {
+        Self {
+            double_click_time: Duration::from_millis(400),
+            double_click_distance: 4.0,
+            drag_threshold: 4.0,
+            pixels_per_line: 20.0,
+        }
+    }
+}
+
+impl PointerConfig {
+    /// Creates the default thresholds.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the longest gap between the presses of a double-click.
+    pub fn with_double_click_time(mut self, time: Duration) -> Self {
+        self.double_click_time = time;
+        self
+    }
+
+    /// Sets how far apart the presses of a double-click may be.
+    pub fn with_double_click_distance(mut self, distance: f64) -> Self {
+        self.double_click_distance = distance;
+        self
+    }
+
+    /// Sets how far a held button must move before a drag starts.
+    pub fn with_drag_threshold(mut self, distance: f64) -> Self {
+        self.drag_threshold = distance;
+        self
+    }
+
+    /// Sets how many pixels of touchpad scrolling make one line.
+    pub fn with_pixels_per_line(mut self, pixels: f64) -> Self {
+        self.pixels_per_line = pixels;
+        self
+    }
+}
+
+/// A button being held, and whether it has become a drag.
+#[derive(Debug, Clone, Copy)]
+struct Held {
+    button: MouseButton,
+    origin: (f64, f64),
+    dragging: bool,
+}
+
+/// Turns raw mouse input into high-level [`InputEvent`]s.
+#[derive(Debug, Clone, Default)]
+pub struct PointerTracker {
+    config: PointerConfig,
+    pos: (f64, f64),
+    held: Option<Held>,
+    last_press: Option<(MouseButton, Instant, (f64, f64))>,
+}
+
+impl PointerTracker {
+    /// Creates a tracker with the default thresholds.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a tracker with custom thresholds.
+    pub fn with_config(config: PointerConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the thresholds in use.
+    pub fn config(&self) -> &PointerConfig {
+        &self.config
+    }
+
+    /// Returns the last known cursor position in pixels.
+    pub fn position(&self) -> (f64, f64) {
+        self.pos
+    }
+
+    /// Returns true while a drag is in progress.
+    pub fn is_dragging(&self) -> bool {
+        self.held.is_some_and(|h| h.dragging)
+    }
+
+    /// Handles the cursor moving to `(x, y)`.
+    ///
+    /// Always yields a [`MouseMove`](InputEvent::MouseMove), followed by drag
+    /// events while a button is held.
+    pub fn cursor_moved(&mut self, x: f64, y: f64) -> Vec<InputEvent> {
+        self.pos = (x, y);
+        let mut events = vec![InputEvent::MouseMove { x, y }];
+        if let Some(held) = &mut self.held {
+            let (ox, oy) = held.origin;
+            if !held.dragging && distance((ox, oy), (x, y)) > self.config.drag_threshold {
+                held.dragging = true;
+                events.push(InputEvent::DragStart {
+                    x: ox,
+                    y: oy,
+                    button: held.button,
+                });
+            }
+            if held.dragging {
+                events.push(InputEvent::Drag {
+                    x,
+                    y,
+                    origin_x: ox,
+                    origin_y: oy,
+                    button: held.button,
+                });
+            }
+        }
+        events
+    }
+
+    /// Handles a button going down at `now`.
+    ///
+    /// Yields a [`MouseClick`](InputEvent::MouseClick), followed by a
+    /// [`DoubleClick`](InputEvent::DoubleClick) if it completes one. A third
+    /// quick press starts a new pair rather than double-clicking again.
+    pub fn button_pressed(&mut self, button: MouseButton, now: Instant) -> Vec<InputEvent> {
+        let (x, y) = self.pos;
+        let mut events = vec![InputEvent::MouseClick { x, y, button }];
+        if self.held.is_none() {
+            self.held = Some(Held {
+                button,
+                origin: self.pos,
+                dragging: false,
+            });
+        }
+
+        let double = self.last_press.is_some_and(|(last, time, pos)| {
+            last == button
+                && now.saturating_duration_since(time) <= self.config.double_click_time
+                && distance(pos, self.pos) <= self.config.double_click_distance
+        });
+        if double {
+            events.push(InputEvent::DoubleClick { x, y, button });
+            self.last_press = None;
+        } else {
+            self.last_press = Some((button, now, self.pos));
+        }
+        events
+    }
+
+    /// Handles a button going up.
+    ///
+    /// Yields a [`DragEnd`](InputEvent::DragEnd) if the button was dragging,
+    /// then a [`MouseRelease`](InputEvent::MouseRelease).
+    pub fn button_released(&mut self, button: MouseButton) -> Vec<InputEvent> {
+        let (x, y) = self.pos;
+        let mut events = Vec::new();
+        if let Some(held) = self.held.filter(|h| h.button == button) {
+            if held.dragging {
+                events.push(InputEvent::DragEnd {
+                    x,
+                    y,
+                    origin_x: held.origin.0,
+                    origin_y: held.origin.1,
+                    button,
+                });
+                // A drag isn't the first half of a double-click
+                self.last_press = None;
+            }
+            self.held = None;
+        }
+        events.push(InputEvent::MouseRelease { x, y, button });
+        events
+    }
+
+    /// Converts a winit scroll delta into a [`MouseWheel`](InputEvent::MouseWheel)
+    /// in lines.
+    pub fn wheel(&self, delta: MouseScrollDelta) -> InputEvent {
+        let (dx, dy) = match delta {
+            MouseScrollDelta::LineDelta(dx, dy) => (dx as f64, dy as f64),
+            MouseScrollDelta::PixelDelta(pos) => {
+                let per_line = self.config.pixels_per_line.max(f64::EPSILON);
+                (pos.x / per_line, pos.y / per_line)
+            }
+        };
+        InputEvent::MouseWheel { dx, dy }
+    }
+
+    /// Converts a winit window event, returning the events it produces.
+    ///
+    /// Mouse events are translated; every other event yields nothing.
+    pub fn handle_window_event(&mut self, event: &WindowEvent) -> Vec<InputEvent> {
+        match event {
+            WindowEvent::CursorMoved { position, .. } => self.cursor_moved(position.x, position.y),
+            WindowEvent::MouseInput { state, button, .. } => match state {
+                ElementState::Pressed => self.button_pressed((*button).into(), Instant::now()),
+                ElementState::Released => self.button_released((*button).into()),
+            },
+            WindowEvent::MouseWheel { delta, .. } => vec![self.wheel(*delta)],
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winit::dpi::PhysicalPosition;
+
+    #[test]
+    fn test_double_click_timing() {
+        let mut pointer = PointerTracker::with_config(
+            PointerConfig::new().with_double_click_time(Duration::from_millis(200)),
+        );
+        let t = Instant::now();
+        let doubles = |events: Vec<InputEvent>| {
+            events
+                .iter()
+                .filter(|e| matches!(e, InputEvent::DoubleClick { .. }))
+                .count()
+        };
+        assert_eq!(doubles(pointer.button_pressed(MouseButton::Left, t)), 0);
+        let later = t + Duration::from_millis(300);
+        assert_eq!(doubles(pointer.button_pressed(MouseButton::Left, later)), 0);
+        let quick = later + Duration::from_millis(100);
+        assert_eq!(doubles(pointer.button_pressed(MouseButton::Left, quick)), 1);
+        let third = quick + Duration::from_millis(50);
+        assert_eq!(doubles(pointer.button_pressed(MouseButton::Left, third)), 0);
+        let other = third + Duration::from_millis(50);
+        assert_eq!(
+            doubles(pointer.button_pressed(MouseButton::Right, other)),
+            0
+        );
+    }
+
+    #[test]
+    fn test_drag_lifecycle() {
+        let mut pointer = PointerTracker::new();
+        pointer.cursor_moved(100.0, 100.0);
+        pointer.button_pressed(MouseButton::Middle, Instant::now());
+        assert_eq!(pointer.cursor_moved(102.0, 101.0).len(), 1);
+        assert!(!pointer.is_dragging());
+
+        let events = pointer.cursor_moved(110.0, 100.0);
+        assert_eq!(events.len(), 3);
+        assert!(pointer.is_dragging());
+        assert_eq!(pointer.cursor_moved(120.0, 90.0).len(), 2);
+
+        // Releasing another button doesn't end the drag
+        assert_eq!(pointer.button_released(MouseButton::Left).len(), 1);
+        let events = pointer.button_released(MouseButton::Middle);
+        assert_eq!(
+            events[0],
+            InputEvent::DragEnd {
+                x: 120.0,
+                y: 90.0,
+                origin_x: 100.0,
+                origin_y: 100.0,
+                button: MouseButton::Middle,
+            }
+        );
+        assert!(!pointer.is_dragging());
+        assert_eq!(pointer.cursor_moved(0.0, 0.0).len(), 1);
+    }
+
+    #[test]
+    fn test_wheel_units() {
+        let pointer = PointerTracker::new();
+        assert_eq!(
+            pointer.wheel(MouseScrollDelta::LineDelta(0.0, -2.0)),
+            InputEvent::MouseWheel { dx: 0.0, dy: -2.0 }
+        );
+        assert_eq!(
+            pointer.wheel(MouseScrollDelta::PixelDelta(PhysicalPosition::new(
+                10.0, 40.0
+            ))),
+            InputEvent::MouseWheel { dx: 0.5, dy: 2.0 }
+        );
+    }
+}
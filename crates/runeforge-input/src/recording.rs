@@ -0,0 +1,349 @@
+//! Frame-indexed input recording and playback.
+//!
+//! An [`InputRecorder`] captures [`InputEvent`]s tagged with the frame they
+//! arrived in, and an [`InputPlayer`] feeds them back frame by frame, into an
+//! [`InputState`] or straight to the game. Unlike a [`Replay`](crate::replay::Replay),
+//! which drives a whole seeded session by wall-clock time, a recording is
+//! tied to frames, so playback is exact at any frame rate. That suits demo
+//! modes, tutorials that show a move sequence, and UI tests that script
+//! input.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_input::recording::{InputPlayer, InputRecorder};
+//! use runeforge_input::{InputEvent, InputState, VirtualKey};
+//!
+//! let mut recorder = InputRecorder::new();
+//! recorder.record(InputEvent::KeyPress(VirtualKey::Inventory));
+//! recorder.next_frame();
+//! recorder.next_frame();
+//! recorder.record(InputEvent::KeyRelease(VirtualKey::Inventory));
+//! let recording = recorder.finish();
+//! assert_eq!(recording.len_frames(), 3);
+//!
+//! let mut player = InputPlayer::new(recording);
+//! let mut state = InputState::new();
+//! player.step_into(&mut state);
+//! assert!(state.is_pressed(VirtualKey::Inventory));
+//! player.step_into(&mut state);
+//! player.step_into(&mut state);
+//! assert!(!state.is_pressed(VirtualKey::Inventory));
+//! assert!(player.is_finished());
+//! ```
+
+use crate::replay::{decode_event, encode_event, ReplayError};
+use crate::{InputEvent, InputState};
+
+const HEADER: &str = "runeforge-input";
+const VERSION: u32 = 1;
+
+/// An input event and the frame it arrived in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedEvent {
+    /// Zero-based frame index
+    pub frame: u64,
+    /// The input event
+    pub event: InputEvent,
+}
+
+/// A recorded sequence of input events, ordered by frame.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputRecording {
+    events: Vec<RecordedEvent>,
+    frames: u64,
+}
+
+impl InputRecording {
+    /// Creates an empty recording.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends an event at `frame`, extending the recording if needed.
+    ///
+    /// Events must be pushed in frame order; an event for an earlier frame
+    /// than the last one is moved up to the last frame.
+    pub fn push(&mut self, frame: u64, event: InputEvent) {
+        let frame = frame.max(self.events.last().map_or(0, |e| e.frame));
+        self.frames = self.frames.max(frame + 1);
+        self.events.push(RecordedEvent { frame, event });
+    }
+
+    /// Returns the recorded events in order.
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+
+    /// Returns the events recorded in one frame.
+    pub fn events_at(&self, frame: u64) -> impl Iterator<Item = &InputEvent> {
+        let start = self.events.partition_point(|e| e.frame < frame);
+        self.events[start..]
+            .iter()
+            .take_while(move |e| e.frame == frame)
+            .map(|e| &e.event)
+    }
+
+    /// Returns how many frames the recording lasts, including trailing
+    /// frames without input.
+    pub fn len_frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Returns true if no events were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Writes the recording in a line-based text format, using the same
+    /// event syntax as replays.
+    pub fn to_text(&self) -> String {
+        let mut out = format!("{} {}\nframes {}\n", HEADER, VERSION, self.frames);
+        for recorded in &self.events {
+            out.push_str(&format!(
+                "#{} {}\n",
+                recorded.frame,
+                encode_event(&recorded.event)
+            ));
+        }
+        out
+    }
+
+    /// Reads a recording from its text format. Blank lines and lines
+    /// starting with `;` are ignored.
+    pub fn from_text(text: &str) -> Result<Self, ReplayError> {
+        let mut recording = InputRecording::new();
+        let mut saw_header = false;
+
+        for (idx, line) in text.lines().enumerate() {
+            let line_no = idx + 1;
+            let err = |message: &str| ReplayError::Parse {
+                line: line_no,
+                message: message.to_string(),
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let first = words.next().unwrap_or_default();
+
+            if !saw_header {
+                if first != HEADER {
+                    return Err(err("missing recording header"));
+                }
+                let version = words
+                    .next()
+                    .and_then(|v| v.parse().ok())
+                    .ok_or_else(|| err("missing version"))?;
+                if version != VERSION {
+                    return Err(ReplayError::UnsupportedVersion(version));
+                }
+                saw_header = true;
+                continue;
+            }
+
+            if first == "frames" {
+                let value = words.next().and_then(|v| v.parse().ok());
+                let frames: u64 = value.ok_or_else(|| err("invalid frame count"))?;
+                recording.frames = recording.frames.max(frames);
+                continue;
+            }
+            let frame = first
+                .strip_prefix('#')
+                .and_then(|f| f.parse().ok())
+                .ok_or_else(|| err("expected '#<frame>'"))?;
+            if recording.events.last().is_some_and(|e| e.frame > frame) {
+                return Err(err("frames out of order"));
+            }
+            let words: Vec<&str> = words.collect();
+            let event = decode_event(&words).map_err(|m| err(&m))?;
+            recording.push(frame, event);
+        }
+        Ok(recording)
+    }
+}
+
+/// Captures input events with the frame they arrived in.
+#[derive(Debug, Clone, Default)]
+pub struct InputRecorder {
+    recording: InputRecording,
+    frame: u64,
+}
+
+impl InputRecorder {
+    /// Starts recording at frame 0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an event in the current frame.
+    pub fn record(&mut self, event: InputEvent) {
+        self.recording.push(self.frame, event);
+    }
+
+    /// Ends the current frame. Call once per game frame, even without input.
+    pub fn next_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    /// Returns the index of the frame being recorded.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Returns the recording so far.
+    pub fn recording(&self) -> &InputRecording {
+        &self.recording
+    }
+
+    /// Stops recording. The recording covers every ended frame, plus the
+    /// current one if it has input.
+    pub fn finish(mut self) -> InputRecording {
+        self.recording.frames = self.recording.frames.max(self.frame);
+        self.recording
+    }
+}
+
+/// Plays an [`InputRecording`] back one frame at a time.
+#[derive(Debug, Clone)]
+pub struct InputPlayer {
+    recording: InputRecording,
+    frame: u64,
+    looping: bool,
+}
+
+impl InputPlayer {
+    /// Creates a player positioned at frame 0.
+    pub fn new(recording: InputRecording) -> Self {
+        Self {
+            recording,
+            frame: 0,
+            looping: false,
+        }
+    }
+
+    /// Restarts from frame 0 after the last frame instead of stopping, as
+    /// attract-mode demos do.
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Returns the recording being played.
+    pub fn recording(&self) -> &InputRecording {
+        &self.recording
+    }
+
+    /// Returns the index of the next frame to play.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Returns true once every frame has been played. A looping player never
+    /// finishes.
+    pub fn is_finished(&self) -> bool {
+        !self.looping && self.frame >= self.recording.frames
+    }
+
+    /// Jumps back to frame 0.
+    pub fn rewind(&mut self) {
+        self.frame = 0;
+    }
+
+    /// Plays one frame, returning its events. Returns nothing once finished.
+    pub fn step(&mut self) -> impl Iterator<Item = &InputEvent> {
+        if self.looping && self.frame >= self.recording.frames {
+            self.frame = 0;
+        }
+        // A finished player stays on the frame past the end, which has no
+        // events.
+        let frame = self.frame;
+        self.frame = (frame + 1).min(self.recording.frames);
+        self.recording.events_at(frame)
+    }
+
+    /// Plays one frame into `state`, returning how many events were applied.
+    ///
+    /// When a looping player wraps around, `state` is cleared first so keys
+    /// held at the end of the recording don't stay pressed.
+    pub fn step_into(&mut self, state: &mut InputState) -> usize {
+        if self.looping && self.frame >= self.recording.frames {
+            state.clear();
+        }
+        let mut count = 0;
+        for event in self.step() {
+            state.update(event);
+            count += 1;
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Direction, MouseButton, VirtualKey};
+
+    fn sample() -> InputRecording {
+        let mut recorder = InputRecorder::new();
+        recorder.record(InputEvent::KeyPress(VirtualKey::Move(Direction::NORTH)));
+        recorder.next_frame();
+        recorder.record(InputEvent::MouseClick {
+            x: 4.0,
+            y: 8.5,
+            button: MouseButton::Left,
+        });
+        recorder.record(InputEvent::KeyRelease(VirtualKey::Move(Direction::NORTH)));
+        recorder.next_frame();
+        recorder.next_frame();
+        recorder.finish()
+    }
+
+    #[test]
+    fn test_recorder_tracks_frames() {
+        let recording = sample();
+        assert_eq!(recording.len_frames(), 3);
+        assert_eq!(recording.events().len(), 3);
+        assert_eq!(recording.events_at(1).count(), 2);
+        assert_eq!(recording.events_at(2).count(), 0);
+    }
+
+    #[test]
+    fn test_player_steps_and_loops() {
+        let mut player = InputPlayer::new(sample());
+        let mut state = InputState::new();
+        assert_eq!(player.step_into(&mut state), 1);
+        assert!(state.is_pressed(VirtualKey::Move(Direction::NORTH)));
+        assert_eq!(player.step_into(&mut state), 2);
+        assert!(state.is_mouse_pressed(MouseButton::Left));
+        assert_eq!(player.step_into(&mut state), 0);
+        assert!(player.is_finished());
+        assert_eq!(player.step().count(), 0);
+
+        let mut looping = InputPlayer::new(sample()).with_looping(true);
+        let counts: Vec<usize> = (0..6).map(|_| looping.step().count()).collect();
+        assert_eq!(counts, vec![1, 2, 0, 1, 2, 0]);
+        assert!(!looping.is_finished());
+
+        looping.step_into(&mut state);
+        assert!(!state.is_mouse_pressed(MouseButton::Left));
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let recording = sample();
+        let text = recording.to_text();
+        assert!(text.starts_with("runeforge-input 1\nframes 3\n#0 press move 0 1\n"));
+        assert_eq!(InputRecording::from_text(&text).unwrap(), recording);
+
+        assert!(matches!(
+            InputRecording::from_text("runeforge-input 1\n#3 quit\n#1 quit\n"),
+            Err(ReplayError::Parse { line: 3, .. })
+        ));
+        assert_eq!(
+            InputRecording::from_text("runeforge-input 9\n"),
+            Err(ReplayError::UnsupportedVersion(9))
+        );
+    }
+}
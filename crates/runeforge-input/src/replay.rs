@@ -0,0 +1,569 @@
+//! Deterministic input recording and replay.
+//!
+//! A [`Replay`] stores the seed a game started with, every [`InputEvent`] it
+//! received with a timestamp, and optionally a hash of the final game state.
+//! Replaying the events into a freshly seeded game must reproduce the same
+//! hash; if it doesn't, something in the game isn't deterministic.
+//!
+//! Replays are saved as plain text so they can be attached to bug reports
+//! ("seed 123 + this file crashes") and checked into a repository as
+//! regression tests.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_input::replay::{Replay, ReplayTarget};
+//! use runeforge_input::{Direction, InputEvent, VirtualKey};
+//!
+//! struct Game {
+//!     x: i32,
+//! }
+//!
+//! impl ReplayTarget for Game {
+//!     fn handle_input(&mut self, _time: f64, event: &InputEvent) {
+//!         if let InputEvent::KeyPress(VirtualKey::Move(dir)) = event {
+//!             self.x += dir.coord().x;
+//!         }
+//!     }
+//!
+//!     fn state_hash(&self) -> u64 {
+//!         self.x as u64
+//!     }
+//! }
+//!
+//! let mut replay = Replay::new(123);
+//! replay.record(0.0, InputEvent::KeyPress(VirtualKey::Move(Direction::EAST)));
+//! replay.record(0.5, InputEvent::KeyPress(VirtualKey::Move(Direction::EAST)));
+//! replay.set_final_hash(2);
+//!
+//! let text = replay.to_text();
+//! let loaded = Replay::from_text(&text).unwrap();
+//! let game = loaded.verify(|seed| Game { x: seed as i32 - 123 }).unwrap();
+//! assert_eq!(game.x, 2);
+//! ```
+
+use runeforge_direction::prelude::IVec2;
+use thiserror::Error;
+use web_time::Instant;
+
+use crate::{Direction, InputEvent, MouseButton, VirtualKey};
+
+const HEADER: &str = "runeforge-replay";
+const VERSION: u32 = 1;
+
+/// A game that can be driven by a [`Replay`].
+pub trait ReplayTarget {
+    /// Applies one input event. `time` is seconds since the recording started.
+    fn handle_input(&mut self, time: f64, event: &InputEvent);
+
+    /// Returns a hash of everything that should be identical between runs.
+    ///
+    /// It must not depend on the platform or on `HashMap` iteration order;
+    /// `runeforge_random::world_hash::WorldHash` takes care of both.
+    fn state_hash(&self) -> u64;
+}
+
+/// One recorded input event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayFrame {
+    /// Seconds since the recording started
+    pub time: f64,
+    /// The input event
+    pub event: InputEvent,
+}
+
+/// Errors from loading or verifying a replay.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    /// A line could not be parsed.
+    #[error("Replay parse error on line {line}: {message}")]
+    Parse {
+        /// One-based line number
+        line: usize,
+        /// What went wrong
+        message: String,
+    },
+    /// The file was written by an unknown format version.
+    #[error("Unsupported replay version {0}")]
+    UnsupportedVersion(u32),
+    /// The file has no `seed` line.
+    #[error("Replay has no seed")]
+    MissingSeed,
+    /// Replaying produced a different final state.
+    #[error("Replay diverged: expected state hash {expected:016x}, found {found:016x}")]
+    HashMismatch {
+        /// Hash stored in the replay
+        expected: u64,
+        /// Hash produced by the game
+        found: u64,
+    },
+}
+
+impl ReplayError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ReplayError::Parse { .. } => "replay.parse",
+            ReplayError::UnsupportedVersion(_) => "replay.unsupported_version",
+            ReplayError::MissingSeed => "replay.missing_seed",
+            ReplayError::HashMismatch { .. } => "replay.hash_mismatch",
+        }
+    }
+}
+
+/// A recorded game session: a seed plus timestamped input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    /// The seed the game was started with
+    pub seed: u64,
+    /// Recorded events in order
+    pub frames: Vec<ReplayFrame>,
+    /// Hash of the game state after the last event, if known
+    pub final_hash: Option<u64>,
+}
+
+impl Replay {
+    /// Creates an empty replay for a game started with `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            frames: Vec::new(),
+            final_hash: None,
+        }
+    }
+
+    /// Appends an event at `time` seconds.
+    pub fn record(&mut self, time: f64, event: InputEvent) {
+        self.frames.push(ReplayFrame { time, event });
+    }
+
+    /// Stores the hash the game must reach at the end of the replay.
+    pub fn set_final_hash(&mut self, hash: u64) {
+        self.final_hash = Some(hash);
+    }
+
+    /// Feeds every event into `target` and returns its final state hash.
+    pub fn play(&self, target: &mut impl ReplayTarget) -> u64 {
+        for frame in &self.frames {
+            target.handle_input(frame.time, &frame.event);
+        }
+        target.state_hash()
+    }
+
+    /// Creates a game from the seed, replays every event and checks the final
+    /// hash (if one was recorded). Returns the game for further inspection.
+    pub fn verify<G: ReplayTarget>(
+        &self,
+        new_game: impl FnOnce(u64) -> G,
+    ) -> Result<G, ReplayError> {
+        let mut game = new_game(self.seed);
+        let found = self.play(&mut game);
+        match self.final_hash {
+            Some(expected) if expected != found => {
+                Err(ReplayError::HashMismatch { expected, found })
+            }
+            _ => Ok(game),
+        }
+    }
+
+    /// Writes the replay in its text format.
+    pub fn to_text(&self) -> String {
+        let mut out = format!("{} {}\nseed {}\n", HEADER, VERSION, self.seed);
+        if let Some(hash) = self.final_hash {
+            out.push_str(&format!("hash {:016x}\n", hash));
+        }
+        for frame in &self.frames {
+            out.push_str(&format!("@{} {}\n", frame.time, encode_event(&frame.event)));
+        }
+        out
+    }
+
+    /// Reads a replay from its text format. Blank lines and lines starting
+    /// with `;` are ignored.
+    pub fn from_text(text: &str) -> Result<Self, ReplayError> {
+        let mut seed = None;
+        let mut replay = Replay::new(0);
+        let mut saw_header = false;
+
+        for (idx, line) in text.lines().enumerate() {
+            let line_no = idx + 1;
+            let err = |message: &str| ReplayError::Parse {
+                line: line_no,
+                message: message.to_string(),
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let first = words.next().unwrap_or_default();
+
+            if !saw_header {
+                if first != HEADER {
+                    return Err(err("missing replay header"));
+                }
+                let version = words
+                    .next()
+                    .and_then(|v| v.parse().ok())
+                    .ok_or_else(|| err("missing version"))?;
+                if version != VERSION {
+                    return Err(ReplayError::UnsupportedVersion(version));
+                }
+                saw_header = true;
+                continue;
+            }
+
+            match first {
+                "seed" => {
+                    let value = words.next().and_then(|v| v.parse().ok());
+                    seed = Some(value.ok_or_else(|| err("invalid seed"))?);
+                }
+                "hash" => {
+                    let value = words.next().and_then(|v| u64::from_str_radix(v, 16).ok());
+                    replay.final_hash = Some(value.ok_or_else(|| err("invalid hash"))?);
+                }
+                _ => {
+                    let time = first
+                        .strip_prefix('@')
+                        .and_then(|t| t.parse().ok())
+                        .ok_or_else(|| err("expected '@<time>'"))?;
+                    let words: Vec<&str> = words.collect();
+                    let event = decode_event(&words).map_err(|m| err(&m))?;
+                    replay.record(time, event);
+                }
+            }
+        }
+
+        replay.seed = seed.ok_or(ReplayError::MissingSeed)?;
+        Ok(replay)
+    }
+}
+
+/// Records input with wall-clock timestamps.
+#[derive(Debug, Clone)]
+pub struct ReplayRecorder {
+    replay: Replay,
+    start: Instant,
+}
+
+impl ReplayRecorder {
+    /// Starts recording a game started with `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            replay: Replay::new(seed),
+            start: Instant::now(),
+        }
+    }
+
+    /// Records an event with the time elapsed since recording started.
+    pub fn record(&mut self, event: &InputEvent) {
+        let time = self.start.elapsed().as_secs_f64();
+        self.replay.record(time, event.clone());
+    }
+
+    /// Returns the replay recorded so far.
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// Stops recording, storing the final state hash of `target`.
+    pub fn finish(mut self, target: &impl ReplayTarget) -> Replay {
+        self.replay.set_final_hash(target.state_hash());
+        self.replay
+    }
+}
+
+fn encode_key(key: &VirtualKey) -> String {
+    match key {
+        VirtualKey::Move(dir) => {
+            let c = dir.coord();
+            format!("move {} {}", c.x, c.y)
+        }
+        VirtualKey::Confirm => "confirm".into(),
+        VirtualKey::Cancel => "cancel".into(),
+        VirtualKey::Pickup => "pickup".into(),
+        VirtualKey::Drop => "drop".into(),
+        VirtualKey::Inventory => "inventory".into(),
+        VirtualKey::Use => "use".into(),
+        VirtualKey::Look => "look".into(),
+        VirtualKey::Wait => "wait".into(),
+        VirtualKey::Eat => "eat".into(),
+        VirtualKey::Quaff => "quaff".into(),
+        VirtualKey::Read => "read".into(),
+        VirtualKey::Zap => "zap".into(),
+        VirtualKey::Open => "open".into(),
+        VirtualKey::Close => "close".into(),
+        VirtualKey::Help => "help".into(),
+        VirtualKey::Quit => "quit".into(),
+        VirtualKey::Custom(id) => format!("custom {}", id),
+    }
+}
+
+fn decode_key(words: &[&str]) -> Result<VirtualKey, String> {
+    let key = match words {
+        ["move", x, y] => {
+            let x = x.parse().map_err(|_| "invalid direction".to_string())?;
+            let y = y.parse().map_err(|_| "invalid direction".to_string())?;
+            VirtualKey::Move(Direction::from_coord(IVec2::new(x, y)))
+        }
+        ["confirm"] => VirtualKey::Confirm,
+        ["cancel"] => VirtualKey::Cancel,
+        ["pickup"] => VirtualKey::Pickup,
+        ["drop"] => VirtualKey::Drop,
+        ["inventory"] => VirtualKey::Inventory,
+        ["use"] => VirtualKey::Use,
+        ["look"] => VirtualKey::Look,
+        ["wait"] => VirtualKey::Wait,
+        ["eat"] => VirtualKey::Eat,
+        ["quaff"] => VirtualKey::Quaff,
+        ["read"] => VirtualKey::Read,
+        ["zap"] => VirtualKey::Zap,
+        ["open"] => VirtualKey::Open,
+        ["close"] => VirtualKey::Close,
+        ["help"] => VirtualKey::Help,
+        ["quit"] => VirtualKey::Quit,
+        ["custom", id] => VirtualKey::Custom(
+            id.parse()
+                .map_err(|_| format!("invalid custom action '{}'", id))?,
+        ),
+        _ => return Err(format!("unknown key '{}'", words.join(" "))),
+    };
+    Ok(key)
+}
+
+fn encode_button(button: MouseButton) -> String {
+    match button {
+        MouseButton::Left => "left".into(),
+        MouseButton::Right => "right".into(),
+        MouseButton::Middle => "middle".into(),
+        MouseButton::Other(n) => format!("other:{}", n),
+    }
+}
+
+fn decode_button(word: &str) -> Result<MouseButton, String> {
+    match word {
+        "left" => Ok(MouseButton::Left),
+        "right" => Ok(MouseButton::Right),
+        "middle" => Ok(MouseButton::Middle),
+        _ => word
+            .strip_prefix("other:")
+            .and_then(|n| n.parse().ok())
+            .map(MouseButton::Other)
+            .ok_or_else(|| format!("unknown mouse button '{}'", word)),
+    }
+}
+
+pub(crate) fn encode_event(event: &InputEvent) -> String {
+    match event {
+        InputEvent::KeyPress(key) => format!("press {}", encode_key(key)),
+        InputEvent::KeyRelease(key) => format!("release {}", encode_key(key)),
+        InputEvent::MouseMove { x, y } => format!("mouse-move {} {}", x, y),
+        InputEvent::MouseClick { x, y, button } => {
+            format!("click {} {} {}", encode_button(*button), x, y)
+        }
+        InputEvent::MouseRelease { x, y, button } => {
+            format!("mouse-release {} {} {}", encode_button(*button), x, y)
+        }
+        InputEvent::DoubleClick { x, y, button } => {
+            format!("double-click {} {} {}", encode_button(*button), x, y)
+        }
+        InputEvent::MouseWheel { dx, dy } => format!("wheel {} {}", dx, dy),
+        InputEvent::DragStart { x, y, button } => {
+            format!("drag-start {} {} {}", encode_button(*button), x, y)
+        }
+        InputEvent::Drag {
+            x,
+            y,
+            origin_x,
+            origin_y,
+            button,
+        } => format!(
+            "drag {} {} {} {} {}",
+            encode_button(*button),
+            x,
+            y,
+            origin_x,
+            origin_y
+        ),
+        InputEvent::DragEnd {
+            x,
+            y,
+            origin_x,
+            origin_y,
+            button,
+        } => format!(
+            "drag-end {} {} {} {} {}",
+            encode_button(*button),
+            x,
+            y,
+            origin_x,
+            origin_y
+        ),
+        InputEvent::Quit => "quit".into(),
+    }
+}
+
+pub(crate) fn decode_event(words: &[&str]) -> Result<InputEvent, String> {
+    let coord = |w: &str| {
+        w.parse::<f64>()
+            .map_err(|_| format!("invalid coordinate '{}'", w))
+    };
+    match words {
+        ["press", key @ ..] => Ok(InputEvent::KeyPress(decode_key(key)?)),
+        ["release", key @ ..] => Ok(InputEvent::KeyRelease(decode_key(key)?)),
+        ["mouse-move", x, y] => Ok(InputEvent::MouseMove {
+            x: coord(x)?,
+            y: coord(y)?,
+        }),
+        ["click", button, x, y] => Ok(InputEvent::MouseClick {
+            x: coord(x)?,
+            y: coord(y)?,
+            button: decode_button(button)?,
+        }),
+        ["mouse-release", button, x, y] => Ok(InputEvent::MouseRelease {
+            x: coord(x)?,
+            y: coord(y)?,
+            button: decode_button(button)?,
+        }),
+        ["double-click", button, x, y] => Ok(InputEvent::DoubleClick {
+            x: coord(x)?,
+            y: coord(y)?,
+            button: decode_button(button)?,
+        }),
+        ["wheel", dx, dy] => Ok(InputEvent::MouseWheel {
+            dx: coord(dx)?,
+            dy: coord(dy)?,
+        }),
+        ["drag-start", button, x, y] => Ok(InputEvent::DragStart {
+            x: coord(x)?,
+            y: coord(y)?,
+            button: decode_button(button)?,
+        }),
+        ["drag", button, x, y, ox, oy] => Ok(InputEvent::Drag {
+            x: coord(x)?,
+            y: coord(y)?,
+            origin_x: coord(ox)?,
+            origin_y: coord(oy)?,
+            button: decode_button(button)?,
+        }),
+        ["drag-end", button, x, y, ox, oy] => Ok(InputEvent::DragEnd {
+            x: coord(x)?,
+            y: coord(y)?,
+            origin_x: coord(ox)?,
+            origin_y: coord(oy)?,
+            button: decode_button(button)?,
+        }),
+        ["quit"] => Ok(InputEvent::Quit),
+        _ => Err(format!("unknown event '{}'", words.join(" "))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Counter {
+        seed: u64,
+        presses: u64,
+    }
+
+    impl ReplayTarget for Counter {
+        fn handle_input(&mut self, _time: f64, event: &InputEvent) {
+            if let InputEvent::KeyPress(_) = event {
+                self.presses = self.presses.wrapping_mul(31).wrapping_add(self.seed);
+            }
+        }
+
+        fn state_hash(&self) -> u64 {
+            self.presses
+        }
+    }
+
+    fn sample() -> Replay {
+        let mut replay = Replay::new(77);
+        replay.record(
+            0.0,
+            InputEvent::KeyPress(VirtualKey::Move(Direction::NORTH_WEST)),
+        );
+        replay.record(0.25, InputEvent::KeyRelease(VirtualKey::Zap));
+        replay.record(0.375, InputEvent::KeyPress(VirtualKey::Custom(513)));
+        replay.record(0.5, InputEvent::MouseMove { x: 10.5, y: -3.0 });
+        replay.record(
+            0.75,
+            InputEvent::MouseClick {
+                x: 1.0,
+                y: 2.0,
+                button: MouseButton::Other(4),
+            },
+        );
+        replay.record(
+            1.0,
+            InputEvent::MouseRelease {
+                x: 1.0,
+                y: 2.0,
+                button: MouseButton::Left,
+            },
+        );
+        replay.record(1.0625, InputEvent::MouseWheel { dx: 0.0, dy: -1.5 });
+        replay.record(
+            1.09375,
+            InputEvent::Drag {
+                x: 5.0,
+                y: 6.0,
+                origin_x: 1.0,
+                origin_y: 2.0,
+                button: MouseButton::Right,
+            },
+        );
+        replay.record(1.125, InputEvent::Quit);
+        replay
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let mut replay = sample();
+        replay.set_final_hash(0xdead_beef);
+        let text = replay.to_text();
+        assert!(text.starts_with("runeforge-replay 1\nseed 77\nhash 00000000deadbeef\n"));
+        assert_eq!(Replay::from_text(&text).unwrap(), replay);
+    }
+
+    #[test]
+    fn test_verify_detects_divergence() {
+        let replay = sample();
+        let hash = replay.play(&mut Counter {
+            seed: 77,
+            presses: 0,
+        });
+
+        let mut good = replay.clone();
+        good.set_final_hash(hash);
+        assert!(good.verify(|seed| Counter { seed, presses: 0 }).is_ok());
+
+        let mut bad = replay;
+        bad.set_final_hash(hash + 1);
+        assert_eq!(
+            bad.verify(|seed| Counter { seed, presses: 0 }).err(),
+            Some(ReplayError::HashMismatch {
+                expected: hash + 1,
+                found: hash
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Replay::from_text("runeforge-replay 2\n"),
+            Err(ReplayError::UnsupportedVersion(2))
+        );
+        assert_eq!(
+            Replay::from_text("runeforge-replay 1\n@0 quit\n"),
+            Err(ReplayError::MissingSeed)
+        );
+        assert!(matches!(
+            Replay::from_text("runeforge-replay 1\nseed 1\n\n@0 press fly\n"),
+            Err(ReplayError::Parse { line: 4, .. })
+        ));
+    }
+}
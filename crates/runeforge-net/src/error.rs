@@ -0,0 +1,64 @@
+//! Error types for lockstep networking.
+
+use thiserror::Error;
+
+use crate::lockstep::PeerId;
+
+/// Errors that can occur while exchanging turns.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum NetError {
+    /// The transport failed to send or receive.
+    #[error("Transport error: {0}")]
+    Transport(String),
+
+    /// A received message couldn't be decoded.
+    #[error("Malformed message: {0}")]
+    Malformed(String),
+
+    /// A message came from a peer outside the session.
+    #[error("Unknown peer {0}")]
+    UnknownPeer(PeerId),
+
+    /// A message was skipped: its sequence number is past the next one
+    /// expected from that peer.
+    #[error("Peer {peer} sent message {found}, expected {expected}")]
+    OutOfSequence {
+        /// The sending peer
+        peer: PeerId,
+        /// The sequence number that should have come next
+        expected: u64,
+        /// The sequence number that arrived
+        found: u64,
+    },
+
+    /// A peer's state hash differs from ours: the games have diverged.
+    #[error("Desync on turn {turn}: peer {peer} has state {remote:016x}, local is {local:016x}")]
+    Desync {
+        /// The turn whose starting state differs
+        turn: u64,
+        /// The peer that disagrees
+        peer: PeerId,
+        /// Local state hash
+        local: u64,
+        /// The peer's state hash
+        remote: u64,
+    },
+
+    /// Commands for this turn were already committed.
+    #[error("Turn {0} was already committed")]
+    AlreadyCommitted(u64),
+}
+
+impl NetError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            NetError::Transport(_) => "net.transport",
+            NetError::Malformed(_) => "net.malformed",
+            NetError::UnknownPeer(_) => "net.unknown_peer",
+            NetError::OutOfSequence { .. } => "net.out_of_sequence",
+            NetError::Desync { .. } => "net.desync",
+            NetError::AlreadyCommitted(_) => "net.already_committed",
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! Experimental networked play for roguelike games.
+//!
+//! # Overview
+//!
+//! `runeforge-net` keeps several copies of a deterministic game in step.
+//! Every peer starts from the same seed, and instead of sending world state
+//! the peers exchange only what each player did each turn. A turn runs once
+//! every peer's commands for it have arrived, in the same order everywhere,
+//! so the worlds stay identical. Each turn also carries a hash of the
+//! sender's state; if the hashes ever disagree, the game has desynced and
+//! [`NetError::Desync`](error::NetError::Desync) says where.
+//!
+//! *   **Lockstep:** [`Lockstep`](lockstep::Lockstep) collects per-turn commands with sequence
+//!     numbers and verifies state hashes.
+//! *   **Commands:** Anything implementing [`Command`](lockstep::Command) can be sent: usually
+//!     a small enum of player intents that the game turns into core `Action`s.
+//! *   **Transports:** The [`Transport`](transport::Transport) trait carries messages over any
+//!     channel; [`LoopbackTransport`](transport::LoopbackTransport) connects peers in memory.
+//!
+//! This crate is experimental: the wire format may change between versions.
+//! Compute state hashes with `runeforge-random`'s `WorldHash` so they agree
+//! across platforms.
+//!
+//! # Usage
+//!
+//! Add this to your `Cargo.toml`:
+//!
+//! ```toml
+//! [dependencies]
+//! runeforge-net = "0.1"
+//! ```
+//!
+//! ## Example
+//!
+//! ```rust
+//! use runeforge_net::prelude::*;
+//!
+//! #[derive(Debug, Clone, PartialEq)]
+//! struct Step(i32);
+//!
+//! impl Command for Step {
+//!     fn encode(&self) -> String {
+//!         self.0.to_string()
+//!     }
+//!     fn decode(text: &str) -> Result<Self, String> {
+//!         text.parse().map(Step).map_err(|e| format!("{e}"))
+//!     }
+//! }
+//!
+//! let mut links = LoopbackTransport::mesh(2);
+//! let mut bob_link = links.pop().unwrap();
+//! let mut alice_link = links.pop().unwrap();
+//! let mut alice = Lockstep::new(0, 2);
+//! let mut bob = Lockstep::new(1, 2);
+//!
+//! // Both start turn 0 in the same state
+//! alice.commit(&mut alice_link, vec![Step(1)], 0xABCD).unwrap();
+//! assert_eq!(alice.poll(&mut alice_link).unwrap(), None); // waiting for Bob
+//! bob.commit(&mut bob_link, vec![Step(-1)], 0xABCD).unwrap();
+//!
+//! let turn = alice.poll(&mut alice_link).unwrap().unwrap();
+//! assert_eq!(turn.commands, vec![(0, Step(1)), (1, Step(-1))]);
+//! assert_eq!(bob.poll(&mut bob_link).unwrap(), Some(turn));
+//! assert_eq!(alice.turn(), 1);
+//! ```
+
+#![deny(missing_docs)]
+
+pub mod error;
+pub mod lockstep;
+pub mod transport;
+
+/// Prelude module for convenient imports.
+pub mod prelude {
+    pub use crate::error::NetError;
+    pub use crate::lockstep::{Command, Lockstep, PeerId, TurnCommands, TurnPacket};
+    pub use crate::transport::{LoopbackTransport, Transport};
+}
@@ -0,0 +1,436 @@
+//! Deterministic lockstep turn exchange.
+//!
+//! Each turn, every peer [`commit`](Lockstep::commit)s its player's
+//! commands together with a hash of its game state at the start of the
+//! turn. [`poll`](Lockstep::poll) gathers the other peers' commits; once all
+//! of them are in for the current turn it checks that every hash matches
+//! and returns the commands in peer order as [`TurnCommands`]. Every peer
+//! gets the same list, applies it to its game the same way, and moves on to
+//! the next turn.
+//!
+//! Messages carry per-peer sequence numbers, so a dropped message is
+//! reported as [`NetError::OutOfSequence`] instead of stalling silently,
+//! and duplicates are ignored.
+//!
+//! # Wire format
+//!
+//! A [`TurnPacket`] is text: a header line
+//! `turn <turn> <peer> <seq> <hash-hex> <count>` followed by one encoded
+//! command per line.
+
+use std::collections::BTreeMap;
+
+use crate::error::NetError;
+use crate::transport::Transport;
+
+/// Identifies a peer in a session, from 0 to the number of peers.
+pub type PeerId = u32;
+
+/// A player command that can be sent over the network.
+///
+/// Encodings must fit on one line.
+pub trait Command: Sized {
+    /// Encodes the command as a single line of text.
+    fn encode(&self) -> String;
+
+    /// Decodes a command, returning a description of the problem on
+    /// failure.
+    fn decode(text: &str) -> Result<Self, String>;
+}
+
+/// One peer's commands for one turn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurnPacket<C> {
+    /// The turn the commands are for
+    pub turn: u64,
+    /// The sending peer
+    pub peer: PeerId,
+    /// The sender's message counter, starting at 0
+    pub seq: u64,
+    /// Hash of the sender's state at the start of the turn
+    pub state_hash: u64,
+    /// The commands, in the order the player gave them
+    pub commands: Vec<C>,
+}
+
+impl<C: Command> TurnPacket<C> {
+    /// Encodes the packet in the wire format.
+    pub fn encode(&self) -> String {
+        let mut out = format!(
+            "turn {} {} {} {:016x} {}",
+            self.turn,
+            self.peer,
+            self.seq,
+            self.state_hash,
+            self.commands.len()
+        );
+        for command in &self.commands {
+            out.push('\n');
+            out.push_str(&command.encode().replace('\n', " "));
+        }
+        out
+    }
+
+    /// Decodes a packet from the wire format.
+    pub fn decode(text: &str) -> Result<Self, NetError> {
+        let malformed = |reason: &str| NetError::Malformed(reason.to_string());
+        let mut lines = text.split('\n');
+        let header: Vec<&str> = lines.next().unwrap_or("").split_whitespace().collect();
+        let [tag, turn, peer, seq, hash, count] = header[..] else {
+            return Err(malformed(
+                "expected 'turn <turn> <peer> <seq> <hash> <count>'",
+            ));
+        };
+        if tag != "turn" {
+            return Err(malformed("expected 'turn' header"));
+        }
+        let number = |s: &str, what: &str| {
+            s.parse::<u64>()
+                .map_err(|_| malformed(&format!("invalid {what} '{s}'")))
+        };
+        let count = number(count, "command count")? as usize;
+        let commands = lines
+            .map(|line| C::decode(line).map_err(|e| malformed(&format!("bad command: {e}"))))
+            .collect::<Result<Vec<C>, _>>()?;
+        if commands.len() != count {
+            return Err(malformed(&format!(
+                "expected {count} commands, found {}",
+                commands.len()
+            )));
+        }
+        Ok(Self {
+            turn: number(turn, "turn")?,
+            peer: peer
+                .parse()
+                .map_err(|_| malformed(&format!("invalid peer '{peer}'")))?,
+            seq: number(seq, "sequence number")?,
+            state_hash: u64::from_str_radix(hash, 16)
+                .map_err(|_| malformed(&format!("invalid hash '{hash}'")))?,
+            commands,
+        })
+    }
+}
+
+/// Every peer's commands for a completed turn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurnCommands<C> {
+    /// The turn that is now ready to run
+    pub turn: u64,
+    /// The state hash all peers agreed on
+    pub state_hash: u64,
+    /// Commands by peer, lowest peer id first
+    pub commands: Vec<(PeerId, C)>,
+}
+
+/// What has arrived for one turn: per peer, its state hash and commands.
+type Arrivals<C> = BTreeMap<PeerId, (u64, Vec<C>)>;
+
+/// Lockstep session state for one peer.
+#[derive(Debug, Clone)]
+pub struct Lockstep<C> {
+    local: PeerId,
+    peers: u32,
+    turn: u64,
+    next_seq: u64,
+    /// Next sequence number expected from each peer.
+    expected: Vec<u64>,
+    pending: BTreeMap<u64, Arrivals<C>>,
+}
+
+impl<C: Command + Clone> Lockstep<C> {
+    /// Joins a session of `peers` peers as peer `local`, at turn 0.
+    pub fn new(local: PeerId, peers: u32) -> Self {
+        Self {
+            local,
+            peers,
+            turn: 0,
+            next_seq: 0,
+            expected: vec![0; peers as usize],
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the local peer id.
+    pub fn local(&self) -> PeerId {
+        self.local
+    }
+
+    /// Returns the number of peers in the session.
+    pub fn peers(&self) -> u32 {
+        self.peers
+    }
+
+    /// Returns the turn being gathered.
+    pub fn turn(&self) -> u64 {
+        self.turn
+    }
+
+    /// Returns true if the local commands for the current turn were sent.
+    pub fn has_committed(&self) -> bool {
+        self.arrived(self.turn, self.local)
+    }
+
+    /// Returns the peers whose commands for the current turn haven't
+    /// arrived yet, including the local peer if it hasn't committed.
+    pub fn waiting_for(&self) -> Vec<PeerId> {
+        (0..self.peers)
+            .filter(|&p| !self.arrived(self.turn, p))
+            .collect()
+    }
+
+    /// Sends the local commands for the current turn. `state_hash` is the
+    /// hash of the local game state before the turn runs.
+    pub fn commit(
+        &mut self,
+        transport: &mut impl Transport,
+        commands: Vec<C>,
+        state_hash: u64,
+    ) -> Result<(), NetError> {
+        if self.has_committed() {
+            return Err(NetError::AlreadyCommitted(self.turn));
+        }
+        let packet = TurnPacket {
+            turn: self.turn,
+            peer: self.local,
+            seq: self.next_seq,
+            state_hash,
+            commands,
+        };
+        transport.send(&packet.encode())?;
+        self.next_seq += 1;
+        self.pending
+            .entry(self.turn)
+            .or_default()
+            .insert(self.local, (state_hash, packet.commands));
+        Ok(())
+    }
+
+    /// Receives waiting messages and returns the current turn's commands if
+    /// every peer's have arrived, advancing to the next turn.
+    ///
+    /// Returns `None` while waiting. Call again after it returns a turn:
+    /// later turns may already be complete.
+    pub fn poll(
+        &mut self,
+        transport: &mut impl Transport,
+    ) -> Result<Option<TurnCommands<C>>, NetError> {
+        while let Some(message) = transport.receive()? {
+            self.receive(TurnPacket::decode(&message)?)?;
+        }
+
+        let turn = self.turn;
+        if !self.has_committed() || !self.waiting_for().is_empty() {
+            return Ok(None);
+        }
+        let arrivals = self.pending.remove(&turn).unwrap_or_default();
+        let local = arrivals[&self.local].0;
+        if let Some((&peer, &(remote, _))) = arrivals.iter().find(|(_, (h, _))| *h != local) {
+            return Err(NetError::Desync {
+                turn,
+                peer,
+                local,
+                remote,
+            });
+        }
+        self.turn += 1;
+        Ok(Some(TurnCommands {
+            turn,
+            state_hash: local,
+            commands: arrivals
+                .into_iter()
+                .flat_map(|(peer, (_, commands))| commands.into_iter().map(move |c| (peer, c)))
+                .collect(),
+        }))
+    }
+
+    /// Files one packet from another peer. Each peer sends exactly one
+    /// packet per turn, never for a turn that already ran.
+    fn receive(&mut self, packet: TurnPacket<C>) -> Result<(), NetError> {
+        let peer = packet.peer;
+        if peer == self.local || peer >= self.peers {
+            return Err(NetError::UnknownPeer(peer));
+        }
+        let expected = &mut self.expected[peer as usize];
+        if packet.seq < *expected {
+            // A duplicate; it was already filed.
+            return Ok(());
+        }
+        if packet.seq > *expected {
+            return Err(NetError::OutOfSequence {
+                peer,
+                expected: *expected,
+                found: packet.seq,
+            });
+        }
+        if packet.turn < self.turn {
+            return Err(NetError::Malformed(format!(
+                "peer {peer} sent commands for turn {}, which already ran",
+                packet.turn
+            )));
+        }
+        if self.arrived(packet.turn, peer) {
+            return Err(NetError::Malformed(format!(
+                "peer {peer} sent commands for turn {} twice",
+                packet.turn
+            )));
+        }
+        self.expected[peer as usize] += 1;
+        self.pending
+            .entry(packet.turn)
+            .or_default()
+            .insert(peer, (packet.state_hash, packet.commands));
+        Ok(())
+    }
+
+    fn arrived(&self, turn: u64, peer: PeerId) -> bool {
+        self.pending
+            .get(&turn)
+            .is_some_and(|arrivals| arrivals.contains_key(&peer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::LoopbackTransport;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Intent {
+        Wait,
+        Move(i32, i32),
+    }
+
+    impl Command for Intent {
+        fn encode(&self) -> String {
+            match self {
+                Intent::Wait => "wait".to_string(),
+                Intent::Move(x, y) => format!("move {x} {y}"),
+            }
+        }
+
+        fn decode(text: &str) -> Result<Self, String> {
+            let words: Vec<&str> = text.split_whitespace().collect();
+            match words[..] {
+                ["wait"] => Ok(Intent::Wait),
+                ["move", x, y] => Ok(Intent::Move(
+                    x.parse().map_err(|_| "bad x")?,
+                    y.parse().map_err(|_| "bad y")?,
+                )),
+                _ => Err(format!("unknown command '{text}'")),
+            }
+        }
+    }
+
+    #[test]
+    fn test_packet_round_trip() {
+        let packet = TurnPacket {
+            turn: 7,
+            peer: 2,
+            seq: 9,
+            state_hash: 0xDEAD_BEEF,
+            commands: vec![Intent::Move(-1, 0), Intent::Wait],
+        };
+        let text = packet.encode();
+        assert!(text.starts_with("turn 7 2 9 00000000deadbeef 2\n"));
+        assert_eq!(TurnPacket::decode(&text), Ok(packet));
+
+        let empty = TurnPacket::<Intent> {
+            commands: vec![],
+            ..TurnPacket::decode(&text).unwrap()
+        };
+        assert_eq!(TurnPacket::decode(&empty.encode()), Ok(empty));
+        assert!(matches!(
+            TurnPacket::<Intent>::decode("turn 1 0 0 zz 0"),
+            Err(NetError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn test_three_peers_run_turns_in_order() {
+        let mut links = LoopbackTransport::mesh(3);
+        let mut peers: Vec<Lockstep<Intent>> = (0..3).map(|i| Lockstep::new(i, 3)).collect();
+
+        for turn in 0..3u64 {
+            // Commit in a different order each turn
+            for i in [2, 0, 1] {
+                let i = (i + turn as usize) % 3;
+                assert_eq!(peers[i].poll(&mut links[i]), Ok(None));
+                let command = Intent::Move(i as i32, turn as i32);
+                peers[i].commit(&mut links[i], vec![command], turn).unwrap();
+            }
+            let results: Vec<_> = (0..3)
+                .map(|i| peers[i].poll(&mut links[i]).unwrap().unwrap())
+                .collect();
+            assert!(results.iter().all(|r| *r == results[0]));
+            let order: Vec<PeerId> = results[0].commands.iter().map(|&(p, _)| p).collect();
+            assert_eq!(order, vec![0, 1, 2]);
+        }
+        assert_eq!(peers[1].turn(), 3);
+    }
+
+    #[test]
+    fn test_desync_and_sequence_errors() {
+        let mut links = LoopbackTransport::mesh(2);
+        let mut a: Lockstep<Intent> = Lockstep::new(0, 2);
+        let mut b: Lockstep<Intent> = Lockstep::new(1, 2);
+        a.commit(&mut links[0], vec![], 1).unwrap();
+        assert_eq!(
+            a.commit(&mut links[0], vec![], 1),
+            Err(NetError::AlreadyCommitted(0))
+        );
+        b.commit(&mut links[1], vec![Intent::Wait], 2).unwrap();
+        assert_eq!(
+            a.poll(&mut links[0]),
+            Err(NetError::Desync {
+                turn: 0,
+                peer: 1,
+                local: 1,
+                remote: 2,
+            })
+        );
+
+        let mut c: Lockstep<Intent> = Lockstep::new(0, 2);
+        let skipped = TurnPacket {
+            turn: 0,
+            peer: 1,
+            seq: 3,
+            state_hash: 0,
+            commands: vec![Intent::Wait],
+        };
+        links[1].send(&skipped.encode()).unwrap();
+        assert_eq!(
+            c.poll(&mut links[0]).unwrap_err().code(),
+            "net.out_of_sequence"
+        );
+    }
+
+    #[test]
+    fn test_rejects_stale_and_repeated_turns() {
+        let mut links = LoopbackTransport::mesh(2);
+        let mut a: Lockstep<Intent> = Lockstep::new(0, 2);
+        let packet = |turn, seq| {
+            TurnPacket {
+                turn,
+                peer: 1,
+                seq,
+                state_hash: 0,
+                commands: vec![Intent::Wait],
+            }
+            .encode()
+        };
+
+        links[1].send(&packet(0, 0)).unwrap();
+        a.commit(&mut links[0], vec![], 0).unwrap();
+        assert_eq!(a.poll(&mut links[0]).unwrap().unwrap().turn, 0);
+
+        links[1].send(&packet(0, 1)).unwrap();
+        assert_eq!(a.poll(&mut links[0]).unwrap_err().code(), "net.malformed");
+        assert!(a.pending.is_empty());
+
+        links[1].send(&packet(1, 1)).unwrap();
+        links[1].send(&packet(1, 2)).unwrap();
+        assert_eq!(a.poll(&mut links[0]).unwrap_err().code(), "net.malformed");
+        assert_eq!(a.pending[&1][&1].1, vec![Intent::Wait]);
+        assert_eq!(a.expected[1], 2);
+    }
+}
@@ -0,0 +1,92 @@
+//! Carrying lockstep messages between peers.
+//!
+//! A [`Transport`] broadcasts text messages to every other peer and hands
+//! back the ones that arrived, without blocking. Lockstep only needs
+//! messages from each peer to arrive complete and in order, so TCP, a
+//! WebSocket or a reliable UDP layer all fit behind it.
+//! [`LoopbackTransport`] connects peers inside one process, for tests and
+//! hot-seat experiments.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_net::prelude::*;
+//!
+//! let mut peers = LoopbackTransport::mesh(3);
+//! peers[0].send("hello").unwrap();
+//! assert_eq!(peers[1].receive().unwrap().as_deref(), Some("hello"));
+//! assert_eq!(peers[2].receive().unwrap().as_deref(), Some("hello"));
+//! assert_eq!(peers[0].receive().unwrap(), None);
+//! ```
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use crate::error::NetError;
+
+/// A channel between one peer and all the others.
+pub trait Transport {
+    /// Sends a message to every other peer.
+    fn send(&mut self, message: &str) -> Result<(), NetError>;
+
+    /// Returns the next message received, or `None` if none is waiting.
+    /// Must not block.
+    fn receive(&mut self) -> Result<Option<String>, NetError>;
+}
+
+type Queue = Rc<RefCell<VecDeque<String>>>;
+
+/// An in-memory transport between peers in the same process.
+#[derive(Debug, Clone)]
+pub struct LoopbackTransport {
+    inbox: Queue,
+    others: Vec<Queue>,
+}
+
+impl LoopbackTransport {
+    /// Creates `peers` connected transports; the one at index `i` belongs to
+    /// peer `i`.
+    pub fn mesh(peers: usize) -> Vec<Self> {
+        let inboxes: Vec<Queue> = (0..peers).map(|_| Queue::default()).collect();
+        (0..peers)
+            .map(|i| Self {
+                inbox: inboxes[i].clone(),
+                others: inboxes
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .map(|(_, q)| q.clone())
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Returns how many messages are waiting to be received.
+    pub fn pending(&self) -> usize {
+        self.inbox.borrow().len()
+    }
+}
+
+impl Transport for LoopbackTransport {
+    fn send(&mut self, message: &str) -> Result<(), NetError> {
+        for queue in &self.others {
+            queue.borrow_mut().push_back(message.to_string());
+        }
+        Ok(())
+    }
+
+    fn receive(&mut self) -> Result<Option<String>, NetError> {
+        Ok(self.inbox.borrow_mut().pop_front())
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for &mut T {
+    fn send(&mut self, message: &str) -> Result<(), NetError> {
+        (**self).send(message)
+    }
+
+    fn receive(&mut self) -> Result<Option<String>, NetError> {
+        (**self).receive()
+    }
+}
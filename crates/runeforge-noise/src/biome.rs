@@ -0,0 +1,512 @@
+//! Biome assignment from layered noise maps.
+//!
+//! A [`BiomeMapper`] combines elevation, moisture and temperature [`NoiseMap`]s
+//! with a user-provided [`BiomeTable`] into a per-tile [`BiomeMap`]. Tiles below
+//! the sea level become water, the coastline is smoothed with a majority filter,
+//! and rivers are traced downhill from high ground until they reach the sea.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_noise::biome::{BiomeConfig, BiomeMapper, BiomeTable};
+//! use runeforge_noise::NoiseMap;
+//!
+//! #[derive(Debug, Clone, Copy, PartialEq)]
+//! enum Biome { Ocean, River, Desert, Forest, Tundra, Grassland }
+//!
+//! let elevation = NoiseMap::new(64, 64, 1);
+//! let moisture = NoiseMap::new(64, 64, 2);
+//! let temperature = NoiseMap::new(64, 64, 3);
+//!
+//! let table = BiomeTable::new(Biome::Grassland)
+//!     .with_water(Biome::Ocean)
+//!     .with_river(Biome::River)
+//!     .rule(Biome::Tundra, 0.0..=1.0, 0.0..=1.0, 0.0..=0.3)
+//!     .rule(Biome::Desert, 0.0..=1.0, 0.0..=0.35, 0.6..=1.0)
+//!     .rule(Biome::Forest, 0.0..=1.0, 0.55..=1.0, 0.0..=1.0);
+//!
+//! let map = BiomeMapper::map(&elevation, &moisture, &temperature, &table, &BiomeConfig::default())
+//!     .unwrap();
+//! assert_eq!(map.width(), 64);
+//! ```
+
+use crate::NoiseMap;
+use std::ops::RangeInclusive;
+use thiserror::Error;
+
+/// Errors that can occur during biome mapping.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BiomeError {
+    /// The input noise maps do not share the same dimensions.
+    #[error(
+        "Mismatched noise map dimensions: expected {}x{}, found {}x{}",
+        expected.0, expected.1, found.0, found.1
+    )]
+    MismatchedDimensions {
+        /// Dimensions of the elevation map
+        expected: (usize, usize),
+        /// Dimensions of the offending map
+        found: (usize, usize),
+    },
+}
+
+impl BiomeError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            BiomeError::MismatchedDimensions { .. } => "biome.mismatched_dimensions",
+        }
+    }
+}
+
+/// A single biome rule matching normalized elevation, moisture and temperature ranges.
+#[derive(Debug, Clone)]
+pub struct BiomeRule<B> {
+    /// Biome assigned when all ranges match
+    pub biome: B,
+    /// Normalized elevation range (0.0 to 1.0)
+    pub elevation: RangeInclusive<f64>,
+    /// Normalized moisture range (0.0 to 1.0)
+    pub moisture: RangeInclusive<f64>,
+    /// Normalized temperature range (0.0 to 1.0)
+    pub temperature: RangeInclusive<f64>,
+}
+
+impl<B> BiomeRule<B> {
+    /// Returns true if the given normalized values fall within this rule.
+    pub fn matches(&self, elevation: f64, moisture: f64, temperature: f64) -> bool {
+        self.elevation.contains(&elevation)
+            && self.moisture.contains(&moisture)
+            && self.temperature.contains(&temperature)
+    }
+}
+
+/// A lookup table from climate values to biomes.
+///
+/// Rules are checked in insertion order; the first match wins. Tiles that
+/// match no rule receive the fallback biome.
+#[derive(Debug, Clone)]
+pub struct BiomeTable<B> {
+    rules: Vec<BiomeRule<B>>,
+    fallback: B,
+    water: Option<B>,
+    river: Option<B>,
+}
+
+impl<B: Clone> BiomeTable<B> {
+    /// Creates an empty table with the given fallback biome.
+    pub fn new(fallback: B) -> Self {
+        Self {
+            rules: Vec::new(),
+            fallback,
+            water: None,
+            river: None,
+        }
+    }
+
+    /// Adds a rule matching the given normalized ranges.
+    pub fn rule(
+        mut self,
+        biome: B,
+        elevation: RangeInclusive<f64>,
+        moisture: RangeInclusive<f64>,
+        temperature: RangeInclusive<f64>,
+    ) -> Self {
+        self.rules.push(BiomeRule {
+            biome,
+            elevation,
+            moisture,
+            temperature,
+        });
+        self
+    }
+
+    /// Sets the biome used for tiles below sea level.
+    pub fn with_water(mut self, biome: B) -> Self {
+        self.water = Some(biome);
+        self
+    }
+
+    /// Sets the biome used for river tiles.
+    pub fn with_river(mut self, biome: B) -> Self {
+        self.river = Some(biome);
+        self
+    }
+
+    /// Returns the rules in this table.
+    pub fn rules(&self) -> &[BiomeRule<B>] {
+        &self.rules
+    }
+
+    /// Looks up the biome for the given normalized climate values.
+    pub fn lookup(&self, elevation: f64, moisture: f64, temperature: f64) -> B {
+        self.rules
+            .iter()
+            .find(|r| r.matches(elevation, moisture, temperature))
+            .map(|r| r.biome.clone())
+            .unwrap_or_else(|| self.fallback.clone())
+    }
+}
+
+/// Configuration for biome mapping.
+#[derive(Debug, Clone)]
+pub struct BiomeConfig {
+    /// Normalized elevation below which tiles are water (0.0 to 1.0).
+    pub sea_level: f64,
+    /// Number of majority-filter passes applied to the coastline.
+    pub coastline_smoothing_passes: u32,
+    /// Maximum number of rivers to trace.
+    pub river_count: usize,
+    /// Minimum normalized elevation for a river source (0.0 to 1.0).
+    pub river_source_elevation: f64,
+    /// Minimum Chebyshev distance between river sources.
+    pub river_source_spacing: usize,
+}
+
+impl Default for BiomeConfig {
+    fn default() -> Self {
+        Self {
+            sea_level: 0.4,
+            coastline_smoothing_passes: 2,
+            river_count: 4,
+            river_source_elevation: 0.65,
+            river_source_spacing: 8,
+        }
+    }
+}
+
+impl BiomeConfig {
+    /// Creates a new configuration with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the sea level (clamped to 0.0-1.0).
+    pub fn with_sea_level(mut self, sea_level: f64) -> Self {
+        self.sea_level = sea_level.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the number of coastline smoothing passes.
+    pub fn with_coastline_smoothing_passes(mut self, passes: u32) -> Self {
+        self.coastline_smoothing_passes = passes;
+        self
+    }
+
+    /// Sets the maximum number of rivers.
+    pub fn with_river_count(mut self, count: usize) -> Self {
+        self.river_count = count;
+        self
+    }
+
+    /// Sets the minimum river source elevation (clamped to 0.0-1.0).
+    pub fn with_river_source_elevation(mut self, elevation: f64) -> Self {
+        self.river_source_elevation = elevation.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the minimum spacing between river sources.
+    pub fn with_river_source_spacing(mut self, spacing: usize) -> Self {
+        self.river_source_spacing = spacing;
+        self
+    }
+}
+
+/// A per-tile biome grid produced by [`BiomeMapper`].
+#[derive(Debug, Clone)]
+pub struct BiomeMap<B> {
+    width: usize,
+    height: usize,
+    biomes: Vec<B>,
+    water: Vec<bool>,
+    rivers: Vec<bool>,
+}
+
+impl<B> BiomeMap<B> {
+    /// Get width of the biome map.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get height of the biome map.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the biome at the given coordinates, or `None` if out of bounds.
+    pub fn get(&self, x: usize, y: usize) -> Option<&B> {
+        self.index(x, y).map(|i| &self.biomes[i])
+    }
+
+    /// Returns true if the tile is below sea level (after coastline smoothing).
+    pub fn is_water(&self, x: usize, y: usize) -> bool {
+        self.index(x, y).is_some_and(|i| self.water[i])
+    }
+
+    /// Returns true if a river runs through the tile.
+    pub fn is_river(&self, x: usize, y: usize) -> bool {
+        self.index(x, y).is_some_and(|i| self.rivers[i])
+    }
+
+    /// Returns the raw biome data in row-major order.
+    pub fn biomes(&self) -> &[B] {
+        &self.biomes
+    }
+
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.width && y < self.height).then(|| y * self.width + x)
+    }
+}
+
+/// Builds biome maps from layered noise.
+pub struct BiomeMapper;
+
+impl BiomeMapper {
+    /// Assigns a biome to every tile of the given noise layers.
+    ///
+    /// All three maps must share the same dimensions. Noise values are
+    /// normalized to [0.0, 1.0] before being matched against the table.
+    pub fn map<B: Clone>(
+        elevation: &NoiseMap,
+        moisture: &NoiseMap,
+        temperature: &NoiseMap,
+        table: &BiomeTable<B>,
+        config: &BiomeConfig,
+    ) -> Result<BiomeMap<B>, BiomeError> {
+        let width = elevation.width();
+        let height = elevation.height();
+        for layer in [moisture, temperature] {
+            if layer.width() != width || layer.height() != height {
+                return Err(BiomeError::MismatchedDimensions {
+                    expected: (width, height),
+                    found: (layer.width(), layer.height()),
+                });
+            }
+        }
+
+        let mut water: Vec<bool> = (0..width * height)
+            .map(|i| elevation.normalized(i % width, i / width) < config.sea_level)
+            .collect();
+        for _ in 0..config.coastline_smoothing_passes {
+            water = Self::smooth_coastline(&water, width, height);
+        }
+
+        let rivers = Self::trace_rivers(elevation, &water, config);
+
+        let biomes = (0..width * height)
+            .map(|i| {
+                let (x, y) = (i % width, i / width);
+                match (&table.water, &table.river) {
+                    (Some(b), _) if water[i] => b.clone(),
+                    (_, Some(b)) if rivers[i] => b.clone(),
+                    _ => table.lookup(
+                        elevation.normalized(x, y),
+                        moisture.normalized(x, y),
+                        temperature.normalized(x, y),
+                    ),
+                }
+            })
+            .collect();
+
+        Ok(BiomeMap {
+            width,
+            height,
+            biomes,
+            water,
+            rivers,
+        })
+    }
+
+    /// Applies one 3x3 majority filter pass to the water mask.
+    fn smooth_coastline(water: &[bool], width: usize, height: usize) -> Vec<bool> {
+        let mut result = water.to_vec();
+        for y in 0..height {
+            for x in 0..width {
+                let mut wet = 0;
+                let mut total = 0;
+                for (nx, ny) in Self::neighborhood(x, y, width, height) {
+                    total += 1;
+                    if water[ny * width + nx] {
+                        wet += 1;
+                    }
+                }
+                let idx = y * width + x;
+                if water[idx] {
+                    wet += 1;
+                }
+                total += 1;
+                result[idx] = wet * 2 > total;
+            }
+        }
+        result
+    }
+
+    /// Traces rivers downhill from the highest land tiles.
+    fn trace_rivers(elevation: &NoiseMap, water: &[bool], config: &BiomeConfig) -> Vec<bool> {
+        let width = elevation.width();
+        let height = elevation.height();
+        let mut rivers = vec![false; width * height];
+
+        let mut candidates: Vec<(usize, usize)> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .filter(|&(x, y)| {
+                !water[y * width + x] && elevation.normalized(x, y) >= config.river_source_elevation
+            })
+            .collect();
+        candidates.sort_by(|a, b| elevation.get(b.0, b.1).total_cmp(&elevation.get(a.0, a.1)));
+
+        let mut sources: Vec<(usize, usize)> = Vec::new();
+        for (x, y) in candidates {
+            if sources.len() >= config.river_count {
+                break;
+            }
+            let spaced = sources
+                .iter()
+                .all(|&(sx, sy)| sx.abs_diff(x).max(sy.abs_diff(y)) >= config.river_source_spacing);
+            if spaced {
+                sources.push((x, y));
+            }
+        }
+
+        for (mut x, mut y) in sources {
+            loop {
+                let idx = y * width + x;
+                if water[idx] {
+                    break;
+                }
+                rivers[idx] = true;
+
+                let lowest = Self::neighborhood(x, y, width, height)
+                    .min_by(|a, b| elevation.get(a.0, a.1).total_cmp(&elevation.get(b.0, b.1)));
+                match lowest {
+                    Some((nx, ny)) if elevation.get(nx, ny) < elevation.get(x, y) => {
+                        (x, y) = (nx, ny);
+                    }
+                    // Local minimum: the river ends in a basin.
+                    _ => break,
+                }
+            }
+        }
+
+        rivers
+    }
+
+    fn neighborhood(
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> impl Iterator<Item = (usize, usize)> {
+        (-1i64..=1)
+            .flat_map(|dy| (-1i64..=1).map(move |dx| (dx, dy)))
+            .filter(|&(dx, dy)| dx != 0 || dy != 0)
+            .filter_map(move |(dx, dy)| {
+                let nx = x as i64 + dx;
+                let ny = y as i64 + dy;
+                (nx >= 0 && ny >= 0 && (nx as usize) < width && (ny as usize) < height)
+                    .then_some((nx as usize, ny as usize))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum TestBiome {
+        Water,
+        River,
+        Cold,
+        Land,
+    }
+
+    fn table() -> BiomeTable<TestBiome> {
+        BiomeTable::new(TestBiome::Land)
+            .with_water(TestBiome::Water)
+            .with_river(TestBiome::River)
+            .rule(TestBiome::Cold, 0.0..=1.0, 0.0..=1.0, 0.0..=0.3)
+    }
+
+    #[test]
+    fn test_biome_table_lookup() {
+        let table = table();
+        assert_eq!(table.lookup(0.5, 0.5, 0.1), TestBiome::Cold);
+        assert_eq!(table.lookup(0.5, 0.5, 0.9), TestBiome::Land);
+    }
+
+    #[test]
+    fn test_biome_mapper_mismatched_dimensions() {
+        let elevation = NoiseMap::new(10, 10, 1);
+        let moisture = NoiseMap::new(10, 12, 2);
+        let temperature = NoiseMap::new(10, 10, 3);
+        let result = BiomeMapper::map(
+            &elevation,
+            &moisture,
+            &temperature,
+            &table(),
+            &BiomeConfig::default(),
+        );
+        assert_eq!(
+            result.unwrap_err(),
+            BiomeError::MismatchedDimensions {
+                expected: (10, 10),
+                found: (10, 12)
+            }
+        );
+    }
+
+    #[test]
+    fn test_biome_mapper_water_below_sea_level() {
+        let elevation = NoiseMap::new(40, 40, 7);
+        let moisture = NoiseMap::new(40, 40, 8);
+        let temperature = NoiseMap::new(40, 40, 9);
+        let config = BiomeConfig::new()
+            .with_coastline_smoothing_passes(0)
+            .with_river_count(0);
+        let map = BiomeMapper::map(&elevation, &moisture, &temperature, &table(), &config).unwrap();
+
+        for y in 0..40 {
+            for x in 0..40 {
+                let below = elevation.normalized(x, y) < config.sea_level;
+                assert_eq!(map.is_water(x, y), below);
+                if below {
+                    assert_eq!(map.get(x, y), Some(&TestBiome::Water));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_smooth_coastline_removes_single_tiles() {
+        // A lone water tile surrounded by land becomes land.
+        let mut water = vec![false; 9];
+        water[4] = true;
+        let smoothed = BiomeMapper::smooth_coastline(&water, 3, 3);
+        assert!(smoothed.iter().all(|&w| !w));
+    }
+
+    #[test]
+    fn test_rivers_flow_downhill() {
+        let elevation = NoiseMap::new(60, 60, 21);
+        let moisture = NoiseMap::new(60, 60, 22);
+        let temperature = NoiseMap::new(60, 60, 23);
+        let config = BiomeConfig::new()
+            .with_river_count(3)
+            .with_river_source_elevation(0.5);
+        let map = BiomeMapper::map(&elevation, &moisture, &temperature, &table(), &config).unwrap();
+
+        let river_tiles = (0..60)
+            .flat_map(|y| (0..60).map(move |x| (x, y)))
+            .filter(|&(x, y)| map.is_river(x, y))
+            .count();
+        assert!(river_tiles > 0);
+
+        // Rivers never run through water tiles.
+        for y in 0..60 {
+            for x in 0..60 {
+                assert!(!(map.is_river(x, y) && map.is_water(x, y)));
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Noise map configuration.
+
+use thiserror::Error;
+
+/// Errors that can occur when generating noise maps.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum NoiseError {
+    /// A configuration value would produce a degenerate map.
+    #[error("Invalid noise config field '{field}': {reason}")]
+    InvalidConfig {
+        /// Name of the offending field
+        field: &'static str,
+        /// Why the value is rejected
+        reason: String,
+    },
+}
+
+impl NoiseError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            NoiseError::InvalidConfig { .. } => "noise.invalid_config",
+        }
+    }
+}
+
+/// Configuration for [`NoiseMap::with_config`](crate::NoiseMap::with_config).
+///
+/// Several octaves of Perlin noise are summed (fractal Brownian motion). The
+/// default is a single octave at scale 20, which matches [`NoiseMap::new`](crate::NoiseMap::new).
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoiseConfig {
+    /// Tiles per noise period of the first octave; larger values give smoother maps.
+    pub scale: f64,
+    /// Number of octaves to sum.
+    pub octaves: u32,
+    /// Amplitude multiplier between octaves (0.0 to 1.0).
+    pub persistence: f64,
+    /// Frequency multiplier between octaves (at least 1.0).
+    pub lacunarity: f64,
+}
+
+impl Default for NoiseConfig {
+    fn default() -> Self {
+        Self {
+            scale: 20.0,
+            octaves: 1,
+            persistence: 0.5,
+            lacunarity: 2.0,
+        }
+    }
+}
+
+impl NoiseConfig {
+    /// Creates a new configuration with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the scale of the first octave.
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Sets the number of octaves.
+    pub fn with_octaves(mut self, octaves: u32) -> Self {
+        self.octaves = octaves;
+        self
+    }
+
+    /// Sets the persistence (clamped to 0.0-1.0).
+    pub fn with_persistence(mut self, persistence: f64) -> Self {
+        self.persistence = persistence.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the lacunarity (at least 1.0).
+    pub fn with_lacunarity(mut self, lacunarity: f64) -> Self {
+        self.lacunarity = lacunarity.max(1.0);
+        self
+    }
+
+    /// Checks that the configuration produces varied noise.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoiseError::InvalidConfig` naming the first offending field.
+    pub fn validate(&self) -> Result<(), NoiseError> {
+        let invalid = |field, reason: String| Err(NoiseError::InvalidConfig { field, reason });
+
+        if !(self.scale.is_finite() && self.scale > 0.0) {
+            return invalid(
+                "scale",
+                format!("{} must be positive and finite", self.scale),
+            );
+        }
+        if self.octaves == 0 {
+            return invalid("octaves", "must be at least 1".into());
+        }
+        if !(0.0..=1.0).contains(&self.persistence) {
+            return invalid(
+                "persistence",
+                format!("{} is outside 0.0-1.0", self.persistence),
+            );
+        }
+        if !(self.lacunarity.is_finite() && self.lacunarity >= 1.0) {
+            return invalid(
+                "lacunarity",
+                format!("{} must be at least 1.0", self.lacunarity),
+            );
+        }
+        Ok(())
+    }
+}
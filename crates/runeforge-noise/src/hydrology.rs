@@ -0,0 +1,505 @@
+//! Rivers and lakes from a heightmap.
+//!
+//! [`Hydrology::compute`] treats a [`NoiseMap`] as terrain and works out
+//! where rain would go:
+//! 1. Tiles below the sea level are ocean
+//! 2. Depressions are filled up to their spill point and become lakes
+//!    (priority-flood), so every river has somewhere to drain
+//! 3. Each land tile flows to one of its eight neighbors, ending in the
+//!    ocean or off the map edge
+//! 4. Flow is accumulated downstream, and tiles draining enough land
+//!    become river
+//!
+//! The result is a [`HydrologyMap`] with the water type and flow direction
+//! of every tile.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_noise::hydrology::{Hydrology, HydrologyConfig, Water};
+//! use runeforge_noise::{NoiseConfig, NoiseMap};
+//!
+//! let terrain = NoiseMap::with_config(64, 64, 11, &NoiseConfig::new().with_octaves(4)).unwrap();
+//! let config = HydrologyConfig::new().with_sea_level(0.35).with_river_threshold(25);
+//! let water = Hydrology::compute(&terrain, &config).unwrap();
+//!
+//! for y in 0..water.height() {
+//!     for x in 0..water.width() {
+//!         let glyph = match water.water(x, y) {
+//!             Some(Water::Ocean) => '~',
+//!             Some(Water::Lake) => 'o',
+//!             Some(Water::River) => '=',
+//!             None => '.',
+//!         };
+//!         print!("{}", glyph);
+//!     }
+//!     println!();
+//! }
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use thiserror::Error;
+
+use crate::NoiseMap;
+
+/// Errors that can occur when computing hydrology.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum HydrologyError {
+    /// A configuration value is out of range.
+    #[error("Invalid hydrology config field '{field}': {reason}")]
+    InvalidConfig {
+        /// Name of the offending field
+        field: &'static str,
+        /// Why the value is rejected
+        reason: String,
+    },
+}
+
+impl HydrologyError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            HydrologyError::InvalidConfig { .. } => "hydrology.invalid_config",
+        }
+    }
+}
+
+/// Settings for [`Hydrology::compute`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HydrologyConfig {
+    /// Normalized elevation (0.0 to 1.0) below which tiles are ocean.
+    pub sea_level: f64,
+    /// Number of upstream tiles (including itself) a land tile must drain
+    /// to become river.
+    pub river_threshold: u32,
+    /// Fill depressions as lakes. When false, rivers stop at local minima.
+    pub fill_lakes: bool,
+    /// Lakes with fewer tiles than this are left dry (water still flows
+    /// across them).
+    pub min_lake_size: usize,
+}
+
+impl Default for HydrologyConfig {
+    fn default() -> Self {
+        Self {
+            sea_level: 0.4,
+            river_threshold: 40,
+            fill_lakes: true,
+            min_lake_size: 4,
+        }
+    }
+}
+
+impl HydrologyConfig {
+    /// Creates a new configuration with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the sea level (clamped to 0.0-1.0).
+    pub fn with_sea_level(mut self, sea_level: f64) -> Self {
+        self.sea_level = sea_level.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets how many tiles a river must drain.
+    pub fn with_river_threshold(mut self, threshold: u32) -> Self {
+        self.river_threshold = threshold;
+        self
+    }
+
+    /// Sets whether depressions are filled as lakes.
+    pub fn with_fill_lakes(mut self, fill: bool) -> Self {
+        self.fill_lakes = fill;
+        self
+    }
+
+    /// Sets the smallest lake that is kept.
+    pub fn with_min_lake_size(mut self, size: usize) -> Self {
+        self.min_lake_size = size;
+        self
+    }
+
+    /// Checks that the configuration is usable.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HydrologyError::InvalidConfig` naming the first offending field.
+    pub fn validate(&self) -> Result<(), HydrologyError> {
+        if !(0.0..=1.0).contains(&self.sea_level) {
+            return Err(HydrologyError::InvalidConfig {
+                field: "sea_level",
+                reason: format!("{} is outside 0.0-1.0", self.sea_level),
+            });
+        }
+        if self.river_threshold == 0 {
+            return Err(HydrologyError::InvalidConfig {
+                field: "river_threshold",
+                reason: "must be at least 1, or every land tile is river".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The kind of water on a tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Water {
+    /// Below sea level.
+    Ocean,
+    /// A filled depression.
+    Lake,
+    /// Land draining at least the river threshold.
+    River,
+}
+
+/// Water and drainage for every tile of a heightmap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HydrologyMap {
+    width: usize,
+    height: usize,
+    water: Vec<Option<Water>>,
+    downstream: Vec<Option<usize>>,
+    accumulation: Vec<u32>,
+    surface: Vec<f64>,
+}
+
+impl HydrologyMap {
+    /// Returns the map width.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the map height.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the water on a tile, or `None` for dry land and out of bounds.
+    pub fn water(&self, x: usize, y: usize) -> Option<Water> {
+        self.index(x, y).and_then(|i| self.water[i])
+    }
+
+    /// Returns true if the tile has any kind of water.
+    pub fn is_water(&self, x: usize, y: usize) -> bool {
+        self.water(x, y).is_some()
+    }
+
+    /// Returns the neighbor a tile drains into.
+    ///
+    /// `None` for ocean tiles, tiles draining off the map edge, and (without
+    /// lake filling) local minima.
+    pub fn downstream(&self, x: usize, y: usize) -> Option<(usize, usize)> {
+        let i = self.downstream[self.index(x, y)?]?;
+        Some((i % self.width, i / self.width))
+    }
+
+    /// Returns the direction a tile drains in as an `(dx, dy)` offset, each
+    /// component in -1..=1.
+    pub fn flow_direction(&self, x: usize, y: usize) -> Option<(i32, i32)> {
+        let (nx, ny) = self.downstream(x, y)?;
+        Some((nx as i32 - x as i32, ny as i32 - y as i32))
+    }
+
+    /// Returns the number of tiles (including itself) that drain through a tile.
+    /// Ocean tiles return 0.
+    pub fn accumulation(&self, x: usize, y: usize) -> u32 {
+        self.index(x, y).map_or(0, |i| self.accumulation[i])
+    }
+
+    /// Returns the normalized water surface height: the lake level on lakes,
+    /// the terrain height elsewhere.
+    pub fn surface_height(&self, x: usize, y: usize) -> f64 {
+        self.index(x, y).map_or(0.0, |i| self.surface[i])
+    }
+
+    /// Follows the flow from a tile until it reaches the ocean, leaves the
+    /// map or stops. The path includes the starting tile.
+    pub fn flow_path(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        let mut path = Vec::new();
+        let mut current = self.index(x, y).map(|_| (x, y));
+        while let Some((cx, cy)) = current {
+            path.push((cx, cy));
+            current = self.downstream(cx, cy);
+        }
+        path
+    }
+
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.width && y < self.height).then(|| y * self.width + x)
+    }
+}
+
+/// Computes rivers and lakes.
+pub struct Hydrology;
+
+impl Hydrology {
+    /// Computes water and drainage for a heightmap. Heights are normalized
+    /// to 0.0-1.0 first, like [`BiomeMapper`](crate::biome::BiomeMapper).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error from [`HydrologyConfig::validate`].
+    pub fn compute(
+        heightmap: &NoiseMap,
+        config: &HydrologyConfig,
+    ) -> Result<HydrologyMap, HydrologyError> {
+        config.validate()?;
+        let (width, height) = (heightmap.width(), heightmap.height());
+        let size = width * height;
+        let terrain: Vec<f64> = (0..size)
+            .map(|i| heightmap.normalized(i % width, i / width))
+            .collect();
+        let ocean: Vec<bool> = terrain.iter().map(|&h| h < config.sea_level).collect();
+
+        // `order` ranks tiles so that every tile drains into a lower-ranked one.
+        let (surface, order) = if config.fill_lakes {
+            Self::priority_flood(&terrain, &ocean, width, height)
+        } else {
+            let mut by_height: Vec<usize> = (0..size).collect();
+            by_height.sort_by(|&a, &b| terrain[a].total_cmp(&terrain[b]).then(a.cmp(&b)));
+            let mut order = vec![0; size];
+            for (rank, &i) in by_height.iter().enumerate() {
+                order[i] = rank;
+            }
+            (terrain.clone(), order)
+        };
+
+        let downstream: Vec<Option<usize>> = (0..size)
+            .map(|i| {
+                if ocean[i] {
+                    return None;
+                }
+                neighbors(i, width, height)
+                    .filter(|&n| order[n] < order[i])
+                    .filter(|&n| config.fill_lakes || surface[n] < surface[i])
+                    .min_by(|&a, &b| {
+                        surface[a]
+                            .total_cmp(&surface[b])
+                            .then(order[a].cmp(&order[b]))
+                    })
+            })
+            .collect();
+
+        let mut accumulation: Vec<u32> = ocean.iter().map(|&o| u32::from(!o)).collect();
+        let mut upstream_first: Vec<usize> = (0..size).collect();
+        upstream_first.sort_by_key(|&i| std::cmp::Reverse(order[i]));
+        for i in upstream_first {
+            if let Some(d) = downstream[i] {
+                accumulation[d] += accumulation[i];
+            }
+        }
+
+        let mut water: Vec<Option<Water>> =
+            ocean.iter().map(|&o| o.then_some(Water::Ocean)).collect();
+        let flooded: Vec<bool> = (0..size)
+            .map(|i| !ocean[i] && surface[i] > terrain[i])
+            .collect();
+        let mut seen = vec![false; size];
+        for start in 0..size {
+            if !flooded[start] || seen[start] {
+                continue;
+            }
+            seen[start] = true;
+            let mut lake = vec![start];
+            let mut next = 0;
+            while next < lake.len() {
+                for n in neighbors(lake[next], width, height) {
+                    if flooded[n] && !seen[n] {
+                        seen[n] = true;
+                        lake.push(n);
+                    }
+                }
+                next += 1;
+            }
+            if lake.len() >= config.min_lake_size {
+                for i in lake {
+                    water[i] = Some(Water::Lake);
+                }
+            }
+        }
+        for i in 0..size {
+            if water[i].is_none() && accumulation[i] >= config.river_threshold {
+                water[i] = Some(Water::River);
+            }
+        }
+
+        Ok(HydrologyMap {
+            width,
+            height,
+            water,
+            downstream,
+            accumulation,
+            surface,
+        })
+    }
+
+    /// Raises every depression to its spill level, flooding inwards from
+    /// the ocean and the map edge. Returns the water surface and the order
+    /// in which tiles were reached.
+    fn priority_flood(
+        terrain: &[f64],
+        ocean: &[bool],
+        width: usize,
+        height: usize,
+    ) -> (Vec<f64>, Vec<usize>) {
+        let size = width * height;
+        let mut surface = terrain.to_vec();
+        let mut order = vec![usize::MAX; size];
+        let mut queued = vec![false; size];
+        let mut heap = BinaryHeap::new();
+        let mut pushed = 0;
+
+        for i in 0..size {
+            let (x, y) = (i % width, i / width);
+            let edge = x == 0 || y == 0 || x + 1 == width || y + 1 == height;
+            if ocean[i] || edge {
+                queued[i] = true;
+                heap.push(FloodCell::new(surface[i], pushed, i));
+                pushed += 1;
+            }
+        }
+
+        let mut reached = 0;
+        while let Some(cell) = heap.pop() {
+            order[cell.index] = reached;
+            reached += 1;
+            for n in neighbors(cell.index, width, height) {
+                if !queued[n] {
+                    queued[n] = true;
+                    surface[n] = surface[n].max(surface[cell.index]);
+                    heap.push(FloodCell::new(surface[n], pushed, n));
+                    pushed += 1;
+                }
+            }
+        }
+
+        (surface, order)
+    }
+}
+
+/// A min-heap entry ordered by level, then by insertion order.
+struct FloodCell {
+    level: f64,
+    seq: usize,
+    index: usize,
+}
+
+impl FloodCell {
+    fn new(level: f64, seq: usize, index: usize) -> Self {
+        Self { level, seq, index }
+    }
+}
+
+impl PartialEq for FloodCell {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for FloodCell {}
+
+impl PartialOrd for FloodCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FloodCell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .level
+            .total_cmp(&self.level)
+            .then(other.seq.cmp(&self.seq))
+    }
+}
+
+fn neighbors(i: usize, width: usize, height: usize) -> impl Iterator<Item = usize> {
+    let (x, y) = ((i % width) as i64, (i / width) as i64);
+    (-1i64..=1)
+        .flat_map(|dy| (-1i64..=1).map(move |dx| (dx, dy)))
+        .filter(|&(dx, dy)| dx != 0 || dy != 0)
+        .filter_map(move |(dx, dy)| {
+            let (nx, ny) = (x + dx, y + dy);
+            (nx >= 0 && ny >= 0 && nx < width as i64 && ny < height as i64)
+                .then(|| ny as usize * width + nx as usize)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heightmap(width: usize, height: usize, f: impl Fn(usize, usize) -> f64) -> NoiseMap {
+        NoiseMap {
+            width,
+            height,
+            values: (0..width * height)
+                .map(|i| f(i % width, i / width))
+                .collect(),
+            seed: 0,
+        }
+    }
+
+    #[test]
+    fn test_depression_fills_as_lake() {
+        // A 3x3 pit in a plateau.
+        let terrain = heightmap(9, 9, |x, y| {
+            if (3..6).contains(&x) && (3..6).contains(&y) {
+                -0.5
+            } else {
+                0.5
+            }
+        });
+        let config = HydrologyConfig::new().with_sea_level(0.1);
+        let map = Hydrology::compute(&terrain, &config).unwrap();
+        assert_eq!(map.water(4, 4), Some(Water::Lake));
+        assert_eq!(map.water(1, 1), None);
+        assert!((map.surface_height(4, 4) - 0.75).abs() < 1e-9);
+
+        // Water in the lake still drains off the map.
+        let path = map.flow_path(4, 4);
+        let &(ex, ey) = path.last().unwrap();
+        assert!(ex == 0 || ey == 0 || ex == 8 || ey == 8);
+
+        let dry = Hydrology::compute(&terrain, &config.clone().with_min_lake_size(10)).unwrap();
+        assert_eq!(dry.water(4, 4), None);
+        let unfilled = Hydrology::compute(&terrain, &config.with_fill_lakes(false)).unwrap();
+        assert_eq!(unfilled.downstream(4, 4), None);
+    }
+
+    #[test]
+    fn test_slope_drains_to_ocean_as_river() {
+        // Falls from west to east into an ocean on the last columns.
+        let terrain = heightmap(20, 5, |x, _| 0.9 - x as f64 * 0.1);
+        let config = HydrologyConfig::new()
+            .with_sea_level(0.1)
+            .with_river_threshold(20);
+        let map = Hydrology::compute(&terrain, &config).unwrap();
+
+        assert_eq!(map.water(19, 2), Some(Water::Ocean));
+        assert_eq!(map.accumulation(19, 2), 0);
+        for x in 1..15 {
+            let (dx, _) = map.flow_direction(x, 2).unwrap();
+            assert_eq!(dx, 1);
+            let (nx, ny) = map.downstream(x, 2).unwrap();
+            assert!(map.accumulation(nx, ny) > map.accumulation(x, 2));
+        }
+        let rivers = (0..20)
+            .flat_map(|x| (0..5).map(move |y| (x, y)))
+            .filter(|&(x, y)| map.water(x, y) == Some(Water::River))
+            .count();
+        assert!(rivers > 0);
+        assert_eq!(map.water(0, 2), None);
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let terrain = NoiseMap::new(4, 4, 1);
+        let err = Hydrology::compute(&terrain, &HydrologyConfig::new().with_river_threshold(0))
+            .unwrap_err();
+        assert_eq!(err.code(), "hydrology.invalid_config");
+    }
+}
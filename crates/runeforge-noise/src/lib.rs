@@ -8,7 +8,8 @@
 //! # Use Cases
 //!
 //! *   **Terrain:** Generating height maps for mountains and valleys.
-//! *   **Biomes:** Creating regions for different environmental types.
+//! *   **Biomes:** Creating regions for different environmental types (see [`biome`]).
+//! *   **Water:** Rivers, lakes and drainage traced over a heightmap (see [`hydrology`]).
 //! *   **Caves:** As a base for carving out organic cave systems.
 //! *   **Textures:** Generating procedural textures for items or effects.
 //!
@@ -45,6 +46,14 @@
 
 #![deny(missing_docs)]
 
+pub mod biome;
+pub mod config;
+pub mod hydrology;
+
+pub use biome::{BiomeConfig, BiomeError, BiomeMap, BiomeMapper, BiomeRule, BiomeTable};
+pub use config::{NoiseConfig, NoiseError};
+pub use hydrology::{Hydrology, HydrologyConfig, HydrologyError, HydrologyMap, Water};
+
 use noise::{NoiseFn, Perlin};
 
 /// A 2D grid of noise values generated using Perlin noise.
@@ -90,24 +99,69 @@ impl NoiseMap {
             values: vec![0.0; width * height],
             seed,
         };
-        noise_map.generate();
+        noise_map.generate(&NoiseConfig::default());
         noise_map
     }
 
+    /// Create a noise map with a custom scale and octave count.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoiseError::InvalidConfig` if [`NoiseConfig::validate`] fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use runeforge_noise::{NoiseConfig, NoiseMap};
+    ///
+    /// let config = NoiseConfig::new().with_scale(32.0).with_octaves(4);
+    /// let noise_map = NoiseMap::with_config(64, 64, 7, &config).unwrap();
+    /// assert!(noise_map.get(10, 10).abs() <= 1.0);
+    ///
+    /// assert!(NoiseMap::with_config(64, 64, 7, &config.with_octaves(0)).is_err());
+    /// ```
+    pub fn with_config(
+        width: usize,
+        height: usize,
+        seed: u32,
+        config: &NoiseConfig,
+    ) -> Result<Self, NoiseError> {
+        config.validate()?;
+        let mut noise_map = Self {
+            width,
+            height,
+            values: vec![0.0; width * height],
+            seed,
+        };
+        noise_map.generate(config);
+        Ok(noise_map)
+    }
+
     /// Generate noise values using Perlin noise.
     ///
     /// This fills the internal values array with noise in the range [-1.0, 1.0].
     /// The scale determines how "zoomed in" the noise appears - higher values
-    /// create more variation over shorter distances.
-    fn generate(&mut self) {
+    /// create more variation over shorter distances. Octaves are summed and
+    /// divided by the total amplitude so the range is preserved.
+    fn generate(&mut self, config: &NoiseConfig) {
         // Create Perlin generator once for efficiency
         let perlin = Perlin::new(self.seed);
 
         for y in 0..self.height {
             for x in 0..self.width {
-                let x_scaled = x as f64 / 20.0;
-                let y_scaled = y as f64 / 20.0;
-                self.values[y * self.width + x] = perlin.get([x_scaled, y_scaled]);
+                let mut frequency = 1.0;
+                let mut amplitude = 1.0;
+                let mut total = 0.0;
+                let mut max_amplitude = 0.0;
+                for _ in 0..config.octaves {
+                    let x_scaled = x as f64 / config.scale * frequency;
+                    let y_scaled = y as f64 / config.scale * frequency;
+                    total += amplitude * perlin.get([x_scaled, y_scaled]);
+                    max_amplitude += amplitude;
+                    amplitude *= config.persistence;
+                    frequency *= config.lacunarity;
+                }
+                self.values[y * self.width + x] = total / max_amplitude;
             }
         }
     }
@@ -218,6 +272,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_default_config_matches_new() {
+        let map1 = NoiseMap::new(16, 16, 9);
+        let map2 = NoiseMap::with_config(16, 16, 9, &NoiseConfig::default()).unwrap();
+        assert_eq!(map1.values, map2.values);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config = NoiseConfig::new().with_scale(0.0);
+        assert!(matches!(
+            config.validate(),
+            Err(NoiseError::InvalidConfig { field: "scale", .. })
+        ));
+        let err = NoiseMap::with_config(4, 4, 1, &NoiseConfig::new().with_octaves(0))
+            .err()
+            .expect("zero octaves is invalid");
+        assert_eq!(err.code(), "noise.invalid_config");
+    }
+
     #[test]
     fn test_different_seeds() {
         let map1 = NoiseMap::new(20, 20, 1);
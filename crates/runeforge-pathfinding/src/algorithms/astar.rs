@@ -1,5 +1,7 @@
+use alloc::vec::Vec;
+
+use super::generic::astar;
 use crate::prelude::*;
-use pathfinding::prelude::astar;
 
 /// A* algorithm.
 pub struct AStar;
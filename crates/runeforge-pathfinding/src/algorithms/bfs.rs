@@ -1,5 +1,7 @@
+use alloc::vec::Vec;
+
+use super::generic::bfs;
 use crate::prelude::*;
-use pathfinding::prelude::bfs;
 
 /// Breadth-first search algorithm.
 pub struct Bfs;
@@ -1,5 +1,7 @@
+use alloc::vec::Vec;
+
+use super::generic::dfs;
 use crate::prelude::*;
-use pathfinding::prelude::dfs;
 
 /// Depth-first search algorithm.
 pub struct Dfs;
@@ -1,5 +1,7 @@
+use alloc::vec::Vec;
+
+use super::generic::dijkstra;
 use crate::prelude::*;
-use pathfinding::prelude::dijkstra;
 
 /// Trait to implement 2D path algorithms.
 pub struct Dijkstra;
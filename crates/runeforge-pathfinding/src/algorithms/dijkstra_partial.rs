@@ -1,5 +1,7 @@
+use alloc::vec::Vec;
+
+use super::generic::{build_path, dijkstra_partial};
 use crate::prelude::*;
-use pathfinding::prelude::{build_path, dijkstra_partial};
 
 /// Dijkstra algorithm that provides partial paths to destination.
 pub struct DijkstraPartial;
@@ -11,7 +13,7 @@ impl PathAlgorithm for DijkstraPartial {
         provider: &mut impl PathProvider<T>,
         mut pass_through_data: T,
     ) -> Vec<IVec2> {
-        let (paths, _) = dijkstra_partial(
+        let paths = dijkstra_partial(
             &origin,
             |&p| provider.generate_successors(p, &mut pass_through_data),
             |&p| p == destination,
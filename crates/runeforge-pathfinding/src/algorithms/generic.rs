@@ -0,0 +1,544 @@
+//! Node-generic search routines shared by the grid algorithms and [`Graph`](crate::graph::Graph).
+//!
+//! These follow the expansion order of the `pathfinding` crate they replace,
+//! but only need `alloc`, so the crate can be built without `std`.
+
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+use core::hash::Hash;
+
+use hashbrown::hash_map::Entry;
+use hashbrown::{HashMap, HashSet};
+
+use crate::prelude::BinaryHeap;
+
+/// Reached nodes in insertion order, each with its parent index and cost.
+struct Parents<N> {
+    nodes: Vec<(N, usize, u32)>,
+    index: HashMap<N, usize>,
+}
+
+impl<N: Clone + Eq + Hash> Parents<N> {
+    fn new(start: &N) -> Self {
+        Self {
+            nodes: vec![(start.clone(), usize::MAX, 0)],
+            index: HashMap::from([(start.clone(), 0)]),
+        }
+    }
+
+    /// Records `node` with a new parent and cost if it is unseen or cheaper,
+    /// returning its index.
+    fn relax(&mut self, node: N, parent: usize, cost: u32) -> Option<usize> {
+        match self.index.entry(node) {
+            Entry::Vacant(e) => {
+                let i = self.nodes.len();
+                self.nodes.push((e.key().clone(), parent, cost));
+                e.insert(i);
+                Some(i)
+            }
+            Entry::Occupied(e) => {
+                let i = *e.get();
+                if self.nodes[i].2 > cost {
+                    self.nodes[i].1 = parent;
+                    self.nodes[i].2 = cost;
+                    Some(i)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    fn path_to(&self, mut i: usize) -> Vec<N> {
+        let mut path = Vec::new();
+        while i != usize::MAX {
+            path.push(self.nodes[i].0.clone());
+            i = self.nodes[i].1;
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Open-list entry: lowest estimate first, then highest cost.
+#[derive(PartialEq, Eq)]
+struct Holder {
+    estimate: u32,
+    cost: u32,
+    index: usize,
+}
+
+impl Ord for Holder {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match other.estimate.cmp(&self.estimate) {
+            Ordering::Equal => self.cost.cmp(&other.cost),
+            s => s,
+        }
+    }
+}
+
+impl PartialOrd for Holder {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Dijkstra open-list entry: lowest cost first.
+#[derive(PartialEq, Eq)]
+struct CostHolder {
+    cost: u32,
+    index: usize,
+}
+
+impl Ord for CostHolder {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.cmp(&self.cost)
+    }
+}
+
+impl PartialOrd for CostHolder {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A* search, returning the path (start and goal inclusive) and its cost.
+pub(crate) fn astar<N, I>(
+    start: &N,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> u32,
+    mut success: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, u32)>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, u32)>,
+{
+    let mut open = BinaryHeap::from([Holder {
+        estimate: 0,
+        cost: 0,
+        index: 0,
+    }]);
+    let mut parents = Parents::new(start);
+    while let Some(Holder { cost, index, .. }) = open.pop() {
+        let node = &parents.nodes[index];
+        if success(&node.0) {
+            return Some((parents.path_to(index), cost));
+        }
+        // Skip stale entries superseded by a cheaper route.
+        if cost > node.2 {
+            continue;
+        }
+        for (next, step) in successors(&node.0.clone()) {
+            // A route whose cost doesn't fit in a u32 is never the cheapest.
+            let Some(next_cost) = cost.checked_add(step) else {
+                continue;
+            };
+            let h = heuristic(&next);
+            if let Some(i) = parents.relax(next, index, next_cost) {
+                open.push(Holder {
+                    estimate: next_cost.saturating_add(h),
+                    cost: next_cost,
+                    index: i,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Dijkstra's algorithm run until `stop` matches a node or the graph is exhausted.
+fn run_dijkstra<N, I>(
+    start: &N,
+    mut successors: impl FnMut(&N) -> I,
+    mut stop: impl FnMut(&N) -> bool,
+) -> (Parents<N>, Option<usize>)
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, u32)>,
+{
+    let mut open = BinaryHeap::from([CostHolder { cost: 0, index: 0 }]);
+    let mut parents = Parents::new(start);
+    while let Some(CostHolder { cost, index }) = open.pop() {
+        let node = &parents.nodes[index];
+        if stop(&node.0) {
+            return (parents, Some(index));
+        }
+        if cost > node.2 {
+            continue;
+        }
+        for (next, step) in successors(&node.0.clone()) {
+            let Some(next_cost) = cost.checked_add(step) else {
+                continue;
+            };
+            if let Some(i) = parents.relax(next, index, next_cost) {
+                open.push(CostHolder {
+                    cost: next_cost,
+                    index: i,
+                });
+            }
+        }
+    }
+    (parents, None)
+}
+
+/// Dijkstra search, returning the path (start and goal inclusive) and its cost.
+pub(crate) fn dijkstra<N, I>(
+    start: &N,
+    successors: impl FnMut(&N) -> I,
+    success: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, u32)>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, u32)>,
+{
+    let (parents, reached) = run_dijkstra(start, successors, success);
+    reached.map(|i| (parents.path_to(i), parents.nodes[i].2))
+}
+
+/// Dijkstra search that stops at the first node matching `stop`, returning
+/// the parent and cost of every reached node (`start` excluded).
+pub(crate) fn dijkstra_partial<N, I>(
+    start: &N,
+    successors: impl FnMut(&N) -> I,
+    stop: impl FnMut(&N) -> bool,
+) -> HashMap<N, (N, u32)>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, u32)>,
+{
+    let (parents, _) = run_dijkstra(start, successors, stop);
+    parents
+        .nodes
+        .iter()
+        .skip(1)
+        .map(|(node, parent, cost)| (node.clone(), (parents.nodes[*parent].0.clone(), *cost)))
+        .collect()
+}
+
+/// Returns the parent and cost of every node reachable from `start`.
+pub(crate) fn dijkstra_all<N, I>(start: &N, successors: impl FnMut(&N) -> I) -> HashMap<N, (N, u32)>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, u32)>,
+{
+    dijkstra_partial(start, successors, |_| false)
+}
+
+/// Follows the parents produced by [`dijkstra_partial`] back from `target`.
+pub(crate) fn build_path<N: Clone + Eq + Hash>(
+    target: &N,
+    parents: &HashMap<N, (N, u32)>,
+) -> Vec<N> {
+    let mut path = vec![target.clone()];
+    let mut next = target;
+    while let Some((parent, _)) = parents.get(next) {
+        path.push(parent.clone());
+        next = parent;
+    }
+    path.reverse();
+    path
+}
+
+/// Breadth-first search, returning the path with the fewest steps.
+pub(crate) fn bfs<N, I>(
+    start: &N,
+    mut successors: impl FnMut(&N) -> I,
+    mut success: impl FnMut(&N) -> bool,
+) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    if success(start) {
+        return Some(vec![start.clone()]);
+    }
+    let mut parents = Parents::new(start);
+    let mut i = 0;
+    while i < parents.nodes.len() {
+        for next in successors(&parents.nodes[i].0.clone()) {
+            if success(&next) {
+                let mut path = parents.path_to(i);
+                path.push(next);
+                return Some(path);
+            }
+            if !parents.index.contains_key(&next) {
+                parents.relax(next, i, 0);
+            }
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Depth-first search. The path found is not necessarily the shortest.
+pub(crate) fn dfs<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut success: impl FnMut(&N) -> bool,
+) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut to_visit = vec![start];
+    let mut visited = HashSet::new();
+    let mut parents: HashMap<N, N> = HashMap::new();
+    while let Some(node) = to_visit.pop() {
+        if !visited.insert(node.clone()) {
+            continue;
+        }
+        if success(&node) {
+            let mut path = vec![node.clone()];
+            let mut current = &node;
+            while let Some(parent) = parents.get(current) {
+                path.push(parent.clone());
+                current = parent;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        let next: Vec<N> = successors(&node).into_iter().collect();
+        for next in next.into_iter().rev() {
+            if !visited.contains(&next) {
+                parents.insert(next.clone(), node.clone());
+                to_visit.push(next);
+            }
+        }
+    }
+    None
+}
+
+/// Iterative deepening depth-first search, returning a path with the fewest steps.
+pub(crate) fn iddfs<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut success: impl FnMut(&N) -> bool,
+) -> Option<Vec<N>>
+where
+    N: Eq,
+    I: IntoIterator<Item = N>,
+{
+    enum Step {
+        Found,
+        NoneAtThisDepth,
+        Impossible,
+    }
+
+    fn step<N: Eq, I: IntoIterator<Item = N>>(
+        path: &mut Vec<N>,
+        successors: &mut impl FnMut(&N) -> I,
+        success: &mut impl FnMut(&N) -> bool,
+        depth: usize,
+    ) -> Step {
+        let Some(last) = path.last() else {
+            return Step::Impossible;
+        };
+        if depth == 0 {
+            return Step::NoneAtThisDepth;
+        }
+        if success(last) {
+            return Step::Found;
+        }
+        let mut best = Step::Impossible;
+        for next in successors(last) {
+            if path.contains(&next) {
+                continue;
+            }
+            path.push(next);
+            match step(path, successors, success, depth - 1) {
+                Step::Found => return Step::Found,
+                Step::NoneAtThisDepth => best = Step::NoneAtThisDepth,
+                Step::Impossible => {}
+            }
+            path.pop();
+        }
+        best
+    }
+
+    let mut path = vec![start];
+    let mut depth = 1;
+    loop {
+        match step(&mut path, &mut successors, &mut success, depth) {
+            Step::Found => return Some(path),
+            Step::NoneAtThisDepth => depth += 1,
+            Step::Impossible => return None,
+        }
+    }
+}
+
+/// Iterative deepening A*, returning the path and its cost.
+pub(crate) fn idastar<N, I>(
+    start: &N,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> u32,
+    mut success: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, u32)>
+where
+    N: Clone + Eq,
+    I: IntoIterator<Item = (N, u32)>,
+{
+    enum Step<N> {
+        Found(Vec<N>, u32),
+        Minimum(u32),
+        Impossible,
+    }
+
+    fn search<N: Clone + Eq, I: IntoIterator<Item = (N, u32)>>(
+        path: &mut Vec<N>,
+        cost: u32,
+        bound: u32,
+        successors: &mut impl FnMut(&N) -> I,
+        heuristic: &mut impl FnMut(&N) -> u32,
+        success: &mut impl FnMut(&N) -> bool,
+    ) -> Step<N> {
+        let neighbors = {
+            let Some(node) = path.last() else {
+                return Step::Impossible;
+            };
+            let f = cost.saturating_add(heuristic(node));
+            if f > bound {
+                return Step::Minimum(f);
+            }
+            if success(node) {
+                return Step::Found(path.clone(), f);
+            }
+            let mut neighbors: Vec<(N, u32, u32)> = successors(node)
+                .into_iter()
+                .filter(|(n, _)| !path.contains(n))
+                .map(|(n, c)| {
+                    let h = heuristic(&n);
+                    (n, c, c.saturating_add(h))
+                })
+                .collect();
+            neighbors.sort_unstable_by_key(|n| n.2);
+            neighbors
+        };
+        let mut min = None;
+        for (node, step, _) in neighbors {
+            let Some(next_cost) = cost.checked_add(step) else {
+                continue;
+            };
+            path.push(node);
+            match search(path, next_cost, bound, successors, heuristic, success) {
+                found @ Step::Found(..) => return found,
+                Step::Minimum(m) if min.is_none_or(|n| n >= m) => min = Some(m),
+                _ => {}
+            }
+            path.pop();
+        }
+        min.map_or(Step::Impossible, Step::Minimum)
+    }
+
+    let mut bound = heuristic(start);
+    let mut path = vec![start.clone()];
+    loop {
+        match search(
+            &mut path,
+            0,
+            bound,
+            &mut successors,
+            &mut heuristic,
+            &mut success,
+        ) {
+            Step::Found(path, cost) => return Some((path, cost)),
+            Step::Minimum(min) if min != bound => bound = min,
+            _ => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `0 -> 4` directly costs 10; the detour `0 -> 1 -> 2 -> 3 -> 4` costs 4.
+    /// Node 5 has no incoming edges.
+    const EDGES: [(u32, u32, u32); 6] = [
+        (0, 4, 10),
+        (0, 1, 1),
+        (1, 2, 1),
+        (2, 3, 1),
+        (3, 4, 1),
+        (5, 0, 1),
+    ];
+
+    fn weighted(node: &u32) -> Vec<(u32, u32)> {
+        EDGES
+            .iter()
+            .filter(|(from, ..)| from == node)
+            .map(|&(_, to, cost)| (to, cost))
+            .collect()
+    }
+
+    fn unweighted(node: &u32) -> Vec<u32> {
+        weighted(node).into_iter().map(|(to, _)| to).collect()
+    }
+
+    #[test]
+    fn test_cheap_path_with_more_steps() {
+        let cheapest = Some((vec![0, 1, 2, 3, 4], 4));
+        assert_eq!(astar(&0, weighted, |_| 0, |&n| n == 4), cheapest);
+        assert_eq!(dijkstra(&0, weighted, |&n| n == 4), cheapest);
+        assert_eq!(idastar(&0, weighted, |_| 0, |&n| n == 4), cheapest);
+
+        assert_eq!(bfs(&0, unweighted, |&n| n == 4), Some(vec![0, 4]));
+        assert_eq!(iddfs(0, unweighted, |&n| n == 4), Some(vec![0, 4]));
+        let path = dfs(0, unweighted, |&n| n == 4).unwrap();
+        assert_eq!((path.first(), path.last()), (Some(&0), Some(&4)));
+    }
+
+    #[test]
+    fn test_start_is_goal() {
+        assert_eq!(astar(&2, weighted, |_| 0, |&n| n == 2), Some((vec![2], 0)));
+        assert_eq!(dijkstra(&2, weighted, |&n| n == 2), Some((vec![2], 0)));
+        assert_eq!(
+            idastar(&2, weighted, |_| 0, |&n| n == 2),
+            Some((vec![2], 0))
+        );
+        assert_eq!(bfs(&2, unweighted, |&n| n == 2), Some(vec![2]));
+        assert_eq!(dfs(2, unweighted, |&n| n == 2), Some(vec![2]));
+        assert_eq!(iddfs(2, unweighted, |&n| n == 2), Some(vec![2]));
+    }
+
+    #[test]
+    fn test_unreachable_goal() {
+        assert_eq!(astar(&0, weighted, |_| 0, |&n| n == 5), None);
+        assert_eq!(dijkstra(&0, weighted, |&n| n == 5), None);
+        assert_eq!(idastar(&0, weighted, |_| 0, |&n| n == 5), None);
+        assert_eq!(bfs(&0, unweighted, |&n| n == 5), None);
+        assert_eq!(dfs(0, unweighted, |&n| n == 5), None);
+        assert_eq!(iddfs(0, unweighted, |&n| n == 5), None);
+    }
+
+    #[test]
+    fn test_overflowing_routes_are_skipped() {
+        // 0 -> 1 -> 2 would wrap around to a cost of 0.
+        let huge = |node: &u32| match node {
+            0 => vec![(1, u32::MAX), (2, 5)],
+            1 => vec![(2, 1)],
+            _ => vec![],
+        };
+        let direct = Some((vec![0, 2], 5));
+        assert_eq!(astar(&0, huge, |_| 0, |&n| n == 2), direct);
+        assert_eq!(dijkstra(&0, huge, |&n| n == 2), direct);
+        assert_eq!(idastar(&0, huge, |_| 0, |&n| n == 2), direct);
+        assert_eq!(astar(&0, huge, |_| u32::MAX, |&n| n == 2), direct);
+    }
+
+    #[test]
+    fn test_dijkstra_partial_parents() {
+        let parents = dijkstra_partial(&0, weighted, |&n| n == 3);
+        assert!(!parents.contains_key(&0));
+        assert_eq!(parents.get(&1), Some(&(0, 1)));
+        assert_eq!(parents.get(&3), Some(&(2, 3)));
+        assert_eq!(build_path(&3, &parents), vec![0, 1, 2, 3]);
+        // Seen from 0 but not yet settled, so still on the expensive edge.
+        assert_eq!(parents.get(&4), Some(&(0, 10)));
+
+        let all = dijkstra_all(&0, weighted);
+        assert_eq!(all.len(), 4);
+        assert_eq!(all.get(&4), Some(&(3, 4)));
+        assert_eq!(build_path(&4, &all), vec![0, 1, 2, 3, 4]);
+        assert_eq!(build_path(&0, &all), vec![0]);
+    }
+}
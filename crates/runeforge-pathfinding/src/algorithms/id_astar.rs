@@ -1,5 +1,7 @@
+use alloc::vec::Vec;
+
+use super::generic::idastar;
 use crate::prelude::*;
-use pathfinding::prelude::idastar;
 
 /// Iterative deepening A* algorithm.
 pub struct IDAstar;
@@ -1,5 +1,7 @@
+use alloc::vec::Vec;
+
+use super::generic::iddfs;
 use crate::prelude::*;
-use pathfinding::prelude::iddfs;
 
 /// Iterative deepening depth-first search algorithm.
 pub struct IDDfs;
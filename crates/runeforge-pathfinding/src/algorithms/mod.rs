@@ -1,14 +1,118 @@
-mod astar;
-pub use astar::*;
-mod bfs;
-pub use bfs::*;
-mod dfs;
-pub use dfs::*;
-mod dijkstra;
-pub use dijkstra::*;
-mod dijkstra_partial;
-pub use dijkstra_partial::*;
-mod id_astar;
-pub use id_astar::*;
-mod id_dfs;
-pub use id_dfs::*;
+pub(crate) mod generic;
+
+mod astar;
+pub use astar::*;
+mod bfs;
+pub use bfs::*;
+mod dfs;
+pub use dfs::*;
+mod dijkstra;
+pub use dijkstra::*;
+mod dijkstra_partial;
+pub use dijkstra_partial::*;
+mod id_astar;
+pub use id_astar::*;
+mod id_dfs;
+pub use id_dfs::*;
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    /// A 4x4 grid with a wall along `x == 2`, open only at `(2, 3)`.
+    fn walled() -> CostGrid {
+        CostGrid::from_walkable(4, 4, |p| p.x != 2 || p.y == 3).with_diagonals(false)
+    }
+
+    fn compute(algorithm: PathFinder, grid: &mut CostGrid, from: IVec2, to: IVec2) -> Vec<IVec2> {
+        algorithm.compute(from, to, grid, ())
+    }
+
+    fn cost(grid: &CostGrid, path: &[IVec2]) -> u32 {
+        path.iter().skip(1).map(|&p| grid.cost_at(p)).sum()
+    }
+
+    #[test]
+    fn test_paths_are_connected() {
+        let mut grid = walled();
+        let (origin, destination) = (IVec2::new(0, 0), IVec2::new(3, 0));
+        for algorithm in PathFinder::ALL {
+            let path = compute(algorithm, &mut grid, origin, destination);
+            assert_eq!(path.first(), Some(&origin), "{}", algorithm.name());
+            // The partial search may stop at a tile that merely looks closer.
+            if algorithm != PathFinder::DijkstraPartial {
+                assert_eq!(path.last(), Some(&destination), "{}", algorithm.name());
+            }
+            for step in path.windows(2) {
+                assert_eq!((step[1] - step[0]).abs().element_sum(), 1);
+                assert!(grid.is_passable(step[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_weighted_grid_prefers_cheap_detour() {
+        // Walking straight along y == 0 crosses a cost-9 tile; going around
+        // through y == 1 takes two more steps but costs 4 instead of 10.
+        let mut grid = CostGrid::from_fn(3, 2, |p| if p == IVec2::new(1, 0) { 9 } else { 1 })
+            .with_diagonals(false);
+        let (origin, destination) = (IVec2::new(0, 0), IVec2::new(2, 0));
+        for algorithm in [PathFinder::Astar, PathFinder::Dijkstra, PathFinder::IDAstar] {
+            let path = compute(algorithm, &mut grid, origin, destination);
+            assert_eq!(path.len(), 5, "{}", algorithm.name());
+            assert_eq!(cost(&grid, &path), 4, "{}", algorithm.name());
+        }
+        for algorithm in [PathFinder::Bfs, PathFinder::IDDfs] {
+            let path = compute(algorithm, &mut grid, origin, destination);
+            assert_eq!(path.len(), 3, "{}", algorithm.name());
+        }
+    }
+
+    #[test]
+    fn test_start_is_goal() {
+        let mut grid = walled();
+        let origin = IVec2::new(1, 1);
+        for algorithm in PathFinder::ALL {
+            let path = compute(algorithm, &mut grid, origin, origin);
+            assert_eq!(path, vec![origin], "{}", algorithm.name());
+        }
+    }
+
+    #[test]
+    fn test_unreachable_goal() {
+        let mut grid = walled();
+        grid.set_blocked(IVec2::new(2, 3), true);
+        let (origin, destination) = (IVec2::new(0, 0), IVec2::new(3, 3));
+        for algorithm in PathFinder::ALL {
+            let path = compute(algorithm, &mut grid, origin, destination);
+            if algorithm == PathFinder::DijkstraPartial {
+                // Heads for a reachable tile instead of giving up.
+                assert_eq!(path.first(), Some(&origin));
+                assert!(path.last().is_some_and(|&p| p.x < 2));
+            } else {
+                assert!(path.is_empty(), "{}", algorithm.name());
+            }
+        }
+    }
+
+    #[test]
+    fn test_iterative_deepening_matches_optimal() {
+        let mut grid = walled();
+        let points: Vec<IVec2> = (0..16)
+            .map(|i| IVec2::new(i % 4, i / 4))
+            .filter(|&p| grid.is_passable(p))
+            .collect();
+        for &origin in &points {
+            for &destination in &points {
+                let astar = compute(PathFinder::Astar, &mut grid, origin, destination);
+                let idastar = compute(PathFinder::IDAstar, &mut grid, origin, destination);
+                assert_eq!(cost(&grid, &idastar), cost(&grid, &astar));
+                assert_eq!(idastar.len(), astar.len());
+
+                let bfs = compute(PathFinder::Bfs, &mut grid, origin, destination);
+                let iddfs = compute(PathFinder::IDDfs, &mut grid, origin, destination);
+                assert_eq!(iddfs.len(), bfs.len());
+            }
+        }
+    }
+}
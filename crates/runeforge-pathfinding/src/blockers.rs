@@ -0,0 +1,319 @@
+//! Entity-aware dynamic obstacles.
+//!
+//! Creatures block tiles, but not the way walls do: a monster shouldn't be
+//! blocked by itself, should still path up to the player it wants to attack,
+//! and may be allowed to swap places with allies. Mixing entity positions
+//! into a cost function by hand tends to get one of those wrong, and the
+//! result is monsters stuck in corridors.
+//!
+//! [`DynamicBlockers`] tracks where entities stand and how they block. Moving
+//! an entity is a couple of map updates, so it can be kept in sync every
+//! turn. [`DynamicBlockers::over`] lays it over any [`PathProvider`], such as
+//! a [`CostGrid`], without touching the underlying map.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_pathfinding::prelude::*;
+//!
+//! let mut grid = CostGrid::new(5, 1, 1);
+//! let mut blockers = DynamicBlockers::new();
+//! blockers.insert(1, IVec2::new(0, 0), Occupancy::Solid); // the goblin
+//! blockers.insert(2, IVec2::new(2, 0), Occupancy::Solid); // its friend
+//! blockers.insert(3, IVec2::new(4, 0), Occupancy::Solid); // the player
+//!
+//! // The friend is in the way
+//! let mut view = blockers.over(&grid).ignoring(1).with_goal(IVec2::new(4, 0));
+//! assert!(PathFinder::Astar.compute(IVec2::new(0, 0), IVec2::new(4, 0), &mut view, ()).is_empty());
+//!
+//! // Allies may swap places, at a price
+//! blockers.set_occupancy(2, Occupancy::Swappable(3));
+//! let mut view = blockers.over(&grid).ignoring(1).with_goal(IVec2::new(4, 0));
+//! let path = PathFinder::Astar.compute(IVec2::new(0, 0), IVec2::new(4, 0), &mut view, ());
+//! assert_eq!(path.len(), 5);
+//! ```
+
+use alloc::vec::Vec;
+use core::hash::Hash;
+
+use hashbrown::HashMap;
+
+use crate::prelude::*;
+
+/// How an entity affects paths through its tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Occupancy {
+    /// Nothing can path through the tile.
+    #[default]
+    Solid,
+    /// Paths may go through at this extra cost, e.g. allies that can swap
+    /// places or creatures that can be pushed aside.
+    Swappable(u32),
+    /// The entity doesn't affect paths (ghosts, items, the dead).
+    PassThrough,
+}
+
+/// Entity positions and how they block, kept separate from the map.
+#[derive(Debug, Clone)]
+pub struct DynamicBlockers<K> {
+    entities: HashMap<K, (IVec2, Occupancy)>,
+    tiles: HashMap<IVec2, Vec<K>>,
+}
+
+impl<K> Default for DynamicBlockers<K> {
+    fn default() -> Self {
+        Self {
+            entities: HashMap::new(),
+            tiles: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Copy + Eq + Hash> DynamicBlockers<K> {
+    /// Creates an empty layer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Places an entity, moving it if it is already present.
+    pub fn insert(&mut self, id: K, pos: IVec2, occupancy: Occupancy) {
+        self.remove(id);
+        self.entities.insert(id, (pos, occupancy));
+        self.tiles.entry(pos).or_default().push(id);
+    }
+
+    /// Removes an entity, returning where it was.
+    pub fn remove(&mut self, id: K) -> Option<IVec2> {
+        let (pos, _) = self.entities.remove(&id)?;
+        self.leave(id, pos);
+        Some(pos)
+    }
+
+    /// Moves an entity to `pos`. Returns false if the entity isn't present.
+    pub fn move_to(&mut self, id: K, pos: IVec2) -> bool {
+        let Some(entry) = self.entities.get_mut(&id) else {
+            return false;
+        };
+        let from = core::mem::replace(&mut entry.0, pos);
+        if from != pos {
+            self.leave(id, from);
+            self.tiles.entry(pos).or_default().push(id);
+        }
+        true
+    }
+
+    /// Changes how an entity blocks. Returns false if it isn't present.
+    pub fn set_occupancy(&mut self, id: K, occupancy: Occupancy) -> bool {
+        match self.entities.get_mut(&id) {
+            Some(entry) => {
+                entry.1 = occupancy;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns an entity's position.
+    pub fn position(&self, id: K) -> Option<IVec2> {
+        self.entities.get(&id).map(|&(pos, _)| pos)
+    }
+
+    /// Returns an entity's occupancy.
+    pub fn occupancy(&self, id: K) -> Option<Occupancy> {
+        self.entities.get(&id).map(|&(_, occupancy)| occupancy)
+    }
+
+    /// Returns the entities standing on `pos`.
+    pub fn occupants(&self, pos: IVec2) -> &[K] {
+        self.tiles.get(&pos).map_or(&[], |ids| ids.as_slice())
+    }
+
+    /// Returns how `pos` affects paths, ignoring `ignore`: [`Occupancy::Solid`]
+    /// if any occupant is solid, otherwise the summed swap costs, or
+    /// [`Occupancy::PassThrough`] if nothing is in the way.
+    pub fn occupancy_at(&self, pos: IVec2, ignore: Option<K>) -> Occupancy {
+        let mut extra = None;
+        for &id in self.occupants(pos) {
+            if Some(id) == ignore {
+                continue;
+            }
+            match self.entities[&id].1 {
+                Occupancy::Solid => return Occupancy::Solid,
+                Occupancy::Swappable(cost) => {
+                    extra = Some(extra.unwrap_or(0u32).saturating_add(cost))
+                }
+                Occupancy::PassThrough => {}
+            }
+        }
+        extra.map_or(Occupancy::PassThrough, Occupancy::Swappable)
+    }
+
+    /// Returns true if a solid entity stands on `pos`.
+    pub fn is_blocked(&self, pos: IVec2) -> bool {
+        self.occupancy_at(pos, None) == Occupancy::Solid
+    }
+
+    /// Returns the number of entities.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Returns true if there are no entities.
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Removes every entity.
+    pub fn clear(&mut self) {
+        self.entities.clear();
+        self.tiles.clear();
+    }
+
+    /// Lays this layer over `provider` for a search.
+    pub fn over<'a, P: ?Sized>(&'a self, provider: &'a P) -> BlockedView<'a, P, K> {
+        BlockedView {
+            provider,
+            blockers: self,
+            ignore: None,
+            goal: None,
+        }
+    }
+
+    fn leave(&mut self, id: K, pos: IVec2) {
+        if let Some(ids) = self.tiles.get_mut(&pos) {
+            ids.retain(|&other| other != id);
+            if ids.is_empty() {
+                self.tiles.remove(&pos);
+            }
+        }
+    }
+}
+
+/// A [`PathProvider`] with [`DynamicBlockers`] laid over it.
+///
+/// Solid entities remove their tiles, and swappable ones add their cost.
+/// The searching entity never blocks itself (see [`ignoring`](Self::ignoring)),
+/// and the goal is always enterable (see [`with_goal`](Self::with_goal)), so
+/// a monster can path up to the creature it is chasing.
+#[derive(Debug)]
+pub struct BlockedView<'a, P: ?Sized, K> {
+    provider: &'a P,
+    blockers: &'a DynamicBlockers<K>,
+    ignore: Option<K>,
+    goal: Option<IVec2>,
+}
+
+impl<P: ?Sized, K: Copy> Clone for BlockedView<'_, P, K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P: ?Sized, K: Copy> Copy for BlockedView<'_, P, K> {}
+
+impl<P: ?Sized, K: Copy + Eq + Hash> BlockedView<'_, P, K> {
+    /// Ignores the searching entity, so it isn't blocked by itself.
+    pub fn ignoring(mut self, id: K) -> Self {
+        self.ignore = Some(id);
+        self
+    }
+
+    /// Lets the search enter `goal` whoever stands there, e.g. the target of
+    /// an attack.
+    pub fn with_goal(mut self, goal: IVec2) -> Self {
+        self.goal = Some(goal);
+        self
+    }
+
+    fn occupancy(&self, pos: IVec2) -> Occupancy {
+        if Some(pos) == self.goal {
+            Occupancy::PassThrough
+        } else {
+            self.blockers.occupancy_at(pos, self.ignore)
+        }
+    }
+}
+
+impl<T, P, K> PathProvider<T> for BlockedView<'_, P, K>
+where
+    P: PathProvider<T> + ?Sized,
+    K: Copy + Eq + Hash,
+{
+    fn get_neighbors(&self, position: IVec2, pass_through_data: &mut T) -> Vec<IVec2> {
+        let mut neighbors = self.provider.get_neighbors(position, pass_through_data);
+        neighbors.retain(|&p| self.occupancy(p) != Occupancy::Solid);
+        neighbors
+    }
+
+    fn cost(&self, from_position: IVec2, to_position: IVec2, pass_through_data: &mut T) -> u32 {
+        let cost = self
+            .provider
+            .cost(from_position, to_position, pass_through_data);
+        match self.occupancy(to_position) {
+            Occupancy::Swappable(extra) => cost.saturating_add(extra),
+            _ => cost,
+        }
+    }
+
+    fn distance(&self, origin: IVec2, destination: IVec2) -> u32 {
+        self.provider.distance(origin, destination)
+    }
+
+    fn generate_successors(&self, p: IVec2, pass_through_data: &mut T) -> Vec<(IVec2, u32)> {
+        let mut successors = self.provider.generate_successors(p, pass_through_data);
+        successors.retain_mut(|(next, cost)| match self.occupancy(*next) {
+            Occupancy::Solid => false,
+            Occupancy::Swappable(extra) => {
+                *cost = cost.saturating_add(extra);
+                true
+            }
+            Occupancy::PassThrough => true,
+        });
+        successors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_moves_update_tiles() {
+        let mut blockers = DynamicBlockers::new();
+        blockers.insert('a', IVec2::new(1, 1), Occupancy::Solid);
+        blockers.insert('b', IVec2::new(1, 1), Occupancy::PassThrough);
+        assert!(blockers.is_blocked(IVec2::new(1, 1)));
+
+        assert!(blockers.move_to('a', IVec2::new(2, 1)));
+        assert_eq!(blockers.occupants(IVec2::new(1, 1)), &['b']);
+        assert!(!blockers.is_blocked(IVec2::new(1, 1)));
+        assert!(blockers.is_blocked(IVec2::new(2, 1)));
+        assert!(!blockers.move_to('z', IVec2::ZERO));
+
+        assert_eq!(blockers.remove('a'), Some(IVec2::new(2, 1)));
+        assert!(blockers.occupants(IVec2::new(2, 1)).is_empty());
+        assert_eq!(blockers.len(), 1);
+    }
+
+    #[test]
+    fn test_view_ignores_self_and_goal() {
+        let grid = CostGrid::new(3, 3, 1).with_diagonals(false);
+        let mut blockers = DynamicBlockers::new();
+        blockers.insert(0u32, IVec2::new(0, 1), Occupancy::Solid);
+        blockers.insert(1, IVec2::new(1, 1), Occupancy::Swappable(5));
+        blockers.insert(2, IVec2::new(2, 1), Occupancy::Solid);
+
+        let view = blockers.over(&grid);
+        let mut data = ();
+        let neighbors = view.get_neighbors(IVec2::new(0, 0), &mut data);
+        assert_eq!(neighbors, vec![IVec2::new(1, 0)]);
+        assert_eq!(view.cost(IVec2::new(1, 0), IVec2::new(1, 1), &mut data), 6);
+
+        // The searcher's own tile and the goal stay open
+        let view = view.ignoring(0).with_goal(IVec2::new(2, 1));
+        let successors = view.generate_successors(IVec2::new(1, 1), &mut data);
+        assert!(successors.contains(&(IVec2::new(0, 1), 1)));
+        assert!(successors.contains(&(IVec2::new(2, 1), 1)));
+        assert_eq!(view.cost(IVec2::new(1, 0), IVec2::new(1, 1), &mut data), 6);
+    }
+}
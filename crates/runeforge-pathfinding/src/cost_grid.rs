@@ -0,0 +1,199 @@
+use alloc::{vec, vec::Vec};
+
+use hashbrown::HashSet;
+
+use crate::prelude::*;
+
+/// Movement cost of a tile that can't be entered.
+pub const IMPASSABLE: u32 = u32::MAX;
+
+/// A ready-made [`PathProvider`] for rectangular maps with per-tile movement costs.
+///
+/// Each tile stores the cost of moving onto it, or [`IMPASSABLE`]. Dynamic
+/// blockers (closed doors, other creatures) live in a separate layer so they
+/// can be toggled every turn without losing the underlying terrain cost.
+///
+/// # Example
+///
+/// ```
+/// use runeforge_pathfinding::prelude::*;
+///
+/// // A 5x3 room with a swamp in the middle row.
+/// let mut grid = CostGrid::from_fn(5, 3, |p| if p.y == 1 && p.x > 0 && p.x < 4 { 5 } else { 1 });
+/// let path = PathFinder::Astar.compute(IVec2::new(0, 1), IVec2::new(4, 1), &mut grid, ());
+/// // Walking around the swamp is cheaper than wading through it.
+/// assert!(path.iter().all(|p| p.y != 1 || p.x == 0 || p.x == 4));
+///
+/// grid.set_blocked(IVec2::new(2, 0), true);
+/// assert!(!grid.is_passable(IVec2::new(2, 0)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CostGrid {
+    width: u32,
+    height: u32,
+    costs: Vec<u32>,
+    blocked: HashSet<IVec2>,
+    diagonals: bool,
+}
+
+impl CostGrid {
+    /// Creates a grid where every tile has the same cost.
+    pub fn new(width: u32, height: u32, cost: u32) -> Self {
+        Self {
+            width,
+            height,
+            costs: vec![cost.max(1); (width * height) as usize],
+            blocked: HashSet::new(),
+            diagonals: true,
+        }
+    }
+
+    /// Creates a grid with the cost of each tile returned by `cost`.
+    pub fn from_fn(width: u32, height: u32, mut cost: impl FnMut(IVec2) -> u32) -> Self {
+        let mut grid = Self::new(width, height, 1);
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                let pos = IVec2::new(x, y);
+                grid.set_cost(pos, cost(pos));
+            }
+        }
+        grid
+    }
+
+    /// Creates a grid where walkable tiles cost 1 and the rest are impassable.
+    pub fn from_walkable(width: u32, height: u32, mut walkable: impl FnMut(IVec2) -> bool) -> Self {
+        Self::from_fn(width, height, |p| if walkable(p) { 1 } else { IMPASSABLE })
+    }
+
+    /// Sets whether diagonal moves are allowed (the default).
+    pub fn with_diagonals(mut self, diagonals: bool) -> Self {
+        self.diagonals = diagonals;
+        self
+    }
+
+    /// Returns true if diagonal moves are allowed.
+    pub fn has_diagonals(&self) -> bool {
+        self.diagonals
+    }
+
+    /// Returns the grid width.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the grid height.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns true if the position is inside the grid.
+    pub fn in_bounds(&self, pos: IVec2) -> bool {
+        pos.x >= 0 && pos.y >= 0 && pos.x < self.width as i32 && pos.y < self.height as i32
+    }
+
+    /// Returns the terrain cost of a tile, ignoring dynamic blockers.
+    ///
+    /// Positions outside the grid are [`IMPASSABLE`].
+    pub fn cost_at(&self, pos: IVec2) -> u32 {
+        self.index(pos).map_or(IMPASSABLE, |i| self.costs[i])
+    }
+
+    /// Sets the terrain cost of a tile. Costs below 1 are stored as 1.
+    pub fn set_cost(&mut self, pos: IVec2, cost: u32) {
+        if let Some(i) = self.index(pos) {
+            self.costs[i] = cost.max(1);
+        }
+    }
+
+    /// Blocks or unblocks a tile without changing its terrain cost.
+    pub fn set_blocked(&mut self, pos: IVec2, blocked: bool) {
+        if blocked {
+            self.blocked.insert(pos);
+        } else {
+            self.blocked.remove(&pos);
+        }
+    }
+
+    /// Returns true if the tile is blocked by a dynamic blocker.
+    pub fn is_blocked(&self, pos: IVec2) -> bool {
+        self.blocked.contains(&pos)
+    }
+
+    /// Removes every dynamic blocker.
+    pub fn clear_blocked(&mut self) {
+        self.blocked.clear();
+    }
+
+    /// Returns true if the tile can be entered.
+    pub fn is_passable(&self, pos: IVec2) -> bool {
+        self.cost_at(pos) != IMPASSABLE && !self.is_blocked(pos)
+    }
+
+    fn index(&self, pos: IVec2) -> Option<usize> {
+        self.in_bounds(pos)
+            .then(|| (pos.y as u32 * self.width + pos.x as u32) as usize)
+    }
+}
+
+impl<T> PathProvider<T> for CostGrid {
+    fn get_neighbors(&self, position: IVec2, _pass_through_data: &mut T) -> Vec<IVec2> {
+        const CARDINAL: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+        const DIAGONAL: [IVec2; 4] = [
+            IVec2::new(1, 1),
+            IVec2::new(1, -1),
+            IVec2::new(-1, 1),
+            IVec2::new(-1, -1),
+        ];
+        let diagonals: &[IVec2] = if self.diagonals { &DIAGONAL } else { &[] };
+        CARDINAL
+            .iter()
+            .chain(diagonals)
+            .map(|&d| position + d)
+            .filter(|&p| self.is_passable(p))
+            .collect()
+    }
+
+    fn cost(&self, _from_position: IVec2, to_position: IVec2, _pass_through_data: &mut T) -> u32 {
+        self.cost_at(to_position)
+    }
+
+    fn distance(&self, origin: IVec2, destination: IVec2) -> u32 {
+        let delta = (origin - destination).abs();
+        if self.diagonals {
+            delta.max_element() as u32
+        } else {
+            (delta.x + delta.y) as u32
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_avoids_expensive_tiles() {
+        let mut grid = CostGrid::from_fn(3, 3, |p| if p == IVec2::new(1, 1) { 10 } else { 1 })
+            .with_diagonals(false);
+        let path = PathFinder::Dijkstra.compute(IVec2::new(0, 1), IVec2::new(2, 1), &mut grid, ());
+        assert_eq!(path.len(), 5);
+        assert!(!path.contains(&IVec2::new(1, 1)));
+    }
+
+    #[test]
+    fn test_blockers_keep_terrain_cost() {
+        let mut grid = CostGrid::from_walkable(3, 1, |p| p.x != 2);
+        assert!(!grid.is_passable(IVec2::new(2, 0)));
+        assert!(!grid.is_passable(IVec2::new(-1, 0)));
+
+        grid.set_cost(IVec2::new(1, 0), 3);
+        grid.set_blocked(IVec2::new(1, 0), true);
+        let path = PathFinder::Astar.compute(IVec2::new(0, 0), IVec2::new(1, 0), &mut grid, ());
+        assert!(path.is_empty());
+
+        grid.clear_blocked();
+        assert_eq!(grid.cost_at(IVec2::new(1, 0)), 3);
+        let path = PathFinder::Astar.compute(IVec2::new(0, 0), IVec2::new(1, 0), &mut grid, ());
+        assert_eq!(path, vec![IVec2::new(0, 0), IVec2::new(1, 0)]);
+    }
+}
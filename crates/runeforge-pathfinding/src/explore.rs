@@ -0,0 +1,272 @@
+use alloc::collections::VecDeque;
+use alloc::{vec, vec::Vec};
+use core::cmp::Reverse;
+use core::hash::Hash;
+
+use hashbrown::{HashMap, HashSet};
+
+use crate::prelude::*;
+
+const CARDINAL: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+const DIAGONAL: [IVec2; 4] = [
+    IVec2::new(1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, -1),
+];
+
+/// Returns the path from `start` to the cheapest unexplored tile that borders
+/// explored ground, or `None` once everything reachable has been explored.
+///
+/// Only explored tiles are walked through, using their costs in `grid`; the
+/// last step of the path is the unexplored tile itself. Its cost is never
+/// read, so `grid` may describe the real map without leaking what the player
+/// hasn't seen. Follow the path until the target becomes explored, then call
+/// this again.
+///
+/// # Example
+///
+/// ```
+/// use runeforge_pathfinding::prelude::*;
+///
+/// let grid = CostGrid::new(10, 1, 1);
+/// let explored = |p: IVec2| p.x < 4;
+/// let path = auto_explore(IVec2::new(1, 0), explored, &grid).unwrap();
+/// assert_eq!(path.last(), Some(&IVec2::new(4, 0)));
+///
+/// assert!(auto_explore(IVec2::new(1, 0), |_| true, &grid).is_none());
+/// ```
+pub fn auto_explore(
+    start: IVec2,
+    is_explored: impl Fn(IVec2) -> bool,
+    grid: &CostGrid,
+) -> Option<Vec<IVec2>> {
+    let (frontier, came_from) = dijkstra(start, grid, |p| {
+        if grid.in_bounds(p) && !is_explored(p) {
+            Step::Goal
+        } else if is_explored(p) && grid.is_passable(p) {
+            Step::Walk
+        } else {
+            Step::Skip
+        }
+    })?;
+    Some(build_path(frontier, &came_from))
+}
+
+/// Returns the cheapest path from `start` to `goal` through explored tiles.
+pub fn explored_path(
+    start: IVec2,
+    goal: IVec2,
+    is_explored: impl Fn(IVec2) -> bool,
+    grid: &CostGrid,
+) -> Option<Vec<IVec2>> {
+    if start == goal {
+        return Some(vec![start]);
+    }
+    let (goal, came_from) = dijkstra(start, grid, |p| {
+        if !is_explored(p) || !grid.is_passable(p) {
+            Step::Skip
+        } else if p == goal {
+            Step::Goal
+        } else {
+            Step::Walk
+        }
+    })?;
+    Some(build_path(goal, &came_from))
+}
+
+enum Step {
+    Walk,
+    Goal,
+    Skip,
+}
+
+fn dijkstra(
+    start: IVec2,
+    grid: &CostGrid,
+    classify: impl Fn(IVec2) -> Step,
+) -> Option<(IVec2, HashMap<IVec2, IVec2>)> {
+    let diagonals: &[IVec2] = if grid.has_diagonals() { &DIAGONAL } else { &[] };
+    let mut costs: HashMap<IVec2, u32> = HashMap::from([(start, 0)]);
+    let mut came_from = HashMap::new();
+    let mut open = BinaryHeap::from([Reverse((0u32, start.y, start.x))]);
+
+    while let Some(Reverse((cost, y, x))) = open.pop() {
+        let current = IVec2::new(x, y);
+        if costs.get(&current).is_some_and(|&c| c < cost) {
+            continue;
+        }
+        for &dir in CARDINAL.iter().chain(diagonals) {
+            let next = current + dir;
+            let next_cost = match classify(next) {
+                Step::Skip => continue,
+                Step::Goal => {
+                    came_from.insert(next, current);
+                    return Some((next, came_from));
+                }
+                Step::Walk => cost.saturating_add(grid.cost_at(next)),
+            };
+            if costs.get(&next).is_none_or(|&c| next_cost < c) {
+                costs.insert(next, next_cost);
+                came_from.insert(next, current);
+                open.push(Reverse((next_cost, next.y, next.x)));
+            }
+        }
+    }
+    None
+}
+
+fn build_path(goal: IVec2, came_from: &HashMap<IVec2, IVec2>) -> Vec<IVec2> {
+    let mut path = vec![goal];
+    let mut current = goal;
+    while let Some(&prev) = came_from.get(&current) {
+        path.push(prev);
+        current = prev;
+    }
+    path.reverse();
+    path
+}
+
+/// What an [`AutoTravel`] wants to do this turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TravelStep<K> {
+    /// Move to this position.
+    Move(IVec2),
+    /// The destination was reached.
+    Arrived,
+    /// Travel stopped because these hostiles came into view.
+    Interrupted(Vec<K>),
+}
+
+/// Follows a path one step per turn, stopping when a new hostile comes into view.
+///
+/// Hostiles are identified by `K` (an entity id, or a position for simple
+/// games). Hostiles already visible when travel starts don't interrupt it.
+///
+/// # Example
+///
+/// ```
+/// use runeforge_pathfinding::prelude::*;
+///
+/// let path = vec![IVec2::new(0, 0), IVec2::new(1, 0), IVec2::new(2, 0)];
+/// let mut travel = AutoTravel::new(path, ["rat"]);
+///
+/// assert_eq!(travel.next_step(["rat"]), TravelStep::Move(IVec2::new(1, 0)));
+/// assert_eq!(travel.next_step(["rat", "ogre"]), TravelStep::Interrupted(vec!["ogre"]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct AutoTravel<K> {
+    path: VecDeque<IVec2>,
+    known_hostiles: HashSet<K>,
+}
+
+impl<K: Hash + Eq + Clone> AutoTravel<K> {
+    /// Starts travel along `path` (which may include the current position as
+    /// its first element) with the hostiles that are currently visible.
+    pub fn new(path: Vec<IVec2>, visible_hostiles: impl IntoIterator<Item = K>) -> Self {
+        let mut path = VecDeque::from(path);
+        // The first element is the traveller's own position.
+        path.pop_front();
+        Self {
+            path,
+            known_hostiles: visible_hostiles.into_iter().collect(),
+        }
+    }
+
+    /// Plans travel to a previously seen position through explored tiles.
+    ///
+    /// Returns `None` if the goal can't be reached over explored ground.
+    pub fn to(
+        start: IVec2,
+        goal: IVec2,
+        is_explored: impl Fn(IVec2) -> bool,
+        grid: &CostGrid,
+        visible_hostiles: impl IntoIterator<Item = K>,
+    ) -> Option<Self> {
+        let path = explored_path(start, goal, is_explored, grid)?;
+        Some(Self::new(path, visible_hostiles))
+    }
+
+    /// Returns the next step, given the hostiles visible this turn.
+    ///
+    /// After an interruption the new hostiles count as known, so calling
+    /// again resumes travel (if the player chooses to).
+    pub fn next_step(&mut self, visible_hostiles: impl IntoIterator<Item = K>) -> TravelStep<K> {
+        let new: Vec<K> = visible_hostiles
+            .into_iter()
+            .filter(|h| !self.known_hostiles.contains(h))
+            .collect();
+        if !new.is_empty() {
+            self.known_hostiles.extend(new.iter().cloned());
+            return TravelStep::Interrupted(new);
+        }
+        match self.path.pop_front() {
+            Some(pos) => TravelStep::Move(pos),
+            None => TravelStep::Arrived,
+        }
+    }
+
+    /// Returns the steps that haven't been taken yet.
+    pub fn remaining(&self) -> impl Iterator<Item = IVec2> + '_ {
+        self.path.iter().copied()
+    }
+
+    /// Returns true once every step has been taken.
+    pub fn is_finished(&self) -> bool {
+        self.path.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explore_goes_to_nearest_frontier() {
+        // A corridor explored from x = 3..=6; the left end is closer.
+        let grid = CostGrid::new(12, 1, 1);
+        let explored = |p: IVec2| (3..=6).contains(&p.x);
+        let path = auto_explore(IVec2::new(4, 0), explored, &grid).unwrap();
+        assert_eq!(
+            path,
+            vec![IVec2::new(4, 0), IVec2::new(3, 0), IVec2::new(2, 0)]
+        );
+    }
+
+    #[test]
+    fn test_explore_ignores_unreachable_frontiers() {
+        // Explored tiles on both sides of a wall; only the far side borders the unknown.
+        let grid = CostGrid::from_walkable(6, 3, |p| p.x != 2).with_diagonals(false);
+        let explored = |p: IVec2| p.x < 5;
+        assert!(auto_explore(IVec2::new(0, 1), explored, &grid).is_none());
+        let path = auto_explore(IVec2::new(3, 1), explored, &grid).unwrap();
+        assert_eq!(path.last().unwrap().x, 5);
+    }
+
+    #[test]
+    fn test_travel_through_explored_tiles() {
+        let grid = CostGrid::new(5, 5, 1);
+        let explored = |p: IVec2| p.y == 0 || p.x == 4;
+        let mut travel =
+            AutoTravel::to(IVec2::ZERO, IVec2::new(4, 4), explored, &grid, [0u32]).unwrap();
+        let mut steps = 0;
+        while let TravelStep::Move(pos) = travel.next_step([0]) {
+            assert!(explored(pos));
+            steps += 1;
+        }
+        assert_eq!(steps, 7);
+        assert!(travel.is_finished());
+
+        assert!(
+            AutoTravel::<u32>::to(IVec2::ZERO, IVec2::new(2, 2), explored, &grid, []).is_none()
+        );
+    }
+
+    #[test]
+    fn test_interruption_resumes() {
+        let mut travel = AutoTravel::new(vec![IVec2::ZERO, IVec2::X], Vec::<u8>::new());
+        assert_eq!(travel.next_step([7]), TravelStep::Interrupted(vec![7]));
+        assert_eq!(travel.next_step([7]), TravelStep::Move(IVec2::X));
+        assert_eq!(travel.next_step([7]), TravelStep::Arrived);
+    }
+}
@@ -0,0 +1,211 @@
+use alloc::vec::Vec;
+use core::hash::Hash;
+
+use hashbrown::HashMap;
+
+use crate::algorithms::generic::{astar, bfs, dijkstra_all};
+
+use crate::prelude::*;
+
+/// A weighted directed graph over arbitrary nodes.
+///
+/// Implement this for world-map travel networks, portal graphs or room
+/// adjacency graphs to reuse the search algorithms without a grid. Edge costs
+/// must be non-negative; [`heuristic`](Self::heuristic) must never
+/// overestimate the remaining cost (the default of 0 turns A* into Dijkstra).
+///
+/// # Example
+///
+/// ```
+/// use runeforge_pathfinding::prelude::*;
+///
+/// let mut roads = AdjacencyGraph::new();
+/// roads.add_undirected_edge("village", "ford", 3);
+/// roads.add_undirected_edge("ford", "castle", 4);
+/// roads.add_undirected_edge("village", "castle", 10);
+///
+/// let (route, cost) = roads.shortest_path(&"village", &"castle").unwrap();
+/// assert_eq!(route, vec!["village", "ford", "castle"]);
+/// assert_eq!(cost, 7);
+/// ```
+pub trait Graph {
+    /// The node type.
+    type Node: Clone + Eq + Hash;
+
+    /// Returns the nodes reachable from `node` in one step, with the cost of each step.
+    fn neighbors(&self, node: &Self::Node) -> impl Iterator<Item = (Self::Node, u32)>;
+
+    /// Returns a lower bound on the cost from `from` to `to`.
+    fn heuristic(&self, _from: &Self::Node, _to: &Self::Node) -> u32 {
+        0
+    }
+
+    /// Finds the cheapest path with A*, returning the nodes (start and goal
+    /// inclusive) and the total cost.
+    fn shortest_path(
+        &self,
+        start: &Self::Node,
+        goal: &Self::Node,
+    ) -> Option<(Vec<Self::Node>, u32)> {
+        astar(
+            start,
+            |n| self.neighbors(n).collect::<Vec<_>>(),
+            |n| self.heuristic(n, goal),
+            |n| n == goal,
+        )
+    }
+
+    /// Finds the path with the fewest steps, ignoring costs.
+    fn fewest_steps(&self, start: &Self::Node, goal: &Self::Node) -> Option<Vec<Self::Node>> {
+        bfs(
+            start,
+            |n| self.neighbors(n).map(|(next, _)| next).collect::<Vec<_>>(),
+            |n| n == goal,
+        )
+    }
+
+    /// Returns the cheapest cost from `start` to every reachable node
+    /// (`start` itself excluded).
+    fn costs_from(&self, start: &Self::Node) -> HashMap<Self::Node, u32> {
+        dijkstra_all(start, |n| self.neighbors(n).collect::<Vec<_>>())
+            .into_iter()
+            .map(|(node, (_, cost))| (node, cost))
+            .collect()
+    }
+}
+
+/// A graph stored as an adjacency list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdjacencyGraph<N: Eq + Hash> {
+    edges: HashMap<N, Vec<(N, u32)>>,
+}
+
+impl<N: Clone + Eq + Hash> Default for AdjacencyGraph<N> {
+    fn default() -> Self {
+        Self {
+            edges: HashMap::new(),
+        }
+    }
+}
+
+impl<N: Clone + Eq + Hash> AdjacencyGraph<N> {
+    /// Creates an empty graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a node with no edges. Adding an existing node does nothing.
+    pub fn add_node(&mut self, node: N) {
+        self.edges.entry(node).or_default();
+    }
+
+    /// Adds a one-way edge, replacing the cost of an existing edge between the same nodes.
+    pub fn add_edge(&mut self, from: N, to: N, cost: u32) {
+        self.add_node(to.clone());
+        let edges = self.edges.entry(from).or_default();
+        match edges.iter_mut().find(|(n, _)| *n == to) {
+            Some(edge) => edge.1 = cost,
+            None => edges.push((to, cost)),
+        }
+    }
+
+    /// Adds an edge in both directions.
+    pub fn add_undirected_edge(&mut self, a: N, b: N, cost: u32) {
+        self.add_edge(a.clone(), b.clone(), cost);
+        self.add_edge(b, a, cost);
+    }
+
+    /// Removes the edge from `from` to `to`. Returns true if it existed.
+    pub fn remove_edge(&mut self, from: &N, to: &N) -> bool {
+        let Some(edges) = self.edges.get_mut(from) else {
+            return false;
+        };
+        let len = edges.len();
+        edges.retain(|(n, _)| n != to);
+        edges.len() != len
+    }
+
+    /// Returns true if the node is in the graph.
+    pub fn contains(&self, node: &N) -> bool {
+        self.edges.contains_key(node)
+    }
+
+    /// Iterates over every node.
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.edges.keys()
+    }
+
+    /// Returns the number of nodes.
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Returns true if the graph has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+}
+
+impl<N: Clone + Eq + Hash> Graph for AdjacencyGraph<N> {
+    type Node = N;
+
+    fn neighbors(&self, node: &N) -> impl Iterator<Item = (N, u32)> {
+        self.edges.get(node).into_iter().flatten().cloned()
+    }
+}
+
+impl Graph for CostGrid {
+    type Node = IVec2;
+
+    fn neighbors(&self, node: &IVec2) -> impl Iterator<Item = (IVec2, u32)> {
+        PathProvider::<()>::get_neighbors(self, *node, &mut ())
+            .into_iter()
+            .map(|next| (next, self.cost_at(next)))
+    }
+
+    fn heuristic(&self, from: &IVec2, to: &IVec2) -> u32 {
+        PathProvider::<()>::distance(self, *from, *to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directed_edges() {
+        let mut graph = AdjacencyGraph::new();
+        graph.add_edge(1, 2, 1);
+        graph.add_edge(2, 3, 1);
+        assert!(graph.shortest_path(&3, &1).is_none());
+        assert_eq!(graph.fewest_steps(&1, &3), Some(vec![1, 2, 3]));
+
+        graph.add_edge(1, 3, 5);
+        graph.add_edge(1, 3, 1);
+        assert_eq!(graph.shortest_path(&1, &3), Some((vec![1, 3], 1)));
+        assert!(graph.remove_edge(&1, &3));
+        assert!(!graph.remove_edge(&1, &3));
+        assert_eq!(graph.len(), 3);
+    }
+
+    #[test]
+    fn test_costs_from() {
+        let mut graph = AdjacencyGraph::new();
+        graph.add_undirected_edge('a', 'b', 2);
+        graph.add_undirected_edge('b', 'c', 2);
+        graph.add_undirected_edge('a', 'c', 5);
+        graph.add_node('z');
+        let costs = graph.costs_from(&'a');
+        assert_eq!(costs.get(&'c'), Some(&4));
+        assert!(!costs.contains_key(&'z'));
+    }
+
+    #[test]
+    fn test_cost_grid_as_graph() {
+        let grid = CostGrid::from_walkable(5, 5, |p| p.x != 2 || p.y == 4);
+        let (path, cost) = grid.shortest_path(&IVec2::ZERO, &IVec2::new(4, 0)).unwrap();
+        assert_eq!(path.first(), Some(&IVec2::ZERO));
+        assert!(path.contains(&IVec2::new(2, 4)));
+        assert_eq!(cost, path.len() as u32 - 1);
+    }
+}
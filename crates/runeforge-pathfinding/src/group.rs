@@ -0,0 +1,242 @@
+//! Moving groups toward a shared goal without stacking up.
+//!
+//! Pathing each monster of a pack on its own sends them all down the same
+//! tiles: they queue behind each other, try to step onto the same square, or
+//! block a corridor for the whole group. Instead, compute one [`FlowField`]
+//! toward the goal and move every member downhill on it each turn, with a
+//! [`ReservationTable`] so no two members claim the same tile.
+//!
+//! [`step_group`] does both, moving members nearest the goal first so the
+//! tiles they leave can be taken by those behind them in the same turn.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_pathfinding::prelude::*;
+//!
+//! let mut grid = CostGrid::new(8, 3, 1);
+//! let goal = IVec2::new(7, 1);
+//! let field = FlowField::new(&[goal], &mut grid, ());
+//!
+//! let mut pack = vec![("a", IVec2::new(0, 1)), ("b", IVec2::new(1, 1)), ("c", IVec2::new(1, 0))];
+//! for _ in 0..10 {
+//!     pack = step_group(&pack, &field, &mut grid, ());
+//! }
+//! // One member stands on the goal, the others crowd around it
+//! assert!(pack.iter().any(|&(_, p)| p == goal));
+//! assert!(pack.iter().all(|&(_, p)| field.cost(p).unwrap() <= 1));
+//! assert_ne!(pack[0].1, pack[1].1);
+//! ```
+
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+use hashbrown::{HashMap, HashSet};
+
+use crate::prelude::*;
+
+/// The cost of reaching the nearest goal from every tile, for many movers to
+/// share.
+///
+/// The field is flooded outward from the goals, so on providers whose costs
+/// differ by direction the values are the costs of walking from the goals
+/// rather than toward them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FlowField {
+    costs: HashMap<IVec2, u32>,
+}
+
+impl FlowField {
+    /// Floods the field outward from `goals`. The provider must describe a
+    /// finite area; use [`within`](Self::within) for open worlds.
+    pub fn new<T>(
+        goals: &[IVec2],
+        provider: &mut impl PathProvider<T>,
+        pass_through_data: T,
+    ) -> Self {
+        Self::within(goals, u32::MAX, provider, pass_through_data)
+    }
+
+    /// Floods the field outward from `goals`, up to a cost of `max_cost`.
+    pub fn within<T>(
+        goals: &[IVec2],
+        max_cost: u32,
+        provider: &mut impl PathProvider<T>,
+        mut pass_through_data: T,
+    ) -> Self {
+        let mut costs: HashMap<IVec2, u32> = goals.iter().map(|&g| (g, 0)).collect();
+        let mut open: BinaryHeap<_> = goals.iter().map(|g| Reverse((0u32, g.y, g.x))).collect();
+
+        while let Some(Reverse((cost, y, x))) = open.pop() {
+            let current = IVec2::new(x, y);
+            if costs.get(&current).is_some_and(|&c| c < cost) {
+                continue;
+            }
+            for (next, step) in provider.generate_successors(current, &mut pass_through_data) {
+                let next_cost = cost.saturating_add(step);
+                if next_cost <= max_cost && costs.get(&next).is_none_or(|&c| next_cost < c) {
+                    costs.insert(next, next_cost);
+                    open.push(Reverse((next_cost, next.y, next.x)));
+                }
+            }
+        }
+        Self { costs }
+    }
+
+    /// Returns the cost of reaching the nearest goal from `pos`, or `None`
+    /// if the flood didn't reach it.
+    pub fn cost(&self, pos: IVec2) -> Option<u32> {
+        self.costs.get(&pos).copied()
+    }
+
+    /// Returns the number of tiles in the field.
+    pub fn len(&self) -> usize {
+        self.costs.len()
+    }
+
+    /// Returns true if the field holds no tiles.
+    pub fn is_empty(&self) -> bool {
+        self.costs.is_empty()
+    }
+
+    /// Returns the neighbors of `pos` that are closer to a goal, best first.
+    pub fn downhill<T>(
+        &self,
+        pos: IVec2,
+        provider: &mut impl PathProvider<T>,
+        pass_through_data: &mut T,
+    ) -> Vec<IVec2> {
+        let Some(here) = self.cost(pos) else {
+            return Vec::new();
+        };
+        let mut steps: Vec<(u32, IVec2)> = provider
+            .get_neighbors(pos, pass_through_data)
+            .into_iter()
+            .filter_map(|p| self.cost(p).filter(|&c| c < here).map(|c| (c, p)))
+            .collect();
+        steps.sort_by_key(|&(cost, p)| (cost, p.y, p.x));
+        steps.into_iter().map(|(_, p)| p).collect()
+    }
+}
+
+/// Tiles claimed for the coming turn.
+///
+/// Each mover claims the tile it will end its move on; a claimed tile can't
+/// be claimed again until it is released or the table is cleared.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReservationTable {
+    claims: HashSet<IVec2>,
+}
+
+impl ReservationTable {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Claims `pos`, returning false if it was already claimed.
+    pub fn claim(&mut self, pos: IVec2) -> bool {
+        self.claims.insert(pos)
+    }
+
+    /// Releases a claim, returning false if `pos` wasn't claimed.
+    pub fn release(&mut self, pos: IVec2) -> bool {
+        self.claims.remove(&pos)
+    }
+
+    /// Returns true if `pos` is claimed.
+    pub fn is_claimed(&self, pos: IVec2) -> bool {
+        self.claims.contains(&pos)
+    }
+
+    /// Returns the number of claims.
+    pub fn len(&self) -> usize {
+        self.claims.len()
+    }
+
+    /// Returns true if nothing is claimed.
+    pub fn is_empty(&self) -> bool {
+        self.claims.is_empty()
+    }
+
+    /// Releases every claim, ready for the next turn.
+    pub fn clear(&mut self) {
+        self.claims.clear();
+    }
+}
+
+/// Moves every member one step down `field`, returning the new positions in
+/// the same order as `members`.
+///
+/// Members nearest the goal move first. Each takes the best downhill tile
+/// nobody else has claimed, and stays put if there is none, so members never
+/// share a tile they didn't start on. Members outside the field stay put.
+pub fn step_group<K: Copy, T>(
+    members: &[(K, IVec2)],
+    field: &FlowField,
+    provider: &mut impl PathProvider<T>,
+    mut pass_through_data: T,
+) -> Vec<(K, IVec2)> {
+    let mut reservations = ReservationTable::new();
+    for &(_, pos) in members {
+        reservations.claim(pos);
+    }
+
+    let mut order: Vec<usize> = (0..members.len()).collect();
+    order.sort_by_key(|&i| field.cost(members[i].1).unwrap_or(u32::MAX));
+
+    let mut moved = members.to_vec();
+    for i in order {
+        let pos = members[i].1;
+        let steps = field.downhill(pos, provider, &mut pass_through_data);
+        if let Some(next) = steps.into_iter().find(|&p| !reservations.is_claimed(p)) {
+            reservations.release(pos);
+            reservations.claim(next);
+            moved[i].1 = next;
+        }
+    }
+    moved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_costs_from_nearest_goal() {
+        let mut grid = CostGrid::new(10, 1, 1);
+        let field = FlowField::new(&[IVec2::new(0, 0), IVec2::new(9, 0)], &mut grid, ());
+        assert_eq!(field.len(), 10);
+        assert_eq!(field.cost(IVec2::new(3, 0)), Some(3));
+        assert_eq!(field.cost(IVec2::new(7, 0)), Some(2));
+
+        let near = FlowField::within(&[IVec2::new(0, 0)], 2, &mut grid, ());
+        assert_eq!(near.len(), 3);
+        let steps = near.downhill(IVec2::new(2, 0), &mut grid, &mut ());
+        assert_eq!(steps, vec![IVec2::new(1, 0)]);
+    }
+
+    #[test]
+    fn test_corridor_queue_never_stacks() {
+        let mut grid = CostGrid::new(6, 1, 1);
+        let field = FlowField::new(&[IVec2::new(5, 0)], &mut grid, ());
+        let mut group: Vec<(u8, IVec2)> = (0..3).map(|i| (i, IVec2::new(i as i32, 0))).collect();
+
+        // The leader moves first, so the whole queue advances together
+        group = step_group(&group, &field, &mut grid, ());
+        let xs: Vec<i32> = group.iter().map(|(_, p)| p.x).collect();
+        assert_eq!(xs, vec![1, 2, 3]);
+
+        for _ in 0..5 {
+            group = step_group(&group, &field, &mut grid, ());
+        }
+        let xs: Vec<i32> = group.iter().map(|(_, p)| p.x).collect();
+        assert_eq!(xs, vec![3, 4, 5]);
+
+        let mut table = ReservationTable::new();
+        assert!(table.claim(IVec2::ZERO));
+        assert!(!table.claim(IVec2::ZERO));
+        assert!(table.release(IVec2::ZERO));
+        assert!(table.is_empty());
+    }
+}
@@ -14,6 +14,31 @@
 //! *   **DFS:** Depth-First Search. Does not guarantee shortest path.
 //! *   **ID A* / ID DFS:** Iterative deepening variants.
 //!
+//! [`registry::PathfinderRegistry`] selects algorithms by name at runtime, for
+//! data-driven configs or overlays that compare algorithms side by side. Custom
+//! algorithms join the built-ins by implementing [`registry::DynPathAlgorithm`].
+//!
+//! Searches aren't limited to grids: implement [`graph::Graph`] for travel networks,
+//! portal graphs or room adjacency and use A*, Dijkstra and BFS over any node type.
+//!
+//! For ordinary grid maps, [`cost_grid::CostGrid`] implements `PathProvider` with
+//! per-tile movement costs and toggleable blockers.
+//!
+//! Creatures and other moving obstacles belong in [`blockers::DynamicBlockers`], which
+//! can be laid over any provider and lets searchers ignore themselves and swap with allies.
+//!
+//! Packs heading for the same goal share a [`group::FlowField`], and
+//! [`group::step_group`] moves them along it without two members claiming one tile.
+//!
+//! Long searches can be spread over several frames with [`search::PathSearch`],
+//! which expands a limited number of nodes per call.
+//!
+//! [`query`] answers everyday distance questions: the nearest tile matching a
+//! predicate, the tiles within a movement budget and everything reachable.
+//!
+//! [`explore::auto_explore`] finds the path to the nearest unexplored tile, and
+//! [`explore::AutoTravel`] walks a path until a new hostile comes into view.
+//!
 //! # Usage
 //!
 //! Add this to your `Cargo.toml`:
@@ -23,19 +48,42 @@
 //! runeforge-pathfinding = "0.1"
 //! ```
 //!
+//! # `no_std`
+//!
+//! The crate is `no_std` + `alloc` when the default `std` feature is disabled.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
 pub mod algorithms;
+pub mod blockers;
+pub mod cost_grid;
+pub mod explore;
+pub mod graph;
+pub mod group;
 pub mod path_algorithm;
 pub mod path_provider;
 pub mod pathfinder;
+pub mod query;
+pub mod registry;
+pub mod search;
 
 pub mod prelude {
+    pub use alloc::collections::BinaryHeap;
     pub use glam::IVec2;
     pub use runeforge_geometry::prelude::GridPoint;
-    pub use std::collections::BinaryHeap;
 
     pub use crate::algorithms::*;
+    pub use crate::blockers::*;
+    pub use crate::cost_grid::*;
+    pub use crate::explore::*;
+    pub use crate::graph::*;
+    pub use crate::group::*;
     pub use crate::path_algorithm::*;
     pub use crate::path_provider::*;
     pub use crate::pathfinder::*;
+    pub use crate::query::*;
+    pub use crate::registry::*;
+    pub use crate::search::*;
 }
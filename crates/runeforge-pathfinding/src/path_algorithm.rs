@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use crate::prelude::*;
 
 /// Trait to implement 2D path algorithms.
@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use crate::prelude::*;
 
 /// Trait to implement path providers.
@@ -25,3 +27,23 @@ pub trait PathProvider<T> {
         successors
     }
 }
+
+// Lets `&dyn PathProvider<T>` be passed where `&mut impl PathProvider<T>` is
+// expected.
+impl<T, P: PathProvider<T> + ?Sized> PathProvider<T> for &P {
+    fn get_neighbors(&self, position: IVec2, pass_through_data: &mut T) -> Vec<IVec2> {
+        (**self).get_neighbors(position, pass_through_data)
+    }
+
+    fn cost(&self, from_position: IVec2, to_position: IVec2, pass_through_data: &mut T) -> u32 {
+        (**self).cost(from_position, to_position, pass_through_data)
+    }
+
+    fn distance(&self, origin: IVec2, destination: IVec2) -> u32 {
+        (**self).distance(origin, destination)
+    }
+
+    fn generate_successors(&self, p: IVec2, pass_through_data: &mut T) -> Vec<(IVec2, u32)> {
+        (**self).generate_successors(p, pass_through_data)
+    }
+}
@@ -1,6 +1,9 @@
+use alloc::vec::Vec;
+
 use crate::prelude::*;
 
 /// Different pathfinding algorithms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PathFinder {
     /// Breadth-first search algorithm.
     Bfs,
@@ -19,6 +22,38 @@ pub enum PathFinder {
 }
 
 impl PathFinder {
+    /// Every built-in algorithm.
+    pub const ALL: [PathFinder; 7] = [
+        Self::Astar,
+        Self::Dijkstra,
+        Self::DijkstraPartial,
+        Self::Bfs,
+        Self::Dfs,
+        Self::IDAstar,
+        Self::IDDfs,
+    ];
+
+    /// Returns the algorithm's name, as used by
+    /// [`PathfinderRegistry`](crate::registry::PathfinderRegistry).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Astar => "astar",
+            Self::Bfs => "bfs",
+            Self::Dfs => "dfs",
+            Self::Dijkstra => "dijkstra",
+            Self::DijkstraPartial => "dijkstra-partial",
+            Self::IDAstar => "id-astar",
+            Self::IDDfs => "id-dfs",
+        }
+    }
+
+    /// Looks up a built-in algorithm by [`name`](Self::name).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.name() == name)
+    }
+
     /// Computes a path from the origin to the destination using the specified algorithm.
     pub fn compute<T>(
         &self,
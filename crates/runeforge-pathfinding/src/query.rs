@@ -0,0 +1,228 @@
+//! Distance queries over a [`PathProvider`].
+//!
+//! Games ask the same few questions of a map over and over: where is the
+//! nearest free tile to drop an item on, which tiles can this unit reach
+//! with the movement points it has left, is the exit reachable at all. Each
+//! is a short Dijkstra flood, and these helpers save writing it again:
+//! - [`nearest_matching`] finds the cheapest tile matching a predicate
+//! - [`tiles_within_cost`] collects every tile within a movement budget
+//! - [`reachable_set`] collects every tile reachable from a start
+//!
+//! The floods return a [`ReachableSet`] that can rebuild the path to any tile
+//! it holds.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_pathfinding::prelude::*;
+//!
+//! // A corridor with a wall at x = 6.
+//! let mut grid = CostGrid::from_walkable(10, 1, |p| p.x != 6);
+//! let start = IVec2::new(2, 0);
+//!
+//! let found = nearest_matching(start, |p| p.x % 4 == 0, &mut grid, ()).unwrap();
+//! assert_eq!((found.position, found.cost), (IVec2::new(0, 0), 2));
+//!
+//! let moves = tiles_within_cost(start, 2, &mut grid, ());
+//! assert_eq!(moves.len(), 5);
+//!
+//! let reachable = reachable_set(start, &mut grid, ());
+//! assert!(!reachable.contains(IVec2::new(8, 0)));
+//! assert_eq!(reachable.path_to(IVec2::new(5, 0)).unwrap().len(), 4);
+//! ```
+
+use alloc::{vec, vec::Vec};
+use core::cmp::Reverse;
+
+use hashbrown::HashMap;
+
+use crate::prelude::*;
+
+/// The tile found by [`nearest_matching`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NearestMatch {
+    /// The matching tile.
+    pub position: IVec2,
+    /// The cost of reaching it.
+    pub cost: u32,
+    /// The cheapest path to it, from the start inclusive.
+    pub path: Vec<IVec2>,
+}
+
+/// Tiles reached by a flood, with the cheapest cost to each.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReachableSet {
+    start: IVec2,
+    /// Cost and predecessor of every reached tile; the start has no
+    /// predecessor.
+    tiles: HashMap<IVec2, (u32, Option<IVec2>)>,
+}
+
+impl ReachableSet {
+    /// Returns the tile the flood started from.
+    pub fn start(&self) -> IVec2 {
+        self.start
+    }
+
+    /// Returns the cost of reaching `pos`, if it was reached.
+    pub fn cost(&self, pos: IVec2) -> Option<u32> {
+        self.tiles.get(&pos).map(|&(cost, _)| cost)
+    }
+
+    /// Returns true if `pos` was reached.
+    pub fn contains(&self, pos: IVec2) -> bool {
+        self.tiles.contains_key(&pos)
+    }
+
+    /// Returns the number of reached tiles, including the start.
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Always false: the start is always reached.
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Returns every reached tile and its cost, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (IVec2, u32)> + '_ {
+        self.tiles.iter().map(|(&pos, &(cost, _))| (pos, cost))
+    }
+
+    /// Returns the cheapest path from the start to `pos`, inclusive.
+    pub fn path_to(&self, pos: IVec2) -> Option<Vec<IVec2>> {
+        let mut path = vec![pos];
+        let mut current = self.tiles.get(&pos)?.1;
+        while let Some(prev) = current {
+            path.push(prev);
+            current = self.tiles[&prev].1;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Returns the cheapest tile reachable from `start` for which `predicate`
+/// is true, or `None` if there is none. `start` itself counts, at cost 0.
+///
+/// On unbounded providers this only returns once a match is found, so pair
+/// an open world with a predicate that will eventually match.
+pub fn nearest_matching<T>(
+    start: IVec2,
+    mut predicate: impl FnMut(IVec2) -> bool,
+    provider: &mut impl PathProvider<T>,
+    mut pass_through_data: T,
+) -> Option<NearestMatch> {
+    let mut found = None;
+    let flood = flood(start, u32::MAX, provider, &mut pass_through_data, |pos| {
+        let matched = predicate(pos);
+        if matched {
+            found = Some(pos);
+        }
+        matched
+    });
+    let position = found?;
+    Some(NearestMatch {
+        position,
+        cost: flood.cost(position)?,
+        path: flood.path_to(position)?,
+    })
+}
+
+/// Returns every tile reachable from `start` at a total cost of at most
+/// `budget`, such as the squares a unit can move to this turn.
+pub fn tiles_within_cost<T>(
+    start: IVec2,
+    budget: u32,
+    provider: &mut impl PathProvider<T>,
+    mut pass_through_data: T,
+) -> ReachableSet {
+    flood(start, budget, provider, &mut pass_through_data, |_| false)
+}
+
+/// Returns every tile reachable from `start`.
+///
+/// The provider must describe a finite area, or this never returns; use
+/// [`tiles_within_cost`] for open worlds.
+pub fn reachable_set<T>(
+    start: IVec2,
+    provider: &mut impl PathProvider<T>,
+    mut pass_through_data: T,
+) -> ReachableSet {
+    flood(start, u32::MAX, provider, &mut pass_through_data, |_| false)
+}
+
+/// Dijkstra flood from `start` up to `budget`, stopping early at the first
+/// tile (in cost order) for which `stop` returns true. Tiles still waiting
+/// in the queue when it stops are dropped, so every cost kept is final.
+fn flood<T>(
+    start: IVec2,
+    budget: u32,
+    provider: &mut impl PathProvider<T>,
+    pass_through_data: &mut T,
+    mut stop: impl FnMut(IVec2) -> bool,
+) -> ReachableSet {
+    let mut best: HashMap<IVec2, (u32, Option<IVec2>)> = HashMap::from([(start, (0, None))]);
+    let mut tiles = HashMap::new();
+    let mut open = BinaryHeap::from([Reverse((0u32, start.y, start.x))]);
+
+    while let Some(Reverse((cost, y, x))) = open.pop() {
+        let current = IVec2::new(x, y);
+        if tiles.contains_key(&current) {
+            continue;
+        }
+        tiles.insert(current, best[&current]);
+        if stop(current) {
+            break;
+        }
+        for (next, step) in provider.generate_successors(current, pass_through_data) {
+            let next_cost = cost.saturating_add(step);
+            if next_cost > budget || tiles.contains_key(&next) {
+                continue;
+            }
+            if best.get(&next).is_none_or(|&(c, _)| next_cost < c) {
+                best.insert(next, (next_cost, Some(current)));
+                open.push(Reverse((next_cost, next.y, next.x)));
+            }
+        }
+    }
+    ReachableSet { start, tiles }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_prefers_cheap_over_close() {
+        // The tile to the right is one step away but behind a swamp.
+        let mut grid = CostGrid::from_fn(5, 1, |p| if p.x == 3 { 10 } else { 1 });
+        let start = IVec2::new(2, 0);
+        let found = nearest_matching(start, |p| p.x == 0 || p.x == 4, &mut grid, ()).unwrap();
+        assert_eq!(found.position, IVec2::new(0, 0));
+        assert_eq!(found.path, vec![start, IVec2::new(1, 0), IVec2::new(0, 0)]);
+
+        let here = nearest_matching(start, |_| true, &mut grid, ()).unwrap();
+        assert_eq!((here.cost, here.path), (0, vec![start]));
+        assert!(nearest_matching(start, |p| p.x > 10, &mut grid, ()).is_none());
+    }
+
+    #[test]
+    fn test_budget_limits_flood() {
+        let mut grid = CostGrid::new(9, 9, 1).with_diagonals(false);
+        let center = IVec2::new(4, 4);
+        let within = tiles_within_cost(center, 2, &mut grid, ());
+        // A diamond of radius 2
+        assert_eq!(within.len(), 13);
+        assert!(within
+            .iter()
+            .all(|(p, cost)| cost == (p - center).abs().element_sum() as u32));
+        assert_eq!(within.cost(center), Some(0));
+        assert!(!within.contains(IVec2::new(6, 5)));
+
+        let all = reachable_set(center, &mut grid, ());
+        assert_eq!(all.len(), 81);
+        assert_eq!(all.path_to(IVec2::ZERO).unwrap().len(), 9);
+        assert_eq!(all.path_to(IVec2::new(-1, 0)), None);
+    }
+}
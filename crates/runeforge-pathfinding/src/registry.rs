@@ -0,0 +1,230 @@
+//! Selecting pathfinding algorithms by name at runtime.
+//!
+//! [`PathAlgorithm`] is generic over the provider, so it can't be boxed.
+//! [`DynPathAlgorithm`] is its object-safe counterpart: it takes the provider
+//! as a trait object, which lets algorithms live side by side in a
+//! [`PathfinderRegistry`] and be picked from a config file, a console command
+//! or a debug overlay that compares them.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_pathfinding::prelude::*;
+//!
+//! let mut registry = PathfinderRegistry::<()>::with_builtins();
+//!
+//! // A custom algorithm: step straight along x, then y, ignoring walls
+//! registry.register("naive", |origin: IVec2, destination: IVec2, _: &mut dyn PathProvider<()>, _| {
+//!     let mut path = vec![origin];
+//!     let mut p = origin;
+//!     while p != destination {
+//!         p += (destination - p).signum() * if p.x != destination.x { IVec2::X } else { IVec2::Y };
+//!         path.push(p);
+//!     }
+//!     path
+//! });
+//!
+//! let mut grid = CostGrid::new(10, 10, 1);
+//! for name in ["astar", "bfs", "naive"] {
+//!     let path = registry.compute(name, IVec2::ZERO, IVec2::new(3, 0), &mut grid, ()).unwrap();
+//!     assert_eq!(path.len(), 4);
+//! }
+//! assert!(registry.compute("jps", IVec2::ZERO, IVec2::ONE, &mut grid, ()).is_none());
+//! ```
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+use crate::prelude::*;
+
+/// An object-safe pathfinding algorithm.
+///
+/// Implemented by [`PathFinder`], so every built-in algorithm can be used as
+/// `dyn DynPathAlgorithm<T>`, and by closures taking the same arguments as
+/// [`compute`](Self::compute).
+pub trait DynPathAlgorithm<T> {
+    /// Computes a path from the origin to the destination, inclusive. Returns
+    /// an empty path if the destination can't be reached.
+    fn compute(
+        &self,
+        origin: IVec2,
+        destination: IVec2,
+        provider: &mut dyn PathProvider<T>,
+        pass_through_data: T,
+    ) -> Vec<IVec2>;
+}
+
+impl<T> DynPathAlgorithm<T> for PathFinder {
+    fn compute(
+        &self,
+        origin: IVec2,
+        destination: IVec2,
+        provider: &mut dyn PathProvider<T>,
+        pass_through_data: T,
+    ) -> Vec<IVec2> {
+        PathFinder::compute(
+            self,
+            origin,
+            destination,
+            &mut &*provider,
+            pass_through_data,
+        )
+    }
+}
+
+impl<T, F> DynPathAlgorithm<T> for F
+where
+    F: Fn(IVec2, IVec2, &mut dyn PathProvider<T>, T) -> Vec<IVec2>,
+{
+    fn compute(
+        &self,
+        origin: IVec2,
+        destination: IVec2,
+        provider: &mut dyn PathProvider<T>,
+        pass_through_data: T,
+    ) -> Vec<IVec2> {
+        self(origin, destination, provider, pass_through_data)
+    }
+}
+
+/// Named pathfinding algorithms, selectable at runtime.
+///
+/// Names keep their registration order, so overlays can list algorithms in a
+/// stable order.
+pub struct PathfinderRegistry<T> {
+    entries: Vec<(String, Box<dyn DynPathAlgorithm<T>>)>,
+}
+
+impl<T> Default for PathfinderRegistry<T> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for PathfinderRegistry<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+impl<T> PathfinderRegistry<T> {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry holding every [`PathFinder`] under its
+    /// [`name`](PathFinder::name).
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        for algorithm in PathFinder::ALL {
+            registry.register(algorithm.name(), algorithm);
+        }
+        registry
+    }
+
+    /// Registers an algorithm under `name`, returning the algorithm it
+    /// replaces, if any.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        algorithm: impl DynPathAlgorithm<T> + 'static,
+    ) -> Option<Box<dyn DynPathAlgorithm<T>>> {
+        let name = name.into();
+        let algorithm: Box<dyn DynPathAlgorithm<T>> = Box::new(algorithm);
+        match self.entries.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, existing)) => Some(core::mem::replace(existing, algorithm)),
+            None => {
+                self.entries.push((name, algorithm));
+                None
+            }
+        }
+    }
+
+    /// Removes the algorithm registered under `name`.
+    pub fn remove(&mut self, name: &str) -> Option<Box<dyn DynPathAlgorithm<T>>> {
+        let index = self.entries.iter().position(|(n, _)| n == name)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    /// Returns the algorithm registered under `name`.
+    pub fn get(&self, name: &str) -> Option<&dyn DynPathAlgorithm<T>> {
+        self.entries
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, algorithm)| algorithm.as_ref())
+    }
+
+    /// Returns true if an algorithm is registered under `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Returns the registered names in registration order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(n, _)| n.as_str())
+    }
+
+    /// Returns the number of registered algorithms.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if no algorithms are registered.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Computes a path with the algorithm registered under `name`, or
+    /// returns `None` if there is no such algorithm.
+    pub fn compute(
+        &self,
+        name: &str,
+        origin: IVec2,
+        destination: IVec2,
+        provider: &mut dyn PathProvider<T>,
+        pass_through_data: T,
+    ) -> Option<Vec<IVec2>> {
+        let algorithm = self.get(name)?;
+        Some(algorithm.compute(origin, destination, provider, pass_through_data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtins_by_name() {
+        let registry = PathfinderRegistry::<()>::with_builtins();
+        assert_eq!(registry.len(), PathFinder::ALL.len());
+        for algorithm in PathFinder::ALL {
+            assert_eq!(PathFinder::from_name(algorithm.name()), Some(algorithm));
+            assert!(registry.contains(algorithm.name()));
+        }
+
+        let mut grid = CostGrid::from_walkable(5, 5, |p| p != IVec2::new(2, 2));
+        let (origin, destination) = (IVec2::new(0, 2), IVec2::new(4, 2));
+        let astar = registry
+            .compute("astar", origin, destination, &mut grid, ())
+            .unwrap();
+        let expected = PathFinder::Astar.compute(origin, destination, &mut grid, ());
+        assert_eq!(astar, expected);
+        assert!(!astar.contains(&IVec2::new(2, 2)));
+    }
+
+    #[test]
+    fn test_register_replaces_and_removes() {
+        let mut registry = PathfinderRegistry::<()>::new();
+        assert!(registry.is_empty());
+        assert!(registry.register("fast", PathFinder::Bfs).is_none());
+        registry.register("best", PathFinder::Astar);
+        assert!(registry.register("fast", PathFinder::Dfs).is_some());
+        assert_eq!(registry.names().collect::<Vec<_>>(), ["fast", "best"]);
+
+        assert!(registry.remove("fast").is_some());
+        assert!(registry.remove("fast").is_none());
+        assert_eq!(registry.len(), 1);
+    }
+}
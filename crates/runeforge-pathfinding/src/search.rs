@@ -0,0 +1,231 @@
+use alloc::{vec, vec::Vec};
+use core::cmp::Reverse;
+
+use hashbrown::{HashMap, HashSet};
+
+use crate::prelude::*;
+
+/// The state of an incremental [`PathSearch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchStatus {
+    /// The search needs more calls to [`PathSearch::poll`].
+    Pending,
+    /// A path was found, from origin to destination inclusive.
+    Found(Vec<IVec2>),
+    /// Every reachable tile was explored without reaching the destination.
+    NotFound,
+}
+
+/// An A* search that can be spread over several frames.
+///
+/// Each call to [`poll`](Self::poll) expands at most `budget` nodes and then
+/// returns, so a long search doesn't stall the render loop. The provider is
+/// passed on every call rather than stored, so the map can be borrowed
+/// elsewhere between polls.
+///
+/// # Example
+///
+/// ```
+/// use runeforge_pathfinding::prelude::*;
+///
+/// let mut grid = CostGrid::new(40, 40, 1);
+/// let mut search = PathSearch::new(IVec2::new(0, 0), IVec2::new(39, 39));
+///
+/// // Spend at most 16 expansions per frame.
+/// let path = loop {
+///     match search.poll(16, &mut grid, &mut ()) {
+///         SearchStatus::Pending => continue, // render a frame here
+///         SearchStatus::Found(path) => break path,
+///         SearchStatus::NotFound => unreachable!(),
+///     }
+/// };
+/// assert_eq!(path.len(), 40);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PathSearch {
+    origin: IVec2,
+    destination: IVec2,
+    open: BinaryHeap<Reverse<(u32, u32, i32, i32)>>,
+    costs: HashMap<IVec2, u32>,
+    came_from: HashMap<IVec2, IVec2>,
+    closed: HashSet<IVec2>,
+    expansions: usize,
+    status: SearchStatus,
+}
+
+impl PathSearch {
+    /// Starts a search from `origin` to `destination`.
+    pub fn new(origin: IVec2, destination: IVec2) -> Self {
+        let mut open = BinaryHeap::new();
+        open.push(Reverse((0, 0, origin.x, origin.y)));
+        Self {
+            origin,
+            destination,
+            open,
+            costs: HashMap::from([(origin, 0)]),
+            came_from: HashMap::new(),
+            closed: HashSet::new(),
+            expansions: 0,
+            status: SearchStatus::Pending,
+        }
+    }
+
+    /// Returns the start of the search.
+    pub fn origin(&self) -> IVec2 {
+        self.origin
+    }
+
+    /// Returns the goal of the search.
+    pub fn destination(&self) -> IVec2 {
+        self.destination
+    }
+
+    /// Returns the current status without doing any work.
+    pub fn status(&self) -> &SearchStatus {
+        &self.status
+    }
+
+    /// Returns true once the search has found a path or given up.
+    pub fn is_finished(&self) -> bool {
+        self.status != SearchStatus::Pending
+    }
+
+    /// Returns how many nodes have been expanded so far.
+    pub fn expansions(&self) -> usize {
+        self.expansions
+    }
+
+    /// Returns the cheapest known cost from the origin to `pos`, if the
+    /// search has reached it. Useful for visualizing the explored area.
+    pub fn cost(&self, pos: IVec2) -> Option<u32> {
+        self.costs.get(&pos).copied()
+    }
+
+    /// Returns true if `pos` has been expanded.
+    pub fn is_expanded(&self, pos: IVec2) -> bool {
+        self.closed.contains(&pos)
+    }
+
+    /// Expands up to `budget` nodes and returns the resulting status.
+    ///
+    /// A budget of zero does no work. Once the search is finished, further
+    /// calls return the same status.
+    pub fn poll<T>(
+        &mut self,
+        budget: usize,
+        provider: &mut impl PathProvider<T>,
+        pass_through_data: &mut T,
+    ) -> SearchStatus {
+        let mut remaining = budget;
+        while !self.is_finished() && remaining > 0 {
+            let Some(Reverse((_, _, x, y))) = self.open.pop() else {
+                self.status = SearchStatus::NotFound;
+                break;
+            };
+            let current = IVec2::new(x, y);
+            if !self.closed.insert(current) {
+                continue;
+            }
+            remaining -= 1;
+            self.expansions += 1;
+
+            if current == self.destination {
+                self.status = SearchStatus::Found(self.build_path());
+                break;
+            }
+
+            let cost = self.costs[&current];
+            for (next, step) in provider.generate_successors(current, pass_through_data) {
+                let next_cost = cost.saturating_add(step);
+                if self.costs.get(&next).is_none_or(|&c| next_cost < c) {
+                    self.costs.insert(next, next_cost);
+                    self.came_from.insert(next, current);
+                    let h = provider.distance(next, self.destination);
+                    self.open
+                        .push(Reverse((next_cost.saturating_add(h), h, next.x, next.y)));
+                }
+            }
+        }
+        self.status.clone()
+    }
+
+    fn build_path(&self) -> Vec<IVec2> {
+        let mut path = vec![self.destination];
+        let mut current = self.destination;
+        while let Some(&prev) = self.came_from.get(&current) {
+            path.push(prev);
+            current = prev;
+        }
+        path.reverse();
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_to_end(
+        search: &mut PathSearch,
+        grid: &mut CostGrid,
+        budget: usize,
+    ) -> (SearchStatus, usize) {
+        let mut polls = 0;
+        loop {
+            polls += 1;
+            let status = search.poll(budget, grid, &mut ());
+            if status != SearchStatus::Pending {
+                return (status, polls);
+            }
+        }
+    }
+
+    fn path_cost(grid: &CostGrid, path: &[IVec2]) -> u32 {
+        path.iter().skip(1).map(|&p| grid.cost_at(p)).sum()
+    }
+
+    #[test]
+    fn test_matches_astar_cost() {
+        let mut grid = CostGrid::from_fn(20, 20, |p| {
+            if p.x == 10 && p.y < 18 {
+                IMPASSABLE
+            } else {
+                1 + (p.y % 3) as u32
+            }
+        });
+        let (origin, goal) = (IVec2::new(2, 2), IVec2::new(17, 3));
+        let expected = PathFinder::Astar.compute(origin, goal, &mut grid, ());
+
+        let mut search = PathSearch::new(origin, goal);
+        let (status, polls) = run_to_end(&mut search, &mut grid, 5);
+        let SearchStatus::Found(path) = status else {
+            panic!("no path found");
+        };
+        assert!(polls > 1);
+        assert_eq!(path.first(), Some(&origin));
+        assert_eq!(path_cost(&grid, &path), path_cost(&grid, &expected));
+        assert!(search.is_finished());
+        assert_eq!(
+            search.poll(5, &mut grid, &mut ()),
+            SearchStatus::Found(path)
+        );
+    }
+
+    #[test]
+    fn test_budget_limits_expansions() {
+        let mut grid = CostGrid::new(30, 30, 1);
+        let mut search = PathSearch::new(IVec2::ZERO, IVec2::new(29, 0));
+        assert_eq!(search.poll(0, &mut grid, &mut ()), SearchStatus::Pending);
+        assert_eq!(search.expansions(), 0);
+        search.poll(3, &mut grid, &mut ());
+        assert_eq!(search.expansions(), 3);
+    }
+
+    #[test]
+    fn test_unreachable_destination() {
+        let mut grid = CostGrid::from_walkable(5, 5, |p| p.x != 2);
+        let mut search = PathSearch::new(IVec2::ZERO, IVec2::new(4, 4));
+        let (status, _) = run_to_end(&mut search, &mut grid, 4);
+        assert_eq!(status, SearchStatus::NotFound);
+    }
+}
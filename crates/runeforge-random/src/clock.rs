@@ -0,0 +1,25 @@
+//! Clock types that also work in the browser.
+//!
+//! `std::time::Instant::now()` panics on `wasm32-unknown-unknown`. These
+//! re-exports are the `std` types on native targets and are backed by
+//! `performance.now()` and `Date.now()` on the web, so timing code can use
+//! them unconditionally.
+//!
+//! ```
+//! use runeforge_random::clock::Instant;
+//!
+//! let start = Instant::now();
+//! assert!(start.elapsed().as_secs_f64() >= 0.0);
+//! ```
+
+pub use web_time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Returns a seed derived from the current wall-clock time.
+///
+/// This is a weak source of entropy, used only when the platform has no
+/// random number source.
+pub fn time_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64)
+}
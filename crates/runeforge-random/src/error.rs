@@ -0,0 +1,117 @@
+//! Error types for random number utilities.
+
+use thiserror::Error;
+
+/// Errors produced when parsing dice notation such as `"3d6+2"`.
+///
+/// Every variant carries the (trimmed, lowercased) notation that failed so
+/// the caller can report which entry in a data file is broken.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DiceError {
+    /// The notation has no `d` separating the count from the sides.
+    #[error("Invalid dice notation: missing 'd' in '{notation}'")]
+    MissingSeparator {
+        /// The notation that failed to parse
+        notation: String,
+    },
+
+    /// The number of dice is not a non-negative integer.
+    #[error("Invalid number of dice: '{value}' in '{notation}'")]
+    InvalidCount {
+        /// The notation that failed to parse
+        notation: String,
+        /// The offending text
+        value: String,
+    },
+
+    /// The number of sides is not an integer.
+    #[error("Invalid number of sides: '{value}' in '{notation}'")]
+    InvalidSides {
+        /// The notation that failed to parse
+        notation: String,
+        /// The offending text
+        value: String,
+    },
+
+    /// The modifier is not an integer.
+    #[error("Invalid modifier: '{value}' in '{notation}'")]
+    InvalidModifier {
+        /// The notation that failed to parse
+        notation: String,
+        /// The offending text
+        value: String,
+    },
+
+    /// The dice have fewer than one side.
+    #[error("Dice must have at least 1 side, got {sides} in '{notation}'")]
+    TooFewSides {
+        /// The notation that failed to parse
+        notation: String,
+        /// The parsed number of sides
+        sides: i32,
+    },
+}
+
+impl DiceError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            DiceError::MissingSeparator { .. } => "dice.missing_separator",
+            DiceError::InvalidCount { .. } => "dice.invalid_count",
+            DiceError::InvalidSides { .. } => "dice.invalid_sides",
+            DiceError::InvalidModifier { .. } => "dice.invalid_modifier",
+            DiceError::TooFewSides { .. } => "dice.too_few_sides",
+        }
+    }
+
+    /// Returns the notation that failed to parse.
+    pub fn notation(&self) -> &str {
+        match self {
+            DiceError::MissingSeparator { notation }
+            | DiceError::InvalidCount { notation, .. }
+            | DiceError::InvalidSides { notation, .. }
+            | DiceError::InvalidModifier { notation, .. }
+            | DiceError::TooFewSides { notation, .. } => notation,
+        }
+    }
+}
+
+/// Errors produced when reading world seeds, share codes and manifests.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SeedError {
+    /// The text is not a seed code.
+    #[error("Invalid seed code: '{text}'")]
+    InvalidSeed {
+        /// The text that failed to parse
+        text: String,
+    },
+
+    /// A manifest line could not be read.
+    #[error("Invalid manifest at line {line}: {reason}")]
+    InvalidManifest {
+        /// One-based line number, or 0 for the manifest as a whole
+        line: usize,
+        /// What is wrong with the line
+        reason: String,
+    },
+
+    /// A share code was made with a different seed or different settings.
+    #[error("Share code {shared} does not match this game's settings ({local})")]
+    Mismatch {
+        /// The code that was shared
+        shared: String,
+        /// The code for the local manifest
+        local: String,
+    },
+}
+
+impl SeedError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SeedError::InvalidSeed { .. } => "seed.invalid_seed",
+            SeedError::InvalidManifest { .. } => "seed.invalid_manifest",
+            SeedError::Mismatch { .. } => "seed.mismatch",
+        }
+    }
+}
@@ -8,6 +8,17 @@
 //! *   **RNG Wrapper:** A seeded random number generator (using `rand`).
 //! *   **Dice Notation:** Parse and roll dice strings like "3d6+2", "1d20", "2d4-1".
 //! *   **Weighted Choice:** Select items from a list with different probabilities.
+//! *   **SplitMix64:** A tiny [`SplitMix64`](splitmix::SplitMix64) stream and seed mixer.
+//! *   **World Seeds:** Shareable seed codes, labelled sub-seeds and generation manifests.
+//! *   **World Hashes:** A platform-stable [`WorldHash`](world_hash::WorldHash) of game state for replays and desync checks.
+//! *   **Clock:** Browser-safe [`clock::Instant`] and [`clock::SystemTime`].
+//!
+//! # WebAssembly
+//!
+//! On `wasm32-unknown-unknown` the OS entropy comes from `crypto.getRandomValues`
+//! (the `getrandom` `wasm_js` backend is enabled automatically), and
+//! [`Rng::new`](rng::Rng::new) seeds through [`Rng::from_entropy`](rng::Rng::from_entropy),
+//! which falls back to the clock rather than panicking.
 //!
 //! # Usage
 //!
@@ -38,13 +49,24 @@
 //! }
 //! ```
 
+pub mod clock;
+pub mod error;
 pub mod rand;
+pub mod random_table;
 pub mod random_value;
 pub mod rng;
+pub mod splitmix;
+pub mod world_hash;
+pub mod world_seed;
 
 pub mod prelude {
+    pub use crate::error::*;
     pub use crate::rand::*;
+    pub use crate::random_table::*;
     pub use crate::random_value::*;
     pub use crate::rng::*;
+    pub use crate::splitmix::*;
+    pub use crate::world_hash::*;
+    pub use crate::world_seed::*;
     pub use std::ops::{Bound, Index, IndexMut, RangeBounds};
 }
@@ -0,0 +1,141 @@
+use crate::prelude::*;
+
+/// A weighted table of entries to roll on, e.g. for loot or monster spawns.
+///
+/// # Examples
+///
+/// ```
+/// use runeforge_random::prelude::*;
+///
+/// let table = RandomTable::new()
+///     .add("goblin", 10)
+///     .add("orc", 3)
+///     .add("troll", 1);
+///
+/// let mut rng = Rng::with_seed(7);
+/// let monster = table.roll(&mut rng).unwrap();
+/// assert!(["goblin", "orc", "troll"].contains(monster));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RandomTable<T> {
+    entries: Vec<T>,
+    weights: Vec<u32>,
+}
+
+impl<T> Default for RandomTable<T> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            weights: Vec::new(),
+        }
+    }
+}
+
+impl<T> RandomTable<T> {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an entry with the given weight. Entries with weight 0 are skipped.
+    pub fn add(mut self, entry: T, weight: u32) -> Self {
+        self.push(entry, weight);
+        self
+    }
+
+    /// Adds an entry with the given weight. Entries with weight 0 are skipped.
+    pub fn push(&mut self, entry: T, weight: u32) {
+        if weight > 0 {
+            self.entries.push(entry);
+            self.weights.push(weight);
+        }
+    }
+
+    /// Rolls on the table, returning `None` if it is empty.
+    pub fn roll(&self, rng: &mut Rng) -> Option<&T> {
+        rng.weighted_choose(&self.entries, &self.weights)
+    }
+
+    /// Returns the sum of all weights. It is a `u64` because many large
+    /// weights can add up to more than a `u32` holds.
+    pub fn total_weight(&self) -> u64 {
+        self.weights.iter().map(|&w| u64::from(w)).sum()
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the table has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over `(entry, weight)` pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, u32)> {
+        self.entries.iter().zip(self.weights.iter().copied())
+    }
+}
+
+impl<T> FromIterator<(T, u32)> for RandomTable<T> {
+    fn from_iter<I: IntoIterator<Item = (T, u32)>>(iter: I) -> Self {
+        let mut table = Self::new();
+        for (entry, weight) in iter {
+            table.push(entry, weight);
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeded_rng(seed: u64) -> Rng {
+        Rng::with_seed(seed)
+    }
+
+    #[test]
+    fn test_empty_table_rolls_none() {
+        let table: RandomTable<i32> = RandomTable::new();
+        assert!(table.roll(&mut seeded_rng(1)).is_none());
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn test_zero_weight_entries_skipped() {
+        let table = RandomTable::new().add('a', 0).add('b', 5);
+        assert_eq!(table.len(), 1);
+        assert_eq!(table.total_weight(), 5);
+
+        let mut rng = seeded_rng(3);
+        for _ in 0..50 {
+            assert_eq!(table.roll(&mut rng), Some(&'b'));
+        }
+    }
+
+    #[test]
+    fn test_roll_respects_weights() {
+        let table: RandomTable<_> = [("common", 95), ("rare", 5)].into_iter().collect();
+        let mut rng = seeded_rng(42);
+        let common = (0..1000)
+            .filter(|_| table.roll(&mut rng) == Some(&"common"))
+            .count();
+        assert!(common > 850, "common rolled {common} times");
+    }
+
+    #[test]
+    fn test_weights_beyond_u32() {
+        let table = RandomTable::new()
+            .add("a", u32::MAX)
+            .add("b", u32::MAX)
+            .add("c", 1);
+        assert_eq!(table.total_weight(), 2 * u64::from(u32::MAX) + 1);
+
+        let mut rng = seeded_rng(5);
+        let rolls: Vec<_> = (0..200).filter_map(|_| table.roll(&mut rng)).collect();
+        assert_eq!(rolls.len(), 200);
+        assert!(rolls.contains(&&"a") && rolls.contains(&&"b"));
+    }
+}
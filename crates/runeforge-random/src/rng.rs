@@ -4,6 +4,9 @@ use rand::{
     Rng as RandRng, RngCore, SeedableRng,
 };
 
+use crate::clock::time_seed;
+use crate::error::DiceError;
+
 /// A random number generator wrapper with convenience methods for roguelike development.
 pub struct Rng {
     rng: RngImpl,
@@ -17,6 +20,9 @@ enum RngImpl {
 impl Rng {
     /// Creates a new random number generator using the thread-local RNG.
     ///
+    /// On `wasm32` this uses [`Rng::from_entropy`] instead, since the
+    /// thread-local RNG panics if the browser's entropy source is unavailable.
+    ///
     /// # Examples
     ///
     /// ```
@@ -27,8 +33,36 @@ impl Rng {
     /// assert!(value >= 1 && value <= 10);
     /// ```
     pub fn new() -> Self {
+        #[cfg(target_arch = "wasm32")]
+        {
+            Self::from_entropy()
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Self {
+                rng: RngImpl::ThreadLocal(rand::rng()),
+            }
+        }
+    }
+
+    /// Creates a new random number generator seeded from the operating system.
+    ///
+    /// Never panics: if no entropy source is available, the seed is taken
+    /// from the current time instead (see [`clock::time_seed`](crate::clock::time_seed)).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use runeforge_random::prelude::*;
+    ///
+    /// let mut rng = Rng::from_entropy();
+    /// let value = rng.range(1, 10);
+    /// assert!(value >= 1 && value <= 10);
+    /// ```
+    pub fn from_entropy() -> Self {
+        let rng = StdRng::try_from_os_rng().unwrap_or_else(|_| StdRng::seed_from_u64(time_seed()));
         Self {
-            rng: RngImpl::ThreadLocal(rand::rng()),
+            rng: RngImpl::Seeded(Box::new(rng)),
         }
     }
 
@@ -54,6 +88,29 @@ impl Rng {
         }
     }
 
+    /// Returns a value identifying the generator's current state, without
+    /// advancing it. Two seeded generators that will produce the same
+    /// numbers have the same fingerprint. Returns `None` for unseeded
+    /// generators, whose state isn't reproducible anyway.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use runeforge_random::prelude::*;
+    ///
+    /// let mut a = Rng::with_seed(7);
+    /// let b = Rng::with_seed(7);
+    /// assert_eq!(a.fingerprint(), b.fingerprint());
+    /// a.range(1, 6);
+    /// assert_ne!(a.fingerprint(), b.fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> Option<u64> {
+        match &self.rng {
+            RngImpl::Seeded(rng) => Some(rng.as_ref().clone().next_u64()),
+            RngImpl::ThreadLocal(_) => None,
+        }
+    }
+
     /// Generates a random number in the inclusive range [min, max].
     ///
     /// # Examples
@@ -128,7 +185,7 @@ impl Rng {
     /// let mut rng = Rng::with_seed(12345);
     /// let result = rng.roll("3d6+2").unwrap();
     /// ```
-    pub fn roll(&mut self, notation: &str) -> Result<i32, String> {
+    pub fn roll(&mut self, notation: &str) -> Result<i32, DiceError> {
         let (num_dice, sides, modifier) = parse_dice(notation)?;
         Ok(self.roll_dice(num_dice, sides) + modifier)
     }
@@ -195,13 +252,18 @@ impl Rng {
             return None;
         }
 
-        let total: u32 = weights.iter().sum();
+        let total: u64 = weights.iter().map(|&w| u64::from(w)).sum();
         if total == 0 {
             return None;
         }
 
-        let mut roll = self.with_rng(|r| r.random_range(0..total));
+        // Totals that fit in a u32 draw a u32, so seeded rolls don't change.
+        let mut roll = match u32::try_from(total) {
+            Ok(total) => u64::from(self.with_rng(|r| r.random_range(0..total))),
+            Err(_) => self.with_rng(|r| r.random_range(0..total)),
+        };
         for (item, &weight) in items.iter().zip(weights.iter()) {
+            let weight = u64::from(weight);
             if roll < weight {
                 return Some(item);
             }
@@ -249,14 +311,11 @@ impl Default for Rng {
 /// assert_eq!(parse_dice("1d20+5").unwrap(), (1, 20, 5));
 /// assert_eq!(parse_dice("d8-2").unwrap(), (1, 8, -2));
 /// ```
-pub fn parse_dice(notation: &str) -> Result<(u32, i32, i32), String> {
+pub fn parse_dice(notation: &str) -> Result<(u32, i32, i32), DiceError> {
     let notation = notation.trim().to_lowercase();
 
     let Some(d_pos) = notation.find('d') else {
-        return Err(format!(
-            "Invalid dice notation: missing 'd' in '{}'",
-            notation
-        ));
+        return Err(DiceError::MissingSeparator { notation });
     };
 
     let num_str = &notation[..d_pos];
@@ -265,32 +324,44 @@ pub fn parse_dice(notation: &str) -> Result<(u32, i32, i32), String> {
     } else {
         num_str
             .parse::<u32>()
-            .map_err(|_| format!("Invalid number of dice: '{}' in '{}'", num_str, notation))?
+            .map_err(|_| DiceError::InvalidCount {
+                notation: notation.clone(),
+                value: num_str.to_string(),
+            })?
     };
 
     let rest = &notation[d_pos + 1..];
+    let invalid_modifier = |value: &str| DiceError::InvalidModifier {
+        notation: notation.clone(),
+        value: value.to_string(),
+    };
     let (sides_str, modifier) = if let Some(plus_pos) = rest.find('+') {
         let modifier_str = &rest[plus_pos + 1..];
         let modifier = modifier_str
             .parse::<i32>()
-            .map_err(|_| format!("Invalid modifier: '{}' in '{}'", modifier_str, notation))?;
+            .map_err(|_| invalid_modifier(modifier_str))?;
         (&rest[..plus_pos], modifier)
     } else if let Some(minus_pos) = rest.find('-') {
         let modifier_str = &rest[minus_pos + 1..];
         let modifier = modifier_str
             .parse::<i32>()
-            .map_err(|_| format!("Invalid modifier: '{}' in '{}'", modifier_str, notation))?;
-        (&rest[..minus_pos], -modifier)
+            .ok()
+            .and_then(i32::checked_neg)
+            .ok_or_else(|| invalid_modifier(modifier_str))?;
+        (&rest[..minus_pos], modifier)
     } else {
         (rest, 0)
     };
 
     let sides = sides_str
         .parse::<i32>()
-        .map_err(|_| format!("Invalid number of sides: '{}' in '{}'", sides_str, notation))?;
+        .map_err(|_| DiceError::InvalidSides {
+            notation: notation.clone(),
+            value: sides_str.to_string(),
+        })?;
 
     if sides < 1 {
-        return Err(format!("Dice must have at least 1 side, got {}", sides));
+        return Err(DiceError::TooFewSides { notation, sides });
     }
 
     Ok((num_dice, sides, modifier))
@@ -306,7 +377,7 @@ pub fn parse_dice(notation: &str) -> Result<(u32, i32, i32), String> {
 /// let result = roll_dice_notation("3d6+2").unwrap();
 /// assert!(result >= 5 && result <= 20);  // 3*1+2 to 3*6+2
 /// ```
-pub fn roll_dice_notation(notation: &str) -> Result<i32, String> {
+pub fn roll_dice_notation(notation: &str) -> Result<i32, DiceError> {
     let (num_dice, sides, modifier) = parse_dice(notation)?;
     let mut rng = Rng::new();
     Ok(rng.roll_dice(num_dice, sides) + modifier)
@@ -323,7 +394,7 @@ pub fn roll_dice_notation(notation: &str) -> Result<i32, String> {
 /// let result2 = roll_dice_notation_seeded("3d6+2", 12345).unwrap();
 /// assert_eq!(result1, result2);  // Same seed = same result
 /// ```
-pub fn roll_dice_notation_seeded(notation: &str, seed: u64) -> Result<i32, String> {
+pub fn roll_dice_notation_seeded(notation: &str, seed: u64) -> Result<i32, DiceError> {
     let (num_dice, sides, modifier) = parse_dice(notation)?;
     let mut rng = Rng::with_seed(seed);
     Ok(rng.roll_dice(num_dice, sides) + modifier)
@@ -332,6 +403,7 @@ pub fn roll_dice_notation_seeded(notation: &str, seed: u64) -> Result<i32, Strin
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::{prop_assert, prop_assert_eq, proptest};
 
     #[test]
     fn test_range() {
@@ -342,6 +414,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_from_entropy() {
+        let mut rng = Rng::from_entropy();
+        for _ in 0..100 {
+            let value = rng.range(1, 6);
+            assert!((1..=6).contains(&value));
+        }
+    }
+
     #[test]
     fn test_seeded_determinism() {
         let mut rng1 = Rng::with_seed(42);
@@ -391,6 +472,58 @@ mod tests {
         assert!(parse_dice("invalid").is_err());
         assert!(parse_dice("3x6").is_err());
         assert!(parse_dice("d0").is_err());
+
+        let err = parse_dice("2d6+x").unwrap_err();
+        assert_eq!(err.code(), "dice.invalid_modifier");
+        assert_eq!(err.notation(), "2d6+x");
+        assert_eq!(
+            parse_dice("3x6").unwrap_err(),
+            DiceError::MissingSeparator {
+                notation: "3x6".into()
+            }
+        );
+        assert!(matches!(
+            parse_dice("d0"),
+            Err(DiceError::TooFewSides { sides: 0, .. })
+        ));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_dice_never_panics(notation in "\\PC*") {
+            let _ = parse_dice(&notation);
+        }
+
+        #[test]
+        fn prop_parse_dice_round_trips(
+            count in 0u32..1000,
+            sides in 1i32..1000,
+            modifier in i32::MIN + 1..=i32::MAX,
+        ) {
+            let notation = match modifier {
+                0 => format!("{count}d{sides}"),
+                m => format!("{count}D{sides}{m:+}"),
+            };
+            prop_assert_eq!(parse_dice(&notation), Ok((count, sides, modifier)));
+        }
+
+        #[test]
+        fn prop_parse_dice_accepts_near_miss_notation(
+            notation in "[0-9]{0,12}[dD][-+]?[0-9]{0,12}([-+][-+]?[0-9]{0,12})?"
+        ) {
+            if let Ok((_, sides, _)) = parse_dice(&notation) {
+                prop_assert!(sides >= 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_dice_extreme_modifier() {
+        assert_eq!(
+            parse_dice("1d6-2147483648").unwrap_err().code(),
+            "dice.invalid_modifier"
+        );
+        assert_eq!(parse_dice("1d6-2147483647").unwrap(), (1, 6, -i32::MAX));
     }
 
     #[test]
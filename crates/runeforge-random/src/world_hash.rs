@@ -0,0 +1,202 @@
+//! Stable hashes of game state for replays and desync detection.
+//!
+//! Checking that a replay reproduced a game, or that two peers still agree,
+//! means hashing the world the same way on every run and machine. The
+//! standard library's hashers don't promise that: `DefaultHasher` may change
+//! between Rust versions, and `Hasher`'s default integer methods depend on
+//! endianness and pointer width. [`WorldHash`] is 64-bit FNV-1a with every
+//! integer written as fixed-size little-endian bytes, so any value that
+//! implements [`Hash`] hashes identically everywhere.
+//!
+//! Collections without a defined order, like `HashMap` entity tables, go
+//! through [`add_unordered`](WorldHash::add_unordered), which doesn't depend
+//! on iteration order. RNG state goes through [`add_rng`](WorldHash::add_rng).
+//!
+//! # Example
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use runeforge_random::prelude::*;
+//!
+//! let tiles = vec![0u8, 1, 1, 0];
+//! let mut hp: HashMap<u32, i32> = HashMap::new();
+//! hp.insert(1, 10);
+//! hp.insert(2, 7);
+//! let rng = Rng::with_seed(99);
+//!
+//! let mut hash = WorldHash::new();
+//! hash.add(&tiles).add_unordered(&hp).add_rng(&rng);
+//! let value = hash.finish();
+//!
+//! // Same state, same hash, regardless of HashMap order
+//! let mut again = WorldHash::new();
+//! let hp2: HashMap<u32, i32> = [(2, 7), (1, 10)].into_iter().collect();
+//! again.add(&tiles).add_unordered(&hp2).add_rng(&rng);
+//! assert_eq!(again.finish(), value);
+//! assert_ne!(WorldHash::of(&tiles), WorldHash::of(&vec![0u8, 1, 1, 1]));
+//! ```
+
+use std::hash::{Hash, Hasher};
+
+use crate::rng::Rng;
+
+const OFFSET: u64 = 0xCBF2_9CE4_8422_2325;
+const PRIME: u64 = 0x0100_0000_01B3;
+
+/// A platform-independent FNV-1a hasher for game state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorldHash(u64);
+
+impl Default for WorldHash {
+    fn default() -> Self {
+        Self(OFFSET)
+    }
+}
+
+impl WorldHash {
+    /// Creates an empty hash.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the hash of a single value.
+    pub fn of<T: Hash + ?Sized>(value: &T) -> u64 {
+        let mut hash = Self::new();
+        hash.add(value);
+        hash.finish()
+    }
+
+    /// Adds a value. Order matters: adding `a` then `b` differs from `b`
+    /// then `a`.
+    pub fn add<T: Hash + ?Sized>(&mut self, value: &T) -> &mut Self {
+        value.hash(self);
+        self
+    }
+
+    /// Adds the items of a collection whose iteration order isn't defined,
+    /// such as a `HashMap` or `HashSet`. The result only depends on which
+    /// items there are.
+    pub fn add_unordered<I>(&mut self, items: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: Hash,
+    {
+        let (mut count, mut sum, mut xor) = (0u64, 0u64, 0u64);
+        for item in items {
+            let h = Self::of(&item);
+            count += 1;
+            sum = sum.wrapping_add(h);
+            xor ^= h;
+        }
+        self.write_u64(count);
+        self.write_u64(sum);
+        self.write_u64(xor);
+        self
+    }
+
+    /// Returns the hash of everything added so far. The same as
+    /// [`Hasher::finish`], without importing the trait.
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+
+    /// Adds the state of a random number generator, without advancing it.
+    /// Unseeded generators add a fixed marker.
+    pub fn add_rng(&mut self, rng: &Rng) -> &mut Self {
+        self.add(&rng.fingerprint())
+    }
+}
+
+impl Hasher for WorldHash {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ u64::from(b)).wrapping_mul(PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_u64(i as i64 as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_values_are_fixed() {
+        // Published FNV-1a test vectors
+        let mut hash = WorldHash::new();
+        hash.write(b"a");
+        assert_eq!(hash.finish(), 0xAF63_DC4C_8601_EC8C);
+        assert_eq!(WorldHash::new().finish(), OFFSET);
+
+        // Integers hash as little-endian regardless of platform
+        let mut int = WorldHash::new();
+        int.write_usize(1);
+        let mut bytes = WorldHash::new();
+        bytes.write(&[1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(int.finish(), bytes.finish());
+    }
+
+    #[test]
+    fn test_unordered_and_rng() {
+        let mut a = WorldHash::new();
+        a.add_unordered([3, 1, 2]);
+        let mut b = WorldHash::new();
+        b.add_unordered([1, 2, 3]);
+        assert_eq!(a, b);
+        let mut c = WorldHash::new();
+        c.add_unordered([1, 2, 3, 3]);
+        assert_ne!(a, c);
+
+        let mut rng = Rng::with_seed(5);
+        let before = *WorldHash::new().add_rng(&rng);
+        rng.range(0, 9);
+        assert_ne!(before, *WorldHash::new().add_rng(&rng));
+        assert_eq!(
+            *WorldHash::new().add_rng(&Rng::new()),
+            *WorldHash::new().add(&None::<u64>)
+        );
+    }
+}
@@ -0,0 +1,514 @@
+//! Shareable world seeds and generation manifests.
+//!
+//! A [`WorldSeed`] is the master seed of a world. It prints as a short code
+//! like `1A2B-3C4D-5E6F-7A8B`, can be made from any phrase, and derives
+//! independent sub-seeds by label so each generator gets its own stream.
+//!
+//! A [`GenManifest`] records everything else that decides what a seed
+//! produces: crate versions and every generator config. Its
+//! [`config_hash`](GenManifest::config_hash) is stable across runs and
+//! platforms, and a [`ShareCode`] pairs the seed with a short checksum of it,
+//! so a game can tell when a shared seed was made with different settings or
+//! library versions.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_random::prelude::*;
+//!
+//! let seed = WorldSeed::from_phrase("the goblin king");
+//! let manifest = GenManifest::new(seed)
+//!     .with_config("caves", &(0.45, 5))
+//!     .with_config("depth", &12);
+//!
+//! // Share this string...
+//! let code = manifest.share_code().to_string();
+//!
+//! // ...and on another machine, rebuild the same manifest from it.
+//! let shared: ShareCode = code.parse().unwrap();
+//! let theirs = GenManifest::new(shared.seed())
+//!     .with_config("caves", &(0.45, 5))
+//!     .with_config("depth", &12);
+//! assert!(shared.verify(&theirs).is_ok());
+//!
+//! let mut dungeon_rng = shared.seed().rng_for("dungeon/level-1");
+//! # let _ = dungeon_rng.range(0, 10);
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+use crate::clock::time_seed;
+use crate::error::SeedError;
+use crate::rng::Rng;
+use crate::splitmix::splitmix64;
+
+/// Format version written at the top of a manifest.
+const MANIFEST_HEADER: &str = "runeforge-manifest 1";
+
+/// The master seed of a world.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct WorldSeed(u64);
+
+impl WorldSeed {
+    /// Wraps a raw seed.
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Creates a seed from the operating system, falling back to the clock.
+    pub fn from_entropy() -> Self {
+        Self(
+            StdRng::try_from_os_rng()
+                .map(|mut rng| rng.next_u64())
+                .unwrap_or_else(|_| time_seed()),
+        )
+    }
+
+    /// Creates a seed from a phrase. Case and surrounding whitespace are
+    /// ignored, so "Goblin King " and "goblin king" give the same world.
+    pub fn from_phrase(phrase: &str) -> Self {
+        Self(fnv1a(phrase.trim().to_lowercase().as_bytes()))
+    }
+
+    /// Reads a seed code, falling back to [`from_phrase`](Self::from_phrase)
+    /// for anything that isn't one. Handy for a "seed" text box.
+    pub fn parse_or_phrase(text: &str) -> Self {
+        text.parse().unwrap_or_else(|_| Self::from_phrase(text))
+    }
+
+    /// Returns the raw seed.
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    /// Derives an independent seed for a labelled part of the world, e.g.
+    /// `"dungeon/level-3"`. The same seed and label always give the same
+    /// result.
+    pub fn derive(&self, label: &str) -> WorldSeed {
+        Self(splitmix64(self.0 ^ splitmix64(fnv1a(label.as_bytes()))))
+    }
+
+    /// Returns an [`Rng`] seeded with this seed.
+    pub fn rng(&self) -> Rng {
+        Rng::with_seed(self.0)
+    }
+
+    /// Returns an [`Rng`] seeded with [`derive(label)`](Self::derive).
+    pub fn rng_for(&self, label: &str) -> Rng {
+        self.derive(label).rng()
+    }
+}
+
+impl From<u64> for WorldSeed {
+    fn from(seed: u64) -> Self {
+        Self(seed)
+    }
+}
+
+impl fmt::Display for WorldSeed {
+    /// Formats the seed as four groups of four hex digits.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex = format!("{:016X}", self.0);
+        write!(
+            f,
+            "{}-{}-{}-{}",
+            &hex[0..4],
+            &hex[4..8],
+            &hex[8..12],
+            &hex[12..16]
+        )
+    }
+}
+
+impl FromStr for WorldSeed {
+    type Err = SeedError;
+
+    /// Parses a seed code. Dashes, spaces and case are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits: String = s.chars().filter(|c| !matches!(c, '-' | ' ')).collect();
+        if digits.is_empty() || digits.len() > 16 {
+            return Err(SeedError::InvalidSeed {
+                text: s.to_string(),
+            });
+        }
+        u64::from_str_radix(&digits, 16)
+            .map(Self)
+            .map_err(|_| SeedError::InvalidSeed {
+                text: s.to_string(),
+            })
+    }
+}
+
+/// Everything that decides what a seed generates.
+///
+/// Configs are recorded by their `Debug` output, so any change to a config's
+/// values, or to its fields in a new library version, changes the
+/// [`config_hash`](Self::config_hash). Entries are kept sorted by name, so
+/// the order they are recorded in doesn't matter.
+///
+/// In the text form, spaces, line breaks and backslashes in crate and config
+/// names are escaped as `\s`, `\n` and `\\`, so any name round-trips.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenManifest {
+    seed: WorldSeed,
+    crates: BTreeMap<String, String>,
+    configs: BTreeMap<String, String>,
+}
+
+impl GenManifest {
+    /// Creates a manifest for a seed, recording this crate's version.
+    pub fn new(seed: WorldSeed) -> Self {
+        let mut crates = BTreeMap::new();
+        crates.insert(
+            env!("CARGO_PKG_NAME").to_string(),
+            env!("CARGO_PKG_VERSION").to_string(),
+        );
+        Self {
+            seed,
+            crates,
+            configs: BTreeMap::new(),
+        }
+    }
+
+    /// Records a crate version, e.g. the game's own or another Runeforge crate.
+    pub fn with_crate(mut self, name: &str, version: &str) -> Self {
+        self.record_crate(name, version);
+        self
+    }
+
+    /// Records a crate version.
+    pub fn record_crate(&mut self, name: &str, version: &str) {
+        self.crates.insert(name.to_string(), version.to_string());
+    }
+
+    /// Records a generator config under a name, replacing any earlier one.
+    pub fn with_config(mut self, name: &str, config: &impl fmt::Debug) -> Self {
+        self.record_config(name, config);
+        self
+    }
+
+    /// Records a generator config under a name, replacing any earlier one.
+    pub fn record_config(&mut self, name: &str, config: &impl fmt::Debug) {
+        self.configs
+            .insert(name.to_string(), format!("{:?}", config));
+    }
+
+    /// Returns the seed.
+    pub fn seed(&self) -> WorldSeed {
+        self.seed
+    }
+
+    /// Returns the recorded crate versions, sorted by name.
+    pub fn crates(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.crates.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Returns the recorded configs as `Debug` text, sorted by name.
+    pub fn configs(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.configs.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Returns a hash of the crate versions and configs, not the seed.
+    ///
+    /// The hash is FNV-1a over the manifest text, which is fixed, so it is
+    /// the same on every run and platform.
+    pub fn config_hash(&self) -> u64 {
+        let mut text = String::new();
+        self.write_entries(&mut text);
+        fnv1a(text.as_bytes())
+    }
+
+    /// Returns a code holding the seed and a checksum of the settings.
+    pub fn share_code(&self) -> ShareCode {
+        ShareCode {
+            seed: self.seed,
+            check: Some(fold16(self.config_hash())),
+        }
+    }
+
+    fn write_entries(&self, out: &mut String) {
+        for (name, version) in &self.crates {
+            out.push_str(&format!("crate {} {}\n", escape_name(name), version));
+        }
+        for (name, config) in &self.configs {
+            out.push_str(&format!("config {} {}\n", escape_name(name), config));
+        }
+    }
+}
+
+impl fmt::Display for GenManifest {
+    /// Writes the manifest as text that [`FromStr`] reads back.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut entries = String::new();
+        self.write_entries(&mut entries);
+        writeln!(f, "{}", MANIFEST_HEADER)?;
+        writeln!(f, "seed {}", self.seed)?;
+        f.write_str(&entries)
+    }
+}
+
+impl FromStr for GenManifest {
+    type Err = SeedError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |line: usize, reason: &str| SeedError::InvalidManifest {
+            line,
+            reason: reason.to_string(),
+        };
+        let mut lines = s.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+        match lines.next() {
+            Some((_, header)) if header.trim() == MANIFEST_HEADER => {}
+            Some((i, _)) => return Err(invalid(i + 1, "missing manifest header")),
+            None => return Err(invalid(0, "empty manifest")),
+        }
+
+        let mut manifest: Option<GenManifest> = None;
+        for (i, line) in lines {
+            let (kind, rest) = line.split_once(' ').unwrap_or((line, ""));
+            match (kind, manifest.as_mut()) {
+                ("seed", None) => {
+                    let mut m = GenManifest::new(rest.parse()?);
+                    m.crates.clear();
+                    manifest = Some(m);
+                }
+                ("crate", Some(m)) => {
+                    let (name, version) = rest
+                        .split_once(' ')
+                        .ok_or_else(|| invalid(i + 1, "expected 'crate <name> <version>'"))?;
+                    let name = unescape_name(name)
+                        .ok_or_else(|| invalid(i + 1, "invalid escape in crate name"))?;
+                    m.crates.insert(name, version.to_string());
+                }
+                ("config", Some(m)) => {
+                    let (name, config) = rest
+                        .split_once(' ')
+                        .ok_or_else(|| invalid(i + 1, "expected 'config <name> <value>'"))?;
+                    let name = unescape_name(name)
+                        .ok_or_else(|| invalid(i + 1, "invalid escape in config name"))?;
+                    m.configs.insert(name, config.to_string());
+                }
+                (_, None) => return Err(invalid(i + 1, "expected the seed line first")),
+                _ => return Err(invalid(i + 1, "unknown entry")),
+            }
+        }
+        manifest.ok_or_else(|| invalid(0, "missing seed"))
+    }
+}
+
+/// A seed plus an optional checksum of the settings it was made with.
+///
+/// Prints as `1A2B-3C4D-5E6F-7A8B` or, with a checksum,
+/// `1A2B-3C4D-5E6F-7A8B/9F3C`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShareCode {
+    seed: WorldSeed,
+    check: Option<u16>,
+}
+
+impl ShareCode {
+    /// Returns the seed.
+    pub fn seed(&self) -> WorldSeed {
+        self.seed
+    }
+
+    /// Returns the settings checksum, if the code has one.
+    pub fn check(&self) -> Option<u16> {
+        self.check
+    }
+
+    /// Checks that a manifest has this code's seed and, if the code carries a
+    /// checksum, the same settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SeedError::Mismatch` if the seed or settings differ.
+    pub fn verify(&self, manifest: &GenManifest) -> Result<(), SeedError> {
+        let expected = manifest.share_code();
+        let seed_matches = self.seed == expected.seed;
+        let check_matches = self.check.is_none_or(|check| Some(check) == expected.check);
+        if seed_matches && check_matches {
+            Ok(())
+        } else {
+            Err(SeedError::Mismatch {
+                shared: self.to_string(),
+                local: expected.to_string(),
+            })
+        }
+    }
+}
+
+impl From<WorldSeed> for ShareCode {
+    fn from(seed: WorldSeed) -> Self {
+        Self { seed, check: None }
+    }
+}
+
+impl fmt::Display for ShareCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.seed)?;
+        if let Some(check) = self.check {
+            write!(f, "/{:04X}", check)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ShareCode {
+    type Err = SeedError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (seed, check) = match s.trim().split_once('/') {
+            Some((seed, check)) => {
+                let check =
+                    u16::from_str_radix(check.trim(), 16).map_err(|_| SeedError::InvalidSeed {
+                        text: s.to_string(),
+                    })?;
+                (seed, Some(check))
+            }
+            None => (s, None),
+        };
+        Ok(Self {
+            seed: seed.parse()?,
+            check,
+        })
+    }
+}
+
+/// Escapes a manifest name so it holds no spaces or line breaks.
+fn escape_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ' ' => out.push_str("\\s"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Reverses [`escape_name`]. Returns `None` for an unknown escape.
+fn unescape_name(text: &str) -> Option<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        out.push(match chars.next()? {
+            '\\' => '\\',
+            's' => ' ',
+            'n' => '\n',
+            'r' => '\r',
+            _ => return None,
+        });
+    }
+    Some(out)
+}
+
+/// 64-bit FNV-1a. Fixed forever: seeds and hashes depend on it.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xCBF2_9CE4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01B3)
+    })
+}
+
+fn fold16(hash: u64) -> u16 {
+    ((hash >> 48) ^ (hash >> 32) ^ (hash >> 16) ^ hash) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_codes_round_trip() {
+        let seed = WorldSeed::new(0x1A2B_3C4D_5E6F_7A8B);
+        assert_eq!(seed.to_string(), "1A2B-3C4D-5E6F-7A8B");
+        assert_eq!("1a2b 3c4d-5e6f7a8b".parse::<WorldSeed>().unwrap(), seed);
+        assert_eq!("ff".parse::<WorldSeed>().unwrap(), WorldSeed::new(255));
+        assert_eq!(
+            "xyz".parse::<WorldSeed>().unwrap_err().code(),
+            "seed.invalid_seed"
+        );
+
+        assert_eq!(WorldSeed::parse_or_phrase("1A2B-3C4D-5E6F-7A8B"), seed);
+        assert_eq!(
+            WorldSeed::parse_or_phrase(" Goblin King"),
+            WorldSeed::from_phrase("goblin king")
+        );
+    }
+
+    #[test]
+    fn test_hashes_are_stable() {
+        // These values must never change, or shared seeds stop working.
+        assert_eq!(
+            WorldSeed::from_phrase("runeforge").value(),
+            0xCF93_64B2_7141_1168
+        );
+        let seed = WorldSeed::new(42);
+        assert_eq!(seed.derive("dungeon").value(), 0x7BE9_6D61_AE5C_B736);
+        assert_ne!(seed.derive("dungeon"), seed.derive("overworld"));
+
+        let a = GenManifest::new(seed)
+            .with_config("a", &1)
+            .with_config("b", &"x");
+        let b = GenManifest::new(WorldSeed::new(7))
+            .with_config("b", &"x")
+            .with_config("a", &1);
+        assert_eq!(a.config_hash(), b.config_hash());
+        assert_ne!(
+            a.config_hash(),
+            a.clone().with_config("a", &2).config_hash()
+        );
+    }
+
+    #[test]
+    fn test_manifest_text_and_share_codes() {
+        let manifest = GenManifest::new(WorldSeed::new(99))
+            .with_crate("my-game", "1.2.0")
+            .with_config("caves", &(0.45f32, 5u32));
+        let parsed: GenManifest = manifest.to_string().parse().unwrap();
+        assert_eq!(parsed, manifest);
+
+        let code: ShareCode = manifest.share_code().to_string().parse().unwrap();
+        assert!(code.check().is_some());
+        assert!(code.verify(&manifest).is_ok());
+        assert!(ShareCode::from(manifest.seed()).verify(&manifest).is_ok());
+
+        let other = manifest.clone().with_crate("my-game", "1.3.0");
+        assert_eq!(code.verify(&other).unwrap_err().code(), "seed.mismatch");
+
+        let err = "seed 0\n".parse::<GenManifest>().unwrap_err();
+        assert_eq!(err.code(), "seed.invalid_manifest");
+    }
+
+    #[test]
+    fn test_manifest_names_with_spaces_round_trip() {
+        let manifest = GenManifest::new(WorldSeed::new(5))
+            .with_crate("my game", "0.1.0 beta")
+            .with_config("my caves", &(0.45f32, 5u32))
+            .with_config("odd\\name\nhere", &"x y");
+        let text = manifest.to_string();
+        assert!(text.contains("config my\\scaves (0.45, 5)\n"));
+        let parsed: GenManifest = text.parse().unwrap();
+        assert_eq!(parsed, manifest);
+        assert_eq!(parsed.config_hash(), manifest.config_hash());
+
+        let bad = format!("{}\nseed 0\nconfig a\\q 1\n", MANIFEST_HEADER);
+        let err = bad.parse::<GenManifest>().unwrap_err();
+        assert_eq!(err.code(), "seed.invalid_manifest");
+    }
+}
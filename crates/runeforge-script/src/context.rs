@@ -0,0 +1,116 @@
+//! What a script run can see and what it asks for.
+//!
+//! A [`ScriptContext`] is built fresh for each hook call. It carries the
+//! position that triggered the hook, a dice roller seeded by the game, and
+//! the tiles currently in view for `is_visible` queries. Calls that would
+//! change the world are collected as [`ScriptEffect`]s, in order, for the
+//! game to apply with its own rules.
+//!
+//! # Example
+//!
+//! ```
+//! use glam::IVec2;
+//! use runeforge_script::prelude::*;
+//!
+//! let mut ctx = ScriptContext::new(IVec2::ZERO, 1).with_visible([IVec2::new(1, 0)]);
+//! assert!(ctx.is_visible(IVec2::new(1, 0)));
+//! assert!(!ctx.is_visible(IVec2::new(2, 0)));
+//! assert!(ctx.take_effects().is_empty());
+//! ```
+
+use std::collections::HashSet;
+
+use glam::IVec2;
+use runeforge_random::prelude::Rng;
+
+/// A change a script asked the game to make.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptEffect {
+    /// Spawn an entity from a template id.
+    Spawn {
+        /// Template id, as in the game's template set
+        template: String,
+        /// Where to place it
+        position: IVec2,
+    },
+    /// Replace the tile at a position.
+    SetTile {
+        /// The tile to change
+        position: IVec2,
+        /// The game's name for the new tile
+        tile: String,
+    },
+    /// Show a message in the log.
+    Log(String),
+}
+
+/// State for one script run.
+pub struct ScriptContext {
+    origin: IVec2,
+    rng: Rng,
+    visible: HashSet<IVec2>,
+    effects: Vec<ScriptEffect>,
+}
+
+impl ScriptContext {
+    /// Creates a context for a hook fired at `origin`. Dice rolls come from
+    /// `seed`; draw it from the game's RNG so runs are reproducible.
+    pub fn new(origin: IVec2, seed: u64) -> Self {
+        Self {
+            origin,
+            rng: Rng::with_seed(seed),
+            visible: HashSet::new(),
+            effects: Vec::new(),
+        }
+    }
+
+    /// Sets the tiles the player can see, usually the current FOV.
+    pub fn with_visible(mut self, visible: impl IntoIterator<Item = IVec2>) -> Self {
+        self.visible = visible.into_iter().collect();
+        self
+    }
+
+    /// Returns the position that fired the hook.
+    pub fn origin(&self) -> IVec2 {
+        self.origin
+    }
+
+    /// Returns true if `position` is in view.
+    pub fn is_visible(&self, position: IVec2) -> bool {
+        self.visible.contains(&position)
+    }
+
+    /// Returns the effects recorded so far.
+    pub fn effects(&self) -> &[ScriptEffect] {
+        &self.effects
+    }
+
+    /// Removes and returns the recorded effects.
+    pub fn take_effects(&mut self) -> Vec<ScriptEffect> {
+        std::mem::take(&mut self.effects)
+    }
+
+    pub(crate) fn push(&mut self, effect: ScriptEffect) {
+        self.effects.push(effect);
+    }
+
+    pub(crate) fn rng(&mut self) -> &mut Rng {
+        &mut self.rng
+    }
+}
+
+impl Default for ScriptContext {
+    fn default() -> Self {
+        Self::new(IVec2::ZERO, 0)
+    }
+}
+
+impl std::fmt::Debug for ScriptContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptContext")
+            .field("origin", &self.origin)
+            .field("visible", &self.visible.len())
+            .field("effects", &self.effects)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,348 @@
+//! Compiling scripts and calling their hooks.
+//!
+//! A script is a set of Rhai functions named after the [`Hook`]s it
+//! handles, each taking the `x` and `y` of the position that fired it:
+//!
+//! ```text
+//! fn on_use(x, y) {
+//!     let heal = roll("2d4+1");
+//!     log(`You feel better (${heal}).`);
+//! }
+//! ```
+//!
+//! Hooks can call these functions:
+//!
+//! | Function | Does |
+//! |----------|------|
+//! | `spawn_entity(template, x, y)` | Records a [`ScriptEffect::Spawn`] |
+//! | `set_tile(x, y, tile)` | Records a [`ScriptEffect::SetTile`] |
+//! | `log(message)` | Records a [`ScriptEffect::Log`]; so does `print` |
+//! | `roll(notation)` | Rolls dice such as `"3d6+2"`, at most 1000 at once |
+//! | `random(min, max)` | Returns a number in `min..=max` |
+//! | `is_visible(x, y)` | Returns true if the tile is in view |
+//!
+//! Top-level statements outside functions are not run.
+//!
+//! # Example
+//!
+//! ```
+//! use glam::IVec2;
+//! use runeforge_script::prelude::*;
+//!
+//! let mut scripts = ScriptEngine::new();
+//! scripts.load("potion", r#"fn on_use(x, y) { log(`healed ${roll("1d1+4")}`); }"#).unwrap();
+//!
+//! let mut ctx = ScriptContext::new(IVec2::ZERO, 3);
+//! assert!(scripts.run("potion", Hook::Use, &mut ctx).unwrap());
+//! assert!(!scripts.run("potion", Hook::Turn, &mut ctx).unwrap()); // no on_turn
+//! assert_eq!(ctx.take_effects(), vec![ScriptEffect::Log("healed 5".into())]);
+//! ```
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use glam::IVec2;
+use rhai::{CallFnOptions, Dynamic, Engine, EvalAltResult, Scope, AST};
+use runeforge_random::prelude::parse_dice;
+
+use crate::context::{ScriptContext, ScriptEffect};
+use crate::error::ScriptError;
+
+/// The most operations one hook call may perform.
+const MAX_OPERATIONS: u64 = 100_000;
+
+/// The most dice one `roll` call may throw. Dice are rolled in native
+/// code, so [`MAX_OPERATIONS`] doesn't bound them.
+const MAX_DICE: u32 = 1000;
+
+/// Events that scripts can respond to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Hook {
+    /// An item was used: `on_use`.
+    Use,
+    /// A trap was sprung: `on_trigger`.
+    Trigger,
+    /// Something entered a tile or region: `on_enter`.
+    Enter,
+    /// A turn passed: `on_turn`.
+    Turn,
+}
+
+impl Hook {
+    /// Returns the script function that handles this hook.
+    pub fn function_name(self) -> &'static str {
+        match self {
+            Hook::Use => "on_use",
+            Hook::Trigger => "on_trigger",
+            Hook::Enter => "on_enter",
+            Hook::Turn => "on_turn",
+        }
+    }
+}
+
+/// Compiled scripts and the sandboxed interpreter that runs them.
+pub struct ScriptEngine {
+    engine: Engine,
+    scripts: HashMap<String, AST>,
+    /// The context of the run in progress, shared with the registered API.
+    current: Rc<RefCell<ScriptContext>>,
+}
+
+impl ScriptEngine {
+    /// Creates an engine with the game API registered and no scripts.
+    pub fn new() -> Self {
+        let current = Rc::new(RefCell::new(ScriptContext::default()));
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(MAX_OPERATIONS)
+            .set_max_call_levels(32)
+            .set_max_expr_depths(64, 32)
+            .set_max_string_size(4096)
+            .set_max_array_size(1024)
+            .set_max_map_size(256)
+            .disable_symbol("eval");
+
+        let ctx = current.clone();
+        engine.register_fn("spawn_entity", move |template: &str, x: i64, y: i64| {
+            let position = point(x, y)?;
+            ctx.borrow_mut().push(ScriptEffect::Spawn {
+                template: template.to_string(),
+                position,
+            });
+            Ok::<_, Box<EvalAltResult>>(())
+        });
+        let ctx = current.clone();
+        engine.register_fn("set_tile", move |x: i64, y: i64, tile: &str| {
+            let position = point(x, y)?;
+            ctx.borrow_mut().push(ScriptEffect::SetTile {
+                position,
+                tile: tile.to_string(),
+            });
+            Ok::<_, Box<EvalAltResult>>(())
+        });
+        let ctx = current.clone();
+        engine.register_fn("log", move |message: &str| {
+            ctx.borrow_mut()
+                .push(ScriptEffect::Log(message.to_string()));
+        });
+        let ctx = current.clone();
+        engine.on_print(move |message| {
+            ctx.borrow_mut()
+                .push(ScriptEffect::Log(message.to_string()));
+        });
+        engine.on_debug(|_, _, _| {});
+        let ctx = current.clone();
+        engine.register_fn("roll", move |notation: &str| {
+            let (count, sides, modifier) =
+                parse_dice(notation).map_err(|e| Box::<EvalAltResult>::from(e.to_string()))?;
+            if count > MAX_DICE {
+                return Err(format!("cannot roll {count} dice; the limit is {MAX_DICE}").into());
+            }
+            // Summed in i64, which `MAX_DICE` dice of any size can't overflow.
+            let mut ctx = ctx.borrow_mut();
+            let total = (0..count).fold(i64::from(modifier), |total, _| {
+                total + i64::from(ctx.rng().range(1, sides))
+            });
+            Ok::<_, Box<EvalAltResult>>(total)
+        });
+        let ctx = current.clone();
+        engine.register_fn("random", move |min: i64, max: i64| {
+            let (min, max) = (clamp(min), clamp(max));
+            i64::from(ctx.borrow_mut().rng().range(min.min(max), min.max(max)))
+        });
+        let ctx = current.clone();
+        engine.register_fn("is_visible", move |x: i64, y: i64| {
+            point(x, y).is_ok_and(|p| ctx.borrow().is_visible(p))
+        });
+
+        Self {
+            engine,
+            scripts: HashMap::new(),
+            current,
+        }
+    }
+
+    /// Compiles `source` and stores it under `id`, replacing any script
+    /// with that id.
+    pub fn load(&mut self, id: impl Into<String>, source: &str) -> Result<(), ScriptError> {
+        let id = id.into();
+        let ast = self
+            .engine
+            .compile(source)
+            .map_err(|e| ScriptError::Compile {
+                script: id.clone(),
+                message: e.to_string(),
+            })?;
+        self.scripts.insert(id, ast);
+        Ok(())
+    }
+
+    /// Removes a script, returning true if it was loaded.
+    pub fn unload(&mut self, id: &str) -> bool {
+        self.scripts.remove(id).is_some()
+    }
+
+    /// Returns true if a script is loaded under `id`.
+    pub fn contains(&self, id: &str) -> bool {
+        self.scripts.contains_key(id)
+    }
+
+    /// Returns true if the script defines a handler for `hook`.
+    pub fn has_hook(&self, id: &str, hook: Hook) -> bool {
+        self.scripts.get(id).is_some_and(|ast| {
+            ast.iter_functions()
+                .any(|f| f.name == hook.function_name() && f.params.len() == 2)
+        })
+    }
+
+    /// Returns the ids of all loaded scripts, in no particular order.
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.scripts.keys().map(String::as_str)
+    }
+
+    /// Runs the script's handler for `hook` at `ctx.origin()`. Effects are
+    /// appended to `ctx`, even if the script fails partway.
+    ///
+    /// Returns `Ok(false)` if the script doesn't handle `hook`.
+    pub fn run(&self, id: &str, hook: Hook, ctx: &mut ScriptContext) -> Result<bool, ScriptError> {
+        let ast = self
+            .scripts
+            .get(id)
+            .ok_or_else(|| ScriptError::MissingScript(id.to_string()))?;
+        if !self.has_hook(id, hook) {
+            return Ok(false);
+        }
+
+        let origin = ctx.origin();
+        *self.current.borrow_mut() = std::mem::take(ctx);
+        let result = self.engine.call_fn_with_options::<Dynamic>(
+            CallFnOptions::new().eval_ast(false),
+            &mut Scope::new(),
+            ast,
+            hook.function_name(),
+            (i64::from(origin.x), i64::from(origin.y)),
+        );
+        *ctx = self.current.take();
+
+        result.map(|_| true).map_err(|e| ScriptError::Runtime {
+            script: id.to_string(),
+            hook: hook.function_name(),
+            message: e.to_string(),
+        })
+    }
+}
+
+impl Default for ScriptEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for ScriptEngine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptEngine")
+            .field("scripts", &self.scripts.len())
+            .finish_non_exhaustive()
+    }
+}
+
+fn clamp(value: i64) -> i32 {
+    value.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32
+}
+
+fn point(x: i64, y: i64) -> Result<IVec2, Box<EvalAltResult>> {
+    match (i32::try_from(x), i32::try_from(y)) {
+        (Ok(x), Ok(y)) => Ok(IVec2::new(x, y)),
+        _ => Err(format!("position ({x}, {y}) is out of range").into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_rolls_repeat() {
+        let mut scripts = ScriptEngine::new();
+        scripts
+            .load(
+                "fountain",
+                "fn on_enter(x, y) { print(roll(\"3d6\")); print(random(1, 100)); }",
+            )
+            .unwrap();
+        let run = |seed| {
+            let mut ctx = ScriptContext::new(IVec2::new(1, 1), seed);
+            scripts.run("fountain", Hook::Enter, &mut ctx).unwrap();
+            ctx.take_effects()
+        };
+        assert_eq!(run(9), run(9));
+        assert_eq!(run(9).len(), 2);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut scripts = ScriptEngine::new();
+        let err = scripts.load("bad", "fn on_use(x, y) {").unwrap_err();
+        assert_eq!(err.code(), "script.compile");
+        assert!(!scripts.contains("bad"));
+
+        let mut ctx = ScriptContext::default();
+        assert_eq!(
+            scripts.run("nope", Hook::Use, &mut ctx),
+            Err(ScriptError::MissingScript("nope".into()))
+        );
+
+        scripts
+            .load("dice", "fn on_use(x, y) { log(\"before\"); roll(\"xd\"); }")
+            .unwrap();
+        let err = scripts.run("dice", Hook::Use, &mut ctx).unwrap_err();
+        assert_eq!(err.code(), "script.runtime");
+        assert_eq!(ctx.effects(), &[ScriptEffect::Log("before".into())]);
+    }
+
+    #[test]
+    fn test_sandbox_limits() {
+        let mut scripts = ScriptEngine::new();
+        scripts
+            .load("loop", "fn on_turn(x, y) { loop { x += 1; } }")
+            .unwrap();
+        let mut ctx = ScriptContext::default();
+        let err = scripts.run("loop", Hook::Turn, &mut ctx).unwrap_err();
+        assert!(matches!(
+            err,
+            ScriptError::Runtime {
+                hook: "on_turn",
+                ..
+            }
+        ));
+
+        assert!(scripts
+            .load("eval", "fn on_use(x, y) { eval(\"1\") }")
+            .is_err());
+
+        scripts
+            .load("many", "fn on_use(x, y) { roll(\"4000000000d1\"); }")
+            .unwrap();
+        let err = scripts.run("many", Hook::Use, &mut ctx).unwrap_err();
+        assert_eq!(err.code(), "script.runtime");
+
+        scripts
+            .load(
+                "huge",
+                "fn on_use(x, y) { log(`${roll(\"1d6+2147483647\")}`); log(`${roll(\"1000d2147483647\")}`); }",
+            )
+            .unwrap();
+        scripts.run("huge", Hook::Use, &mut ctx).unwrap();
+        let totals: Vec<i64> = ctx
+            .take_effects()
+            .into_iter()
+            .map(|effect| match effect {
+                ScriptEffect::Log(total) => total.parse().unwrap(),
+                other => panic!("unexpected effect {other:?}"),
+            })
+            .collect();
+        assert!(totals[0] > i64::from(i32::MAX));
+        assert!(totals[1] >= 1000);
+    }
+}
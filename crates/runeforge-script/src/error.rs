@@ -0,0 +1,42 @@
+//! Error types for scripting.
+
+use thiserror::Error;
+
+/// Errors that can occur while loading or running scripts.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ScriptError {
+    /// A script failed to compile.
+    #[error("Script '{script}' failed to compile: {message}")]
+    Compile {
+        /// Id of the script
+        script: String,
+        /// The compiler's message
+        message: String,
+    },
+
+    /// No script is loaded under this id.
+    #[error("No script named '{0}'")]
+    MissingScript(String),
+
+    /// A script raised an error or hit a sandbox limit while running.
+    #[error("Script '{script}' failed in {hook}: {message}")]
+    Runtime {
+        /// Id of the script
+        script: String,
+        /// The hook function that was running
+        hook: &'static str,
+        /// The interpreter's message
+        message: String,
+    },
+}
+
+impl ScriptError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ScriptError::Compile { .. } => "script.compile",
+            ScriptError::MissingScript(_) => "script.missing_script",
+            ScriptError::Runtime { .. } => "script.runtime",
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! Scripted content for roguelike games.
+//!
+//! # Overview
+//!
+//! `runeforge-script` runs [Rhai](https://rhai.rs) scripts attached to items,
+//! traps and map events, so content can be written and tweaked without
+//! recompiling the game.
+//!
+//! *   **Engine:** [`ScriptEngine`](engine::ScriptEngine) compiles scripts by id and calls their
+//!     [`Hook`](engine::Hook) functions (`on_use`, `on_trigger`, `on_enter`, `on_turn`).
+//! *   **Context:** A [`ScriptContext`](context::ScriptContext) gives a run its seeded dice and
+//!     the tiles in view, and collects what the script asked for.
+//! *   **Effects:** Scripts never touch the world directly: `spawn_entity`, `set_tile` and `log`
+//!     record [`ScriptEffect`](context::ScriptEffect)s that the game applies afterwards.
+//!
+//! Scripts are sandboxed: they have no file or system access, `eval` is
+//! disabled, each run is capped in operations and call depth, and `roll`
+//! throws at most 1000 dice per call.
+//!
+//! # Usage
+//!
+//! Add this to your `Cargo.toml`:
+//!
+//! ```toml
+//! [dependencies]
+//! runeforge-script = "0.1"
+//! ```
+//!
+//! ## Example
+//!
+//! ```rust
+//! use glam::IVec2;
+//! use runeforge_script::prelude::*;
+//!
+//! let mut scripts = ScriptEngine::new();
+//! scripts
+//!     .load(
+//!         "trap.summon",
+//!         r#"
+//!             fn on_trigger(x, y) {
+//!                 log("The floor gives way!");
+//!                 set_tile(x, y, "pit");
+//!                 if is_visible(x + 1, y) { spawn_entity("rat", x + 1, y); }
+//!             }
+//!         "#,
+//!     )
+//!     .unwrap();
+//!
+//! let mut ctx = ScriptContext::new(IVec2::new(4, 2), 7).with_visible([IVec2::new(5, 2)]);
+//! assert!(scripts.run("trap.summon", Hook::Trigger, &mut ctx).unwrap());
+//! assert_eq!(
+//!     ctx.take_effects(),
+//!     vec![
+//!         ScriptEffect::Log("The floor gives way!".into()),
+//!         ScriptEffect::SetTile { position: IVec2::new(4, 2), tile: "pit".into() },
+//!         ScriptEffect::Spawn { template: "rat".into(), position: IVec2::new(5, 2) },
+//!     ]
+//! );
+//! ```
+
+#![deny(missing_docs)]
+
+pub mod context;
+pub mod engine;
+pub mod error;
+
+/// Prelude module for convenient imports.
+pub mod prelude {
+    pub use crate::context::{ScriptContext, ScriptEffect};
+    pub use crate::engine::{Hook, ScriptEngine};
+    pub use crate::error::ScriptError;
+}
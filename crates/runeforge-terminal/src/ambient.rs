@@ -0,0 +1,232 @@
+//! Day and night: ambient light that follows the game clock.
+//!
+//! An [`AmbientLight`] maps the time of day to an ambient [`AmbientPreset`],
+//! a color and an intensity, by blending between keys on a looping curve.
+//! Advance it with the game's turns and [`apply`](AmbientLight::apply) it to
+//! a [`LightLayer`] before adding torches and other lights, and the whole
+//! console dims into night and warms up at dawn without touching the map.
+//!
+//! Time of day runs from 0.0 (midnight) through 0.5 (noon) back to 1.0.
+//! The default curve uses the [`NIGHT`](AmbientPreset::NIGHT),
+//! [`DAWN`](AmbientPreset::DAWN), [`DAY`](AmbientPreset::DAY) and
+//! [`DUSK`](AmbientPreset::DUSK) presets.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_color::Color;
+//! use runeforge_terminal::prelude::*;
+//!
+//! // A day lasts 400 turns; start at noon
+//! let mut ambient = AmbientLight::new(400).with_turn(200);
+//! assert_eq!(ambient.color(), AmbientPreset::DAY.light());
+//!
+//! ambient.advance(200);
+//! assert_eq!(ambient.current(), AmbientPreset::NIGHT);
+//!
+//! let mut light = LightLayer::new(20, 10);
+//! ambient.apply(&mut light);
+//! light.set(IVec2::new(4, 4), Color::rgb(255, 200, 120)); // a torch
+//! assert_eq!(light.get(IVec2::ZERO), AmbientPreset::NIGHT.light());
+//! ```
+
+use runeforge_color::Color;
+
+use crate::prelude::*;
+
+/// An ambient color and how bright it is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AmbientPreset {
+    /// Hue of the ambient light.
+    pub color: Color,
+    /// Brightness from 0.0 (black) to 1.0 (the full color).
+    pub intensity: f32,
+}
+
+impl AmbientPreset {
+    /// Full, neutral daylight.
+    pub const DAY: Self = Self::new(Color::WHITE, 1.0);
+    /// Cool pink morning light.
+    pub const DAWN: Self = Self::new(Color::rgb(255, 190, 200), 0.7);
+    /// Warm orange evening light.
+    pub const DUSK: Self = Self::new(Color::rgb(255, 170, 110), 0.6);
+    /// Dim blue moonlight.
+    pub const NIGHT: Self = Self::new(Color::rgb(110, 130, 255), 0.3);
+
+    /// Creates a preset.
+    pub const fn new(color: Color, intensity: f32) -> Self {
+        Self { color, intensity }
+    }
+
+    /// Returns the color scaled by the intensity, as written to a
+    /// [`LightLayer`].
+    pub fn light(&self) -> Color {
+        Color::BLACK.lerp(self.color, self.intensity)
+    }
+
+    /// Blends toward `other` by `t` (0.0 to 1.0).
+    pub fn lerp(&self, other: Self, t: f32) -> Self {
+        if *self == other {
+            return other;
+        }
+        let t = t.clamp(0.0, 1.0);
+        Self {
+            color: self.color.lerp(other.color, t),
+            intensity: self.intensity + (other.intensity - self.intensity) * t,
+        }
+    }
+}
+
+/// Drives ambient light from a turn counter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmbientLight {
+    day_length: u32,
+    turn: u64,
+    /// Curve keys by time of day, sorted.
+    keys: Vec<(f32, AmbientPreset)>,
+}
+
+impl AmbientLight {
+    /// Creates a controller whose day lasts `day_length` turns, at midnight
+    /// of turn 0, with the default curve: night until a dawn at 0.25, full
+    /// day from 0.35 to 0.65, dusk at 0.75 and night again from 0.85.
+    pub fn new(day_length: u32) -> Self {
+        Self {
+            day_length: day_length.max(1),
+            turn: 0,
+            keys: vec![
+                (0.15, AmbientPreset::NIGHT),
+                (0.25, AmbientPreset::DAWN),
+                (0.35, AmbientPreset::DAY),
+                (0.65, AmbientPreset::DAY),
+                (0.75, AmbientPreset::DUSK),
+                (0.85, AmbientPreset::NIGHT),
+            ],
+        }
+    }
+
+    /// Creates a controller with a constant light, for underground levels.
+    pub fn constant(preset: AmbientPreset) -> Self {
+        Self::new(1).with_curve([(0.0, preset)])
+    }
+
+    /// Replaces the curve. Times are wrapped into `0.0..1.0`; the curve
+    /// loops, blending from the last key around to the first.
+    pub fn with_curve(mut self, keys: impl IntoIterator<Item = (f32, AmbientPreset)>) -> Self {
+        self.keys = keys
+            .into_iter()
+            .map(|(time, preset)| (time.rem_euclid(1.0), preset))
+            .collect();
+        self.keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self
+    }
+
+    /// Sets the current turn.
+    pub fn with_turn(mut self, turn: u64) -> Self {
+        self.turn = turn;
+        self
+    }
+
+    /// Returns the number of turns in a day.
+    pub fn day_length(&self) -> u32 {
+        self.day_length
+    }
+
+    /// Returns the current turn.
+    pub fn turn(&self) -> u64 {
+        self.turn
+    }
+
+    /// Sets the current turn, e.g. from the game's scheduler.
+    pub fn set_turn(&mut self, turn: u64) {
+        self.turn = turn;
+    }
+
+    /// Moves the clock forward.
+    pub fn advance(&mut self, turns: u64) {
+        self.turn = self.turn.saturating_add(turns);
+    }
+
+    /// Returns the number of whole days passed.
+    pub fn day(&self) -> u64 {
+        self.turn / self.day_length as u64
+    }
+
+    /// Returns the time of day, from 0.0 (midnight) to just below 1.0.
+    pub fn time_of_day(&self) -> f32 {
+        (self.turn % self.day_length as u64) as f32 / self.day_length as f32
+    }
+
+    /// Returns the ambient light at the current time.
+    pub fn current(&self) -> AmbientPreset {
+        self.at(self.time_of_day())
+    }
+
+    /// Returns the ambient light at a time of day.
+    pub fn at(&self, time: f32) -> AmbientPreset {
+        let Some(&(last_time, last)) = self.keys.last() else {
+            return AmbientPreset::DAY;
+        };
+        let time = time.rem_euclid(1.0);
+        // The key at or before `time`, wrapping to the last key of the
+        // previous day, and the one after it.
+        let next_idx = self.keys.partition_point(|(t, _)| *t <= time);
+        let (from_time, from) = match next_idx {
+            0 => (last_time - 1.0, last),
+            i => self.keys[i - 1],
+        };
+        let (to_time, to) = match self.keys.get(next_idx) {
+            Some(&key) => key,
+            None => (self.keys[0].0 + 1.0, self.keys[0].1),
+        };
+        let span = to_time - from_time;
+        let t = if span > 0.0 {
+            (time - from_time) / span
+        } else {
+            0.0
+        };
+        from.lerp(to, t)
+    }
+
+    /// Returns the ambient light color at the current time.
+    pub fn color(&self) -> Color {
+        self.current().light()
+    }
+
+    /// Fills `layer` with the current ambient light. Set brighter lights
+    /// afterwards.
+    pub fn apply(&self, layer: &mut LightLayer) {
+        layer.fill(self.color());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curve_blends_and_wraps() {
+        let night = AmbientPreset::new(Color::BLACK, 0.0);
+        let day = AmbientPreset::new(Color::WHITE, 1.0);
+        let ambient = AmbientLight::new(100).with_curve([(0.75, night), (0.25, day)]);
+
+        assert_eq!(ambient.at(0.25), day);
+        assert_eq!(ambient.at(0.5).intensity, 0.5);
+        // From 0.75 the curve wraps through midnight back to 0.25
+        assert_eq!(ambient.at(0.0).intensity, 0.5);
+        assert_eq!(ambient.at(0.125).intensity, 0.75);
+        assert_eq!(ambient.at(1.75), night);
+    }
+
+    #[test]
+    fn test_clock_and_constant() {
+        let mut ambient = AmbientLight::new(10);
+        ambient.advance(25);
+        assert_eq!(ambient.day(), 2);
+        assert_eq!(ambient.time_of_day(), 0.5);
+
+        let cave = AmbientLight::constant(AmbientPreset::NIGHT).with_turn(12345);
+        assert_eq!(cave.current(), AmbientPreset::NIGHT);
+        assert_eq!(AmbientLight::new(5).with_curve([]).color(), Color::WHITE);
+    }
+}
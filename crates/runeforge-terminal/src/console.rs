@@ -21,6 +21,7 @@
 
 use crate::prelude::*;
 use runeforge_color::Color;
+use runeforge_geometry::prelude::{ScreenMapping, ScreenPos, WorldPos};
 
 /// Trait for console rendering backends.
 ///
@@ -106,8 +107,53 @@ pub trait Console {
     /// for software rendering.
     fn present(&mut self);
 
+    /// Returns the cursor state.
+    ///
+    /// Backends without cursor support return the default, which has no
+    /// position.
+    fn cursor(&self) -> Cursor {
+        Cursor::default()
+    }
+
+    /// Replaces the cursor state. Backends without cursor support ignore it.
+    fn set_cursor_state(&mut self, _cursor: Cursor) {}
+
     // --- Convenience methods with default implementations ---
 
+    /// Moves the cursor to a cell.
+    ///
+    /// Backends restart the blink cycle when the position changes, so the
+    /// caret stays lit while the player types. Setting the same position
+    /// every frame is fine.
+    fn set_cursor(&mut self, pos: IVec2) {
+        let cursor = Cursor {
+            pos: Some(pos),
+            ..self.cursor()
+        };
+        self.set_cursor_state(cursor);
+    }
+
+    /// Removes the cursor from the console.
+    fn clear_cursor(&mut self) {
+        let cursor = Cursor {
+            pos: None,
+            ..self.cursor()
+        };
+        self.set_cursor_state(cursor);
+    }
+
+    /// Shows or hides the cursor without forgetting its position.
+    fn cursor_visible(&mut self, visible: bool) {
+        let cursor = self.cursor().with_visible(visible);
+        self.set_cursor_state(cursor);
+    }
+
+    /// Sets the cursor blink interval, or `None` for a steady cursor.
+    fn set_cursor_blink(&mut self, rate: Option<std::time::Duration>) {
+        let cursor = self.cursor().with_blink_rate(rate);
+        self.set_cursor_state(cursor);
+    }
+
     /// Returns the console dimensions as (width, height).
     fn size(&self) -> (u32, u32) {
         (self.width(), self.height())
@@ -160,6 +206,24 @@ pub trait Console {
         self.set(pos, Cell::new(ch, fg, bg))
     }
 
+    /// Sets the cell at a typed screen position.
+    fn set_screen(&mut self, pos: ScreenPos, cell: Cell) -> bool {
+        self.set(pos.0, cell)
+    }
+
+    /// Sets the cell showing world position `pos`, converting it to the
+    /// screen through `mapping`.
+    ///
+    /// Returns `false` if the position is off screen.
+    fn set_world(&mut self, mapping: &ScreenMapping, pos: WorldPos, cell: Cell) -> bool {
+        self.set_screen(mapping.to_screen(pos), cell)
+    }
+
+    /// Gets the cell showing world position `pos` through `mapping`.
+    fn get_world(&self, mapping: &ScreenMapping, pos: WorldPos) -> Option<Cell> {
+        self.get(mapping.to_screen(pos).0)
+    }
+
     /// Draws a string horizontally starting at the given position.
     ///
     /// Returns the number of characters actually drawn.
@@ -307,6 +371,7 @@ pub trait Console {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use runeforge_geometry::prelude::CoordinateSystem;
 
     // Simple test console implementation
     struct TestConsole {
@@ -424,6 +489,25 @@ mod tests {
         assert_eq!(console.get(IVec2::new(5, 6)).unwrap().ch, '│');
     }
 
+    #[test]
+    fn test_cursor_ignored_without_support() {
+        let mut console = TestConsole::new(4, 4);
+        console.set_cursor(IVec2::new(1, 1));
+        console.cursor_visible(false);
+        assert_eq!(console.cursor(), Cursor::default());
+    }
+
+    #[test]
+    fn test_set_world_flips_y_up() {
+        let mut console = TestConsole::new(5, 5);
+        let mapping = ScreenMapping::new(CoordinateSystem::YUp, WorldPos::new(0, 4));
+        let cell = Cell::new('@', Color::WHITE, Color::BLACK);
+        assert!(console.set_world(&mapping, WorldPos::new(1, 3), cell));
+        assert_eq!(console.get(IVec2::new(1, 1)), Some(cell));
+        assert_eq!(console.get_world(&mapping, WorldPos::new(1, 3)), Some(cell));
+        assert!(!console.set_world(&mapping, WorldPos::new(1, 5), cell));
+    }
+
     #[test]
     fn test_console_clear() {
         let mut console = TestConsole::new(10, 10);
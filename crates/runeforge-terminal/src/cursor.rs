@@ -0,0 +1,112 @@
+//! Cursor and text caret state for consoles.
+//!
+//! Text input fields and targeting modes need a caret that survives the
+//! clear/redraw cycle. Instead of drawing it by hand, set it on the console
+//! with [`Console::set_cursor`]: the ANSI [`Terminal`] moves the native
+//! terminal cursor, and the pixel renderers draw the cell under it with its
+//! colors swapped, blinking at [`Cursor::blink_rate`].
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_terminal::prelude::*;
+//! use std::time::Duration;
+//!
+//! let cursor = Cursor::at(IVec2::new(4, 2)).with_blink_rate(Some(Duration::from_millis(500)));
+//!
+//! assert_eq!(cursor.shown_at(Duration::from_millis(100)), Some(IVec2::new(4, 2)));
+//! assert_eq!(cursor.shown_at(Duration::from_millis(600)), None); // blinked off
+//! assert_eq!(cursor.shown_at(Duration::from_millis(1100)), Some(IVec2::new(4, 2)));
+//! ```
+
+use std::time::Duration;
+
+use crate::prelude::*;
+
+/// The default caret blink interval, matching common desktop settings.
+pub const DEFAULT_BLINK_RATE: Duration = Duration::from_millis(530);
+
+/// Where the cursor is and how it is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    /// Cell the cursor is on, or `None` for no cursor
+    pub pos: Option<IVec2>,
+    /// Whether the cursor is drawn at all
+    pub visible: bool,
+    /// Time the cursor stays on (and then off), or `None` for a steady cursor
+    pub blink_rate: Option<Duration>,
+}
+
+impl Default for Cursor {
+    fn default() -> Self {
+        Self {
+            pos: None,
+            visible: true,
+            blink_rate: Some(DEFAULT_BLINK_RATE),
+        }
+    }
+}
+
+impl Cursor {
+    /// Creates a visible, blinking cursor at `pos`.
+    pub fn at(pos: IVec2) -> Self {
+        Self {
+            pos: Some(pos),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the blink interval, or `None` for a steady cursor.
+    pub fn with_blink_rate(mut self, rate: Option<Duration>) -> Self {
+        self.blink_rate = rate;
+        self
+    }
+
+    /// Shows or hides the cursor.
+    pub fn with_visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    /// Returns the cursor position if it should be drawn `elapsed` after it
+    /// was last moved. The cursor starts each blink cycle in the on phase.
+    pub fn shown_at(&self, elapsed: Duration) -> Option<IVec2> {
+        if !self.visible {
+            return None;
+        }
+        let on = match self.blink_rate {
+            Some(rate) if !rate.is_zero() => (elapsed.as_nanos() / rate.as_nanos()) % 2 == 0,
+            _ => true,
+        };
+        self.pos.filter(|_| on)
+    }
+}
+
+/// Returns `cell` with its foreground and background swapped, as drawn under
+/// the cursor.
+pub fn invert_cell(cell: Cell) -> Cell {
+    cell.with_fg(cell.bg).with_bg(cell.fg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runeforge_color::Color;
+
+    #[test]
+    fn test_hidden_and_steady() {
+        let cursor = Cursor::at(IVec2::ONE).with_blink_rate(None);
+        assert_eq!(cursor.shown_at(Duration::from_secs(7)), Some(IVec2::ONE));
+        assert_eq!(cursor.with_visible(false).shown_at(Duration::ZERO), None);
+        assert_eq!(Cursor::default().shown_at(Duration::ZERO), None);
+    }
+
+    #[test]
+    fn test_invert_cell() {
+        let cell = Cell::new('a', Color::WHITE, Color::BLUE);
+        let inverted = invert_cell(cell);
+        assert_eq!(inverted.fg, Color::BLUE);
+        assert_eq!(inverted.bg, Color::WHITE);
+        assert_eq!(inverted.ch, 'a');
+    }
+}
@@ -0,0 +1,227 @@
+//! Diagnostic drawing for pathfinding and distance fields.
+//!
+//! Tuning costs and heuristics by printing coordinates is slow going. These
+//! helpers draw search results straight onto any [`Console`], over the map
+//! that is already there:
+//! - [`draw_path`] marks each step of a path, with arrows showing which way
+//!   it goes
+//! - [`draw_dijkstra_heatmap`] tints cell backgrounds by a value per cell,
+//!   such as a Dijkstra map's distances or an A* search's costs so far
+//!
+//! Both take positions in console coordinates, with y growing downward;
+//! translate world positions by the camera offset first.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_color::Color;
+//! use runeforge_terminal::prelude::*;
+//! use runeforge_terminal::rfc::ConsoleImage;
+//!
+//! let mut console = ConsoleImage::new(8, 4);
+//!
+//! // Distance from the top-left corner
+//! draw_dijkstra_heatmap(&mut console, |p| Some((p.x + p.y) as f32), &HEATMAP);
+//! assert_eq!(console.get(IVec2::ZERO).unwrap().bg, HEATMAP[0]);
+//!
+//! let path = [IVec2::new(0, 0), IVec2::new(1, 0), IVec2::new(2, 1)];
+//! draw_path(&mut console, &path, &PathStyle::arrows());
+//! assert_eq!(console.get(IVec2::new(0, 0)).unwrap().ch, '>');
+//! assert_eq!(console.get(IVec2::new(1, 0)).unwrap().ch, '\\');
+//! assert_eq!(console.get(IVec2::new(2, 1)).unwrap().ch, 'X');
+//! ```
+
+use runeforge_color::Color;
+
+use crate::prelude::*;
+
+/// A blue-to-red gradient for heatmaps: low values are cold.
+pub const HEATMAP: [Color; 4] = [
+    Color::rgb(20, 30, 140),
+    Color::rgb(0, 160, 160),
+    Color::rgb(230, 200, 0),
+    Color::rgb(200, 20, 20),
+];
+
+/// How [`draw_path`] marks a path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathStyle {
+    /// Glyph for every step, or `None` to point an arrow toward the next
+    /// step (`<>^v` and `/\` for diagonals).
+    pub glyph: Option<char>,
+    /// Glyph for the last position, or `None` to treat it like any other.
+    pub goal: Option<char>,
+    /// Foreground color of the marks.
+    pub fg: Color,
+    /// Background color of the marks, or `None` to keep the existing one so
+    /// a heatmap underneath stays visible.
+    pub bg: Option<Color>,
+}
+
+impl Default for PathStyle {
+    /// Yellow `*` steps ending in an `X`, on the existing background.
+    fn default() -> Self {
+        Self {
+            glyph: Some('*'),
+            goal: Some('X'),
+            fg: Color::rgb(255, 220, 0),
+            bg: None,
+        }
+    }
+}
+
+impl PathStyle {
+    /// Creates the default style.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The default style with direction arrows instead of `*`.
+    pub fn arrows() -> Self {
+        Self::default().with_glyph(None)
+    }
+
+    /// Sets the step glyph, or `None` for direction arrows.
+    pub fn with_glyph(mut self, glyph: Option<char>) -> Self {
+        self.glyph = glyph;
+        self
+    }
+
+    /// Sets the glyph for the last position.
+    pub fn with_goal(mut self, goal: Option<char>) -> Self {
+        self.goal = goal;
+        self
+    }
+
+    /// Sets the foreground color.
+    pub fn with_fg(mut self, fg: Color) -> Self {
+        self.fg = fg;
+        self
+    }
+
+    /// Sets the background color, or `None` to keep the existing one.
+    pub fn with_bg(mut self, bg: Option<Color>) -> Self {
+        self.bg = bg;
+        self
+    }
+}
+
+/// Draws `path` over the console. Positions outside the console are
+/// skipped.
+pub fn draw_path<C: Console + ?Sized>(console: &mut C, path: &[IVec2], style: &PathStyle) {
+    for (i, &pos) in path.iter().enumerate() {
+        let Some(existing) = console.get(pos) else {
+            continue;
+        };
+        let next = path.get(i + 1).copied();
+        let ch = match (next, style.goal) {
+            (None, Some(goal)) => goal,
+            _ => style
+                .glyph
+                .unwrap_or_else(|| arrow(next.map_or(IVec2::ZERO, |n| n - pos))),
+        };
+        console.set(
+            pos,
+            Cell::new(ch, style.fg, style.bg.unwrap_or(existing.bg)),
+        );
+    }
+}
+
+/// Tints the background of every cell for which `value` returns a finite
+/// number, mapping the smallest value to the first color of `gradient` and
+/// the largest to the last. Glyphs and foregrounds are kept.
+///
+/// `value` is called twice per cell: once to find the range, once to draw.
+/// An empty gradient draws nothing.
+pub fn draw_dijkstra_heatmap<C: Console + ?Sized>(
+    console: &mut C,
+    value: impl Fn(IVec2) -> Option<f32>,
+    gradient: &[Color],
+) {
+    if gradient.is_empty() {
+        return;
+    }
+    let (width, height) = (console.width() as i32, console.height() as i32);
+    let cells = move || (0..height).flat_map(move |y| (0..width).map(move |x| IVec2::new(x, y)));
+    let sample = |pos| value(pos).filter(|v: &f32| v.is_finite());
+
+    let (min, max) = cells()
+        .filter_map(sample)
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        });
+    if min > max {
+        return;
+    }
+    let range = max - min;
+
+    for pos in cells() {
+        if let Some(v) = sample(pos) {
+            let t = if range > 0.0 { (v - min) / range } else { 0.0 };
+            console.set_bg(pos, gradient_at(gradient, t));
+        }
+    }
+}
+
+/// Samples evenly spaced gradient stops at `t` in `0.0..=1.0`.
+fn gradient_at(gradient: &[Color], t: f32) -> Color {
+    let scaled = t.clamp(0.0, 1.0) * (gradient.len() - 1) as f32;
+    let index = (scaled as usize).min(gradient.len() - 1);
+    match gradient.get(index + 1) {
+        Some(&next) => gradient[index].lerp(next, scaled - index as f32),
+        None => gradient[index],
+    }
+}
+
+fn arrow(step: IVec2) -> char {
+    match (step.x.signum(), step.y.signum()) {
+        (1, 0) => '>',
+        (-1, 0) => '<',
+        (0, -1) => '^',
+        (0, 1) => 'v',
+        (1, 1) | (-1, -1) => '\\',
+        (1, -1) | (-1, 1) => '/',
+        _ => '*',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rfc::ConsoleImage;
+
+    #[test]
+    fn test_path_keeps_background() {
+        let mut console = ConsoleImage::new(4, 4);
+        console.set_bg(IVec2::new(1, 1), Color::rgb(1, 2, 3));
+        let path = [IVec2::new(1, 1), IVec2::new(1, 2), IVec2::new(9, 9)];
+        draw_path(&mut console, &path, &PathStyle::default());
+
+        let cell = console.get(IVec2::new(1, 1)).unwrap();
+        assert_eq!((cell.ch, cell.bg), ('*', Color::rgb(1, 2, 3)));
+        assert_eq!(console.get(IVec2::new(1, 2)).unwrap().ch, '*');
+
+        let style = PathStyle::arrows()
+            .with_goal(None)
+            .with_bg(Some(Color::BLACK));
+        draw_path(&mut console, &path[..2], &style);
+        assert_eq!(console.get(IVec2::new(1, 1)).unwrap().ch, 'v');
+        assert_eq!(console.get(IVec2::new(1, 1)).unwrap().bg, Color::BLACK);
+        assert_eq!(console.get(IVec2::new(1, 2)).unwrap().ch, '*');
+    }
+
+    #[test]
+    fn test_heatmap_normalizes_range() {
+        let mut console = ConsoleImage::new(3, 1);
+        let gradient = [Color::BLACK, Color::WHITE];
+        let values = [Some(10.0), Some(20.0), None];
+        draw_dijkstra_heatmap(&mut console, |p| values[p.x as usize], &gradient);
+
+        assert_eq!(console.get(IVec2::new(0, 0)).unwrap().bg, Color::BLACK);
+        assert_eq!(console.get(IVec2::new(1, 0)).unwrap().bg, Color::WHITE);
+        assert_eq!(console.get(IVec2::new(2, 0)).unwrap(), Cell::empty());
+
+        assert_eq!(gradient_at(&HEATMAP, 0.5), HEATMAP[1].lerp(HEATMAP[2], 0.5));
+        assert_eq!(gradient_at(&HEATMAP, 1.0), HEATMAP[3]);
+    }
+}
@@ -0,0 +1,375 @@
+//! Recorded draw commands that any [`Console`] can replay.
+//!
+//! Game code fills a [`DrawList`] instead of touching a backend directly. The
+//! list owns all of its data, so it can be built on the game thread, sent to a
+//! render thread and applied there, or kept around to record a frame.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_terminal::prelude::*;
+//! use runeforge_color::Color;
+//!
+//! let mut list = DrawList::new();
+//! list.clear();
+//! list.draw_char(IVec2::new(1, 1), '@', Color::YELLOW, Color::BLACK);
+//! list.draw_string(IVec2::new(3, 1), "Hero", Color::WHITE, Color::BLACK);
+//!
+//! let handle = std::thread::spawn(move || list);
+//! let list = handle.join().unwrap();
+//! assert_eq!(list.len(), 3);
+//! ```
+
+use runeforge_color::Color;
+
+use crate::prelude::*;
+
+/// A single recorded drawing operation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawCommand {
+    /// Clears the console to empty cells.
+    Clear,
+    /// Clears the console with a background color.
+    ClearWithColor(Color),
+    /// Writes one cell.
+    Set {
+        /// Target position
+        pos: IVec2,
+        /// Cell to write
+        cell: Cell,
+    },
+    /// Writes a string horizontally.
+    String {
+        /// Position of the first character
+        pos: IVec2,
+        /// Text to write
+        text: String,
+        /// Foreground color
+        fg: Color,
+        /// Background color
+        bg: Color,
+    },
+    /// Fills a rectangle with one cell.
+    Fill {
+        /// Top-left corner
+        pos: IVec2,
+        /// Width in cells
+        width: u32,
+        /// Height in cells
+        height: u32,
+        /// Cell to write
+        cell: Cell,
+    },
+    /// Draws a box outline.
+    Box {
+        /// Top-left corner
+        pos: IVec2,
+        /// Width in cells
+        width: u32,
+        /// Height in cells
+        height: u32,
+        /// Foreground color
+        fg: Color,
+        /// Background color
+        bg: Color,
+        /// Use double-line characters
+        double: bool,
+    },
+    /// Copies a block of cells, row by row.
+    Blit {
+        /// Top-left corner
+        pos: IVec2,
+        /// Width of the block in cells
+        width: u32,
+        /// Cells in row-major order
+        cells: Vec<Cell>,
+        /// Cells with this character are skipped, letting the console show through
+        transparent: Option<char>,
+    },
+}
+
+impl DrawCommand {
+    /// Applies this command to a console.
+    pub fn apply<C: Console + ?Sized>(&self, console: &mut C) {
+        match self {
+            DrawCommand::Clear => console.clear(),
+            DrawCommand::ClearWithColor(bg) => console.clear_with_color(*bg),
+            DrawCommand::Set { pos, cell } => {
+                console.set(*pos, *cell);
+            }
+            DrawCommand::String { pos, text, fg, bg } => {
+                console.draw_string(*pos, text, *fg, *bg);
+            }
+            DrawCommand::Fill {
+                pos,
+                width,
+                height,
+                cell,
+            } => {
+                console.fill_rect(pos.x, pos.y, *width, *height, *cell);
+            }
+            DrawCommand::Box {
+                pos,
+                width,
+                height,
+                fg,
+                bg,
+                double,
+            } => console.draw_box(pos.x, pos.y, *width, *height, *fg, *bg, *double),
+            DrawCommand::Blit {
+                pos,
+                width,
+                cells,
+                transparent,
+            } => {
+                let width = (*width).max(1) as usize;
+                for (i, cell) in cells.iter().enumerate() {
+                    if Some(cell.ch) == *transparent {
+                        continue;
+                    }
+                    let offset = IVec2::new((i % width) as i32, (i / width) as i32);
+                    console.set(*pos + offset, *cell);
+                }
+            }
+        }
+    }
+}
+
+/// An ordered list of draw commands.
+///
+/// Commands are applied in the order they were added, so later writes win.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DrawList {
+    commands: Vec<DrawCommand>,
+}
+
+impl DrawList {
+    /// Creates an empty draw list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty draw list with room for `capacity` commands.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            commands: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Adds a command.
+    pub fn push(&mut self, command: DrawCommand) {
+        self.commands.push(command);
+    }
+
+    /// Records clearing the console to empty cells.
+    pub fn clear(&mut self) {
+        self.push(DrawCommand::Clear);
+    }
+
+    /// Records clearing the console with a background color.
+    pub fn clear_with_color(&mut self, bg: Color) {
+        self.push(DrawCommand::ClearWithColor(bg));
+    }
+
+    /// Records writing one cell.
+    pub fn set(&mut self, pos: IVec2, cell: Cell) {
+        self.push(DrawCommand::Set { pos, cell });
+    }
+
+    /// Records writing a character with colors.
+    pub fn draw_char(&mut self, pos: IVec2, ch: char, fg: Color, bg: Color) {
+        self.set(pos, Cell::new(ch, fg, bg));
+    }
+
+    /// Records writing a string horizontally.
+    pub fn draw_string(&mut self, pos: IVec2, text: impl Into<String>, fg: Color, bg: Color) {
+        self.push(DrawCommand::String {
+            pos,
+            text: text.into(),
+            fg,
+            bg,
+        });
+    }
+
+    /// Records filling a rectangle with one cell.
+    pub fn fill_rect(&mut self, pos: IVec2, width: u32, height: u32, cell: Cell) {
+        self.push(DrawCommand::Fill {
+            pos,
+            width,
+            height,
+            cell,
+        });
+    }
+
+    /// Records drawing a box outline.
+    pub fn draw_box(
+        &mut self,
+        pos: IVec2,
+        width: u32,
+        height: u32,
+        fg: Color,
+        bg: Color,
+        double: bool,
+    ) {
+        self.push(DrawCommand::Box {
+            pos,
+            width,
+            height,
+            fg,
+            bg,
+            double,
+        });
+    }
+
+    /// Records copying a block of cells in row-major order, `width` cells per row.
+    ///
+    /// Cells whose character equals `transparent` are skipped.
+    pub fn blit(&mut self, pos: IVec2, width: u32, cells: Vec<Cell>, transparent: Option<char>) {
+        self.push(DrawCommand::Blit {
+            pos,
+            width,
+            cells,
+            transparent,
+        });
+    }
+
+    /// Appends every command from another list.
+    pub fn append(&mut self, other: &mut DrawList) {
+        self.commands.append(&mut other.commands);
+    }
+
+    /// Returns the recorded commands.
+    pub fn commands(&self) -> &[DrawCommand] {
+        &self.commands
+    }
+
+    /// Returns the number of recorded commands.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Returns true if nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Removes all commands, keeping the allocation for the next frame.
+    pub fn reset(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Applies every command to a console, in order.
+    pub fn apply<C: Console + ?Sized>(&self, console: &mut C) {
+        for command in &self.commands {
+            command.apply(console);
+        }
+    }
+}
+
+impl Extend<DrawCommand> for DrawList {
+    fn extend<I: IntoIterator<Item = DrawCommand>>(&mut self, iter: I) {
+        self.commands.extend(iter);
+    }
+}
+
+impl FromIterator<DrawCommand> for DrawList {
+    fn from_iter<I: IntoIterator<Item = DrawCommand>>(iter: I) -> Self {
+        Self {
+            commands: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct GridConsole {
+        width: u32,
+        height: u32,
+        cells: Vec<Cell>,
+    }
+
+    impl GridConsole {
+        fn new(width: u32, height: u32) -> Self {
+            Self {
+                width,
+                height,
+                cells: vec![Cell::empty(); (width * height) as usize],
+            }
+        }
+
+        fn text(&self) -> String {
+            self.cells
+                .chunks(self.width as usize)
+                .map(|row| row.iter().map(|c| c.ch).collect::<String>() + "\n")
+                .collect()
+        }
+    }
+
+    impl Console for GridConsole {
+        fn width(&self) -> u32 {
+            self.width
+        }
+
+        fn height(&self) -> u32 {
+            self.height
+        }
+
+        fn set(&mut self, pos: IVec2, cell: Cell) -> bool {
+            if !self.in_bounds(pos) {
+                return false;
+            }
+            self.cells[(pos.y as u32 * self.width + pos.x as u32) as usize] = cell;
+            true
+        }
+
+        fn get(&self, pos: IVec2) -> Option<Cell> {
+            self.in_bounds(pos)
+                .then(|| self.cells[(pos.y as u32 * self.width + pos.x as u32) as usize])
+        }
+
+        fn clear(&mut self) {
+            self.cells.fill(Cell::empty());
+        }
+
+        fn present(&mut self) {}
+    }
+
+    #[test]
+    fn test_apply_in_order() {
+        let mut list = DrawList::new();
+        list.fill_rect(IVec2::ZERO, 4, 2, Cell::from_char('.'));
+        list.draw_string(IVec2::new(1, 0), "ab", Color::WHITE, Color::BLACK);
+        list.draw_char(IVec2::new(2, 0), '@', Color::YELLOW, Color::BLACK);
+
+        let mut console = GridConsole::new(4, 2);
+        list.apply(&mut console);
+        assert_eq!(console.text(), ".a@.\n....\n");
+    }
+
+    #[test]
+    fn test_blit_transparency() {
+        let cells = "x#x#".chars().map(Cell::from_char).collect();
+        let mut list = DrawList::new();
+        list.blit(IVec2::new(1, 0), 2, cells, Some('x'));
+
+        let mut console = GridConsole::new(3, 2);
+        list.apply(&mut console);
+        assert_eq!(console.text(), "  #\n  #\n");
+    }
+
+    #[test]
+    fn test_reset_and_append() {
+        let mut a = DrawList::new();
+        a.clear();
+        let mut b: DrawList = [DrawCommand::Clear, DrawCommand::Clear]
+            .into_iter()
+            .collect();
+        a.append(&mut b);
+        assert_eq!(a.len(), 3);
+        assert!(b.is_empty());
+        a.reset();
+        assert!(a.is_empty());
+    }
+}
@@ -0,0 +1,403 @@
+//! Real tiles in a terminal window through terminal graphics protocols.
+//!
+//! Terminals such as kitty, WezTerm and ghostty accept images through the
+//! kitty graphics protocol, and foot, mlterm and others through sixel. A
+//! [`GraphicsOutput`] sends the [`SoftwareRenderer`]'s pixel buffer in
+//! whichever protocol the terminal speaks, and falls back to writing the
+//! cells as ANSI colored characters when it speaks neither.
+//!
+//! Detection reads the environment: `KITTY_WINDOW_ID`, `TERM` and
+//! `TERM_PROGRAM`. Set `RUNEFORGE_GRAPHICS` to `kitty`, `sixel` or `none` to
+//! override it.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use runeforge_terminal::prelude::*;
+//! use runeforge_tileset::prelude::*;
+//!
+//! let font = TrueTypeFont::from_file("font.ttf", 16.0).unwrap();
+//! let mut renderer = SoftwareRenderer::new(80, 24, &font);
+//! let output = GraphicsOutput::detect();
+//!
+//! // ... draw to renderer ...
+//! output.present(&mut renderer, &mut std::io::stdout()).unwrap();
+//! ```
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use runeforge_color::{quantize::Palette, Color};
+
+use crate::prelude::*;
+use crate::terminal::write_ansi_cells;
+
+/// Largest base64 payload per kitty escape sequence, as the protocol asks.
+const KITTY_CHUNK: usize = 4096;
+/// Pixels with less alpha than this are left transparent in sixel output.
+const SIXEL_ALPHA_CUTOFF: u8 = 128;
+
+/// A terminal graphics protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GraphicsProtocol {
+    /// The kitty graphics protocol: full color RGBA, scaled by the terminal
+    /// to the console's cell area.
+    Kitty,
+    /// DEC sixel graphics: up to 256 colors, drawn at the image's pixel size.
+    Sixel,
+}
+
+impl GraphicsProtocol {
+    /// Returns the protocol the current terminal supports, if any.
+    pub fn detect() -> Option<Self> {
+        Self::detect_with(|name| std::env::var(name).ok())
+    }
+
+    /// Returns the protocol suggested by environment variables read through
+    /// `var`.
+    pub fn detect_with(var: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        if let Some(forced) = var("RUNEFORGE_GRAPHICS") {
+            match forced.to_ascii_lowercase().as_str() {
+                "kitty" => return Some(Self::Kitty),
+                "sixel" => return Some(Self::Sixel),
+                "none" | "cells" => return None,
+                _ => {}
+            }
+        }
+
+        let term = var("TERM").unwrap_or_default();
+        let program = var("TERM_PROGRAM").unwrap_or_default();
+        if var("KITTY_WINDOW_ID").is_some()
+            || term.contains("kitty")
+            || matches!(program.as_str(), "WezTerm" | "ghostty")
+        {
+            return Some(Self::Kitty);
+        }
+        if term.contains("sixel")
+            || ["foot", "mlterm", "contour", "yaft"]
+                .iter()
+                .any(|t| term.starts_with(t))
+            || program == "iTerm.app"
+        {
+            return Some(Self::Sixel);
+        }
+        None
+    }
+}
+
+/// Sends rendered frames to a terminal as an image, or as character cells
+/// when no graphics protocol is available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GraphicsOutput {
+    protocol: Option<GraphicsProtocol>,
+    image_id: u32,
+}
+
+impl GraphicsOutput {
+    /// Creates an output using `protocol`, or character cells with `None`.
+    pub fn new(protocol: Option<GraphicsProtocol>) -> Self {
+        Self {
+            protocol,
+            image_id: 1,
+        }
+    }
+
+    /// Creates an output using the protocol the terminal supports.
+    pub fn detect() -> Self {
+        Self::new(GraphicsProtocol::detect())
+    }
+
+    /// Sets the kitty image id, so several outputs can share a terminal.
+    /// Each frame replaces the previous image with the same id.
+    pub fn with_image_id(mut self, id: u32) -> Self {
+        self.image_id = id.max(1);
+        self
+    }
+
+    /// Returns the protocol in use, or `None` for character cells.
+    pub fn protocol(&self) -> Option<GraphicsProtocol> {
+        self.protocol
+    }
+
+    /// Renders `renderer` and writes the frame to `out` at the top-left of
+    /// the screen.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn present<W: Write>(
+        &self,
+        renderer: &mut SoftwareRenderer,
+        out: &mut W,
+    ) -> io::Result<()> {
+        renderer.present();
+        write!(out, "\x1b[H")?;
+        match self.protocol {
+            Some(GraphicsProtocol::Kitty) => out.write_all(
+                encode_kitty(
+                    renderer.pixel_buffer(),
+                    renderer.pixel_width(),
+                    renderer.pixel_height(),
+                    (renderer.width(), renderer.height()),
+                    self.image_id,
+                )
+                .as_bytes(),
+            )?,
+            Some(GraphicsProtocol::Sixel) => out.write_all(
+                encode_sixel(
+                    renderer.pixel_buffer(),
+                    renderer.pixel_width(),
+                    renderer.pixel_height(),
+                    renderer.palette(),
+                )
+                .as_bytes(),
+            )?,
+            None => {
+                let cells: Vec<Cell> = (0..renderer.height() as i32)
+                    .flat_map(|y| (0..renderer.width() as i32).map(move |x| IVec2::new(x, y)))
+                    .map(|pos| renderer.get(pos).unwrap_or_default())
+                    .collect();
+                write_ansi_cells(out, renderer.width(), &cells)?;
+            }
+        }
+        out.flush()
+    }
+}
+
+/// Encodes an RGBA image as kitty graphics escape sequences that place it
+/// over `cells` (columns, rows) at the cursor without moving the cursor.
+///
+/// Sending the same `image_id` again replaces the earlier image.
+pub fn encode_kitty(
+    rgba: &[u8],
+    width: u32,
+    height: u32,
+    cells: (u32, u32),
+    image_id: u32,
+) -> String {
+    let payload = base64(rgba);
+    let chunks: Vec<&str> = if payload.is_empty() {
+        vec![""]
+    } else {
+        payload
+            .as_bytes()
+            .chunks(KITTY_CHUNK)
+            // Base64 is ASCII, so any byte boundary is a char boundary
+            .map(|c| std::str::from_utf8(c).unwrap_or_default())
+            .collect()
+    };
+
+    let mut out = String::with_capacity(payload.len() + chunks.len() * 16 + 64);
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        if i == 0 {
+            let _ = write!(
+                out,
+                "\x1b_Ga=T,f=32,s={width},v={height},c={},r={},i={image_id},p=1,C=1,q=2,m={more};",
+                cells.0, cells.1
+            );
+        } else {
+            let _ = write!(out, "\x1b_Gm={more};");
+        }
+        out.push_str(chunk);
+        out.push_str("\x1b\\");
+    }
+    out
+}
+
+/// Encodes an RGBA image as a sixel sequence.
+///
+/// Colors are mapped to `palette` when given, and to a 6×6×6 color cube
+/// otherwise. Mostly transparent pixels are left undrawn.
+pub fn encode_sixel(rgba: &[u8], width: u32, height: u32, palette: Option<&Palette>) -> String {
+    let (w, h) = (width as usize, height as usize);
+    let index_of = |px: &[u8]| -> Option<usize> {
+        if px[3] < SIXEL_ALPHA_CUTOFF {
+            return None;
+        }
+        match palette {
+            Some(palette) => palette.nearest_index(Color::rgb(px[0], px[1], px[2])),
+            None => {
+                let level = |v: u8| (usize::from(v) * 5 + 127) / 255;
+                Some(level(px[0]) * 36 + level(px[1]) * 6 + level(px[2]))
+            }
+        }
+    };
+    let indices: Vec<Option<usize>> = rgba.chunks_exact(4).take(w * h).map(index_of).collect();
+
+    let color_of = |index: usize| -> (u8, u8, u8) {
+        match palette {
+            Some(palette) => {
+                let c = palette.colors()[index];
+                (c.r, c.g, c.b)
+            }
+            None => {
+                let level = |l: usize| (l * 51) as u8;
+                (level(index / 36), level(index / 6 % 6), level(index % 6))
+            }
+        }
+    };
+
+    // P2 = 1 keeps undrawn pixels transparent
+    let mut out = format!("\x1bP0;1;0q\"1;1;{width};{height}");
+    let mut used: Vec<usize> = indices.iter().flatten().copied().collect();
+    used.sort_unstable();
+    used.dedup();
+    for &index in &used {
+        let (r, g, b) = color_of(index);
+        let percent = |v: u8| (u32::from(v) * 100 + 127) / 255;
+        let _ = write!(
+            out,
+            "#{index};2;{};{};{}",
+            percent(r),
+            percent(g),
+            percent(b)
+        );
+    }
+
+    for band in (0..h).step_by(6) {
+        let rows = (h - band).min(6);
+        let mut colors: Vec<usize> = (band..band + rows)
+            .flat_map(|y| indices[y * w..(y + 1) * w].iter().flatten().copied())
+            .collect();
+        colors.sort_unstable();
+        colors.dedup();
+
+        for (n, &color) in colors.iter().enumerate() {
+            if n > 0 {
+                // Graphics carriage return: overprint the same band
+                out.push('$');
+            }
+            let _ = write!(out, "#{color}");
+            let mut run: Option<(u8, usize)> = None;
+            for x in 0..w {
+                let bits = (0..rows)
+                    .filter(|&dy| indices[(band + dy) * w + x] == Some(color))
+                    .fold(0u8, |bits, dy| bits | 1 << dy);
+                match &mut run {
+                    Some((last, count)) if *last == bits => *count += 1,
+                    _ => {
+                        if let Some(done) = run.replace((bits, 1)) {
+                            push_sixel_run(&mut out, done);
+                        }
+                    }
+                }
+            }
+            if let Some(done) = run {
+                push_sixel_run(&mut out, done);
+            }
+        }
+        if band + 6 < h {
+            out.push('-');
+        }
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+fn push_sixel_run(out: &mut String, (bits, count): (u8, usize)) {
+    let ch = char::from(63 + bits);
+    if count > 3 {
+        let _ = write!(out, "!{count}{ch}");
+    } else {
+        out.extend(std::iter::repeat_n(ch, count));
+    }
+}
+
+/// Standard padded base64.
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(char::from(ALPHABET[(n >> (18 - 6 * i) & 63) as usize]));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn test_detect() {
+        let env = |pairs: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                pairs
+                    .iter()
+                    .find(|(k, _)| *k == name)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+        let detect = |pairs| GraphicsProtocol::detect_with(env(pairs));
+        assert_eq!(
+            detect(&[("TERM", "xterm-kitty")]),
+            Some(GraphicsProtocol::Kitty)
+        );
+        assert_eq!(
+            detect(&[("TERM_PROGRAM", "WezTerm")]),
+            Some(GraphicsProtocol::Kitty)
+        );
+        assert_eq!(detect(&[("TERM", "foot")]), Some(GraphicsProtocol::Sixel));
+        assert_eq!(detect(&[("TERM", "xterm-256color")]), None);
+        assert_eq!(
+            detect(&[("TERM", "xterm-kitty"), ("RUNEFORGE_GRAPHICS", "none")]),
+            None
+        );
+        assert_eq!(
+            detect(&[("TERM", "xterm"), ("RUNEFORGE_GRAPHICS", "Sixel")]),
+            Some(GraphicsProtocol::Sixel)
+        );
+    }
+
+    #[test]
+    fn test_kitty_chunks() {
+        let pixels = vec![7u8; 4 * 2000];
+        let encoded = encode_kitty(&pixels, 40, 50, (5, 3), 9);
+        assert!(encoded.starts_with("\x1b_Ga=T,f=32,s=40,v=50,c=5,r=3,i=9,p=1,C=1,q=2,m=1;"));
+        assert_eq!(encoded.matches("\x1b\\").count(), 3);
+        assert!(encoded.contains("\x1b_Gm=1;"));
+        assert!(encoded.contains("\x1b_Gm=0;"));
+
+        let payload: String = encoded
+            .split("\x1b\\")
+            .filter_map(|seq| seq.split_once(';').map(|(_, data)| data))
+            .collect();
+        assert_eq!(payload, base64(&pixels));
+    }
+
+    #[test]
+    fn test_sixel_bands_and_runs() {
+        // 5x7: red everywhere except a transparent top-left pixel
+        let mut rgba = [255u8, 0, 0, 255].repeat(5 * 7);
+        rgba[3] = 0;
+        let encoded = encode_sixel(&rgba, 5, 7, None);
+        // Red is cube index 5 * 36 = 180
+        assert_eq!(
+            encoded,
+            "\x1bP0;1;0q\"1;1;5;7#180;2;100;0;0#180}!4~-#180!5@\x1b\\"
+        );
+
+        let palette = Palette::new(vec![Color::BLACK, Color::rgb(200, 0, 0)]);
+        let encoded = encode_sixel(&rgba, 5, 7, Some(&palette));
+        assert!(encoded.starts_with("\x1bP0;1;0q\"1;1;5;7#1;2;78;0;0#1}!4~-"));
+    }
+}
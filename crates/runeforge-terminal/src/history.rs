@@ -0,0 +1,236 @@
+//! A ring buffer of recent console frames to step through.
+//!
+//! [`FrameHistory`] keeps the last N snapshots of a console as
+//! [`ConsoleImage`]s. Record one per turn (or per frame) and step backward
+//! and forward through them, to scrub through a debugging session or show
+//! an instant replay of the last few turns. The oldest snapshot is dropped
+//! once the buffer is full.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_color::Color;
+//! use runeforge_terminal::prelude::*;
+//! use runeforge_terminal::rfc::ConsoleImage;
+//!
+//! let mut console = ConsoleImage::new(10, 1);
+//! let mut history = FrameHistory::new(3);
+//! for turn in 0..5 {
+//!     console.draw_string(IVec2::ZERO, &format!("turn {turn}"), Color::WHITE, Color::BLACK);
+//!     history.record(&console);
+//! }
+//! assert_eq!(history.len(), 3);
+//!
+//! // Scrub back, then show the frame
+//! let frame = history.step_back().unwrap();
+//! assert_eq!(frame.get(IVec2::new(5, 0)).unwrap().ch, '3');
+//! frame.blit(&mut console, IVec2::ZERO);
+//!
+//! // Replay everything that's left
+//! let replay: Vec<_> = history.iter().collect();
+//! assert_eq!(replay.len(), 3);
+//! ```
+
+use std::collections::VecDeque;
+
+use crate::prelude::*;
+use crate::rfc::ConsoleImage;
+
+/// The last N console snapshots, with a cursor for stepping through them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameHistory {
+    frames: VecDeque<ConsoleImage>,
+    capacity: usize,
+    /// Index of the frame being viewed, or `None` when following the latest.
+    cursor: Option<usize>,
+}
+
+impl FrameHistory {
+    /// Creates an empty history holding up to `capacity` frames.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            frames: VecDeque::with_capacity(capacity),
+            capacity,
+            cursor: None,
+        }
+    }
+
+    /// Returns how many frames the history can hold.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns how many frames are stored.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns true if no frames are stored.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Captures `console` as the newest frame.
+    ///
+    /// If an older frame is being viewed, the frames after it are discarded
+    /// first, as with undo, and the history follows the latest frame again.
+    pub fn record<C: Console + ?Sized>(&mut self, console: &C) {
+        self.push(ConsoleImage::capture(console));
+    }
+
+    /// Adds `frame` as the newest frame, like [`record`](Self::record).
+    pub fn push(&mut self, frame: ConsoleImage) {
+        if let Some(cursor) = self.cursor.take() {
+            self.frames.truncate(cursor + 1);
+        }
+        if self.frames.len() == self.capacity {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame);
+    }
+
+    /// Returns the frame being viewed: the latest one unless the history has
+    /// been stepped back.
+    pub fn current(&self) -> Option<&ConsoleImage> {
+        self.frames.get(self.position()?)
+    }
+
+    /// Returns the newest frame.
+    pub fn latest(&self) -> Option<&ConsoleImage> {
+        self.frames.back()
+    }
+
+    /// Returns the frame at `index`, 0 being the oldest.
+    pub fn get(&self, index: usize) -> Option<&ConsoleImage> {
+        self.frames.get(index)
+    }
+
+    /// Returns the index of the frame being viewed, or `None` if empty.
+    pub fn position(&self) -> Option<usize> {
+        self.cursor.or(self.frames.len().checked_sub(1))
+    }
+
+    /// Returns true when following the latest frame rather than viewing an
+    /// older one.
+    pub fn is_live(&self) -> bool {
+        self.cursor.is_none()
+    }
+
+    /// Moves to the previous frame and returns it, or returns `None` if
+    /// already at the oldest frame.
+    pub fn step_back(&mut self) -> Option<&ConsoleImage> {
+        let index = self.position()?.checked_sub(1)?;
+        self.cursor = Some(index);
+        self.frames.get(index)
+    }
+
+    /// Moves to the next frame and returns it, or returns `None` if already
+    /// at the latest frame. Reaching the latest frame resumes following it.
+    pub fn step_forward(&mut self) -> Option<&ConsoleImage> {
+        let index = self.cursor? + 1;
+        self.cursor = (index + 1 < self.frames.len()).then_some(index);
+        self.frames.get(index)
+    }
+
+    /// Moves to the frame at `index`, 0 being the oldest. Returns `None`
+    /// and stays put if there is no such frame.
+    pub fn seek(&mut self, index: usize) -> Option<&ConsoleImage> {
+        if index >= self.frames.len() {
+            return None;
+        }
+        self.cursor = (index + 1 < self.frames.len()).then_some(index);
+        self.frames.get(index)
+    }
+
+    /// Stops viewing an older frame and follows the latest again.
+    pub fn resume(&mut self) {
+        self.cursor = None;
+    }
+
+    /// Iterates over the frames, oldest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &ConsoleImage> + '_ {
+        self.frames.iter()
+    }
+
+    /// Iterates over the last `count` frames, oldest first, e.g. for an
+    /// instant replay of the last few turns.
+    pub fn last(&self, count: usize) -> impl Iterator<Item = &ConsoleImage> + '_ {
+        self.frames
+            .iter()
+            .skip(self.frames.len().saturating_sub(count))
+    }
+
+    /// Removes every frame.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.cursor = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runeforge_color::Color;
+
+    fn frame(ch: char) -> ConsoleImage {
+        let mut image = ConsoleImage::new(1, 1);
+        image.set(IVec2::ZERO, Cell::new(ch, Color::WHITE, Color::BLACK));
+        image
+    }
+
+    fn ch(frame: Option<&ConsoleImage>) -> Option<char> {
+        frame.map(|f| f.cells()[0].ch)
+    }
+
+    #[test]
+    fn test_ring_drops_oldest() {
+        let mut history = FrameHistory::new(2);
+        assert_eq!(history.current(), None);
+        for c in ['a', 'b', 'c'] {
+            history.push(frame(c));
+        }
+        assert_eq!(history.len(), 2);
+        assert_eq!(ch(history.get(0)), Some('b'));
+        assert_eq!(ch(history.current()), Some('c'));
+        let last: Vec<char> = history.last(5).map(|f| f.cells()[0].ch).collect();
+        assert_eq!(last, ['b', 'c']);
+    }
+
+    #[test]
+    fn test_step_back_and_forward() {
+        let mut history = FrameHistory::new(5);
+        for c in ['a', 'b', 'c'] {
+            history.push(frame(c));
+        }
+        assert_eq!(ch(history.step_back()), Some('b'));
+        assert_eq!(ch(history.step_back()), Some('a'));
+        assert_eq!(ch(history.step_back()), None);
+        assert_eq!(history.position(), Some(0));
+        assert!(!history.is_live());
+
+        assert_eq!(ch(history.step_forward()), Some('b'));
+        assert_eq!(ch(history.step_forward()), Some('c'));
+        assert!(history.is_live());
+        assert_eq!(ch(history.step_forward()), None);
+
+        assert_eq!(ch(history.seek(0)), Some('a'));
+        assert!(history.seek(3).is_none());
+        history.resume();
+        assert_eq!(ch(history.current()), Some('c'));
+    }
+
+    #[test]
+    fn test_record_while_scrubbing_discards_newer_frames() {
+        let mut history = FrameHistory::new(5);
+        for c in ['a', 'b', 'c'] {
+            history.push(frame(c));
+        }
+        history.step_back();
+        history.step_back();
+        history.record(&frame('d'));
+        assert!(history.is_live());
+        let all: Vec<char> = history.iter().map(|f| f.cells()[0].ch).collect();
+        assert_eq!(all, ['a', 'd']);
+    }
+}
@@ -0,0 +1,387 @@
+//! Stacked cell layers that composite into a single console.
+//!
+//! A [`LayeredConsole`] keeps several z-ordered layers per position, e.g.
+//! terrain, items, actors and effects. Each layer can be drawn and cleared on
+//! its own, so erasing a spell effect is a [`clear_layer`] instead of a
+//! redraw of the whole map. [`render`] flattens the stack into any
+//! [`Console`].
+//!
+//! Layers are transparent where nothing was drawn. A drawn cell covers the
+//! layers below it by its colors' alpha: its background is blended over the
+//! background below, and a non-space glyph replaces the glyph below, its
+//! foreground blended over the new background. A space keeps the glyph
+//! below, so a translucent red space tints a tile without hiding what's on
+//! it.
+//!
+//! [`clear_layer`]: LayeredConsole::clear_layer
+//! [`render`]: LayeredConsole::render
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_terminal::prelude::*;
+//! use runeforge_color::Color;
+//!
+//! let mut layers = LayeredConsole::new(20, 10, 4);
+//! let floor = Cell::new('.', Color::GRAY, Color::BLACK);
+//! layers.fill_layer(LayeredConsole::TERRAIN, floor);
+//! layers.set(LayeredConsole::ACTORS, IVec2::new(3, 3), Cell::new('@', Color::YELLOW, Color::BLACK));
+//!
+//! // A fireball flashes over the player, then goes away
+//! let fire = Cell::new('*', Color::RED, Color::rgba(255, 0, 0, 128));
+//! layers.set(LayeredConsole::EFFECTS, IVec2::new(3, 3), fire);
+//! assert_eq!(layers.composite(IVec2::new(3, 3)).unwrap().ch, '*');
+//! layers.clear_layer(LayeredConsole::EFFECTS);
+//! assert_eq!(layers.composite(IVec2::new(3, 3)).unwrap().ch, '@');
+//!
+//! // Draw helpers work on a single layer too
+//! layers.layer_mut(LayeredConsole::EFFECTS).unwrap().draw_string(
+//!     IVec2::new(0, 0),
+//!     "Boom",
+//!     Color::WHITE,
+//!     Color::BLACK,
+//! );
+//! ```
+
+use runeforge_color::Color;
+
+use crate::prelude::*;
+
+/// A stack of transparent cell layers, composited bottom to top.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayeredConsole {
+    width: u32,
+    height: u32,
+    /// Drawn cells per layer, bottom layer first.
+    layers: Vec<Vec<Option<Cell>>>,
+    /// What shows where no layer has drawn anything.
+    base: Cell,
+}
+
+impl LayeredConsole {
+    /// Conventional index of the map terrain layer.
+    pub const TERRAIN: usize = 0;
+    /// Conventional index of the item layer.
+    pub const ITEMS: usize = 1;
+    /// Conventional index of the actor layer.
+    pub const ACTORS: usize = 2;
+    /// Conventional index of the effect layer.
+    pub const EFFECTS: usize = 3;
+
+    /// Creates `layers` empty layers of `width` x `height` cells.
+    pub fn new(width: u32, height: u32, layers: usize) -> Self {
+        Self {
+            width,
+            height,
+            layers: vec![vec![None; (width * height) as usize]; layers],
+            base: Cell::empty(),
+        }
+    }
+
+    /// Sets the cell shown where no layer has drawn anything.
+    pub fn with_base(mut self, base: Cell) -> Self {
+        self.base = base;
+        self
+    }
+
+    /// Returns the width in cells.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height in cells.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the number of layers.
+    pub fn layer_count(&self) -> usize {
+        self.layers.len()
+    }
+
+    fn index(&self, pos: IVec2) -> Option<usize> {
+        if pos.x < 0 || pos.y < 0 || pos.x >= self.width as i32 || pos.y >= self.height as i32 {
+            return None;
+        }
+        Some((pos.y as u32 * self.width + pos.x as u32) as usize)
+    }
+
+    /// Draws a cell on one layer.
+    ///
+    /// Returns `false` if the layer or position doesn't exist.
+    pub fn set(&mut self, layer: usize, pos: IVec2, cell: Cell) -> bool {
+        self.put(layer, pos, Some(cell))
+    }
+
+    /// Makes one layer transparent again at `pos`.
+    ///
+    /// Returns `false` if the layer or position doesn't exist.
+    pub fn erase(&mut self, layer: usize, pos: IVec2) -> bool {
+        self.put(layer, pos, None)
+    }
+
+    fn put(&mut self, layer: usize, pos: IVec2, cell: Option<Cell>) -> bool {
+        let Some(idx) = self.index(pos) else {
+            return false;
+        };
+        match self.layers.get_mut(layer) {
+            Some(cells) => {
+                cells[idx] = cell;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the cell drawn on one layer, or `None` where the layer is
+    /// transparent or out of range.
+    pub fn get(&self, layer: usize, pos: IVec2) -> Option<Cell> {
+        let idx = self.index(pos)?;
+        self.layers.get(layer)?[idx]
+    }
+
+    /// Makes a whole layer transparent.
+    pub fn clear_layer(&mut self, layer: usize) {
+        if let Some(cells) = self.layers.get_mut(layer) {
+            cells.fill(None);
+        }
+    }
+
+    /// Draws `cell` on every position of a layer.
+    pub fn fill_layer(&mut self, layer: usize, cell: Cell) {
+        if let Some(cells) = self.layers.get_mut(layer) {
+            cells.fill(Some(cell));
+        }
+    }
+
+    /// Makes every layer transparent.
+    pub fn clear(&mut self) {
+        for cells in &mut self.layers {
+            cells.fill(None);
+        }
+    }
+
+    /// Returns a [`Console`] view of one layer, so the usual draw helpers
+    /// can target it. Its `clear` clears only that layer.
+    pub fn layer_mut(&mut self, layer: usize) -> Option<LayerMut<'_>> {
+        let (width, height) = (self.width, self.height);
+        self.layers.get_mut(layer).map(|cells| LayerMut {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Runs every drawn cell of one layer through a [`TileShader`].
+    /// `origin` is the world position of the top-left cell, usually the
+    /// camera offset. Call it after drawing the layer each frame.
+    pub fn shade_layer(
+        &mut self,
+        layer: usize,
+        origin: IVec2,
+        shader: &(impl TileShader + ?Sized),
+    ) {
+        let width = self.width as usize;
+        let Some(cells) = self.layers.get_mut(layer) else {
+            return;
+        };
+        for (idx, slot) in cells.iter_mut().enumerate() {
+            if let Some(cell) = slot {
+                let pos = IVec2::new((idx % width) as i32, (idx / width) as i32);
+                *cell = shader.shade(origin + pos, *cell);
+            }
+        }
+    }
+
+    /// Returns the final cell at `pos` after compositing every layer, or
+    /// `None` out of bounds.
+    pub fn composite(&self, pos: IVec2) -> Option<Cell> {
+        let idx = self.index(pos)?;
+        Some(
+            self.layers
+                .iter()
+                .filter_map(|cells| cells[idx])
+                .fold(self.base, composite_over),
+        )
+    }
+
+    /// Writes the composited cells into `console`, clipped to its size.
+    pub fn render<C: Console + ?Sized>(&self, console: &mut C) {
+        let width = self.width.min(console.width());
+        let height = self.height.min(console.height());
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                let pos = IVec2::new(x, y);
+                if let Some(cell) = self.composite(pos) {
+                    console.set(pos, cell);
+                }
+            }
+        }
+    }
+
+    /// Writes the composited cells into `console` shifted by `offset`, e.g.
+    /// [`ScreenEffects::offset`]. Positions uncovered by the shift show the
+    /// base cell; the layers themselves are not moved.
+    pub fn render_offset<C: Console + ?Sized>(&self, console: &mut C, offset: IVec2) {
+        for y in 0..console.height() as i32 {
+            for x in 0..console.width() as i32 {
+                let pos = IVec2::new(x, y);
+                let cell = self.composite(pos - offset).unwrap_or(self.base);
+                console.set(pos, cell);
+            }
+        }
+    }
+}
+
+/// Draws `above` over `below` using the alpha of `above`'s colors.
+fn composite_over(below: Cell, above: Cell) -> Cell {
+    let bg = blend(below.bg, above.bg);
+    if above.ch == ' ' {
+        return Cell { bg, ..below };
+    }
+    Cell {
+        ch: above.ch,
+        fg: blend(bg, above.fg),
+        bg,
+    }
+}
+
+/// Blends `above` over `below` by `above`'s alpha. The result is opaque.
+fn blend(below: Color, above: Color) -> Color {
+    let color = match above.a {
+        255 => above,
+        0 => below,
+        a => below.lerp(above, a as f32 / 255.0),
+    };
+    Color { a: 255, ..color }
+}
+
+/// A mutable [`Console`] view of one layer of a [`LayeredConsole`].
+///
+/// Reading a transparent position gives [`Cell::empty`]; drawing makes it
+/// opaque.
+#[derive(Debug)]
+pub struct LayerMut<'a> {
+    width: u32,
+    height: u32,
+    cells: &'a mut [Option<Cell>],
+}
+
+impl LayerMut<'_> {
+    /// Makes the layer transparent again at `pos`.
+    pub fn erase(&mut self, pos: IVec2) -> bool {
+        if !self.in_bounds(pos) {
+            return false;
+        }
+        self.cells[(pos.y as u32 * self.width + pos.x as u32) as usize] = None;
+        true
+    }
+}
+
+impl Console for LayerMut<'_> {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn set(&mut self, pos: IVec2, cell: Cell) -> bool {
+        if !self.in_bounds(pos) {
+            return false;
+        }
+        self.cells[(pos.y as u32 * self.width + pos.x as u32) as usize] = Some(cell);
+        true
+    }
+
+    fn get(&self, pos: IVec2) -> Option<Cell> {
+        if !self.in_bounds(pos) {
+            return None;
+        }
+        Some(self.cells[(pos.y as u32 * self.width + pos.x as u32) as usize].unwrap_or_default())
+    }
+
+    fn clear(&mut self) {
+        self.cells.fill(None);
+    }
+
+    fn present(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layers_stack_and_clear() {
+        let mut layers = LayeredConsole::new(4, 4, 3);
+        let pos = IVec2::new(1, 2);
+        assert_eq!(layers.composite(pos), Some(Cell::empty()));
+
+        let floor = Cell::new('.', Color::GRAY, Color::BLUE);
+        layers.fill_layer(0, floor);
+        layers.set(1, pos, Cell::new('@', Color::YELLOW, Color::BLACK));
+        assert_eq!(layers.composite(pos).unwrap().ch, '@');
+        assert_eq!(layers.composite(IVec2::new(0, 0)), Some(floor));
+
+        layers.clear_layer(1);
+        assert_eq!(layers.composite(pos), Some(floor));
+        assert!(!layers.set(5, pos, floor));
+        assert!(!layers.set(0, IVec2::new(4, 0), floor));
+        assert_eq!(layers.composite(IVec2::new(-1, 0)), None);
+    }
+
+    #[test]
+    fn test_transparent_tint_keeps_glyph() {
+        let mut layers = LayeredConsole::new(2, 1, 2);
+        let pos = IVec2::new(0, 0);
+        layers.set(0, pos, Cell::new('g', Color::GREEN, Color::BLACK));
+        let tint = Cell::new(' ', Color::WHITE, Color::rgba(255, 0, 0, 255));
+        layers.set(1, pos, tint);
+        let cell = layers.composite(pos).unwrap();
+        assert_eq!((cell.ch, cell.fg, cell.bg), ('g', Color::GREEN, Color::RED));
+
+        let half = Cell::new('*', Color::rgba(255, 255, 255, 0), Color::rgba(0, 0, 0, 0));
+        layers.set(1, pos, half);
+        let cell = layers.composite(pos).unwrap();
+        assert_eq!(
+            (cell.ch, cell.fg, cell.bg),
+            ('*', Color::BLACK, Color::BLACK)
+        );
+    }
+
+    #[test]
+    fn test_layer_view_and_render() {
+        let mut layers = LayeredConsole::new(6, 2, 2);
+        let mut top = layers.layer_mut(1).unwrap();
+        top.draw_string(IVec2::new(1, 0), "hi", Color::WHITE, Color::BLACK);
+        assert!(top.erase(IVec2::new(2, 0)));
+
+        let mut target = LayeredConsole::new(3, 3, 1);
+        layers.render(&mut target.layer_mut(0).unwrap());
+        assert_eq!(target.get(0, IVec2::new(1, 0)).unwrap().ch, 'h');
+        assert_eq!(target.get(0, IVec2::new(2, 0)), Some(Cell::empty()));
+        assert_eq!(target.get(0, IVec2::new(2, 2)), None);
+
+        layers.layer_mut(1).unwrap().clear();
+        assert_eq!(layers.get(1, IVec2::new(1, 0)), None);
+    }
+
+    #[test]
+    fn test_render_offset() {
+        let base = Cell::new('~', Color::BLUE, Color::BLACK);
+        let mut layers = LayeredConsole::new(3, 1, 1).with_base(base);
+        layers.set(
+            0,
+            IVec2::new(0, 0),
+            Cell::new('@', Color::WHITE, Color::BLACK),
+        );
+
+        let mut target = LayeredConsole::new(3, 1, 1);
+        layers.render_offset(&mut target.layer_mut(0).unwrap(), IVec2::new(1, 0));
+        assert_eq!(target.get(0, IVec2::new(0, 0)), Some(base));
+        assert_eq!(target.get(0, IVec2::new(1, 0)).unwrap().ch, '@');
+        assert_eq!(layers.get(0, IVec2::new(0, 0)).unwrap().ch, '@');
+    }
+}
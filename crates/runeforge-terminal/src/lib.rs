@@ -14,6 +14,39 @@
 //!
 //! *   **`Terminal`**: The main rendering context. Handles grid storage and output.
 //! *   **`Cell`**: A single character with foreground and background colors.
+//! *   **`Cursor`**: A text caret that terminals show natively and pixel renderers
+//!     draw as a blinking inverted cell.
+//! *   **`DrawList`**: Recorded draw commands that any `Console` can replay, so frames
+//!     can be built on one thread and rendered on another.
+//! *   **`GraphicsOutput`**: Sends the software renderer's pixels to terminals that speak the
+//!     kitty graphics or sixel protocol, for real tiles in a terminal window, and falls back
+//!     to colored character cells elsewhere.
+//! *   **`LayeredConsole`**: Z-ordered, transparent cell layers (terrain, items, actors,
+//!     effects) that are drawn and cleared separately and composited into any `Console`.
+//! *   **`ScreenEffects`**: Camera shake with decay and knockback nudges, applied as a
+//!     cell offset when composing the screen without moving the game's buffers.
+//! *   **`ConsoleImage`**: An off-screen console that saves to and loads from the compact
+//!     `.rfc` file format, for title screens and prebaked UI (see [`rfc`]).
+//! *   **`Transition`**: Fades through black, directional wipes and dissolves between two
+//!     console snapshots over a number of frames, for level changes and menus.
+//! *   **`FrameHistory`**: The last N console snapshots, stepped backward and forward for
+//!     debug scrubbing or an instant replay of the last few turns.
+//! *   **`Morgue`**: A post-game dump of the character, final map, last messages and
+//!     statistics, written as text or colored HTML (see [`morgue`]).
+//! *   **`LightLayer`**: Per-cell light that renderers multiply over cell colors,
+//!     so lighting never has to rewrite the map.
+//! *   **`TileShader`**: Per-tile color hooks keyed by world position; [`NoiseTexture`](shader::NoiseTexture)
+//!     speckles and mottles floors so large areas don't look flat.
+//! *   **`AmbientLight`**: A day/night curve over game turns that fills a `LightLayer`
+//!     with dawn, day, dusk and night tints.
+//! *   **`Weather`**: Rain, snow and fog per climate, advancing with the turns, that shorten
+//!     sight, tint the ambient light and draw falling particles.
+//! *   **`draw_path` / `draw_dijkstra_heatmap`**: Debug views of paths, search expansions
+//!     and distance fields over any `Console` (see [`debug`]).
+//! *   **`DebugOverlay`**: Named layers of short tile labels (path costs, AI targets, room
+//!     ids) that any system can tag, drawn over any `Console` behind one toggle (see [`overlay`]).
+//! *   **`draw_targeting`**: An aiming overlay showing a spell's path, affected tiles
+//!     and whether the cursor's target can be reached (see [`targeting`]).
 //!
 //! # Usage
 //!
@@ -49,16 +82,46 @@
 //! }
 //! ```
 
+pub mod ambient;
 pub mod cell;
 pub mod console;
+pub mod cursor;
+pub mod debug;
+pub mod draw_list;
+pub mod graphics;
+pub mod history;
+pub mod layers;
+pub mod light;
+pub mod morgue;
+pub mod overlay;
 pub mod renderer;
+pub mod rfc;
+pub mod screen;
+pub mod shader;
+pub mod targeting;
 pub mod terminal;
+pub mod transition;
+pub mod weather;
 
 pub mod prelude {
     pub use runeforge_geometry::prelude::IVec2;
 
+    pub use crate::ambient::*;
     pub use crate::cell::*;
     pub use crate::console::*;
+    pub use crate::cursor::*;
+    pub use crate::debug::*;
+    pub use crate::draw_list::*;
+    pub use crate::graphics::*;
+    pub use crate::history::*;
+    pub use crate::layers::*;
+    pub use crate::light::*;
+    pub use crate::overlay::*;
     pub use crate::renderer::*;
+    pub use crate::screen::*;
+    pub use crate::shader::*;
+    pub use crate::targeting::*;
     pub use crate::terminal::*;
+    pub use crate::transition::*;
+    pub use crate::weather::*;
 }
@@ -0,0 +1,187 @@
+//! A per-cell light and tint layer applied by renderers at draw time.
+//!
+//! Lighting systems write into a [`LightLayer`] each frame instead of
+//! rewriting cell colors, so the map in the console can stay untouched.
+//! Renderers multiply the layer over each cell's colors when drawing.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_terminal::prelude::*;
+//! use runeforge_color::Color;
+//!
+//! let mut light = LightLayer::new(10, 10);
+//! light.fill(Color::grayscale(64)); // dark everywhere
+//! light.set(IVec2::new(2, 2), Color::rgb(255, 200, 120)); // torch
+//!
+//! let wall = Cell::new('#', Color::WHITE, Color::grayscale(100));
+//! let lit = light.tint(IVec2::new(2, 2), wall);
+//! assert_eq!(lit.fg, Color::rgb(255, 200, 120));
+//! assert_eq!(light.tint(IVec2::new(5, 5), wall).fg, Color::grayscale(64));
+//! ```
+
+use glam::Vec2;
+use runeforge_color::Color;
+
+use crate::prelude::*;
+
+/// How a renderer applies a [`LightLayer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LightBlend {
+    /// Each cell's colors are multiplied by its own light value.
+    #[default]
+    Flat,
+    /// Light is interpolated between cell centers per pixel, giving smooth
+    /// gradients across cell boundaries.
+    Smooth,
+}
+
+/// A grid of light colors, one per console cell.
+///
+/// White leaves a cell unchanged; darker colors dim and tint it. Positions
+/// outside the grid are treated as white.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightLayer {
+    width: u32,
+    height: u32,
+    light: Vec<Color>,
+    blend: LightBlend,
+}
+
+impl LightLayer {
+    /// Creates a layer of the given size, fully lit.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            light: vec![Color::WHITE; (width * height) as usize],
+            blend: LightBlend::default(),
+        }
+    }
+
+    /// Sets how renderers blend the layer.
+    pub fn with_blend(mut self, blend: LightBlend) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    /// Returns how renderers blend the layer.
+    pub fn blend(&self) -> LightBlend {
+        self.blend
+    }
+
+    /// Returns the layer width in cells.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the layer height in cells.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the light at a cell (white outside the grid).
+    pub fn get(&self, pos: IVec2) -> Color {
+        self.index(pos).map_or(Color::WHITE, |i| self.light[i])
+    }
+
+    /// Sets the light at a cell. Positions outside the grid are ignored.
+    pub fn set(&mut self, pos: IVec2, color: Color) {
+        if let Some(i) = self.index(pos) {
+            self.light[i] = color;
+        }
+    }
+
+    /// Sets every cell to the same light, e.g. the ambient level.
+    pub fn fill(&mut self, color: Color) {
+        self.light.fill(color);
+    }
+
+    /// Resets every cell to white (no effect).
+    pub fn reset(&mut self) {
+        self.fill(Color::WHITE);
+    }
+
+    /// Returns `cell` with its colors multiplied by the light at `pos`.
+    ///
+    /// The cell's alpha is kept.
+    pub fn tint(&self, pos: IVec2, cell: Cell) -> Cell {
+        let light = self.get(pos);
+        let light = Color::rgb(light.r, light.g, light.b);
+        cell.with_fg(cell.fg.multiply(light))
+            .with_bg(cell.bg.multiply(light))
+    }
+
+    /// Samples the light at a point in cell units, interpolating bilinearly
+    /// between cell centers. `(0.5, 0.5)` is the center of cell `(0, 0)`.
+    pub fn sample(&self, point: Vec2) -> Color {
+        let p = point - Vec2::splat(0.5);
+        let base = p.floor();
+        let t = p - base;
+        let cell = base.as_ivec2();
+        let clamp = |c: IVec2| {
+            c.clamp(
+                IVec2::ZERO,
+                IVec2::new(self.width as i32 - 1, self.height as i32 - 1).max(IVec2::ZERO),
+            )
+        };
+        let at = |dx, dy| self.get(clamp(cell + IVec2::new(dx, dy)));
+        let top = at(0, 0).lerp(at(1, 0), t.x);
+        let bottom = at(0, 1).lerp(at(1, 1), t.x);
+        top.lerp(bottom, t.y)
+    }
+
+    /// Multiplies an RGBA pixel buffer by the smoothly interpolated light.
+    ///
+    /// `cell_size` is the size of one cell in pixels. Alpha is left unchanged.
+    pub fn apply_smooth(&self, buffer: &mut [u8], pixel_width: u32, cell_size: (u32, u32)) {
+        let (cw, ch) = (cell_size.0.max(1) as f32, cell_size.1.max(1) as f32);
+        let width = pixel_width.max(1) as usize;
+        for (i, px) in buffer.chunks_exact_mut(4).enumerate() {
+            let (x, y) = (i % width, i / width);
+            let point = Vec2::new((x as f32 + 0.5) / cw, (y as f32 + 0.5) / ch);
+            let light = self.sample(point);
+            for (c, l) in px[..3].iter_mut().zip([light.r, light.g, light.b]) {
+                *c = ((*c as u16 * l as u16) / 255) as u8;
+            }
+        }
+    }
+
+    fn index(&self, pos: IVec2) -> Option<usize> {
+        if pos.x < 0 || pos.y < 0 || pos.x >= self.width as i32 || pos.y >= self.height as i32 {
+            return None;
+        }
+        Some((pos.y as u32 * self.width + pos.x as u32) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_unlit_passthrough() {
+        let layer = LightLayer::new(4, 4);
+        let cell = Cell::new('@', Color::rgb(10, 20, 30), Color::rgb(40, 50, 60));
+        assert_eq!(layer.tint(IVec2::new(1, 1), cell), cell);
+        assert_eq!(layer.get(IVec2::new(-1, 9)), Color::WHITE);
+    }
+
+    #[test]
+    fn test_sample_interpolates_between_centers() {
+        let mut layer = LightLayer::new(2, 1);
+        layer.set(IVec2::new(0, 0), Color::BLACK);
+        assert_eq!(layer.sample(Vec2::new(0.5, 0.5)), Color::BLACK);
+        assert_eq!(layer.sample(Vec2::new(1.5, 0.5)), Color::WHITE);
+        assert_eq!(layer.sample(Vec2::new(1.0, 0.5)).r, 127);
+    }
+
+    #[test]
+    fn test_apply_smooth_keeps_alpha() {
+        let mut layer = LightLayer::new(1, 1);
+        layer.fill(Color::grayscale(128));
+        let mut buffer = vec![255u8; 2 * 2 * 4];
+        layer.apply_smooth(&mut buffer, 2, (2, 2));
+        assert_eq!(&buffer[0..4], &[128, 128, 128, 255]);
+    }
+}
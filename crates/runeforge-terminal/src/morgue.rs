@@ -0,0 +1,341 @@
+//! Post-game "morgue" dumps of a finished character.
+//!
+//! When a character dies or wins, classic roguelikes write a file with
+//! everything worth remembering about the run. A [`Morgue`] collects the
+//! pieces from whichever systems the game has: character fields, a snapshot
+//! of the map console (taken with [`ConsoleImage::capture`]), the tail of
+//! the message log, statistics and any extra sections such as the
+//! inventory. It renders them as plain text, or as HTML that keeps the map's
+//! colors.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_color::Color;
+//! use runeforge_terminal::morgue::Morgue;
+//! use runeforge_terminal::prelude::*;
+//! use runeforge_terminal::rfc::ConsoleImage;
+//!
+//! let mut map = ConsoleImage::new(5, 1);
+//! map.draw_string(IVec2::ZERO, "#.@.#", Color::WHITE, Color::BLACK);
+//!
+//! let messages = ["You enter the crypt.", "The lich casts a spell.", "You die..."];
+//! let morgue = Morgue::new("Ayla the Fighter")
+//!     .with_field("Level", 7)
+//!     .with_field("Killed by", "a lich")
+//!     .with_map(&map)
+//!     .with_messages(messages, 2)
+//!     .with_stat("kills", 41);
+//!
+//! let text = morgue.to_text();
+//! assert!(text.contains("Killed by: a lich"));
+//! assert!(text.contains("#.@.#"));
+//! assert!(!text.contains("You enter the crypt."));
+//! assert!(morgue.to_html().contains("<pre"));
+//! ```
+
+use std::fmt::{Display, Write as _};
+use std::{fs, io, path::Path};
+
+use runeforge_color::Color;
+
+use crate::prelude::*;
+use crate::rfc::ConsoleImage;
+
+/// A post-game dump built from the game's systems.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Morgue {
+    title: String,
+    fields: Vec<(String, String)>,
+    map: Option<ConsoleImage>,
+    messages: Vec<String>,
+    stats: Vec<(String, String)>,
+    sections: Vec<(String, Vec<String>)>,
+}
+
+impl Morgue {
+    /// Creates an empty dump headed by `title`, usually the character's
+    /// name.
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            fields: Vec::new(),
+            map: None,
+            messages: Vec::new(),
+            stats: Vec::new(),
+            sections: Vec::new(),
+        }
+    }
+
+    /// Adds a character field such as the level or the cause of death.
+    pub fn with_field(mut self, label: impl Into<String>, value: impl Display) -> Self {
+        self.fields.push((label.into(), value.to_string()));
+        self
+    }
+
+    /// Snapshots the cells of `console` as the final map.
+    pub fn with_map<C: Console + ?Sized>(mut self, console: &C) -> Self {
+        self.map = Some(ConsoleImage::capture(console));
+        self
+    }
+
+    /// Keeps the last `tail` of `messages`, oldest first.
+    pub fn with_messages<S: Into<String>>(
+        mut self,
+        messages: impl IntoIterator<Item = S>,
+        tail: usize,
+    ) -> Self {
+        let all: Vec<String> = messages.into_iter().map(Into::into).collect();
+        let skip = all.len().saturating_sub(tail);
+        self.messages = all.into_iter().skip(skip).collect();
+        self
+    }
+
+    /// Adds one statistic.
+    pub fn with_stat(mut self, label: impl Into<String>, value: impl Display) -> Self {
+        self.stats.push((label.into(), value.to_string()));
+        self
+    }
+
+    /// Adds several statistics, e.g. from a counter map.
+    pub fn with_stats<K: Display, V: Display>(
+        mut self,
+        stats: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        self.stats.extend(
+            stats
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string())),
+        );
+        self
+    }
+
+    /// Adds a titled section of free-form lines, such as the inventory.
+    pub fn with_section<S: Into<String>>(
+        mut self,
+        title: impl Into<String>,
+        lines: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.sections
+            .push((title.into(), lines.into_iter().map(Into::into).collect()));
+        self
+    }
+
+    /// Returns the map snapshot, if one was added.
+    pub fn map(&self) -> Option<&ConsoleImage> {
+        self.map.as_ref()
+    }
+
+    /// Renders the dump as plain text.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "{}", self.title);
+        let _ = writeln!(out, "{}", "=".repeat(self.title.chars().count()));
+        if !self.fields.is_empty() {
+            out.push('\n');
+            push_pairs(&mut out, &self.fields);
+        }
+        if let Some(map) = &self.map {
+            push_heading(&mut out, "Map");
+            for row in map.cells().chunks(map.width().max(1) as usize) {
+                let line: String = row.iter().map(|c| printable(c.ch)).collect();
+                let _ = writeln!(out, "{}", line.trim_end());
+            }
+        }
+        if !self.messages.is_empty() {
+            push_heading(&mut out, "Last messages");
+            for message in &self.messages {
+                let _ = writeln!(out, "{message}");
+            }
+        }
+        if !self.stats.is_empty() {
+            push_heading(&mut out, "Statistics");
+            push_pairs(&mut out, &self.stats);
+        }
+        for (title, lines) in &self.sections {
+            push_heading(&mut out, title);
+            for line in lines {
+                let _ = writeln!(out, "{line}");
+            }
+        }
+        out
+    }
+
+    /// Renders the dump as a standalone HTML page, with the map in its
+    /// original colors.
+    pub fn to_html(&self) -> String {
+        let mut out = String::new();
+        let title = escape(&self.title);
+        let _ = write!(
+            out,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{title}</title>\n</head>\n<body>\n<h1>{title}</h1>\n"
+        );
+        if !self.fields.is_empty() {
+            push_html_pairs(&mut out, &self.fields);
+        }
+        if let Some(map) = &self.map {
+            out.push_str("<h2>Map</h2>\n<pre style=\"background:#000\">");
+            for row in map.cells().chunks(map.width().max(1) as usize) {
+                let mut run: Option<(Color, Color, String)> = None;
+                for cell in row {
+                    let ch = printable(cell.ch);
+                    if let Some((fg, bg, text)) = &mut run {
+                        if *fg == cell.fg && *bg == cell.bg {
+                            text.push(ch);
+                            continue;
+                        }
+                    }
+                    if let Some(done) = run.replace((cell.fg, cell.bg, ch.to_string())) {
+                        push_span(&mut out, &done);
+                    }
+                }
+                if let Some(done) = run {
+                    push_span(&mut out, &done);
+                }
+                out.push('\n');
+            }
+            out.push_str("</pre>\n");
+        }
+        if !self.messages.is_empty() {
+            out.push_str("<h2>Last messages</h2>\n<ul>\n");
+            for message in &self.messages {
+                let _ = writeln!(out, "<li>{}</li>", escape(message));
+            }
+            out.push_str("</ul>\n");
+        }
+        if !self.stats.is_empty() {
+            out.push_str("<h2>Statistics</h2>\n");
+            push_html_pairs(&mut out, &self.stats);
+        }
+        for (title, lines) in &self.sections {
+            let _ = writeln!(out, "<h2>{}</h2>\n<pre>", escape(title));
+            for line in lines {
+                let _ = writeln!(out, "{}", escape(line));
+            }
+            out.push_str("</pre>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    /// Writes the dump to `path`, as HTML if the extension is `html` or
+    /// `htm` and as plain text otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be written.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let html = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("html") || e.eq_ignore_ascii_case("htm"));
+        let contents = if html { self.to_html() } else { self.to_text() };
+        fs::write(path, contents)
+    }
+}
+
+fn printable(ch: char) -> char {
+    if ch.is_control() {
+        ' '
+    } else {
+        ch
+    }
+}
+
+fn push_heading(out: &mut String, title: &str) {
+    let _ = write!(out, "\n-- {title} --\n\n");
+}
+
+fn push_pairs(out: &mut String, pairs: &[(String, String)]) {
+    let width = pairs
+        .iter()
+        .map(|(label, _)| label.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (label, value) in pairs {
+        let pad = width - label.chars().count();
+        let _ = writeln!(out, "{label}: {}{value}", " ".repeat(pad));
+    }
+}
+
+fn push_html_pairs(out: &mut String, pairs: &[(String, String)]) {
+    out.push_str("<table>\n");
+    for (label, value) in pairs {
+        let _ = writeln!(
+            out,
+            "<tr><th>{}</th><td>{}</td></tr>",
+            escape(label),
+            escape(value)
+        );
+    }
+    out.push_str("</table>\n");
+}
+
+fn push_span(out: &mut String, (fg, bg, text): &(Color, Color, String)) {
+    let _ = write!(
+        out,
+        "<span style=\"color:#{:02x}{:02x}{:02x};background:#{:02x}{:02x}{:02x}\">{}</span>",
+        fg.r,
+        fg.g,
+        fg.b,
+        bg.r,
+        bg.g,
+        bg.b,
+        escape(text)
+    );
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Morgue {
+        let mut map = ConsoleImage::new(3, 2);
+        map.draw_string(IVec2::ZERO, "<@>", Color::YELLOW, Color::BLACK);
+        map.set(IVec2::new(0, 1), Cell::from_char('#'));
+        Morgue::new("Bob")
+            .with_field("Depth", 3)
+            .with_field("Turns", 1200)
+            .with_map(&map)
+            .with_messages(["a", "b", "c"], 5)
+            .with_stats([("kills", 2), ("kills.rat", 2)])
+            .with_section("Inventory", ["a dagger"])
+    }
+
+    #[test]
+    fn test_text_layout() {
+        let text = sample().to_text();
+        assert_eq!(
+            text,
+            "Bob\n===\n\nDepth: 3\nTurns: 1200\n\n-- Map --\n\n<@>\n#\n\n\
+             -- Last messages --\n\na\nb\nc\n\n-- Statistics --\n\n\
+             kills:     2\nkills.rat: 2\n\n-- Inventory --\n\na dagger\n"
+        );
+        assert_eq!(Morgue::new("x").to_text(), "x\n=\n");
+    }
+
+    #[test]
+    fn test_html_escapes_and_colors() {
+        let html = sample().to_html();
+        assert!(html.contains("<span style=\"color:#ffff00;background:#000000\">&lt;@&gt;</span>"));
+        assert!(html.contains("<tr><th>Depth</th><td>3</td></tr>"));
+        assert!(html.contains("<li>c</li>"));
+        assert!(html.ends_with("</html>\n"));
+    }
+}
@@ -0,0 +1,308 @@
+//! Tile annotations for debugging, drawn on demand.
+//!
+//! When pathfinding, AI and generation interact, the quickest way to see
+//! why a monster went the wrong way is to look at what each system thinks
+//! about each tile. A [`DebugOverlay`] is a shared registry for that: any
+//! system tags world tiles with a short label and colors on its own named
+//! layer (path costs, AI targets, room ids), and one toggle draws every
+//! visible layer over any [`Console`].
+//!
+//! Tags are kept while the overlay is off, so turning it on shows the
+//! current state straight away. Layers draw in the order they were first
+//! used, so later layers cover earlier ones where tags overlap.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_color::Color;
+//! use runeforge_geometry::prelude::{CoordinateSystem, ScreenMapping, WorldPos};
+//! use runeforge_terminal::prelude::*;
+//! use runeforge_terminal::rfc::ConsoleImage;
+//!
+//! let mut overlay = DebugOverlay::new();
+//!
+//! // The pathfinder and the AI tag tiles as they work
+//! overlay.tag("costs", IVec2::new(3, 1), Annotation::new("12", Color::YELLOW));
+//! overlay.tag("ai", IVec2::new(5, 1), Annotation::new('T', Color::RED).with_bg(Color::BLACK));
+//!
+//! let mut console = ConsoleImage::new(8, 3);
+//! let mapping = ScreenMapping::new(CoordinateSystem::YDown, WorldPos::new(0, 0));
+//!
+//! // Off by default: nothing is drawn
+//! overlay.draw(&mut console, &mapping);
+//! assert_eq!(console.get(IVec2::new(3, 1)), Some(Cell::empty()));
+//!
+//! overlay.toggle();
+//! overlay.draw(&mut console, &mapping);
+//! assert_eq!(console.get(IVec2::new(3, 1)).unwrap().ch, '1');
+//! assert_eq!(console.get(IVec2::new(4, 1)).unwrap().ch, '2');
+//! assert_eq!(console.get(IVec2::new(5, 1)).unwrap().ch, 'T');
+//! ```
+
+use std::collections::HashMap;
+
+use runeforge_color::Color;
+use runeforge_geometry::prelude::{ScreenMapping, WorldPos};
+
+use crate::prelude::*;
+
+/// Longest label kept by [`Annotation::new`]; longer labels are cut.
+pub const MAX_LABEL_LEN: usize = 3;
+
+/// A short label on a tile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    /// Up to [`MAX_LABEL_LEN`] characters, drawn from the tile rightward.
+    pub label: String,
+    /// Color of the label.
+    pub fg: Color,
+    /// Background color, or `None` to keep the map's background.
+    pub bg: Option<Color>,
+}
+
+impl Annotation {
+    /// Creates an annotation on the map's background. The label is cut to
+    /// [`MAX_LABEL_LEN`] characters.
+    pub fn new(label: impl ToString, fg: Color) -> Self {
+        Self {
+            label: label.to_string().chars().take(MAX_LABEL_LEN).collect(),
+            fg,
+            bg: None,
+        }
+    }
+
+    /// Sets the background color.
+    pub fn with_bg(mut self, bg: Color) -> Self {
+        self.bg = Some(bg);
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+struct OverlayLayer {
+    name: String,
+    visible: bool,
+    tags: HashMap<IVec2, Annotation>,
+}
+
+/// Named layers of tile annotations with a single on/off switch.
+///
+/// The overlay starts off, with every new layer visible.
+#[derive(Debug, Clone, Default)]
+pub struct DebugOverlay {
+    enabled: bool,
+    layers: Vec<OverlayLayer>,
+}
+
+impl DebugOverlay {
+    /// Creates an empty overlay, turned off.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if the overlay is drawn.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Turns drawing on or off.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Flips drawing on or off, e.g. from a debug key. Returns the new state.
+    pub fn toggle(&mut self) -> bool {
+        self.enabled = !self.enabled;
+        self.enabled
+    }
+
+    /// Tags the world tile `pos` on `layer`, replacing that layer's
+    /// previous tag there. The layer is created on first use.
+    pub fn tag(&mut self, layer: &str, pos: IVec2, annotation: Annotation) {
+        self.layer_mut(layer).tags.insert(pos, annotation);
+    }
+
+    /// Removes a tag, returning it.
+    pub fn untag(&mut self, layer: &str, pos: IVec2) -> Option<Annotation> {
+        self.layer_index(layer)
+            .and_then(|i| self.layers[i].tags.remove(&pos))
+    }
+
+    /// Returns the tag on `layer` at `pos`.
+    pub fn get(&self, layer: &str, pos: IVec2) -> Option<&Annotation> {
+        self.layer_index(layer)
+            .and_then(|i| self.layers[i].tags.get(&pos))
+    }
+
+    /// Returns every tag at `pos` with its layer name, in drawing order.
+    pub fn annotations_at(&self, pos: IVec2) -> impl Iterator<Item = (&str, &Annotation)> {
+        self.layers
+            .iter()
+            .filter_map(move |l| l.tags.get(&pos).map(|a| (l.name.as_str(), a)))
+    }
+
+    /// Removes every tag on `layer`, e.g. before a system re-tags for the
+    /// new turn. The layer and its visibility are kept.
+    pub fn clear_layer(&mut self, layer: &str) {
+        if let Some(i) = self.layer_index(layer) {
+            self.layers[i].tags.clear();
+        }
+    }
+
+    /// Removes every tag on every layer, keeping the layers and their
+    /// visibility.
+    pub fn clear(&mut self) {
+        self.layers.iter_mut().for_each(|l| l.tags.clear());
+    }
+
+    /// Shows or hides one layer. The setting is remembered for layers that
+    /// have no tags yet.
+    pub fn set_layer_visible(&mut self, layer: &str, visible: bool) {
+        self.layer_mut(layer).visible = visible;
+    }
+
+    /// Flips whether a layer is shown. Returns the new state.
+    pub fn toggle_layer(&mut self, layer: &str) -> bool {
+        let layer = self.layer_mut(layer);
+        layer.visible = !layer.visible;
+        layer.visible
+    }
+
+    /// Returns true if `layer` is shown. Unknown layers are shown once used.
+    pub fn is_layer_visible(&self, layer: &str) -> bool {
+        self.layer_index(layer)
+            .is_none_or(|i| self.layers[i].visible)
+    }
+
+    /// Returns the layer names in drawing order.
+    pub fn layers(&self) -> impl Iterator<Item = &str> {
+        self.layers.iter().map(|l| l.name.as_str())
+    }
+
+    /// Returns the number of tags on every layer.
+    pub fn len(&self) -> usize {
+        self.layers.iter().map(|l| l.tags.len()).sum()
+    }
+
+    /// Returns true if no layer has tags.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Draws every visible layer's tags over the console, with `mapping`
+    /// placing world tiles on it. Does nothing while the overlay is off.
+    pub fn draw<C: Console + ?Sized>(&self, console: &mut C, mapping: &ScreenMapping) {
+        if !self.enabled {
+            return;
+        }
+        for layer in self.layers.iter().filter(|l| l.visible) {
+            // Sorted so overlapping labels within a layer draw the same way
+            // every frame
+            let mut tags: Vec<(&IVec2, &Annotation)> = layer.tags.iter().collect();
+            tags.sort_by_key(|(p, _)| (p.y, p.x));
+
+            for (&pos, annotation) in tags {
+                let start = mapping.to_screen(WorldPos(pos)).0;
+                for (i, ch) in annotation.label.chars().enumerate() {
+                    let pos = start + IVec2::new(i as i32, 0);
+                    if let Some(existing) = console.get(pos) {
+                        let bg = annotation.bg.unwrap_or(existing.bg);
+                        console.set(pos, Cell::new(ch, annotation.fg, bg));
+                    }
+                }
+            }
+        }
+    }
+
+    fn layer_index(&self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|l| l.name == name)
+    }
+
+    fn layer_mut(&mut self, name: &str) -> &mut OverlayLayer {
+        let i = self.layer_index(name).unwrap_or_else(|| {
+            self.layers.push(OverlayLayer {
+                name: name.to_string(),
+                visible: true,
+                tags: HashMap::new(),
+            });
+            self.layers.len() - 1
+        });
+        &mut self.layers[i]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use runeforge_geometry::prelude::CoordinateSystem;
+
+    use super::*;
+    use crate::rfc::ConsoleImage;
+
+    fn mapping() -> ScreenMapping {
+        ScreenMapping::new(CoordinateSystem::YDown, WorldPos::new(10, 10))
+    }
+
+    #[test]
+    fn test_labels_keep_background_and_clip() {
+        let mut overlay = DebugOverlay::new();
+        overlay.set_enabled(true);
+        overlay.tag(
+            "rooms",
+            IVec2::new(13, 10),
+            Annotation::new(1042, Color::WHITE),
+        );
+        assert_eq!(
+            overlay.get("rooms", IVec2::new(13, 10)).unwrap().label,
+            "104"
+        );
+
+        let mut console = ConsoleImage::new(5, 2);
+        console.set_bg(IVec2::new(3, 0), Color::BLUE);
+        overlay.draw(&mut console, &mapping());
+
+        assert_eq!(
+            console.get(IVec2::new(3, 0)),
+            Some(Cell::new('1', Color::WHITE, Color::BLUE))
+        );
+        assert_eq!(console.get(IVec2::new(4, 0)).unwrap().ch, '0');
+        // '4' falls off the right edge
+        assert_eq!(console.get(IVec2::new(0, 1)), Some(Cell::empty()));
+    }
+
+    #[test]
+    fn test_later_layers_draw_on_top() {
+        let mut overlay = DebugOverlay::new();
+        let pos = IVec2::new(11, 11);
+        overlay.tag("costs", pos, Annotation::new('5', Color::YELLOW));
+        overlay.tag("ai", pos, Annotation::new('!', Color::RED));
+        overlay.toggle();
+
+        let mut console = ConsoleImage::new(3, 3);
+        overlay.draw(&mut console, &mapping());
+        assert_eq!(console.get(IVec2::ONE).unwrap().ch, '!');
+
+        assert!(!overlay.toggle_layer("ai"));
+        overlay.draw(&mut console, &mapping());
+        assert_eq!(console.get(IVec2::ONE).unwrap().ch, '5');
+
+        let layers: Vec<&str> = overlay.annotations_at(pos).map(|(l, _)| l).collect();
+        assert_eq!(layers, ["costs", "ai"]);
+    }
+
+    #[test]
+    fn test_clearing_keeps_layer_settings() {
+        let mut overlay = DebugOverlay::new();
+        overlay.set_layer_visible("targets", false);
+        overlay.tag("targets", IVec2::ZERO, Annotation::new('T', Color::RED));
+        overlay.tag("paths", IVec2::ZERO, Annotation::new('*', Color::RED));
+        assert_eq!(overlay.len(), 2);
+
+        overlay.clear_layer("targets");
+        assert_eq!(overlay.len(), 1);
+        overlay.clear();
+        assert!(overlay.is_empty());
+        assert!(!overlay.is_layer_visible("targets"));
+        assert!(overlay.is_layer_visible("unused"));
+        assert_eq!(overlay.layers().collect::<Vec<_>>(), ["targets", "paths"]);
+    }
+}
@@ -0,0 +1,167 @@
+//! Fitting a rendered console into a window.
+//!
+//! A console renders into a buffer whose size is fixed by the font. The window
+//! around it can be any size, so windowed backends use [`DisplayOptions`] to
+//! decide how the buffer is scaled: whole multiples for crisp pixel fonts,
+//! aspect-preserving letterboxing, or stretching to fill. A per-axis cell
+//! aspect lets square glyphs be shown in tall cells (e.g. 8x8 glyphs in 8x16
+//! cells) without being squashed.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_terminal::prelude::*;
+//! use glam::{UVec2, Vec2};
+//!
+//! let options = DisplayOptions::new()
+//!     .with_scale_mode(ScaleMode::Integer)
+//!     .with_cell_aspect(1.0, 2.0);
+//!
+//! // An 80x25 console of 8x8 glyphs in a 1920x1080 window
+//! let layout = options.layout(UVec2::new(640, 200), UVec2::new(1920, 1080));
+//! assert_eq!(layout.scale, Vec2::new(2.0, 4.0));
+//! assert_eq!(layout.cell_size(UVec2::new(8, 8)), Vec2::new(16.0, 32.0));
+//! assert_eq!(layout.window_to_cell(layout.offset, UVec2::new(8, 8)), Some(IVec2::ZERO));
+//! ```
+
+use glam::{UVec2, Vec2};
+
+use crate::prelude::IVec2;
+
+/// How the console buffer is scaled to fit the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScaleMode {
+    /// Scale by the largest whole number that fits, centered. Keeps pixel
+    /// fonts crisp. Falls back to [`Letterbox`](Self::Letterbox) if the
+    /// window is smaller than the buffer.
+    #[default]
+    Integer,
+    /// Scale as large as fits while preserving the aspect ratio, centered.
+    Letterbox,
+    /// Fill the whole window, ignoring the aspect ratio.
+    Stretch,
+}
+
+/// Display settings for windowed backends.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayOptions {
+    /// How the buffer is fitted to the window
+    pub scale_mode: ScaleMode,
+    /// Extra scale applied to each axis before fitting
+    pub cell_aspect: Vec2,
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        Self {
+            scale_mode: ScaleMode::Integer,
+            cell_aspect: Vec2::ONE,
+        }
+    }
+}
+
+impl DisplayOptions {
+    /// Creates options with integer scaling and square cells.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the scale mode.
+    pub fn with_scale_mode(mut self, scale_mode: ScaleMode) -> Self {
+        self.scale_mode = scale_mode;
+        self
+    }
+
+    /// Sets the per-axis cell aspect. `(1.0, 2.0)` shows each cell twice as
+    /// tall as the font renders it. Non-positive values are replaced by 1.0.
+    pub fn with_cell_aspect(mut self, x: f32, y: f32) -> Self {
+        let fix = |v: f32| if v.is_finite() && v > 0.0 { v } else { 1.0 };
+        self.cell_aspect = Vec2::new(fix(x), fix(y));
+        self
+    }
+
+    /// Computes where a buffer of `buffer` pixels is drawn in a `window`-sized surface.
+    pub fn layout(&self, buffer: UVec2, window: UVec2) -> DisplayLayout {
+        let buffer_f = buffer.max(UVec2::ONE).as_vec2();
+        let window_f = window.as_vec2();
+        let content = buffer_f * self.cell_aspect;
+        let fit = (window_f / content).min_element();
+
+        let scale = match self.scale_mode {
+            ScaleMode::Stretch => window_f / buffer_f,
+            ScaleMode::Integer if fit >= 1.0 => self.cell_aspect * fit.floor(),
+            ScaleMode::Integer | ScaleMode::Letterbox => self.cell_aspect * fit,
+        };
+        let size = (buffer_f * scale).floor();
+        DisplayLayout {
+            offset: ((window_f - size) / 2.0).floor(),
+            size,
+            scale,
+        }
+    }
+}
+
+/// Where the console buffer lands in the window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayLayout {
+    /// Top-left corner of the drawn area, in window pixels
+    pub offset: Vec2,
+    /// Size of the drawn area, in window pixels
+    pub size: Vec2,
+    /// Window pixels per buffer pixel on each axis
+    pub scale: Vec2,
+}
+
+impl DisplayLayout {
+    /// Returns the on-screen size of a cell that is `cell` buffer pixels large.
+    pub fn cell_size(&self, cell: UVec2) -> Vec2 {
+        cell.as_vec2() * self.scale
+    }
+
+    /// Converts a window position to a buffer pixel position, or `None` in the
+    /// letterbox bars.
+    pub fn window_to_buffer(&self, pos: Vec2) -> Option<Vec2> {
+        let local = pos - self.offset;
+        if local.cmplt(Vec2::ZERO).any() || local.cmpge(self.size).any() {
+            return None;
+        }
+        Some(local / self.scale)
+    }
+
+    /// Converts a window position to the cell under it, for mouse input.
+    pub fn window_to_cell(&self, pos: Vec2, cell: UVec2) -> Option<IVec2> {
+        self.window_to_buffer(pos)
+            .map(|p| (p / cell.max(UVec2::ONE).as_vec2()).floor().as_ivec2())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_letterbox_centers() {
+        let layout = DisplayOptions::new()
+            .with_scale_mode(ScaleMode::Letterbox)
+            .layout(UVec2::new(100, 50), UVec2::new(300, 300));
+        assert_eq!(layout.scale, Vec2::splat(3.0));
+        assert_eq!(layout.size, Vec2::new(300.0, 150.0));
+        assert_eq!(layout.offset, Vec2::new(0.0, 75.0));
+        assert_eq!(layout.window_to_buffer(Vec2::new(10.0, 10.0)), None);
+    }
+
+    #[test]
+    fn test_integer_falls_back_when_window_is_small() {
+        let layout = DisplayOptions::new().layout(UVec2::new(200, 100), UVec2::new(100, 100));
+        assert_eq!(layout.scale, Vec2::splat(0.5));
+    }
+
+    #[test]
+    fn test_stretch_fills_window() {
+        let layout = DisplayOptions::new()
+            .with_scale_mode(ScaleMode::Stretch)
+            .layout(UVec2::new(100, 50), UVec2::new(300, 200));
+        assert_eq!(layout.scale, Vec2::new(3.0, 4.0));
+        assert_eq!(layout.offset, Vec2::ZERO);
+    }
+}
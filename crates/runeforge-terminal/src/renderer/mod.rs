@@ -1,5 +1,9 @@
+mod display;
 mod pixel;
+mod post;
 mod software;
 
+pub use display::*;
 pub use pixel::*;
+pub use post::*;
 pub use software::*;
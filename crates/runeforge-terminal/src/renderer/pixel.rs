@@ -29,8 +29,10 @@
 #![deny(missing_docs)]
 
 use crate::prelude::*;
+use glam::UVec2;
 use runeforge_color::Color;
 use runeforge_tileset::prelude::{Font, GlyphAtlas};
+use std::time::Instant;
 
 /// GPU-ready renderer using pixel buffers.
 ///
@@ -50,6 +52,17 @@ pub struct PixelsRenderer {
     pixel_width: u32,
     pixel_height: u32,
     pixel_buffer: Vec<u8>,
+    display_options: DisplayOptions,
+    light: Option<LightLayer>,
+    cursor: Cursor,
+    /// When the cursor last moved, for its blink phase.
+    cursor_moved: Instant,
+    /// The smoothly lit copy of `pixel_buffer`, used with [`LightBlend::Smooth`].
+    frame: Vec<u8>,
+    /// The window the buffer is scaled to on present, if one is set.
+    window: Option<UVec2>,
+    /// The buffer scaled to `window`, filled by [`present`](Console::present).
+    scaled: Vec<u8>,
 }
 
 impl PixelsRenderer {
@@ -90,6 +103,118 @@ impl PixelsRenderer {
             pixel_width,
             pixel_height,
             pixel_buffer,
+            display_options: DisplayOptions::default(),
+            light: None,
+            cursor: Cursor::default(),
+            cursor_moved: Instant::now(),
+            frame: Vec::new(),
+            window: None,
+            scaled: Vec::new(),
+        }
+    }
+
+    /// Sets the light layer multiplied over cell colors when rendering, or
+    /// removes it with `None`. The cells themselves are not modified.
+    pub fn set_light_layer(&mut self, light: Option<LightLayer>) {
+        self.light = light;
+    }
+
+    /// Returns the light layer, if any.
+    pub fn light_layer(&self) -> Option<&LightLayer> {
+        self.light.as_ref()
+    }
+
+    /// Returns the light layer for updating this frame's lighting.
+    pub fn light_layer_mut(&mut self) -> Option<&mut LightLayer> {
+        self.light.as_mut()
+    }
+
+    /// Returns the smooth light layer, if one is set.
+    fn smooth_light(&self) -> Option<&LightLayer> {
+        self.light
+            .as_ref()
+            .filter(|l| l.blend() == LightBlend::Smooth)
+    }
+
+    /// Sets how the buffer is fitted to the window.
+    pub fn with_display_options(mut self, options: DisplayOptions) -> Self {
+        self.display_options = options;
+        self
+    }
+
+    /// Returns how the buffer is fitted to the window.
+    pub fn display_options(&self) -> &DisplayOptions {
+        &self.display_options
+    }
+
+    /// Changes how the buffer is fitted to the window.
+    pub fn set_display_options(&mut self, options: DisplayOptions) {
+        self.display_options = options;
+    }
+
+    /// Sets the window size `present()` scales the buffer to, using the
+    /// display options. Call again whenever the window is resized.
+    pub fn set_window_size(&mut self, window: UVec2) {
+        self.window = Some(window);
+        self.scaled
+            .resize(window.x as usize * window.y as usize * 4, 0);
+    }
+
+    /// Returns the window size set with [`set_window_size`](Self::set_window_size).
+    pub fn window_size(&self) -> Option<UVec2> {
+        self.window
+    }
+
+    /// Returns the frame to show: the buffer scaled to the window if a window
+    /// size is set, otherwise the unscaled [`pixel_buffer`](Self::pixel_buffer).
+    pub fn display_frame(&self) -> &[u8] {
+        if self.window.is_some() {
+            &self.scaled
+        } else {
+            self.pixel_buffer()
+        }
+    }
+
+    /// Returns the size of one cell in the render buffer, in pixels.
+    pub fn cell_size(&self) -> UVec2 {
+        UVec2::new(self.cell_width, self.cell_height)
+    }
+
+    /// Returns where the buffer is drawn in a window of the given size.
+    pub fn display_layout(&self, window: UVec2) -> DisplayLayout {
+        self.display_options
+            .layout(UVec2::new(self.pixel_width, self.pixel_height), window)
+    }
+
+    /// Returns the on-screen size of a cell in a window of the given size.
+    pub fn effective_cell_size(&self, window: UVec2) -> glam::Vec2 {
+        self.display_layout(window).cell_size(self.cell_size())
+    }
+
+    /// Copies the buffer into an RGBA `frame` of `window` pixels, scaled with
+    /// nearest-neighbor sampling. Letterbox bars are filled with black.
+    ///
+    /// Pass the frame of a `pixels` surface created at the window size.
+    pub fn render_scaled(&self, window: UVec2, frame: &mut [u8]) {
+        let layout = self.display_layout(window);
+        let min = layout.offset.as_uvec2();
+        let max = (layout.offset + layout.size).as_uvec2().min(window);
+        frame.fill(0);
+        for y in min.y..max.y {
+            let src_y = (((y - min.y) as f32 + 0.5) / layout.scale.y) as u32;
+            let src_y = src_y.min(self.pixel_height.saturating_sub(1));
+            for x in min.x..max.x {
+                let src_x = (((x - min.x) as f32 + 0.5) / layout.scale.x) as u32;
+                let src_x = src_x.min(self.pixel_width.saturating_sub(1));
+                let src = ((src_y * self.pixel_width + src_x) * 4) as usize;
+                let source = self.pixel_buffer();
+                let dst = ((y * window.x + x) * 4) as usize;
+                if let (Some(from), Some(to)) =
+                    (source.get(src..src + 4), frame.get_mut(dst..dst + 4))
+                {
+                    to.copy_from_slice(from);
+                }
+            }
         }
     }
 
@@ -107,18 +232,27 @@ impl PixelsRenderer {
     ///
     /// This buffer can be used with the `pixels` crate for GPU rendering.
     pub fn pixel_buffer(&self) -> &[u8] {
-        &self.pixel_buffer
+        if self.smooth_light().is_some() {
+            &self.frame
+        } else {
+            &self.pixel_buffer
+        }
     }
 
     /// Returns a mutable reference to the raw RGBA pixel buffer.
     ///
     /// This buffer can be used with the `pixels` crate for GPU rendering.
     pub fn pixel_buffer_mut(&mut self) -> &mut [u8] {
-        &mut self.pixel_buffer
+        if self.smooth_light().is_some() {
+            &mut self.frame
+        } else {
+            &mut self.pixel_buffer
+        }
     }
 
     /// Renders the current console state to the pixel buffer.
     fn render_to_buffer(&mut self) {
+        let cursor = self.cursor.shown_at(self.cursor_moved.elapsed());
         for y in 0..self.height {
             for x in 0..self.width {
                 let cell_idx = (y * self.width + x) as usize;
@@ -126,16 +260,26 @@ impl PixelsRenderer {
                     continue;
                 }
 
-                let cell = self.cells[cell_idx];
+                let mut cell = self.cells[cell_idx];
                 let px = x * self.cell_width;
                 let py = y * self.cell_height;
 
+                let pos = IVec2::new(x as i32, y as i32);
+                let mut transparent = cell.bg == Color::BLACK && cell.ch == ' ';
+                if let Some(light) = self
+                    .light
+                    .as_ref()
+                    .filter(|l| l.blend() == LightBlend::Flat)
+                {
+                    cell = light.tint(pos, cell);
+                }
+                if cursor == Some(pos) {
+                    cell = invert_cell(cell);
+                    transparent = false;
+                }
+
                 let fg = cell.fg;
-                let bg = if cell.bg == Color::BLACK && cell.ch == ' ' {
-                    None
-                } else {
-                    Some(cell.bg)
-                };
+                let bg = if transparent { None } else { Some(cell.bg) };
 
                 self.glyph_atlas.render_char(
                     &mut self.pixel_buffer,
@@ -148,6 +292,19 @@ impl PixelsRenderer {
                 );
             }
         }
+
+        if let Some(light) = self
+            .light
+            .as_ref()
+            .filter(|l| l.blend() == LightBlend::Smooth)
+        {
+            self.frame.clone_from(&self.pixel_buffer);
+            light.apply_smooth(
+                &mut self.frame,
+                self.pixel_width,
+                (self.cell_width, self.cell_height),
+            );
+        }
     }
 }
 
@@ -189,21 +346,87 @@ impl Console for PixelsRenderer {
         self.pixel_buffer.fill(0);
     }
 
+    fn cursor(&self) -> Cursor {
+        self.cursor
+    }
+
+    fn set_cursor_state(&mut self, cursor: Cursor) {
+        if cursor.pos != self.cursor.pos {
+            self.cursor_moved = Instant::now();
+        }
+        self.cursor = cursor;
+    }
+
     fn present(&mut self) {
         // For GPU rendering, present() renders to the buffer
         // The actual pixel buffer can then be uploaded to GPU via pixels crate
         self.render_to_buffer();
+        if let Some(window) = self.window {
+            let mut scaled = std::mem::take(&mut self.scaled);
+            self.render_scaled(window, &mut scaled);
+            self.scaled = scaled;
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    // Note: These tests require a font file, so we skip actual rendering tests
-    // In a real project, you'd include a test font in the repository
+    use super::*;
+    use runeforge_tileset::prelude::RenderedGlyph;
+
+    /// A 2x2 font that fills every cell.
+    struct Block;
+
+    impl Font for Block {
+        fn name(&self) -> &str {
+            "block"
+        }
+
+        fn cell_width(&self) -> u32 {
+            2
+        }
+
+        fn cell_height(&self) -> u32 {
+            2
+        }
+
+        fn line_height(&self) -> u32 {
+            2
+        }
+
+        fn render_glyph(&self, c: char) -> Option<RenderedGlyph> {
+            Some(RenderedGlyph {
+                character: c,
+                width: 2,
+                height: 2,
+                bearing_x: 0,
+                bearing_y: 2,
+                bitmap: vec![255; 4],
+                rgba: None,
+            })
+        }
+
+        fn has_glyph(&self, _c: char) -> bool {
+            true
+        }
+    }
 
     #[test]
-    fn test_console_trait_implementation() {
-        // Verify that PixelsRenderer implements Console
-        // This is a compile-time check more than a runtime test
+    fn test_present_scales_to_window() {
+        let mut renderer = PixelsRenderer::new(2, 1, &Block);
+        renderer.draw_char(IVec2::ZERO, '#', Color::RED, Color::BLACK);
+        renderer.present();
+        assert_eq!(renderer.display_frame(), renderer.pixel_buffer());
+
+        // A 4x2 buffer doubled into a 10x6 window, letterboxed by one pixel
+        renderer.set_window_size(UVec2::new(10, 6));
+        renderer.present();
+        let frame = renderer.display_frame();
+        assert_eq!(frame.len(), 10 * 6 * 4);
+        let pixel = |x: usize, y: usize| &frame[(y * 10 + x) * 4..(y * 10 + x) * 4 + 4];
+        assert_eq!(pixel(0, 0), [0, 0, 0, 0]);
+        assert_eq!(pixel(1, 1), &renderer.pixel_buffer()[..4]);
+        assert_eq!(pixel(4, 4), &renderer.pixel_buffer()[..4]);
+        assert_eq!(pixel(5, 1), &renderer.pixel_buffer()[8..12]);
     }
 }
@@ -0,0 +1,253 @@
+//! Retro post-processing passes over an RGBA pixel buffer.
+//!
+//! [`PostEffects`] runs after the console has been rendered. Every effect is
+//! optional and can be switched on or off between frames, e.g. a short screen
+//! shake when the player is hit.
+//!
+//! Passes run in a fixed order: shake, glow, scanlines, vignette.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_terminal::prelude::*;
+//!
+//! let effects = PostEffects::new()
+//!     .with_scanlines(0.5)
+//!     .with_shake(IVec2::new(1, 0));
+//!
+//! // A 2x2 white image
+//! let mut buffer = vec![255u8; 2 * 2 * 4];
+//! effects.apply(&mut buffer, 2, 2);
+//!
+//! // Shifted right by one pixel, with the bottom row dimmed
+//! assert_eq!(&buffer[0..4], &[0, 0, 0, 255]);
+//! assert_eq!(&buffer[4..8], &[255, 255, 255, 255]);
+//! assert_eq!(&buffer[12..16], &[128, 128, 128, 255]);
+//! ```
+
+use crate::prelude::IVec2;
+
+/// Settings for the glow pass.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Glow {
+    /// Pixels brighter than this (0-255 luma) bleed light into their neighbors
+    pub threshold: u8,
+    /// Blur radius in pixels
+    pub radius: u32,
+    /// How much of the blurred light is added back (0.0 to 1.0 is typical)
+    pub strength: f32,
+}
+
+impl Default for Glow {
+    fn default() -> Self {
+        Self {
+            threshold: 160,
+            radius: 2,
+            strength: 0.6,
+        }
+    }
+}
+
+/// Optional post-processing effects for the software renderer.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PostEffects {
+    /// Darkens every other row by this fraction (0.0 to 1.0), like a CRT.
+    pub scanlines: Option<f32>,
+    /// Bright pixels bleed light into their surroundings.
+    pub glow: Option<Glow>,
+    /// Darkens the corners by up to this fraction (0.0 to 1.0).
+    pub vignette: Option<f32>,
+    /// Shifts the whole image by this many pixels, filling the gap with black.
+    pub shake: IVec2,
+}
+
+impl PostEffects {
+    /// Creates a set of effects with everything disabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables scanlines (clamped to 0.0-1.0).
+    pub fn with_scanlines(mut self, intensity: f32) -> Self {
+        self.scanlines = Some(intensity.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Enables glow.
+    pub fn with_glow(mut self, glow: Glow) -> Self {
+        self.glow = Some(glow);
+        self
+    }
+
+    /// Enables the vignette (clamped to 0.0-1.0).
+    pub fn with_vignette(mut self, strength: f32) -> Self {
+        self.vignette = Some(strength.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Sets the screen shake offset for this frame.
+    pub fn with_shake(mut self, offset: IVec2) -> Self {
+        self.shake = offset;
+        self
+    }
+
+    /// Returns true if any effect is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.scanlines.is_some()
+            || self.glow.is_some()
+            || self.vignette.is_some()
+            || self.shake != IVec2::ZERO
+    }
+
+    /// Applies the enabled effects to an RGBA buffer of `width` x `height` pixels.
+    ///
+    /// Alpha is left unchanged.
+    pub fn apply(&self, buffer: &mut [u8], width: u32, height: u32) {
+        let (w, h) = (width as usize, height as usize);
+        if buffer.len() < w * h * 4 {
+            return;
+        }
+        if self.shake != IVec2::ZERO {
+            shake(buffer, w, h, self.shake);
+        }
+        if let Some(glow) = self.glow {
+            self::glow(buffer, w, h, glow);
+        }
+        if let Some(intensity) = self.scanlines {
+            for row in buffer.chunks_exact_mut(w * 4).take(h).skip(1).step_by(2) {
+                scale_rgb(row, 1.0 - intensity);
+            }
+        }
+        if let Some(strength) = self.vignette {
+            let center = (w as f32 / 2.0, h as f32 / 2.0);
+            let max_sq = center.0 * center.0 + center.1 * center.1;
+            for y in 0..h {
+                for x in 0..w {
+                    let dx = x as f32 + 0.5 - center.0;
+                    let dy = y as f32 + 0.5 - center.1;
+                    let falloff = (dx * dx + dy * dy) / max_sq.max(f32::EPSILON);
+                    let i = (y * w + x) * 4;
+                    scale_rgb(&mut buffer[i..i + 4], 1.0 - strength * falloff);
+                }
+            }
+        }
+    }
+}
+
+/// Multiplies the RGB channels of every pixel in `pixels` by `factor`.
+fn scale_rgb(pixels: &mut [u8], factor: f32) {
+    for px in pixels.chunks_exact_mut(4) {
+        for c in &mut px[..3] {
+            *c = (*c as f32 * factor).round().clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+fn shake(buffer: &mut [u8], w: usize, h: usize, offset: IVec2) {
+    let source = buffer.to_vec();
+    for y in 0..h {
+        for x in 0..w {
+            let sx = x as i64 - offset.x as i64;
+            let sy = y as i64 - offset.y as i64;
+            let dst = (y * w + x) * 4;
+            if sx >= 0 && sy >= 0 && (sx as usize) < w && (sy as usize) < h {
+                let src = (sy as usize * w + sx as usize) * 4;
+                buffer[dst..dst + 3].copy_from_slice(&source[src..src + 3]);
+            } else {
+                buffer[dst..dst + 3].fill(0);
+            }
+        }
+    }
+}
+
+fn glow(buffer: &mut [u8], w: usize, h: usize, glow: Glow) {
+    // Bright pass: keep only pixels above the threshold.
+    let mut light: Vec<[f32; 3]> = buffer
+        .chunks_exact(4)
+        .take(w * h)
+        .map(|px| {
+            let luma = 0.299 * px[0] as f32 + 0.587 * px[1] as f32 + 0.114 * px[2] as f32;
+            if luma > glow.threshold as f32 {
+                [px[0] as f32, px[1] as f32, px[2] as f32]
+            } else {
+                [0.0; 3]
+            }
+        })
+        .collect();
+
+    // Separable box blur.
+    let r = glow.radius as i64;
+    let blur = |light: &[[f32; 3]], horizontal: bool| -> Vec<[f32; 3]> {
+        let mut out = vec![[0.0; 3]; light.len()];
+        for y in 0..h as i64 {
+            for x in 0..w as i64 {
+                let mut sum = [0.0; 3];
+                for d in -r..=r {
+                    let (sx, sy) = if horizontal { (x + d, y) } else { (x, y + d) };
+                    if sx < 0 || sy < 0 || sx >= w as i64 || sy >= h as i64 {
+                        continue;
+                    }
+                    let p = light[sy as usize * w + sx as usize];
+                    sum.iter_mut().zip(p).for_each(|(s, v)| *s += v);
+                }
+                out[y as usize * w + x as usize] = sum.map(|s| s / (2 * r + 1) as f32);
+            }
+        }
+        out
+    };
+    light = blur(&light, true);
+    light = blur(&light, false);
+
+    for (px, l) in buffer.chunks_exact_mut(4).zip(light) {
+        for (c, v) in px[..3].iter_mut().zip(l) {
+            *c = (*c as f32 + v * glow.strength).round().min(255.0) as u8;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(w: usize, h: usize, value: u8) -> Vec<u8> {
+        let mut buffer = vec![value; w * h * 4];
+        buffer.chunks_exact_mut(4).for_each(|px| px[3] = 255);
+        buffer
+    }
+
+    #[test]
+    fn test_disabled_is_noop() {
+        let mut buffer = image(3, 3, 100);
+        let original = buffer.clone();
+        let effects = PostEffects::new();
+        assert!(!effects.is_enabled());
+        effects.apply(&mut buffer, 3, 3);
+        assert_eq!(buffer, original);
+    }
+
+    #[test]
+    fn test_vignette_darkens_corners_more() {
+        let mut buffer = image(5, 5, 200);
+        PostEffects::new()
+            .with_vignette(1.0)
+            .apply(&mut buffer, 5, 5);
+        let center = buffer[(2 * 5 + 2) * 4];
+        let corner = buffer[0];
+        assert!(corner < center);
+        assert_eq!(buffer[3], 255);
+    }
+
+    #[test]
+    fn test_glow_spreads_light() {
+        let mut buffer = image(5, 1, 0);
+        buffer[2 * 4..2 * 4 + 3].fill(255);
+        let glow = Glow {
+            threshold: 100,
+            radius: 1,
+            strength: 1.0,
+        };
+        PostEffects::new().with_glow(glow).apply(&mut buffer, 5, 1);
+        assert!(buffer[4] > 0, "neighbor should be lit");
+        assert_eq!(buffer[0], 0, "outside the radius stays dark");
+    }
+}
@@ -25,13 +25,19 @@
 //!
 //! // Save to PNG
 //! // renderer.save_png("output.png").unwrap();
+//!
+//! // Add CRT scanlines and a vignette to every following frame
+//! // renderer.set_post_effects(PostEffects::new().with_scanlines(0.3).with_vignette(0.4));
+//!
+//! // Restrict the output to the PICO-8 palette, dithering gradients
+//! // renderer.set_palette(Some(Palette::pico8()), Some(48.0));
 //! ```
 
 use crate::prelude::*;
 use image::{ImageBuffer, Rgba};
-use runeforge_color::Color;
+use runeforge_color::{quantize::Palette, Color};
 use runeforge_tileset::prelude::{Font, GlyphAtlas};
-use std::{io, path::Path};
+use std::{io, path::Path, time::Instant};
 
 /// CPU-based software renderer using pixel buffers.
 ///
@@ -47,6 +53,16 @@ pub struct SoftwareRenderer {
     pixel_width: u32,
     pixel_height: u32,
     pixel_buffer: Vec<u8>,
+    post_effects: PostEffects,
+    light: Option<LightLayer>,
+    /// Palette every output pixel is quantized to, with the dither spread.
+    palette: Option<(Palette, Option<f32>)>,
+    cursor: Cursor,
+    /// When the cursor last moved, for its blink phase.
+    cursor_moved: Instant,
+    /// The post-processed copy of `pixel_buffer`, used when effects, smooth
+    /// lighting or a palette are enabled.
+    frame: Vec<u8>,
 }
 
 impl SoftwareRenderer {
@@ -87,9 +103,73 @@ impl SoftwareRenderer {
             pixel_width,
             pixel_height,
             pixel_buffer,
+            post_effects: PostEffects::default(),
+            light: None,
+            palette: None,
+            cursor: Cursor::default(),
+            cursor_moved: Instant::now(),
+            frame: Vec::new(),
         }
     }
 
+    /// Sets the light layer multiplied over cell colors when rendering, or
+    /// removes it with `None`. The cells themselves are not modified.
+    pub fn set_light_layer(&mut self, light: Option<LightLayer>) {
+        self.light = light;
+    }
+
+    /// Returns the light layer, if any.
+    pub fn light_layer(&self) -> Option<&LightLayer> {
+        self.light.as_ref()
+    }
+
+    /// Returns the light layer for updating this frame's lighting.
+    pub fn light_layer_mut(&mut self) -> Option<&mut LightLayer> {
+        self.light.as_mut()
+    }
+
+    /// Returns true if the output goes through the separate frame buffer.
+    fn uses_frame(&self) -> bool {
+        self.post_effects.is_enabled()
+            || self.palette.is_some()
+            || self
+                .light
+                .as_ref()
+                .is_some_and(|l| l.blend() == LightBlend::Smooth)
+    }
+
+    /// Returns the post-processing effects applied to each rendered frame.
+    pub fn post_effects(&self) -> &PostEffects {
+        &self.post_effects
+    }
+
+    /// Returns the post-processing effects for modification, e.g. to set
+    /// this frame's shake offset.
+    pub fn post_effects_mut(&mut self) -> &mut PostEffects {
+        &mut self.post_effects
+    }
+
+    /// Replaces the post-processing effects applied to each rendered frame.
+    pub fn set_post_effects(&mut self, effects: PostEffects) {
+        self.post_effects = effects;
+    }
+
+    /// Restricts the output to `palette`, or lifts the restriction with
+    /// `None`.
+    ///
+    /// Every pixel is mapped to its nearest palette color after lighting and
+    /// post effects. With a dither `spread` (in 0-255 channel units, about
+    /// the gap between palette colors) an ordered dither is used instead, so
+    /// gradients become patterns rather than bands.
+    pub fn set_palette(&mut self, palette: Option<Palette>, spread: Option<f32>) {
+        self.palette = palette.map(|p| (p, spread));
+    }
+
+    /// Returns the palette the output is quantized to, if any.
+    pub fn palette(&self) -> Option<&Palette> {
+        self.palette.as_ref().map(|(p, _)| p)
+    }
+
     /// Returns the pixel width of the render buffer.
     pub fn pixel_width(&self) -> u32 {
         self.pixel_width
@@ -100,13 +180,19 @@ impl SoftwareRenderer {
         self.pixel_height
     }
 
-    /// Returns a reference to the raw RGBA pixel buffer.
+    /// Returns a reference to the raw RGBA pixel buffer, with post-processing
+    /// effects applied.
     pub fn pixel_buffer(&self) -> &[u8] {
-        &self.pixel_buffer
+        if self.uses_frame() {
+            &self.frame
+        } else {
+            &self.pixel_buffer
+        }
     }
 
     /// Renders the current console state to the pixel buffer.
     fn render_to_buffer(&mut self) {
+        let cursor = self.cursor.shown_at(self.cursor_moved.elapsed());
         for y in 0..self.height {
             for x in 0..self.width {
                 let cell_idx = (y * self.width + x) as usize;
@@ -114,13 +200,27 @@ impl SoftwareRenderer {
                     continue;
                 }
 
-                let cell = self.cells[cell_idx];
+                let mut cell = self.cells[cell_idx];
                 let px = x * self.cell_width;
                 let py = y * self.cell_height;
 
+                let pos = IVec2::new(x as i32, y as i32);
+                let mut transparent = cell.bg == Color::BLACK && cell.ch == ' ';
+                if let Some(light) = self
+                    .light
+                    .as_ref()
+                    .filter(|l| l.blend() == LightBlend::Flat)
+                {
+                    cell = light.tint(pos, cell);
+                }
+                if cursor == Some(pos) {
+                    cell = invert_cell(cell);
+                    transparent = false;
+                }
+
                 // Use Color directly (GlyphAtlas accepts Color)
                 let fg = cell.fg;
-                let bg = if cell.bg == Color::BLACK && cell.ch == ' ' {
+                let bg = if transparent {
                     None // Transparent for empty cells
                 } else {
                     Some(cell.bg)
@@ -137,6 +237,28 @@ impl SoftwareRenderer {
                 );
             }
         }
+
+        // Effects go into a separate copy so they don't pile up on the
+        // persistent glyph buffer from frame to frame.
+        if self.uses_frame() {
+            self.frame.clone_from(&self.pixel_buffer);
+            if let Some(light) = self
+                .light
+                .as_ref()
+                .filter(|l| l.blend() == LightBlend::Smooth)
+            {
+                light.apply_smooth(
+                    &mut self.frame,
+                    self.pixel_width,
+                    (self.cell_width, self.cell_height),
+                );
+            }
+            self.post_effects
+                .apply(&mut self.frame, self.pixel_width, self.pixel_height);
+            if let Some((palette, spread)) = &self.palette {
+                palette.apply(&mut self.frame, self.pixel_width, *spread);
+            }
+        }
     }
 
     /// Saves the rendered output to a PNG file.
@@ -158,7 +280,7 @@ impl SoftwareRenderer {
         let img: ImageBuffer<Rgba<u8>, Vec<u8>> = ImageBuffer::from_raw(
             self.pixel_width,
             self.pixel_height,
-            self.pixel_buffer.clone(),
+            self.pixel_buffer().to_vec(),
         )
         .ok_or_else(|| io::Error::other("Failed to create image buffer"))?;
 
@@ -173,7 +295,7 @@ impl SoftwareRenderer {
         ImageBuffer::from_raw(
             self.pixel_width,
             self.pixel_height,
-            self.pixel_buffer.clone(),
+            self.pixel_buffer().to_vec(),
         )
         .expect("Failed to create image buffer")
     }
@@ -217,6 +339,17 @@ impl Console for SoftwareRenderer {
         self.pixel_buffer.fill(0);
     }
 
+    fn cursor(&self) -> Cursor {
+        self.cursor
+    }
+
+    fn set_cursor_state(&mut self, cursor: Cursor) {
+        if cursor.pos != self.cursor.pos {
+            self.cursor_moved = Instant::now();
+        }
+        self.cursor = cursor;
+    }
+
     fn present(&mut self) {
         // For software rendering, present() renders to the buffer
         // Actual display/saving is done via save_png() or to_image_buffer()
@@ -0,0 +1,526 @@
+//! Saving and loading whole consoles as `.rfc` (Runeforge console) files.
+//!
+//! A [`ConsoleImage`] is an off-screen grid of cells plus metadata: an
+//! optional [`Palette`] the art was drawn for and an optional font hint such
+//! as `"cp437_8x8"`. It implements [`Console`], so it can be drawn with the
+//! usual helpers, captured from another console, saved, and later drawn back
+//! with [`ConsoleImage::blit`]. Title screens, cutscene frames and prebaked
+//! UI made in external tools can ship as `.rfc` files.
+//!
+//! # Format
+//!
+//! All integers are little-endian. Version 1 is:
+//!
+//! ```text
+//! magic    "RFC\0"
+//! version  u16 = 1
+//! flags    u16     bit 0: font hint present, bit 1: palette present
+//! width    u32
+//! height   u32
+//! [font]   u16 length, UTF-8 bytes
+//! [palette] u16 count, count x RGBA
+//! cells    runs of (u32 count, u32 char, RGBA fg, RGBA bg), row-major,
+//!          until width x height cells are covered
+//! ```
+//!
+//! Run-length encoding keeps mostly blank screens small.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_terminal::prelude::*;
+//! use runeforge_terminal::rfc::ConsoleImage;
+//! use runeforge_color::Color;
+//!
+//! let mut title = ConsoleImage::new(40, 10).with_font_hint("cp437_8x8");
+//! title.draw_string(IVec2::new(14, 4), "RUNEFORGE", Color::YELLOW, Color::BLACK);
+//!
+//! let bytes = title.to_bytes().unwrap();
+//! let loaded = ConsoleImage::from_bytes(&bytes).unwrap();
+//! assert_eq!(loaded, title);
+//!
+//! let mut screen = ConsoleImage::new(80, 24);
+//! loaded.blit(&mut screen, IVec2::new(20, 7));
+//! assert_eq!(screen.get(IVec2::new(34, 11)).unwrap().ch, 'R');
+//! ```
+
+use std::{fs, io, path::Path};
+
+use runeforge_color::{quantize::Palette, Color};
+use thiserror::Error;
+
+use crate::prelude::*;
+
+const MAGIC: &[u8; 4] = b"RFC\0";
+const VERSION: u16 = 1;
+const FLAG_FONT: u16 = 1;
+const FLAG_PALETTE: u16 = 2;
+/// Largest console accepted, 1024x1024 cells. A single run can cover the
+/// whole grid, so this bounds what a tiny file can expand into.
+const MAX_CELLS: u64 = 1 << 20;
+
+/// Errors from reading or writing `.rfc` files.
+#[derive(Error, Debug)]
+pub enum RfcError {
+    /// The data doesn't start with the `.rfc` magic bytes.
+    #[error("Not a runeforge console file")]
+    InvalidMagic,
+
+    /// The file was written by an unknown format version.
+    #[error("Unsupported console file version {0}")]
+    UnsupportedVersion(u16),
+
+    /// The data is truncated or inconsistent.
+    #[error("Corrupt console file: {0}")]
+    Corrupt(String),
+
+    /// The image has more cells than a console file can hold.
+    #[error("{width}x{height} console is too large to save")]
+    TooLarge { width: u32, height: u32 },
+
+    /// I/O error.
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+}
+
+impl RfcError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            RfcError::InvalidMagic => "rfc.invalid_magic",
+            RfcError::UnsupportedVersion(_) => "rfc.unsupported_version",
+            RfcError::Corrupt(_) => "rfc.corrupt",
+            RfcError::TooLarge { .. } => "rfc.too_large",
+            RfcError::Io(_) => "rfc.io",
+        }
+    }
+}
+
+/// An off-screen console with metadata, stored as an `.rfc` file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsoleImage {
+    width: u32,
+    height: u32,
+    cells: Vec<Cell>,
+    palette: Option<Palette>,
+    font_hint: Option<String>,
+}
+
+impl ConsoleImage {
+    /// Creates a blank image of `width` x `height` empty cells.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::empty(); (width * height) as usize],
+            palette: None,
+            font_hint: None,
+        }
+    }
+
+    /// Copies every cell of `console` into a new image.
+    pub fn capture<C: Console + ?Sized>(console: &C) -> Self {
+        let mut image = Self::new(console.width(), console.height());
+        for y in 0..image.height as i32 {
+            for x in 0..image.width as i32 {
+                let pos = IVec2::new(x, y);
+                if let Some(cell) = console.get(pos) {
+                    image.set(pos, cell);
+                }
+            }
+        }
+        image
+    }
+
+    /// Records the palette the image was drawn for.
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = Some(palette);
+        self
+    }
+
+    /// Records which font the image was drawn for.
+    pub fn with_font_hint(mut self, hint: impl Into<String>) -> Self {
+        self.font_hint = Some(hint.into());
+        self
+    }
+
+    /// Returns the palette the image was drawn for, if recorded.
+    pub fn palette(&self) -> Option<&Palette> {
+        self.palette.as_ref()
+    }
+
+    /// Returns the font the image was drawn for, if recorded.
+    pub fn font_hint(&self) -> Option<&str> {
+        self.font_hint.as_deref()
+    }
+
+    /// Returns the cells in row-major order.
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    /// Draws the image into `console` with its top-left corner at `offset`.
+    /// Cells falling outside the console are skipped.
+    pub fn blit<C: Console + ?Sized>(&self, console: &mut C, offset: IVec2) {
+        for (i, &cell) in self.cells.iter().enumerate() {
+            let pos = IVec2::new(
+                (i as u32 % self.width) as i32,
+                (i as u32 / self.width) as i32,
+            );
+            console.set(offset + pos, cell);
+        }
+    }
+
+    /// Encodes the image in the `.rfc` format.
+    ///
+    /// A font hint longer than 65535 bytes is cut at the last character that
+    /// fits.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RfcError::TooLarge`] if the image has more than 1024x1024
+    /// cells, which [`from_bytes`](Self::from_bytes) would refuse to load.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, RfcError> {
+        if self.width as u64 * self.height as u64 > MAX_CELLS {
+            return Err(RfcError::TooLarge {
+                width: self.width,
+                height: self.height,
+            });
+        }
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        let mut flags = 0;
+        if self.font_hint.is_some() {
+            flags |= FLAG_FONT;
+        }
+        if self.palette.is_some() {
+            flags |= FLAG_PALETTE;
+        }
+        out.extend_from_slice(&flags.to_le_bytes());
+        out.extend_from_slice(&self.width.to_le_bytes());
+        out.extend_from_slice(&self.height.to_le_bytes());
+        if let Some(hint) = &self.font_hint {
+            let mut len = hint.len().min(u16::MAX as usize);
+            while !hint.is_char_boundary(len) {
+                len -= 1;
+            }
+            let bytes = &hint.as_bytes()[..len];
+            out.extend_from_slice(&(bytes.len() as u16).to_le_bytes());
+            out.extend_from_slice(bytes);
+        }
+        if let Some(palette) = &self.palette {
+            let colors = &palette.colors()[..palette.len().min(u16::MAX as usize)];
+            out.extend_from_slice(&(colors.len() as u16).to_le_bytes());
+            for &color in colors {
+                write_color(&mut out, color);
+            }
+        }
+
+        let mut cells = self.cells.iter().peekable();
+        while let Some(&cell) = cells.next() {
+            let mut run = 1u32;
+            while cells.next_if_eq(&&cell).is_some() {
+                run += 1;
+            }
+            out.extend_from_slice(&run.to_le_bytes());
+            out.extend_from_slice(&(cell.ch as u32).to_le_bytes());
+            write_color(&mut out, cell.fg);
+            write_color(&mut out, cell.bg);
+        }
+        Ok(out)
+    }
+
+    /// Decodes an image from `.rfc` data.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data isn't a supported `.rfc` file or is
+    /// truncated or inconsistent.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, RfcError> {
+        let mut reader = Reader { bytes };
+        if reader.take(4).ok() != Some(&MAGIC[..]) {
+            return Err(RfcError::InvalidMagic);
+        }
+        let version = reader.u16()?;
+        if version != VERSION {
+            return Err(RfcError::UnsupportedVersion(version));
+        }
+        let flags = reader.u16()?;
+        let width = reader.u32()?;
+        let height = reader.u32()?;
+        let total = width as u64 * height as u64;
+        if total > MAX_CELLS {
+            return Err(corrupt(format!(
+                "{}x{} console is too large",
+                width, height
+            )));
+        }
+
+        let font_hint = if flags & FLAG_FONT != 0 {
+            let len = reader.u16()? as usize;
+            let text = std::str::from_utf8(reader.take(len)?)
+                .map_err(|_| corrupt("font hint is not UTF-8"))?;
+            Some(text.to_string())
+        } else {
+            None
+        };
+        let palette = if flags & FLAG_PALETTE != 0 {
+            let count = reader.u16()? as usize;
+            let colors = (0..count)
+                .map(|_| reader.color())
+                .collect::<Result<Vec<_>, _>>()?;
+            Some(Palette::new(colors))
+        } else {
+            None
+        };
+
+        let mut cells = Vec::new();
+        while (cells.len() as u64) < total {
+            let run = reader.u32()? as u64;
+            let code = reader.u32()?;
+            let ch = char::from_u32(code)
+                .ok_or_else(|| corrupt(format!("invalid character U+{:X}", code)))?;
+            let cell = Cell::new(ch, reader.color()?, reader.color()?);
+            if run == 0 || cells.len() as u64 + run > total {
+                return Err(corrupt("cell runs don't match the console size"));
+            }
+            cells.extend(std::iter::repeat_n(cell, run as usize));
+        }
+        if !reader.bytes.is_empty() {
+            return Err(corrupt("trailing data after the cells"));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            cells,
+            palette,
+            font_hint,
+        })
+    }
+
+    /// Writes the image in the `.rfc` format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the image is too large or writing fails.
+    pub fn write_to<W: io::Write>(&self, mut writer: W) -> Result<(), RfcError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Reads an image in the `.rfc` format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails or the data is invalid.
+    pub fn read_from<R: io::Read>(mut reader: R) -> Result<Self, RfcError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Saves the image to an `.rfc` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the image is too large or the file can't be
+    /// written.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), RfcError> {
+        fs::write(path, self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Loads an image from an `.rfc` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or is invalid.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, RfcError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+}
+
+impl Console for ConsoleImage {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn set(&mut self, pos: IVec2, cell: Cell) -> bool {
+        if !self.in_bounds(pos) {
+            return false;
+        }
+        let idx = (pos.y as u32 * self.width + pos.x as u32) as usize;
+        self.cells[idx] = cell;
+        true
+    }
+
+    fn get(&self, pos: IVec2) -> Option<Cell> {
+        if !self.in_bounds(pos) {
+            return None;
+        }
+        let idx = (pos.y as u32 * self.width + pos.x as u32) as usize;
+        self.cells.get(idx).copied()
+    }
+
+    fn clear(&mut self) {
+        self.cells.fill(Cell::empty());
+    }
+
+    fn present(&mut self) {}
+}
+
+fn corrupt(reason: impl Into<String>) -> RfcError {
+    RfcError::Corrupt(reason.into())
+}
+
+fn write_color(out: &mut Vec<u8>, color: Color) {
+    out.extend_from_slice(&[color.r, color.g, color.b, color.a]);
+}
+
+/// A cursor over the input that fails cleanly on truncation.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], RfcError> {
+        if self.bytes.len() < n {
+            return Err(corrupt("unexpected end of data"));
+        }
+        let (head, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn u16(&mut self) -> Result<u16, RfcError> {
+        let b = self.take(2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, RfcError> {
+        let b = self.take(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn color(&mut self) -> Result<Color, RfcError> {
+        let b = self.take(4)?;
+        Ok(Color::rgba(b[0], b[1], b[2], b[3]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> ConsoleImage {
+        let mut image = ConsoleImage::new(12, 4)
+            .with_palette(Palette::ega())
+            .with_font_hint("cp437_8x8");
+        image.draw_box(0, 0, 12, 4, Color::WHITE, Color::BLUE, true);
+        image.draw_string(IVec2::new(2, 1), "Héllo ✓", Color::YELLOW, Color::BLACK);
+        image
+    }
+
+    #[test]
+    fn test_round_trip_and_compression() {
+        let image = sample();
+        let bytes = image.to_bytes().unwrap();
+        assert!(bytes.len() < 12 * 4 * 16);
+        assert_eq!(ConsoleImage::from_bytes(&bytes).unwrap(), image);
+
+        let plain = ConsoleImage::new(80, 25);
+        let bytes = plain.to_bytes().unwrap();
+        assert_eq!(bytes.len(), 16 + 16);
+        assert_eq!(ConsoleImage::from_bytes(&bytes).unwrap(), plain);
+
+        let mut written = Vec::new();
+        image.write_to(&mut written).unwrap();
+        let read = ConsoleImage::read_from(written.as_slice()).unwrap();
+        assert_eq!(read.font_hint(), Some("cp437_8x8"));
+        assert_eq!(read.palette().map(Palette::len), Some(16));
+    }
+
+    #[test]
+    fn test_capture_and_blit() {
+        let image = sample();
+        let copy = ConsoleImage::capture(&image);
+        assert_eq!(copy.cells(), image.cells());
+
+        let mut target = ConsoleImage::new(6, 6);
+        image.blit(&mut target, IVec2::new(-2, 2));
+        assert_eq!(target.get(IVec2::new(0, 3)).unwrap().ch, 'H');
+        assert_eq!(target.get(IVec2::new(0, 0)), Some(Cell::empty()));
+    }
+
+    #[test]
+    fn test_rejects_bad_data() {
+        let bytes = sample().to_bytes().unwrap();
+        assert_eq!(
+            ConsoleImage::from_bytes(b"nope").unwrap_err().code(),
+            "rfc.invalid_magic"
+        );
+        let mut future = bytes.clone();
+        future[4] = 9;
+        assert_eq!(
+            ConsoleImage::from_bytes(&future).unwrap_err().code(),
+            "rfc.unsupported_version"
+        );
+        for len in [10, 20, bytes.len() - 1] {
+            let err = ConsoleImage::from_bytes(&bytes[..len]).unwrap_err();
+            assert_eq!(err.code(), "rfc.corrupt");
+        }
+        let mut extra = bytes;
+        extra.push(0);
+        assert_eq!(
+            ConsoleImage::from_bytes(&extra).unwrap_err().code(),
+            "rfc.corrupt"
+        );
+    }
+
+    #[test]
+    fn test_rejects_huge_console() {
+        // One run covering a 4096x4096 console: 32 bytes that would expand
+        // to 16M cells.
+        let mut bytes = ConsoleImage::new(1, 1).to_bytes().unwrap();
+        bytes[8..12].copy_from_slice(&4096u32.to_le_bytes());
+        bytes[12..16].copy_from_slice(&4096u32.to_le_bytes());
+        bytes[16..20].copy_from_slice(&(4096u32 * 4096).to_le_bytes());
+        assert!(bytes.len() < 40);
+        let err = ConsoleImage::from_bytes(&bytes).unwrap_err();
+        assert_eq!(err.code(), "rfc.corrupt");
+
+        let mut allowed = ConsoleImage::new(1, 1).to_bytes().unwrap();
+        allowed[8..12].copy_from_slice(&1024u32.to_le_bytes());
+        allowed[12..16].copy_from_slice(&1024u32.to_le_bytes());
+        allowed[16..20].copy_from_slice(&(1024u32 * 1024).to_le_bytes());
+        assert_eq!(
+            ConsoleImage::from_bytes(&allowed).unwrap().cells().len(),
+            1 << 20
+        );
+    }
+
+    #[test]
+    fn test_refuses_to_save_huge_console() {
+        let image = ConsoleImage::new(1025, 1024);
+        assert_eq!(image.to_bytes().unwrap_err().code(), "rfc.too_large");
+        assert!(image.write_to(Vec::new()).is_err());
+        assert!(ConsoleImage::new(1024, 1024).to_bytes().is_ok());
+    }
+
+    #[test]
+    fn test_long_font_hint_is_cut_on_char_boundary() {
+        // 'é' is two bytes, so byte 65535 falls in the middle of one.
+        let hint = "é".repeat(40_000);
+        let image = ConsoleImage::new(1, 1).with_font_hint(hint.clone());
+        let loaded = ConsoleImage::from_bytes(&image.to_bytes().unwrap()).unwrap();
+        let cut = loaded.font_hint().unwrap();
+        assert_eq!(cut.len(), 65_534);
+        assert!(hint.starts_with(cut));
+    }
+}
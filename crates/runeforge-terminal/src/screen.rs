@@ -0,0 +1,256 @@
+//! Camera shake and knockback nudges applied when composing the screen.
+//!
+//! [`ScreenEffects`] turns short-lived effects into a whole-cell offset for
+//! the current frame. The game keeps drawing its map, layers and UI at
+//! their usual positions; the offset is only applied when copying them to
+//! the output with [`compose`](ScreenEffects::compose) or
+//! [`LayeredConsole::render_offset`], so no game buffer is ever moved.
+//!
+//! *   [`shake`](ScreenEffects::shake) jitters the view randomly, dying away
+//!     over a number of frames: explosions, earthquakes, heavy hits.
+//! *   [`nudge`](ScreenEffects::nudge) pushes the view in one direction and
+//!     eases it back: the player being knocked back or firing a big gun.
+//!
+//! Jitter is seeded, so replays shake the same way.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_color::Color;
+//! use runeforge_terminal::prelude::*;
+//! use runeforge_terminal::rfc::ConsoleImage;
+//!
+//! let mut map = ConsoleImage::new(20, 10);
+//! map.draw_char(IVec2::new(5, 5), '@', Color::YELLOW, Color::BLACK);
+//!
+//! let mut effects = ScreenEffects::new(7);
+//! effects.nudge(IVec2::new(1, 0), 2, 4);
+//! assert_eq!(effects.offset(), IVec2::new(2, 0));
+//!
+//! let mut screen = ConsoleImage::new(20, 10);
+//! effects.compose(&map, &mut screen, Cell::empty());
+//! assert_eq!(screen.get(IVec2::new(7, 5)).unwrap().ch, '@');
+//!
+//! for _ in 0..4 {
+//!     effects.update();
+//! }
+//! assert!(!effects.is_active());
+//! ```
+
+use crate::prelude::*;
+use crate::shader::hash;
+
+/// A random jitter that decays to nothing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Shake {
+    strength: f32,
+    frames: u32,
+    remaining: u32,
+}
+
+/// A push in one direction that eases back.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Nudge {
+    offset: IVec2,
+    frames: u32,
+    remaining: u32,
+}
+
+/// Temporary screen offsets from camera shake and knockback.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScreenEffects {
+    seed: u32,
+    frame: u32,
+    max_offset: Option<IVec2>,
+    shakes: Vec<Shake>,
+    nudges: Vec<Nudge>,
+}
+
+impl ScreenEffects {
+    /// Creates a controller with no effects running. `seed` fixes the
+    /// shake jitter.
+    pub fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            frame: 0,
+            max_offset: None,
+            shakes: Vec::new(),
+            nudges: Vec::new(),
+        }
+    }
+
+    /// Limits the combined offset to `max` cells on each axis, so stacked
+    /// effects can't throw the view too far.
+    pub fn with_max_offset(mut self, max: IVec2) -> Self {
+        self.max_offset = Some(max.abs());
+        self
+    }
+
+    /// Starts a shake of up to `strength` cells that fades out over
+    /// `frames` frames.
+    pub fn shake(&mut self, strength: f32, frames: u32) {
+        if frames > 0 && strength > 0.0 {
+            self.shakes.push(Shake {
+                strength,
+                frames,
+                remaining: frames,
+            });
+        }
+    }
+
+    /// Pushes the view `distance` cells towards `direction` and eases it
+    /// back over `frames` frames.
+    pub fn nudge(&mut self, direction: IVec2, distance: i32, frames: u32) {
+        if frames > 0 && distance != 0 && direction != IVec2::ZERO {
+            self.nudges.push(Nudge {
+                offset: direction.signum() * distance,
+                frames,
+                remaining: frames,
+            });
+        }
+    }
+
+    /// Advances every effect by one frame and drops finished ones.
+    pub fn update(&mut self) {
+        self.frame = self.frame.wrapping_add(1);
+        for shake in &mut self.shakes {
+            shake.remaining -= 1;
+        }
+        for nudge in &mut self.nudges {
+            nudge.remaining -= 1;
+        }
+        self.shakes.retain(|s| s.remaining > 0);
+        self.nudges.retain(|n| n.remaining > 0);
+    }
+
+    /// Returns true while any effect is running.
+    pub fn is_active(&self) -> bool {
+        !self.shakes.is_empty() || !self.nudges.is_empty()
+    }
+
+    /// Stops every effect at once.
+    pub fn clear(&mut self) {
+        self.shakes.clear();
+        self.nudges.clear();
+    }
+
+    /// Returns the offset, in cells, to draw this frame with.
+    pub fn offset(&self) -> IVec2 {
+        // Shakes fade quadratically, so the tail end is a gentle tremble
+        let amplitude: f32 = self
+            .shakes
+            .iter()
+            .map(|s| {
+                let left = s.remaining as f32 / s.frames as f32;
+                s.strength * left * left
+            })
+            .sum();
+        let jitter = |axis: i32| (hash(self.seed, self.frame as i32, axis) * 2.0 - 1.0) * amplitude;
+        let mut offset = IVec2::new(jitter(0).round() as i32, jitter(1).round() as i32);
+
+        for nudge in &self.nudges {
+            let left = nudge.remaining as f32 / nudge.frames as f32;
+            offset += (nudge.offset.as_vec2() * left).round().as_ivec2();
+        }
+
+        match self.max_offset {
+            Some(max) => offset.clamp(-max, max),
+            None => offset,
+        }
+    }
+
+    /// Copies `source` into `target` shifted by the current offset. Cells
+    /// uncovered by the shift are set to `fill`.
+    pub fn compose<S, T>(&self, source: &S, target: &mut T, fill: Cell)
+    where
+        S: Console + ?Sized,
+        T: Console + ?Sized,
+    {
+        let offset = self.offset();
+        for y in 0..target.height() as i32 {
+            for x in 0..target.width() as i32 {
+                let pos = IVec2::new(x, y);
+                target.set(pos, source.get(pos - offset).unwrap_or(fill));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rfc::ConsoleImage;
+    use runeforge_color::Color;
+
+    #[test]
+    fn test_shake_decays() {
+        let mut effects = ScreenEffects::new(1);
+        effects.shake(3.0, 10);
+        let mut moved = false;
+        for _ in 0..10 {
+            let offset = effects.offset();
+            assert!(offset.x.abs() <= 3 && offset.y.abs() <= 3);
+            moved |= offset != IVec2::ZERO;
+            effects.update();
+        }
+        assert!(moved);
+        assert!(!effects.is_active());
+        assert_eq!(effects.offset(), IVec2::ZERO);
+    }
+
+    #[test]
+    fn test_shake_is_seeded() {
+        let run = |seed| {
+            let mut effects = ScreenEffects::new(seed);
+            effects.shake(4.0, 6);
+            (0..6)
+                .map(|_| {
+                    let offset = effects.offset();
+                    effects.update();
+                    offset
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(run(5), run(5));
+    }
+
+    #[test]
+    fn test_nudge_eases_back() {
+        let mut effects = ScreenEffects::new(0);
+        effects.nudge(IVec2::new(0, -5), 4, 4);
+        let offsets: Vec<IVec2> = (0..5)
+            .map(|_| {
+                let offset = effects.offset();
+                effects.update();
+                offset
+            })
+            .collect();
+        assert_eq!(
+            offsets,
+            [
+                IVec2::new(0, -4),
+                IVec2::new(0, -3),
+                IVec2::new(0, -2),
+                IVec2::new(0, -1),
+                IVec2::ZERO
+            ]
+        );
+    }
+
+    #[test]
+    fn test_max_offset_and_compose() {
+        let mut effects = ScreenEffects::new(0).with_max_offset(IVec2::new(1, 1));
+        effects.nudge(IVec2::new(-1, 0), 3, 2);
+        effects.nudge(IVec2::new(-1, 0), 3, 2);
+        assert_eq!(effects.offset(), IVec2::new(-1, 0));
+
+        let mut map = ConsoleImage::new(3, 1);
+        map.draw_string(IVec2::ZERO, "abc", Color::WHITE, Color::BLACK);
+        let mut screen = ConsoleImage::new(3, 1);
+        let fill = Cell::new('x', Color::WHITE, Color::BLACK);
+        effects.compose(&map, &mut screen, fill);
+        let row: String = screen.cells().iter().map(|c| c.ch).collect();
+        assert_eq!(row, "bcx");
+        assert_eq!(map.get(IVec2::ZERO).unwrap().ch, 'a');
+    }
+}
@@ -0,0 +1,241 @@
+//! Per-tile color variation so large areas of floor don't look flat.
+//!
+//! A [`TileShader`] rewrites a cell based on its world position just before
+//! it is shown. [`NoiseTexture`] is the built-in one: it lightens and darkens
+//! backgrounds with seeded noise, as per-tile speckle for grass and sand or
+//! as soft patches for stone mottling. Because the noise is keyed by world
+//! position rather than screen position, the texture stays put when the
+//! camera scrolls.
+//!
+//! Apply a shader to a finished console with [`shade_console`], or to one
+//! layer of a [`LayeredConsole`] with
+//! [`shade_layer`](LayeredConsole::shade_layer) so actors and effects on the
+//! layers above stay untouched. Plain functions and closures of the form
+//! `fn(pos, base_cell) -> Cell` are shaders too.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_color::Color;
+//! use runeforge_terminal::prelude::*;
+//!
+//! let grass = Cell::new('.', Color::rgb(40, 160, 40), Color::rgb(20, 90, 20));
+//! let mut layers = LayeredConsole::new(16, 8, 4);
+//! layers.fill_layer(LayeredConsole::TERRAIN, grass);
+//!
+//! // The camera looks at world tiles starting at (100, 40)
+//! let speckle = NoiseTexture::speckle(7);
+//! layers.shade_layer(LayeredConsole::TERRAIN, IVec2::new(100, 40), &speckle);
+//!
+//! let a = layers.composite(IVec2::new(0, 0)).unwrap();
+//! assert_eq!(a, speckle.shade(IVec2::new(100, 40), grass));
+//! assert_eq!(a.fg, grass.fg);
+//!
+//! // Any function works as a shader
+//! let checker = |pos: IVec2, cell: Cell| if (pos.x + pos.y) % 2 == 0 { cell } else { cell.with_bg(Color::BLACK) };
+//! assert_eq!(checker.shade(IVec2::new(1, 0), grass).bg, Color::BLACK);
+//! ```
+
+use runeforge_color::Color;
+
+use crate::prelude::*;
+
+/// Rewrites a cell based on its world position.
+pub trait TileShader {
+    /// Returns the cell to show at world position `pos` in place of `cell`.
+    fn shade(&self, pos: IVec2, cell: Cell) -> Cell;
+}
+
+impl<F: Fn(IVec2, Cell) -> Cell> TileShader for F {
+    fn shade(&self, pos: IVec2, cell: Cell) -> Cell {
+        self(pos, cell)
+    }
+}
+
+/// Lightens and darkens cell colors with seeded value noise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoiseTexture {
+    /// Noise seed; different seeds give unrelated textures.
+    pub seed: u32,
+    /// Size of a noise feature in tiles. 1 or less varies every tile on its
+    /// own; larger values give smooth patches.
+    pub scale: f32,
+    /// Largest change, as a fraction of the way to white or black.
+    pub amount: f32,
+    /// Whether foregrounds vary too, not just backgrounds.
+    pub foreground: bool,
+}
+
+impl Default for NoiseTexture {
+    /// Seed 0, soft 4-tile patches, up to 10% lighter or darker, background
+    /// only.
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            scale: 4.0,
+            amount: 0.1,
+            foreground: false,
+        }
+    }
+}
+
+impl NoiseTexture {
+    /// Creates the default texture with a seed.
+    pub fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            ..Self::default()
+        }
+    }
+
+    /// Independent per-tile flecks, for grass, sand and dirt.
+    pub fn speckle(seed: u32) -> Self {
+        Self::new(seed).with_scale(1.0).with_amount(0.08)
+    }
+
+    /// Broad, blotchy patches, for stone and cave floors.
+    pub fn mottle(seed: u32) -> Self {
+        Self::new(seed).with_scale(5.0).with_amount(0.15)
+    }
+
+    /// Sets the feature size in tiles.
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Sets the largest lightening or darkening.
+    pub fn with_amount(mut self, amount: f32) -> Self {
+        self.amount = amount;
+        self
+    }
+
+    /// Sets whether foregrounds vary too.
+    pub fn with_foreground(mut self, foreground: bool) -> Self {
+        self.foreground = foreground;
+        self
+    }
+
+    /// Returns the noise at a world position, from -1.0 to 1.0.
+    pub fn sample(&self, pos: IVec2) -> f32 {
+        if self.scale <= 1.0 {
+            return hash(self.seed, pos.x, pos.y) * 2.0 - 1.0;
+        }
+        let x = pos.x as f32 / self.scale;
+        let y = pos.y as f32 / self.scale;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (smooth(x - x0), smooth(y - y0));
+        let (ix, iy) = (x0 as i32, y0 as i32);
+        let corner = |dx, dy| hash(self.seed, ix + dx, iy + dy);
+        let top = corner(0, 0) + (corner(1, 0) - corner(0, 0)) * tx;
+        let bottom = corner(0, 1) + (corner(1, 1) - corner(0, 1)) * tx;
+        (top + (bottom - top) * ty) * 2.0 - 1.0
+    }
+}
+
+impl TileShader for NoiseTexture {
+    fn shade(&self, pos: IVec2, cell: Cell) -> Cell {
+        let v = self.sample(pos) * self.amount;
+        let fg = if self.foreground {
+            vary(cell.fg, v)
+        } else {
+            cell.fg
+        };
+        Cell {
+            fg,
+            bg: vary(cell.bg, v),
+            ..cell
+        }
+    }
+}
+
+/// Shades every cell of `console`. `origin` is the world position shown in
+/// the top-left cell, usually the camera offset.
+pub fn shade_console<C: Console + ?Sized>(
+    console: &mut C,
+    origin: IVec2,
+    shader: &(impl TileShader + ?Sized),
+) {
+    for y in 0..console.height() as i32 {
+        for x in 0..console.width() as i32 {
+            let pos = IVec2::new(x, y);
+            if let Some(cell) = console.get(pos) {
+                console.set(pos, shader.shade(origin + pos, cell));
+            }
+        }
+    }
+}
+
+/// Moves `color` toward white for positive `v` and black for negative `v`,
+/// keeping its alpha.
+fn vary(color: Color, v: f32) -> Color {
+    let target = if v >= 0.0 { Color::WHITE } else { Color::BLACK };
+    Color {
+        a: color.a,
+        ..color.lerp(target, v.abs().min(1.0))
+    }
+}
+
+/// Hashes a seeded position to `0.0..1.0`.
+pub(crate) fn hash(seed: u32, x: i32, y: i32) -> f32 {
+    let mut h = seed
+        .wrapping_mul(0x9E37_79B9)
+        .wrapping_add((x as u32).wrapping_mul(0x85EB_CA6B))
+        .wrapping_add((y as u32).wrapping_mul(0xC2B2_AE35));
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7FEB_352D);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x846C_A68B);
+    h ^= h >> 16;
+    (h >> 8) as f32 / (1u32 << 24) as f32
+}
+
+fn smooth(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rfc::ConsoleImage;
+
+    #[test]
+    fn test_noise_is_stable_and_bounded() {
+        let texture = NoiseTexture::mottle(3);
+        let base = Cell::new('.', Color::GRAY, Color::grayscale(100));
+        let mut seen = std::collections::HashSet::new();
+        for y in -20..20 {
+            for x in -20..20 {
+                let pos = IVec2::new(x, y);
+                let v = texture.sample(pos);
+                assert!((-1.0..=1.0).contains(&v));
+                assert_eq!(v, texture.sample(pos));
+                let shaded = texture.shade(pos, base);
+                assert!(shaded.bg.r.abs_diff(100) <= 24);
+                assert_eq!(shaded.fg, Color::GRAY);
+                seen.insert(shaded.bg);
+            }
+        }
+        assert!(seen.len() > 10);
+        assert_ne!(
+            NoiseTexture::speckle(1).sample(IVec2::ZERO),
+            NoiseTexture::speckle(2).sample(IVec2::ZERO)
+        );
+    }
+
+    #[test]
+    fn test_shade_console_uses_world_positions() {
+        let texture = NoiseTexture::speckle(9).with_foreground(true);
+        let base = Cell::new('#', Color::grayscale(150), Color::grayscale(60));
+        let mut console = ConsoleImage::new(3, 2);
+        for y in 0..2 {
+            for x in 0..3 {
+                console.set(IVec2::new(x, y), base);
+            }
+        }
+        shade_console(&mut console, IVec2::new(-5, 10), &texture);
+        let expected = texture.shade(IVec2::new(-3, 11), base);
+        assert_eq!(console.get(IVec2::new(2, 1)), Some(expected));
+        assert_ne!(expected.fg, base.fg);
+    }
+}
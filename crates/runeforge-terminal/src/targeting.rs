@@ -0,0 +1,144 @@
+//! Overlay for aiming spells and ranged attacks.
+//!
+//! While the player aims, the map should show where the effect would go and
+//! what it would hit. [`draw_targeting`] tints the affected tiles, marks the
+//! path the effect travels and highlights the cursor, in one color when the
+//! aimed tile can be reached and another when it can't. It takes plain
+//! positions, so it works with `runeforge-fov`'s `TargetArea` (its `path`,
+//! `affected` and `reaches_target` fields) or any other targeting code.
+//!
+//! Positions are in console coordinates; translate world positions by the
+//! camera offset first.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_terminal::prelude::*;
+//! use runeforge_terminal::rfc::ConsoleImage;
+//!
+//! let mut console = ConsoleImage::new(8, 3);
+//! let path = [IVec2::new(1, 1), IVec2::new(2, 1)];
+//! let area = [IVec2::new(3, 1), IVec2::new(4, 1), IVec2::new(3, 0)];
+//! let style = TargetingStyle::default();
+//!
+//! draw_targeting(&mut console, &path, &area, IVec2::new(3, 1), true, &style);
+//! assert_eq!(console.get(IVec2::new(1, 1)).unwrap().ch, '*');
+//! assert_eq!(console.get(IVec2::new(4, 1)).unwrap().bg, style.area_bg);
+//! assert_eq!(console.get(IVec2::new(3, 1)).unwrap().bg, style.cursor_bg);
+//! ```
+
+use runeforge_color::Color;
+
+use crate::prelude::*;
+
+/// Colors and glyphs for [`draw_targeting`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TargetingStyle {
+    /// Glyph drawn along the path, or `None` to keep the map's glyphs.
+    pub path_glyph: Option<char>,
+    /// Foreground of the path glyphs.
+    pub path_fg: Color,
+    /// Background of affected tiles.
+    pub area_bg: Color,
+    /// Background of the cursor when the target can be reached.
+    pub cursor_bg: Color,
+    /// Background of the cursor when it can't.
+    pub blocked_bg: Color,
+}
+
+impl Default for TargetingStyle {
+    /// Yellow `*` path, dark orange area, green cursor that turns red when
+    /// blocked.
+    fn default() -> Self {
+        Self {
+            path_glyph: Some('*'),
+            path_fg: Color::rgb(255, 220, 0),
+            area_bg: Color::rgb(120, 60, 0),
+            cursor_bg: Color::rgb(0, 140, 60),
+            blocked_bg: Color::rgb(160, 20, 20),
+        }
+    }
+}
+
+impl TargetingStyle {
+    /// Creates the default style.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the path glyph, or `None` to keep the map's glyphs.
+    pub fn with_path_glyph(mut self, glyph: Option<char>) -> Self {
+        self.path_glyph = glyph;
+        self
+    }
+
+    /// Sets the path color.
+    pub fn with_path_fg(mut self, fg: Color) -> Self {
+        self.path_fg = fg;
+        self
+    }
+
+    /// Sets the background of affected tiles.
+    pub fn with_area_bg(mut self, bg: Color) -> Self {
+        self.area_bg = bg;
+        self
+    }
+
+    /// Sets the cursor backgrounds for reachable and blocked targets.
+    pub fn with_cursor_bg(mut self, reachable: Color, blocked: Color) -> Self {
+        self.cursor_bg = reachable;
+        self.blocked_bg = blocked;
+        self
+    }
+}
+
+/// Draws a targeting overlay over the console: the `affected` tiles are
+/// tinted, the `path` marked on top, and the `cursor` highlighted according
+/// to `reachable`. Positions outside the console are skipped.
+pub fn draw_targeting<C: Console + ?Sized>(
+    console: &mut C,
+    path: &[IVec2],
+    affected: &[IVec2],
+    cursor: IVec2,
+    reachable: bool,
+    style: &TargetingStyle,
+) {
+    for &pos in affected {
+        console.set_bg(pos, style.area_bg);
+    }
+    for &pos in path {
+        let Some(existing) = console.get(pos) else {
+            continue;
+        };
+        let ch = style.path_glyph.unwrap_or(existing.ch);
+        console.set(pos, Cell::new(ch, style.path_fg, existing.bg));
+    }
+    let cursor_bg = if reachable {
+        style.cursor_bg
+    } else {
+        style.blocked_bg
+    };
+    console.set_bg(cursor, cursor_bg);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rfc::ConsoleImage;
+
+    #[test]
+    fn test_blocked_cursor_and_kept_glyphs() {
+        let mut console = ConsoleImage::new(4, 1);
+        console.set(IVec2::new(1, 0), Cell::new('g', Color::WHITE, Color::BLACK));
+        let style = TargetingStyle::new().with_path_glyph(None);
+        let path = [IVec2::new(1, 0), IVec2::new(9, 0)];
+        draw_targeting(&mut console, &path, &path, IVec2::new(2, 0), false, &style);
+
+        let cell = console.get(IVec2::new(1, 0)).unwrap();
+        assert_eq!(
+            (cell.ch, cell.fg, cell.bg),
+            ('g', style.path_fg, style.area_bg)
+        );
+        assert_eq!(console.get(IVec2::new(2, 0)).unwrap().bg, style.blocked_bg);
+    }
+}
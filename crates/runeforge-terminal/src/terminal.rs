@@ -25,6 +25,9 @@ pub struct Terminal {
     width: u32,
     height: u32,
     cells: Vec<Cell>,
+    /// `None` until the cursor is first used, so plain output keeps the
+    /// terminal's own cursor handling.
+    cursor: Option<Cursor>,
 }
 
 impl Terminal {
@@ -43,6 +46,7 @@ impl Terminal {
             width,
             height,
             cells,
+            cursor: None,
         }
     }
     /// Returns the width of the terminal.
@@ -98,6 +102,61 @@ impl Terminal {
         count
     }
 
+    /// Returns the cursor state.
+    pub fn cursor(&self) -> Cursor {
+        self.cursor.unwrap_or_default()
+    }
+
+    /// Replaces the cursor state, shown natively on the next [`present`](Self::present).
+    ///
+    /// Terminals pick their own blink speed, so only whether
+    /// [`Cursor::blink_rate`] is set matters here.
+    pub fn set_cursor_state(&mut self, cursor: Cursor) {
+        self.cursor = Some(cursor);
+    }
+
+    /// Moves the cursor to a cell.
+    pub fn set_cursor(&mut self, pos: IVec2) {
+        self.set_cursor_state(Cursor {
+            pos: Some(pos),
+            ..self.cursor()
+        });
+    }
+
+    /// Removes the cursor, hiding it on the next present.
+    pub fn clear_cursor(&mut self) {
+        self.set_cursor_state(Cursor {
+            pos: None,
+            ..self.cursor()
+        });
+    }
+
+    /// Shows or hides the cursor without forgetting its position.
+    pub fn cursor_visible(&mut self, visible: bool) {
+        self.set_cursor_state(self.cursor().with_visible(visible));
+    }
+
+    /// Switches between a blinking (`Some`) and a steady (`None`) cursor.
+    pub fn set_cursor_blink(&mut self, rate: Option<std::time::Duration>) {
+        self.set_cursor_state(self.cursor().with_blink_rate(rate));
+    }
+
+    /// Returns the escape codes that place and style the native cursor after
+    /// a frame has been drawn.
+    fn cursor_escape(&self) -> String {
+        let Some(cursor) = self.cursor else {
+            return String::new();
+        };
+        match cursor.pos.filter(|_| cursor.visible) {
+            Some(pos) => {
+                // DECSCUSR: 1 is a blinking block, 2 a steady block
+                let shape = if cursor.blink_rate.is_some() { 1 } else { 2 };
+                format!("\x1b[{};{}H\x1b[{} q\x1b[?25h", pos.y + 1, pos.x + 1, shape)
+            }
+            None => "\x1b[?25l".to_string(),
+        }
+    }
+
     /// Gets a mutable reference to the cell at the given position.
     fn get_cell_mut(&mut self, pos: IVec2) -> Option<&mut Cell> {
         if pos.x < 0 || pos.y < 0 || pos.x >= self.width as i32 || pos.y >= self.height as i32 {
@@ -125,32 +184,9 @@ impl Terminal {
         // Clear screen and move cursor to top-left
         write!(stdout, "\x1b[2J\x1b[H")?;
 
-        let mut last_fg = Color::WHITE;
-        let mut last_bg = Color::BLACK;
-
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let pos = IVec2::new(x as i32, y as i32);
-                let cell = self.get_cell(pos).unwrap();
-
-                // Only change colors if they're different from the last cell
-                if cell.fg != last_fg || cell.bg != last_bg {
-                    write!(
-                        stdout,
-                        "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m",
-                        cell.fg.r, cell.fg.g, cell.fg.b, cell.bg.r, cell.bg.g, cell.bg.b
-                    )?;
-                    last_fg = cell.fg;
-                    last_bg = cell.bg;
-                }
-
-                write!(stdout, "{}", cell.ch)?;
-            }
-            writeln!(stdout)?;
-        }
+        write_ansi_cells(&mut stdout, self.width, &self.cells)?;
 
-        // Reset colors
-        write!(stdout, "\x1b[0m")?;
+        write!(stdout, "{}", self.cursor_escape())?;
         stdout.flush()?;
 
         Ok(())
@@ -185,6 +221,38 @@ impl Terminal {
     }
 }
 
+/// Writes rows of cells as 24-bit ANSI colored text, one line per row, and
+/// resets the colors afterwards.
+pub(crate) fn write_ansi_cells<W: Write>(
+    out: &mut W,
+    width: u32,
+    cells: &[Cell],
+) -> io::Result<()> {
+    let mut last_fg = Color::WHITE;
+    let mut last_bg = Color::BLACK;
+
+    for row in cells.chunks(width.max(1) as usize) {
+        for cell in row {
+            // Only change colors if they're different from the last cell
+            if cell.fg != last_fg || cell.bg != last_bg {
+                write!(
+                    out,
+                    "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m",
+                    cell.fg.r, cell.fg.g, cell.fg.b, cell.bg.r, cell.bg.g, cell.bg.b
+                )?;
+                last_fg = cell.fg;
+                last_bg = cell.bg;
+            }
+
+            write!(out, "{}", cell.ch)?;
+        }
+        writeln!(out)?;
+    }
+
+    // Reset colors
+    write!(out, "\x1b[0m")
+}
+
 impl Drop for Terminal {
     fn drop(&mut self) {
         // Ensure cursor is visible when terminal is dropped
@@ -230,6 +298,22 @@ mod tests {
         assert!(term.get_cell(IVec2::new(100, 100)).is_none());
     }
 
+    #[test]
+    fn test_cursor_escape() {
+        let mut term = Terminal::new(10, 10);
+        assert_eq!(term.cursor_escape(), "");
+
+        term.set_cursor(IVec2::new(3, 1));
+        assert_eq!(term.cursor_escape(), "\x1b[2;4H\x1b[1 q\x1b[?25h");
+
+        term.set_cursor_blink(None);
+        assert_eq!(term.cursor_escape(), "\x1b[2;4H\x1b[2 q\x1b[?25h");
+
+        term.cursor_visible(false);
+        assert_eq!(term.cursor_escape(), "\x1b[?25l");
+        assert_eq!(term.cursor().pos, Some(IVec2::new(3, 1)));
+    }
+
     #[test]
     fn test_clear() {
         let mut term = Terminal::new(10, 10);
@@ -0,0 +1,308 @@
+//! Screen transitions between two console snapshots.
+//!
+//! A [`Transition`] interpolates from one [`ConsoleImage`] to another over a
+//! fixed number of frames, for level changes, opening menus and the like.
+//! Capture the old and new screens with [`ConsoleImage::capture`], then
+//! draw one frame per tick until the transition finishes:
+//!
+//! *   [`TransitionEffect::FadeThroughBlack`] darkens the old screen to black
+//!     and brightens the new one out of it.
+//! *   [`TransitionEffect::Wipe`] sweeps the new screen in from one edge.
+//! *   [`TransitionEffect::Dissolve`] swaps cells over in a random order.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_color::Color;
+//! use runeforge_terminal::prelude::*;
+//! use runeforge_terminal::rfc::ConsoleImage;
+//!
+//! let mut old = ConsoleImage::new(10, 3);
+//! old.fill_rect(0, 0, 10, 3, Cell::new('#', Color::GRAY, Color::BLACK));
+//! let mut new = ConsoleImage::new(10, 3);
+//! new.draw_string(IVec2::new(2, 1), "Depth 2", Color::WHITE, Color::BLACK);
+//!
+//! let mut screen = ConsoleImage::new(10, 3);
+//! let mut transition = Transition::new(old, new, TransitionEffect::Wipe(WipeDirection::Right), 10);
+//! while !transition.is_finished() {
+//!     transition.advance();
+//!     transition.draw(&mut screen);
+//! }
+//! assert_eq!(screen.get(IVec2::new(2, 1)).unwrap().ch, 'D');
+//! ```
+
+use runeforge_color::Color;
+
+use crate::prelude::*;
+use crate::rfc::ConsoleImage;
+use crate::shader::hash;
+
+/// The edge a wipe moves towards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WipeDirection {
+    /// From the right edge towards the left.
+    Left,
+    /// From the left edge towards the right.
+    Right,
+    /// From the bottom edge towards the top.
+    Up,
+    /// From the top edge towards the bottom.
+    Down,
+}
+
+/// How a [`Transition`] moves from the old screen to the new one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransitionEffect {
+    /// Fades the old screen's colors to black over the first half, then the
+    /// new screen's up from black over the second.
+    FadeThroughBlack,
+    /// Reveals the new screen behind an edge sweeping in `direction`.
+    Wipe(WipeDirection),
+    /// Switches each cell at a random moment, fixed by `seed`.
+    Dissolve {
+        /// Seed for the order in which cells switch.
+        seed: u32,
+    },
+}
+
+/// An interpolation between two console snapshots over a number of frames.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition {
+    from: ConsoleImage,
+    to: ConsoleImage,
+    effect: TransitionEffect,
+    frames: u32,
+    frame: u32,
+}
+
+impl Transition {
+    /// Creates a transition from `from` to `to` lasting `frames` frames,
+    /// starting on the old screen.
+    pub fn new(
+        from: ConsoleImage,
+        to: ConsoleImage,
+        effect: TransitionEffect,
+        frames: u32,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            effect,
+            frames: frames.max(1),
+            frame: 0,
+        }
+    }
+
+    /// Returns the effect.
+    pub fn effect(&self) -> TransitionEffect {
+        self.effect
+    }
+
+    /// Returns the current frame, from 0 to [`frames`](Self::frames).
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    /// Returns the length of the transition in frames.
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// Returns how far along the transition is, from 0.0 to 1.0.
+    pub fn progress(&self) -> f32 {
+        self.frame as f32 / self.frames as f32
+    }
+
+    /// Returns true once the last frame, showing only the new screen, has
+    /// been reached.
+    pub fn is_finished(&self) -> bool {
+        self.frame >= self.frames
+    }
+
+    /// Moves to the next frame. Returns false if the transition was already
+    /// finished.
+    pub fn advance(&mut self) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+        self.frame += 1;
+        true
+    }
+
+    /// Goes back to the first frame.
+    pub fn restart(&mut self) {
+        self.frame = 0;
+    }
+
+    /// Returns the cell shown at `pos` at progress `t` (0.0 to 1.0).
+    ///
+    /// Positions outside one of the snapshots count as empty cells there.
+    pub fn cell_at(&self, pos: IVec2, t: f32) -> Cell {
+        let t = t.clamp(0.0, 1.0);
+        let from = self.from.get(pos).unwrap_or_else(Cell::empty);
+        let to = self.to.get(pos).unwrap_or_else(Cell::empty);
+        let (width, height) = self.size();
+        match self.effect {
+            TransitionEffect::FadeThroughBlack => {
+                if t < 0.5 {
+                    darken(from, t * 2.0)
+                } else {
+                    darken(to, 2.0 - t * 2.0)
+                }
+            }
+            TransitionEffect::Wipe(direction) => {
+                // Cell centers, measured from the edge the wipe starts at
+                let along = |i: i32, len: u32| (i as f32 + 0.5) / len.max(1) as f32;
+                let reached = match direction {
+                    WipeDirection::Right => along(pos.x, width),
+                    WipeDirection::Left => 1.0 - along(pos.x, width),
+                    WipeDirection::Down => along(pos.y, height),
+                    WipeDirection::Up => 1.0 - along(pos.y, height),
+                };
+                if reached < t {
+                    to
+                } else {
+                    from
+                }
+            }
+            TransitionEffect::Dissolve { seed } => {
+                if hash(seed, pos.x, pos.y) < t {
+                    to
+                } else {
+                    from
+                }
+            }
+        }
+    }
+
+    /// Draws the current frame into `console` at its top-left corner.
+    pub fn draw<C: Console + ?Sized>(&self, console: &mut C) {
+        self.draw_at(console, self.progress());
+    }
+
+    /// Draws the transition at progress `t` (0.0 to 1.0) into `console`.
+    pub fn draw_at<C: Console + ?Sized>(&self, console: &mut C, t: f32) {
+        let (width, height) = self.size();
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                let pos = IVec2::new(x, y);
+                console.set(pos, self.cell_at(pos, t));
+            }
+        }
+    }
+
+    /// The area covered by either snapshot.
+    fn size(&self) -> (u32, u32) {
+        (
+            self.from.width().max(self.to.width()),
+            self.from.height().max(self.to.height()),
+        )
+    }
+}
+
+/// Blends both of a cell's colors towards black by `amount`.
+fn darken(cell: Cell, amount: f32) -> Cell {
+    Cell::new(
+        cell.ch,
+        cell.fg.lerp(Color::BLACK, amount),
+        cell.bg.lerp(Color::BLACK, amount),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screens() -> (ConsoleImage, ConsoleImage) {
+        let mut from = ConsoleImage::new(4, 2);
+        from.fill_rect(0, 0, 4, 2, Cell::new('a', Color::WHITE, Color::BLUE));
+        let mut to = ConsoleImage::new(4, 2);
+        to.fill_rect(0, 0, 4, 2, Cell::new('b', Color::RED, Color::GREEN));
+        (from, to)
+    }
+
+    #[test]
+    fn test_endpoints_show_each_screen() {
+        let (from, to) = screens();
+        for effect in [
+            TransitionEffect::FadeThroughBlack,
+            TransitionEffect::Wipe(WipeDirection::Up),
+            TransitionEffect::Dissolve { seed: 7 },
+        ] {
+            let transition = Transition::new(from.clone(), to.clone(), effect, 8);
+            let mut screen = ConsoleImage::new(4, 2);
+            transition.draw_at(&mut screen, 0.0);
+            assert_eq!(screen, from, "{effect:?}");
+            transition.draw_at(&mut screen, 1.0);
+            assert_eq!(screen, to, "{effect:?}");
+        }
+    }
+
+    #[test]
+    fn test_fade_passes_through_black() {
+        let (from, to) = screens();
+        let transition = Transition::new(from, to, TransitionEffect::FadeThroughBlack, 4);
+        let quarter = transition.cell_at(IVec2::ZERO, 0.25);
+        assert_eq!(quarter.ch, 'a');
+        assert_eq!(quarter.bg, Color::BLUE.lerp(Color::BLACK, 0.5));
+
+        let middle = transition.cell_at(IVec2::ZERO, 0.5);
+        assert_eq!(middle.ch, 'b');
+        assert_eq!((middle.fg, middle.bg), (Color::BLACK, Color::BLACK));
+    }
+
+    #[test]
+    fn test_wipe_direction() {
+        let (from, to) = screens();
+        let wipe = |direction| {
+            Transition::new(
+                from.clone(),
+                to.clone(),
+                TransitionEffect::Wipe(direction),
+                2,
+            )
+        };
+        let right = wipe(WipeDirection::Right);
+        assert_eq!(right.cell_at(IVec2::new(1, 0), 0.5).ch, 'b');
+        assert_eq!(right.cell_at(IVec2::new(2, 0), 0.5).ch, 'a');
+
+        let left = wipe(WipeDirection::Left);
+        assert_eq!(left.cell_at(IVec2::new(1, 0), 0.5).ch, 'a');
+        assert_eq!(left.cell_at(IVec2::new(2, 0), 0.5).ch, 'b');
+
+        let down = wipe(WipeDirection::Down);
+        assert_eq!(down.cell_at(IVec2::new(0, 0), 0.5).ch, 'b');
+        assert_eq!(down.cell_at(IVec2::new(0, 1), 0.5).ch, 'a');
+    }
+
+    #[test]
+    fn test_dissolve_only_adds_new_cells() {
+        let (from, to) = screens();
+        let transition = Transition::new(from, to, TransitionEffect::Dissolve { seed: 3 }, 10);
+        let mut switched = Vec::new();
+        for step in 0..=10 {
+            let t = step as f32 / 10.0;
+            let now: Vec<IVec2> = (0..8)
+                .map(|i| IVec2::new(i % 4, i / 4))
+                .filter(|&pos| transition.cell_at(pos, t).ch == 'b')
+                .collect();
+            assert!(switched.iter().all(|pos| now.contains(pos)));
+            switched = now;
+        }
+        assert_eq!(switched.len(), 8);
+    }
+
+    #[test]
+    fn test_advance() {
+        let (from, to) = screens();
+        let mut transition = Transition::new(from, to, TransitionEffect::FadeThroughBlack, 2);
+        assert_eq!(transition.progress(), 0.0);
+        assert!(transition.advance());
+        assert!(transition.advance());
+        assert!(transition.is_finished());
+        assert!(!transition.advance());
+        assert_eq!(transition.frame(), 2);
+        transition.restart();
+        assert_eq!(transition.progress(), 0.0);
+    }
+}
@@ -0,0 +1,404 @@
+//! Rain, snow and fog driven by the turn clock.
+//!
+//! A [`Climate`] says which weather a map or biome gets, how often and for
+//! how long, and what each kind does: how far it lets the player see, how
+//! it tints the ambient light and which particles it draws. A [`Weather`]
+//! runs a climate: [`advance`](Weather::advance) it with the game's turns and
+//! it changes on its own, then
+//! - scale the FOV radius with [`sight_radius`](Weather::sight_radius)
+//! - tint the [`AmbientLight`](crate::ambient::AmbientLight) color with
+//!   [`tint`](Weather::tint)
+//! - step and [`draw`](Weather::draw) its particles over the map each frame
+//!
+//! Changes are seeded, so the same seed gives the same weather.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_color::Color;
+//! use runeforge_terminal::prelude::*;
+//! use runeforge_terminal::rfc::ConsoleImage;
+//!
+//! let mut weather = Weather::new(Climate::temperate(), 42);
+//! weather.set(WeatherKind::Rain, 50);
+//! assert_eq!(weather.sight_radius(12), 9);
+//!
+//! let mut console = ConsoleImage::new(40, 20);
+//! for _ in 0..5 {
+//!     weather.step_particles(40, 20);
+//! }
+//! weather.draw(&mut console);
+//! assert!(weather.particle_count() > 0);
+//!
+//! // After the rain, the climate picks what comes next
+//! weather.advance(50);
+//! assert!(weather.remaining() >= 100);
+//! ```
+
+use runeforge_color::Color;
+use runeforge_random::splitmix::SplitMix64;
+
+use crate::prelude::*;
+
+/// A kind of weather.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum WeatherKind {
+    /// No weather effects.
+    #[default]
+    Clear,
+    /// Falling rain that shortens sight a little.
+    Rain,
+    /// Drifting snow.
+    Snow,
+    /// Thick fog that shortens sight a lot, without particles.
+    Fog,
+}
+
+/// Particles drawn over the map while some weather lasts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Particles {
+    /// Glyph of each particle.
+    pub glyph: char,
+    /// Color of each particle.
+    pub fg: Color,
+    /// Fraction of the top row that spawns a particle each step.
+    pub density: f32,
+    /// How far each particle moves per step, in cells.
+    pub velocity: IVec2,
+}
+
+/// What one kind of weather does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeatherEffect {
+    /// Multiplier for the FOV radius.
+    pub sight: f32,
+    /// Color multiplied over the ambient light.
+    pub tint: Color,
+    /// Particles to draw, if any.
+    pub particles: Option<Particles>,
+}
+
+impl WeatherEffect {
+    /// The built-in effect of a kind of weather.
+    pub fn of(kind: WeatherKind) -> Self {
+        match kind {
+            WeatherKind::Clear => Self {
+                sight: 1.0,
+                tint: Color::WHITE,
+                particles: None,
+            },
+            WeatherKind::Rain => Self {
+                sight: 0.75,
+                tint: Color::rgb(190, 200, 220),
+                particles: Some(Particles {
+                    glyph: '|',
+                    fg: Color::rgb(90, 120, 200),
+                    density: 0.15,
+                    velocity: IVec2::new(0, 2),
+                }),
+            },
+            WeatherKind::Snow => Self {
+                sight: 0.6,
+                tint: Color::rgb(225, 230, 240),
+                particles: Some(Particles {
+                    glyph: '*',
+                    fg: Color::WHITE,
+                    density: 0.1,
+                    velocity: IVec2::new(1, 1),
+                }),
+            },
+            WeatherKind::Fog => Self {
+                sight: 0.4,
+                tint: Color::rgb(170, 170, 180),
+                particles: None,
+            },
+        }
+    }
+}
+
+/// The weather a map or biome gets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Climate {
+    /// Possible weather with its relative weight and effect.
+    kinds: Vec<(WeatherKind, u32, WeatherEffect)>,
+    /// Shortest and longest spell of weather, in turns.
+    duration: (u32, u32),
+}
+
+impl Default for Climate {
+    /// Always clear, changing every 100 to 300 turns.
+    fn default() -> Self {
+        Self {
+            kinds: Vec::new(),
+            duration: (100, 300),
+        }
+    }
+}
+
+impl Climate {
+    /// Creates a climate that is always clear until kinds are added.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mostly clear, with rain and some fog.
+    pub fn temperate() -> Self {
+        Self::new()
+            .with_kind(WeatherKind::Clear, 6)
+            .with_kind(WeatherKind::Rain, 3)
+            .with_kind(WeatherKind::Fog, 1)
+    }
+
+    /// Mostly snow.
+    pub fn arctic() -> Self {
+        Self::new()
+            .with_kind(WeatherKind::Clear, 2)
+            .with_kind(WeatherKind::Snow, 5)
+            .with_kind(WeatherKind::Fog, 1)
+    }
+
+    /// Fog and rain.
+    pub fn swamp() -> Self {
+        Self::new()
+            .with_kind(WeatherKind::Clear, 2)
+            .with_kind(WeatherKind::Rain, 3)
+            .with_kind(WeatherKind::Fog, 5)
+    }
+
+    /// Adds a kind of weather with its built-in effect, or changes its
+    /// weight.
+    pub fn with_kind(self, kind: WeatherKind, weight: u32) -> Self {
+        self.with_effect(kind, weight, WeatherEffect::of(kind))
+    }
+
+    /// Adds a kind of weather with a custom effect, such as red rain.
+    pub fn with_effect(mut self, kind: WeatherKind, weight: u32, effect: WeatherEffect) -> Self {
+        match self.kinds.iter_mut().find(|(k, ..)| *k == kind) {
+            Some(entry) => *entry = (kind, weight, effect),
+            None => self.kinds.push((kind, weight, effect)),
+        }
+        self
+    }
+
+    /// Sets the shortest and longest spell of weather, in turns.
+    pub fn with_duration(mut self, min: u32, max: u32) -> Self {
+        self.duration = (min.max(1), max.max(min.max(1)));
+        self
+    }
+
+    /// Returns the effect of a kind of weather in this climate.
+    pub fn effect(&self, kind: WeatherKind) -> WeatherEffect {
+        self.kinds
+            .iter()
+            .find(|(k, ..)| *k == kind)
+            .map_or_else(|| WeatherEffect::of(kind), |&(_, _, effect)| effect)
+    }
+
+    fn pick(&self, roll: u64) -> WeatherKind {
+        let total: u64 = self.kinds.iter().map(|&(_, w, _)| w as u64).sum();
+        if total == 0 {
+            return WeatherKind::Clear;
+        }
+        let mut roll = roll % total;
+        for &(kind, weight, _) in &self.kinds {
+            if roll < weight as u64 {
+                return kind;
+            }
+            roll -= weight as u64;
+        }
+        WeatherKind::Clear
+    }
+}
+
+/// Current weather and its particles.
+#[derive(Debug, Clone)]
+pub struct Weather {
+    climate: Climate,
+    kind: WeatherKind,
+    remaining: u32,
+    rng: SplitMix64,
+    particles: Vec<IVec2>,
+}
+
+impl Weather {
+    /// Starts weather for a climate, picking the first spell from `seed`.
+    pub fn new(climate: Climate, seed: u64) -> Self {
+        let mut weather = Self {
+            climate,
+            kind: WeatherKind::Clear,
+            remaining: 0,
+            rng: SplitMix64::new(seed),
+            particles: Vec::new(),
+        };
+        weather.change();
+        weather
+    }
+
+    /// Returns the climate.
+    pub fn climate(&self) -> &Climate {
+        &self.climate
+    }
+
+    /// Switches to another climate, e.g. on entering a new biome. The
+    /// current weather lasts until it would have changed anyway.
+    pub fn set_climate(&mut self, climate: Climate) {
+        self.climate = climate;
+    }
+
+    /// Returns the current weather.
+    pub fn kind(&self) -> WeatherKind {
+        self.kind
+    }
+
+    /// Returns the effect of the current weather.
+    pub fn effect(&self) -> WeatherEffect {
+        self.climate.effect(self.kind)
+    }
+
+    /// Returns how many turns the current weather lasts.
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    /// Forces a kind of weather for a number of turns, e.g. for a storm
+    /// spell or a scripted event.
+    pub fn set(&mut self, kind: WeatherKind, turns: u32) {
+        if kind != self.kind {
+            self.particles.clear();
+        }
+        self.kind = kind;
+        self.remaining = turns.max(1);
+    }
+
+    /// Moves the clock forward. Returns the old and new weather if it
+    /// changed, spell by spell, to the last change.
+    pub fn advance(&mut self, turns: u32) -> Option<(WeatherKind, WeatherKind)> {
+        let start = self.kind;
+        let mut turns = turns;
+        while turns >= self.remaining {
+            turns -= self.remaining;
+            self.change();
+        }
+        self.remaining -= turns;
+        (self.kind != start).then_some((start, self.kind))
+    }
+
+    /// Returns `radius` scaled by the current weather.
+    pub fn sight_radius(&self, radius: u32) -> u32 {
+        (radius as f32 * self.effect().sight).round() as u32
+    }
+
+    /// Returns an ambient light color tinted by the current weather.
+    pub fn tint(&self, ambient: Color) -> Color {
+        ambient.multiply(self.effect().tint)
+    }
+
+    /// Moves particles and spawns new ones along the top edge of a
+    /// `width` x `height` view. Call once per frame or per turn.
+    pub fn step_particles(&mut self, width: u32, height: u32) {
+        let Some(style) = self.effect().particles else {
+            self.particles.clear();
+            return;
+        };
+        let (w, h) = (width as i32, height as i32);
+        for p in &mut self.particles {
+            *p += style.velocity;
+            // Sideways drift wraps so snow doesn't thin out on one side
+            p.x = p.x.rem_euclid(w.max(1));
+        }
+        self.particles.retain(|p| p.y < h);
+        for x in 0..w {
+            if self.rng.next_f32() < style.density {
+                self.particles.push(IVec2::new(x, 0));
+            }
+        }
+    }
+
+    /// Returns the number of live particles.
+    pub fn particle_count(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// Draws the particles over `console`, keeping each cell's background.
+    pub fn draw<C: Console + ?Sized>(&self, console: &mut C) {
+        let Some(style) = self.effect().particles else {
+            return;
+        };
+        for &pos in &self.particles {
+            if let Some(cell) = console.get(pos) {
+                console.set(pos, Cell::new(style.glyph, style.fg, cell.bg));
+            }
+        }
+    }
+
+    fn change(&mut self) {
+        let roll = self.rng.next_u64();
+        let kind = self.climate.pick(roll);
+        let (min, max) = self.climate.duration;
+        let turns = min + (self.rng.next_u64() % (max - min + 1) as u64) as u32;
+        self.set(kind, turns);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rfc::ConsoleImage;
+
+    #[test]
+    fn test_advance_follows_climate() {
+        let climate = Climate::new()
+            .with_kind(WeatherKind::Snow, 1)
+            .with_duration(10, 10);
+        let mut weather = Weather::new(climate, 1);
+        assert_eq!(weather.kind(), WeatherKind::Snow);
+        assert_eq!(weather.remaining(), 10);
+        assert_eq!(weather.advance(25), None);
+        assert_eq!(weather.remaining(), 5);
+
+        weather.set(WeatherKind::Fog, 3);
+        assert_eq!(weather.sight_radius(10), 4);
+        assert_eq!(
+            weather.advance(3),
+            Some((WeatherKind::Fog, WeatherKind::Snow))
+        );
+
+        let a: Vec<_> = (0..20)
+            .map(|_| Weather::new(Climate::temperate(), 7).kind())
+            .collect();
+        assert!(a.windows(2).all(|w| w[0] == w[1]));
+    }
+
+    #[test]
+    fn test_particles_fall_and_draw() {
+        let red_rain = WeatherEffect {
+            particles: Some(Particles {
+                glyph: '!',
+                fg: Color::RED,
+                density: 1.0,
+                velocity: IVec2::new(0, 1),
+            }),
+            ..WeatherEffect::of(WeatherKind::Rain)
+        };
+        let climate = Climate::new().with_effect(WeatherKind::Rain, 1, red_rain);
+        let mut weather = Weather::new(climate, 3);
+        weather.step_particles(4, 2);
+        weather.step_particles(4, 2);
+        assert_eq!(weather.particle_count(), 8);
+        weather.step_particles(4, 2);
+        assert_eq!(weather.particle_count(), 8);
+
+        let mut console = ConsoleImage::new(4, 2);
+        console.set(IVec2::new(1, 1), Cell::new('.', Color::GRAY, Color::BLUE));
+        weather.draw(&mut console);
+        assert_eq!(
+            console.get(IVec2::new(1, 1)),
+            Some(Cell::new('!', Color::RED, Color::BLUE))
+        );
+        assert_eq!(weather.tint(Color::WHITE), red_rain.tint);
+
+        weather.set(WeatherKind::Clear, 5);
+        weather.step_particles(4, 2);
+        assert_eq!(weather.particle_count(), 0);
+    }
+}
@@ -0,0 +1,331 @@
+//! Message catalogs keyed by message id, with plural forms and fallbacks.
+//!
+//! Game code asks for messages by key (`"combat.hit"`) instead of building
+//! sentences by concatenation, so translators can reorder words and supply
+//! the right plural forms. A [`Catalog`] holds one language; a [`Localizer`]
+//! chains catalogs so a partial translation falls back to the base language.
+//!
+//! # Catalog Format
+//!
+//! Catalogs are plain text, one message per line. Lines starting with `#` are
+//! comments. Plural forms add the CLDR category in brackets and are chosen by
+//! the `n` argument:
+//!
+//! ```text
+//! # Combat
+//! combat.hit = You hit the {monster} for {n} damage.
+//! loot.coins[one] = You find a coin.
+//! loot.coins[other] = You find {n} coins.
+//! ```
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_text::prelude::*;
+//!
+//! let en = Catalog::parse("en", "
+//!     loot.coins[one] = You find a coin.
+//!     loot.coins[other] = You find {n} coins.
+//!     door.locked = The door is locked.
+//! ").unwrap();
+//! let fr = Catalog::parse("fr", "
+//!     loot.coins[one] = Vous trouvez {n} pièce.
+//!     loot.coins[other] = Vous trouvez {n} pièces.
+//! ").unwrap();
+//!
+//! let text = Localizer::new(fr).with_fallback(en);
+//! assert_eq!(text.text("loot.coins", &Args::new().with_count(0)), "Vous trouvez 0 pièce.");
+//! assert_eq!(text.text("door.locked", &Args::new()), "The door is locked.");
+//! ```
+
+use std::collections::HashMap;
+
+use crate::error::TextError;
+use crate::plural::{PluralCategory, PluralRule};
+use crate::template::{Args, StyledText, Template};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Message {
+    Single(Template),
+    Plural(Vec<(PluralCategory, Template)>),
+}
+
+/// The messages for one language.
+#[derive(Debug, Clone)]
+pub struct Catalog {
+    language: String,
+    rule: PluralRule,
+    messages: HashMap<String, Message>,
+}
+
+impl Catalog {
+    /// Creates an empty catalog for a language tag such as `"en"` or
+    /// `"pt-BR"`, using that language's [`PluralRule`].
+    pub fn new(language: impl Into<String>) -> Self {
+        let language = language.into();
+        Self {
+            rule: PluralRule::for_language(&language),
+            language,
+            messages: HashMap::new(),
+        }
+    }
+
+    /// Overrides the plural rule.
+    pub fn with_plural_rule(mut self, rule: PluralRule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    /// Parses a catalog in the format described in the [module docs](self).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TextError::Parse`] with the line number of the first bad line.
+    pub fn parse(language: impl Into<String>, source: &str) -> Result<Self, TextError> {
+        let mut catalog = Self::new(language);
+        for (i, line) in source.lines().enumerate() {
+            let parse_error = |reason: String| TextError::Parse {
+                line: i + 1,
+                reason,
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| parse_error("expected 'key = message'".to_string()))?;
+            let (key, value) = (key.trim(), value.trim());
+            let result = match key.strip_suffix(']').and_then(|k| k.split_once('[')) {
+                Some((key, category)) => {
+                    let category = PluralCategory::from_name(category.trim()).ok_or_else(|| {
+                        parse_error(format!("unknown plural category '{category}'"))
+                    })?;
+                    catalog.insert_plural(key.trim(), category, value)
+                }
+                None => catalog.insert(key, value),
+            };
+            result.map_err(|e| parse_error(e.to_string()))?;
+        }
+        Ok(catalog)
+    }
+
+    /// Returns the language tag.
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Returns the plural rule.
+    pub fn plural_rule(&self) -> PluralRule {
+        self.rule
+    }
+
+    /// Adds a message, replacing any existing one with the same key.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TextError::InvalidTemplate`] if the template is malformed.
+    pub fn insert(&mut self, key: impl Into<String>, template: &str) -> Result<(), TextError> {
+        let template = Template::parse(template)?;
+        self.messages.insert(key.into(), Message::Single(template));
+        Ok(())
+    }
+
+    /// Adds one plural form of a message. A single-form message with the same
+    /// key is replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TextError::InvalidTemplate`] if the template is malformed.
+    pub fn insert_plural(
+        &mut self,
+        key: impl Into<String>,
+        category: PluralCategory,
+        template: &str,
+    ) -> Result<(), TextError> {
+        let template = Template::parse(template)?;
+        let message = self
+            .messages
+            .entry(key.into())
+            .or_insert_with(|| Message::Plural(Vec::new()));
+        if let Message::Single(_) = message {
+            *message = Message::Plural(Vec::new());
+        }
+        if let Message::Plural(forms) = message {
+            forms.retain(|(c, _)| *c != category);
+            forms.push((category, template));
+        }
+        Ok(())
+    }
+
+    /// Returns true if the catalog has a message with this key.
+    pub fn contains(&self, key: &str) -> bool {
+        self.messages.contains_key(key)
+    }
+
+    /// Returns the message keys, in no particular order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.messages.keys().map(String::as_str)
+    }
+
+    /// Returns the number of messages.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Returns true if the catalog has no messages.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Formats a message. Plural messages pick their form from the `n`
+    /// argument, falling back to the `other` form.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is unknown, an argument is missing, or no
+    /// suitable plural form exists.
+    pub fn format(&self, key: &str, args: &Args) -> Result<StyledText, TextError> {
+        let message = self
+            .messages
+            .get(key)
+            .ok_or_else(|| TextError::MissingKey(key.to_string()))?;
+        let template = match message {
+            Message::Single(template) => template,
+            Message::Plural(forms) => {
+                let n = args
+                    .count()
+                    .ok_or_else(|| TextError::MissingArg(crate::template::COUNT_ARG.into()))?;
+                let category = self.rule.category(n);
+                let find = |wanted| forms.iter().find(|(c, _)| *c == wanted).map(|(_, t)| t);
+                find(category)
+                    .or_else(|| find(PluralCategory::Other))
+                    .ok_or_else(|| TextError::MissingPluralForm {
+                        key: key.to_string(),
+                        category,
+                    })?
+            }
+        };
+        template.render(args)
+    }
+}
+
+/// A language catalog with fallbacks for untranslated messages.
+#[derive(Debug, Clone)]
+pub struct Localizer {
+    catalogs: Vec<Catalog>,
+}
+
+impl Localizer {
+    /// Creates a localizer for one language.
+    pub fn new(catalog: Catalog) -> Self {
+        Self {
+            catalogs: vec![catalog],
+        }
+    }
+
+    /// Adds a catalog consulted when the earlier ones lack a message.
+    pub fn with_fallback(mut self, catalog: Catalog) -> Self {
+        self.catalogs.push(catalog);
+        self
+    }
+
+    /// Returns the catalogs in lookup order.
+    pub fn catalogs(&self) -> &[Catalog] {
+        &self.catalogs
+    }
+
+    /// Returns the language of the primary catalog.
+    pub fn language(&self) -> &str {
+        self.catalogs[0].language()
+    }
+
+    /// Formats a message from the first catalog that has it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TextError::MissingKey`] if no catalog has the key, or the
+    /// error from [`Catalog::format`].
+    pub fn try_format(&self, key: &str, args: &Args) -> Result<StyledText, TextError> {
+        self.catalogs
+            .iter()
+            .find(|c| c.contains(key))
+            .ok_or_else(|| TextError::MissingKey(key.to_string()))?
+            .format(key, args)
+    }
+
+    /// Formats a message, showing the key itself if it can't be formatted so
+    /// a missing translation is visible without crashing the game.
+    pub fn format(&self, key: &str, args: &Args) -> StyledText {
+        self.try_format(key, args)
+            .unwrap_or_else(|_| StyledText::from(key))
+    }
+
+    /// Formats a message as plain text. See [`format`](Self::format).
+    pub fn text(&self, key: &str, args: &Args) -> String {
+        self.format(key, args).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        let err = Catalog::parse("en", "# ok\nno equals sign").unwrap_err();
+        assert_eq!(
+            err,
+            TextError::Parse {
+                line: 2,
+                reason: "expected 'key = message'".to_string()
+            }
+        );
+        let err = Catalog::parse("en", "a[several] = x").unwrap_err();
+        assert_eq!(err.code(), "text.parse");
+        let err = Catalog::parse("en", "a = {broken").unwrap_err();
+        assert_eq!(err.code(), "text.parse");
+    }
+
+    #[test]
+    fn test_slavic_plurals() {
+        let ru = Catalog::parse(
+            "ru",
+            "coins[one] = {n} монета\ncoins[few] = {n} монеты\ncoins[many] = {n} монет",
+        )
+        .unwrap();
+        let coins = |n: i64| ru.format("coins", &Args::new().with_count(n)).unwrap();
+        assert_eq!(coins(1).to_string(), "1 монета");
+        assert_eq!(coins(22).to_string(), "22 монеты");
+        assert_eq!(coins(5).to_string(), "5 монет");
+    }
+
+    #[test]
+    fn test_plural_errors() {
+        let mut catalog = Catalog::new("en");
+        catalog
+            .insert_plural("x", PluralCategory::One, "one")
+            .unwrap();
+        assert_eq!(
+            catalog.format("x", &Args::new()).unwrap_err().code(),
+            "text.missing_arg"
+        );
+        assert_eq!(
+            catalog
+                .format("x", &Args::new().with_count(2))
+                .unwrap_err()
+                .code(),
+            "text.missing_plural_form"
+        );
+    }
+
+    #[test]
+    fn test_localizer_shows_key_when_missing() {
+        let text = Localizer::new(Catalog::new("en"));
+        assert_eq!(text.text("ui.quit", &Args::new()), "ui.quit");
+        assert_eq!(
+            text.try_format("ui.quit", &Args::new()),
+            Err(TextError::MissingKey("ui.quit".to_string()))
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! Drawing [`StyledText`] to a terminal [`Console`].
+//!
+//! Only available with the `terminal` feature.
+
+use runeforge_color::Color;
+use runeforge_terminal::prelude::{Console, IVec2};
+
+use crate::template::StyledText;
+
+impl StyledText {
+    /// Draws the text on one row starting at `pos`. Spans without a color
+    /// use `fg`. Text past the right edge is cut off.
+    ///
+    /// Returns the number of cells drawn.
+    pub fn draw<C: Console + ?Sized>(
+        &self,
+        console: &mut C,
+        pos: IVec2,
+        fg: Color,
+        bg: Color,
+    ) -> usize {
+        let mut x = pos.x;
+        let mut count = 0;
+        for span in self.spans() {
+            let written =
+                console.draw_string(IVec2::new(x, pos.y), &span.text, span.fg.unwrap_or(fg), bg);
+            count += written;
+            x += span.text.chars().count() as i32;
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use runeforge_terminal::prelude::Cell;
+
+    use super::*;
+    use crate::prelude::*;
+
+    struct Row(Vec<Cell>);
+
+    impl Console for Row {
+        fn width(&self) -> u32 {
+            self.0.len() as u32
+        }
+
+        fn height(&self) -> u32 {
+            1
+        }
+
+        fn set(&mut self, pos: IVec2, cell: Cell) -> bool {
+            if !self.in_bounds(pos) {
+                return false;
+            }
+            self.0[pos.x as usize] = cell;
+            true
+        }
+
+        fn get(&self, pos: IVec2) -> Option<Cell> {
+            self.in_bounds(pos).then(|| self.0[pos.x as usize])
+        }
+
+        fn clear(&mut self) {
+            self.0.fill(Cell::empty());
+        }
+
+        fn present(&mut self) {}
+    }
+
+    #[test]
+    fn test_draw_colors_spans() {
+        let text = Template::parse("Hit {monster}!")
+            .unwrap()
+            .render(&Args::new().with_colored("monster", "orc", Color::RED))
+            .unwrap();
+        let mut row = Row(vec![Cell::empty(); 6]);
+        assert_eq!(
+            text.draw(&mut row, IVec2::ZERO, Color::WHITE, Color::BLACK),
+            6
+        );
+        assert_eq!(row.0[0].fg, Color::WHITE);
+        assert_eq!(row.0[4].fg, Color::RED);
+        assert_eq!(row.0[5].ch, 'r');
+    }
+}
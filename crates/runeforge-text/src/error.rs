@@ -0,0 +1,57 @@
+//! Error types for message catalogs and templates.
+
+use thiserror::Error;
+
+use crate::plural::PluralCategory;
+
+/// Errors that can occur when loading or formatting messages.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TextError {
+    /// No catalog has a message with this key.
+    #[error("No message with key '{0}'")]
+    MissingKey(String),
+
+    /// A template refers to an argument that wasn't supplied.
+    #[error("Missing argument '{0}'")]
+    MissingArg(String),
+
+    /// A plural message has neither the needed form nor an `other` form.
+    #[error("Message '{key}' has no '{category}' or 'other' form")]
+    MissingPluralForm {
+        /// Message key
+        key: String,
+        /// The category that was needed
+        category: PluralCategory,
+    },
+
+    /// A template is malformed, e.g. an unclosed `{`.
+    #[error("Invalid template '{template}': {reason}")]
+    InvalidTemplate {
+        /// The template source
+        template: String,
+        /// What is wrong with it
+        reason: String,
+    },
+
+    /// A catalog file could not be parsed.
+    #[error("Catalog line {line}: {reason}")]
+    Parse {
+        /// 1-based line number
+        line: usize,
+        /// What is wrong with the line
+        reason: String,
+    },
+}
+
+impl TextError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            TextError::MissingKey(_) => "text.missing_key",
+            TextError::MissingArg(_) => "text.missing_arg",
+            TextError::MissingPluralForm { .. } => "text.missing_plural_form",
+            TextError::InvalidTemplate { .. } => "text.invalid_template",
+            TextError::Parse { .. } => "text.parse",
+        }
+    }
+}
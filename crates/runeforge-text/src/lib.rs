@@ -0,0 +1,63 @@
+//! Localizable text for roguelike games.
+//!
+//! # Overview
+//!
+//! `runeforge-text` replaces string-concatenated messages with keyed,
+//! translatable templates, so a game can be localized without rewriting its
+//! message log.
+//!
+//! *   **Templates:** [`Template`](template::Template)s such as `"You hit the {monster} for {n} damage."`
+//!     filled in from named [`Args`](template::Args).
+//! *   **Catalogs:** Per-language [`Catalog`](catalog::Catalog)s loaded from a simple `key = message`
+//!     format, chained with fallbacks by a [`Localizer`](catalog::Localizer).
+//! *   **Plurals:** [`PluralRule`](plural::PluralRule)s that pick the right form for each language.
+//! *   **Styled Text:** Arguments can carry colors, producing [`StyledText`](template::StyledText)
+//!     spans that a console draws in several colors.
+//!
+//! # Features
+//!
+//! *   `terminal`: Draw [`StyledText`](template::StyledText) to any `runeforge-terminal` console.
+//!
+//! # Usage
+//!
+//! Add this to your `Cargo.toml`:
+//!
+//! ```toml
+//! [dependencies]
+//! runeforge-text = "0.1"
+//! ```
+//!
+//! ## Example
+//!
+//! ```rust
+//! use runeforge_text::prelude::*;
+//! use runeforge_color::Color;
+//!
+//! let catalog = Catalog::parse("en", "
+//!     combat.hit = You hit the {monster} for {n} damage.
+//!     combat.kills[one] = You have slain {n} foe.
+//!     combat.kills[other] = You have slain {n} foes.
+//! ").unwrap();
+//! let text = Localizer::new(catalog);
+//!
+//! let args = Args::new().with_colored("monster", "goblin", Color::GREEN).with_count(4);
+//! assert_eq!(text.text("combat.hit", &args), "You hit the goblin for 4 damage.");
+//! assert_eq!(text.text("combat.kills", &Args::new().with_count(1)), "You have slain 1 foe.");
+//! ```
+
+#![deny(missing_docs)]
+
+pub mod catalog;
+#[cfg(feature = "terminal")]
+pub mod console;
+pub mod error;
+pub mod plural;
+pub mod template;
+
+/// Prelude module for convenient imports.
+pub mod prelude {
+    pub use crate::catalog::{Catalog, Localizer};
+    pub use crate::error::TextError;
+    pub use crate::plural::{PluralCategory, PluralRule};
+    pub use crate::template::{Arg, ArgValue, Args, Span, StyledText, Template, COUNT_ARG};
+}
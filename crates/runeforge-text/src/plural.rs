@@ -0,0 +1,173 @@
+//! Plural categories and per-language plural rules.
+//!
+//! Languages split numbers into different grammatical groups: English has
+//! "one" and "other", Russian adds "few" and "many", Japanese has no plural
+//! at all. A [`PluralRule`] maps a count to its [`PluralCategory`] so a
+//! catalog can pick the right form of a message.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_text::prelude::*;
+//!
+//! let rule = PluralRule::for_language("ru");
+//! assert_eq!(rule.category(1), PluralCategory::One);
+//! assert_eq!(rule.category(3), PluralCategory::Few);
+//! assert_eq!(rule.category(11), PluralCategory::Many);
+//!
+//! assert_eq!(PluralRule::for_language("en-US").category(0), PluralCategory::Other);
+//! ```
+
+use std::fmt;
+
+/// A grammatical number class, using the CLDR category names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PluralCategory {
+    /// Used by some languages for zero.
+    Zero,
+    /// Singular.
+    One,
+    /// Dual.
+    Two,
+    /// Paucal, e.g. Russian 2-4.
+    Few,
+    /// Used for large counts in some languages.
+    Many,
+    /// Everything else. Every plural message should have this form.
+    Other,
+}
+
+impl PluralCategory {
+    /// All categories, in order.
+    pub const ALL: [PluralCategory; 6] = [
+        PluralCategory::Zero,
+        PluralCategory::One,
+        PluralCategory::Two,
+        PluralCategory::Few,
+        PluralCategory::Many,
+        PluralCategory::Other,
+    ];
+
+    /// Returns the CLDR name of the category, such as `"few"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PluralCategory::Zero => "zero",
+            PluralCategory::One => "one",
+            PluralCategory::Two => "two",
+            PluralCategory::Few => "few",
+            PluralCategory::Many => "many",
+            PluralCategory::Other => "other",
+        }
+    }
+
+    /// Parses a CLDR category name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.name() == name)
+    }
+}
+
+impl fmt::Display for PluralCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// How a language chooses between plural forms.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum PluralRule {
+    /// No plural forms; every count is [`Other`](PluralCategory::Other).
+    /// Chinese, Japanese, Korean, Thai, Vietnamese.
+    Single,
+    /// 1 is [`One`](PluralCategory::One), everything else is
+    /// [`Other`](PluralCategory::Other). English, German, Spanish, Italian
+    /// and most Germanic and Romance languages.
+    #[default]
+    OneOther,
+    /// 0 and 1 are [`One`](PluralCategory::One). French, Portuguese (Brazil).
+    ZeroOneOther,
+    /// Ends in 1 (but not 11) is one, ends in 2-4 (but not 12-14) is few,
+    /// the rest is many. Russian, Ukrainian, Belarusian, Serbian, Croatian.
+    Slavic,
+    /// Like [`Slavic`](Self::Slavic), but only 1 itself is one. Polish.
+    Polish,
+    /// A custom rule for languages not covered here.
+    Custom(fn(u64) -> PluralCategory),
+}
+
+impl PluralRule {
+    /// Returns the rule for a language tag such as `"fr"` or `"pt-BR"`.
+    ///
+    /// Unknown languages get [`OneOther`](Self::OneOther).
+    pub fn for_language(tag: &str) -> Self {
+        let primary = tag
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match primary.as_str() {
+            "ja" | "zh" | "ko" | "th" | "vi" | "id" | "ms" => PluralRule::Single,
+            "fr" => PluralRule::ZeroOneOther,
+            "pt" if tag.eq_ignore_ascii_case("pt-br") || tag.eq_ignore_ascii_case("pt_br") => {
+                PluralRule::ZeroOneOther
+            }
+            "ru" | "uk" | "be" | "sr" | "hr" | "bs" => PluralRule::Slavic,
+            "pl" => PluralRule::Polish,
+            _ => PluralRule::OneOther,
+        }
+    }
+
+    /// Returns the category for a count. Negative counts use their absolute value.
+    pub fn category(&self, n: i64) -> PluralCategory {
+        let n = n.unsigned_abs();
+        let (n10, n100) = (n % 10, n % 100);
+        let few = (2..=4).contains(&n10) && !(12..=14).contains(&n100);
+        match self {
+            PluralRule::Single => PluralCategory::Other,
+            PluralRule::OneOther if n == 1 => PluralCategory::One,
+            PluralRule::OneOther => PluralCategory::Other,
+            PluralRule::ZeroOneOther if n <= 1 => PluralCategory::One,
+            PluralRule::ZeroOneOther => PluralCategory::Other,
+            PluralRule::Slavic if n10 == 1 && n100 != 11 => PluralCategory::One,
+            PluralRule::Polish if n == 1 => PluralCategory::One,
+            PluralRule::Slavic | PluralRule::Polish if few => PluralCategory::Few,
+            PluralRule::Slavic | PluralRule::Polish => PluralCategory::Many,
+            PluralRule::Custom(rule) => rule(n),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category_names_round_trip() {
+        for category in PluralCategory::ALL {
+            assert_eq!(PluralCategory::from_name(category.name()), Some(category));
+        }
+        assert_eq!(PluralCategory::from_name("several"), None);
+    }
+
+    #[test]
+    fn test_language_rules() {
+        let category = |tag, n| PluralRule::for_language(tag).category(n);
+        assert_eq!(category("fr", 0), PluralCategory::One);
+        assert_eq!(category("pt-BR", 0), PluralCategory::One);
+        assert_eq!(category("pt-PT", 0), PluralCategory::Other);
+        assert_eq!(category("ja", 1), PluralCategory::Other);
+        assert_eq!(category("pl", 21), PluralCategory::Many);
+        assert_eq!(category("ru", 21), PluralCategory::One);
+        assert_eq!(category("ru", -3), PluralCategory::Few);
+        assert_eq!(category("xx", 1), PluralCategory::One);
+    }
+
+    #[test]
+    fn test_custom_rule() {
+        let rule = PluralRule::Custom(|n| match n {
+            0 => PluralCategory::Zero,
+            2 => PluralCategory::Two,
+            _ => PluralCategory::Other,
+        });
+        assert_eq!(rule.category(-2), PluralCategory::Two);
+    }
+}
@@ -0,0 +1,364 @@
+//! Parameterized message templates and the styled text they produce.
+//!
+//! A [`Template`] such as `"You hit the {monster} for {n} damage."` is parsed
+//! once and filled in from [`Args`] each time the message is shown. Arguments
+//! may carry a color, so the result is [`StyledText`]: a run of spans that a
+//! console can draw with the monster's name in red while the rest of the line
+//! stays in the log color. Write `{{` and `}}` for literal braces.
+//!
+//! # Example
+//!
+//! ```
+//! use runeforge_text::prelude::*;
+//! use runeforge_color::Color;
+//!
+//! let template = Template::parse("You hit the {monster} for {n} damage.").unwrap();
+//! let args = Args::new()
+//!     .with_colored("monster", "orc", Color::RED)
+//!     .with_count(7);
+//!
+//! let text = template.render(&args).unwrap();
+//! assert_eq!(text.to_string(), "You hit the orc for 7 damage.");
+//! assert_eq!(text.spans()[1].fg, Some(Color::RED));
+//! ```
+
+use std::fmt;
+
+use runeforge_color::Color;
+
+use crate::error::TextError;
+
+/// The name of the argument that selects plural forms.
+pub const COUNT_ARG: &str = "n";
+
+/// The value of a template argument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgValue {
+    /// A piece of text, e.g. an already-translated monster name.
+    Text(String),
+    /// A whole number. Numbers can select plural forms.
+    Number(i64),
+}
+
+impl fmt::Display for ArgValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgValue::Text(text) => f.write_str(text),
+            ArgValue::Number(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+impl From<&str> for ArgValue {
+    fn from(value: &str) -> Self {
+        ArgValue::Text(value.to_string())
+    }
+}
+
+impl From<String> for ArgValue {
+    fn from(value: String) -> Self {
+        ArgValue::Text(value)
+    }
+}
+
+macro_rules! impl_number_arg {
+    ($($ty:ty),*) => {
+        $(impl From<$ty> for ArgValue {
+            fn from(value: $ty) -> Self {
+                ArgValue::Number(value as i64)
+            }
+        })*
+    };
+}
+
+impl_number_arg!(i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+/// A named argument with an optional highlight color.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Arg {
+    /// The argument's value
+    pub value: ArgValue,
+    /// Foreground color for the inserted text, or `None` to use the message color
+    pub fg: Option<Color>,
+}
+
+/// Named arguments for filling in a template.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Args {
+    args: Vec<(String, Arg)>,
+}
+
+impl Args {
+    /// Creates an empty argument list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an argument, replacing any earlier one with the same name.
+    pub fn with(self, name: impl Into<String>, value: impl Into<ArgValue>) -> Self {
+        self.with_arg(
+            name,
+            Arg {
+                value: value.into(),
+                fg: None,
+            },
+        )
+    }
+
+    /// Adds an argument that is drawn in its own color.
+    pub fn with_colored(
+        self,
+        name: impl Into<String>,
+        value: impl Into<ArgValue>,
+        fg: Color,
+    ) -> Self {
+        self.with_arg(
+            name,
+            Arg {
+                value: value.into(),
+                fg: Some(fg),
+            },
+        )
+    }
+
+    /// Sets the count (the `{n}` argument), which selects plural forms.
+    pub fn with_count(self, n: impl Into<ArgValue>) -> Self {
+        self.with(COUNT_ARG, n)
+    }
+
+    /// Adds an argument, replacing any earlier one with the same name.
+    pub fn with_arg(mut self, name: impl Into<String>, arg: Arg) -> Self {
+        let name = name.into();
+        match self.args.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, existing)) => *existing = arg,
+            None => self.args.push((name, arg)),
+        }
+        self
+    }
+
+    /// Returns an argument by name.
+    pub fn get(&self, name: &str) -> Option<&Arg> {
+        self.args.iter().find(|(n, _)| n == name).map(|(_, a)| a)
+    }
+
+    /// Returns the count used to select plural forms, if a numeric `n` was given.
+    pub fn count(&self) -> Option<i64> {
+        match self.get(COUNT_ARG)?.value {
+            ArgValue::Number(n) => Some(n),
+            ArgValue::Text(_) => None,
+        }
+    }
+}
+
+/// A run of text in one color.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    /// The text
+    pub text: String,
+    /// Foreground color, or `None` for the default color of whatever draws it
+    pub fg: Option<Color>,
+}
+
+/// Text made of differently colored spans.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StyledText {
+    spans: Vec<Span>,
+}
+
+impl StyledText {
+    /// Creates empty text.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends text in the default color.
+    pub fn push(&mut self, text: &str) {
+        self.push_span(text, None);
+    }
+
+    /// Appends text in a specific color.
+    pub fn push_colored(&mut self, text: &str, fg: Color) {
+        self.push_span(text, Some(fg));
+    }
+
+    fn push_span(&mut self, text: &str, fg: Option<Color>) {
+        if text.is_empty() {
+            return;
+        }
+        match self.spans.last_mut() {
+            Some(last) if last.fg == fg => last.text.push_str(text),
+            _ => self.spans.push(Span {
+                text: text.to_string(),
+                fg,
+            }),
+        }
+    }
+
+    /// Returns the spans in order. Adjacent spans never share a color.
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// Returns the length in characters, which is the width in console cells.
+    pub fn char_count(&self) -> usize {
+        self.spans.iter().map(|s| s.text.chars().count()).sum()
+    }
+
+    /// Returns true if there is no text.
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+}
+
+impl From<&str> for StyledText {
+    fn from(text: &str) -> Self {
+        let mut styled = StyledText::new();
+        styled.push(text);
+        styled
+    }
+}
+
+impl fmt::Display for StyledText {
+    /// Writes the plain text, without colors.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.spans.iter().try_for_each(|s| f.write_str(&s.text))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Arg(String),
+}
+
+/// A parsed message template with `{name}` placeholders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    /// Parses a template.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TextError::InvalidTemplate`] for unclosed or empty
+    /// placeholders and unmatched `}`.
+    pub fn parse(source: &str) -> Result<Self, TextError> {
+        let invalid = |reason: &str| TextError::InvalidTemplate {
+            template: source.to_string(),
+            reason: reason.to_string(),
+        };
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut chars = source.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some('{') | None => return Err(invalid("unclosed '{'")),
+                            Some(c) => name.push(c),
+                        }
+                    }
+                    let name = name.trim();
+                    if name.is_empty() {
+                        return Err(invalid("empty placeholder"));
+                    }
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+                    segments.push(Segment::Arg(name.to_string()));
+                }
+                '}' => return Err(invalid("unmatched '}'")),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+        Ok(Self { segments })
+    }
+
+    /// Returns the names of the placeholders, in order of appearance.
+    pub fn placeholders(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().filter_map(|s| match s {
+            Segment::Arg(name) => Some(name.as_str()),
+            Segment::Text(_) => None,
+        })
+    }
+
+    /// Fills in the placeholders.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TextError::MissingArg`] if a placeholder has no argument.
+    pub fn render(&self, args: &Args) -> Result<StyledText, TextError> {
+        let mut out = StyledText::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => out.push(text),
+                Segment::Arg(name) => {
+                    let arg = args
+                        .get(name)
+                        .ok_or_else(|| TextError::MissingArg(name.clone()))?;
+                    out.push_span(&arg.value.to_string(), arg.fg);
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escapes_and_placeholders() {
+        let template = Template::parse("{{{ name }}} has {n}").unwrap();
+        assert_eq!(template.placeholders().collect::<Vec<_>>(), ["name", "n"]);
+        let args = Args::new().with("name", "Ana").with_count(3);
+        assert_eq!(template.render(&args).unwrap().to_string(), "{Ana} has 3");
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        for source in ["{oops", "{}", "a } b", "{a{b}"] {
+            let err = Template::parse(source).unwrap_err();
+            assert_eq!(err.code(), "text.invalid_template", "{source}");
+        }
+    }
+
+    #[test]
+    fn test_missing_arg() {
+        let template = Template::parse("Hello {name}").unwrap();
+        assert_eq!(
+            template.render(&Args::new()),
+            Err(TextError::MissingArg("name".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_spans_merge_by_color() {
+        let template = Template::parse("{a}{b} and {c}").unwrap();
+        let args = Args::new()
+            .with_colored("a", "x", Color::RED)
+            .with_colored("b", "y", Color::RED)
+            .with("c", "z");
+        let text = template.render(&args).unwrap();
+        assert_eq!(text.spans().len(), 2);
+        assert_eq!(text.spans()[0].text, "xy");
+        assert_eq!(text.char_count(), 8);
+    }
+}
@@ -1,8 +1,14 @@
 //! Glyph atlas for efficient text rendering.
 
+use std::path::{Path, PathBuf};
+
+use crate::error::{Result, TilesetError};
 use crate::font::Font;
 use runeforge_color::Color;
 
+const ATLAS_HEADER: &str = "runeforge-atlas";
+const ATLAS_VERSION: u32 = 1;
+
 /// A single glyph with its position in the atlas.
 #[derive(Debug, Clone)]
 pub struct Glyph {
@@ -20,6 +26,9 @@ pub struct Glyph {
     pub bearing_x: i32,
     /// Vertical bearing.
     pub bearing_y: i32,
+    /// True for color glyphs, which keep their own colors instead of
+    /// being tinted with the foreground.
+    pub color: bool,
 }
 
 /// A texture atlas containing pre-rendered glyphs.
@@ -103,9 +112,13 @@ impl GlyphAtlas {
                 let offset_x = (cell_width.saturating_sub(rendered.width)) / 2;
                 let offset_y = (cell_height.saturating_sub(rendered.height)) / 2;
 
-                // Copy glyph bitmap to atlas (convert grayscale to RGBA white)
-                for gy in 0..rendered.height {
-                    for gx in 0..rendered.width {
+                // Copy glyph bitmap to atlas (convert grayscale to RGBA white,
+                // color glyphs keep their RGB). Anything past the cell is
+                // cropped rather than bleeding into the next one.
+                let width = rendered.width.min(cell_width);
+                let height = rendered.height.min(cell_height);
+                for gy in 0..height {
+                    for gx in 0..width {
                         let src_idx = (gy * rendered.width + gx) as usize;
                         if src_idx < rendered.bitmap.len() {
                             let alpha = rendered.bitmap[src_idx];
@@ -114,10 +127,15 @@ impl GlyphAtlas {
                                 let ty = atlas_y + offset_y + gy;
                                 if tx < texture_width && ty < texture_height {
                                     let dst_idx = ((ty * texture_width + tx) * 4) as usize;
-                                    // White color with alpha from glyph
-                                    texture[dst_idx] = 255; // R
-                                    texture[dst_idx + 1] = 255; // G
-                                    texture[dst_idx + 2] = 255; // B
+                                    let rgb = match &rendered.rgba {
+                                        Some(rgba) => {
+                                            let i = src_idx * 4;
+                                            [rgba[i], rgba[i + 1], rgba[i + 2]]
+                                        }
+                                        // White color with alpha from glyph
+                                        None => [255, 255, 255],
+                                    };
+                                    texture[dst_idx..dst_idx + 3].copy_from_slice(&rgb);
                                     texture[dst_idx + 3] = alpha; // A
                                 }
                             }
@@ -131,10 +149,11 @@ impl GlyphAtlas {
                         character: *c,
                         atlas_x,
                         atlas_y,
-                        width: rendered.width,
-                        height: rendered.height,
+                        width,
+                        height,
                         bearing_x: rendered.bearing_x,
                         bearing_y: rendered.bearing_y,
+                        color: rendered.rgba.is_some(),
                     },
                 );
             }
@@ -255,7 +274,17 @@ impl GlyphAtlas {
                         let by = y + cy;
                         let buf_idx = ((by * buffer_width + bx) * 4) as usize;
                         if buf_idx + 3 < buffer.len() {
-                            // Alpha blend foreground color
+                            // Alpha blend foreground color, or the glyph's
+                            // own color for emoji
+                            let fg = if glyph.color {
+                                Color::rgb(
+                                    self.texture[atlas_idx],
+                                    self.texture[atlas_idx + 1],
+                                    self.texture[atlas_idx + 2],
+                                )
+                            } else {
+                                fg
+                            };
                             let alpha_f = alpha as f32 / 255.0;
                             let inv_alpha = 1.0 - alpha_f;
 
@@ -284,12 +313,296 @@ impl GlyphAtlas {
     pub fn is_empty(&self) -> bool {
         self.glyphs.is_empty()
     }
+
+    /// Saves the atlas for loading with [`load`](Self::load): the texture as
+    /// a PNG at `path` and the glyph metrics in a text sidecar next to it,
+    /// at [`metrics_path`](Self::metrics_path).
+    ///
+    /// Baking atlases at build time skips rasterizing fonts on startup,
+    /// which is slow for large character sets such as CJK.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use runeforge_tileset::prelude::*;
+    ///
+    /// let font = TrueTypeFont::from_file("font.ttf", 16.0).unwrap();
+    /// GlyphAtlas::from_font(&font, 16, 16).save("assets/font16.png").unwrap();
+    ///
+    /// // At startup
+    /// let atlas = GlyphAtlas::load("assets/font16.png").unwrap();
+    /// ```
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        image::save_buffer_with_format(
+            path,
+            &self.texture,
+            self.texture_width,
+            self.texture_height,
+            image::ExtendedColorType::Rgba8,
+            image::ImageFormat::Png,
+        )?;
+        std::fs::write(Self::metrics_path(path), self.metrics_to_text())?;
+        Ok(())
+    }
+
+    /// Loads an atlas written by [`save`](Self::save).
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let image = image::open(path)?;
+        let metrics = std::fs::read_to_string(Self::metrics_path(path))?;
+        Self::from_image_and_metrics(image, &metrics)
+    }
+
+    /// Loads an atlas from PNG bytes and its metrics text, e.g. embedded
+    /// with `include_bytes!` and `include_str!`.
+    pub fn from_bytes(png: &[u8], metrics: &str) -> Result<Self> {
+        Self::from_image_and_metrics(image::load_from_memory(png)?, metrics)
+    }
+
+    /// Returns where [`save`](Self::save) puts the metrics for an atlas
+    /// image at `path`: the same path with a `.glyphs` extension.
+    pub fn metrics_path(path: &Path) -> PathBuf {
+        path.with_extension("glyphs")
+    }
+
+    /// Writes the grid layout and glyph metrics in a line-based text format.
+    ///
+    /// ```text
+    /// runeforge-atlas 1
+    /// cell 16 16
+    /// grid 10 10
+    /// ; code point (hex), atlas x, y, width, height, bearing x, y, color
+    /// glyph 40 0 0 9 12 1 11 0
+    /// ```
+    pub fn metrics_to_text(&self) -> String {
+        let mut out = format!(
+            "{} {}\ncell {} {}\ngrid {} {}\n",
+            ATLAS_HEADER, ATLAS_VERSION, self.cell_width, self.cell_height, self.columns, self.rows
+        );
+        let mut glyphs: Vec<&Glyph> = self.glyphs.values().collect();
+        glyphs.sort_by_key(|g| (g.atlas_y, g.atlas_x));
+        for g in glyphs {
+            out.push_str(&format!(
+                "glyph {:x} {} {} {} {} {} {} {}\n",
+                g.character as u32,
+                g.atlas_x,
+                g.atlas_y,
+                g.width,
+                g.height,
+                g.bearing_x,
+                g.bearing_y,
+                u8::from(g.color)
+            ));
+        }
+        out
+    }
+
+    fn from_image_and_metrics(image: image::DynamicImage, metrics: &str) -> Result<Self> {
+        let texture = image.to_rgba8();
+        let (texture_width, texture_height) = texture.dimensions();
+        let mut atlas = Self {
+            texture: texture.into_raw(),
+            texture_width,
+            texture_height,
+            cell_width: 0,
+            cell_height: 0,
+            columns: 0,
+            rows: 0,
+            glyphs: std::collections::HashMap::new(),
+        };
+
+        let mut saw_header = false;
+        for (idx, line) in metrics.lines().enumerate() {
+            let err = |message: &str| {
+                TilesetError::InvalidAtlas(format!("line {}: {}", idx + 1, message))
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let num = |i: usize| -> Result<i64> {
+                words
+                    .get(i)
+                    .and_then(|w| w.parse().ok())
+                    .ok_or_else(|| err("expected a number"))
+            };
+            let unsigned = |i: usize| -> Result<u32> {
+                u32::try_from(num(i)?).map_err(|_| err("expected a non-negative number"))
+            };
+            let signed = |i: usize| -> Result<i32> {
+                i32::try_from(num(i)?).map_err(|_| err("number out of range"))
+            };
+
+            if !saw_header {
+                if words[0] != ATLAS_HEADER {
+                    return Err(err("missing atlas header"));
+                }
+                if num(1)? != i64::from(ATLAS_VERSION) {
+                    return Err(err("unsupported atlas version"));
+                }
+                saw_header = true;
+                continue;
+            }
+
+            match words[0] {
+                "cell" => {
+                    atlas.cell_width = unsigned(1)?;
+                    atlas.cell_height = unsigned(2)?;
+                }
+                "grid" => {
+                    atlas.columns = unsigned(1)?;
+                    atlas.rows = unsigned(2)?;
+                }
+                "glyph" => {
+                    let character = words
+                        .get(1)
+                        .and_then(|w| u32::from_str_radix(w, 16).ok())
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| err("invalid code point"))?;
+                    let glyph = Glyph {
+                        character,
+                        atlas_x: unsigned(2)?,
+                        atlas_y: unsigned(3)?,
+                        width: unsigned(4)?,
+                        height: unsigned(5)?,
+                        bearing_x: signed(6)?,
+                        bearing_y: signed(7)?,
+                        color: num(8)? != 0,
+                    };
+                    if atlas.cell_width == 0 || atlas.cell_height == 0 {
+                        return Err(err("glyph before the cell size"));
+                    }
+                    // Rendering samples a whole cell, so both the glyph and
+                    // its cell must fit.
+                    let fits = |pos: u32, size: u32, limit: u32| {
+                        pos.checked_add(size).is_some_and(|end| end <= limit)
+                    };
+                    let inside = fits(glyph.atlas_x, glyph.width, texture_width)
+                        && fits(glyph.atlas_y, glyph.height, texture_height)
+                        && fits(glyph.atlas_x, atlas.cell_width, texture_width)
+                        && fits(glyph.atlas_y, atlas.cell_height, texture_height);
+                    if !inside {
+                        return Err(err("glyph lies outside the atlas image"));
+                    }
+                    atlas.glyphs.insert(character, glyph);
+                }
+                other => return Err(err(&format!("unknown entry '{}'", other))),
+            }
+        }
+
+        if !saw_header {
+            return Err(TilesetError::InvalidAtlas(
+                "missing atlas header".to_string(),
+            ));
+        }
+        Ok(atlas)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    struct Swatch;
+
+    impl Font for Swatch {
+        fn name(&self) -> &str {
+            "swatch"
+        }
+
+        fn cell_width(&self) -> u32 {
+            1
+        }
+
+        fn cell_height(&self) -> u32 {
+            1
+        }
+
+        fn line_height(&self) -> u32 {
+            1
+        }
+
+        fn render_glyph(&self, c: char) -> Option<crate::font::RenderedGlyph> {
+            Some(crate::font::RenderedGlyph {
+                character: c,
+                width: 1,
+                height: 1,
+                bearing_x: 0,
+                bearing_y: 1,
+                bitmap: vec![255],
+                rgba: (c == 'r').then(|| vec![255, 0, 0, 255]),
+            })
+        }
+
+        fn has_glyph(&self, _c: char) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_color_glyphs_keep_their_color() {
+        let atlas = GlyphAtlas::from_font_with_chars(&Swatch, 1, 1, ['r', 'm']);
+        assert!(atlas.get_glyph('r').unwrap().color);
+        assert!(!atlas.get_glyph('m').unwrap().color);
+
+        let mut buffer = vec![0u8; 2 * 4];
+        atlas.render_char(&mut buffer, 2, 0, 0, 'r', Color::BLUE, None);
+        atlas.render_char(&mut buffer, 2, 1, 0, 'm', Color::BLUE, None);
+        assert_eq!(&buffer[..4], &[255, 0, 0, 255]);
+        assert_eq!(&buffer[4..], &[0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_metrics_round_trip() {
+        let atlas = GlyphAtlas::from_font_with_chars(&Swatch, 1, 1, ['r', 'm', '✓']);
+        let mut png = Vec::new();
+        image::RgbaImage::from_raw(
+            atlas.texture_width,
+            atlas.texture_height,
+            atlas.texture.clone(),
+        )
+        .unwrap()
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .unwrap();
+
+        let loaded = GlyphAtlas::from_bytes(&png, &atlas.metrics_to_text()).unwrap();
+        assert_eq!(loaded.texture, atlas.texture);
+        assert_eq!((loaded.columns, loaded.rows), (atlas.columns, atlas.rows));
+        assert_eq!(loaded.len(), 3);
+        assert!(loaded.get_glyph('r').unwrap().color);
+        assert_eq!(loaded.get_uv('✓'), atlas.get_uv('✓'));
+        assert_eq!(loaded.metrics_to_text(), atlas.metrics_to_text());
+
+        let bad = atlas.metrics_to_text().replace("glyph 72 ", "glyph 72 99 ");
+        assert_eq!(
+            GlyphAtlas::from_bytes(&png, &bad).unwrap_err().code(),
+            "tileset.invalid_atlas"
+        );
+        assert!(GlyphAtlas::from_bytes(&png, "cell 1 1").is_err());
+
+        let header = format!("{} {}\n", ATLAS_HEADER, ATLAS_VERSION);
+        for hostile in [
+            "glyph 41 0 0 1 1 0 0 0\ncell 1 1\n",
+            "cell 1 1\nglyph 41 4294967295 0 1 1 0 0 0\n",
+            "cell 1 1\nglyph 41 0 0 99 1 0 0 0\n",
+            "cell 1 1\nglyph 41 0 0 1 1 4294967296 0 0\n",
+        ] {
+            let metrics = format!("{}{}", header, hostile);
+            assert_eq!(
+                GlyphAtlas::from_bytes(&png, &metrics).unwrap_err().code(),
+                "tileset.invalid_atlas",
+                "{}",
+                hostile
+            );
+        }
+        assert_eq!(
+            GlyphAtlas::metrics_path(Path::new("fonts/a.png")),
+            PathBuf::from("fonts/a.glyphs")
+        );
+    }
+
     #[test]
     fn test_default_charset() {
         let chars = GlyphAtlas::default_charset();
@@ -0,0 +1,225 @@
+//! Color glyphs from emoji and icon fonts.
+//!
+//! Two kinds of color data are read:
+//!
+//! *   **Bitmap tables** (`CBDT`/`CBLC`, `sbix`): prerendered PNG or BGRA
+//!     images, scaled to the requested size.
+//! *   **Layered outlines** (`COLR`/`CPAL`): glyph outlines stacked with a
+//!     fill color each. Solid fills are drawn in their palette color;
+//!     gradient fills use the foreground (white) so the shape still shows.
+//!
+//! Fonts without these tables return `None` and are rendered as
+//! monochrome outlines as before.
+
+use ab_glyph::{Font as AbFont, FontVec, GlyphId, GlyphImageFormat, PxScale, ScaleFont};
+use ttf_parser::colr::{ClipBox, CompositeMode, Paint, Painter};
+use ttf_parser::{RgbaColor, Transform};
+
+/// An RGBA image of one glyph, positioned relative to the pen origin.
+pub(crate) struct ColorBitmap {
+    pub width: u32,
+    pub height: u32,
+    /// Left edge relative to the origin, in pixels.
+    pub min_x: f32,
+    /// Top edge relative to the baseline, in pixels; negative is above.
+    pub min_y: f32,
+    /// Straight (not premultiplied) RGBA pixels.
+    pub rgba: Vec<u8>,
+}
+
+impl ColorBitmap {
+    /// Returns the alpha channel, for monochrome consumers.
+    pub fn alpha(&self) -> Vec<u8> {
+        self.rgba.chunks_exact(4).map(|px| px[3]).collect()
+    }
+}
+
+/// Renders `c` from the font's color tables, if it has any for it.
+pub(crate) fn render(font: &FontVec, scale: PxScale, c: char) -> Option<ColorBitmap> {
+    let glyph_id = font.glyph_id(c);
+    if glyph_id.0 == 0 {
+        return None;
+    }
+    render_layers(font, scale, glyph_id).or_else(|| render_raster(font, scale, glyph_id))
+}
+
+/// Draws a `COLR` glyph by compositing its layers.
+fn render_layers(font: &FontVec, scale: PxScale, glyph_id: GlyphId) -> Option<ColorBitmap> {
+    let face = ttf_parser::Face::parse(font.as_slice(), 0).ok()?;
+    let id = ttf_parser::GlyphId(glyph_id.0);
+    if !face.is_color_glyph(id) {
+        return None;
+    }
+    let mut layers = LayerCollector::default();
+    face.paint_color_glyph(id, 0, RgbaColor::new(255, 255, 255, 255), &mut layers)?;
+
+    let outlined: Vec<_> = layers
+        .layers
+        .iter()
+        .filter_map(|&(layer, color)| {
+            let glyph = GlyphId(layer).with_scale_and_position(scale, ab_glyph::point(0.0, 0.0));
+            font.outline_glyph(glyph).map(|o| (o, color))
+        })
+        .collect();
+    let first = outlined.first()?.0.px_bounds();
+    let (mut min, mut max) = (first.min, first.max);
+    for (o, _) in &outlined {
+        let b = o.px_bounds();
+        min.x = min.x.min(b.min.x);
+        min.y = min.y.min(b.min.y);
+        max.x = max.x.max(b.max.x);
+        max.y = max.y.max(b.max.y);
+    }
+    let width = (max.x - min.x).ceil() as u32;
+    let height = (max.y - min.y).ceil() as u32;
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let mut rgba = vec![0u8; (width * height * 4) as usize];
+    for (o, color) in &outlined {
+        let b = o.px_bounds();
+        let dx = (b.min.x - min.x) as u32;
+        let dy = (b.min.y - min.y) as u32;
+        o.draw(|x, y, coverage| {
+            let (px, py) = (x + dx, y + dy);
+            if px < width && py < height {
+                let idx = ((py * width + px) * 4) as usize;
+                blend(&mut rgba[idx..idx + 4], *color, coverage);
+            }
+        });
+    }
+
+    Some(ColorBitmap {
+        width,
+        height,
+        min_x: min.x,
+        min_y: min.y,
+        rgba,
+    })
+}
+
+/// Reads a prerendered `CBDT` or `sbix` image, scaled to the font size.
+fn render_raster(font: &FontVec, scale: PxScale, glyph_id: GlyphId) -> Option<ColorBitmap> {
+    let size = scale.y.round().clamp(1.0, u16::MAX as f32) as u16;
+    let raster = font.glyph_raster_image2(glyph_id, size)?;
+    let (w, h) = (u32::from(raster.width), u32::from(raster.height));
+    let image = match raster.format {
+        GlyphImageFormat::Png => image::load_from_memory(raster.data).ok()?.to_rgba8(),
+        GlyphImageFormat::BitmapPremulBgra32 => {
+            let mut rgba = Vec::with_capacity(raster.data.len());
+            for px in raster.data.chunks_exact(4) {
+                let a = px[3];
+                let unmul = |v: u8| {
+                    if a == 0 {
+                        0
+                    } else {
+                        (u32::from(v) * 255 / u32::from(a)).min(255) as u8
+                    }
+                };
+                rgba.extend_from_slice(&[unmul(px[2]), unmul(px[1]), unmul(px[0]), a]);
+            }
+            image::RgbaImage::from_raw(w, h, rgba)?
+        }
+        _ => return None,
+    };
+
+    // Strikes come in fixed sizes; scale the nearest one to the font size
+    let factor = scale.y / f32::from(raster.pixels_per_em.max(1));
+    let width = ((image.width() as f32 * factor).round() as u32).max(1);
+    let height = ((image.height() as f32 * factor).round() as u32).max(1);
+    let image = if (width, height) == image.dimensions() {
+        image
+    } else {
+        image::imageops::resize(&image, width, height, image::imageops::FilterType::Triangle)
+    };
+
+    // The origin is the image's bottom-left corner relative to the pen
+    let min_x = raster.origin.x * factor;
+    let min_y = -(raster.origin.y * factor) - height as f32;
+    let ascent = font.as_scaled(scale).ascent();
+    Some(ColorBitmap {
+        width,
+        height,
+        min_x,
+        min_y: min_y.max(-ascent),
+        rgba: image.into_raw(),
+    })
+}
+
+/// Composites `color` over a straight-alpha pixel with `coverage`.
+fn blend(dst: &mut [u8], color: RgbaColor, coverage: f32) {
+    let src_a = coverage.clamp(0.0, 1.0) * f32::from(color.alpha) / 255.0;
+    if src_a <= 0.0 {
+        return;
+    }
+    let dst_a = f32::from(dst[3]) / 255.0;
+    let out_a = src_a + dst_a * (1.0 - src_a);
+    let mix = |s: u8, d: u8| {
+        ((f32::from(s) * src_a + f32::from(d) * dst_a * (1.0 - src_a)) / out_a).round() as u8
+    };
+    dst[0] = mix(color.red, dst[0]);
+    dst[1] = mix(color.green, dst[1]);
+    dst[2] = mix(color.blue, dst[2]);
+    dst[3] = (out_a * 255.0).round() as u8;
+}
+
+/// Records `COLR` layers as (outline glyph, fill color) pairs.
+#[derive(Default)]
+struct LayerCollector {
+    current: Option<u16>,
+    layers: Vec<(u16, RgbaColor)>,
+}
+
+impl<'a> Painter<'a> for LayerCollector {
+    fn outline_glyph(&mut self, glyph_id: ttf_parser::GlyphId) {
+        self.current = Some(glyph_id.0);
+    }
+
+    fn paint(&mut self, paint: Paint<'a>) {
+        let color = match paint {
+            Paint::Solid(color) => color,
+            _ => RgbaColor::new(255, 255, 255, 255),
+        };
+        if let Some(glyph) = self.current.take() {
+            self.layers.push((glyph, color));
+        }
+    }
+
+    fn push_clip(&mut self) {}
+
+    fn push_clip_box(&mut self, _clipbox: ClipBox) {}
+
+    fn pop_clip(&mut self) {}
+
+    fn push_layer(&mut self, _mode: CompositeMode) {}
+
+    fn pop_layer(&mut self) {}
+
+    fn push_transform(&mut self, _transform: Transform) {}
+
+    fn pop_transform(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blend_over() {
+        let mut px = [0u8, 0, 0, 0];
+        blend(&mut px, RgbaColor::new(255, 0, 0, 255), 1.0);
+        assert_eq!(px, [255, 0, 0, 255]);
+        blend(&mut px, RgbaColor::new(0, 0, 255, 255), 0.5);
+        assert_eq!(px, [128, 0, 128, 255]);
+        blend(&mut px, RgbaColor::new(0, 255, 0, 255), 0.0);
+        assert_eq!(px, [128, 0, 128, 255]);
+    }
+
+    #[test]
+    fn test_monochrome_font_has_no_color_glyphs() {
+        let font =
+            FontVec::try_from_vec(include_bytes!("../../../assets/font.ttf").to_vec()).unwrap();
+        assert!(render(&font, PxScale::from(16.0), 'A').is_none());
+    }
+}
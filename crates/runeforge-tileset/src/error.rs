@@ -21,6 +21,10 @@ pub enum TilesetError {
     #[error("Invalid tileset dimensions: {0}")]
     InvalidDimensions(String),
 
+    /// A saved glyph atlas's metrics are missing or invalid.
+    #[error("Invalid glyph atlas: {0}")]
+    InvalidAtlas(String),
+
     /// Character not found in font.
     #[error("Character '{0}' not found in font")]
     CharacterNotFound(char),
@@ -30,5 +34,20 @@ pub enum TilesetError {
     IoError(#[from] std::io::Error),
 }
 
+impl TilesetError {
+    /// Returns a stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            TilesetError::TrueTypeFontError(_) => "tileset.truetype_font",
+            TilesetError::BdfFontError(_) => "tileset.bdf_font",
+            TilesetError::ImageError(_) => "tileset.image",
+            TilesetError::InvalidDimensions(_) => "tileset.invalid_dimensions",
+            TilesetError::InvalidAtlas(_) => "tileset.invalid_atlas",
+            TilesetError::CharacterNotFound(_) => "tileset.character_not_found",
+            TilesetError::IoError(_) => "tileset.io",
+        }
+    }
+}
+
 /// Result type alias for tileset operations.
 pub type Result<T> = std::result::Result<T, TilesetError>;
@@ -19,6 +19,9 @@ pub struct RenderedGlyph {
     pub bearing_y: i32,
     /// Grayscale bitmap data (0-255 alpha values).
     pub bitmap: Vec<u8>,
+    /// RGBA pixel data for color glyphs such as emoji, `None` for
+    /// monochrome glyphs. `bitmap` then holds the alpha channel.
+    pub rgba: Option<Vec<u8>>,
 }
 
 /// Trait for font implementations.
@@ -42,6 +45,96 @@ pub trait Font {
     fn has_glyph(&self, c: char) -> bool;
 }
 
+/// Overrides for how a [`TrueTypeFont`] is fitted into console cells.
+///
+/// Cell sizes are normally derived from the font: the advance of `M` and the
+/// line height. Fonts that report odd advances, or proportional fonts used as
+/// a console font, then produce clipped or overlapping glyphs. Setting
+/// [`monospace`](Self::monospace), a baseline offset, padding or per-glyph
+/// advances switches the font to cell layout: every glyph is rendered into a
+/// full cell-sized bitmap on a shared baseline, so the atlas places it
+/// exactly.
+///
+/// # Example
+///
+/// ```no_run
+/// use runeforge_tileset::prelude::*;
+///
+/// let metrics = FontMetrics::new()
+///     .with_cell_size(10, 18)
+///     .with_baseline_offset(-1)
+///     .with_advance('W', 10.0)
+///     .monospace(true);
+/// let data = std::fs::read("font.ttf").unwrap();
+/// let font = TrueTypeFont::from_bytes_with_metrics(&data, 16.0, metrics).unwrap();
+/// assert_eq!(font.cell_width(), 10);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FontMetrics {
+    /// Forced cell width, before padding. `None` uses the font's.
+    pub cell_width: Option<u32>,
+    /// Forced cell height. `None` uses the font's line height.
+    pub cell_height: Option<u32>,
+    /// Pixels to move glyphs down within the cell; negative moves them up.
+    pub baseline_offset: i32,
+    /// Blank pixels added to each side of every cell.
+    pub padding_x: u32,
+    /// Fit proportional fonts to a fixed grid: size cells by the widest
+    /// ASCII advance instead of `M`, and center each glyph in its cell.
+    pub monospace: bool,
+    /// Advances to use for specific characters when centering them.
+    pub advances: std::collections::HashMap<char, f32>,
+}
+
+impl FontMetrics {
+    /// Creates metrics that keep the font's own values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forces the cell width and height.
+    pub fn with_cell_size(mut self, width: u32, height: u32) -> Self {
+        self.cell_width = Some(width);
+        self.cell_height = Some(height);
+        self
+    }
+
+    /// Moves glyphs down by `offset` pixels, or up if negative.
+    pub fn with_baseline_offset(mut self, offset: i32) -> Self {
+        self.baseline_offset = offset;
+        self
+    }
+
+    /// Adds `padding` blank pixels to the left and right of every cell.
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding_x = padding;
+        self
+    }
+
+    /// Overrides the advance of one character.
+    pub fn with_advance(mut self, c: char, advance: f32) -> Self {
+        self.advances.insert(c, advance);
+        self
+    }
+
+    /// Enables or disables monospace enforcement.
+    pub fn monospace(mut self, enabled: bool) -> Self {
+        self.monospace = enabled;
+        self
+    }
+
+    /// Returns true if glyphs are rendered into full cells, which any
+    /// override does.
+    pub fn uses_cell_layout(&self) -> bool {
+        self.monospace
+            || self.cell_width.is_some()
+            || self.cell_height.is_some()
+            || self.baseline_offset != 0
+            || self.padding_x != 0
+            || !self.advances.is_empty()
+    }
+}
+
 /// TrueType font loaded from .ttf or .otf files.
 #[cfg(feature = "truetype")]
 pub struct TrueTypeFont {
@@ -51,6 +144,8 @@ pub struct TrueTypeFont {
     cell_width: u32,
     cell_height: u32,
     line_height: u32,
+    ascent: f32,
+    metrics: FontMetrics,
 }
 
 #[cfg(feature = "truetype")]
@@ -71,6 +166,11 @@ impl TrueTypeFont {
     /// // let font = TrueTypeFont::from_bytes(&font_data, 16.0).unwrap();
     /// ```
     pub fn from_bytes(data: &[u8], size: f32) -> Result<Self> {
+        Self::from_bytes_with_metrics(data, size, FontMetrics::default())
+    }
+
+    /// Loads a TrueType font from bytes, fitting it to cells with `metrics`.
+    pub fn from_bytes_with_metrics(data: &[u8], size: f32, metrics: FontMetrics) -> Result<Self> {
         use ab_glyph::{Font as AbFont, FontVec, PxScale, ScaleFont};
 
         let font = FontVec::try_from_vec(data.to_vec())
@@ -79,12 +179,23 @@ impl TrueTypeFont {
         let scale = PxScale::from(size);
         let scaled_font = font.as_scaled(scale);
 
-        // Calculate metrics based on 'M' character (em-square)
-        let m_glyph = font.glyph_id('M');
-        let cell_width = scaled_font.h_advance(m_glyph).ceil() as u32;
+        // Calculate metrics based on 'M' character (em-square), or on the
+        // widest ASCII glyph when enforcing a fixed grid
+        let natural_width = if metrics.monospace {
+            (32u8..=126)
+                .map(|b| scaled_font.h_advance(font.glyph_id(b as char)))
+                .fold(0.0, f32::max)
+        } else {
+            scaled_font.h_advance(font.glyph_id('M'))
+        };
+        let cell_width =
+            metrics.cell_width.unwrap_or(natural_width.ceil() as u32) + 2 * metrics.padding_x;
 
-        let line_height = scaled_font.height().ceil() as u32;
+        let line_height = metrics
+            .cell_height
+            .unwrap_or(scaled_font.height().ceil() as u32);
         let cell_height = line_height;
+        let ascent = scaled_font.ascent();
 
         Ok(Self {
             font,
@@ -93,14 +204,103 @@ impl TrueTypeFont {
             cell_width,
             cell_height,
             line_height,
+            ascent,
+            metrics,
         })
     }
 
+    /// Returns the cell metrics the font was loaded with.
+    pub fn metrics(&self) -> &FontMetrics {
+        &self.metrics
+    }
+
+    /// Returns the horizontal advance of `c` in pixels, honoring advance
+    /// overrides.
+    pub fn advance(&self, c: char) -> f32 {
+        use ab_glyph::{Font as AbFont, ScaleFont};
+
+        match self.metrics.advances.get(&c) {
+            Some(&advance) => advance,
+            None => self
+                .font
+                .as_scaled(self.scale)
+                .h_advance(self.font.glyph_id(c)),
+        }
+    }
+
     /// Loads a TrueType font from a file path.
     pub fn from_file(path: &str, size: f32) -> Result<Self> {
         let data = std::fs::read(path)?;
         Self::from_bytes(&data, size)
     }
+
+    /// Copies a color glyph into a full cell, placed like
+    /// [`render_in_cell`](Self::render_in_cell) places outlines.
+    fn color_in_cell(&self, c: char, color: &crate::color_glyph::ColorBitmap) -> RenderedGlyph {
+        let (width, height) = (self.cell_width, self.cell_height);
+        let mut rgba = vec![0u8; (width * height * 4) as usize];
+
+        let inner = width.saturating_sub(2 * self.metrics.padding_x) as f32;
+        let left = self.metrics.padding_x as f32 + (inner - self.advance(c)) / 2.0;
+        let origin_x = (left + color.min_x).round() as i32;
+        let origin_y = (self.ascent + color.min_y).round() as i32 + self.metrics.baseline_offset;
+        for y in 0..color.height {
+            for x in 0..color.width {
+                let px = origin_x + x as i32;
+                let py = origin_y + y as i32;
+                if px >= 0 && py >= 0 && (px as u32) < width && (py as u32) < height {
+                    let src = ((y * color.width + x) * 4) as usize;
+                    let dst = ((py as u32 * width + px as u32) * 4) as usize;
+                    rgba[dst..dst + 4].copy_from_slice(&color.rgba[src..src + 4]);
+                }
+            }
+        }
+
+        RenderedGlyph {
+            character: c,
+            width,
+            height,
+            bearing_x: 0,
+            bearing_y: self.ascent.round() as i32 + self.metrics.baseline_offset,
+            bitmap: rgba.chunks_exact(4).map(|px| px[3]).collect(),
+            rgba: Some(rgba),
+        }
+    }
+
+    /// Draws an outlined glyph into a full cell on the shared baseline,
+    /// centered on its advance and clipped to the cell.
+    fn render_in_cell(&self, c: char, outlined: Option<ab_glyph::OutlinedGlyph>) -> RenderedGlyph {
+        let (width, height) = (self.cell_width, self.cell_height);
+        let mut bitmap = vec![0u8; (width * height) as usize];
+
+        if let Some(outlined) = outlined {
+            let bounds = outlined.px_bounds();
+            let inner = width.saturating_sub(2 * self.metrics.padding_x) as f32;
+            let left = self.metrics.padding_x as f32 + (inner - self.advance(c)) / 2.0;
+            let origin_x = (left + bounds.min.x).round() as i32;
+            let origin_y =
+                (self.ascent + bounds.min.y).round() as i32 + self.metrics.baseline_offset;
+
+            outlined.draw(|x, y, coverage| {
+                let px = origin_x + x as i32;
+                let py = origin_y + y as i32;
+                if px >= 0 && py >= 0 && (px as u32) < width && (py as u32) < height {
+                    let idx = (py as u32 * width + px as u32) as usize;
+                    bitmap[idx] = bitmap[idx].max((coverage * 255.0) as u8);
+                }
+            });
+        }
+
+        RenderedGlyph {
+            character: c,
+            width,
+            height,
+            bearing_x: 0,
+            bearing_y: self.ascent.round() as i32 + self.metrics.baseline_offset,
+            bitmap,
+            rgba: None,
+        }
+    }
 }
 
 #[cfg(feature = "truetype")]
@@ -132,8 +332,27 @@ impl Font for TrueTypeFont {
             return None;
         }
 
+        if let Some(color) = crate::color_glyph::render(&self.font, self.scale, c) {
+            if self.metrics.uses_cell_layout() {
+                return Some(self.color_in_cell(c, &color));
+            }
+            return Some(RenderedGlyph {
+                character: c,
+                width: color.width,
+                height: color.height,
+                bearing_x: color.min_x as i32,
+                bearing_y: (scaled_font.ascent() - color.min_y) as i32,
+                bitmap: color.alpha(),
+                rgba: Some(color.rgba),
+            });
+        }
+
         let glyph = glyph_id.with_scale_and_position(self.scale, ab_glyph::point(0.0, 0.0));
 
+        if self.metrics.uses_cell_layout() {
+            return Some(self.render_in_cell(c, self.font.outline_glyph(glyph)));
+        }
+
         if let Some(outlined) = self.font.outline_glyph(glyph) {
             let bounds = outlined.px_bounds();
             let width = bounds.width().ceil() as u32;
@@ -148,6 +367,7 @@ impl Font for TrueTypeFont {
                     bearing_x: 0,
                     bearing_y: 0,
                     bitmap: vec![0; (self.cell_width * self.cell_height) as usize],
+                    rgba: None,
                 });
             }
 
@@ -167,6 +387,7 @@ impl Font for TrueTypeFont {
                 bearing_x: bounds.min.x as i32,
                 bearing_y: (scaled_font.ascent() - bounds.min.y) as i32,
                 bitmap,
+                rgba: None,
             })
         } else {
             // Space or glyph without outline
@@ -177,6 +398,7 @@ impl Font for TrueTypeFont {
                 bearing_x: 0,
                 bearing_y: 0,
                 bitmap: vec![0; (self.cell_width * self.cell_height) as usize],
+                rgba: None,
             })
         }
     }
@@ -188,6 +410,11 @@ impl Font for TrueTypeFont {
     }
 }
 
+/// Largest glyph width or height accepted from a BDF file, so a corrupt or
+/// hostile file can't request a huge bitmap.
+#[cfg(feature = "bitmap")]
+const MAX_BDF_GLYPH_SIZE: u32 = 1024;
+
 /// BDF bitmap font loaded from .bdf files.
 #[cfg(feature = "bitmap")]
 pub struct BitmapFont {
@@ -240,8 +467,13 @@ impl BitmapFont {
 
             if let Some(c) = char::from_u32(encoding_value) {
                 let bounds = glyph.bounding_box;
-                let width = bounds.size.x as u32;
-                let height = bounds.size.y as u32;
+                let size = |v: i32| u32::try_from(v).ok().filter(|&v| v <= MAX_BDF_GLYPH_SIZE);
+                let (Some(width), Some(height)) = (size(bounds.size.x), size(bounds.size.y)) else {
+                    return Err(TilesetError::BdfFontError(format!(
+                        "glyph {:?} has an invalid size {}x{}",
+                        c, bounds.size.x, bounds.size.y
+                    )));
+                };
 
                 max_width = max_width.max(width);
                 max_height = max_height.max(height);
@@ -274,7 +506,7 @@ impl BitmapFont {
                         width,
                         height,
                         bearing_x: bounds.offset.x,
-                        bearing_y: bounds.offset.y + height as i32,
+                        bearing_y: bounds.offset.y.saturating_add(height as i32),
                         bitmap,
                     },
                 );
@@ -324,6 +556,7 @@ impl Font for BitmapFont {
             bearing_x: g.bearing_x,
             bearing_y: g.bearing_y,
             bitmap: g.bitmap.clone(),
+            rgba: None,
         })
     }
 
@@ -458,6 +691,52 @@ ENDFONT
         assert!(result.is_err(), "Invalid BDF data should return error");
     }
 
+    #[test]
+    #[cfg(feature = "bitmap")]
+    fn test_bitmap_font_rejects_bad_glyph_size() {
+        let bdf = String::from_utf8(MINIMAL_BDF.to_vec()).unwrap().replacen(
+            "BBX 8 8 0 0",
+            "BBX -8 8 0 0",
+            1,
+        );
+        assert!(matches!(
+            BitmapFont::from_bytes(bdf.as_bytes()),
+            Err(TilesetError::BdfFontError(_))
+        ));
+    }
+
+    #[cfg(feature = "bitmap")]
+    proptest::proptest! {
+        #[test]
+        fn prop_bitmap_font_never_panics(data in proptest::collection::vec(proptest::num::u8::ANY, 0..512)) {
+            let _ = BitmapFont::from_bytes(&data);
+        }
+
+        #[test]
+        fn prop_bitmap_font_survives_odd_glyphs(
+            width in -4i32..40,
+            height in -4i32..40,
+            x in proptest::num::i32::ANY,
+            y in proptest::num::i32::ANY,
+            rows in proptest::collection::vec("[0-9A-F]{0,6}", 0..12),
+        ) {
+            let bdf = String::from_utf8(MINIMAL_BDF.to_vec())
+                .unwrap()
+                .replacen("BBX 8 8 0 0", &format!("BBX {width} {height} {x} {y}"), 2)
+                .replacen("18\n24\n42\n42\n7E\n42\n42\n00\n", &(rows.join("\n") + "\n"), 1);
+            if let Ok(font) = BitmapFont::from_bytes(bdf.as_bytes()) {
+                for c in ['A', 'B', ' '] {
+                    if let Some(glyph) = font.render_glyph(c) {
+                        proptest::prop_assert_eq!(
+                            glyph.bitmap.len(),
+                            (glyph.width * glyph.height) as usize
+                        );
+                    }
+                }
+            }
+        }
+    }
+
     #[test]
     #[cfg(feature = "truetype")]
     fn test_truetype_font_invalid_data() {
@@ -472,6 +751,71 @@ ENDFONT
         assert!(result.is_err(), "Empty data should return error");
     }
 
+    #[test]
+    #[cfg(feature = "truetype")]
+    fn test_truetype_metrics_override() {
+        let data = include_bytes!("../../../assets/font.ttf");
+        let plain = TrueTypeFont::from_bytes(data, 16.0).unwrap();
+        assert!(!plain.metrics().uses_cell_layout());
+
+        let forced = TrueTypeFont::from_bytes_with_metrics(
+            data,
+            16.0,
+            FontMetrics::new().with_cell_size(12, 20).with_padding(1),
+        )
+        .unwrap();
+        assert_eq!(forced.cell_width(), 14);
+        assert_eq!(forced.cell_height(), 20);
+        assert_eq!(forced.line_height(), 20);
+
+        // A forced size alone is enough to lay glyphs out in cells
+        let sized = FontMetrics::new().with_cell_size(12, 20);
+        assert!(sized.uses_cell_layout());
+        let sized = TrueTypeFont::from_bytes_with_metrics(data, 16.0, sized).unwrap();
+        let glyph = sized.render_glyph('i').unwrap();
+        assert_eq!((glyph.width, glyph.height), (12, 20));
+
+        // Cell layout renders every glyph as a whole cell
+        let glyph = forced.render_glyph('i').unwrap();
+        assert_eq!((glyph.width, glyph.height), (14, 20));
+        assert_eq!(glyph.bitmap.len(), 14 * 20);
+        // Padding columns stay blank
+        for y in 0..20 {
+            assert_eq!(glyph.bitmap[y * 14], 0);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "truetype")]
+    fn test_truetype_monospace_and_baseline() {
+        let data = include_bytes!("../../../assets/font.ttf");
+        let mono =
+            TrueTypeFont::from_bytes_with_metrics(data, 16.0, FontMetrics::new().monospace(true))
+                .unwrap();
+        let widest = (32u8..=126)
+            .map(|b| mono.advance(b as char))
+            .fold(0.0, f32::max);
+        assert_eq!(mono.cell_width(), widest.ceil() as u32);
+
+        let lowest_row = |font: &TrueTypeFont| {
+            let g = font.render_glyph('A').unwrap();
+            (0..g.height)
+                .rev()
+                .find(|&y| (0..g.width).any(|x| g.bitmap[(y * g.width + x) as usize] > 0))
+        };
+        let shifted = TrueTypeFont::from_bytes_with_metrics(
+            data,
+            16.0,
+            FontMetrics::new().monospace(true).with_baseline_offset(-2),
+        )
+        .unwrap();
+        assert_eq!(lowest_row(&shifted).map(|y| y + 2), lowest_row(&mono));
+
+        let custom = FontMetrics::new().with_advance('i', 3.0);
+        let font = TrueTypeFont::from_bytes_with_metrics(data, 16.0, custom).unwrap();
+        assert_eq!(font.advance('i'), 3.0);
+    }
+
     #[test]
     fn test_rendered_glyph_struct() {
         let glyph = RenderedGlyph {
@@ -481,6 +825,7 @@ ENDFONT
             bearing_x: 0,
             bearing_y: 8,
             bitmap: vec![255; 64],
+            rgba: None,
         };
 
         assert_eq!(glyph.character, '@');
@@ -14,7 +14,15 @@
 //!     *   **Images:** PNG, JPEG, etc. (via `image` crate).
 //!     *   **Fonts:** TrueType/OpenType (via `ab_glyph`) and BDF Bitmap fonts (via `bdf-parser`).
 //! *   **CP437 Mapping:** Built-in utilities to map characters to standard roguelike tilesets (e.g., Dwarf Fortress style).
+//! *   **Color Keys:** Magenta (or any color) backgrounds of old sheets keyed out to transparency,
+//!     with automatic detection for classic CP437 sheets.
+//! *   **Font Metrics:** [`FontMetrics`](font::FontMetrics) overrides cell size, baseline, padding and
+//!     per-glyph advances, and fits proportional TrueType fonts to a fixed grid.
+//! *   **Color Glyphs:** Emoji and icon fonts with `COLR`, `CBDT` or `sbix` tables render to RGBA
+//!     glyphs that keep their colors in the atlas; other fonts stay monochrome.
 //! *   **Atlas Generation:** Helper to create texture atlases from fonts.
+//!     Atlases can be saved as a PNG plus a metrics sidecar and loaded back, so fonts can be
+//!     pre-baked at build time instead of rasterized on startup.
 //!
 //! # Usage
 //!
@@ -64,6 +72,8 @@
 //! ```
 
 pub mod atlas;
+#[cfg(feature = "truetype")]
+mod color_glyph;
 pub mod error;
 pub mod font;
 pub mod tileset;
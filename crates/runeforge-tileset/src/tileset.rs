@@ -51,6 +51,22 @@ impl Tile {
     }
 }
 
+/// How a tileset's background becomes transparent.
+///
+/// Many classic tilesets are drawn on an opaque magenta or black background
+/// instead of using alpha, and render as solid blocks unless the background
+/// color is keyed out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Transparency {
+    /// Keep the image's own alpha.
+    #[default]
+    Opaque,
+    /// Treat every pixel of this color (ignoring alpha) as fully transparent.
+    Key(Color),
+    /// Key out the background found by [`Tileset::detect_background`], if any.
+    Auto,
+}
+
 /// A tileset loaded from an image file.
 ///
 /// Tilesets are sprite sheets containing a grid of fixed-size tiles.
@@ -258,13 +274,72 @@ impl Tileset {
     pub fn iter(&self) -> impl Iterator<Item = &Tile> {
         self.tiles.iter()
     }
+
+    /// Applies a transparency option to the loaded texture and tiles.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use runeforge_color::Color;
+    /// use runeforge_tileset::prelude::*;
+    ///
+    /// let tileset = Tileset::from_file("cp437_16x16.png", 16, 16)
+    ///     .unwrap()
+    ///     .with_transparency(Transparency::Key(Color::MAGENTA));
+    /// ```
+    pub fn with_transparency(mut self, transparency: Transparency) -> Self {
+        let key = match transparency {
+            Transparency::Opaque => return self,
+            Transparency::Key(color) => color,
+            Transparency::Auto => match self.detect_background() {
+                Some(color) => color,
+                None => return self,
+            },
+        };
+        key_out(&mut self.texture, key);
+        for tile in &mut self.tiles {
+            key_out(&mut tile.pixels, key);
+        }
+        self
+    }
+
+    /// Guesses the background color of a sheet drawn without alpha.
+    ///
+    /// Returns `None` if the image already has transparent pixels. Otherwise
+    /// the top-left pixel is taken as the background if it is magenta, or if
+    /// the first tile (the blank NUL glyph of a CP437 sheet) is entirely
+    /// that color.
+    pub fn detect_background(&self) -> Option<Color> {
+        if self.texture.chunks_exact(4).any(|px| px[3] < 255) {
+            return None;
+        }
+        let corner = self.tiles.first()?.get_color(0, 0)?;
+        let blank = self.tiles[0]
+            .pixels
+            .chunks_exact(4)
+            .all(|px| same_rgb(px, corner));
+        (blank || same_rgb(&[corner.r, corner.g, corner.b], Color::MAGENTA)).then_some(corner)
+    }
+}
+
+/// Sets alpha to 0 on every RGBA pixel matching `key`.
+fn key_out(pixels: &mut [u8], key: Color) {
+    for px in pixels.chunks_exact_mut(4) {
+        if same_rgb(px, key) {
+            px[3] = 0;
+        }
+    }
+}
+
+fn same_rgb(px: &[u8], color: Color) -> bool {
+    px[0] == color.r && px[1] == color.g && px[2] == color.b
 }
 
 /// Converts a Unicode character to its CP437 code point.
 ///
 /// CP437 is the classic IBM PC character set used by many roguelikes.
 /// Returns None for characters not in CP437.
-fn char_to_cp437(c: char) -> Option<u8> {
+pub fn char_to_cp437(c: char) -> Option<u8> {
     // Standard ASCII range (0x20-0x7E maps directly)
     if c as u32 >= 0x20 && c as u32 <= 0x7E {
         return Some(c as u8);
@@ -306,7 +381,58 @@ fn char_to_cp437(c: char) -> Option<u8> {
         '▲' => Some(30),
         '▼' => Some(31),
 
+        // House (0x7F)
+        '⌂' => Some(127),
+
         // Extended ASCII (0x80-0xFF)
+        'Ç' => Some(128),
+        'ü' => Some(129),
+        'é' => Some(130),
+        'â' => Some(131),
+        'ä' => Some(132),
+        'à' => Some(133),
+        'å' => Some(134),
+        'ç' => Some(135),
+        'ê' => Some(136),
+        'ë' => Some(137),
+        'è' => Some(138),
+        'ï' => Some(139),
+        'î' => Some(140),
+        'ì' => Some(141),
+        'Ä' => Some(142),
+        'Å' => Some(143),
+        'É' => Some(144),
+        'æ' => Some(145),
+        'Æ' => Some(146),
+        'ô' => Some(147),
+        'ö' => Some(148),
+        'ò' => Some(149),
+        'û' => Some(150),
+        'ù' => Some(151),
+        'ÿ' => Some(152),
+        'Ö' => Some(153),
+        'Ü' => Some(154),
+        '¢' => Some(155),
+        '£' => Some(156),
+        '¥' => Some(157),
+        '₧' => Some(158),
+        'ƒ' => Some(159),
+        'á' => Some(160),
+        'í' => Some(161),
+        'ó' => Some(162),
+        'ú' => Some(163),
+        'ñ' => Some(164),
+        'Ñ' => Some(165),
+        'ª' => Some(166),
+        'º' => Some(167),
+        '¿' => Some(168),
+        '⌐' => Some(169),
+        '¬' => Some(170),
+        '½' => Some(171),
+        '¼' => Some(172),
+        '¡' => Some(173),
+        '«' => Some(174),
+        '»' => Some(175),
         '░' => Some(176),
         '▒' => Some(177),
         '▓' => Some(178),
@@ -478,6 +604,7 @@ impl crate::font::Font for Tileset {
             bearing_x: 0,
             bearing_y: tile.height as i32,
             bitmap,
+            rgba: None,
         })
     }
 
@@ -514,6 +641,23 @@ mod tests {
         assert_eq!(char_to_cp437('░'), Some(176));
     }
 
+    #[test]
+    fn test_cp437_table_round_trips() {
+        for code in 0..=255u8 {
+            let c = cp437_to_char(code);
+            assert_eq!(char_to_cp437(c).map(cp437_to_char), Some(c), "{code:#04x}");
+        }
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_char_to_cp437_inverts(c in proptest::char::any()) {
+            if let Some(code) = char_to_cp437(c) {
+                proptest::prop_assert_eq!(cp437_to_char(code), c);
+            }
+        }
+    }
+
     #[test]
     fn test_cp437_roundtrip() {
         // Test that common characters roundtrip correctly
@@ -650,4 +794,51 @@ mod tests {
         // Tile larger than image should fail
         assert!(Tileset::from_image(image::DynamicImage::ImageRgba8(img), 8, 8).is_err());
     }
+
+    fn sheet(background: [u8; 3]) -> Tileset {
+        // Two 2x2 tiles: a blank one and one with a white pixel
+        let mut pixels = Vec::new();
+        for i in 0..8 {
+            let [r, g, b] = if i == 6 { [255; 3] } else { background };
+            pixels.extend_from_slice(&[r, g, b, 255]);
+        }
+        // Row-major over a 4x2 image: pixel 6 is (2, 1), inside tile 1
+        let img = image::RgbaImage::from_raw(4, 2, pixels).unwrap();
+        Tileset::from_image(image::DynamicImage::ImageRgba8(img), 2, 2).unwrap()
+    }
+
+    #[test]
+    fn test_color_key() {
+        let tileset = sheet([0, 0, 40]).with_transparency(Transparency::Key(Color::rgb(0, 0, 40)));
+        let tile = tileset.get_tile(1).unwrap();
+        assert_eq!(tile.get_pixel(0, 0), Some([0, 0, 40, 0]));
+        assert_eq!(tile.get_pixel(0, 1), Some([255, 255, 255, 255]));
+        assert_eq!(tileset.texture[3], 0);
+
+        let opaque = sheet([0, 0, 40]).with_transparency(Transparency::Opaque);
+        assert_eq!(
+            opaque.get_tile(1).unwrap().get_pixel(0, 0),
+            Some([0, 0, 40, 255])
+        );
+    }
+
+    #[test]
+    fn test_detect_background() {
+        let magenta = sheet([255, 0, 255]);
+        assert_eq!(magenta.detect_background(), Some(Color::MAGENTA));
+        let keyed = magenta.with_transparency(Transparency::Auto);
+        assert_eq!(
+            keyed.get_tile(0).unwrap().get_pixel(1, 1),
+            Some([255, 0, 255, 0])
+        );
+
+        // Already transparent: nothing to detect
+        assert_eq!(keyed.detect_background(), None);
+
+        // The blank first tile gives the background away
+        assert_eq!(
+            sheet([10, 20, 30]).detect_background(),
+            Some(Color::rgb(10, 20, 30))
+        );
+    }
 }
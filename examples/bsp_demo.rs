@@ -66,7 +66,7 @@ fn main() -> io::Result<()> {
     // Demo 1: Default configuration
     let config1 = BspConfig::default();
     let mut rng = Rng::new();
-    let dungeon1 = DungeonGenerator::generate(60, 25, &config1, &mut rng);
+    let dungeon1 = DungeonGenerator::generate(60, 25, &config1, &mut rng).unwrap();
     render_dungeon(&dungeon1, "Demo 1: Default Configuration (60x25)");
     println!("\nDefault settings create balanced, medium-sized rooms.");
     thread::sleep(Duration::from_secs(3));
@@ -76,7 +76,7 @@ fn main() -> io::Result<()> {
         .with_min_partition_size(8, 8)
         .with_min_room_size(3, 3)
         .with_max_depth(6);
-    let dungeon2 = DungeonGenerator::generate(60, 25, &config2, &mut rng);
+    let dungeon2 = DungeonGenerator::generate(60, 25, &config2, &mut rng).unwrap();
     render_dungeon(&dungeon2, "Demo 2: Many Small Rooms (depth=6, min=3x3)");
     println!("\nIncreasing depth and reducing minimum sizes creates more rooms.");
     thread::sleep(Duration::from_secs(3));
@@ -86,7 +86,7 @@ fn main() -> io::Result<()> {
         .with_min_partition_size(20, 15)
         .with_min_room_size(8, 6)
         .with_max_depth(3);
-    let dungeon3 = DungeonGenerator::generate(60, 25, &config3, &mut rng);
+    let dungeon3 = DungeonGenerator::generate(60, 25, &config3, &mut rng).unwrap();
     render_dungeon(&dungeon3, "Demo 3: Few Large Rooms (depth=3, min=8x6)");
     println!("\nReducing depth and increasing minimums creates fewer, larger rooms.");
     thread::sleep(Duration::from_secs(3));
@@ -95,7 +95,7 @@ fn main() -> io::Result<()> {
     let config4 = BspConfig::new()
         .with_split_ratio(0.3, 0.7)
         .with_max_depth(5);
-    let dungeon4 = DungeonGenerator::generate(60, 25, &config4, &mut rng);
+    let dungeon4 = DungeonGenerator::generate(60, 25, &config4, &mut rng).unwrap();
     render_dungeon(&dungeon4, "Demo 4: Varied Room Sizes (ratio 0.3-0.7)");
     println!("\nWider split ratio range creates more variety in room sizes.");
     thread::sleep(Duration::from_secs(3));
@@ -105,7 +105,7 @@ fn main() -> io::Result<()> {
         .with_min_partition_size(12, 10)
         .with_min_room_size(5, 4)
         .with_max_depth(5);
-    let dungeon5 = DungeonGenerator::generate(80, 30, &config5, &mut rng);
+    let dungeon5 = DungeonGenerator::generate(80, 30, &config5, &mut rng).unwrap();
     render_dungeon(&dungeon5, "Demo 5: Large Dungeon (80x30)");
     println!("\nLarger dungeons with appropriate settings for roguelike games.");
     thread::sleep(Duration::from_secs(3));
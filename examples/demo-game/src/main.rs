@@ -123,7 +123,7 @@ impl Game {
             .with_max_depth(5)
             .with_room_padding(1);
 
-        let dungeon = DungeonGenerator::generate(MAP_WIDTH, MAP_HEIGHT, &config, &mut rng);
+        let dungeon = DungeonGenerator::generate(MAP_WIDTH, MAP_HEIGHT, &config, &mut rng).unwrap();
 
         // Convert dungeon to tile map
         let mut map = vec![vec![Tile::Wall; MAP_WIDTH as usize]; MAP_HEIGHT as usize];
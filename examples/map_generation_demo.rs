@@ -45,7 +45,7 @@ fn demo_bsp_dungeon(rng: &mut Rng) {
         .with_min_room_size(4, 4)
         .with_max_depth(5);
 
-    let dungeon = DungeonGenerator::generate(MAP_WIDTH, MAP_HEIGHT, &config, rng);
+    let dungeon = DungeonGenerator::generate(MAP_WIDTH, MAP_HEIGHT, &config, rng).unwrap();
 
     // Print the dungeon
     for y in 0..dungeon.height() {
@@ -128,7 +128,7 @@ fn demo_comparison(rng: &mut Rng) {
 
     // Generate all three
     let bsp_config = BspConfig::default().with_min_room_size(3, 3);
-    let bsp = DungeonGenerator::generate(small_width, small_height, &bsp_config, rng);
+    let bsp = DungeonGenerator::generate(small_width, small_height, &bsp_config, rng).unwrap();
 
     let cave_config = CaveConfig::default();
     let cellular = CaveGenerator::generate(small_width, small_height, &cave_config, rng).unwrap();
@@ -8,15 +8,16 @@
 //! - ESC: Close window
 //!
 //! Configuration:
-//! - Adjust SCALE_FACTOR constant (line 26) to change window size
-//! - Default is 3x for crisp, pixel-perfect scaling
+//! - Adjust SCALE_FACTOR constant (line 30) to change the starting window size
+//! - The window can be resized; the console is scaled by whole multiples and
+//!   letterboxed to stay crisp (see `DisplayOptions`)
 //!
 //! Run with: cargo run --example windowed_roguelike
 
 use runeforge_color::Color;
-use runeforge_geometry::prelude::IVec2;
+use runeforge_geometry::prelude::{IVec2, UVec2};
 use runeforge_input::{InputMap, VirtualKey};
-use runeforge_terminal::prelude::{Console, PixelsRenderer};
+use runeforge_terminal::prelude::{Console, DisplayOptions, PixelsRenderer, ScaleMode};
 use runeforge_tileset::prelude::TrueTypeFont;
 
 use pixels::{Pixels, SurfaceTexture};
@@ -39,7 +40,8 @@ struct Game {
 
 impl Game {
     fn new(font: &dyn runeforge_tileset::prelude::Font) -> Self {
-        let renderer = PixelsRenderer::new(CONSOLE_WIDTH, CONSOLE_HEIGHT, font);
+        let renderer = PixelsRenderer::new(CONSOLE_WIDTH, CONSOLE_HEIGHT, font)
+            .with_display_options(DisplayOptions::new().with_scale_mode(ScaleMode::Integer));
         Self {
             window: None,
             pixels: None,
@@ -169,7 +171,7 @@ impl ApplicationHandler for Game {
                 self.renderer.pixel_width() * SCALE_FACTOR,
                 self.renderer.pixel_height() * SCALE_FACTOR,
             ))
-            .with_resizable(false);
+            .with_resizable(true);
 
         let window = event_loop
             .create_window(window_attributes)
@@ -184,12 +186,11 @@ impl ApplicationHandler for Game {
         let surface_texture =
             SurfaceTexture::new(window_size.width, window_size.height, window_ref);
 
-        let pixels = Pixels::new(
-            self.renderer.pixel_width(),
-            self.renderer.pixel_height(),
-            surface_texture,
-        )
-        .expect("Failed to create Pixels");
+        // The pixels buffer matches the window; the renderer scales into it
+        let pixels = Pixels::new(window_size.width, window_size.height, surface_texture)
+            .expect("Failed to create Pixels");
+        self.renderer
+            .set_window_size(UVec2::new(window_size.width, window_size.height));
 
         self.window = Some(window_ref);
         self.pixels = Some(pixels);
@@ -242,6 +243,24 @@ impl ApplicationHandler for Game {
                     }
                 }
             }
+            WindowEvent::Resized(size) => {
+                if size.width == 0 || size.height == 0 {
+                    // Minimized
+                    return;
+                }
+                if let Some(pixels) = &mut self.pixels {
+                    if let Err(e) = pixels.resize_surface(size.width, size.height) {
+                        eprintln!("pixels.resize_surface() failed: {}", e);
+                    }
+                    if let Err(e) = pixels.resize_buffer(size.width, size.height) {
+                        eprintln!("pixels.resize_buffer() failed: {}", e);
+                    }
+                }
+                self.renderer
+                    .set_window_size(UVec2::new(size.width, size.height));
+                self.render_scene();
+                self.update_pixels();
+            }
             WindowEvent::RedrawRequested => {
                 self.update_pixels();
             }
@@ -253,9 +272,12 @@ impl ApplicationHandler for Game {
 impl Game {
     fn update_pixels(&mut self) {
         if let (Some(window), Some(pixels)) = (&self.window, &mut self.pixels) {
-            // Copy renderer's pixel buffer to pixels frame
+            // Copy the renderer's scaled frame to the pixels frame
             let frame = pixels.frame_mut();
-            frame.copy_from_slice(self.renderer.pixel_buffer());
+            let display = self.renderer.display_frame();
+            if frame.len() == display.len() {
+                frame.copy_from_slice(display);
+            }
 
             // Render to window
             if let Err(e) = pixels.render() {
@@ -271,9 +293,9 @@ fn main() {
     println!("Windowed Roguelike Demo");
     println!("======================");
     println!();
-    println!("Note: Adjust SCALE_FACTOR constant to change window size");
+    println!("Note: Adjust SCALE_FACTOR constant to change the starting window size");
     println!(
-        "      Current scale: {}x (change line 26 in source)",
+        "      Current scale: {}x (change line 30 in source)",
         SCALE_FACTOR
     );
     println!();
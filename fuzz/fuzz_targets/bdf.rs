@@ -0,0 +1,17 @@
+//! BDF fonts are loaded from downloaded files.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use runeforge_tileset::prelude::*;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(font) = BitmapFont::from_bytes(data) else {
+        return;
+    };
+    for c in (0u32..0x800).filter_map(char::from_u32) {
+        if let Some(glyph) = font.render_glyph(c) {
+            assert_eq!(glyph.bitmap.len(), (glyph.width * glyph.height) as usize);
+        }
+    }
+});
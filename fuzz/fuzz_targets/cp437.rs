@@ -0,0 +1,20 @@
+//! CP437 conversions run on text from downloaded tilesets and `.rfc` files.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use runeforge_tileset::prelude::*;
+
+fuzz_target!(|data: &[u8]| {
+    for &code in data {
+        let c = cp437_to_char(code);
+        assert_eq!(char_to_cp437(c).map(cp437_to_char), Some(c));
+    }
+    if let Ok(text) = std::str::from_utf8(data) {
+        for c in text.chars() {
+            if let Some(code) = char_to_cp437(c) {
+                assert_eq!(cp437_to_char(code), c);
+            }
+        }
+    }
+});
@@ -0,0 +1,12 @@
+//! Dice notation comes from user config files and mod data.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use runeforge_random::prelude::*;
+
+fuzz_target!(|notation: &str| {
+    if let Ok((_, sides, _)) = parse_dice(notation) {
+        assert!(sides >= 1, "{notation:?} parsed with {sides} sides");
+    }
+});
@@ -0,0 +1,28 @@
+//! Rect and line iterators, with coordinates small enough to walk in full.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use runeforge_geometry::prelude::*;
+
+fuzz_target!(|input: (i16, i16, i16, i16, u8, u8)| {
+    let (x0, y0, x1, y1, w, h) = input;
+    let start = IVec2::new(x0.into(), y0.into());
+    let end = IVec2::new(x1.into(), y1.into());
+
+    let line: Vec<IVec2> = Line::new(start, end).into_iter().collect();
+    assert_eq!(line.first(), Some(&start));
+    assert_eq!(line.last(), Some(&end));
+    assert_eq!(line.len() as i32, (end - start).abs().max_element() + 1);
+    for pair in line.windows(2) {
+        assert_eq!((pair[1] - pair[0]).abs().max_element(), 1);
+    }
+
+    let rect = Rect::new_xywh(start.x, start.y, w.into(), h.into());
+    let mut count = 0;
+    for p in rect {
+        assert!(rect.intersects(Rect::new(p, p)));
+        count += 1;
+    }
+    assert_eq!(count, (i32::from(w) + 1) * (i32::from(h) + 1));
+});
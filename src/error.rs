@@ -0,0 +1,220 @@
+//! A single error type covering every Runeforge crate.
+//!
+//! Each crate keeps its own error enum so it can be used on its own. When a
+//! game uses several crates, [`RuneforgeError`] wraps them all so one `?`
+//! works everywhere, while [`RuneforgeError::code`] still lets callers match on
+//! the exact failure without parsing messages.
+//!
+//! ```
+//! use runeforge_rl::error::RuneforgeError;
+//! use runeforge_rl::random::prelude::*;
+//!
+//! fn damage(notation: &str) -> Result<i32, RuneforgeError> {
+//!     Ok(Rng::with_seed(1).roll(notation)?)
+//! }
+//!
+//! let err = damage("2d").unwrap_err();
+//! assert_eq!(err.code(), "dice.invalid_sides");
+//! ```
+
+use thiserror::Error;
+
+/// Any error produced by a Runeforge crate.
+///
+/// Variants only exist for the crates enabled through features.
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum RuneforgeError {
+    /// Invalid dice notation.
+    #[error(transparent)]
+    Dice(#[from] crate::random::prelude::DiceError),
+
+    /// Invalid seed code or generation manifest.
+    #[error(transparent)]
+    Seed(#[from] crate::random::prelude::SeedError),
+
+    /// A terrain edit could not be made.
+    #[error(transparent)]
+    Terrain(#[from] crate::terrain::TerrainError),
+
+    /// BSP dungeon generation failed.
+    #[cfg(feature = "algorithms")]
+    #[error(transparent)]
+    Bsp(#[from] crate::algorithms::bsp::BspError),
+
+    /// Cave generation failed.
+    #[cfg(feature = "algorithms")]
+    #[error(transparent)]
+    Cave(#[from] crate::algorithms::caves::CaveError),
+
+    /// Drunkard's walk generation failed.
+    #[cfg(feature = "algorithms")]
+    #[error(transparent)]
+    Drunkard(#[from] crate::algorithms::drunkard::DrunkardError),
+
+    /// DLA generation failed.
+    #[cfg(feature = "algorithms")]
+    #[error(transparent)]
+    Dla(#[from] crate::algorithms::dla::DlaError),
+
+    /// Noise cave generation failed.
+    #[cfg(feature = "algorithms")]
+    #[error(transparent)]
+    NoiseCave(#[from] crate::algorithms::noise_cave::NoiseCaveError),
+
+    /// Locks and keys could not be placed.
+    #[cfg(feature = "algorithms")]
+    #[error(transparent)]
+    Lock(#[from] crate::algorithms::locks::LockError),
+
+    /// A road network could not be built.
+    #[cfg(feature = "algorithms")]
+    #[error(transparent)]
+    Road(#[from] crate::algorithms::roads::RoadError),
+
+    /// A dungeon could not be populated.
+    #[cfg(feature = "algorithms")]
+    #[error(transparent)]
+    Spawn(#[from] crate::algorithms::spawn::SpawnError),
+
+    /// A town could not be generated.
+    #[cfg(feature = "algorithms")]
+    #[error(transparent)]
+    Town(#[from] crate::algorithms::town::TownError),
+
+    /// A Voronoi map could not be built.
+    #[cfg(feature = "algorithms")]
+    #[error(transparent)]
+    Voronoi(#[from] crate::algorithms::voronoi::VoronoiError),
+
+    /// A map could not be read from text.
+    #[cfg(feature = "algorithms")]
+    #[error(transparent)]
+    AsciiMap(#[from] crate::algorithms::ascii::AsciiMapError),
+
+    /// Noise map generation failed.
+    #[cfg(feature = "noise")]
+    #[error(transparent)]
+    Noise(#[from] crate::noise::NoiseError),
+
+    /// Biome mapping failed.
+    #[cfg(feature = "noise")]
+    #[error(transparent)]
+    Biome(#[from] crate::noise::BiomeError),
+
+    /// River and lake computation failed.
+    #[cfg(feature = "noise")]
+    #[error(transparent)]
+    Hydrology(#[from] crate::noise::HydrologyError),
+
+    /// An action could not be performed or undone.
+    #[cfg(feature = "core")]
+    #[error(transparent)]
+    Action(#[from] crate::core::action::ActionError),
+
+    /// An inventory operation failed.
+    #[cfg(feature = "core")]
+    #[error(transparent)]
+    Inventory(#[from] crate::core::inventory::InventoryError),
+
+    /// Entity templates could not be loaded.
+    #[cfg(feature = "core")]
+    #[error(transparent)]
+    Template(#[from] crate::core::template::TemplateError),
+
+    /// A high-score file could not be read or written.
+    #[cfg(feature = "core")]
+    #[error(transparent)]
+    Score(#[from] crate::core::score::ScoreError),
+
+    /// A replay could not be loaded or diverged.
+    #[cfg(feature = "input")]
+    #[error(transparent)]
+    Replay(#[from] crate::input::replay::ReplayError),
+
+    /// A lockstep session failed or desynced.
+    #[cfg(feature = "net")]
+    #[error(transparent)]
+    Net(#[from] crate::net::error::NetError),
+
+    /// A script failed to compile or run.
+    #[cfg(feature = "script")]
+    #[error(transparent)]
+    Script(#[from] crate::script::error::ScriptError),
+
+    /// A console file could not be read or written.
+    #[cfg(feature = "terminal")]
+    #[error(transparent)]
+    Rfc(#[from] crate::terminal::rfc::RfcError),
+
+    /// A message could not be loaded or formatted.
+    #[cfg(feature = "text")]
+    #[error(transparent)]
+    Text(#[from] crate::text::error::TextError),
+
+    /// A font or tileset could not be loaded.
+    #[cfg(feature = "tileset")]
+    #[error(transparent)]
+    Tileset(#[from] crate::tileset::error::TilesetError),
+}
+
+impl RuneforgeError {
+    /// Returns the stable machine-readable code of the wrapped error,
+    /// such as `"dice.missing_separator"`.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Dice(e) => e.code(),
+            Self::Seed(e) => e.code(),
+            Self::Terrain(e) => e.code(),
+            #[cfg(feature = "algorithms")]
+            Self::Bsp(e) => e.code(),
+            #[cfg(feature = "algorithms")]
+            Self::Cave(e) => e.code(),
+            #[cfg(feature = "algorithms")]
+            Self::Drunkard(e) => e.code(),
+            #[cfg(feature = "algorithms")]
+            Self::Dla(e) => e.code(),
+            #[cfg(feature = "algorithms")]
+            Self::NoiseCave(e) => e.code(),
+            #[cfg(feature = "algorithms")]
+            Self::Lock(e) => e.code(),
+            #[cfg(feature = "algorithms")]
+            Self::Road(e) => e.code(),
+            #[cfg(feature = "algorithms")]
+            Self::Spawn(e) => e.code(),
+            #[cfg(feature = "algorithms")]
+            Self::Town(e) => e.code(),
+            #[cfg(feature = "algorithms")]
+            Self::Voronoi(e) => e.code(),
+            #[cfg(feature = "algorithms")]
+            Self::AsciiMap(e) => e.code(),
+            #[cfg(feature = "noise")]
+            Self::Noise(e) => e.code(),
+            #[cfg(feature = "noise")]
+            Self::Biome(e) => e.code(),
+            #[cfg(feature = "noise")]
+            Self::Hydrology(e) => e.code(),
+            #[cfg(feature = "core")]
+            Self::Action(e) => e.code(),
+            #[cfg(feature = "core")]
+            Self::Inventory(e) => e.code(),
+            #[cfg(feature = "core")]
+            Self::Template(e) => e.code(),
+            #[cfg(feature = "core")]
+            Self::Score(e) => e.code(),
+            #[cfg(feature = "input")]
+            Self::Replay(e) => e.code(),
+            #[cfg(feature = "net")]
+            Self::Net(e) => e.code(),
+            #[cfg(feature = "script")]
+            Self::Script(e) => e.code(),
+            #[cfg(feature = "terminal")]
+            Self::Rfc(e) => e.code(),
+            #[cfg(feature = "text")]
+            Self::Text(e) => e.code(),
+            #[cfg(feature = "tileset")]
+            Self::Tileset(e) => e.code(),
+        }
+    }
+}
@@ -48,13 +48,19 @@
 //!
 //! `runeforge` is highly modular. You can enable features for the functionality you need:
 //!
+//! -   `ai`: Composable monster AI behaviors.
 //! -   `algorithms`: Procedural generation (BSP, Cellular Automata).
+//! -   `combat`: Dice-driven attack resolution (implies `core`).
+//! -   `core`: Game-object plumbing (entities, event bus).
 //! -   `direction`: Grid-based direction handling.
 //! -   `fov`: Field-of-view algorithms.
 //! -   `input`: Action-based input mapping.
+//! -   `net`: Experimental lockstep turn exchange.
 //! -   `noise`: Perlin noise generation.
 //! -   `pathfinding`: A* and other pathfinding algorithms.
+//! -   `script`: Rhai scripting hooks for content.
 //! -   `terminal`: A complete rendering solution with multiple backends.
+//! -   `text`: Localizable message catalogs and templates.
 //! -   `tileset`: Loading for graphical tilesets and fonts.
 //! -   `full`: Enables all features.
 //!
@@ -71,9 +77,15 @@ pub use runeforge_geometry as geometry;
 pub use runeforge_random as random;
 
 // Optional feature-gated crates
+#[cfg(feature = "ai")]
+pub use runeforge_ai as ai;
+
 #[cfg(feature = "algorithms")]
 pub use runeforge_algorithms as algorithms;
 
+#[cfg(feature = "core")]
+pub use runeforge_core as core;
+
 #[cfg(feature = "direction")]
 pub use runeforge_direction as direction;
 
@@ -83,44 +95,76 @@ pub use runeforge_fov as fov;
 #[cfg(feature = "input")]
 pub use runeforge_input as input;
 
+#[cfg(feature = "net")]
+pub use runeforge_net as net;
+
 #[cfg(feature = "noise")]
 pub use runeforge_noise as noise;
 
 #[cfg(feature = "pathfinding")]
 pub use runeforge_pathfinding as pathfinding;
 
+#[cfg(feature = "script")]
+pub use runeforge_script as script;
+
 #[cfg(feature = "terminal")]
 pub use runeforge_terminal as terminal;
 
+#[cfg(feature = "text")]
+pub use runeforge_text as text;
+
 #[cfg(feature = "tileset")]
 pub use runeforge_tileset as tileset;
 
+pub mod error;
+pub mod terrain;
+
 /// Prelude module for convenient imports.
 pub mod prelude {
     // Core types (always available)
     pub use crate::color::Color;
+    pub use crate::error::RuneforgeError;
     pub use crate::geometry::prelude::*;
     pub use crate::random::prelude::*;
+    pub use crate::terrain::{
+        Connectivity, DirtyTiles, TerrainEditor, TerrainError, TerrainMap, TerrainObserver,
+        TileChange, TileProperties,
+    };
 
     // Optional feature-gated modules
+    #[cfg(feature = "ai")]
+    pub use crate::ai::prelude::*;
+
     #[cfg(feature = "algorithms")]
     pub use crate::algorithms::prelude::*;
 
+    #[cfg(feature = "core")]
+    pub use crate::core::prelude::*;
+
     #[cfg(feature = "fov")]
     pub use crate::fov::prelude::*;
 
     #[cfg(feature = "input")]
     pub use crate::input::*;
 
+    #[cfg(feature = "net")]
+    pub use crate::net::prelude::*;
+
     #[cfg(feature = "noise")]
     pub use crate::noise::*;
 
     #[cfg(feature = "pathfinding")]
     pub use crate::pathfinding::prelude::*;
 
+    #[cfg(feature = "script")]
+    pub use crate::script::prelude::*;
+
     #[cfg(feature = "terminal")]
     pub use crate::terminal::prelude::*;
 
+    #[cfg(feature = "text")]
+    pub use crate::text::prelude::*;
+
     #[cfg(feature = "tileset")]
     pub use crate::tileset::prelude::*;
 }
@@ -0,0 +1,796 @@
+//! Terrain edits that keep dependent systems in sync.
+//!
+//! Digging out a wall changes more than the tile: the FOV opacity grid, the
+//! pathfinding cost grid, the autotiled glyphs around it and the map's
+//! connected regions all go stale. A [`TerrainEditor`] wraps the game's tile
+//! layer (anything implementing [`TerrainMap`]) and tells every registered
+//! [`TerrainObserver`] about each change, so one call keeps them all up to
+//! date.
+//!
+//! Observers are implemented for:
+//!
+//! -   [`OpacityGrid`](crate::fov::prelude::OpacityGrid) and
+//!     [`FovCache`](crate::fov::prelude::FovCache), with the `fov` feature.
+//! -   [`CostGrid`](crate::pathfinding::prelude::CostGrid), with the
+//!     `pathfinding` feature.
+//! -   [`DirtyTiles`], which collects the tiles to re-autotile.
+//! -   [`Connectivity`], which labels connected regions and relabels them
+//!     when passability changes.
+//!
+//! # Example
+//!
+//! Needs the `fov` and `pathfinding` features (both on by default).
+//!
+#![cfg_attr(all(feature = "fov", feature = "pathfinding"), doc = "```")]
+#![cfg_attr(not(all(feature = "fov", feature = "pathfinding")), doc = "```ignore")]
+//! use runeforge_rl::prelude::*;
+//!
+//! #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+//! enum Tile {
+//!     Floor,
+//!     Wall,
+//!     ClosedDoor,
+//!     OpenDoor,
+//! }
+//!
+//! struct Map {
+//!     width: i32,
+//!     tiles: Vec<Tile>,
+//! }
+//!
+//! impl TerrainMap for Map {
+//!     type Tile = Tile;
+//!
+//!     fn tile(&self, pos: IVec2) -> Option<Tile> {
+//!         if pos.x < 0 || pos.x >= self.width || pos.y < 0 {
+//!             return None;
+//!         }
+//!         self.tiles.get((pos.y * self.width + pos.x) as usize).copied()
+//!     }
+//!
+//!     fn set_tile(&mut self, pos: IVec2, tile: Tile) {
+//!         self.tiles[(pos.y * self.width + pos.x) as usize] = tile;
+//!     }
+//!
+//!     fn properties(&self, tile: Tile) -> TileProperties {
+//!         match tile {
+//!             Tile::Floor | Tile::OpenDoor => TileProperties::FLOOR,
+//!             Tile::Wall => TileProperties::WALL,
+//!             Tile::ClosedDoor => TileProperties::new(true, Some(2)),
+//!         }
+//!     }
+//!
+//!     fn dug(&self, tile: Tile) -> Option<Tile> {
+//!         (tile == Tile::Wall).then_some(Tile::Floor)
+//!     }
+//!
+//!     fn opened(&self, tile: Tile) -> Option<Tile> {
+//!         (tile == Tile::ClosedDoor).then_some(Tile::OpenDoor)
+//!     }
+//!
+//!     fn closed(&self, tile: Tile) -> Option<Tile> {
+//!         (tile == Tile::OpenDoor).then_some(Tile::ClosedDoor)
+//!     }
+//! }
+//!
+//! // Two rooms split by a wall at x = 2
+//! let is_wall = |p: IVec2| p.x == 2;
+//! let tiles = (0..15).map(|i| if i % 5 == 2 { Tile::Wall } else { Tile::Floor });
+//! let mut map = Map { width: 5, tiles: tiles.collect() };
+//!
+//! let mut opacity = OpacityGrid::from_fn(5, 3, is_wall);
+//! let mut costs = CostGrid::from_walkable(5, 3, |p| !is_wall(p));
+//! let mut regions = Connectivity::from_fn(5, 3, |p| !is_wall(p));
+//! let mut dirty = DirtyTiles::new();
+//! assert!(!regions.connected(IVec2::new(0, 1), IVec2::new(4, 1)));
+//!
+//! let mut editor = TerrainEditor::new(&mut map)
+//!     .with_observer(&mut opacity)
+//!     .with_observer(&mut costs)
+//!     .with_observer(&mut regions)
+//!     .with_observer(&mut dirty);
+//! editor.dig(IVec2::new(2, 1)).unwrap();
+//! assert!(editor.open_door(IVec2::new(2, 1)).is_err());
+//!
+//! // One call updated everything
+//! assert!(!opacity.is_opaque(IVec2::new(2, 1)));
+//! assert!(costs.is_passable(IVec2::new(2, 1)));
+//! assert!(regions.connected(IVec2::new(0, 1), IVec2::new(4, 1)));
+//! assert_eq!(dirty.take().len(), 9);
+//! ```
+
+use std::collections::HashSet;
+
+use thiserror::Error;
+
+use crate::geometry::prelude::*;
+
+/// How a tile affects sight and movement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TileProperties {
+    /// Whether the tile blocks sight.
+    pub opaque: bool,
+    /// Cost of moving onto the tile, or `None` if it can't be entered.
+    pub movement_cost: Option<u32>,
+}
+
+impl TileProperties {
+    /// Open floor: see-through, costs 1 to enter.
+    pub const FLOOR: Self = Self::new(false, Some(1));
+
+    /// Solid wall: opaque and impassable.
+    pub const WALL: Self = Self::new(true, None);
+
+    /// Creates tile properties.
+    #[must_use]
+    pub const fn new(opaque: bool, movement_cost: Option<u32>) -> Self {
+        Self {
+            opaque,
+            movement_cost,
+        }
+    }
+
+    /// Returns true if the tile can be entered.
+    #[must_use]
+    pub const fn is_passable(&self) -> bool {
+        self.movement_cost.is_some()
+    }
+}
+
+/// A game's tile layer, as edited by a [`TerrainEditor`].
+///
+/// Only [`tile`](Self::tile), [`set_tile`](Self::set_tile) and
+/// [`properties`](Self::properties) are required. The rest say which tiles
+/// can be dug or opened, and default to none.
+pub trait TerrainMap {
+    /// The game's tile type.
+    type Tile: Copy + PartialEq;
+
+    /// Returns the tile at `pos`, or `None` outside the map.
+    fn tile(&self, pos: IVec2) -> Option<Self::Tile>;
+
+    /// Replaces the tile at `pos`, which is inside the map.
+    fn set_tile(&mut self, pos: IVec2, tile: Self::Tile);
+
+    /// Returns how `tile` affects sight and movement.
+    fn properties(&self, tile: Self::Tile) -> TileProperties;
+
+    /// Returns what `tile` becomes when dug out, or `None` if it can't be.
+    fn dug(&self, _tile: Self::Tile) -> Option<Self::Tile> {
+        None
+    }
+
+    /// Returns what `tile` becomes when opened, or `None` if it isn't a
+    /// closed door.
+    fn opened(&self, _tile: Self::Tile) -> Option<Self::Tile> {
+        None
+    }
+
+    /// Returns what `tile` becomes when closed, or `None` if it isn't an
+    /// open door.
+    fn closed(&self, _tile: Self::Tile) -> Option<Self::Tile> {
+        None
+    }
+}
+
+/// A tile that a [`TerrainEditor`] changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileChange {
+    /// Position of the tile.
+    pub pos: IVec2,
+    /// Properties of the old tile.
+    pub before: TileProperties,
+    /// Properties of the new tile.
+    pub after: TileProperties,
+}
+
+impl TileChange {
+    /// Returns true if the tile started or stopped blocking sight.
+    #[must_use]
+    pub const fn opacity_changed(&self) -> bool {
+        self.before.opaque != self.after.opaque
+    }
+
+    /// Returns true if the tile became passable or impassable.
+    #[must_use]
+    pub const fn passability_changed(&self) -> bool {
+        self.before.is_passable() != self.after.is_passable()
+    }
+
+    /// Returns true if the cost of entering the tile changed.
+    #[must_use]
+    pub fn cost_changed(&self) -> bool {
+        self.before.movement_cost != self.after.movement_cost
+    }
+}
+
+/// Something that depends on the terrain and must hear about changes.
+///
+/// Observers are told about every change of tile, including changes that
+/// keep the same [`TileProperties`] (grass to dirt), and pick out the ones
+/// they care about.
+pub trait TerrainObserver {
+    /// Called after the tile at `change.pos` was replaced.
+    fn tile_changed(&mut self, change: &TileChange);
+}
+
+/// Why a [`TerrainEditor`] couldn't make an edit.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerrainError {
+    /// The position is outside the map.
+    #[error("{0} is outside the map")]
+    OutOfBounds(IVec2),
+    /// The tile can't be dug out.
+    #[error("The tile at {0} can't be dug")]
+    NotDiggable(IVec2),
+    /// Walls can only be built on passable tiles.
+    #[error("Can't build on the impassable tile at {0}")]
+    NotBuildable(IVec2),
+    /// There is no door to open or close.
+    #[error("No door to open or close at {0}")]
+    NotADoor(IVec2),
+}
+
+impl TerrainError {
+    /// Returns a stable machine-readable code for this error.
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::OutOfBounds(_) => "terrain.out_of_bounds",
+            Self::NotDiggable(_) => "terrain.not_diggable",
+            Self::NotBuildable(_) => "terrain.not_buildable",
+            Self::NotADoor(_) => "terrain.not_a_door",
+        }
+    }
+}
+
+/// Edits a [`TerrainMap`] and notifies every observer of each change.
+pub struct TerrainEditor<'a, M: TerrainMap> {
+    map: &'a mut M,
+    observers: Vec<&'a mut dyn TerrainObserver>,
+}
+
+impl<'a, M: TerrainMap> TerrainEditor<'a, M> {
+    /// Creates an editor with no observers.
+    pub const fn new(map: &'a mut M) -> Self {
+        Self {
+            map,
+            observers: Vec::new(),
+        }
+    }
+
+    /// Adds an observer to notify of every change.
+    #[must_use]
+    pub fn with_observer(mut self, observer: &'a mut dyn TerrainObserver) -> Self {
+        self.observers.push(observer);
+        self
+    }
+
+    /// Returns the map being edited.
+    #[must_use]
+    pub const fn map(&self) -> &M {
+        self.map
+    }
+
+    /// Replaces the tile at `pos`. Observers are notified if the tile is
+    /// different from the one already there.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TerrainError::OutOfBounds`] if `pos` is outside the map.
+    pub fn set(&mut self, pos: IVec2, tile: M::Tile) -> Result<TileChange, TerrainError> {
+        let old = self.tile(pos)?;
+        let change = TileChange {
+            pos,
+            before: self.map.properties(old),
+            after: self.map.properties(tile),
+        };
+        if old != tile {
+            self.map.set_tile(pos, tile);
+            for observer in &mut self.observers {
+                observer.tile_changed(&change);
+            }
+        }
+        Ok(change)
+    }
+
+    /// Digs out the tile at `pos`, replacing it with [`TerrainMap::dug`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TerrainError::NotDiggable`] if the tile can't be dug, or
+    /// [`TerrainError::OutOfBounds`].
+    pub fn dig(&mut self, pos: IVec2) -> Result<TileChange, TerrainError> {
+        let old = self.tile(pos)?;
+        let floor = self.map.dug(old).ok_or(TerrainError::NotDiggable(pos))?;
+        self.set(pos, floor)
+    }
+
+    /// Builds `wall` on the passable tile at `pos`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TerrainError::NotBuildable`] if the tile is impassable, or
+    /// [`TerrainError::OutOfBounds`].
+    pub fn build_wall(&mut self, pos: IVec2, wall: M::Tile) -> Result<TileChange, TerrainError> {
+        let old = self.tile(pos)?;
+        if !self.map.properties(old).is_passable() {
+            return Err(TerrainError::NotBuildable(pos));
+        }
+        self.set(pos, wall)
+    }
+
+    /// Opens the closed door at `pos`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TerrainError::NotADoor`] if there is no closed door, or
+    /// [`TerrainError::OutOfBounds`].
+    pub fn open_door(&mut self, pos: IVec2) -> Result<TileChange, TerrainError> {
+        let old = self.tile(pos)?;
+        let open = self.map.opened(old).ok_or(TerrainError::NotADoor(pos))?;
+        self.set(pos, open)
+    }
+
+    /// Closes the open door at `pos`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TerrainError::NotADoor`] if there is no open door, or
+    /// [`TerrainError::OutOfBounds`].
+    pub fn close_door(&mut self, pos: IVec2) -> Result<TileChange, TerrainError> {
+        let old = self.tile(pos)?;
+        let closed = self.map.closed(old).ok_or(TerrainError::NotADoor(pos))?;
+        self.set(pos, closed)
+    }
+
+    /// Opens the door at `pos` if it is closed, and closes it if it is open.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TerrainError::NotADoor`] if there is no door, or
+    /// [`TerrainError::OutOfBounds`].
+    pub fn toggle_door(&mut self, pos: IVec2) -> Result<TileChange, TerrainError> {
+        let old = self.tile(pos)?;
+        let toggled = self
+            .map
+            .opened(old)
+            .or_else(|| self.map.closed(old))
+            .ok_or(TerrainError::NotADoor(pos))?;
+        self.set(pos, toggled)
+    }
+
+    fn tile(&self, pos: IVec2) -> Result<M::Tile, TerrainError> {
+        self.map.tile(pos).ok_or(TerrainError::OutOfBounds(pos))
+    }
+}
+
+/// Tiles whose autotiled glyph may need recomputing.
+///
+/// An autotiled wall picks its glyph from its neighbors, so a change marks
+/// the tile and its eight neighbors. Renderers [`take`](Self::take) the
+/// dirty tiles once per frame.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DirtyTiles {
+    tiles: HashSet<IVec2>,
+}
+
+impl DirtyTiles {
+    /// Creates an empty set.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks a tile dirty by hand.
+    pub fn mark(&mut self, pos: IVec2) {
+        self.tiles.insert(pos);
+    }
+
+    /// Returns true if the tile is dirty.
+    #[must_use]
+    pub fn contains(&self, pos: IVec2) -> bool {
+        self.tiles.contains(&pos)
+    }
+
+    /// Returns the number of dirty tiles.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Returns true if no tile is dirty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Returns the dirty tiles in row-major order and clears the set.
+    pub fn take(&mut self) -> Vec<IVec2> {
+        let mut tiles: Vec<IVec2> = self.tiles.drain().collect();
+        tiles.sort_by_key(|p| (p.y, p.x));
+        tiles
+    }
+}
+
+impl TerrainObserver for DirtyTiles {
+    fn tile_changed(&mut self, change: &TileChange) {
+        self.tiles.insert(change.pos);
+        self.tiles.extend(change.pos.neighbors_ordinal());
+    }
+}
+
+/// Connected regions of passable tiles.
+///
+/// Regions are numbered in row-major order of their first tile, joining
+/// orthogonal neighbors. Changes to passability mark the labels stale, and
+/// they are recomputed on the next query.
+#[derive(Debug, Clone)]
+pub struct Connectivity {
+    passable: BitGrid,
+    regions: Vec<Option<usize>>,
+    count: usize,
+    dirty: bool,
+}
+
+impl Connectivity {
+    /// Creates a region map with `passable` deciding every tile.
+    #[must_use]
+    pub fn from_fn(width: u32, height: u32, mut passable: impl FnMut(IVec2) -> bool) -> Self {
+        let mut grid = BitGrid::new(width, height);
+        for y in 0..i32::try_from(height).unwrap_or(i32::MAX) {
+            for x in 0..i32::try_from(width).unwrap_or(i32::MAX) {
+                let pos = IVec2::new(x, y);
+                grid.set(pos, u8::from(passable(pos)));
+            }
+        }
+        Self {
+            regions: vec![None; width as usize * height as usize],
+            passable: grid,
+            count: 0,
+            dirty: true,
+        }
+    }
+
+    /// Returns true if `pos` is passable.
+    #[must_use]
+    pub fn is_passable(&self, pos: IVec2) -> bool {
+        self.passable.is_set(pos)
+    }
+
+    /// Returns true if the labels will be recomputed on the next query.
+    #[must_use]
+    pub const fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Returns the region of `pos`, or `None` if it is impassable or
+    /// outside the map.
+    pub fn region_at(&mut self, pos: IVec2) -> Option<usize> {
+        self.relabel();
+        self.index(pos).and_then(|i| self.regions[i])
+    }
+
+    /// Returns true if a walk between `a` and `b` exists.
+    pub fn connected(&mut self, a: IVec2, b: IVec2) -> bool {
+        self.region_at(a).is_some() && self.region_at(a) == self.region_at(b)
+    }
+
+    /// Returns the number of regions.
+    pub fn region_count(&mut self) -> usize {
+        self.relabel();
+        self.count
+    }
+
+    fn relabel(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        self.dirty = false;
+        self.regions.fill(None);
+        self.count = 0;
+
+        let width = i32::try_from(self.passable.width()).unwrap_or(i32::MAX);
+        let height = i32::try_from(self.passable.height()).unwrap_or(i32::MAX);
+        let mut stack = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let start = IVec2::new(x, y);
+                let Some(i) = self.index(start) else {
+                    continue;
+                };
+                if self.regions[i].is_some() || !self.passable.is_set(start) {
+                    continue;
+                }
+                let region = self.count;
+                self.count += 1;
+                stack.push(start);
+                while let Some(pos) = stack.pop() {
+                    let Some(i) = self.index(pos) else {
+                        continue;
+                    };
+                    if self.regions[i].is_some() || !self.passable.is_set(pos) {
+                        continue;
+                    }
+                    self.regions[i] = Some(region);
+                    stack.extend(pos.neighbors());
+                }
+            }
+        }
+    }
+
+    fn index(&self, pos: IVec2) -> Option<usize> {
+        if !self.passable.in_bounds(pos) {
+            return None;
+        }
+        let x = usize::try_from(pos.x).ok()?;
+        let y = usize::try_from(pos.y).ok()?;
+        Some(y * self.passable.width() as usize + x)
+    }
+}
+
+impl TerrainObserver for Connectivity {
+    fn tile_changed(&mut self, change: &TileChange) {
+        if change.passability_changed() {
+            self.passable
+                .set(change.pos, u8::from(change.after.is_passable()));
+            self.dirty = true;
+        }
+    }
+}
+
+#[cfg(feature = "fov")]
+impl TerrainObserver for crate::fov::prelude::OpacityGrid {
+    fn tile_changed(&mut self, change: &TileChange) {
+        self.set_opaque(change.pos, change.after.opaque);
+    }
+}
+
+#[cfg(feature = "fov")]
+impl<K: std::hash::Hash + Eq + Clone> TerrainObserver for crate::fov::prelude::FovCache<K> {
+    fn tile_changed(&mut self, change: &TileChange) {
+        if change.opacity_changed() {
+            self.invalidate_at(change.pos);
+        }
+    }
+}
+
+#[cfg(feature = "pathfinding")]
+impl TerrainObserver for crate::pathfinding::prelude::CostGrid {
+    fn tile_changed(&mut self, change: &TileChange) {
+        use crate::pathfinding::prelude::IMPASSABLE;
+
+        if change.cost_changed() {
+            self.set_cost(change.pos, change.after.movement_cost.unwrap_or(IMPASSABLE));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Tile {
+        Floor,
+        Grass,
+        Wall,
+        ClosedDoor,
+        OpenDoor,
+    }
+
+    struct Map {
+        width: i32,
+        tiles: Vec<Tile>,
+    }
+
+    impl Map {
+        /// A 5x3 map with `middle` on the column x = 2.
+        fn split(middle: Tile) -> Self {
+            let tiles = (0..15).map(|i| if i % 5 == 2 { middle } else { Tile::Floor });
+            Self {
+                width: 5,
+                tiles: tiles.collect(),
+            }
+        }
+
+        fn index(&self, pos: IVec2) -> Option<usize> {
+            if pos.x < 0 || pos.x >= self.width {
+                return None;
+            }
+            usize::try_from(pos.y * self.width + pos.x).ok()
+        }
+
+        fn is(&self, pos: IVec2, tile: Tile) -> bool {
+            self.tile(pos) == Some(tile)
+        }
+    }
+
+    impl TerrainMap for Map {
+        type Tile = Tile;
+
+        fn tile(&self, pos: IVec2) -> Option<Tile> {
+            self.index(pos).and_then(|i| self.tiles.get(i).copied())
+        }
+
+        fn set_tile(&mut self, pos: IVec2, tile: Tile) {
+            if let Some(i) = self.index(pos) {
+                self.tiles[i] = tile;
+            }
+        }
+
+        fn properties(&self, tile: Tile) -> TileProperties {
+            match tile {
+                Tile::Floor | Tile::Grass | Tile::OpenDoor => TileProperties::FLOOR,
+                Tile::Wall => TileProperties::WALL,
+                Tile::ClosedDoor => TileProperties::new(true, Some(2)),
+            }
+        }
+
+        fn dug(&self, tile: Tile) -> Option<Tile> {
+            (tile == Tile::Wall).then_some(Tile::Floor)
+        }
+
+        fn opened(&self, tile: Tile) -> Option<Tile> {
+            (tile == Tile::ClosedDoor).then_some(Tile::OpenDoor)
+        }
+
+        fn closed(&self, tile: Tile) -> Option<Tile> {
+            (tile == Tile::OpenDoor).then_some(Tile::ClosedDoor)
+        }
+    }
+
+    #[derive(Default)]
+    struct Counter {
+        changes: Vec<TileChange>,
+    }
+
+    impl TerrainObserver for Counter {
+        fn tile_changed(&mut self, change: &TileChange) {
+            self.changes.push(*change);
+        }
+    }
+
+    const DOOR: IVec2 = IVec2::new(2, 1);
+
+    fn around_door() -> Vec<IVec2> {
+        (0..3)
+            .flat_map(|y| (1..4).map(move |x| IVec2::new(x, y)))
+            .collect()
+    }
+
+    #[test]
+    fn test_dig_relabels_regions_and_marks_neighbors() {
+        let mut map = Map::split(Tile::Wall);
+        let mut regions = Connectivity::from_fn(5, 3, |p| p.x != 2);
+        let mut dirty = DirtyTiles::new();
+        assert_eq!(regions.region_count(), 2);
+
+        let mut editor = TerrainEditor::new(&mut map)
+            .with_observer(&mut regions)
+            .with_observer(&mut dirty);
+        let change = editor.dig(DOOR).unwrap();
+        assert!(change.opacity_changed() && change.passability_changed());
+        assert!(editor.map().is(DOOR, Tile::Floor));
+
+        assert!(regions.is_dirty());
+        assert_eq!(regions.region_count(), 1);
+        assert!(regions.connected(IVec2::new(0, 0), IVec2::new(4, 2)));
+        assert_eq!(dirty.take(), around_door());
+        assert!(dirty.is_empty());
+    }
+
+    #[test]
+    fn test_build_wall_splits_regions() {
+        let mut map = Map::split(Tile::Floor);
+        let mut regions = Connectivity::from_fn(5, 3, |_| true);
+        let mut dirty = DirtyTiles::new();
+        assert_eq!(regions.region_count(), 1);
+
+        let mut editor = TerrainEditor::new(&mut map)
+            .with_observer(&mut regions)
+            .with_observer(&mut dirty);
+        for y in 0..3 {
+            editor.build_wall(IVec2::new(2, y), Tile::Wall).unwrap();
+        }
+        assert_eq!(
+            editor.build_wall(DOOR, Tile::Wall),
+            Err(TerrainError::NotBuildable(DOOR))
+        );
+
+        assert_eq!(regions.region_at(DOOR), None);
+        assert_eq!(regions.region_count(), 2);
+        assert!(!regions.connected(IVec2::new(0, 1), IVec2::new(4, 1)));
+        // The wall and the columns beside it, one row past each end
+        assert_eq!(dirty.len(), 15);
+    }
+
+    #[test]
+    fn test_noop_edit_does_not_notify() {
+        let mut map = Map::split(Tile::Wall);
+        let mut counter = Counter::default();
+        let mut regions = Connectivity::from_fn(5, 3, |p| p.x != 2);
+        assert_eq!(regions.region_count(), 2);
+
+        let mut editor = TerrainEditor::new(&mut map)
+            .with_observer(&mut counter)
+            .with_observer(&mut regions);
+        let change = editor.set(DOOR, Tile::Wall).unwrap();
+        assert!(!change.opacity_changed() && !change.cost_changed());
+        assert_eq!(
+            editor.dig(IVec2::ZERO),
+            Err(TerrainError::NotDiggable(IVec2::ZERO))
+        );
+        assert_eq!(editor.open_door(DOOR), Err(TerrainError::NotADoor(DOOR)));
+        let outside = IVec2::new(5, 0);
+        assert_eq!(
+            editor.set(outside, Tile::Floor).unwrap_err().code(),
+            "terrain.out_of_bounds"
+        );
+        assert!(!regions.is_dirty());
+        assert!(counter.changes.is_empty());
+
+        // A different tile with the same properties is still a change
+        let mut editor = TerrainEditor::new(&mut map).with_observer(&mut counter);
+        editor.set(IVec2::ZERO, Tile::Grass).unwrap();
+        assert_eq!(counter.changes.len(), 1);
+        assert!(!counter.changes[0].cost_changed());
+    }
+
+    #[cfg(feature = "fov")]
+    #[test]
+    fn test_doors_update_opacity_and_fov() {
+        use crate::fov::prelude::{Fov, FovCache, OpacityGrid};
+
+        let mut map = Map::split(Tile::ClosedDoor);
+        let mut opacity = OpacityGrid::from_fn(5, 3, |p| p.x == 2);
+        let mut cache = FovCache::new(Fov::Shadowcast);
+        cache.update('@', IVec2::new(0, 1), 5, &mut &opacity, ());
+        assert!(!cache.can_see(&'@', IVec2::new(4, 1)));
+
+        let mut editor = TerrainEditor::new(&mut map)
+            .with_observer(&mut opacity)
+            .with_observer(&mut cache);
+        editor.open_door(DOOR).unwrap();
+        assert!(!opacity.is_opaque(DOOR));
+        cache.update('@', IVec2::new(0, 1), 5, &mut &opacity, ());
+        assert_eq!(cache.recompute_count(), 2);
+        assert!(cache.can_see(&'@', IVec2::new(4, 1)));
+
+        let mut editor = TerrainEditor::new(&mut map)
+            .with_observer(&mut opacity)
+            .with_observer(&mut cache);
+        editor.close_door(DOOR).unwrap();
+        assert!(editor.close_door(DOOR).is_err());
+        assert!(opacity.is_opaque(DOOR));
+        cache.update('@', IVec2::new(0, 1), 5, &mut &opacity, ());
+        assert_eq!(cache.recompute_count(), 3);
+        assert!(!cache.can_see(&'@', IVec2::new(4, 1)));
+    }
+
+    #[cfg(feature = "pathfinding")]
+    #[test]
+    fn test_edits_update_costs() {
+        use crate::pathfinding::prelude::{CostGrid, IMPASSABLE};
+
+        let mut map = Map::split(Tile::ClosedDoor);
+        let mut costs = CostGrid::from_fn(5, 3, |p| if p.x == 2 { 2 } else { 1 });
+        let mut regions = Connectivity::from_fn(5, 3, |_| true);
+        assert_eq!(regions.region_count(), 1);
+
+        let mut editor = TerrainEditor::new(&mut map)
+            .with_observer(&mut costs)
+            .with_observer(&mut regions);
+        editor.toggle_door(DOOR).unwrap();
+        editor.build_wall(IVec2::new(2, 0), Tile::Wall).unwrap();
+        editor.dig(IVec2::new(2, 0)).unwrap();
+        editor.build_wall(IVec2::new(2, 2), Tile::Wall).unwrap();
+
+        assert_eq!(costs.cost_at(DOOR), 1);
+        assert_eq!(costs.cost_at(IVec2::new(2, 0)), 1);
+        assert_eq!(costs.cost_at(IVec2::new(2, 2)), IMPASSABLE);
+        // Opening a door keeps it passable, so only the walls touched regions
+        assert!(regions.is_dirty());
+        assert_eq!(regions.region_count(), 1);
+    }
+}